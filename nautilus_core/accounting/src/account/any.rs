@@ -0,0 +1,241 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::ops::{Deref, DerefMut};
+
+use nautilus_core::{time::UnixNanos, uuid::UUID4};
+use nautilus_model::{
+    enums::{AccountType, LiquiditySide},
+    events::{account::state::AccountState, order::filled::OrderFilled},
+    instruments::Instrument,
+    position::Position,
+    types::{balance::MarginBalance, money::Money, price::Price, quantity::Quantity},
+};
+
+use crate::account::{base::BaseAccount, cash::CashAccount, margin::MarginAccount, Account};
+
+/// Wraps any concrete account type, so that accounts can be held together and
+/// rebuilt from their state events.
+#[derive(Debug)]
+pub enum AccountAny {
+    Cash(CashAccount),
+    Margin(MarginAccount),
+}
+
+impl AccountAny {
+    /// Creates a new account of the type for the given initial `event`.
+    pub fn new(event: AccountState, calculate_account_state: bool) -> anyhow::Result<Self> {
+        match event.account_type {
+            AccountType::Cash => Ok(Self::Cash(CashAccount::new(
+                event,
+                calculate_account_state,
+            )?)),
+            AccountType::Margin => Ok(Self::Margin(MarginAccount::new(
+                event,
+                calculate_account_state,
+            )?)),
+            AccountType::Betting => anyhow::bail!("Betting accounts are not supported"),
+        }
+    }
+
+    /// Rebuilds an account from the given state `events`, in the order they were applied.
+    pub fn from_events(
+        events: &[AccountState],
+        calculate_account_state: bool,
+    ) -> anyhow::Result<Self> {
+        let Some((init_event, events)) = events.split_first() else {
+            anyhow::bail!("No account events to rebuild from")
+        };
+        let mut account = Self::new(init_event.clone(), calculate_account_state)?;
+        for event in events {
+            account.apply(event.clone());
+        }
+        Ok(account)
+    }
+
+    #[must_use]
+    pub fn is_cash_account(&self) -> bool {
+        matches!(self, Self::Cash(_))
+    }
+
+    #[must_use]
+    pub fn is_margin_account(&self) -> bool {
+        matches!(self, Self::Margin(_))
+    }
+
+    pub fn apply(&mut self, event: AccountState) {
+        match self {
+            Self::Cash(account) => account.apply(event),
+            Self::Margin(account) => account.apply(event),
+        }
+    }
+
+    /// Returns the margin balances (empty for cash accounts).
+    #[must_use]
+    pub fn margins(&self) -> Vec<MarginBalance> {
+        match self {
+            Self::Cash(_) => Vec::new(),
+            Self::Margin(account) => account.margins.values().copied().collect(),
+        }
+    }
+
+    pub fn calculate_pnls<T: Instrument>(
+        &self,
+        instrument: T,
+        fill: OrderFilled,
+        position: Option<Position>,
+    ) -> anyhow::Result<Vec<Money>> {
+        match self {
+            Self::Cash(account) => account.calculate_pnls(instrument, fill, position),
+            Self::Margin(account) => account.calculate_pnls(instrument, fill, position),
+        }
+    }
+
+    pub fn calculate_commission<T: Instrument>(
+        &self,
+        instrument: T,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        match self {
+            Self::Cash(account) => account.calculate_commission(
+                instrument,
+                last_qty,
+                last_px,
+                liquidity_side,
+                use_quote_for_inverse,
+            ),
+            Self::Margin(account) => account.calculate_commission(
+                instrument,
+                last_qty,
+                last_px,
+                liquidity_side,
+                use_quote_for_inverse,
+            ),
+        }
+    }
+
+    /// Generates a (calculated) account state event from the current account balances
+    /// and margins.
+    pub fn generate_account_state(
+        &self,
+        ts_event: UnixNanos,
+        ts_init: UnixNanos,
+    ) -> anyhow::Result<AccountState> {
+        AccountState::new(
+            self.id,
+            self.account_type,
+            self.balances.values().copied().collect(),
+            self.margins(),
+            false,
+            UUID4::new(),
+            ts_event,
+            ts_init,
+            self.base_currency,
+        )
+    }
+}
+
+impl Deref for AccountAny {
+    type Target = BaseAccount;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Cash(account) => account,
+            Self::Margin(account) => account,
+        }
+    }
+}
+
+impl DerefMut for AccountAny {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Cash(account) => account,
+            Self::Margin(account) => account,
+        }
+    }
+}
+
+impl From<CashAccount> for AccountAny {
+    fn from(account: CashAccount) -> Self {
+        Self::Cash(account)
+    }
+}
+
+impl From<MarginAccount> for AccountAny {
+    fn from(account: MarginAccount) -> Self {
+        Self::Margin(account)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        events::account::stubs::*,
+        types::{balance::AccountBalance, currency::Currency},
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_new_creates_account_for_type(
+        cash_account_state: AccountState,
+        margin_account_state: AccountState,
+    ) {
+        let cash = AccountAny::new(cash_account_state, true).unwrap();
+        let margin = AccountAny::new(margin_account_state, true).unwrap();
+
+        assert!(cash.is_cash_account());
+        assert!(margin.is_margin_account());
+    }
+
+    #[rstest]
+    fn test_from_events_applies_events_in_order(cash_account_state: AccountState) {
+        let mut changed = cash_account_state.clone();
+        changed.balances = vec![AccountBalance::new(
+            Money::from("500000 USD"),
+            Money::from("0 USD"),
+            Money::from("500000 USD"),
+        )
+        .unwrap()];
+
+        let account = AccountAny::from_events(&[cash_account_state, changed], true).unwrap();
+
+        assert_eq!(account.events.len(), 2);
+        assert_eq!(
+            account.base_balance_total(Some(Currency::USD())),
+            Some(Money::from("500000 USD"))
+        );
+        assert!(AccountAny::from_events(&[], true).is_err());
+    }
+
+    #[rstest]
+    fn test_generate_account_state(cash_account_state: AccountState) {
+        let account = AccountAny::new(cash_account_state.clone(), true).unwrap();
+
+        let state = account.generate_account_state(1, 2).unwrap();
+
+        assert_eq!(state.account_id, cash_account_state.account_id);
+        assert_eq!(state.balances, cash_account_state.balances);
+        assert!(!state.is_reported);
+        assert_eq!(state.ts_event, 1);
+    }
+}
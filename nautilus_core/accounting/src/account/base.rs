@@ -15,10 +15,11 @@
 
 use std::collections::HashMap;
 
+use nautilus_common::cache::Cache;
 use nautilus_model::{
-    enums::{AccountType, LiquiditySide, OrderSide},
+    enums::{AccountType, LiquiditySide, OrderSide, PriceType},
     events::{account::state::AccountState, order::filled::OrderFilled},
-    identifiers::account_id::AccountId,
+    identifiers::{account_id::AccountId, venue::Venue},
     instruments::Instrument,
     position::Position,
     types::{
@@ -27,6 +28,8 @@ use nautilus_model::{
 };
 use rust_decimal::prelude::ToPrimitive;
 
+use crate::fee::{FeeModel, MakerTakerFeeModel};
+
 #[derive(Debug)]
 #[cfg_attr(
     feature = "python",
@@ -41,6 +44,7 @@ pub struct BaseAccount {
     pub commissions: HashMap<Currency, f64>,
     pub balances: HashMap<Currency, AccountBalance>,
     pub balances_starting: HashMap<Currency, Money>,
+    pub fee_model: Box<dyn FeeModel>,
 }
 
 impl BaseAccount {
@@ -60,9 +64,22 @@ impl BaseAccount {
             commissions: HashMap::new(),
             balances,
             balances_starting,
+            fee_model: Box::new(MakerTakerFeeModel),
         })
     }
 
+    /// Sets the fee model used to calculate commissions (maker/taker rates by default).
+    pub fn set_fee_model(&mut self, fee_model: Box<dyn FeeModel>) {
+        self.fee_model = fee_model;
+    }
+
+    /// Records the given `fill` with the fee model, for fee models which depend on the
+    /// trading history of the account.
+    pub fn record_fill<T: Instrument>(&mut self, instrument: &T, fill: &OrderFilled) {
+        self.fee_model
+            .record_fill(instrument, fill.last_qty, fill.last_px, fill.ts_event);
+    }
+
     #[must_use]
     pub fn base_balance_total(&self, currency: Option<Currency>) -> Option<Money> {
         let currency = currency
@@ -114,6 +131,59 @@ impl BaseAccount {
             .collect()
     }
 
+    /// Returns the total balance across all currencies converted into the given
+    /// `currency` (or the account base currency).
+    ///
+    /// Returns `None` if an exchange rate is unavailable for any balance currency.
+    pub fn base_balance_total_converted(
+        &self,
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        let totals: Vec<Money> = self
+            .balances
+            .values()
+            .map(|balance| balance.total)
+            .collect();
+        self.base_convert_total(&totals, currency, cache, price_type)
+    }
+
+    /// Returns the sum of the given `amounts` (such as calculated PnLs) converted into the
+    /// given `currency` (or the account base currency), at the exchange rates calculated
+    /// from the latest quotes in the `cache` for the account venue.
+    ///
+    /// Returns `None` if an exchange rate is unavailable for any amount currency.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if no `currency` is given and the account has no base
+    /// currency, or if an exchange rate cannot be calculated for the `price_type`.
+    pub fn base_convert_total(
+        &self,
+        amounts: &[Money],
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        let Some(currency) = currency.or(self.base_currency) else {
+            anyhow::bail!(
+                "Currency must be specified for account {} with no base currency",
+                self.id
+            )
+        };
+        let venue = Venue::from_str_unchecked(self.id.get_issuer().as_str());
+
+        let mut total = 0.0;
+        for amount in amounts {
+            let Some(xrate) = cache.get_xrate(venue, amount.currency, currency, price_type)? else {
+                return Ok(None);
+            };
+            total += amount.as_f64() * xrate;
+        }
+        Ok(Some(Money::new(total, currency)?))
+    }
+
     #[must_use]
     pub fn base_last_event(&self) -> Option<AccountState> {
         self.events.last().cloned()
@@ -132,6 +202,14 @@ impl BaseAccount {
         }
     }
 
+    /// Adds the given `commission` to the total commissions for its currency.
+    pub fn update_commissions(&mut self, commission: Money) {
+        if commission.is_zero() {
+            return; // Nothing to update
+        }
+        *self.commissions.entry(commission.currency).or_insert(0.0) += commission.as_f64();
+    }
+
     pub fn base_apply(&mut self, event: AccountState) {
         self.update_balances(event.balances.clone());
         self.events.push(event);
@@ -222,24 +300,12 @@ impl BaseAccount {
         liquidity_side: LiquiditySide,
         use_quote_for_inverse: Option<bool>,
     ) -> anyhow::Result<Money> {
-        assert!(
-            liquidity_side != LiquiditySide::NoLiquiditySide,
-            "Invalid liquidity side"
-        );
-        let notional = instrument
-            .calculate_notional_value(last_qty, last_px, use_quote_for_inverse)
-            .as_f64();
-        let commission = if liquidity_side == LiquiditySide::Maker {
-            notional * instrument.maker_fee().to_f64().unwrap()
-        } else if liquidity_side == LiquiditySide::Taker {
-            notional * instrument.taker_fee().to_f64().unwrap()
-        } else {
-            panic!("Invalid liquid side {liquidity_side}")
-        };
-        if instrument.is_inverse() && !use_quote_for_inverse.unwrap_or(false) {
-            Ok(Money::new(commission, instrument.base_currency().unwrap()).unwrap())
-        } else {
-            Ok(Money::new(commission, instrument.quote_currency()).unwrap())
-        }
+        self.fee_model.get_commission(
+            &instrument,
+            last_qty,
+            last_px,
+            liquidity_side,
+            use_quote_for_inverse,
+        )
     }
 }
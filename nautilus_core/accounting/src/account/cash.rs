@@ -19,9 +19,11 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use nautilus_common::cache::Cache;
 use nautilus_model::{
-    enums::{AccountType, LiquiditySide, OrderSide},
+    enums::{AccountType, LiquiditySide, OrderSide, PriceType},
     events::{account::state::AccountState, order::filled::OrderFilled},
+    identifiers::instrument_id::InstrumentId,
     instruments::Instrument,
     position::Position,
     types::{
@@ -38,15 +40,59 @@ use crate::account::{base::BaseAccount, Account};
 )]
 pub struct CashAccount {
     pub base: BaseAccount,
+    pub balances_locked_by_instrument: HashMap<InstrumentId, Money>,
 }
 
 impl CashAccount {
     pub fn new(event: AccountState, calculate_account_state: bool) -> anyhow::Result<Self> {
         Ok(Self {
             base: BaseAccount::new(event, calculate_account_state)?,
+            balances_locked_by_instrument: HashMap::new(),
         })
     }
 
+    /// Updates the balance locked for the given `instrument_id`.
+    pub fn update_balance_locked(
+        &mut self,
+        instrument_id: InstrumentId,
+        locked: Money,
+    ) -> anyhow::Result<()> {
+        if locked.raw < 0 {
+            anyhow::bail!("`locked` was negative, was {locked}");
+        }
+        self.balances_locked_by_instrument
+            .insert(instrument_id, locked);
+        self.recalculate_balance(locked.currency);
+        Ok(())
+    }
+
+    /// Clears the balance locked for the given `instrument_id`.
+    pub fn clear_balance_locked(&mut self, instrument_id: &InstrumentId) {
+        if let Some(locked) = self.balances_locked_by_instrument.remove(instrument_id) {
+            self.recalculate_balance(locked.currency);
+        }
+    }
+
+    fn recalculate_balance(&mut self, currency: Currency) {
+        let Some(current_balance) = self.balances.get(&currency) else {
+            return; // No balance to recalculate
+        };
+
+        let total_locked: i64 = self
+            .balances_locked_by_instrument
+            .values()
+            .filter(|locked| locked.currency == currency)
+            .map(|locked| locked.raw)
+            .sum();
+        let new_balance = AccountBalance::new(
+            current_balance.total,
+            Money::from_raw(total_locked, currency),
+            Money::from_raw(current_balance.total.raw - total_locked, currency),
+        )
+        .unwrap();
+        self.balances.insert(currency, new_balance);
+    }
+
     #[must_use]
     pub fn is_cash_account(&self) -> bool {
         self.account_type == AccountType::Cash
@@ -83,6 +129,23 @@ impl Account for CashAccount {
     fn balances_locked(&self) -> HashMap<Currency, Money> {
         self.base_balances_locked()
     }
+    fn balance_total_converted(
+        &self,
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        self.base_balance_total_converted(currency, cache, price_type)
+    }
+    fn convert_total(
+        &self,
+        amounts: &[Money],
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        self.base_convert_total(amounts, currency, cache, price_type)
+    }
     fn last_event(&self) -> Option<AccountState> {
         self.base_last_event()
     }
@@ -185,11 +248,15 @@ impl Display for CashAccount {
 mod tests {
     use std::collections::{HashMap, HashSet};
 
-    use nautilus_common::{factories::OrderFactory, stubs::*};
+    use nautilus_common::{cache::Cache, factories::OrderFactory, stubs::*};
     use nautilus_model::{
-        enums::{AccountType, LiquiditySide, OrderSide},
+        data::quote::QuoteTick,
+        enums::{AccountType, LiquiditySide, OrderSide, PriceType},
         events::account::{state::AccountState, stubs::*},
-        identifiers::{account_id::AccountId, position_id::PositionId, strategy_id::StrategyId},
+        identifiers::{
+            account_id::AccountId, position_id::PositionId, strategy_id::StrategyId,
+            symbol::Symbol, venue::Venue,
+        },
         instruments::{
             crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair, equity::Equity,
             stubs::*,
@@ -572,4 +639,51 @@ mod tests {
             .unwrap();
         assert_eq!(result, Money::from("5294 JPY"));
     }
+
+    #[rstest]
+    fn test_balance_total_converted_for_multi_currency_cash_account(
+        cash_account_multi: CashAccount,
+    ) {
+        let mut cache = Cache::default();
+        for (symbol, price) in [("BTC/USDT", "50000.00000"), ("ETH/USDT", "3000.00000")] {
+            let instrument = default_fx_ccy(Symbol::from(symbol), Some(Venue::from("SIM")));
+            let quote = QuoteTick::new(
+                instrument.id,
+                Price::from(price),
+                Price::from(price),
+                Quantity::from(1),
+                Quantity::from(1),
+                0,
+                0,
+            )
+            .unwrap();
+            cache.add_instrument(Box::new(instrument)).unwrap();
+            cache.add_quote_tick(quote);
+        }
+
+        let total = cash_account_multi
+            .balance_total_converted(Some(Currency::USDT()), &cache, PriceType::Mid)
+            .unwrap();
+        let pnls = [Money::from("-1 BTC"), Money::from("60000 USDT")];
+        let pnl = cash_account_multi
+            .convert_total(&pnls, Some(Currency::USDT()), &cache, PriceType::Mid)
+            .unwrap();
+        let missing = cash_account_multi
+            .balance_total_converted(Some(Currency::AUD()), &cache, PriceType::Mid)
+            .unwrap();
+
+        assert_eq!(total, Some(Money::from("560000 USDT")));
+        assert_eq!(pnl, Some(Money::from("10000 USDT")));
+        assert_eq!(missing, None);
+    }
+
+    #[rstest]
+    fn test_convert_total_without_currency_errors(cash_account_multi: CashAccount) {
+        let cache = Cache::default();
+
+        let result =
+            cash_account_multi.convert_total(&[Money::from("1 BTC")], None, &cache, PriceType::Mid);
+
+        assert!(result.is_err());
+    }
 }
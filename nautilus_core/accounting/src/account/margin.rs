@@ -22,8 +22,9 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+use nautilus_common::cache::Cache;
 use nautilus_model::{
-    enums::{AccountType, LiquiditySide, OrderSide},
+    enums::{AccountType, LiquiditySide, OrderSide, PriceType},
     events::{account::state::AccountState, order::filled::OrderFilled},
     identifiers::instrument_id::InstrumentId,
     instruments::Instrument,
@@ -36,9 +37,11 @@ use nautilus_model::{
         quantity::Quantity,
     },
 };
-use rust_decimal::prelude::ToPrimitive;
 
-use crate::account::{base::BaseAccount, Account};
+use crate::{
+    account::{base::BaseAccount, Account},
+    margin::{LeveragedMarginModel, MarginModel, MarginPosition, SpreadLeg},
+};
 
 #[derive(Debug)]
 #[cfg_attr(
@@ -50,6 +53,8 @@ pub struct MarginAccount {
     pub leverages: HashMap<InstrumentId, f64>,
     pub margins: HashMap<InstrumentId, MarginBalance>,
     pub default_leverage: f64,
+    pub margin_model: Box<dyn MarginModel>,
+    pub spread_legs: HashMap<InstrumentId, Vec<SpreadLeg>>,
 }
 
 impl MarginAccount {
@@ -59,9 +64,22 @@ impl MarginAccount {
             leverages: HashMap::new(),
             margins: HashMap::new(),
             default_leverage: 1.0,
+            margin_model: Box::new(LeveragedMarginModel),
+            spread_legs: HashMap::new(),
         })
     }
 
+    /// Sets the margin model used to calculate margins (leveraged per-instrument by default).
+    pub fn set_margin_model(&mut self, margin_model: Box<dyn MarginModel>) {
+        self.margin_model = margin_model;
+    }
+
+    /// Sets the legs of the given spread instrument, so that the margin model can credit
+    /// offsetting legs.
+    pub fn set_spread_legs(&mut self, instrument_id: InstrumentId, legs: Vec<SpreadLeg>) {
+        self.spread_legs.insert(instrument_id, legs);
+    }
+
     pub fn set_default_leverage(&mut self, leverage: f64) {
         self.default_leverage = leverage;
     }
@@ -166,6 +184,30 @@ impl MarginAccount {
         self.recalculate_balance(margin_maintenance.currency);
     }
 
+    /// Clears the initial margin for the given `instrument_id`.
+    pub fn clear_initial_margin(&mut self, instrument_id: InstrumentId) {
+        if let Some(margin_balance) = self.margins.get_mut(&instrument_id) {
+            margin_balance.initial = Money::from_raw(0, margin_balance.currency);
+            self.clear_empty_margin(instrument_id);
+        }
+    }
+
+    /// Clears the maintenance margin for the given `instrument_id`.
+    pub fn clear_maintenance_margin(&mut self, instrument_id: InstrumentId) {
+        if let Some(margin_balance) = self.margins.get_mut(&instrument_id) {
+            margin_balance.maintenance = Money::from_raw(0, margin_balance.currency);
+            self.clear_empty_margin(instrument_id);
+        }
+    }
+
+    fn clear_empty_margin(&mut self, instrument_id: InstrumentId) {
+        let margin_balance = self.margins[&instrument_id];
+        if margin_balance.initial.raw == 0 && margin_balance.maintenance.raw == 0 {
+            self.margins.remove(&instrument_id);
+        }
+        self.recalculate_balance(margin_balance.currency);
+    }
+
     #[must_use]
     pub fn maintenance_margin(&self, instrument_id: InstrumentId) -> Money {
         let margin_balance = self.margins.get(&instrument_id);
@@ -176,56 +218,87 @@ impl MarginAccount {
         margin_balance.unwrap().maintenance
     }
 
+    /// Returns the initial margin for the given `quantity` of the `instrument` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margin cannot be calculated.
     pub fn calculate_initial_margin<T: Instrument>(
         &mut self,
         instrument: T,
         quantity: Quantity,
         price: Price,
         use_quote_for_inverse: Option<bool>,
-    ) -> Money {
-        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
+    ) -> anyhow::Result<Money> {
         let leverage = self.get_leverage(&instrument.id());
         if leverage == 0.0 {
             self.leverages
                 .insert(instrument.id(), self.default_leverage);
         }
-        let adjusted_notional = notional / leverage;
-        let initial_margin_f64 = instrument.margin_init().to_f64().unwrap();
-        let mut margin = adjusted_notional * initial_margin_f64;
-        // add taker fee
-        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap() * 2.0;
-        let use_quote_for_inverse = use_quote_for_inverse.unwrap_or(false);
-        if instrument.is_inverse() && !use_quote_for_inverse {
-            Money::new(margin, instrument.base_currency().unwrap()).unwrap()
-        } else {
-            Money::new(margin, instrument.quote_currency()).unwrap()
-        }
+        self.margin_model.calculate_initial_margin(
+            &instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
     }
 
+    /// Returns the maintenance margin for the given `quantity` of the `instrument` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margin cannot be calculated.
     pub fn calculate_maintenance_margin<T: Instrument>(
         &mut self,
         instrument: T,
         quantity: Quantity,
         price: Price,
         use_quote_for_inverse: Option<bool>,
-    ) -> Money {
-        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
+    ) -> anyhow::Result<Money> {
         let leverage = self.get_leverage(&instrument.id());
         if leverage == 0.0 {
             self.leverages
                 .insert(instrument.id(), self.default_leverage);
         }
-        let adjusted_notional = notional / leverage;
-        let margin_maint_f64 = instrument.margin_maint().to_f64().unwrap();
-        let mut margin = adjusted_notional * margin_maint_f64;
-        // Add taker fee
-        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap();
-        let use_quote_for_inverse = use_quote_for_inverse.unwrap_or(false);
-        if instrument.is_inverse() && !use_quote_for_inverse {
-            Money::new(margin, instrument.base_currency().unwrap()).unwrap()
-        } else {
-            Money::new(margin, instrument.quote_currency()).unwrap()
+        self.margin_model.calculate_maintenance_margin(
+            &instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    /// Returns the maintenance margins for the given open `positions` across instruments,
+    /// calculated together by the margin model (allowing offsets between positions).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margins cannot be calculated.
+    pub fn calculate_positions_margin(
+        &self,
+        positions: &[MarginPosition],
+    ) -> anyhow::Result<HashMap<InstrumentId, Money>> {
+        self.margin_model.calculate_positions_margin(positions)
+    }
+
+    /// Updates the maintenance margins for the given open `positions` across instruments,
+    /// calculated together by the margin model (allowing offsets between positions).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margins cannot be calculated.
+    pub fn update_positions_margin(&mut self, positions: &[MarginPosition]) -> anyhow::Result<()> {
+        let margins = self.calculate_positions_margin(positions)?;
+        for (instrument_id, margin_maint) in margins {
+            if margin_maint.raw == 0 {
+                self.clear_maintenance_margin(instrument_id);
+            } else {
+                self.update_maintenance_margin(instrument_id, margin_maint);
+            }
         }
+        Ok(())
     }
 
     pub fn recalculate_balance(&mut self, currency: Currency) {
@@ -293,6 +366,23 @@ impl Account for MarginAccount {
     fn balances_locked(&self) -> HashMap<Currency, Money> {
         self.base_balances_locked()
     }
+    fn balance_total_converted(
+        &self,
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        self.base_balance_total_converted(currency, cache, price_type)
+    }
+    fn convert_total(
+        &self,
+        amounts: &[Money],
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>> {
+        self.base_convert_total(amounts, currency, cache, price_type)
+    }
     fn last_event(&self) -> Option<AccountState> {
         self.base_last_event()
     }
@@ -326,11 +416,25 @@ impl Account for MarginAccount {
     }
     fn calculate_pnls<T: Instrument>(
         &self,
-        instrument: T,
+        _instrument: T,
         fill: OrderFilled,
         position: Option<Position>,
     ) -> anyhow::Result<Vec<Money>> {
-        self.base_calculate_pnls(instrument, fill, position)
+        // Only the realized PnL of a position reducing fill settles to a margin account
+        let pnls = position
+            .filter(|position| {
+                position.quantity.raw != 0 && position.is_opposite_side(fill.order_side)
+            })
+            .map(|position| {
+                // PnL quantity is capped at the position quantity
+                vec![position.calculate_pnl(
+                    position.avg_px_open,
+                    fill.last_px.as_f64(),
+                    fill.last_qty,
+                )]
+            })
+            .unwrap_or_default();
+        Ok(pnls)
     }
     fn calculate_commission<T: Instrument>(
         &self,
@@ -386,15 +490,27 @@ impl Hash for MarginAccount {
 mod tests {
     use std::collections::HashMap;
 
+    use nautilus_common::{factories::OrderFactory, stubs::*};
     use nautilus_model::{
+        enums::OrderSide,
         events::account::{state::AccountState, stubs::*},
-        identifiers::{instrument_id::InstrumentId, stubs::*},
-        instruments::{crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair, stubs::*},
+        identifiers::{
+            instrument_id::InstrumentId, position_id::PositionId, strategy_id::StrategyId, stubs::*,
+        },
+        instruments::{
+            crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair, stubs::*,
+            InstrumentType,
+        },
+        orders::{market::MarketOrder, stubs::TestOrderEventStubs},
+        position::Position,
         types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
     };
     use rstest::rstest;
 
-    use crate::account::{margin::MarginAccount, stubs::*, Account};
+    use crate::{
+        account::{margin::MarginAccount, stubs::*, Account},
+        margin::{MarginPosition, PortfolioMarginModel},
+    };
 
     #[rstest]
     fn test_display(margin_account: MarginAccount) {
@@ -533,18 +649,46 @@ mod tests {
         assert_eq!(margins, vec![margin]);
     }
 
+    #[rstest]
+    fn test_clear_margins(
+        mut margin_account: MarginAccount,
+        instrument_id_aud_usd_sim: InstrumentId,
+    ) {
+        margin_account.update_initial_margin(instrument_id_aud_usd_sim, Money::from("1000 USD"));
+        margin_account.update_maintenance_margin(instrument_id_aud_usd_sim, Money::from("500 USD"));
+
+        margin_account.clear_initial_margin(instrument_id_aud_usd_sim);
+        assert_eq!(
+            margin_account.initial_margin(instrument_id_aud_usd_sim),
+            Money::from("0 USD")
+        );
+        assert_eq!(
+            margin_account.balance_locked(None),
+            Some(Money::from("500 USD"))
+        );
+
+        margin_account.clear_maintenance_margin(instrument_id_aud_usd_sim);
+        assert!(margin_account.margins.is_empty());
+        assert_eq!(
+            margin_account.balance_locked(None),
+            Some(Money::from("0 USD"))
+        );
+    }
+
     #[rstest]
     fn test_calculate_margin_init_with_leverage(
         mut margin_account: MarginAccount,
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_leverage(audusd_sim.id, 50.0);
-        let result = margin_account.calculate_initial_margin(
-            audusd_sim,
-            Quantity::from(100_000),
-            Price::from("0.8000"),
-            None,
-        );
+        let result = margin_account
+            .calculate_initial_margin(
+                audusd_sim,
+                Quantity::from(100_000),
+                Price::from("0.8000"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("48.06 USD"));
     }
 
@@ -554,12 +698,14 @@ mod tests {
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_default_leverage(10.0);
-        let result = margin_account.calculate_initial_margin(
-            audusd_sim,
-            Quantity::from(100_000),
-            Price::from("0.8"),
-            None,
-        );
+        let result = margin_account
+            .calculate_initial_margin(
+                audusd_sim,
+                Quantity::from(100_000),
+                Price::from("0.8"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("240.32 USD"));
     }
 
@@ -568,19 +714,23 @@ mod tests {
         mut margin_account: MarginAccount,
         xbtusd_bitmex: CryptoPerpetual,
     ) {
-        let result_use_quote_inverse_true = margin_account.calculate_initial_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            Some(false),
-        );
+        let result_use_quote_inverse_true = margin_account
+            .calculate_initial_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                Some(false),
+            )
+            .unwrap();
         assert_eq!(result_use_quote_inverse_true, Money::from("0.10005568 BTC"));
-        let result_use_quote_inverse_false = margin_account.calculate_initial_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            Some(true),
-        );
+        let result_use_quote_inverse_false = margin_account
+            .calculate_initial_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                Some(true),
+            )
+            .unwrap();
         assert_eq!(result_use_quote_inverse_false, Money::from("1150 USD"));
     }
 
@@ -589,12 +739,14 @@ mod tests {
         mut margin_account: MarginAccount,
         xbtusd_bitmex: CryptoPerpetual,
     ) {
-        let result = margin_account.calculate_maintenance_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("11493.60"),
-            None,
-        );
+        let result = margin_account
+            .calculate_maintenance_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("11493.60"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("0.03697710 BTC"));
     }
 
@@ -604,12 +756,14 @@ mod tests {
         audusd_sim: CurrencyPair,
     ) {
         margin_account.set_default_leverage(50.0);
-        let result = margin_account.calculate_maintenance_margin(
-            audusd_sim,
-            Quantity::from(1_000_000),
-            Price::from("1"),
-            None,
-        );
+        let result = margin_account
+            .calculate_maintenance_margin(
+                audusd_sim,
+                Quantity::from(1_000_000),
+                Price::from("1"),
+                None,
+            )
+            .unwrap();
         assert_eq!(result, Money::from("600.40 USD"));
     }
 
@@ -619,12 +773,102 @@ mod tests {
         xbtusd_bitmex: CryptoPerpetual,
     ) {
         margin_account.set_default_leverage(10.0);
-        let result = margin_account.calculate_maintenance_margin(
-            xbtusd_bitmex,
-            Quantity::from(100_000),
-            Price::from("100000.00"),
+        let result = margin_account
+            .calculate_maintenance_margin(
+                xbtusd_bitmex,
+                Quantity::from(100_000),
+                Price::from("100000.00"),
+                None,
+            )
+            .unwrap();
+        assert_eq!(result, Money::from("0.00042500 BTC"));
+    }
+
+    #[rstest]
+    fn test_update_positions_margin_with_portfolio_model_offsets_hedged_positions(
+        mut margin_account: MarginAccount,
+        audusd_sim: CurrencyPair,
+    ) {
+        margin_account.set_default_leverage(50.0);
+        let instrument = InstrumentType::CurrencyPair(audusd_sim);
+        let long = MarginPosition::new(instrument.clone(), 1_000_000.0, Price::from("1"), 50.0);
+        let short = MarginPosition::new(instrument, -1_000_000.0, Price::from("1"), 50.0);
+
+        margin_account
+            .update_positions_margin(std::slice::from_ref(&long))
+            .unwrap();
+        assert_eq!(
+            margin_account.maintenance_margin(audusd_sim.id),
+            Money::from("600.40 USD")
+        );
+
+        margin_account.set_margin_model(Box::new(PortfolioMarginModel::new(vec![], 0.0).unwrap()));
+        margin_account
+            .update_positions_margin(&[long, short])
+            .unwrap();
+        assert!(!margin_account.margins.contains_key(&audusd_sim.id));
+    }
+
+    #[rstest]
+    fn test_calculate_pnls_realizes_position_reducing_fill(
+        margin_account: MarginAccount,
+        mut order_factory: OrderFactory,
+        audusd_sim: CurrencyPair,
+    ) {
+        let order1 = order_factory.market(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from("100000"),
+            None,
+            None,
+            None,
+            None,
+            None,
             None,
         );
-        assert_eq!(result, Money::from("0.00042500 BTC"));
+        let fill1 = TestOrderEventStubs::order_filled::<MarketOrder, CurrencyPair>(
+            &order1,
+            &audusd_sim,
+            Some(StrategyId::new("S-001").unwrap()),
+            None,
+            Some(PositionId::new("P-123456").unwrap()),
+            Some(Price::from("0.80000")),
+            None,
+            None,
+            None,
+        );
+        let position = Position::new(audusd_sim, fill1).unwrap();
+        let order2 = order_factory.market(
+            audusd_sim.id,
+            OrderSide::Sell,
+            Quantity::from("150000"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let fill2 = TestOrderEventStubs::order_filled::<MarketOrder, CurrencyPair>(
+            &order2,
+            &audusd_sim,
+            Some(StrategyId::new("S-001").unwrap()),
+            None,
+            Some(PositionId::new("P-123456").unwrap()),
+            Some(Price::from("0.80010")),
+            None,
+            None,
+            None,
+        );
+
+        let opening_pnls = margin_account
+            .calculate_pnls(audusd_sim, fill1, None)
+            .unwrap();
+        let closing_pnls = margin_account
+            .calculate_pnls(audusd_sim, fill2, Some(position))
+            .unwrap();
+
+        assert!(opening_pnls.is_empty());
+        assert_eq!(closing_pnls, vec![Money::from("10.00 USD")]);
     }
 }
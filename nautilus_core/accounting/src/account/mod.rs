@@ -15,8 +15,9 @@
 
 use std::collections::HashMap;
 
+use nautilus_common::cache::Cache;
 use nautilus_model::{
-    enums::{LiquiditySide, OrderSide},
+    enums::{LiquiditySide, OrderSide, PriceType},
     events::{account::state::AccountState, order::filled::OrderFilled},
     instruments::Instrument,
     position::Position,
@@ -33,6 +34,19 @@ pub trait Account {
 
     fn balance_locked(&self, currency: Option<Currency>) -> Option<Money>;
     fn balances_locked(&self) -> HashMap<Currency, Money>;
+    fn balance_total_converted(
+        &self,
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>>;
+    fn convert_total(
+        &self,
+        amounts: &[Money],
+        currency: Option<Currency>,
+        cache: &Cache,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<Money>>;
     fn last_event(&self) -> Option<AccountState>;
     fn events(&self) -> Vec<AccountState>;
     fn event_count(&self) -> usize;
@@ -66,6 +80,7 @@ pub trait Account {
     ) -> anyhow::Result<Money>;
 }
 
+pub mod any;
 pub mod base;
 pub mod cash;
 pub mod margin;
@@ -0,0 +1,611 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Fee models for calculating the commission charged on fills.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+};
+
+use nautilus_core::{
+    datetime::NANOSECONDS_IN_SECOND,
+    time::{AtomicTime, UnixNanos},
+};
+use nautilus_model::{
+    enums::LiquiditySide,
+    instruments::Instrument,
+    types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+/// The default rolling window for volume tiers (30 days).
+pub const DEFAULT_VOLUME_WINDOW_NS: u64 = 30 * 24 * 60 * 60 * NANOSECONDS_IN_SECOND;
+
+/// Provides the commission charged for a fill.
+pub trait FeeModel: Debug + Send {
+    /// Returns the commission for a fill of `last_qty` at `last_px` with the given `liquidity_side`.
+    ///
+    /// A negative commission is a rebate.
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money>;
+
+    /// Records a fill, for fee models which depend on the trading history.
+    fn record_fill(
+        &mut self,
+        _instrument: &dyn Instrument,
+        _last_qty: Quantity,
+        _last_px: Price,
+        _ts_event: UnixNanos,
+    ) {
+    }
+}
+
+/// Returns the currency commissions are charged in for the given `instrument`.
+fn commission_currency(
+    instrument: &dyn Instrument,
+    use_quote_for_inverse: Option<bool>,
+) -> Currency {
+    if instrument.is_inverse() && !use_quote_for_inverse.unwrap_or(false) {
+        instrument
+            .base_currency()
+            .expect("Inverse instrument must have a base currency")
+    } else {
+        instrument.quote_currency()
+    }
+}
+
+fn notional_commission(
+    instrument: &dyn Instrument,
+    last_qty: Quantity,
+    last_px: Price,
+    fee: Decimal,
+    use_quote_for_inverse: Option<bool>,
+) -> anyhow::Result<Money> {
+    let notional = instrument
+        .calculate_notional_value(last_qty, last_px, use_quote_for_inverse)
+        .as_f64();
+    let Some(fee) = fee.to_f64() else {
+        anyhow::bail!("Invalid fee, was {fee}");
+    };
+    Money::new(
+        notional * fee,
+        commission_currency(instrument, use_quote_for_inverse),
+    )
+}
+
+fn check_liquidity_side(liquidity_side: LiquiditySide) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        liquidity_side != LiquiditySide::NoLiquiditySide,
+        "Invalid liquidity side, was {liquidity_side}"
+    );
+    Ok(())
+}
+
+/// Provides a fee model which charges the notional value of a fill multiplied by the
+/// maker or taker fee rate of the instrument.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MakerTakerFeeModel;
+
+impl FeeModel for MakerTakerFeeModel {
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let fee = match liquidity_side {
+            LiquiditySide::Maker => instrument.maker_fee(),
+            _ => instrument.taker_fee(),
+        };
+        notional_commission(instrument, last_qty, last_px, fee, use_quote_for_inverse)
+    }
+}
+
+/// Provides a fee model which charges a fixed commission per unit (share or contract)
+/// filled, with optional minimum and maximum commissions per fill.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedFeeModel {
+    /// The commission per unit filled (may be finer than the currency precision).
+    pub commission_per_unit: Decimal,
+    /// The currency the commission is charged in.
+    pub currency: Currency,
+    /// The minimum commission per fill.
+    pub min_commission: Option<Money>,
+    /// The maximum commission per fill.
+    pub max_commission: Option<Money>,
+}
+
+impl FixedFeeModel {
+    /// Creates a new [`FixedFeeModel`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - The `min_commission` or `max_commission` currency differs from `currency`.
+    /// - The `min_commission` is greater than the `max_commission`.
+    pub fn new(
+        commission_per_unit: Decimal,
+        currency: Currency,
+        min_commission: Option<Money>,
+        max_commission: Option<Money>,
+    ) -> anyhow::Result<Self> {
+        for limit in [min_commission, max_commission].iter().flatten() {
+            anyhow::ensure!(
+                limit.currency == currency,
+                "Commission limit currency {} does not match commission currency {}",
+                limit.currency.code,
+                currency.code
+            );
+        }
+        if let (Some(min), Some(max)) = (min_commission, max_commission) {
+            anyhow::ensure!(
+                min <= max,
+                "`min_commission` {min} was greater than `max_commission` {max}"
+            );
+        }
+        Ok(Self {
+            commission_per_unit,
+            currency,
+            min_commission,
+            max_commission,
+        })
+    }
+}
+
+impl FeeModel for FixedFeeModel {
+    fn get_commission(
+        &self,
+        _instrument: &dyn Instrument,
+        last_qty: Quantity,
+        _last_px: Price,
+        liquidity_side: LiquiditySide,
+        _use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let Some(per_unit) = self.commission_per_unit.to_f64() else {
+            anyhow::bail!(
+                "Invalid commission per unit, was {}",
+                self.commission_per_unit
+            );
+        };
+        let mut commission = Money::new(per_unit * last_qty.as_f64(), self.currency)?;
+        if let Some(min) = self.min_commission {
+            commission = commission.max(min);
+        }
+        if let Some(max) = self.max_commission {
+            commission = commission.min(max);
+        }
+        Ok(commission)
+    }
+}
+
+/// Represents a fee tier, which applies once the rolling volume reaches `min_volume`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeTier {
+    /// The rolling notional volume at which the tier applies (in the currency volume is tiered in).
+    pub min_volume: Money,
+    /// The maker fee rate for the tier (negative for a rebate).
+    pub maker_fee: Decimal,
+    /// The taker fee rate for the tier (negative for a rebate).
+    pub taker_fee: Decimal,
+}
+
+impl FeeTier {
+    #[must_use]
+    pub fn new(min_volume: Money, maker_fee: Decimal, taker_fee: Decimal) -> Self {
+        Self {
+            min_volume,
+            maker_fee,
+            taker_fee,
+        }
+    }
+}
+
+/// Provides a fee model with maker and taker fee rates tiered on the notional volume
+/// filled over a rolling window (30 days by default).
+///
+/// Fills are recorded with [`FeeModel::record_fill`]. Volumes are kept separately for
+/// each notional currency, and fills drop out of the window as the `clock` moves on (so
+/// the tier for a commission is always for the volume within the window at that time).
+///
+/// Tiers are all in the currency of their minimum volumes, so only instruments with a
+/// notional value in that currency can be charged commission.
+#[derive(Clone, Debug)]
+pub struct TieredFeeModel {
+    tiers: Vec<FeeTier>,
+    window_ns: u64,
+    clock: &'static AtomicTime,
+    fills: VecDeque<(UnixNanos, Currency, f64)>,
+    volumes: HashMap<Currency, f64>,
+}
+
+impl TieredFeeModel {
+    /// Creates a new [`TieredFeeModel`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - The `tiers` are empty.
+    /// - The `tiers` minimum volumes are not all in the same currency.
+    /// - The `tiers` minimum volumes are not strictly increasing from zero.
+    /// - The `window_ns` is zero.
+    pub fn new(
+        tiers: Vec<FeeTier>,
+        window_ns: Option<u64>,
+        clock: &'static AtomicTime,
+    ) -> anyhow::Result<Self> {
+        let window_ns = window_ns.unwrap_or(DEFAULT_VOLUME_WINDOW_NS);
+        anyhow::ensure!(!tiers.is_empty(), "`tiers` was empty");
+        let currency = tiers[0].min_volume.currency;
+        anyhow::ensure!(
+            tiers
+                .iter()
+                .all(|tier| tier.min_volume.currency == currency),
+            "Tier `min_volume`s must all be in {}",
+            currency.code
+        );
+        anyhow::ensure!(
+            tiers[0].min_volume.is_zero(),
+            "First tier `min_volume` must be zero, was {}",
+            tiers[0].min_volume
+        );
+        anyhow::ensure!(
+            tiers
+                .windows(2)
+                .all(|pair| pair[0].min_volume < pair[1].min_volume),
+            "Tier `min_volume`s must be strictly increasing"
+        );
+        anyhow::ensure!(window_ns > 0, "`window_ns` must be positive");
+
+        Ok(Self {
+            tiers,
+            window_ns,
+            clock,
+            fills: VecDeque::new(),
+            volumes: HashMap::new(),
+        })
+    }
+
+    /// Returns the currency volume is tiered in.
+    #[must_use]
+    pub fn currency(&self) -> Currency {
+        self.tiers[0].min_volume.currency
+    }
+
+    /// Returns the notional volume in the given `currency` filled within the window
+    /// ending at the current time.
+    #[must_use]
+    pub fn volume(&self, currency: Currency) -> f64 {
+        let ts_now = self.clock.get_time_ns();
+        let expired: f64 = self
+            .fills
+            .iter()
+            .take_while(|(ts, _, _)| self.is_expired(*ts, ts_now))
+            .filter(|(_, fill_currency, _)| *fill_currency == currency)
+            .map(|(_, _, notional)| notional)
+            .sum();
+        let volume = self.volumes.get(&currency).copied().unwrap_or(0.0) - expired;
+        volume.max(0.0)
+    }
+
+    /// Returns the fee tier for the current volume in the tier currency.
+    #[must_use]
+    pub fn current_tier(&self) -> &FeeTier {
+        let volume = self.volume(self.currency());
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume.as_f64())
+            .unwrap_or(&self.tiers[0])
+    }
+
+    fn is_expired(&self, ts: UnixNanos, ts_now: UnixNanos) -> bool {
+        ts.saturating_add(self.window_ns) <= ts_now
+    }
+
+    fn roll_window(&mut self, ts_now: UnixNanos) {
+        while let Some((ts, currency, notional)) = self.fills.front().copied() {
+            if !self.is_expired(ts, ts_now) {
+                break;
+            }
+            self.fills.pop_front();
+            if let Some(volume) = self.volumes.get_mut(&currency) {
+                *volume -= notional;
+            }
+        }
+        if self.fills.is_empty() {
+            self.volumes.clear(); // Avoid accumulating rounding errors
+        }
+    }
+}
+
+impl FeeModel for TieredFeeModel {
+    fn get_commission(
+        &self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidity_side: LiquiditySide,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        check_liquidity_side(liquidity_side)?;
+        let notional_currency = instrument
+            .calculate_notional_value(last_qty, last_px, None)
+            .currency;
+        anyhow::ensure!(
+            notional_currency == self.currency(),
+            "Cannot tier fees for {} notional, tiers are in {}",
+            notional_currency.code,
+            self.currency().code
+        );
+        let tier = self.current_tier();
+        let fee = match liquidity_side {
+            LiquiditySide::Maker => tier.maker_fee,
+            _ => tier.taker_fee,
+        };
+        notional_commission(instrument, last_qty, last_px, fee, use_quote_for_inverse)
+    }
+
+    fn record_fill(
+        &mut self,
+        instrument: &dyn Instrument,
+        last_qty: Quantity,
+        last_px: Price,
+        ts_event: UnixNanos,
+    ) {
+        self.roll_window(ts_event);
+        let notional = instrument.calculate_notional_value(last_qty, last_px, None);
+        self.fills
+            .push_back((ts_event, notional.currency, notional.as_f64()));
+        *self.volumes.entry(notional.currency).or_insert(0.0) += notional.as_f64();
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::instruments::{
+        crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair, equity::Equity, stubs::*,
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[rstest]
+    #[case(LiquiditySide::Maker, Money::from("-0.00218331 BTC"))]
+    #[case(LiquiditySide::Taker, Money::from("0.00654993 BTC"))]
+    fn test_maker_taker_inverse(
+        #[case] liquidity_side: LiquiditySide,
+        #[case] expected: Money,
+        xbtusd_bitmex: CryptoPerpetual,
+    ) {
+        let commission = MakerTakerFeeModel
+            .get_commission(
+                &xbtusd_bitmex,
+                Quantity::from("100000"),
+                Price::from("11450.50"),
+                liquidity_side,
+                None,
+            )
+            .unwrap();
+        assert_eq!(commission, expected);
+    }
+
+    #[rstest]
+    fn test_maker_taker_with_no_liquidity_side_fails(audusd_sim: CurrencyPair) {
+        let result = MakerTakerFeeModel.get_commission(
+            &audusd_sim,
+            Quantity::from("100000"),
+            Price::from("0.80000"),
+            LiquiditySide::NoLiquiditySide,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case("100", Money::from("1.00 USD"))] // Minimum
+    #[case("500", Money::from("2.50 USD"))]
+    #[case("10000", Money::from("20.00 USD"))] // Maximum
+    fn test_fixed_per_unit_with_min_max(
+        #[case] quantity: &str,
+        #[case] expected: Money,
+        equity_aapl: Equity,
+    ) {
+        let fee_model = FixedFeeModel::new(
+            dec!(0.005),
+            Currency::USD(),
+            Some(Money::from("1.00 USD")),
+            Some(Money::from("20.00 USD")),
+        )
+        .unwrap();
+
+        let commission = fee_model
+            .get_commission(
+                &equity_aapl,
+                Quantity::from(quantity),
+                Price::from("180.00"),
+                LiquiditySide::Taker,
+                None,
+            )
+            .unwrap();
+        assert_eq!(commission, expected);
+    }
+
+    #[rstest]
+    fn test_fixed_with_invalid_limits_fails() {
+        let usd = Currency::USD();
+        assert!(FixedFeeModel::new(dec!(0.005), usd, Some(Money::from("1.00 EUR")), None).is_err());
+        assert!(FixedFeeModel::new(
+            dec!(0.005),
+            usd,
+            Some(Money::from("2.00 USD")),
+            Some(Money::from("1.00 USD"))
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    #[case(vec![])]
+    #[case(vec![FeeTier::new(Money::from("100 USD"), dec!(0.001), dec!(0.002))])]
+    #[case(vec![
+        FeeTier::new(Money::from("0 USD"), dec!(0.001), dec!(0.002)),
+        FeeTier::new(Money::from("0 USD"), dec!(0.0), dec!(0.001)),
+    ])]
+    #[case(vec![
+        FeeTier::new(Money::from("0 USD"), dec!(0.001), dec!(0.002)),
+        FeeTier::new(Money::from("1000000 EUR"), dec!(0.0), dec!(0.001)),
+    ])]
+    fn test_tiered_with_invalid_tiers_fails(#[case] tiers: Vec<FeeTier>) {
+        let clock = Box::leak(Box::new(AtomicTime::new(false, 0)));
+        assert!(TieredFeeModel::new(tiers, None, clock).is_err());
+    }
+
+    fn tiered_fee_model(clock: &'static AtomicTime) -> TieredFeeModel {
+        TieredFeeModel::new(
+            vec![
+                FeeTier::new(Money::from("0 USD"), dec!(0.0002), dec!(0.0004)),
+                FeeTier::new(Money::from("1000000 USD"), dec!(-0.0001), dec!(0.0002)),
+            ],
+            None,
+            clock,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_tiered_steps_through_tiers_and_rolls_window(audusd_sim: CurrencyPair) {
+        let clock = Box::leak(Box::new(AtomicTime::new(false, 0)));
+        let mut fee_model = tiered_fee_model(clock);
+        let usd = Currency::USD();
+        let qty = Quantity::from("1000000");
+        let px = Price::from("0.80000");
+
+        let maker = fee_model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Maker, None)
+            .unwrap();
+        assert_eq!(maker, Money::from("160.00 USD"));
+
+        fee_model.record_fill(&audusd_sim, qty, px, 0);
+        fee_model.record_fill(&audusd_sim, qty, px, 1);
+        assert_eq!(fee_model.volume(usd), 1_600_000.0);
+
+        let rebate = fee_model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Maker, None)
+            .unwrap();
+        assert_eq!(rebate, Money::from("-80.00 USD"));
+
+        // The first fill drops out of the window, the second stays in
+        fee_model.record_fill(
+            &audusd_sim,
+            Quantity::from("1"),
+            px,
+            DEFAULT_VOLUME_WINDOW_NS,
+        );
+        clock.set_time(DEFAULT_VOLUME_WINDOW_NS);
+        assert_eq!(fee_model.current_tier().min_volume, Money::from("0 USD"));
+
+        // All fills drop out of the window
+        fee_model.record_fill(
+            &audusd_sim,
+            Quantity::from("1"),
+            px,
+            3 * DEFAULT_VOLUME_WINDOW_NS,
+        );
+        clock.set_time(3 * DEFAULT_VOLUME_WINDOW_NS);
+        assert_eq!(fee_model.volume(usd), 0.8);
+    }
+
+    #[rstest]
+    fn test_tiered_volume_decays_without_new_fills(audusd_sim: CurrencyPair) {
+        let clock = Box::leak(Box::new(AtomicTime::new(false, 0)));
+        let mut fee_model = tiered_fee_model(clock);
+        let qty = Quantity::from("2000000");
+        let px = Price::from("0.80000");
+        fee_model.record_fill(&audusd_sim, qty, px, 0);
+
+        let rebate = fee_model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Maker, None)
+            .unwrap();
+        assert_eq!(rebate, Money::from("-160.00 USD"));
+
+        clock.set_time(DEFAULT_VOLUME_WINDOW_NS);
+
+        let commission = fee_model
+            .get_commission(&audusd_sim, qty, px, LiquiditySide::Maker, None)
+            .unwrap();
+        assert_eq!(fee_model.volume(Currency::USD()), 0.0);
+        assert_eq!(commission, Money::from("320.00 USD"));
+    }
+
+    #[rstest]
+    fn test_tiered_volume_is_kept_by_currency(
+        audusd_sim: CurrencyPair,
+        usdjpy_idealpro: CurrencyPair,
+    ) {
+        let clock = Box::leak(Box::new(AtomicTime::new(false, 0)));
+        let mut fee_model = tiered_fee_model(clock);
+        fee_model.record_fill(
+            &usdjpy_idealpro,
+            Quantity::from("1000000"),
+            Price::from("110.000"),
+            0,
+        );
+
+        assert_eq!(fee_model.volume(Currency::JPY()), 110_000_000.0);
+        assert_eq!(fee_model.volume(Currency::USD()), 0.0);
+        assert_eq!(fee_model.current_tier().min_volume, Money::from("0 USD"));
+
+        let commission = fee_model
+            .get_commission(
+                &audusd_sim,
+                Quantity::from("1000000"),
+                Price::from("0.80000"),
+                LiquiditySide::Maker,
+                None,
+            )
+            .unwrap();
+        assert_eq!(commission, Money::from("160.00 USD"));
+    }
+
+    #[rstest]
+    fn test_tiered_with_notional_in_other_currency_fails(usdjpy_idealpro: CurrencyPair) {
+        let clock = Box::leak(Box::new(AtomicTime::new(false, 0)));
+        let fee_model = tiered_fee_model(clock);
+
+        let result = fee_model.get_commission(
+            &usdjpy_idealpro,
+            Quantity::from("1000000"),
+            Price::from("110.000"),
+            LiquiditySide::Maker,
+            None,
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -14,6 +14,8 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod account;
+pub mod fee;
+pub mod margin;
 #[cfg(test)]
 pub mod stubs;
 
@@ -0,0 +1,795 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Margin models for calculating the initial and maintenance margins of a margin account.
+
+use std::{collections::HashMap, fmt::Debug};
+
+use nautilus_model::{
+    identifiers::instrument_id::InstrumentId,
+    instruments::{Instrument, InstrumentType},
+    position::Position,
+    types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
+};
+use rust_decimal::prelude::ToPrimitive;
+use ustr::Ustr;
+
+/// Provides the initial and maintenance margins for a margin account.
+pub trait MarginModel: Debug + Send {
+    /// Returns the initial margin for an order of `quantity` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margin currency or amount is invalid.
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money>;
+
+    /// Returns the maintenance margin for a position of `quantity` at `price`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the margin currency or amount is invalid.
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money>;
+
+    /// Returns the maintenance margins for the given open `positions`, allocated by instrument.
+    ///
+    /// The default implementation margins each position separately.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a position quantity or margin is invalid.
+    fn calculate_positions_margin(
+        &self,
+        positions: &[MarginPosition],
+    ) -> anyhow::Result<HashMap<InstrumentId, Money>> {
+        let mut margins: HashMap<InstrumentId, Money> = HashMap::new();
+        for position in positions {
+            let margin = position.standalone_margin(self)?;
+            add_margin(&mut margins, position.instrument.id(), margin);
+        }
+        Ok(margins)
+    }
+}
+
+fn add_margin(
+    margins: &mut HashMap<InstrumentId, Money>,
+    instrument_id: InstrumentId,
+    margin: Money,
+) {
+    margins
+        .entry(instrument_id)
+        .and_modify(|total| *total += margin)
+        .or_insert(margin);
+}
+
+fn margin_currency(
+    instrument: &dyn Instrument,
+    use_quote_for_inverse: Option<bool>,
+) -> anyhow::Result<Currency> {
+    if instrument.is_inverse() && !use_quote_for_inverse.unwrap_or(false) {
+        instrument.base_currency().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Inverse instrument {} has no base currency",
+                instrument.id()
+            )
+        })
+    } else {
+        Ok(instrument.quote_currency())
+    }
+}
+
+/// Returns the notional value of one contract of the `instrument` at `px`.
+fn contract_notional(instrument: &InstrumentType, px: f64) -> f64 {
+    if instrument.is_inverse() {
+        instrument.multiplier().as_f64() / px
+    } else {
+        instrument.multiplier().as_f64() * px
+    }
+}
+
+/// Represents a leg of a spread instrument, as the signed number of leg contracts per
+/// spread contract (negative when the leg is sold).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpreadLeg {
+    /// The instrument ID of the leg.
+    pub instrument_id: InstrumentId,
+    /// The signed number of leg contracts per spread contract.
+    pub ratio: f64,
+}
+
+impl SpreadLeg {
+    #[must_use]
+    pub fn new(instrument_id: InstrumentId, ratio: f64) -> Self {
+        Self {
+            instrument_id,
+            ratio,
+        }
+    }
+}
+
+/// Represents a leg of a spread position as an input to a margin model.
+#[derive(Clone, Debug)]
+pub struct MarginLeg {
+    /// The instrument for the leg.
+    pub instrument: InstrumentType,
+    /// The signed number of leg contracts per spread contract (negative when sold).
+    pub ratio: f64,
+    /// The mark price of the leg instrument.
+    pub price: Price,
+    /// The sensitivity of the leg price to the underlying price (1.0 for delta-one instruments).
+    pub delta: f64,
+}
+
+impl MarginLeg {
+    /// Creates a new delta-one [`MarginLeg`] instance.
+    #[must_use]
+    pub fn new(instrument: InstrumentType, ratio: f64, price: Price) -> Self {
+        Self {
+            instrument,
+            ratio,
+            price,
+            delta: 1.0,
+        }
+    }
+
+    /// Returns the leg with the given `delta`.
+    #[must_use]
+    pub fn with_delta(mut self, delta: f64) -> Self {
+        self.delta = delta;
+        self
+    }
+}
+
+/// Represents an open position as an input to a margin model.
+#[derive(Clone, Debug)]
+pub struct MarginPosition {
+    /// The instrument for the position.
+    pub instrument: InstrumentType,
+    /// The signed quantity of the position (negative when short).
+    pub signed_qty: f64,
+    /// The mark price of the instrument.
+    pub price: Price,
+    /// The leverage for the instrument.
+    pub leverage: f64,
+    /// The sensitivity of the instrument price to the underlying price (1.0 for delta-one instruments).
+    pub delta: f64,
+    /// The change in instrument price for a 1.0 (100%) change in implied volatility.
+    pub vega: f64,
+    /// The underlying price, for instruments priced differently to their underlying (such as options).
+    pub underlying_px: Option<f64>,
+    /// The legs of a spread position (empty when the legs are unknown).
+    pub legs: Vec<MarginLeg>,
+}
+
+impl MarginPosition {
+    /// Creates a new delta-one [`MarginPosition`] instance.
+    #[must_use]
+    pub fn new(instrument: InstrumentType, signed_qty: f64, price: Price, leverage: f64) -> Self {
+        Self {
+            instrument,
+            signed_qty,
+            price,
+            leverage,
+            delta: 1.0,
+            vega: 0.0,
+            underlying_px: None,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Creates a new [`MarginPosition`] instance from the given open `position`.
+    #[must_use]
+    pub fn from_position(
+        instrument: InstrumentType,
+        position: &Position,
+        price: Price,
+        leverage: f64,
+    ) -> Self {
+        Self::new(instrument, position.signed_qty, price, leverage)
+    }
+
+    /// Returns the position with the given option sensitivities.
+    #[must_use]
+    pub fn with_greeks(mut self, delta: f64, vega: f64, underlying_px: f64) -> Self {
+        self.delta = delta;
+        self.vega = vega;
+        self.underlying_px = Some(underlying_px);
+        self
+    }
+
+    /// Returns the spread position with the given `legs`, from which its exposure and
+    /// spread credit are calculated.
+    #[must_use]
+    pub fn with_legs(mut self, legs: Vec<MarginLeg>) -> Self {
+        self.legs = legs;
+        self
+    }
+
+    /// Returns the underlying which positions are netted against.
+    ///
+    /// Instruments with no underlying are only netted against themselves.
+    #[must_use]
+    pub fn underlying(&self) -> Ustr {
+        match &self.instrument {
+            InstrumentType::CryptoFuture(inst) => inst.underlying.code,
+            InstrumentType::CryptoPerpetual(inst) => inst.base_currency.code,
+            InstrumentType::FuturesContract(inst) => inst.underlying,
+            InstrumentType::FuturesSpread(inst) => inst.underlying,
+            InstrumentType::OptionsContract(inst) => inst.underlying,
+            InstrumentType::OptionsSpread(inst) => inst.underlying,
+            InstrumentType::CurrencyPair(_) | InstrumentType::Equity(_) => {
+                Ustr::from(&self.instrument.id().to_string())
+            }
+        }
+    }
+
+    /// Returns if the position is in a spread instrument.
+    #[must_use]
+    pub fn is_spread(&self) -> bool {
+        matches!(
+            self.instrument,
+            InstrumentType::FuturesSpread(_) | InstrumentType::OptionsSpread(_)
+        )
+    }
+
+    /// Returns the signed delta-adjusted notional exposure to the underlying.
+    ///
+    /// The exposure of a spread position with legs is the net exposure of its legs.
+    #[must_use]
+    pub fn exposure(&self) -> f64 {
+        if !self.legs.is_empty() {
+            return self.legs.iter().map(|leg| self.leg_exposure(leg)).sum();
+        }
+        let px = self.underlying_px.unwrap_or(self.price.as_f64());
+        self.signed_qty * contract_notional(&self.instrument, px) * self.delta
+    }
+
+    /// Returns the signed delta-adjusted notional exposure of the given spread `leg`.
+    #[must_use]
+    pub fn leg_exposure(&self, leg: &MarginLeg) -> f64 {
+        self.signed_qty
+            * leg.ratio
+            * contract_notional(&leg.instrument, leg.price.as_f64())
+            * leg.delta
+    }
+
+    /// Returns the signed change in position value for a 1.0 change in implied volatility.
+    #[must_use]
+    pub fn vega_exposure(&self) -> f64 {
+        self.signed_qty * self.instrument.multiplier().as_f64() * self.vega
+    }
+
+    fn quantity(&self) -> anyhow::Result<Quantity> {
+        Quantity::new(self.signed_qty.abs(), self.instrument.size_precision())
+    }
+
+    fn standalone_margin<M: MarginModel + ?Sized>(&self, model: &M) -> anyhow::Result<Money> {
+        model.calculate_maintenance_margin(
+            &self.instrument,
+            self.quantity()?,
+            self.price,
+            self.leverage,
+            None,
+        )
+    }
+}
+
+/// Provides the per-instrument margin model, where margins are the leveraged notional value
+/// multiplied by the instrument margin rate, plus expected taker fees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LeveragedMarginModel;
+
+impl MarginModel for LeveragedMarginModel {
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
+        let adjusted_notional = notional / leverage;
+        let initial_margin_f64 = instrument.margin_init().to_f64().unwrap();
+        let mut margin = adjusted_notional * initial_margin_f64;
+        // Add taker fee
+        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap() * 2.0;
+        Money::new(margin, margin_currency(instrument, use_quote_for_inverse)?)
+    }
+
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        let notional = instrument.calculate_notional_value(quantity, price, use_quote_for_inverse);
+        let adjusted_notional = notional / leverage;
+        let margin_maint_f64 = instrument.margin_maint().to_f64().unwrap();
+        let mut margin = adjusted_notional * margin_maint_f64;
+        // Add taker fee
+        margin += adjusted_notional * instrument.taker_fee().to_f64().unwrap();
+        Money::new(margin, margin_currency(instrument, use_quote_for_inverse)?)
+    }
+}
+
+/// Represents a price and volatility shock scenario for portfolio margining.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarginScenario {
+    /// The relative underlying price move (e.g. 0.05 for up 5%).
+    pub price_shock: f64,
+    /// The absolute implied volatility move (e.g. 0.04 for up 4 vol points).
+    pub vol_shock: f64,
+    /// The fraction of the scenario loss counted towards the requirement.
+    pub weight: f64,
+}
+
+impl MarginScenario {
+    #[must_use]
+    pub fn new(price_shock: f64, vol_shock: f64, weight: f64) -> Self {
+        Self {
+            price_shock,
+            vol_shock,
+            weight,
+        }
+    }
+
+    /// Returns a SPAN-style grid of 16 scenarios for the given scan ranges.
+    ///
+    /// The grid is the underlying price unchanged and moved up and down by 1/3, 2/3 and 3/3
+    /// of the `price_scan_range`, each with volatility moved up and down by the
+    /// `vol_scan_range`, plus extreme moves up and down of twice the `price_scan_range`
+    /// with 35% of the loss counted.
+    #[must_use]
+    pub fn span_grid(price_scan_range: f64, vol_scan_range: f64) -> Vec<Self> {
+        let mut scenarios = Vec::with_capacity(16);
+        for fraction in [0.0, 1.0, -1.0, 2.0, -2.0, 3.0, -3.0] {
+            let price_shock = price_scan_range * fraction / 3.0;
+            scenarios.push(Self::new(price_shock, vol_scan_range, 1.0));
+            scenarios.push(Self::new(price_shock, -vol_scan_range, 1.0));
+        }
+        scenarios.push(Self::new(price_scan_range * 2.0, 0.0, 0.35));
+        scenarios.push(Self::new(-price_scan_range * 2.0, 0.0, 0.35));
+        scenarios
+    }
+}
+
+/// Provides a portfolio margin model, which margins positions in the same underlying together.
+///
+/// For each underlying (and margin currency) the requirement is the greater of:
+/// - The per-instrument maintenance margins scaled by the fraction of the gross exposure
+///   which is not offset by opposing positions (netting long and short positions).
+/// - The largest weighted loss over the scenario grid (scanning risk).
+///
+/// The requirement is capped at the sum of the per-instrument maintenance margins, then
+/// allocated to the instruments in proportion to their per-instrument margins.
+///
+/// A spread position with legs is margined as the sum of its leg margins, less the
+/// `spread_credit` fraction of the margin on the part of the legs which offset each other,
+/// and its exposure is the net exposure of the legs. A spread position without legs is
+/// margined as an outright position in the spread instrument, with no credit.
+///
+/// Initial margins for orders are per-instrument, as with the [`LeveragedMarginModel`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PortfolioMarginModel {
+    scenarios: Vec<MarginScenario>,
+    spread_credit: f64,
+}
+
+impl PortfolioMarginModel {
+    /// Creates a new [`PortfolioMarginModel`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - The `spread_credit` is not in the range [0, 1].
+    /// - Any scenario `weight` is not in the range (0, 1].
+    pub fn new(scenarios: Vec<MarginScenario>, spread_credit: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&spread_credit),
+            "`spread_credit` must be in the range [0, 1], was {spread_credit}"
+        );
+        for scenario in &scenarios {
+            anyhow::ensure!(
+                scenario.weight > 0.0 && scenario.weight <= 1.0,
+                "Scenario `weight` must be in the range (0, 1], was {}",
+                scenario.weight
+            );
+        }
+        Ok(Self {
+            scenarios,
+            spread_credit,
+        })
+    }
+
+    /// Returns the largest weighted loss over the scenario grid for the given `positions`.
+    #[must_use]
+    pub fn scanning_risk(&self, positions: &[&MarginPosition]) -> f64 {
+        self.scenarios
+            .iter()
+            .map(|scenario| {
+                let pnl: f64 = positions
+                    .iter()
+                    .map(|position| {
+                        position.exposure() * scenario.price_shock
+                            + position.vega_exposure() * scenario.vol_shock
+                    })
+                    .sum();
+                -pnl * scenario.weight
+            })
+            .fold(0.0, f64::max)
+    }
+
+    fn group_margins(
+        &self,
+        positions: &[&MarginPosition],
+    ) -> anyhow::Result<Vec<(InstrumentId, f64)>> {
+        let mut standalone: Vec<(InstrumentId, f64)> = Vec::with_capacity(positions.len());
+        for position in positions {
+            let margin = self.position_margin(position)?;
+            standalone.push((position.instrument.id(), margin));
+        }
+
+        let total_standalone: f64 = standalone.iter().map(|(_, margin)| margin).sum();
+        if total_standalone <= 0.0 {
+            return Ok(standalone);
+        }
+
+        let gross: f64 = positions.iter().map(|p| p.exposure().abs()).sum();
+        let net: f64 = positions.iter().map(|p| p.exposure()).sum();
+        let net_margin = if gross > 0.0 {
+            total_standalone * net.abs() / gross
+        } else {
+            total_standalone
+        };
+        let requirement = net_margin
+            .max(self.scanning_risk(positions))
+            .min(total_standalone);
+
+        Ok(standalone
+            .into_iter()
+            .map(|(id, margin)| (id, requirement * margin / total_standalone))
+            .collect())
+    }
+
+    /// Returns the standalone maintenance margin of the `position`, calculated from the
+    /// legs of a spread position (when known).
+    fn position_margin(&self, position: &MarginPosition) -> anyhow::Result<f64> {
+        if !position.is_spread() || position.legs.is_empty() {
+            return Ok(position.standalone_margin(&LeveragedMarginModel)?.as_f64());
+        }
+
+        let currency = margin_currency(&position.instrument, None)?;
+        let mut legs_margin = 0.0;
+        let mut gross = 0.0;
+        let mut net = 0.0;
+        for leg in &position.legs {
+            let quantity = Quantity::new(
+                (position.signed_qty * leg.ratio).abs(),
+                leg.instrument.size_precision(),
+            )?;
+            let margin = LeveragedMarginModel.calculate_maintenance_margin(
+                &leg.instrument,
+                quantity,
+                leg.price,
+                position.leverage,
+                None,
+            )?;
+            anyhow::ensure!(
+                margin.currency == currency,
+                "Spread leg {} margin currency {} does not match {} for {}",
+                leg.instrument.id(),
+                margin.currency.code,
+                currency.code,
+                position.instrument.id(),
+            );
+            legs_margin += margin.as_f64();
+
+            let exposure = position.leg_exposure(leg);
+            gross += exposure.abs();
+            net += exposure;
+        }
+
+        let offset_fraction = if gross > 0.0 {
+            1.0 - net.abs() / gross
+        } else {
+            0.0
+        };
+        Ok(legs_margin * (1.0 - self.spread_credit * offset_fraction))
+    }
+}
+
+impl MarginModel for PortfolioMarginModel {
+    fn calculate_initial_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        LeveragedMarginModel.calculate_initial_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    fn calculate_maintenance_margin(
+        &self,
+        instrument: &dyn Instrument,
+        quantity: Quantity,
+        price: Price,
+        leverage: f64,
+        use_quote_for_inverse: Option<bool>,
+    ) -> anyhow::Result<Money> {
+        LeveragedMarginModel.calculate_maintenance_margin(
+            instrument,
+            quantity,
+            price,
+            leverage,
+            use_quote_for_inverse,
+        )
+    }
+
+    fn calculate_positions_margin(
+        &self,
+        positions: &[MarginPosition],
+    ) -> anyhow::Result<HashMap<InstrumentId, Money>> {
+        let mut groups: HashMap<(Ustr, Currency), Vec<&MarginPosition>> = HashMap::new();
+        for position in positions {
+            let currency = margin_currency(&position.instrument, None)?;
+            groups
+                .entry((position.underlying(), currency))
+                .or_default()
+                .push(position);
+        }
+
+        let mut margins: HashMap<InstrumentId, Money> = HashMap::new();
+        for ((_, currency), group) in groups {
+            for (instrument_id, margin) in self.group_margins(&group)? {
+                add_margin(&mut margins, instrument_id, Money::new(margin, currency)?);
+            }
+        }
+        Ok(margins)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::instruments::{
+        currency_pair::CurrencyPair, futures_contract::FuturesContract,
+        futures_spread::FuturesSpread, options_contract::OptionsContract, stubs::*,
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn es_future(mut instrument: FuturesContract) -> InstrumentType {
+        instrument.multiplier = Quantity::from(50);
+        instrument.margin_maint = dec!(0.05);
+        InstrumentType::FuturesContract(instrument)
+    }
+
+    fn es_spread(mut instrument: FuturesSpread) -> InstrumentType {
+        instrument.multiplier = Quantity::from(50);
+        instrument.margin_maint = dec!(0.05);
+        InstrumentType::FuturesSpread(instrument)
+    }
+
+    #[rstest]
+    fn test_leveraged_margins(audusd_sim: CurrencyPair) {
+        let qty = Quantity::from(100_000);
+        let px = Price::from("0.80000");
+
+        let init = LeveragedMarginModel
+            .calculate_initial_margin(&audusd_sim, qty, px, 50.0, None)
+            .unwrap();
+        let maint = LeveragedMarginModel
+            .calculate_maintenance_margin(&audusd_sim, qty, px, 50.0, None)
+            .unwrap();
+
+        assert_eq!(init, Money::from("48.06 USD"));
+        assert_eq!(maint, Money::from("48.03 USD"));
+    }
+
+    #[rstest]
+    fn test_leveraged_positions_margin_margins_each_position(futures_contract_es: FuturesContract) {
+        let es = es_future(futures_contract_es);
+        let positions = vec![
+            MarginPosition::new(es.clone(), 2.0, Price::from("4000.00"), 1.0),
+            MarginPosition::new(es, -1.0, Price::from("4000.00"), 1.0),
+        ];
+
+        let margins = LeveragedMarginModel
+            .calculate_positions_margin(&positions)
+            .unwrap();
+
+        // 3 contracts x 50 x 4000 x 5%
+        assert_eq!(
+            margins[&positions[0].instrument.id()],
+            Money::from("30000 USD")
+        );
+    }
+
+    #[rstest]
+    fn test_portfolio_nets_long_and_short_in_same_underlying(
+        futures_contract_es: FuturesContract,
+        audusd_sim: CurrencyPair,
+    ) {
+        let es_front_id = futures_contract_es.id;
+        let es_next_id = InstrumentId::from("ESH2.GLBX");
+        let mut es_next = futures_contract_es;
+        es_next.id = es_next_id;
+        let audusd_id = audusd_sim.id;
+        let positions = vec![
+            MarginPosition::new(
+                es_future(futures_contract_es),
+                2.0,
+                Price::from("4000.00"),
+                1.0,
+            ),
+            MarginPosition::new(es_future(es_next), -1.0, Price::from("4000.00"), 1.0),
+            MarginPosition::new(
+                InstrumentType::CurrencyPair(audusd_sim),
+                100_000.0,
+                Price::from("0.80000"),
+                50.0,
+            ),
+        ];
+        let model = PortfolioMarginModel::new(vec![], 0.0).unwrap();
+
+        let margins = model.calculate_positions_margin(&positions).unwrap();
+
+        // Standalone 30,000 USD scaled by net 1 / gross 3 contracts, allocated 2:1
+        assert_eq!(margins[&es_front_id], Money::from("6666.67 USD"));
+        assert_eq!(margins[&es_next_id], Money::from("3333.33 USD"));
+        // Other underlyings are unaffected
+        assert_eq!(margins[&audusd_id], Money::from("48.03 USD"));
+    }
+
+    #[rstest]
+    fn test_portfolio_spread_credit_from_legs(
+        futures_spread_es: FuturesSpread,
+        futures_contract_es: FuturesContract,
+    ) {
+        // Calendar spread: long the front month and short the next month
+        let spread_id = futures_spread_es.id;
+        let mut es_next = futures_contract_es;
+        es_next.id = InstrumentId::from("ESH2.GLBX");
+        let legs = vec![
+            MarginLeg::new(es_future(futures_contract_es), 1.0, Price::from("4000.00")),
+            MarginLeg::new(es_future(es_next), -1.0, Price::from("4010.00")),
+        ];
+        let position = MarginPosition::new(
+            es_spread(futures_spread_es),
+            1.0,
+            Price::from("-10.00"),
+            1.0,
+        )
+        .with_legs(legs);
+        let model = PortfolioMarginModel::new(vec![], 0.75).unwrap();
+
+        let margins = model
+            .calculate_positions_margin(std::slice::from_ref(&position))
+            .unwrap();
+
+        // The legs net to a short exposure of 50 x 10
+        assert!((position.exposure() + 500.0).abs() < 1e-9);
+        // Leg margins of 10,000 + 10,025 USD, with 75% of the offsetting margin credited
+        assert_eq!(margins[&spread_id], Money::from("5025 USD"));
+    }
+
+    #[rstest]
+    fn test_portfolio_spread_without_legs_has_no_credit(futures_spread_es: FuturesSpread) {
+        let spread_id = futures_spread_es.id;
+        let positions = vec![MarginPosition::new(
+            es_spread(futures_spread_es),
+            1.0,
+            Price::from("4000.00"),
+            1.0,
+        )];
+        let model = PortfolioMarginModel::new(vec![], 0.75).unwrap();
+
+        let margins = model.calculate_positions_margin(&positions).unwrap();
+
+        assert_eq!(margins[&spread_id], Money::from("10000 USD"));
+    }
+
+    #[rstest]
+    fn test_portfolio_scanning_risk_for_hedged_options(
+        futures_contract_es: FuturesContract,
+        options_contract_appl: OptionsContract,
+    ) {
+        // Short 2 calls delta hedged with 1 future on the same underlying
+        let mut future = futures_contract_es;
+        future.underlying = Ustr::from("AAPL");
+        let mut option = options_contract_appl;
+        option.multiplier = Quantity::from(50);
+        option.margin_maint = dec!(0.50);
+        let positions = vec![
+            MarginPosition::new(es_future(future), 1.0, Price::from("150.00"), 1.0),
+            MarginPosition::new(
+                InstrumentType::OptionsContract(option),
+                -2.0,
+                Price::from("5.00"),
+                1.0,
+            )
+            .with_greeks(0.5, 30.0, 150.0),
+        ];
+        let netted = PortfolioMarginModel::new(vec![], 0.0).unwrap();
+        let scanned =
+            PortfolioMarginModel::new(MarginScenario::span_grid(0.10, 0.05), 0.0).unwrap();
+
+        let netted_total: f64 = netted
+            .calculate_positions_margin(&positions)
+            .unwrap()
+            .values()
+            .map(Money::as_f64)
+            .sum();
+        let scanned_total: f64 = scanned
+            .calculate_positions_margin(&positions)
+            .unwrap()
+            .values()
+            .map(Money::as_f64)
+            .sum();
+
+        // Delta neutral, so only the scanning risk from the short vega remains:
+        // 2 x 50 x 30 x 0.05 vol shock
+        assert_eq!(netted_total, 0.0);
+        assert!((scanned_total - 150.0).abs() < 0.02);
+    }
+
+    #[rstest]
+    fn test_span_grid() {
+        let grid = MarginScenario::span_grid(0.09, 0.04);
+
+        assert_eq!(grid.len(), 16);
+        assert!((grid[10].price_shock - 0.09).abs() < 1e-12);
+        assert_eq!(grid[11].vol_shock, -0.04);
+        assert_eq!(grid[15], MarginScenario::new(-0.18, 0.0, 0.35));
+    }
+
+    #[rstest]
+    #[case(-0.1, vec![])]
+    #[case(0.5, vec![MarginScenario::new(0.1, 0.0, 0.0)])]
+    fn test_portfolio_with_invalid_params_fails(
+        #[case] spread_credit: f64,
+        #[case] scenarios: Vec<MarginScenario>,
+    ) {
+        assert!(PortfolioMarginModel::new(scenarios, spread_credit).is_err());
+    }
+}
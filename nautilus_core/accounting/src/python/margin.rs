@@ -177,52 +177,28 @@ impl MarginAccount {
             .extract::<String>(py)?;
         if instrument_type == "CryptoFuture" {
             let instrument_rust = instrument.extract::<CryptoFuture>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else if instrument_type == "CryptoPerpetual" {
             let instrument_rust = instrument.extract::<CryptoPerpetual>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else if instrument_type == "CurrencyPair" {
             let instrument_rust = instrument.extract::<CurrencyPair>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else if instrument_type == "Equity" {
             let instrument_rust = instrument.extract::<Equity>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else if instrument_type == "FuturesContract" {
             let instrument_rust = instrument.extract::<FuturesContract>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else if instrument_type == "OptionsContract" {
             let instrument_rust = instrument.extract::<OptionsContract>(py)?;
-            Ok(self.calculate_initial_margin(
-                instrument_rust,
-                quantity,
-                price,
-                use_quote_for_inverse,
-            ))
+            self.calculate_initial_margin(instrument_rust, quantity, price, use_quote_for_inverse)
+                .map_err(to_pyvalue_err)
         } else {
             // throw error unsupported instrument
             Err(to_pyvalue_err("Unsupported instrument type"))
@@ -244,52 +220,58 @@ impl MarginAccount {
             .extract::<String>(py)?;
         if instrument_type == "CryptoFuture" {
             let instrument_rust = instrument.extract::<CryptoFuture>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else if instrument_type == "CryptoPerpetual" {
             let instrument_rust = instrument.extract::<CryptoPerpetual>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else if instrument_type == "CurrencyPair" {
             let instrument_rust = instrument.extract::<CurrencyPair>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else if instrument_type == "Equity" {
             let instrument_rust = instrument.extract::<Equity>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else if instrument_type == "FuturesContract" {
             let instrument_rust = instrument.extract::<FuturesContract>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else if instrument_type == "OptionsContract" {
             let instrument_rust = instrument.extract::<OptionsContract>(py)?;
-            Ok(self.calculate_maintenance_margin(
+            self.calculate_maintenance_margin(
                 instrument_rust,
                 quantity,
                 price,
                 use_quote_for_inverse,
-            ))
+            )
+            .map_err(to_pyvalue_err)
         } else {
             // throw error unsupported instrument
             Err(to_pyvalue_err("Unsupported instrument type"))
@@ -15,7 +15,11 @@
 
 use std::ops::{Deref, DerefMut};
 
-use nautilus_common::{clock::TestClock, ffi::clock::TestClock_API, timer::TimeEventHandler};
+use nautilus_common::{
+    clock::TestClock,
+    ffi::clock::TestClock_API,
+    timer::{LocalTimeEventHandler, TimeEventHandler},
+};
 use nautilus_core::{
     ffi::{cvec::CVec, parsing::u8_as_bool},
     time::UnixNanos,
@@ -24,6 +28,7 @@ use nautilus_core::{
 /// Provides a means of accumulating and draining time event handlers.
 pub struct TimeEventAccumulator {
     event_handlers: Vec<TimeEventHandler>,
+    local_handlers: Vec<LocalTimeEventHandler>,
 }
 
 impl TimeEventAccumulator {
@@ -32,16 +37,26 @@ impl TimeEventAccumulator {
     pub fn new() -> Self {
         Self {
             event_handlers: Vec::new(),
+            local_handlers: Vec::new(),
         }
     }
 
     /// Advance the given clock to the `to_time_ns`.
     pub fn advance_clock(&mut self, clock: &mut TestClock, to_time_ns: UnixNanos, set_time: bool) {
         let events = clock.advance_time(to_time_ns, set_time);
+        self.local_handlers
+            .extend(clock.match_local_handlers(&events));
         let handlers = clock.match_handlers(events);
         self.event_handlers.extend(handlers);
     }
 
+    /// Drain the accumulated Rust-native time event handlers in sorted order (by the events
+    /// `ts_event`), which should be handled once the clock is no longer borrowed.
+    pub fn drain_local(&mut self) -> Vec<LocalTimeEventHandler> {
+        self.local_handlers.sort_by_key(|v| v.event.ts_event);
+        self.local_handlers.drain(..).collect()
+    }
+
     /// Drain the accumulated time event handlers in sorted order (by the events `ts_event`).
     pub fn drain(&mut self) -> Vec<TimeEventHandler> {
         // stable sort is not necessary since there is no relation between
@@ -103,14 +118,23 @@ pub extern "C" fn time_event_accumulator_drain(accumulator: &mut TimeEventAccumu
     accumulator.drain().into()
 }
 
+/// Handles the accumulated Rust-native time event handlers in sorted order (by the events
+/// `ts_event`), as these cannot be passed across the FFI boundary.
+#[no_mangle]
+pub extern "C" fn time_event_accumulator_handle_local(accumulator: &mut TimeEventAccumulatorAPI) {
+    for handler in accumulator.drain_local() {
+        handler.handle();
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Tests
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use std::ffi::c_char;
+    use std::{cell::RefCell, ffi::c_char, rc::Rc};
 
-    use nautilus_common::timer::TimeEvent;
+    use nautilus_common::{handlers::LocalTimeEventCallback, timer::TimeEvent};
     use nautilus_core::uuid::UUID4;
     use pyo3::{types::PyList, Py, Python};
     use rstest::*;
@@ -167,4 +191,49 @@ mod tests {
             assert_eq!(drained_handlers[2].event.ts_event, time_event2.ts_event);
         });
     }
+
+    #[rstest]
+    fn test_accumulator_drain_local_sorted() {
+        let handled: Rc<RefCell<Vec<(Ustr, UnixNanos)>>> = Rc::default();
+        let handled_clone = handled.clone();
+        let callback: Rc<LocalTimeEventCallback> = Rc::new(move |event: &TimeEvent| {
+            handled_clone
+                .borrow_mut()
+                .push((event.name, event.ts_event));
+        });
+        let mut clock = TestClock::new();
+        clock.set_local_time_alert_ns("ALERT_2", 200, callback.clone());
+        clock.set_local_time_alert_ns("ALERT_1", 100, callback);
+
+        let mut accumulator = TimeEventAccumulator::new();
+        accumulator.advance_clock(&mut clock, 300, true);
+        for handler in accumulator.drain_local() {
+            handler.handle();
+        }
+
+        assert!(accumulator.drain().is_empty());
+        assert_eq!(
+            *handled.borrow(),
+            vec![(Ustr::from("ALERT_1"), 100), (Ustr::from("ALERT_2"), 200)]
+        );
+    }
+
+    #[rstest]
+    fn test_accumulator_handle_local_api() {
+        let handled: Rc<RefCell<Vec<Ustr>>> = Rc::default();
+        let handled_clone = handled.clone();
+        let callback: Rc<LocalTimeEventCallback> = Rc::new(move |event: &TimeEvent| {
+            handled_clone.borrow_mut().push(event.name);
+        });
+        let mut clock = TestClock::new();
+        clock.set_local_time_alert_ns("ALERT", 100, callback);
+
+        let mut accumulator = time_event_accumulator_new();
+        accumulator.advance_clock(&mut clock, 200, true);
+        time_event_accumulator_handle_local(&mut accumulator);
+
+        assert_eq!(*handled.borrow(), vec![Ustr::from("ALERT")]);
+        assert!(accumulator.drain_local().is_empty());
+        time_event_accumulator_drop(accumulator);
+    }
 }
@@ -14,4 +14,6 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod engine;
+pub mod liquidation;
 pub mod matching_engine;
+pub mod models;
@@ -0,0 +1,1098 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Margin call and liquidation simulation for margin accounts.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use log::{debug, warn};
+use nautilus_accounting::account::{any::AccountAny, margin::MarginAccount, Account};
+use nautilus_common::{cache::Cache, handlers::LocalMessageHandler, msgbus::MessageBus};
+use nautilus_core::time::{AtomicTime, UnixNanos};
+use nautilus_model::{
+    enums::{AccountType, PositionSide},
+    events::account::state::AccountState,
+    identifiers::{
+        account_id::AccountId, instrument_id::InstrumentId, position_id::PositionId, venue::Venue,
+    },
+    position::Position,
+    types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
+};
+use nautilus_portfolio::portfolio::Portfolio;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use ustr::Ustr;
+
+use crate::matching_engine::OrderMatchingEngine;
+
+const HANDLER_PRIORITY: u8 = 5;
+
+/// The policy for selecting which positions are force-closed when an account is liquidated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LiquidationPolicy {
+    /// Closes whole positions in order of largest unrealized loss, until the
+    /// maintenance margin is covered.
+    #[default]
+    LargestLossFirst,
+    /// Reduces every position by the same fraction, just enough to cover the
+    /// maintenance margin.
+    Proportional,
+}
+
+/// Configuration for `LiquidationEngine` instances.
+#[derive(Clone, Debug)]
+pub struct LiquidationConfig {
+    /// The ratio of equity to maintenance margin at or below which a margin call is issued.
+    pub margin_call_ratio: f64,
+    /// The policy for selecting which positions are force-closed.
+    pub policy: LiquidationPolicy,
+    /// The fee rate charged on the notional value of liquidation fills.
+    pub liquidation_fee: Option<Decimal>,
+}
+
+impl Default for LiquidationConfig {
+    fn default() -> Self {
+        Self {
+            margin_call_ratio: 1.25,
+            policy: LiquidationPolicy::default(),
+            liquidation_fee: None,
+        }
+    }
+}
+
+/// The margin status of an account for a single currency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarginStatus {
+    /// Equity is above the margin call ratio of the maintenance margin.
+    Healthy,
+    /// Equity is at or below the margin call ratio, but still covers the maintenance margin.
+    MarginCall,
+    /// Equity no longer covers the maintenance margin.
+    Liquidation,
+}
+
+/// Represents a margin call on an account, published on the `events.margin.{account_id}` topic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarginCall {
+    /// The account ID for the margin call.
+    pub account_id: AccountId,
+    /// The currency of the equity and margin.
+    pub currency: Currency,
+    /// The margin status which triggered the call.
+    pub status: MarginStatus,
+    /// The account equity (total balance plus unrealized PnL).
+    pub equity: Money,
+    /// The total maintenance margin for the currency.
+    pub margin_maint: Money,
+    /// UNIX timestamp (nanoseconds) when the margin call occurred.
+    pub ts_event: UnixNanos,
+}
+
+impl MarginCall {
+    /// Returns the ratio of equity to maintenance margin.
+    #[must_use]
+    pub fn margin_ratio(&self) -> f64 {
+        self.equity.as_f64() / self.margin_maint.as_f64()
+    }
+}
+
+/// Represents a position (or part of one) force-closed by the liquidation engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForcedClose {
+    pub position_id: PositionId,
+    pub instrument_id: InstrumentId,
+    pub quantity: Quantity,
+}
+
+/// A position considered for liquidation, marked at the price it could be closed at.
+struct Candidate<'a> {
+    position: &'a Position,
+    pnl: f64,
+    margin: f64,
+    fee: f64,
+}
+
+/// Provides margin monitoring for margin accounts, issuing margin calls and force-closing
+/// positions through the matching engines when maintenance margin is breached.
+///
+/// Accounts are checked on every account state published on the `events.account.*` topics,
+/// once subscribed with [`LiquidationEngine::subscribe_account_states`]. The account checked
+/// is the one held by the portfolio, along with its margin model and calculated margins.
+pub struct LiquidationEngine {
+    /// The config for the liquidation engine.
+    pub config: LiquidationConfig,
+    clock: &'static AtomicTime,
+    cache: Rc<RefCell<Cache>>,
+    msgbus: Rc<RefCell<MessageBus>>,
+    portfolio: Rc<Portfolio>,
+    margin_calls: HashSet<(AccountId, Currency)>,
+    pending_accounts: Vec<AccountId>,
+}
+
+impl LiquidationEngine {
+    /// Creates a new [`LiquidationEngine`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - The `margin_call_ratio` is less than 1.
+    /// - The `liquidation_fee` is negative.
+    pub fn new(
+        config: LiquidationConfig,
+        clock: &'static AtomicTime,
+        cache: Rc<RefCell<Cache>>,
+        msgbus: Rc<RefCell<MessageBus>>,
+        portfolio: Rc<Portfolio>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.margin_call_ratio >= 1.0,
+            "Invalid `margin_call_ratio`, was {} (must be >= 1)",
+            config.margin_call_ratio
+        );
+        if let Some(liquidation_fee) = config.liquidation_fee {
+            anyhow::ensure!(
+                !liquidation_fee.is_sign_negative(),
+                "Invalid `liquidation_fee`, was {liquidation_fee}"
+            );
+        }
+
+        Ok(Self {
+            config,
+            clock,
+            cache,
+            msgbus,
+            portfolio,
+            margin_calls: HashSet::new(),
+            pending_accounts: Vec::new(),
+        })
+    }
+
+    /// Subscribes the `liquidation` engine to the account states published on the message bus,
+    /// checking each margin account against its open positions in the cache.
+    ///
+    /// Account states published while the matching `engines` are borrowed (such as from
+    /// within a fill) are queued, and checked on the next call to
+    /// [`LiquidationEngine::check_pending_accounts`].
+    ///
+    /// The message bus only holds weak references, so it does not keep the engine or the
+    /// matching `engines` alive.
+    pub fn subscribe_account_states(
+        liquidation: &Rc<RefCell<Self>>,
+        engines: &Rc<RefCell<HashMap<InstrumentId, OrderMatchingEngine>>>,
+    ) {
+        let weak_liquidation = Rc::downgrade(liquidation);
+        let weak_engines = Rc::downgrade(engines);
+        let callback = Rc::new(move |msg: &dyn Any| {
+            let Some(state) = msg.downcast_ref::<AccountState>() else {
+                return;
+            };
+            let (Some(liquidation), Some(engines)) =
+                (weak_liquidation.upgrade(), weak_engines.upgrade())
+            else {
+                return;
+            };
+            // Account states published by a liquidation already in progress are not re-checked
+            let Ok(mut liquidation) = liquidation.try_borrow_mut() else {
+                debug!(
+                    "Skipping check of {}, liquidation in progress",
+                    state.account_id
+                );
+                return;
+            };
+            liquidation.on_account_state(state);
+            let Ok(mut engines) = engines.try_borrow_mut() else {
+                debug!(
+                    "Deferring check of {}, matching engines in use",
+                    state.account_id
+                );
+                return;
+            };
+            liquidation.check_pending_accounts(&mut engines);
+        });
+
+        let handler = LocalMessageHandler::new(Ustr::from("LiquidationEngine"), callback);
+        let msgbus = liquidation.borrow().msgbus.clone();
+        msgbus
+            .borrow_mut()
+            .subscribe_local("events.account.*", handler, Some(HANDLER_PRIORITY));
+    }
+
+    pub fn reset(&mut self) {
+        self.margin_calls.clear();
+        self.pending_accounts.clear();
+    }
+
+    fn on_account_state(&mut self, state: &AccountState) {
+        if state.account_type != AccountType::Margin {
+            return;
+        }
+        if !self.pending_accounts.contains(&state.account_id) {
+            self.pending_accounts.push(state.account_id);
+        }
+    }
+
+    /// Checks the portfolio account for every margin account with an account state published
+    /// since the last check.
+    ///
+    /// Returns the forced closes submitted to the matching engines.
+    pub fn check_pending_accounts(
+        &mut self,
+        engines: &mut HashMap<InstrumentId, OrderMatchingEngine>,
+    ) -> Vec<ForcedClose> {
+        let mut closes = Vec::new();
+        for account_id in std::mem::take(&mut self.pending_accounts) {
+            let positions: Vec<Position> = self
+                .cache
+                .borrow()
+                .positions_open(None, None)
+                .into_iter()
+                .filter(|position| position.account_id == account_id)
+                .cloned()
+                .collect();
+            let positions: Vec<&Position> = positions.iter().collect();
+
+            // The portfolio account is released before any margin call or fill is published
+            let portfolio = self.portfolio.clone();
+            let (margin_calls, account_closes) = {
+                let venue = Venue::from(account_id.get_issuer().as_str());
+                let account = portfolio.account(&venue);
+                match account.as_deref() {
+                    Some(AccountAny::Margin(account)) if account.id == account_id => {
+                        self.assess_account(account, &positions, engines)
+                    }
+                    _ => {
+                        warn!("Cannot check margin of {account_id}: no margin account found");
+                        continue;
+                    }
+                }
+            };
+            self.liquidate(&margin_calls, &account_closes, &positions, engines);
+            closes.extend(account_closes);
+        }
+        closes
+    }
+
+    /// Returns the margin status for the given `equity` and maintenance margin.
+    #[must_use]
+    pub fn margin_status(&self, equity: Money, margin_maint: Money) -> MarginStatus {
+        if margin_maint.as_f64() <= 0.0 {
+            return MarginStatus::Healthy;
+        }
+        let margin_ratio = equity.as_f64() / margin_maint.as_f64();
+        if margin_ratio < 1.0 {
+            MarginStatus::Liquidation
+        } else if margin_ratio <= self.config.margin_call_ratio {
+            MarginStatus::MarginCall
+        } else {
+            MarginStatus::Healthy
+        }
+    }
+
+    /// Checks the equity of the `account` against its maintenance margins for each currency.
+    ///
+    /// A margin call is published when the margin call ratio is first reached. When the
+    /// maintenance margin is breached, positions are force-closed according to the
+    /// liquidation policy through the matching engine for their instrument. Open positions
+    /// are marked at the best price they could be closed at, and each liquidation order is
+    /// added to the cache before it is filled.
+    ///
+    /// Returns the forced closes submitted to the matching engines.
+    pub fn check_account(
+        &mut self,
+        account: &MarginAccount,
+        positions: &[&Position],
+        engines: &mut HashMap<InstrumentId, OrderMatchingEngine>,
+    ) -> Vec<ForcedClose> {
+        let (margin_calls, closes) = self.assess_account(account, positions, engines);
+        self.liquidate(&margin_calls, &closes, positions, engines);
+        closes
+    }
+
+    /// Returns the margin calls to publish and the forced closes to submit for the `account`.
+    fn assess_account(
+        &mut self,
+        account: &MarginAccount,
+        positions: &[&Position],
+        engines: &HashMap<InstrumentId, OrderMatchingEngine>,
+    ) -> (Vec<MarginCall>, Vec<ForcedClose>) {
+        let marks: Vec<(&Position, Price)> = positions
+            .iter()
+            .filter(|position| position.is_open() && position.account_id == account.id)
+            .filter_map(|position| {
+                let engine = engines.get(&position.instrument_id)?;
+                let price = match position.side {
+                    PositionSide::Long => engine.best_bid_price(),
+                    _ => engine.best_ask_price(),
+                }?;
+                Some((*position, price))
+            })
+            .collect();
+
+        let mut margins_maint: HashMap<Currency, Money> = HashMap::new();
+        for margin_maint in account.maintenance_margins().into_values() {
+            margins_maint
+                .entry(margin_maint.currency)
+                .and_modify(|total| *total += margin_maint)
+                .or_insert(margin_maint);
+        }
+
+        let ts_now = self.clock.get_time_ns();
+        let mut margin_calls = Vec::new();
+        let mut closes = Vec::new();
+        for (currency, margin_maint) in margins_maint {
+            let key = (account.id, currency);
+            let equity = self.equity(account, currency, &marks);
+            let status = self.margin_status(equity, margin_maint);
+            let margin_call = MarginCall {
+                account_id: account.id,
+                currency,
+                status,
+                equity,
+                margin_maint,
+                ts_event: ts_now,
+            };
+            match status {
+                MarginStatus::Healthy => {
+                    self.margin_calls.remove(&key);
+                }
+                MarginStatus::MarginCall => {
+                    if self.margin_calls.insert(key) {
+                        margin_calls.push(margin_call);
+                    }
+                }
+                MarginStatus::Liquidation => {
+                    self.margin_calls.insert(key);
+                    closes.extend(self.select_closes(account, &margin_call, &marks));
+                    margin_calls.push(margin_call);
+                }
+            }
+        }
+
+        (margin_calls, closes)
+    }
+
+    /// Publishes the `margin_calls`, then submits the forced `closes` to the matching engines.
+    fn liquidate(
+        &self,
+        margin_calls: &[MarginCall],
+        closes: &[ForcedClose],
+        positions: &[&Position],
+        engines: &mut HashMap<InstrumentId, OrderMatchingEngine>,
+    ) {
+        for margin_call in margin_calls {
+            self.publish_margin_call(margin_call);
+        }
+        for close in closes {
+            let Some(position) = positions.iter().find(|p| p.id == close.position_id) else {
+                continue;
+            };
+            if let Some(engine) = engines.get_mut(&close.instrument_id) {
+                engine.liquidate_position(
+                    position,
+                    close.quantity,
+                    self.config.liquidation_fee,
+                    &mut self.cache.borrow_mut(),
+                );
+            }
+        }
+    }
+
+    /// Returns the equity for the `currency`, being the total balance plus the
+    /// unrealized PnL of the marked positions settled in the currency.
+    fn equity(
+        &self,
+        account: &MarginAccount,
+        currency: Currency,
+        marks: &[(&Position, Price)],
+    ) -> Money {
+        let balance = account
+            .balance_total(Some(currency))
+            .map_or(0.0, |balance| balance.as_f64());
+        let unrealized_pnl: f64 = marks
+            .iter()
+            .filter(|(position, _)| position.settlement_currency == currency)
+            .map(|(position, price)| position.unrealized_pnl(*price).as_f64())
+            .sum();
+        Money::new(balance + unrealized_pnl, currency).expect("Invalid equity")
+    }
+
+    fn select_closes(
+        &self,
+        account: &MarginAccount,
+        margin_call: &MarginCall,
+        marks: &[(&Position, Price)],
+    ) -> Vec<ForcedClose> {
+        let mut candidates = self.candidates(account, margin_call.currency, marks);
+        let mut margin_maint = margin_call.margin_maint.as_f64();
+        let mut equity = margin_call.equity.as_f64();
+
+        match self.config.policy {
+            LiquidationPolicy::LargestLossFirst => {
+                candidates.sort_by(|a, b| a.pnl.total_cmp(&b.pnl));
+                let mut closes = Vec::new();
+                for candidate in candidates {
+                    if margin_maint <= equity {
+                        break; // Maintenance margin covered
+                    }
+                    closes.push(forced_close(
+                        candidate.position,
+                        candidate.position.quantity,
+                    ));
+                    margin_maint -= candidate.margin;
+                    equity -= candidate.fee;
+                }
+                closes
+            }
+            LiquidationPolicy::Proportional => {
+                // Closing a fraction `f` of every position releases `f` of their margin,
+                // and charges `f` of their fees.
+                let margin: f64 = candidates.iter().map(|c| c.margin).sum();
+                let fees: f64 = candidates.iter().map(|c| c.fee).sum();
+                let fraction = if margin - fees > 0.0 {
+                    ((margin_maint - equity) / (margin - fees)).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                candidates
+                    .iter()
+                    .filter_map(|c| {
+                        let quantity = fraction_qty(c.position.quantity, fraction)?;
+                        Some(forced_close(c.position, quantity))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    fn candidates<'a>(
+        &self,
+        account: &MarginAccount,
+        currency: Currency,
+        marks: &[(&'a Position, Price)],
+    ) -> Vec<Candidate<'a>> {
+        // The maintenance margin for an instrument is shared by its positions by quantity
+        let mut instrument_qtys: HashMap<InstrumentId, f64> = HashMap::new();
+        for (position, _) in marks {
+            *instrument_qtys.entry(position.instrument_id).or_default() +=
+                position.quantity.as_f64();
+        }
+        let margins_maint = account.maintenance_margins();
+        let fee_rate = self
+            .config
+            .liquidation_fee
+            .and_then(|fee| fee.to_f64())
+            .unwrap_or_default();
+
+        marks
+            .iter()
+            .filter(|(position, _)| position.settlement_currency == currency)
+            .map(|(position, price)| {
+                let margin = margins_maint
+                    .get(&position.instrument_id)
+                    .filter(|margin| margin.currency == currency)
+                    .map_or(0.0, |margin| {
+                        margin.as_f64() * position.quantity.as_f64()
+                            / instrument_qtys[&position.instrument_id]
+                    });
+                let notional = position.notional_value(*price);
+                let fee = if notional.currency == currency {
+                    notional.as_f64() * fee_rate
+                } else {
+                    0.0
+                };
+                Candidate {
+                    position,
+                    pnl: position.unrealized_pnl(*price).as_f64(),
+                    margin,
+                    fee,
+                }
+            })
+            .collect()
+    }
+
+    fn publish_margin_call(&self, margin_call: &MarginCall) {
+        warn!(
+            "Margin call for {} {}: equity={}, margin_maint={}, ratio={:.4}",
+            margin_call.account_id,
+            margin_call.currency.code,
+            margin_call.equity,
+            margin_call.margin_maint,
+            margin_call.margin_ratio(),
+        );
+        let topic = format!("events.margin.{}", margin_call.account_id);
+        self.msgbus.borrow().publish(&topic, margin_call);
+    }
+}
+
+fn forced_close(position: &Position, quantity: Quantity) -> ForcedClose {
+    ForcedClose {
+        position_id: position.id,
+        instrument_id: position.instrument_id,
+        quantity,
+    }
+}
+
+/// Returns the `fraction` of the `quantity` rounded up to its precision, if positive.
+fn fraction_qty(quantity: Quantity, fraction: f64) -> Option<Quantity> {
+    let scale = 10_f64.powi(i32::from(quantity.precision));
+    let value = ((quantity.as_f64() * fraction * scale).ceil() / scale).min(quantity.as_f64());
+    let quantity = Quantity::new(value, quantity.precision).ok()?;
+    quantity.is_positive().then_some(quantity)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{
+        any::Any,
+        sync::{Arc, Mutex},
+    };
+
+    use nautilus_accounting::fee::MakerTakerFeeModel;
+    use nautilus_common::{
+        handlers::{MessageHandler, SafeAnyMessageCallback},
+        msgbus::MessageBus,
+    };
+    use nautilus_core::uuid::UUID4;
+    use nautilus_model::{
+        data::quote::QuoteTick,
+        enums::{BookType, OmsType, OrderSide, OrderStatus},
+        events::{
+            order::{event::OrderEvent, submitted::OrderSubmitted},
+            position::{opened::PositionOpened, PositionEvent},
+        },
+        identifiers::{strategy_id::StrategyId, stubs::trader_id},
+        instruments::{equity::Equity, stubs::*, Instrument},
+        orders::{any::OrderAny, stubs::TestOrderStubs},
+        types::balance::{AccountBalance, MarginBalance},
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::matching_engine::{OrderMatchingEngineConfig, EXEC_ENGINE_PROCESS};
+
+    type EventStore = Arc<Mutex<Vec<OrderEvent>>>;
+    type MarginCallStore = Arc<Mutex<Vec<MarginCall>>>;
+
+    struct TestVenue {
+        liquidation: Rc<RefCell<LiquidationEngine>>,
+        engines: Rc<RefCell<HashMap<InstrumentId, OrderMatchingEngine>>>,
+        events: EventStore,
+        margin_calls: MarginCallStore,
+        clock: &'static AtomicTime,
+        cache: Rc<RefCell<Cache>>,
+        msgbus: Rc<RefCell<MessageBus>>,
+        portfolio: Rc<Portfolio>,
+        exec_msgbus: &'static MessageBus,
+    }
+
+    impl TestVenue {
+        fn new(config: LiquidationConfig) -> Self {
+            let events: EventStore = Arc::new(Mutex::new(Vec::new()));
+            let margin_calls: MarginCallStore = Arc::new(Mutex::new(Vec::new()));
+            let events_clone = events.clone();
+            let margin_calls_clone = margin_calls.clone();
+
+            let mut exec_msgbus = MessageBus::new(trader_id(), UUID4::new(), None, None).unwrap();
+            let callback = SafeAnyMessageCallback {
+                callback: Arc::new(move |message: &dyn Any| {
+                    if let Some(event) = message.downcast_ref::<OrderEvent>() {
+                        events_clone.lock().unwrap().push(event.clone());
+                    }
+                }),
+            };
+            exec_msgbus.register(
+                EXEC_ENGINE_PROCESS,
+                MessageHandler::with_any_callback(Ustr::from("ExecEngine"), callback),
+            );
+            let exec_msgbus: &'static MessageBus = Box::leak(Box::new(exec_msgbus));
+
+            let mut msgbus = MessageBus::new(trader_id(), UUID4::new(), None, None).unwrap();
+            let callback = SafeAnyMessageCallback {
+                callback: Arc::new(move |message: &dyn Any| {
+                    if let Some(margin_call) = message.downcast_ref::<MarginCall>() {
+                        margin_calls_clone.lock().unwrap().push(*margin_call);
+                    }
+                }),
+            };
+            msgbus.subscribe(
+                "events.margin.*",
+                MessageHandler::with_any_callback(Ustr::from("Risk"), callback),
+                None,
+            );
+            let msgbus = Rc::new(RefCell::new(msgbus));
+            let cache = Rc::new(RefCell::new(Cache::default()));
+            let clock: &'static AtomicTime = Box::leak(Box::new(AtomicTime::new(false, 0)));
+            let portfolio = Rc::new(Portfolio::new(clock, cache.clone(), msgbus.clone()));
+            portfolio.register_calculated_account("SIM");
+            let liquidation = LiquidationEngine::new(
+                config,
+                clock,
+                cache.clone(),
+                msgbus.clone(),
+                portfolio.clone(),
+            )
+            .unwrap();
+
+            Self {
+                liquidation: Rc::new(RefCell::new(liquidation)),
+                engines: Rc::new(RefCell::new(HashMap::new())),
+                events,
+                margin_calls,
+                clock,
+                cache,
+                msgbus,
+                portfolio,
+                exec_msgbus,
+            }
+        }
+
+        fn add_instrument<T: Instrument + Copy + 'static>(&self, instrument: T) {
+            let instrument_id = instrument.id();
+            self.cache
+                .borrow_mut()
+                .add_instrument(Box::new(instrument))
+                .unwrap();
+            let raw_id = self.engines.borrow().len() as u64 + 1;
+            let engine = OrderMatchingEngine::new(
+                Box::new(instrument),
+                raw_id,
+                BookType::L1_MBP,
+                OmsType::Netting,
+                AccountType::Margin,
+                self.clock,
+                self.exec_msgbus,
+                OrderMatchingEngineConfig::default(),
+                Box::new(MakerTakerFeeModel),
+                None,
+            );
+            self.engines.borrow_mut().insert(instrument_id, engine);
+        }
+
+        fn quote(&self, instrument_id: InstrumentId, bid: &str, ask: &str) {
+            let quote = QuoteTick::new(
+                instrument_id,
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from(1_000_000),
+                Quantity::from(1_000_000),
+                0,
+                0,
+            )
+            .unwrap();
+            self.engines
+                .borrow_mut()
+                .get_mut(&instrument_id)
+                .unwrap()
+                .process_quote_tick(&quote);
+        }
+
+        /// Opens a long position of `quantity` at the current ask.
+        fn open_long<T: Instrument + Copy>(&self, instrument: T, quantity: i64) -> Position {
+            let mut order: OrderAny = TestOrderStubs::market_order(
+                instrument.id(),
+                OrderSide::Buy,
+                Quantity::from(quantity),
+                None,
+                None,
+            )
+            .into();
+            let submitted = OrderSubmitted::new(
+                order.trader_id(),
+                order.strategy_id(),
+                order.instrument_id(),
+                order.client_order_id(),
+                account_id(),
+                UUID4::new(),
+                0,
+                0,
+            )
+            .unwrap();
+            order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+            self.engines
+                .borrow_mut()
+                .get_mut(&instrument.id())
+                .unwrap()
+                .process_order(&order, account_id());
+
+            let mut fill = match self.events.lock().unwrap().pop() {
+                Some(OrderEvent::OrderFilled(fill)) => fill,
+                event => panic!("Unexpected event {event:?}"),
+            };
+            fill.position_id = Some(
+                PositionId::new(&format!("{}-{}", instrument.id(), StrategyId::default())).unwrap(),
+            );
+            Position::new(instrument, fill).unwrap()
+        }
+
+        fn publish_account_state(&self, state: &AccountState) {
+            self.msgbus
+                .borrow()
+                .publish(&format!("events.account.{}", state.account_id), state);
+        }
+
+        /// Adds the `position` to the cache and publishes its opening, for the portfolio
+        /// to calculate its margin.
+        fn publish_position_opened(&self, position: &Position) {
+            self.cache
+                .borrow_mut()
+                .add_position(position.clone())
+                .unwrap();
+            let fill = position.events.last().unwrap();
+            let event = PositionEvent::PositionOpened(PositionOpened {
+                trader_id: position.trader_id,
+                strategy_id: position.strategy_id,
+                instrument_id: position.instrument_id,
+                position_id: position.id,
+                account_id: position.account_id,
+                opening_order_id: position.opening_order_id,
+                entry: position.entry,
+                side: position.side,
+                signed_qty: position.signed_qty,
+                quantity: position.quantity,
+                last_qty: fill.last_qty,
+                last_px: fill.last_px,
+                currency: position.settlement_currency,
+                avg_px_open: position.avg_px_open,
+                ts_event: 0,
+                ts_init: 0,
+            });
+            self.msgbus
+                .borrow()
+                .publish(&format!("events.position.{}", position.strategy_id), &event);
+        }
+
+        fn check_account(
+            &self,
+            account: &MarginAccount,
+            positions: &[&Position],
+        ) -> Vec<ForcedClose> {
+            self.liquidation.borrow_mut().check_account(
+                account,
+                positions,
+                &mut self.engines.borrow_mut(),
+            )
+        }
+
+        fn liquidation_fills(&self) -> Vec<OrderEvent> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|event| event.client_order_id().to_string().starts_with("LIQ-"))
+                .cloned()
+                .collect()
+        }
+    }
+
+    fn account_id() -> AccountId {
+        AccountId::from("SIM-001")
+    }
+
+    fn margin_account_state(balance: &str, margins_maint: &[(InstrumentId, &str)]) -> AccountState {
+        let total = Money::from(balance);
+        let margins = margins_maint
+            .iter()
+            .map(|(instrument_id, margin_maint)| {
+                let margin_maint = Money::from(*margin_maint);
+                MarginBalance::new(margin_maint, margin_maint, *instrument_id).unwrap()
+            })
+            .collect();
+        AccountState::new(
+            account_id(),
+            AccountType::Margin,
+            vec![
+                AccountBalance::new(total, Money::new(0.0, total.currency).unwrap(), total)
+                    .unwrap(),
+            ],
+            margins,
+            true,
+            UUID4::new(),
+            0,
+            0,
+            Some(total.currency),
+        )
+        .unwrap()
+    }
+
+    fn margin_account(balance: &str, margins_maint: &[(InstrumentId, &str)]) -> MarginAccount {
+        let mut account =
+            MarginAccount::new(margin_account_state(balance, margins_maint), false).unwrap();
+        for (instrument_id, margin_maint) in margins_maint {
+            account.update_maintenance_margin(*instrument_id, Money::from(*margin_maint));
+        }
+        account
+    }
+
+    /// Opens a long 100,000 AUD/USD position at 0.80010 then marks it at 0.75000,
+    /// for an unrealized loss of 5,010 USD.
+    fn venue_with_audusd_loss(config: LiquidationConfig) -> (TestVenue, Position) {
+        let audusd_sim = audusd_sim();
+        let venue = TestVenue::new(config);
+        venue.add_instrument(audusd_sim);
+        venue.quote(audusd_sim.id, "0.80000", "0.80010");
+        let position = venue.open_long(audusd_sim, 100_000);
+        venue.quote(audusd_sim.id, "0.75000", "0.75010");
+        (venue, position)
+    }
+
+    #[rstest]
+    fn test_new_with_invalid_margin_call_ratio_errors() {
+        let venue = TestVenue::new(LiquidationConfig::default());
+        let config = LiquidationConfig {
+            margin_call_ratio: 0.9,
+            ..Default::default()
+        };
+
+        assert!(LiquidationEngine::new(
+            config,
+            venue.clock,
+            venue.cache,
+            venue.msgbus,
+            venue.portfolio
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    #[case("3000 USD", MarginStatus::Healthy)]
+    #[case("4500 USD", MarginStatus::MarginCall)]
+    #[case("6000 USD", MarginStatus::Liquidation)]
+    fn test_margin_status(#[case] margin_maint: &str, #[case] expected: MarginStatus) {
+        let venue = TestVenue::new(LiquidationConfig::default());
+
+        let status = venue
+            .liquidation
+            .borrow()
+            .margin_status(Money::from("4990 USD"), Money::from(margin_maint));
+
+        assert_eq!(status, expected);
+    }
+
+    #[rstest]
+    fn test_check_account_when_healthy_does_nothing() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        let account = margin_account("10000 USD", &[(position.instrument_id, "3000 USD")]);
+
+        let closes = venue.check_account(&account, &[&position]);
+
+        assert!(closes.is_empty());
+        assert!(venue.margin_calls.lock().unwrap().is_empty());
+        assert!(venue.liquidation_fills().is_empty());
+    }
+
+    #[rstest]
+    fn test_check_account_issues_margin_call_once() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        let account = margin_account("10000 USD", &[(position.instrument_id, "4500 USD")]);
+
+        let closes = venue.check_account(&account, &[&position]);
+        venue.check_account(&account, &[&position]);
+
+        let margin_calls = venue.margin_calls.lock().unwrap();
+        assert!(closes.is_empty());
+        assert_eq!(margin_calls.len(), 1);
+        assert_eq!(margin_calls[0].status, MarginStatus::MarginCall);
+        assert_eq!(margin_calls[0].equity, Money::from("4990 USD"));
+        assert_eq!(margin_calls[0].margin_maint, Money::from("4500 USD"));
+        assert!(venue.liquidation_fills().is_empty());
+    }
+
+    #[rstest]
+    fn test_check_account_liquidates_largest_loss_first_with_fee(equity_aapl: Equity) {
+        let config = LiquidationConfig {
+            liquidation_fee: Some(dec!(0.005)),
+            ..Default::default()
+        };
+        let (venue, audusd_position) = venue_with_audusd_loss(config);
+        venue.add_instrument(equity_aapl);
+        venue.quote(equity_aapl.id, "149.99", "150.00");
+        let aapl_position = venue.open_long(equity_aapl, 100);
+        venue.quote(equity_aapl.id, "160.00", "160.01");
+        // Equity is 10,000 - 5,010 + 1,000 = 4,990 against 7,000 maintenance
+        let account = margin_account(
+            "10000 USD",
+            &[
+                (audusd_position.instrument_id, "5000 USD"),
+                (aapl_position.instrument_id, "2000 USD"),
+            ],
+        );
+
+        let closes = venue.check_account(&account, &[&aapl_position, &audusd_position]);
+
+        assert_eq!(
+            closes,
+            vec![ForcedClose {
+                position_id: audusd_position.id,
+                instrument_id: audusd_position.instrument_id,
+                quantity: Quantity::from(100_000),
+            }]
+        );
+        assert_eq!(
+            venue.margin_calls.lock().unwrap()[0].status,
+            MarginStatus::Liquidation
+        );
+        let fills = venue.liquidation_fills();
+        match &fills[..] {
+            [OrderEvent::OrderFilled(fill)] => {
+                assert_eq!(fill.order_side, OrderSide::Sell);
+                assert_eq!(fill.last_qty, Quantity::from(100_000));
+                assert_eq!(fill.last_px, Price::from("0.75000"));
+                // Taker fee of 1.50 USD plus 0.5% of the 75,000 USD notional
+                assert_eq!(fill.commission, Some(Money::from("376.50 USD")));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_check_account_liquidates_proportionally() {
+        let config = LiquidationConfig {
+            policy: LiquidationPolicy::Proportional,
+            ..Default::default()
+        };
+        let (venue, position) = venue_with_audusd_loss(config);
+        let account = margin_account("10000 USD", &[(position.instrument_id, "6000 USD")]);
+
+        let closes = venue.check_account(&account, &[&position]);
+
+        // Closing (6,000 - 4,990) / 6,000 of the position covers the maintenance margin
+        assert_eq!(closes.len(), 1);
+        assert_eq!(closes[0].quantity, Quantity::from(16_834));
+        match &venue.liquidation_fills()[..] {
+            [OrderEvent::OrderFilled(fill)] => {
+                assert_eq!(fill.order_side, OrderSide::Sell);
+                assert_eq!(fill.last_qty, Quantity::from(16_834));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_check_account_adds_liquidation_order_to_cache() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        let account = margin_account("10000 USD", &[(position.instrument_id, "6000 USD")]);
+
+        venue.check_account(&account, &[&position]);
+
+        let fills = venue.liquidation_fills();
+        let client_order_id = match &fills[..] {
+            [OrderEvent::OrderFilled(fill)] => fill.client_order_id,
+            events => panic!("Unexpected events {events:?}"),
+        };
+        let cache = venue.cache.borrow();
+        let order = cache.order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Submitted);
+        assert_eq!(order.quantity(), Quantity::from(100_000));
+        assert_eq!(cache.position_id(&client_order_id), Some(&position.id));
+    }
+
+    #[rstest]
+    fn test_account_state_checks_portfolio_account() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        LiquidationEngine::subscribe_account_states(&venue.liquidation, &venue.engines);
+        venue.publish_account_state(&margin_account_state("7000 USD", &[]));
+
+        // The portfolio calculates the margin and publishes the account state
+        venue.publish_position_opened(&position);
+
+        // Equity is 7,000 - 5,010 against 3% of the 80,010 USD notional plus commission
+        let margin_calls = venue.margin_calls.lock().unwrap();
+        assert_eq!(margin_calls.len(), 1);
+        assert_eq!(margin_calls[0].status, MarginStatus::Liquidation);
+        assert_eq!(margin_calls[0].equity, Money::from("1990 USD"));
+        assert_eq!(margin_calls[0].margin_maint, Money::from("2401.90 USD"));
+        match &venue.liquidation_fills()[..] {
+            [OrderEvent::OrderFilled(fill)] => {
+                assert_eq!(fill.order_side, OrderSide::Sell);
+                assert_eq!(fill.last_qty, Quantity::from(100_000));
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_account_state_margins_without_portfolio_margins_are_ignored() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        venue
+            .cache
+            .borrow_mut()
+            .add_position(position.clone())
+            .unwrap();
+        LiquidationEngine::subscribe_account_states(&venue.liquidation, &venue.engines);
+
+        venue.publish_account_state(&margin_account_state(
+            "10000 USD",
+            &[(position.instrument_id, "6000 USD")],
+        ));
+
+        assert!(venue.margin_calls.lock().unwrap().is_empty());
+        assert!(venue.liquidation_fills().is_empty());
+    }
+
+    #[rstest]
+    fn test_account_state_while_engines_borrowed_is_deferred() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        LiquidationEngine::subscribe_account_states(&venue.liquidation, &venue.engines);
+        venue.publish_account_state(&margin_account_state("7000 USD", &[]));
+
+        let mut engines = venue.engines.borrow_mut();
+        venue.publish_position_opened(&position);
+        assert!(venue.margin_calls.lock().unwrap().is_empty());
+
+        let closes = venue
+            .liquidation
+            .borrow_mut()
+            .check_pending_accounts(&mut engines);
+
+        assert_eq!(closes.len(), 1);
+        assert_eq!(closes[0].quantity, Quantity::from(100_000));
+        assert_eq!(venue.liquidation_fills().len(), 1);
+    }
+
+    #[rstest]
+    fn test_account_state_for_cash_account_is_ignored() {
+        let (venue, position) = venue_with_audusd_loss(LiquidationConfig::default());
+        venue
+            .cache
+            .borrow_mut()
+            .add_position(position.clone())
+            .unwrap();
+        LiquidationEngine::subscribe_account_states(&venue.liquidation, &venue.engines);
+        let mut state = margin_account_state("10000 USD", &[(position.instrument_id, "6000 USD")]);
+        state.account_type = AccountType::Cash;
+
+        venue.publish_account_state(&state);
+
+        assert!(venue.liquidation.borrow().pending_accounts.is_empty());
+        assert!(venue.margin_calls.lock().unwrap().is_empty());
+        assert!(venue.liquidation_fills().is_empty());
+    }
+
+    #[rstest]
+    #[case(0.5, Some(Quantity::from(50_000)))]
+    #[case(0.000_001, Some(Quantity::from(1)))]
+    #[case(0.0, None)]
+    fn test_fraction_qty(#[case] fraction: f64, #[case] expected: Option<Quantity>) {
+        assert_eq!(fraction_qty(Quantity::from(100_000), fraction), expected);
+    }
+}
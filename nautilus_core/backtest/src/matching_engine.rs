@@ -13,22 +13,75 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-#![allow(dead_code)] // Under development
-
-use std::collections::HashMap;
+use std::{
+    any::Any,
+    cell::RefCell,
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    rc::Rc,
+};
 
-use nautilus_common::msgbus::MessageBus;
-use nautilus_core::time::AtomicTime;
-use nautilus_execution::matching_core::OrderMatchingCore;
+use log::{debug, error, info, warn};
+use nautilus_accounting::fee::FeeModel;
+use nautilus_common::{
+    cache::Cache,
+    clock::{Clock, TestClock},
+    handlers::LocalTimeEventCallback,
+    msgbus::MessageBus,
+    timer::TimeEvent,
+};
+use nautilus_core::{
+    time::{AtomicTime, UnixNanos},
+    uuid::UUID4,
+};
+use nautilus_execution::{
+    matching_core::OrderMatchingCore,
+    messages::{
+        cancel::CancelOrder, cancel_all::CancelAllOrders, modify::ModifyOrder, submit::SubmitOrder,
+        TradingCommand,
+    },
+    trailing::trailing_stop_calculate,
+};
 use nautilus_model::{
-    data::bar::Bar,
-    enums::{AccountType, BookType, MarketStatus, OmsType},
-    identifiers::{account_id::AccountId, trader_id::TraderId, venue::Venue},
-    instruments::Instrument,
-    orderbook::{book_mbo::OrderBookMbo, book_mbp::OrderBookMbp},
-    types::price::Price,
+    data::{
+        bar::Bar, delta::OrderBookDelta, deltas::OrderBookDeltas, order::BookOrder,
+        quote::QuoteTick, trade::TradeTick,
+    },
+    enums::{
+        AccountType, AggressorSide, BookType, ContingencyType, LiquiditySide, MarketStatus,
+        OmsType, OrderSide, OrderStatus, OrderType, PositionSide, PriceType, TimeInForce,
+    },
+    events::order::{
+        accepted::OrderAccepted, cancel_rejected::OrderCancelRejected, canceled::OrderCanceled,
+        event::OrderEvent, expired::OrderExpired, filled::OrderFilled,
+        modify_rejected::OrderModifyRejected, rejected::OrderRejected, submitted::OrderSubmitted,
+        triggered::OrderTriggered, updated::OrderUpdated,
+    },
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
+        position_id::PositionId, strategy_id::StrategyId, trade_id::TradeId, trader_id::TraderId,
+        venue::Venue, venue_order_id::VenueOrderId,
+    },
+    instruments::{equity::Equity, Instrument},
+    orderbook::{book::OrderBook, level::Level},
+    orders::{
+        any::OrderAny,
+        base::{order_side_to_fixed, GetClientOrderId},
+        market::MarketOrder,
+    },
+    position::Position,
+    types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
 };
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use ustr::Ustr;
+
+use crate::models::{fill::QueuePositionFillModel, latency::LatencyModel};
+
+/// The message bus endpoint which receives the order events generated by the engine.
+pub(crate) const EXEC_ENGINE_PROCESS: &str = "ExecEngine.process";
 
+/// Configuration for `OrderMatchingEngine` instances.
+#[derive(Clone, Debug)]
 pub struct OrderMatchingEngineConfig {
     pub bar_execution: bool,
     pub reject_stop_orders: bool,
@@ -37,24 +90,105 @@ pub struct OrderMatchingEngineConfig {
     pub use_position_ids: bool,
     pub use_random_ids: bool,
     pub use_reduce_only: bool,
+    /// If passive orders should track their queue position at the level they rest at,
+    /// and only fill once the size ahead of them has traded or been deleted.
+    pub queue_position: bool,
+}
+
+impl Default for OrderMatchingEngineConfig {
+    fn default() -> Self {
+        Self {
+            bar_execution: true,
+            reject_stop_orders: true,
+            support_gtd_orders: true,
+            support_contingent_orders: true,
+            use_position_ids: true,
+            use_random_ids: false,
+            use_reduce_only: true,
+            queue_position: false,
+        }
+    }
+}
+
+/// A trading command held in flight until its simulated latency has elapsed.
+#[derive(Clone, Debug)]
+struct InflightCommand {
+    ts: UnixNanos,
+    counter: u64,
+    command: TradingCommand,
+    account_id: AccountId,
+}
+
+impl PartialEq for InflightCommand {
+    fn eq(&self, other: &Self) -> bool {
+        self.ts == other.ts && self.counter == other.counter
+    }
 }
 
+impl Eq for InflightCommand {}
+
+impl PartialOrd for InflightCommand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InflightCommand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Commands arriving at the same time are processed in the order they were sent
+        (self.ts, self.counter).cmp(&(other.ts, other.counter))
+    }
+}
+
+/// The time alert releasing in-flight commands as a `TestClock` is advanced.
+struct InflightTimer {
+    name: String,
+    clock: Rc<RefCell<TestClock>>,
+    callback: Rc<LocalTimeEventCallback>,
+    alert_ts: Option<UnixNanos>,
+}
+
+/// Provides an order matching engine for a single market.
+///
+/// The engine maintains an order book for the instrument, processes market data to simulate
+/// market dynamics, and matches the orders it manages. All resulting order events are sent
+/// to the `ExecEngine.process` endpoint on the message bus.
 pub struct OrderMatchingEngine {
+    /// The venue for the matching engine.
     pub venue: Venue,
+    /// The instrument for the matching engine.
     pub instrument: Box<dyn Instrument>,
+    /// The instruments raw integer ID for the venue.
     pub raw_id: u64,
+    /// The order book type for the matching engine.
     pub book_type: BookType,
+    /// The order management system (OMS) type for the matching engine.
     pub oms_type: OmsType,
+    /// The account type for the matching engine.
     pub account_type: AccountType,
+    /// The market status for the matching engine.
     pub market_status: MarketStatus,
+    /// The config for the matching engine.
     pub config: OrderMatchingEngineConfig,
     // pub cache: Cache  // TODO
     clock: &'static AtomicTime,
     msgbus: &'static MessageBus,
-    book_mbo: Option<OrderBookMbo>,
-    book_mbp: Option<OrderBookMbp>,
+    book: OrderBook,
     account_ids: HashMap<TraderId, AccountId>,
     core: OrderMatchingCore,
+    orders: HashMap<ClientOrderId, OrderAny>,
+    position_ids: HashMap<ClientOrderId, PositionId>,
+    net_positions: HashMap<PositionId, i64>, // Signed raw quantities
+    triggered_prices: HashMap<ClientOrderId, Price>,
+    liquidation_fees: HashMap<ClientOrderId, Decimal>,
+    fill_model: QueuePositionFillModel,
+    fee_model: Box<dyn FeeModel>,
+    latency_model: Option<Box<dyn LatencyModel>>,
+    inflight_queue: BinaryHeap<Reverse<InflightCommand>>,
+    inflight_count: u64,
+    inflight_timer: Option<InflightTimer>,
+    ts_processing: Option<UnixNanos>,
+    has_targets: bool,
     target_bid: Option<Price>,
     target_ask: Option<Price>,
     target_last: Option<Price>,
@@ -63,4 +197,3990 @@ pub struct OrderMatchingEngine {
     position_count: usize,
     order_count: usize,
     execution_count: usize,
+    liquidation_count: usize,
+}
+
+impl OrderMatchingEngine {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        instrument: Box<dyn Instrument>,
+        raw_id: u64,
+        book_type: BookType,
+        oms_type: OmsType,
+        account_type: AccountType,
+        clock: &'static AtomicTime,
+        msgbus: &'static MessageBus,
+        config: OrderMatchingEngineConfig,
+        fee_model: Box<dyn FeeModel>,
+        latency_model: Option<Box<dyn LatencyModel>>,
+    ) -> Self {
+        let instrument_id = instrument.id();
+        let book = OrderBook::new(instrument_id, book_type);
+        let core = OrderMatchingCore::new(
+            instrument_id,
+            instrument.price_increment(),
+            None,
+            None,
+            None,
+        );
+
+        Self {
+            venue: instrument.venue(),
+            instrument,
+            raw_id,
+            book_type,
+            oms_type,
+            account_type,
+            market_status: MarketStatus::Open,
+            config,
+            clock,
+            msgbus,
+            book,
+            account_ids: HashMap::new(),
+            core,
+            orders: HashMap::new(),
+            position_ids: HashMap::new(),
+            net_positions: HashMap::new(),
+            triggered_prices: HashMap::new(),
+            liquidation_fees: HashMap::new(),
+            fill_model: QueuePositionFillModel::new(),
+            fee_model,
+            latency_model,
+            inflight_queue: BinaryHeap::new(),
+            inflight_count: 0,
+            inflight_timer: None,
+            ts_processing: None,
+            has_targets: false,
+            target_bid: None,
+            target_ask: None,
+            target_last: None,
+            last_bar_bid: None,
+            last_bar_ask: None,
+            position_count: 0,
+            order_count: 0,
+            execution_count: 0,
+            liquidation_count: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.book.reset();
+        self.account_ids.clear();
+        self.core.reset();
+        self.orders.clear();
+        self.position_ids.clear();
+        self.net_positions.clear();
+        self.triggered_prices.clear();
+        self.liquidation_fees.clear();
+        self.fill_model.reset();
+        self.inflight_queue.clear();
+        self.inflight_count = 0;
+        if let Some(timer) = self.inflight_timer.as_mut() {
+            if timer.alert_ts.take().is_some() {
+                timer.clock.borrow_mut().cancel_timer(&timer.name);
+            }
+        }
+        self.reset_targets();
+        self.last_bar_bid = None;
+        self.last_bar_ask = None;
+        self.position_count = 0;
+        self.order_count = 0;
+        self.execution_count = 0;
+        self.liquidation_count = 0;
+    }
+
+    // -- QUERIES ---------------------------------------------------------------------------------
+
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        self.instrument.id()
+    }
+
+    #[must_use]
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.book.best_bid_price()
+    }
+
+    #[must_use]
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.book.best_ask_price()
+    }
+
+    #[must_use]
+    pub fn get_order(&self, client_order_id: &ClientOrderId) -> Option<&OrderAny> {
+        self.orders.get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn get_open_orders(&self) -> Vec<&OrderAny> {
+        let mut orders = self.get_open_bid_orders();
+        orders.extend(self.get_open_ask_orders());
+        orders
+    }
+
+    #[must_use]
+    pub fn get_open_bid_orders(&self) -> Vec<&OrderAny> {
+        self.core
+            .get_orders_bid()
+            .iter()
+            .filter_map(|o| self.orders.get(&o.get_client_order_id()))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn get_open_ask_orders(&self) -> Vec<&OrderAny> {
+        self.core
+            .get_orders_ask()
+            .iter()
+            .filter_map(|o| self.orders.get(&o.get_client_order_id()))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn order_exists(&self, client_order_id: &ClientOrderId) -> bool {
+        self.core
+            .get_orders_bid()
+            .iter()
+            .chain(self.core.get_orders_ask())
+            .any(|o| o.get_client_order_id() == *client_order_id)
+    }
+
+    // -- DATA PROCESSING -------------------------------------------------------------------------
+
+    /// Processes the venue market for the given order book `delta`.
+    pub fn process_order_book_delta(&mut self, delta: OrderBookDelta) {
+        debug!("Processing {delta:?}");
+        let ts_init = delta.ts_init;
+        let level_sizes = self.queued_level_sizes();
+        if let Err(e) = self.book.apply_delta(delta) {
+            error!("{e}");
+        }
+        self.update_queue_positions(level_sizes);
+        self.iterate(ts_init);
+    }
+
+    /// Processes the venue market for the given order book `deltas`.
+    pub fn process_order_book_deltas(&mut self, deltas: OrderBookDeltas) {
+        debug!("Processing {deltas:?}");
+        let ts_init = deltas.ts_init;
+        let level_sizes = self.queued_level_sizes();
+        if let Err(e) = self.book.apply_deltas(deltas) {
+            error!("{e}");
+        }
+        self.update_queue_positions(level_sizes);
+        self.iterate(ts_init);
+    }
+
+    /// Processes the venue market for the given `quote`.
+    pub fn process_quote_tick(&mut self, quote: &QuoteTick) {
+        debug!("Processing {quote}");
+        if self.book_type == BookType::L1_MBP {
+            let level_sizes = self.queued_level_sizes();
+            self.book.update_quote_tick(quote);
+            self.update_queue_positions(level_sizes);
+        }
+        self.iterate(quote.ts_init);
+    }
+
+    /// Processes the venue market for the given `trade`.
+    pub fn process_trade_tick(&mut self, trade: &TradeTick) {
+        debug!("Processing {trade}");
+        if self.config.queue_position {
+            self.fill_model.process_trade(trade);
+        }
+        if self.book_type == BookType::L1_MBP {
+            self.book.update_trade_tick(trade);
+        }
+        self.core.last = Some(trade.price);
+        self.iterate(trade.ts_init);
+    }
+
+    /// Processes the venue market for the given `bar`.
+    ///
+    /// Bars are only processed when `bar_execution` is enabled and the book type is `L1_MBP`.
+    pub fn process_bar(&mut self, bar: &Bar) {
+        if !self.config.bar_execution || self.book_type != BookType::L1_MBP {
+            return;
+        }
+        debug!("Processing {bar}");
+
+        match bar.bar_type.spec.price_type {
+            PriceType::Last | PriceType::Mid => self.process_trade_ticks_from_bar(bar),
+            PriceType::Bid => {
+                self.last_bar_bid = Some(*bar);
+                self.process_quote_ticks_from_bars();
+            }
+            PriceType::Ask => {
+                self.last_bar_ask = Some(*bar);
+                self.process_quote_ticks_from_bars();
+            }
+        }
+    }
+
+    /// Processes the given market `status` for the venue.
+    pub fn process_status(&mut self, status: MarketStatus) {
+        self.market_status = status;
+    }
+
+    fn process_trade_ticks_from_bar(&mut self, bar: &Bar) {
+        let size = Quantity::new(bar.volume.as_f64() / 4.0, bar.volume.precision)
+            .expect("Invalid trade tick size from bar volume");
+        let aggressor_side = match self.core.last {
+            Some(last) if bar.open <= last => AggressorSide::Seller,
+            _ => AggressorSide::Buyer,
+        };
+        let mut trade = TradeTick::new(
+            bar.bar_type.instrument_id,
+            bar.open,
+            size,
+            aggressor_side,
+            self.generate_trade_id(),
+            bar.ts_event,
+            bar.ts_event,
+        );
+
+        // Open
+        if self.core.last != Some(bar.open) {
+            self.update_book_with_trade(&trade);
+            self.iterate(trade.ts_init);
+            self.core.last = Some(bar.open);
+        }
+
+        // High
+        if self.core.last.map_or(true, |last| bar.high > last) {
+            trade.price = bar.high;
+            trade.aggressor_side = AggressorSide::Buyer;
+            trade.trade_id = self.generate_trade_id();
+            self.update_book_with_trade(&trade);
+            self.iterate(trade.ts_init);
+            self.core.last = Some(bar.high);
+        }
+
+        // Low
+        if self.core.last.map_or(true, |last| bar.low < last) {
+            trade.price = bar.low;
+            trade.aggressor_side = AggressorSide::Seller;
+            trade.trade_id = self.generate_trade_id();
+            self.update_book_with_trade(&trade);
+            self.iterate(trade.ts_init);
+            self.core.last = Some(bar.low);
+        }
+
+        // Close
+        if self.core.last != Some(bar.close) {
+            trade.price = bar.close;
+            trade.aggressor_side = match self.core.last {
+                Some(last) if bar.close <= last => AggressorSide::Seller,
+                _ => AggressorSide::Buyer,
+            };
+            trade.trade_id = self.generate_trade_id();
+            self.update_book_with_trade(&trade);
+            self.iterate(trade.ts_init);
+            self.core.last = Some(bar.close);
+        }
+    }
+
+    fn process_quote_ticks_from_bars(&mut self) {
+        let (bid_bar, ask_bar) = match (self.last_bar_bid, self.last_bar_ask) {
+            (Some(bid_bar), Some(ask_bar)) if bid_bar.ts_event == ask_bar.ts_event => {
+                (bid_bar, ask_bar)
+            }
+            _ => return, // Wait for next bar
+        };
+
+        let bid_size = Quantity::new(bid_bar.volume.as_f64() / 4.0, bid_bar.volume.precision)
+            .expect("Invalid quote tick size from bar volume");
+        let ask_size = Quantity::new(ask_bar.volume.as_f64() / 4.0, ask_bar.volume.precision)
+            .expect("Invalid quote tick size from bar volume");
+
+        let prices = [
+            (bid_bar.open, ask_bar.open),
+            (bid_bar.high, ask_bar.high),
+            (bid_bar.low, ask_bar.low),
+            (bid_bar.close, ask_bar.close),
+        ];
+        for (bid_price, ask_price) in prices {
+            let quote = QuoteTick::new(
+                self.instrument.id(),
+                bid_price,
+                ask_price,
+                bid_size,
+                ask_size,
+                bid_bar.ts_event,
+                ask_bar.ts_init,
+            )
+            .expect("Invalid quote tick from bars");
+            self.book.update_quote_tick(&quote);
+            self.iterate(quote.ts_init);
+        }
+
+        self.last_bar_bid = None;
+        self.last_bar_ask = None;
+    }
+
+    fn update_book_with_trade(&mut self, trade: &TradeTick) {
+        if self.config.queue_position {
+            self.fill_model.process_trade(trade);
+        }
+        self.book.update_trade_tick(trade);
+    }
+
+    /// Returns the sizes of the book levels which orders are queued at.
+    fn queued_level_sizes(&self) -> Vec<(OrderSide, Price, Quantity)> {
+        if !self.config.queue_position {
+            return Vec::new();
+        }
+        self.fill_model
+            .queued_levels()
+            .into_iter()
+            .map(|(side, price)| (side, price, self.level_size(side, price)))
+            .collect()
+    }
+
+    /// Updates the queue positions for any changes to the given `level_sizes`, following
+    /// book updates (from both deletes and updates to a smaller size).
+    fn update_queue_positions(&mut self, level_sizes: Vec<(OrderSide, Price, Quantity)>) {
+        for (side, price, previous_size) in level_sizes {
+            let size = self.level_size(side, price);
+            self.fill_model
+                .process_level_update(side, price, previous_size, size);
+        }
+    }
+
+    // -- TRADING COMMANDS ------------------------------------------------------------------------
+
+    /// Processes the given trading `command`.
+    ///
+    /// If the engine has a latency model then the command is held in flight, and is only
+    /// processed once the simulated latency from the commands `ts_init` has elapsed (see
+    /// [`OrderMatchingEngine::register_inflight_timer`]).
+    pub fn process_command(&mut self, command: TradingCommand, account_id: AccountId) {
+        let Some(latency_model) = self.latency_model.as_mut() else {
+            self.execute_command(command, account_id);
+            return;
+        };
+
+        let latency_nanos = match &command {
+            TradingCommand::SubmitOrder(_) => latency_model.insert_latency_nanos(),
+            TradingCommand::ModifyOrder(_) => latency_model.update_latency_nanos(),
+            TradingCommand::CancelOrder(_) | TradingCommand::CancelAllOrders(_) => {
+                latency_model.cancel_latency_nanos()
+            }
+        };
+
+        self.inflight_count += 1;
+        self.inflight_queue.push(Reverse(InflightCommand {
+            ts: command.ts_init() + latency_nanos,
+            counter: self.inflight_count,
+            command,
+            account_id,
+        }));
+        self.schedule_inflight_release();
+    }
+
+    /// Registers a time alert on the `clock` to release in-flight commands, so that each
+    /// command is processed when the clock is advanced to its arrival time (such as by the
+    /// `TimeEventAccumulator`), even in the absence of market data.
+    ///
+    /// The engine is only borrowed by the alert when its event is handled.
+    pub fn register_inflight_timer(engine: &Rc<RefCell<Self>>, clock: Rc<RefCell<TestClock>>) {
+        let weak_engine = Rc::downgrade(engine);
+        let callback: Rc<LocalTimeEventCallback> = Rc::new(move |event: &TimeEvent| {
+            if let Some(engine) = weak_engine.upgrade() {
+                engine.borrow_mut().on_inflight_time_event(event);
+            }
+        });
+
+        let mut engine = engine.borrow_mut();
+        let name = format!("{}-INFLIGHT", engine.instrument.id());
+        engine.inflight_timer = Some(InflightTimer {
+            name,
+            clock,
+            callback,
+            alert_ts: None,
+        });
+        engine.schedule_inflight_release();
+    }
+
+    fn on_inflight_time_event(&mut self, event: &TimeEvent) {
+        if let Some(timer) = self.inflight_timer.as_mut() {
+            timer.alert_ts = None;
+        }
+        self.process_inflight_commands(event.ts_event);
+        self.schedule_inflight_release();
+    }
+
+    /// Sets the in-flight time alert (if registered) for the arrival of the next command.
+    fn schedule_inflight_release(&mut self) {
+        let Some(next_ts) = self.next_inflight_ts() else {
+            return;
+        };
+        let Some(timer) = self.inflight_timer.as_mut() else {
+            return;
+        };
+        if timer.alert_ts.is_some_and(|alert_ts| alert_ts <= next_ts) {
+            return; // Already alerting on or before arrival
+        }
+
+        let ts_now = timer.clock.borrow().timestamp_ns();
+        if next_ts <= ts_now {
+            // Already arrived, as the clock was advanced past the arrival time
+            self.process_inflight_commands(ts_now);
+            return;
+        }
+
+        timer.clock.borrow_mut().set_local_time_alert_ns(
+            &timer.name,
+            next_ts,
+            timer.callback.clone(),
+        );
+        timer.alert_ts = Some(next_ts);
+    }
+
+    /// Processes all in-flight commands which have reached the venue as at `ts_now`.
+    ///
+    /// Each command is processed as at its arrival time, so that the resulting events are
+    /// timestamped when the command reached the venue.
+    ///
+    /// This is called on every iteration of the engine, and by the time alert of the engine
+    /// when registered with [`OrderMatchingEngine::register_inflight_timer`].
+    pub fn process_inflight_commands(&mut self, ts_now: UnixNanos) {
+        let ts_prev = self.ts_processing;
+        let mut processed = false;
+        while let Some(Reverse(inflight)) = self.inflight_queue.peek() {
+            if inflight.ts > ts_now {
+                break; // Not yet arrived at the venue
+            }
+            let Reverse(inflight) = self.inflight_queue.pop().unwrap();
+            self.ts_processing = Some(inflight.ts);
+            self.execute_command(inflight.command, inflight.account_id);
+            processed = true;
+        }
+
+        if processed {
+            self.ts_processing = ts_prev;
+            self.schedule_inflight_release();
+        }
+    }
+
+    /// Returns the UNIX timestamp (nanoseconds) at which the next in-flight command will
+    /// reach the venue (if any).
+    #[must_use]
+    pub fn next_inflight_ts(&self) -> Option<UnixNanos> {
+        self.inflight_queue
+            .peek()
+            .map(|Reverse(inflight)| inflight.ts)
+    }
+
+    fn execute_command(&mut self, command: TradingCommand, account_id: AccountId) {
+        match command {
+            TradingCommand::SubmitOrder(command) => self.process_submit(&command, account_id),
+            TradingCommand::ModifyOrder(command) => self.process_modify(&command, account_id),
+            TradingCommand::CancelOrder(command) => self.process_cancel(&command, account_id),
+            TradingCommand::CancelAllOrders(command) => {
+                self.process_cancel_all(&command, account_id);
+            }
+        }
+    }
+
+    /// Processes the given `command` to submit an order.
+    pub fn process_submit(&mut self, command: &SubmitOrder, account_id: AccountId) {
+        if let Some(position_id) = command.position_id {
+            self.position_ids
+                .insert(command.client_order_id, position_id);
+        }
+        self.process_order(&command.order, account_id);
+    }
+
+    /// Processes the given `order` for the venue.
+    pub fn process_order(&mut self, order: &OrderAny, account_id: AccountId) {
+        let client_order_id = order.client_order_id();
+        if self.order_exists(&client_order_id) {
+            return; // Already processed
+        }
+
+        // Index identifiers
+        self.account_ids.insert(order.trader_id(), account_id);
+        if let Some(position_id) = order.position_id() {
+            self.position_ids.insert(client_order_id, position_id);
+        }
+        let order = self
+            .orders
+            .entry(client_order_id)
+            .or_insert_with(|| order.clone())
+            .clone();
+
+        if self.config.support_contingent_orders {
+            if let Some(parent_order_id) = order.parent_order_id() {
+                if let Some(parent) = self.orders.get(&parent_order_id) {
+                    match parent.status() {
+                        OrderStatus::Rejected => {
+                            self.generate_order_rejected(
+                                &order,
+                                &format!("REJECT OTO from {parent_order_id}"),
+                            );
+                            return; // Order rejected
+                        }
+                        OrderStatus::Accepted | OrderStatus::Triggered => {
+                            info!("Pending OTO {client_order_id} triggers from {parent_order_id}");
+                            return; // Pending trigger
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if matches!(
+                order.contingency_type(),
+                Some(ContingencyType::Oco | ContingencyType::Ouo)
+            ) && !order.is_closed()
+            {
+                for linked_order_id in order.linked_order_ids().unwrap_or_default() {
+                    if self
+                        .orders
+                        .get(&linked_order_id)
+                        .map_or(false, OrderAny::is_closed)
+                    {
+                        self.generate_order_rejected(
+                            &order,
+                            &format!("Contingent order {linked_order_id} already closed"),
+                        );
+                        return; // Order rejected
+                    }
+                }
+            }
+        }
+
+        // Check order quantity precision
+        let size_precision = self.instrument.size_precision();
+        if order.quantity().precision != size_precision {
+            self.generate_order_rejected(
+                &order,
+                &format!(
+                    "Invalid size precision for order {client_order_id}, was {} when {} size precision is {size_precision}",
+                    order.quantity().precision,
+                    self.instrument.id(),
+                ),
+            );
+            return; // Invalid order
+        }
+
+        // Check order price precision
+        let price_precision = self.instrument.price_precision();
+        if let Some(price) = order.price() {
+            if price.precision != price_precision {
+                self.generate_order_rejected(
+                    &order,
+                    &format!(
+                        "Invalid price precision for order {client_order_id}, was {} when {} price precision is {price_precision}",
+                        price.precision,
+                        self.instrument.id(),
+                    ),
+                );
+                return; // Invalid order
+            }
+        }
+
+        // Check order trigger price precision
+        if let Some(trigger_price) = order.trigger_price() {
+            if trigger_price.precision != price_precision {
+                self.generate_order_rejected(
+                    &order,
+                    &format!(
+                        "Invalid trigger price precision for order {client_order_id}, was {} when {} price precision is {price_precision}",
+                        trigger_price.precision,
+                        self.instrument.id(),
+                    ),
+                );
+                return; // Invalid order
+            }
+        }
+
+        let position_qty = self.position_qty(&order);
+
+        // Check not shorting an equity without a MARGIN account
+        if order.is_sell()
+            && self.account_type != AccountType::Margin
+            && self.instrument.as_any().is::<Equity>()
+            && !position_qty.map_or(false, |qty| qty >= order.quantity().raw as i64)
+        {
+            self.generate_order_rejected(
+                &order,
+                &format!(
+                    "SHORT SELLING not permitted on a CASH account with order {client_order_id}"
+                ),
+            );
+            return; // Cannot short sell
+        }
+
+        // Check reduce-only instruction
+        if self.config.use_reduce_only && order.is_reduce_only() && !order.is_closed() {
+            let would_increase = match position_qty {
+                None | Some(0) => true,
+                Some(qty) => (order.is_buy() && qty > 0) || (order.is_sell() && qty < 0),
+            };
+            if would_increase {
+                self.generate_order_rejected(
+                    &order,
+                    &format!(
+                        "REDUCE_ONLY {} {} order would have increased position",
+                        order.order_type(),
+                        order.order_side(),
+                    ),
+                );
+                return; // Reduce only
+            }
+        }
+
+        match order.order_type() {
+            OrderType::Market => self.process_market_order(&order),
+            OrderType::MarketToLimit => self.process_market_to_limit_order(&order),
+            OrderType::Limit => self.process_limit_order(&order),
+            OrderType::StopMarket | OrderType::MarketIfTouched => {
+                self.process_stop_market_order(&order);
+            }
+            OrderType::StopLimit | OrderType::LimitIfTouched => {
+                self.process_stop_limit_order(&order);
+            }
+            OrderType::TrailingStopMarket | OrderType::TrailingStopLimit => {
+                self.process_trailing_stop_order(&order);
+            }
+        }
+    }
+
+    /// Force-closes the given `quantity` of the `position` with a reduce-only market order
+    /// generated by the venue, as when the position is liquidated.
+    ///
+    /// The order is added to the `cache` before it is filled, so that the order events
+    /// sent to the execution engine are for a known order. Any `liquidation_fee` is charged
+    /// as a rate of the notional value filled, in addition to the commission of the fee model.
+    pub fn liquidate_position(
+        &mut self,
+        position: &Position,
+        quantity: Quantity,
+        liquidation_fee: Option<Decimal>,
+        cache: &mut Cache,
+    ) {
+        if !position.is_open() {
+            warn!(
+                "Cannot liquidate position {} which is not open",
+                position.id
+            );
+            return;
+        }
+
+        let order_side = match position.side {
+            PositionSide::Long => OrderSide::Sell,
+            _ => OrderSide::Buy,
+        };
+        let ts_now = self.ts_now();
+        let client_order_id = self.generate_liquidation_order_id();
+        let order = MarketOrder::new(
+            position.trader_id,
+            position.strategy_id,
+            position.instrument_id,
+            client_order_id,
+            order_side,
+            quantity.min(position.quantity),
+            TimeInForce::Ioc,
+            UUID4::new(),
+            ts_now,
+            true,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Ustr::from("LIQUIDATION")),
+        )
+        .expect("Invalid liquidation order");
+        let submitted = OrderSubmitted::new(
+            position.trader_id,
+            position.strategy_id,
+            position.instrument_id,
+            client_order_id,
+            position.account_id,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+        )
+        .expect("Invalid order submitted event");
+        let mut order = OrderAny::Market(order);
+        order
+            .apply(OrderEvent::OrderSubmitted(submitted))
+            .expect("Invalid liquidation order state");
+
+        if let Err(e) = cache.add_order(order.clone(), Some(position.id), None) {
+            error!("Cannot liquidate position {}: {e}", position.id);
+            return;
+        }
+
+        warn!(
+            "Liquidating {} of position {} with {client_order_id}",
+            order.quantity(),
+            position.id,
+        );
+        self.position_ids.insert(client_order_id, position.id);
+        if let Some(liquidation_fee) = liquidation_fee {
+            self.liquidation_fees
+                .insert(client_order_id, liquidation_fee);
+        }
+        self.process_order(&order, position.account_id);
+        self.liquidation_fees.remove(&client_order_id);
+    }
+
+    /// Processes the given `command` to modify an order.
+    pub fn process_modify(&mut self, command: &ModifyOrder, account_id: AccountId) {
+        match self.orders.get(&command.client_order_id) {
+            Some(order) if self.order_exists(&command.client_order_id) => {
+                let order = order.clone();
+                self.update_order(
+                    &order,
+                    command.quantity,
+                    command.price,
+                    command.trigger_price,
+                    true,
+                );
+            }
+            _ => self.generate_order_modify_rejected(
+                command.trader_id,
+                command.strategy_id,
+                command.instrument_id,
+                command.client_order_id,
+                command.venue_order_id,
+                Some(account_id),
+                &format!("{} not found", command.client_order_id),
+            ),
+        }
+    }
+
+    /// Processes the given `command` to cancel an order.
+    pub fn process_cancel(&mut self, command: &CancelOrder, account_id: AccountId) {
+        match self.orders.get(&command.client_order_id) {
+            Some(order) if self.order_exists(&command.client_order_id) => {
+                if order.is_inflight() || order.is_open() {
+                    let order = order.clone();
+                    self.cancel_order(&order, true);
+                }
+            }
+            _ => self.generate_order_cancel_rejected(
+                command.trader_id,
+                command.strategy_id,
+                command.instrument_id,
+                command.client_order_id,
+                command.venue_order_id,
+                Some(account_id),
+                &format!("{} not found", command.client_order_id),
+            ),
+        }
+    }
+
+    /// Processes the given `command` to cancel all open orders (optionally for one side only).
+    pub fn process_cancel_all(&mut self, command: &CancelAllOrders, _account_id: AccountId) {
+        let orders: Vec<OrderAny> = self
+            .get_open_orders()
+            .into_iter()
+            .filter(|order| {
+                command.order_side == OrderSide::NoOrderSide
+                    || command.order_side == order.order_side()
+            })
+            .cloned()
+            .collect();
+
+        for order in orders {
+            if order.is_inflight() || order.is_open() {
+                self.cancel_order(&order, true);
+            }
+        }
+    }
+
+    fn process_market_order(&mut self, order: &OrderAny) {
+        if !self.has_market_for(order.order_side()) {
+            self.generate_order_rejected(
+                order,
+                &format!("No market for {}", order.instrument_id()),
+            );
+            return; // Cannot accept order
+        }
+
+        // Immediately fill marketable order
+        self.fill_market_order(order.client_order_id());
+    }
+
+    fn process_market_to_limit_order(&mut self, order: &OrderAny) {
+        if !self.has_market_for(order.order_side()) {
+            self.generate_order_rejected(
+                order,
+                &format!("No market for {}", order.instrument_id()),
+            );
+            return; // Cannot accept order
+        }
+
+        // Immediately fill marketable order
+        let client_order_id = order.client_order_id();
+        self.fill_market_order(client_order_id);
+
+        if self.order(client_order_id).is_open() {
+            self.accept_order(client_order_id);
+        }
+    }
+
+    fn process_limit_order(&mut self, order: &OrderAny) {
+        let client_order_id = order.client_order_id();
+        let price = order.price().expect("Limit order must have a price");
+
+        if order.is_post_only() && self.is_limit_matched(order.order_side(), price) {
+            self.generate_order_rejected(
+                order,
+                &format!(
+                    "POST_ONLY {} {} order limit px of {price} would have been a TAKER: bid={}, ask={}",
+                    order.order_type(),
+                    order.order_side(),
+                    fmt_price(self.core.bid),
+                    fmt_price(self.core.ask),
+                ),
+            );
+            return; // Invalid price
+        }
+
+        // Order is valid and accepted
+        self.accept_order(client_order_id);
+
+        // Check for immediate fill
+        if self.is_limit_matched(order.order_side(), price) {
+            // Filling as liquidity taker
+            let order = self.order_mut(client_order_id);
+            if order.liquidity_side().is_none() {
+                order.set_liquidity_side(LiquiditySide::Taker);
+            }
+            self.fill_limit_order(client_order_id);
+        } else if matches!(order.time_in_force(), TimeInForce::Fok | TimeInForce::Ioc) {
+            self.cancel_order(&self.order(client_order_id).clone(), true);
+        }
+    }
+
+    fn process_stop_market_order(&mut self, order: &OrderAny) {
+        let trigger_price = order
+            .trigger_price()
+            .expect("Stop order must have a trigger price");
+
+        if self.is_triggered(order, trigger_price) {
+            if self.config.reject_stop_orders {
+                self.generate_order_rejected(
+                    order,
+                    &format!(
+                        "{} {} order stop px of {trigger_price} was in the market: bid={}, ask={}",
+                        order.order_type(),
+                        order.order_side(),
+                        fmt_price(self.core.bid),
+                        fmt_price(self.core.ask),
+                    ),
+                );
+                return; // Invalid price
+            }
+            self.fill_market_order(order.client_order_id());
+            return;
+        }
+
+        // Order is valid and accepted
+        self.accept_order(order.client_order_id());
+    }
+
+    fn process_stop_limit_order(&mut self, order: &OrderAny) {
+        let client_order_id = order.client_order_id();
+        let trigger_price = order
+            .trigger_price()
+            .expect("Stop order must have a trigger price");
+        let price = order.price().expect("Stop limit order must have a price");
+
+        if self.is_triggered(order, trigger_price) {
+            if self.config.reject_stop_orders {
+                self.generate_order_rejected(
+                    order,
+                    &format!(
+                        "{} {} order trigger stop px of {trigger_price} was in the market: bid={}, ask={}",
+                        order.order_type(),
+                        order.order_side(),
+                        fmt_price(self.core.bid),
+                        fmt_price(self.core.ask),
+                    ),
+                );
+                return; // Invalid price
+            }
+            self.accept_order(client_order_id);
+            self.generate_order_triggered(&self.order(client_order_id).clone());
+
+            // Check if immediately marketable
+            if self.is_limit_matched(order.order_side(), price) {
+                self.order_mut(client_order_id)
+                    .set_liquidity_side(LiquiditySide::Taker);
+                self.fill_limit_order(client_order_id);
+            }
+            return;
+        }
+
+        // Order is valid and accepted
+        self.accept_order(client_order_id);
+    }
+
+    fn process_trailing_stop_order(&mut self, order: &OrderAny) {
+        if let Some(trigger_price) = order.trigger_price() {
+            if self.is_stop_triggered(order.order_side(), trigger_price) {
+                self.generate_order_rejected(
+                    order,
+                    &format!(
+                        "{} {} order trigger stop px of {trigger_price} was in the market: bid={}, ask={}",
+                        order.order_type(),
+                        order.order_side(),
+                        fmt_price(self.core.bid),
+                        fmt_price(self.core.ask),
+                    ),
+                );
+                return; // Invalid price
+            }
+        }
+
+        // Order is valid and accepted
+        self.accept_order(order.client_order_id());
+    }
+
+    // -- ORDER UPDATES ---------------------------------------------------------------------------
+
+    fn update_order(
+        &mut self,
+        order: &OrderAny,
+        quantity: Option<Quantity>,
+        price: Option<Price>,
+        trigger_price: Option<Price>,
+        update_contingencies: bool,
+    ) {
+        let client_order_id = order.client_order_id();
+        let quantity = quantity.unwrap_or(order.quantity());
+        let price = price.or(order.price());
+        let trigger_price = trigger_price.or(order.trigger_price());
+
+        match order.order_type() {
+            OrderType::Limit | OrderType::MarketToLimit => {
+                let price = price.expect("Limit order must have a price");
+                self.update_limit_order(order, quantity, price);
+            }
+            OrderType::StopMarket => {
+                let trigger_price = trigger_price.expect("Stop order must have a trigger price");
+                self.update_stop_market_order(order, quantity, trigger_price);
+            }
+            OrderType::MarketIfTouched | OrderType::TrailingStopMarket => {
+                let trigger_price = trigger_price.expect("Stop order must have a trigger price");
+                self.update_market_if_touched_order(order, quantity, trigger_price);
+            }
+            OrderType::StopLimit | OrderType::LimitIfTouched | OrderType::TrailingStopLimit => {
+                let price = price.expect("Stop limit order must have a price");
+                let trigger_price = trigger_price.expect("Stop order must have a trigger price");
+                self.update_stop_limit_order(order, quantity, price, trigger_price);
+            }
+            OrderType::Market => {
+                self.generate_order_updated(order, quantity, None, None);
+            }
+        }
+
+        if self.fill_model.contains(&client_order_id) {
+            let updated = self.order(client_order_id);
+            // Moving the limit price or increasing the quantity loses the orders place in the queue
+            if updated.price() != order.price() || updated.quantity() > order.quantity() {
+                self.queue_order(client_order_id);
+            } else {
+                let leaves_qty = updated.leaves_qty();
+                self.fill_model
+                    .update_leaves_qty(&client_order_id, leaves_qty);
+            }
+        }
+
+        if self.config.support_contingent_orders
+            && update_contingencies
+            && order.contingency_type().is_some()
+            && order.contingency_type() != Some(ContingencyType::NoContingency)
+        {
+            self.update_contingent_orders(client_order_id);
+        }
+    }
+
+    fn update_limit_order(&mut self, order: &OrderAny, quantity: Quantity, price: Price) {
+        if self.is_limit_matched(order.order_side(), price) {
+            if order.is_post_only() {
+                self.generate_order_modify_rejected_for(
+                    order,
+                    &format!(
+                        "POST_ONLY {} {} order new limit px of {price} would have been a TAKER: bid={}, ask={}",
+                        order.order_type(),
+                        order.order_side(),
+                        fmt_price(self.core.bid),
+                        fmt_price(self.core.ask),
+                    ),
+                );
+                return; // Cannot update order
+            }
+
+            self.generate_order_updated(order, quantity, Some(price), None);
+            let client_order_id = order.client_order_id();
+            self.order_mut(client_order_id)
+                .set_liquidity_side(LiquiditySide::Taker);
+            self.fill_limit_order(client_order_id); // Immediate fill as TAKER
+            return; // Filled
+        }
+
+        self.generate_order_updated(order, quantity, Some(price), None);
+    }
+
+    fn update_stop_market_order(
+        &mut self,
+        order: &OrderAny,
+        quantity: Quantity,
+        trigger_price: Price,
+    ) {
+        if self.is_stop_triggered(order.order_side(), trigger_price) {
+            self.generate_order_modify_rejected_for(
+                order,
+                &format!(
+                    "{} {} order new stop px of {trigger_price} was in the market: bid={}, ask={}",
+                    order.order_type(),
+                    order.order_side(),
+                    fmt_price(self.core.bid),
+                    fmt_price(self.core.ask),
+                ),
+            );
+            return; // Cannot update order
+        }
+
+        self.generate_order_updated(order, quantity, None, Some(trigger_price));
+    }
+
+    fn update_market_if_touched_order(
+        &mut self,
+        order: &OrderAny,
+        quantity: Quantity,
+        trigger_price: Price,
+    ) {
+        if self.is_touch_triggered(order.order_side(), trigger_price) {
+            self.generate_order_modify_rejected_for(
+                order,
+                &format!(
+                    "{} {} order new stop px of {trigger_price} was in the market: bid={}, ask={}",
+                    order.order_type(),
+                    order.order_side(),
+                    fmt_price(self.core.bid),
+                    fmt_price(self.core.ask),
+                ),
+            );
+            return; // Cannot update order
+        }
+
+        self.generate_order_updated(order, quantity, None, Some(trigger_price));
+    }
+
+    fn update_stop_limit_order(
+        &mut self,
+        order: &OrderAny,
+        quantity: Quantity,
+        price: Price,
+        trigger_price: Price,
+    ) {
+        if order.is_triggered() {
+            // Updating limit price
+            if self.is_limit_matched(order.order_side(), price) {
+                if order.is_post_only() {
+                    self.generate_order_modify_rejected_for(
+                        order,
+                        &format!(
+                            "POST_ONLY {} {} order new limit px of {price} would have been a TAKER: bid={}, ask={}",
+                            order.order_type(),
+                            order.order_side(),
+                            fmt_price(self.core.bid),
+                            fmt_price(self.core.ask),
+                        ),
+                    );
+                    return; // Cannot update order
+                }
+
+                self.generate_order_updated(order, quantity, Some(price), None);
+                let client_order_id = order.client_order_id();
+                self.order_mut(client_order_id)
+                    .set_liquidity_side(LiquiditySide::Taker);
+                self.fill_limit_order(client_order_id); // Immediate fill as TAKER
+                return; // Filled
+            }
+        } else if self.is_triggered(order, trigger_price) {
+            // Updating stop price
+            self.generate_order_modify_rejected_for(
+                order,
+                &format!(
+                    "{} {} order new trigger stop px of {trigger_price} was in the market: bid={}, ask={}",
+                    order.order_type(),
+                    order.order_side(),
+                    fmt_price(self.core.bid),
+                    fmt_price(self.core.ask),
+                ),
+            );
+            return; // Cannot update order
+        }
+
+        self.generate_order_updated(order, quantity, Some(price), Some(trigger_price));
+    }
+
+    fn update_trailing_stop_order(&mut self, client_order_id: ClientOrderId) {
+        let order = self.order(client_order_id).clone();
+        let (new_trigger_price, new_price) = match trailing_stop_calculate(
+            self.instrument.price_increment(),
+            &order,
+            self.core.bid,
+            self.core.ask,
+            self.core.last,
+        ) {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Cannot update trailing stop {client_order_id}: {e}");
+                return;
+            }
+        };
+
+        if new_trigger_price.is_none() && new_price.is_none() {
+            return; // No updates
+        }
+
+        self.generate_order_updated(&order, order.quantity(), new_price, new_trigger_price);
+    }
+
+    fn update_contingent_orders(&mut self, client_order_id: ClientOrderId) {
+        debug!("Updating OUO orders from {client_order_id}");
+        let order = self.order(client_order_id).clone();
+
+        for linked_order_id in order.linked_order_ids().unwrap_or_default() {
+            let Some(ouo_order) = self.orders.get(&linked_order_id).cloned() else {
+                warn!("OUO order {linked_order_id} not found");
+                continue;
+            };
+            if ouo_order.is_active_local()
+                || ouo_order.order_type() == OrderType::Market
+                || ouo_order.is_closed()
+            {
+                continue;
+            }
+
+            if order.leaves_qty().is_zero() {
+                self.cancel_order(&ouo_order, true);
+            } else if ouo_order.leaves_qty() != order.leaves_qty() {
+                self.update_order(&ouo_order, Some(order.leaves_qty()), None, None, false);
+            }
+        }
+    }
+
+    // -- ORDER PROCESSING ------------------------------------------------------------------------
+
+    /// Iterates the matching engine by processing the bid and ask order sides
+    /// as at the given UNIX `timestamp_ns`.
+    ///
+    /// Events generated by the iteration are timestamped `timestamp_ns`, without
+    /// changing the time of the shared clock.
+    pub fn iterate(&mut self, timestamp_ns: UnixNanos) {
+        let ts_prev = self.ts_processing.replace(timestamp_ns);
+        self.process_inflight_commands(timestamp_ns);
+
+        if let Some(bid) = self.best_bid_price() {
+            self.core.bid = Some(bid);
+        }
+        if let Some(ask) = self.best_ask_price() {
+            self.core.ask = Some(ask);
+        }
+
+        for client_order_id in self.core_order_ids() {
+            if self
+                .orders
+                .get(&client_order_id)
+                .map_or(true, OrderAny::is_closed)
+            {
+                continue; // Orders state has changed since iteration started
+            }
+            self.match_order(client_order_id, false);
+        }
+
+        for client_order_id in self.core_order_ids() {
+            let order = self.order(client_order_id).clone();
+            if order.is_closed() {
+                continue;
+            }
+
+            // Check expiry
+            if self.config.support_gtd_orders {
+                if let Some(expire_time) = order.expire_time() {
+                    if expire_time > 0 && timestamp_ns >= expire_time {
+                        self.delete_from_core(&order);
+                        self.expire_order(&order);
+                        continue;
+                    }
+                }
+            }
+
+            // Manage trailing stop
+            if matches!(
+                order.order_type(),
+                OrderType::TrailingStopMarket | OrderType::TrailingStopLimit
+            ) {
+                self.update_trailing_stop_order(client_order_id);
+            }
+
+            // Move market back to targets
+            if self.has_targets {
+                self.core.bid = self.target_bid;
+                self.core.ask = self.target_ask;
+                self.core.last = self.target_last;
+                self.has_targets = false;
+            }
+        }
+
+        // Reset any targets after iteration
+        self.reset_targets();
+        self.ts_processing = ts_prev;
+    }
+
+    fn match_order(&mut self, client_order_id: ClientOrderId, initial: bool) {
+        let order = self.order(client_order_id).clone();
+        let side = order.order_side();
+
+        match order.order_type() {
+            OrderType::Limit | OrderType::MarketToLimit => {
+                let price = order.price().expect("Limit order must have a price");
+                self.match_passive_limit_order(client_order_id, side, price);
+            }
+            OrderType::StopMarket | OrderType::TrailingStopMarket => {
+                let trigger_price = order.trigger_price().expect("No trigger price");
+                if self.is_stop_triggered(side, trigger_price) {
+                    self.triggered_prices.insert(client_order_id, trigger_price);
+                    self.fill_market_order(client_order_id);
+                }
+            }
+            OrderType::MarketIfTouched => {
+                let trigger_price = order.trigger_price().expect("No trigger price");
+                if self.is_touch_triggered(side, trigger_price) {
+                    self.triggered_prices.insert(client_order_id, trigger_price);
+                    self.fill_market_order(client_order_id);
+                }
+            }
+            OrderType::StopLimit | OrderType::TrailingStopLimit | OrderType::LimitIfTouched => {
+                let price = order.price().expect("Stop limit order must have a price");
+                let trigger_price = order.trigger_price().expect("No trigger price");
+
+                if order.is_triggered() {
+                    self.match_passive_limit_order(client_order_id, side, price);
+                    return;
+                }
+
+                if self.is_triggered(&order, trigger_price) {
+                    if !initial || order.order_type() != OrderType::LimitIfTouched {
+                        self.triggered_prices.insert(client_order_id, trigger_price);
+                    }
+                    let liquidity_side =
+                        determine_order_liquidity(initial, side, price, trigger_price);
+                    self.order_mut(client_order_id)
+                        .set_liquidity_side(liquidity_side);
+                    self.trigger_stop_order(client_order_id);
+
+                    // Check if immediately marketable
+                    if self.order(client_order_id).is_open() && self.is_limit_matched(side, price) {
+                        self.order_mut(client_order_id)
+                            .set_liquidity_side(LiquiditySide::Taker);
+                        self.fill_limit_order(client_order_id);
+                    }
+                }
+            }
+            OrderType::Market => {
+                error!(
+                    "Invalid order type for matching, was {}",
+                    order.order_type()
+                );
+            }
+        }
+    }
+
+    fn trigger_stop_order(&mut self, client_order_id: ClientOrderId) {
+        // Always STOP_LIMIT, TRAILING_STOP_LIMIT or LIMIT_IF_TOUCHED orders
+        let order = self.order(client_order_id).clone();
+        let side = order.order_side();
+        let price = order.price().expect("Stop limit order must have a price");
+        let trigger_price = order.trigger_price().expect("No trigger price");
+
+        self.generate_order_triggered(&order);
+
+        // Check for immediate fill
+        let is_inside = match side {
+            OrderSide::Buy => {
+                trigger_price > price && self.core.ask.map_or(false, |ask| price > ask)
+            }
+            OrderSide::Sell => {
+                trigger_price < price && self.core.bid.map_or(false, |bid| price < bid)
+            }
+            _ => false,
+        };
+        if is_inside {
+            self.order_mut(client_order_id)
+                .set_liquidity_side(LiquiditySide::Maker);
+            self.fill_limit_order(client_order_id);
+            return;
+        }
+
+        if self.is_limit_matched(side, price) {
+            if order.is_post_only() {
+                // Would be liquidity taker
+                self.delete_from_core(&order);
+                self.generate_order_rejected(
+                    &order,
+                    &format!(
+                        "POST_ONLY {} {} order limit px of {price} would have been a TAKER: bid={}, ask={}",
+                        order.order_type(),
+                        order.order_side(),
+                        fmt_price(self.core.bid),
+                        fmt_price(self.core.ask),
+                    ),
+                );
+                return;
+            }
+            self.order_mut(client_order_id)
+                .set_liquidity_side(LiquiditySide::Taker);
+            self.fill_limit_order(client_order_id);
+        } else if self.config.queue_position {
+            self.queue_order(client_order_id);
+        }
+    }
+
+    fn match_passive_limit_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        side: OrderSide,
+        price: Price,
+    ) {
+        if !self.is_limit_matched(side, price) {
+            return;
+        }
+
+        if self.fill_model.contains(&client_order_id) && !self.is_limit_crossed(side, price) {
+            // Market is only touching the limit price, so fill from the queue
+            self.fill_from_queue(client_order_id, price);
+            return;
+        }
+
+        self.order_mut(client_order_id)
+            .set_liquidity_side(LiquiditySide::Maker);
+        self.fill_limit_order(client_order_id);
+    }
+
+    fn fill_from_queue(&mut self, client_order_id: ClientOrderId, price: Price) {
+        let order = self.order(client_order_id).clone();
+        let Some(fillable_qty) = self.fill_model.fillable_qty(&client_order_id) else {
+            return;
+        };
+        let fill_qty = fillable_qty.min(order.leaves_qty());
+        if fill_qty.is_zero() {
+            return; // Still queued behind other orders
+        }
+
+        self.fill_model.consume(&client_order_id, fill_qty);
+        let venue_position_id = self.get_position_id(&order, true);
+        self.apply_fills(
+            client_order_id,
+            vec![(price, fill_qty)],
+            LiquiditySide::Maker,
+            venue_position_id,
+        );
+    }
+
+    /// Returns the projected fills for the given *limit* order filling passively
+    /// from its limit price.
+    ///
+    /// # Panics
+    ///
+    /// If the order does not have a limit price.
+    pub fn determine_limit_price_and_volume(
+        &mut self,
+        client_order_id: ClientOrderId,
+    ) -> Vec<(Price, Quantity)> {
+        let order = self.order(client_order_id).clone();
+        let price = order.price().expect("Order has no limit `price`");
+        let mut fills = self.simulate_fills(&order, false);
+        if fills.is_empty() || self.book_type != BookType::L1_MBP {
+            return fills;
+        }
+
+        let triggered_price = self.triggered_prices.get(&client_order_id).copied();
+        let side = order.order_side();
+
+        if let Some(triggered_price) = triggered_price {
+            if order.liquidity_side() == Some(LiquiditySide::Taker) {
+                // Filling as TAKER from a trigger
+                if (side == OrderSide::Buy && price > triggered_price)
+                    || (side == OrderSide::Sell && price < triggered_price)
+                {
+                    fills[0].0 = triggered_price;
+                    self.set_targets();
+                    self.move_market(side, price);
+                }
+            }
+        }
+
+        if order.liquidity_side() == Some(LiquiditySide::Maker) {
+            // Filling as MAKER
+            let initial_fill_price = fills[0].0;
+            let mut limit_price = price;
+            match side {
+                OrderSide::Buy => {
+                    if let Some(triggered_price) = triggered_price {
+                        if limit_price > triggered_price {
+                            limit_price = triggered_price;
+                        }
+                    }
+                    if initial_fill_price < limit_price {
+                        // Marketable BUY would have filled at limit
+                        self.set_targets();
+                        self.move_market(side, limit_price);
+                        fills[0].0 = price;
+                    }
+                }
+                OrderSide::Sell => {
+                    if let Some(triggered_price) = triggered_price {
+                        if limit_price < triggered_price {
+                            limit_price = triggered_price;
+                        }
+                    }
+                    if initial_fill_price > limit_price {
+                        // Marketable SELL would have filled at limit
+                        self.set_targets();
+                        self.move_market(side, limit_price);
+                        fills[0].0 = price;
+                    }
+                }
+                _ => panic!("Invalid `OrderSide`, was {side}"),
+            }
+        }
+
+        fills
+    }
+
+    /// Returns the projected fills for the given *marketable* order filling
+    /// aggressively into the opposite order side.
+    pub fn determine_market_price_and_volume(
+        &mut self,
+        client_order_id: ClientOrderId,
+    ) -> Vec<(Price, Quantity)> {
+        let order = self.order(client_order_id).clone();
+        let mut fills = self.simulate_fills(&order, true);
+        if fills.is_empty() || self.book_type != BookType::L1_MBP {
+            return fills;
+        }
+
+        let triggered_price = self.triggered_prices.get(&client_order_id).copied();
+        let side = order.order_side();
+
+        match order.order_type() {
+            OrderType::Market | OrderType::MarketToLimit | OrderType::MarketIfTouched => {
+                let price = match side {
+                    OrderSide::Buy => self.core.ask.or_else(|| self.best_ask_price()),
+                    OrderSide::Sell => self.core.bid.or_else(|| self.best_bid_price()),
+                    _ => panic!("Invalid `OrderSide`, was {side}"),
+                };
+                let price = triggered_price
+                    .or(price)
+                    .expect("Market best price was `None` when filling market order");
+                self.core.last = Some(price);
+                fills[0].0 = price;
+            }
+            _ => {
+                let price = match order.order_type() {
+                    OrderType::Limit | OrderType::LimitIfTouched => order.price(),
+                    _ => order.trigger_price(),
+                };
+                let price = triggered_price
+                    .or(price)
+                    .expect("No price for filling order");
+                self.move_market(side, price);
+                fills[0].0 = price;
+            }
+        }
+
+        fills
+    }
+
+    /// Fills the given *marketable* order.
+    pub fn fill_market_order(&mut self, client_order_id: ClientOrderId) {
+        let order = self.order(client_order_id).clone();
+        let venue_position_id = self.get_position_id(&order, true);
+        let position_qty = self.position_qty(&order);
+
+        if self.config.use_reduce_only
+            && order.is_reduce_only()
+            && position_qty.map_or(true, |qty| qty == 0)
+        {
+            warn!(
+                "Canceling REDUCE_ONLY {} as would increase position",
+                order.order_type()
+            );
+            self.cancel_order(&order, true);
+            return; // Order canceled
+        }
+
+        self.order_mut(client_order_id)
+            .set_liquidity_side(LiquiditySide::Taker);
+        let fills = self.determine_market_price_and_volume(client_order_id);
+        self.apply_fills(
+            client_order_id,
+            fills,
+            LiquiditySide::Taker,
+            venue_position_id,
+        );
+    }
+
+    /// Fills the given *limit* order.
+    pub fn fill_limit_order(&mut self, client_order_id: ClientOrderId) {
+        let order = self.order(client_order_id).clone();
+        let venue_position_id = self.get_position_id(&order, true);
+        let position_qty = self.position_qty(&order);
+
+        if self.config.use_reduce_only
+            && order.is_reduce_only()
+            && position_qty.map_or(true, |qty| qty == 0)
+        {
+            warn!(
+                "Canceling REDUCE_ONLY {} as would increase position",
+                order.order_type()
+            );
+            self.cancel_order(&order, true);
+            return; // Order canceled
+        }
+
+        let liquidity_side = order.liquidity_side().unwrap_or(LiquiditySide::Maker);
+        let fills = self.determine_limit_price_and_volume(client_order_id);
+        self.apply_fills(client_order_id, fills, liquidity_side, venue_position_id);
+    }
+
+    /// Applies the given `fills` to the order.
+    ///
+    /// # Panics
+    ///
+    /// If the `liquidity_side` is `NoLiquiditySide`, or any fill has an invalid precision.
+    pub fn apply_fills(
+        &mut self,
+        client_order_id: ClientOrderId,
+        fills: Vec<(Price, Quantity)>,
+        liquidity_side: LiquiditySide,
+        venue_position_id: Option<PositionId>,
+    ) {
+        assert_ne!(
+            liquidity_side,
+            LiquiditySide::NoLiquiditySide,
+            "Invalid `LiquiditySide`, was {liquidity_side}"
+        );
+        self.order_mut(client_order_id)
+            .set_liquidity_side(liquidity_side);
+        let order = self.order(client_order_id).clone();
+
+        if order.time_in_force() == TimeInForce::Fok {
+            // Check FOK requirement
+            let total_size_raw: u64 = fills.iter().map(|(_, qty)| qty.raw).sum();
+            if order.leaves_qty().raw > total_size_raw {
+                self.cancel_order(&order, true);
+                return; // Cannot fill full size - so kill/cancel
+            }
+        }
+
+        if fills.is_empty() {
+            error!("Cannot fill order: no fills from book when fills were expected (check sizes in data)");
+            return; // No fills
+        }
+
+        let venue_position_id = if self.oms_type == OmsType::Netting {
+            None // No position IDs generated by the venue
+        } else {
+            venue_position_id
+        };
+
+        debug!(
+            "Applying fills to {client_order_id}, venue_position_id={venue_position_id:?}, fills={fills:?}"
+        );
+
+        let price_precision = self.instrument.price_precision();
+        let size_precision = self.instrument.size_precision();
+        let mut initial_market_to_limit_fill = false;
+        let mut last_fill_px = None;
+
+        for (fill_px, mut fill_qty) in fills {
+            assert_eq!(
+                fill_px.precision,
+                price_precision,
+                "Invalid price precision for fill {} when instrument price precision is {price_precision}. \
+                Check that the data price precision matches the {} instrument",
+                fill_px.precision,
+                self.instrument.id(),
+            );
+            assert_eq!(
+                fill_qty.precision,
+                size_precision,
+                "Invalid size precision for fill {} when instrument size precision is {size_precision}. \
+                Check that the data size precision matches the {} instrument",
+                fill_qty.precision,
+                self.instrument.id(),
+            );
+
+            let order = self.order(client_order_id).clone();
+            if order.is_closed() {
+                return; // Fill was rejected
+            }
+            if order.filled_qty().is_zero() && order.order_type() == OrderType::MarketToLimit {
+                self.generate_order_updated(&order, order.quantity(), Some(fill_px), None);
+                initial_market_to_limit_fill = true;
+            }
+
+            // Check reduce only order
+            if self.config.use_reduce_only && order.is_reduce_only() {
+                let position_qty = self.position_qty(&order).unwrap_or(0).unsigned_abs();
+                if fill_qty.raw > position_qty {
+                    if position_qty == 0 {
+                        return; // Done
+                    }
+
+                    // Adjust fill to honor reduce only execution (fill remaining position size only)
+                    fill_qty = Quantity::from_raw(position_qty, fill_qty.precision)
+                        .expect("Invalid reduce only fill quantity");
+                    self.generate_order_updated(&order, fill_qty, None, None);
+                }
+            }
+
+            if fill_qty.is_zero() {
+                return; // Done
+            }
+
+            self.fill_order(
+                client_order_id,
+                fill_px,
+                fill_qty,
+                liquidity_side,
+                venue_position_id,
+            );
+
+            if order.order_type() == OrderType::MarketToLimit && initial_market_to_limit_fill {
+                return; // Filled initial level
+            }
+
+            last_fill_px = Some(fill_px);
+        }
+
+        let order = self.order(client_order_id).clone();
+        if order.time_in_force() == TimeInForce::Ioc && order.is_open() {
+            // IOC order has filled all available size
+            self.cancel_order(&order, true);
+            return;
+        }
+
+        if order.is_open()
+            && self.book_type == BookType::L1_MBP
+            && matches!(
+                order.order_type(),
+                OrderType::Market | OrderType::MarketIfTouched | OrderType::StopMarket
+            )
+        {
+            // Exhausted simulated book volume (continue aggressive filling into next level)
+            // This is a very basic implementation of slipping by a single tick, in the future
+            // we will implement more detailed fill modeling.
+            let Some(last_fill_px) = last_fill_px else {
+                return;
+            };
+            let price_increment = self.instrument.price_increment();
+            let fill_px = match order.order_side() {
+                OrderSide::Buy => last_fill_px + price_increment,
+                OrderSide::Sell => last_fill_px - price_increment,
+                side => panic!("Invalid `OrderSide`, was {side}"),
+            };
+            self.fill_order(
+                client_order_id,
+                fill_px,
+                order.leaves_qty(),
+                liquidity_side,
+                venue_position_id,
+            );
+        }
+    }
+
+    /// Fills the order with the given `last_px` and `last_qty`.
+    ///
+    /// If the commission cannot be calculated (such as when the `liquidity_side` is
+    /// `NoLiquiditySide`) the fill is skipped, and the order is rejected with the error as
+    /// the reason (or canceled, if already partially filled).
+    pub fn fill_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        last_px: Price,
+        last_qty: Quantity,
+        liquidity_side: LiquiditySide,
+        venue_position_id: Option<PositionId>,
+    ) {
+        self.order_mut(client_order_id)
+            .set_liquidity_side(liquidity_side);
+
+        // Calculate commission (not using quote for inverse)
+        let commission = match self.fee_model.get_commission(
+            self.instrument.as_ref(),
+            last_qty,
+            last_px,
+            liquidity_side,
+            Some(false),
+        ) {
+            Ok(commission) => commission,
+            Err(e) => {
+                let order = self.order(client_order_id).clone();
+                self.reject_fill(&order, &format!("Invalid commission: {e}"));
+                return;
+            }
+        };
+        let commission = match self.liquidation_fees.get(&client_order_id) {
+            Some(liquidation_fee) => {
+                commission + self.liquidation_fee(last_qty, last_px, *liquidation_fee, commission)
+            }
+            None => commission,
+        };
+        self.fee_model
+            .record_fill(self.instrument.as_ref(), last_qty, last_px, self.ts_now());
+
+        let order = self.order(client_order_id).clone();
+        self.generate_order_filled(
+            &order,
+            venue_position_id,
+            last_qty,
+            last_px,
+            self.instrument.quote_currency(),
+            commission,
+            liquidity_side,
+        );
+
+        // Update net position
+        let position_id = venue_position_id.or_else(|| self.lookup_position_id(&order));
+        if let Some(position_id) = position_id {
+            if self.oms_type != OmsType::Netting {
+                self.position_ids.insert(client_order_id, position_id);
+            }
+            let signed_qty = match order.order_side() {
+                OrderSide::Buy => last_qty.raw as i64,
+                _ => -(last_qty.raw as i64),
+            };
+            *self.net_positions.entry(position_id).or_insert(0) += signed_qty;
+        }
+
+        let order = self.order(client_order_id).clone();
+        if order.is_passive() && order.is_closed() {
+            // Remove order from market
+            self.delete_from_core(&order);
+        }
+
+        if !self.config.support_contingent_orders {
+            return;
+        }
+
+        // Check contingent orders
+        match order.contingency_type() {
+            Some(ContingencyType::Oto) => {
+                for child_order_id in order.linked_order_ids().unwrap_or_default() {
+                    let Some(child_order) = self.orders.get(&child_order_id).cloned() else {
+                        warn!("OTO child order {child_order_id} not found");
+                        continue;
+                    };
+                    if child_order.is_closed() || child_order.is_active_local() {
+                        continue; // Order is not on the exchange yet
+                    }
+                    if child_order.position_id().is_none() {
+                        if let Some(position_id) = position_id {
+                            self.position_ids.insert(child_order_id, position_id);
+                        }
+                    }
+                    if !child_order.is_open()
+                        || (child_order.status() == OrderStatus::PendingUpdate
+                            && child_order.previous_status() == Some(OrderStatus::Submitted))
+                    {
+                        let account_id = order
+                            .account_id()
+                            .or_else(|| self.account_ids.get(&order.trader_id()).copied())
+                            .expect("No account ID for order");
+                        self.process_order(&child_order, account_id);
+                    }
+                }
+            }
+            Some(ContingencyType::Oco) => {
+                for oco_order_id in order.linked_order_ids().unwrap_or_default() {
+                    let Some(oco_order) = self.orders.get(&oco_order_id).cloned() else {
+                        warn!("OCO order {oco_order_id} not found");
+                        continue;
+                    };
+                    if oco_order.is_closed() || oco_order.is_active_local() {
+                        continue; // Order is not on the exchange yet
+                    }
+                    self.cancel_order(&oco_order, true);
+                }
+            }
+            Some(ContingencyType::Ouo) => {
+                for ouo_order_id in order.linked_order_ids().unwrap_or_default() {
+                    let Some(ouo_order) = self.orders.get(&ouo_order_id).cloned() else {
+                        warn!("OUO order {ouo_order_id} not found");
+                        continue;
+                    };
+                    if ouo_order.is_active_local() {
+                        continue; // Order is not on the exchange yet
+                    }
+                    if order.is_closed() && ouo_order.is_open() {
+                        self.cancel_order(&ouo_order, true);
+                    } else if !order.leaves_qty().is_zero()
+                        && order.leaves_qty() != ouo_order.leaves_qty()
+                    {
+                        self.update_order(&ouo_order, Some(order.leaves_qty()), None, None, false);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let Some(position_id) = position_id else {
+            return; // Fill completed
+        };
+
+        // Check reduce only orders for position
+        if !self.config.use_reduce_only {
+            return;
+        }
+        let position_qty = self.net_positions.get(&position_id).copied().unwrap_or(0);
+        let reduce_only_orders: Vec<OrderAny> = self
+            .get_open_orders()
+            .into_iter()
+            .filter(|o| {
+                o.is_reduce_only()
+                    && o.is_open()
+                    && o.is_passive()
+                    && self.lookup_position_id(o) == Some(position_id)
+            })
+            .cloned()
+            .collect();
+
+        for order in reduce_only_orders {
+            if position_qty == 0 {
+                self.cancel_order(&order, true);
+            } else if order.leaves_qty().raw != position_qty.unsigned_abs() {
+                let quantity =
+                    Quantity::from_raw(position_qty.unsigned_abs(), order.quantity().precision)
+                        .expect("Invalid position quantity");
+                self.update_order(&order, Some(quantity), None, None, true);
+            }
+        }
+    }
+
+    // -- IDENTIFIER GENERATORS -------------------------------------------------------------------
+
+    fn get_position_id(&mut self, order: &OrderAny, generate: bool) -> Option<PositionId> {
+        let position_id = self.lookup_position_id(order);
+        if position_id.is_none() && generate {
+            // Generate a venue position ID
+            return self.generate_venue_position_id();
+        }
+        position_id
+    }
+
+    fn lookup_position_id(&self, order: &OrderAny) -> Option<PositionId> {
+        if self.oms_type == OmsType::Netting {
+            // Position ID will be `{instrument_id}-{strategy_id}`
+            return Some(netting_position_id(
+                order.instrument_id(),
+                order.strategy_id(),
+            ));
+        }
+
+        self.position_ids.get(&order.client_order_id()).copied()
+    }
+
+    fn generate_venue_position_id(&mut self) -> Option<PositionId> {
+        if !self.config.use_position_ids {
+            return None;
+        }
+
+        self.position_count += 1;
+        let value = if self.config.use_random_ids {
+            UUID4::new().to_string()
+        } else {
+            format!("{}-{}-{:03}", self.venue, self.raw_id, self.position_count)
+        };
+        Some(PositionId::new(&value).expect("Invalid position ID"))
+    }
+
+    fn generate_venue_order_id(&mut self) -> VenueOrderId {
+        self.order_count += 1;
+        let value = if self.config.use_random_ids {
+            UUID4::new().to_string()
+        } else {
+            format!("{}-{}-{:03}", self.venue, self.raw_id, self.order_count)
+        };
+        VenueOrderId::new(&value).expect("Invalid venue order ID")
+    }
+
+    fn generate_liquidation_order_id(&mut self) -> ClientOrderId {
+        self.liquidation_count += 1;
+        let value = format!(
+            "LIQ-{}-{}-{:03}",
+            self.venue, self.raw_id, self.liquidation_count
+        );
+        ClientOrderId::new(&value).expect("Invalid client order ID")
+    }
+
+    fn generate_trade_id(&mut self) -> TradeId {
+        self.execution_count += 1;
+        let value = if self.config.use_random_ids {
+            UUID4::new().to_string()
+        } else {
+            format!("{}-{}-{:03}", self.venue, self.raw_id, self.execution_count)
+        };
+        TradeId::new(&value).expect("Invalid trade ID")
+    }
+
+    // -- EVENT HANDLING --------------------------------------------------------------------------
+
+    fn accept_order(&mut self, client_order_id: ClientOrderId) {
+        let order = self.order(client_order_id).clone();
+        if order.is_closed() {
+            return; // Temporary guard to prevent invalid processing
+        }
+
+        // Check if order already accepted (being added back into the matching engine)
+        if order.status() != OrderStatus::Accepted {
+            self.generate_order_accepted(&order);
+        }
+
+        if !self.order_exists(&client_order_id) {
+            let passive = self
+                .order(client_order_id)
+                .to_passive()
+                .expect("Order must be passive to add to the matching core");
+            if let Err(e) = self.core.add_order(passive) {
+                error!("Cannot add order {client_order_id} to matching core: {e}");
+            }
+        }
+
+        if self.config.queue_position
+            && matches!(
+                order.order_type(),
+                OrderType::Limit | OrderType::MarketToLimit
+            )
+        {
+            self.queue_order(client_order_id);
+        }
+    }
+
+    fn expire_order(&mut self, order: &OrderAny) {
+        if self.config.support_contingent_orders && has_contingencies(order) {
+            self.cancel_contingent_orders(order);
+        }
+
+        self.generate_order_expired(order);
+    }
+
+    fn cancel_order(&mut self, order: &OrderAny, cancel_contingencies: bool) {
+        if order.is_active_local() {
+            error!(
+                "Cannot cancel an order with {} from the matching engine",
+                order.status()
+            );
+            return;
+        }
+
+        self.delete_from_core(order);
+        self.generate_order_canceled(order);
+
+        if self.config.support_contingent_orders && has_contingencies(order) && cancel_contingencies
+        {
+            self.cancel_contingent_orders(order);
+        }
+    }
+
+    /// Closes an `order` which cannot be filled, rejecting it with the given `reason` unless
+    /// already partially filled (when it is canceled).
+    fn reject_fill(&mut self, order: &OrderAny, reason: &str) {
+        error!("Cannot fill order {}: {reason}", order.client_order_id());
+        if !order.filled_qty().is_zero() {
+            self.cancel_order(order, true);
+            return;
+        }
+
+        self.delete_from_core(order);
+        self.generate_order_rejected(order, reason);
+
+        if self.config.support_contingent_orders && has_contingencies(order) {
+            self.cancel_contingent_orders(order);
+        }
+    }
+
+    fn cancel_contingent_orders(&mut self, order: &OrderAny) {
+        // Iterate all contingent orders and cancel if active
+        for linked_order_id in order.linked_order_ids().unwrap_or_default() {
+            let Some(contingent_order) = self.orders.get(&linked_order_id).cloned() else {
+                warn!("Contingent order {linked_order_id} not found");
+                continue;
+            };
+            if contingent_order.is_active_local() {
+                continue; // Order is not on the exchange yet
+            }
+            if !contingent_order.is_closed() {
+                self.cancel_order(&contingent_order, false);
+            }
+        }
+    }
+
+    fn generate_order_rejected(&mut self, order: &OrderAny, reason: &str) {
+        let ts_now = self.ts_now();
+        let account_id = self.account_id_for(order);
+        let event = OrderRejected::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            account_id,
+            Ustr::from(reason),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+        )
+        .expect("Invalid order rejected event");
+        self.send_event(OrderEvent::OrderRejected(event));
+    }
+
+    fn generate_order_accepted(&mut self, order: &OrderAny) {
+        let ts_now = self.ts_now();
+        let venue_order_id = order
+            .venue_order_id()
+            .unwrap_or_else(|| self.generate_venue_order_id());
+        let account_id = self.account_id_for(order);
+        let event = OrderAccepted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            venue_order_id,
+            account_id,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+        )
+        .expect("Invalid order accepted event");
+        self.send_event(OrderEvent::OrderAccepted(event));
+    }
+
+    fn generate_order_modify_rejected_for(&mut self, order: &OrderAny, reason: &str) {
+        let account_id = self.account_id_for(order);
+        self.generate_order_modify_rejected(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            order.venue_order_id(),
+            Some(account_id),
+            reason,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_order_modify_rejected(
+        &mut self,
+        trader_id: TraderId,
+        strategy_id: StrategyId,
+        instrument_id: InstrumentId,
+        client_order_id: ClientOrderId,
+        venue_order_id: Option<VenueOrderId>,
+        account_id: Option<AccountId>,
+        reason: &str,
+    ) {
+        let ts_now = self.ts_now();
+        let event = OrderModifyRejected::new(
+            trader_id,
+            strategy_id,
+            instrument_id,
+            client_order_id,
+            Ustr::from(reason),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            venue_order_id,
+            account_id,
+        )
+        .expect("Invalid order modify rejected event");
+        self.send_event(OrderEvent::OrderModifyRejected(event));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_order_cancel_rejected(
+        &mut self,
+        trader_id: TraderId,
+        strategy_id: StrategyId,
+        instrument_id: InstrumentId,
+        client_order_id: ClientOrderId,
+        venue_order_id: Option<VenueOrderId>,
+        account_id: Option<AccountId>,
+        reason: &str,
+    ) {
+        let ts_now = self.ts_now();
+        let event = OrderCancelRejected::new(
+            trader_id,
+            strategy_id,
+            instrument_id,
+            client_order_id,
+            Ustr::from(reason),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            venue_order_id,
+            account_id,
+        )
+        .expect("Invalid order cancel rejected event");
+        self.send_event(OrderEvent::OrderCancelRejected(event));
+    }
+
+    fn generate_order_updated(
+        &mut self,
+        order: &OrderAny,
+        quantity: Quantity,
+        price: Option<Price>,
+        trigger_price: Option<Price>,
+    ) {
+        let ts_now = self.ts_now();
+        let venue_order_id = order
+            .venue_order_id()
+            .unwrap_or_else(|| self.generate_venue_order_id());
+        let account_id = self.account_id_for(order);
+        let event = OrderUpdated::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            quantity,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            Some(venue_order_id),
+            Some(account_id),
+            price,
+            trigger_price,
+        )
+        .expect("Invalid order updated event");
+        self.send_event(OrderEvent::OrderUpdated(event));
+    }
+
+    fn generate_order_canceled(&mut self, order: &OrderAny) {
+        let ts_now = self.ts_now();
+        let venue_order_id = order
+            .venue_order_id()
+            .unwrap_or_else(|| self.generate_venue_order_id());
+        let account_id = self.account_id_for(order);
+        let event = OrderCanceled::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            Some(venue_order_id),
+            Some(account_id),
+        )
+        .expect("Invalid order canceled event");
+        self.send_event(OrderEvent::OrderCanceled(event));
+    }
+
+    fn generate_order_triggered(&mut self, order: &OrderAny) {
+        let ts_now = self.ts_now();
+        let account_id = self.account_id_for(order);
+        let event = OrderTriggered::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(account_id),
+        )
+        .expect("Invalid order triggered event");
+        self.send_event(OrderEvent::OrderTriggered(event));
+    }
+
+    fn generate_order_expired(&mut self, order: &OrderAny) {
+        let ts_now = self.ts_now();
+        let account_id = self.account_id_for(order);
+        let event = OrderExpired::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            order.venue_order_id(),
+            Some(account_id),
+        )
+        .expect("Invalid order expired event");
+        self.send_event(OrderEvent::OrderExpired(event));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_order_filled(
+        &mut self,
+        order: &OrderAny,
+        venue_position_id: Option<PositionId>,
+        last_qty: Quantity,
+        last_px: Price,
+        quote_currency: Currency,
+        commission: Money,
+        liquidity_side: LiquiditySide,
+    ) {
+        let ts_now = self.ts_now();
+        let venue_order_id = order
+            .venue_order_id()
+            .unwrap_or_else(|| self.generate_venue_order_id());
+        let account_id = self.account_id_for(order);
+        let trade_id = self.generate_trade_id();
+        let event = OrderFilled::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            venue_order_id,
+            account_id,
+            trade_id,
+            order.order_side(),
+            order.order_type(),
+            last_qty,
+            last_px,
+            quote_currency,
+            liquidity_side,
+            UUID4::new(),
+            ts_now,
+            ts_now,
+            false,
+            venue_position_id,
+            Some(commission),
+        )
+        .expect("Invalid order filled event");
+
+        if last_qty < order.leaves_qty() {
+            self.send_event(OrderEvent::OrderPartiallyFilled(event));
+        } else {
+            self.send_event(OrderEvent::OrderFilled(event));
+        }
+    }
+
+    /// Applies the `event` to the engines own order state, then sends the event
+    /// to the execution engine.
+    fn send_event(&mut self, event: OrderEvent) {
+        if let Some(order) = self.orders.get_mut(&event.client_order_id()) {
+            if let Err(e) = order.apply(event.clone()) {
+                error!("Error applying event to {}: {e}", event.client_order_id());
+            }
+            if order.is_closed() {
+                self.fill_model.remove_order(&order.client_order_id());
+            }
+        }
+        self.msgbus.send(EXEC_ENGINE_PROCESS, &event as &dyn Any);
+    }
+
+    // -- HELPERS ---------------------------------------------------------------------------------
+
+    /// Returns the timestamp for events generated by the engine, being the time of the
+    /// in-flight command or iteration being processed (otherwise the current clock time).
+    fn ts_now(&self) -> UnixNanos {
+        self.ts_processing
+            .unwrap_or_else(|| self.clock.get_time_ns())
+    }
+
+    fn order(&self, client_order_id: ClientOrderId) -> &OrderAny {
+        self.orders
+            .get(&client_order_id)
+            .unwrap_or_else(|| panic!("Order {client_order_id} not found"))
+    }
+
+    fn order_mut(&mut self, client_order_id: ClientOrderId) -> &mut OrderAny {
+        self.orders
+            .get_mut(&client_order_id)
+            .unwrap_or_else(|| panic!("Order {client_order_id} not found"))
+    }
+
+    fn core_order_ids(&self) -> Vec<ClientOrderId> {
+        self.core
+            .get_orders_bid()
+            .iter()
+            .chain(self.core.get_orders_ask())
+            .map(GetClientOrderId::get_client_order_id)
+            .collect()
+    }
+
+    fn delete_from_core(&mut self, order: &OrderAny) {
+        if let Some(passive) = order.to_passive() {
+            // The order may not be in the core (e.g. already filled or never accepted)
+            let _ = self.core.delete_order(&passive);
+        }
+    }
+
+    fn account_id_for(&self, order: &OrderAny) -> AccountId {
+        order
+            .account_id()
+            .or_else(|| self.account_ids.get(&order.trader_id()).copied())
+            .unwrap_or_else(|| panic!("No account ID for {}", order.trader_id()))
+    }
+
+    /// Returns the liquidation fee for a fill, in the currency of its `commission`.
+    fn liquidation_fee(
+        &self,
+        last_qty: Quantity,
+        last_px: Price,
+        liquidation_fee: Decimal,
+        commission: Money,
+    ) -> Money {
+        let notional = self
+            .instrument
+            .calculate_notional_value(last_qty, last_px, Some(false));
+        if notional.currency != commission.currency {
+            warn!(
+                "Cannot charge liquidation fee in {} on commission in {}",
+                notional.currency.code, commission.currency.code,
+            );
+            return Money::new(0.0, commission.currency).expect("Invalid money");
+        }
+        let rate = liquidation_fee.to_f64().unwrap_or_default();
+        Money::new(notional.as_f64() * rate, commission.currency).expect("Invalid liquidation fee")
+    }
+
+    fn position_qty(&self, order: &OrderAny) -> Option<i64> {
+        let position_id = self.lookup_position_id(order)?;
+        self.net_positions.get(&position_id).copied()
+    }
+
+    fn queue_order(&mut self, client_order_id: ClientOrderId) {
+        let order = self.order(client_order_id);
+        let side = order.order_side();
+        let price = order
+            .price()
+            .expect("Order must have a price to join the queue");
+        let leaves_qty = order.leaves_qty();
+        let size_ahead = self.level_size(side, price);
+        self.fill_model
+            .add_order(client_order_id, side, price, size_ahead, leaves_qty);
+    }
+
+    /// Returns the size resting at the given `price` on the book side for `side` orders.
+    fn level_size(&self, side: OrderSide, price: Price) -> Quantity {
+        let size_raw = match side {
+            OrderSide::Buy => level_size_raw(self.book.bids(), price),
+            _ => level_size_raw(self.book.asks(), price),
+        };
+        Quantity::from_raw(size_raw, self.instrument.size_precision()).expect("Invalid level size")
+    }
+
+    fn has_market_for(&self, side: OrderSide) -> bool {
+        match side {
+            OrderSide::Buy => self.core.ask.is_some(),
+            OrderSide::Sell => self.core.bid.is_some(),
+            _ => false,
+        }
+    }
+
+    fn is_limit_matched(&self, side: OrderSide, price: Price) -> bool {
+        self.core
+            .is_limit_price_matched(order_side_to_fixed(side), price)
+    }
+
+    /// Returns whether the market has moved strictly through the given limit `price`.
+    fn is_limit_crossed(&self, side: OrderSide, price: Price) -> bool {
+        match side {
+            OrderSide::Buy => self.core.ask.map_or(false, |ask| ask < price),
+            _ => self.core.bid.map_or(false, |bid| bid > price),
+        }
+    }
+
+    fn is_stop_triggered(&self, side: OrderSide, trigger_price: Price) -> bool {
+        self.core
+            .is_stop_triggered(order_side_to_fixed(side), trigger_price)
+    }
+
+    fn is_touch_triggered(&self, side: OrderSide, trigger_price: Price) -> bool {
+        self.core
+            .is_touch_triggered(order_side_to_fixed(side), trigger_price)
+    }
+
+    /// Returns whether the `trigger_price` would trigger the order, based on its type.
+    fn is_triggered(&self, order: &OrderAny, trigger_price: Price) -> bool {
+        match order.order_type() {
+            OrderType::MarketIfTouched | OrderType::LimitIfTouched => {
+                self.is_touch_triggered(order.order_side(), trigger_price)
+            }
+            _ => self.is_stop_triggered(order.order_side(), trigger_price),
+        }
+    }
+
+    fn simulate_fills(&self, order: &OrderAny, is_aggressive: bool) -> Vec<(Price, Quantity)> {
+        let price_precision = self.instrument.price_precision();
+        let price = match (is_aggressive, order.order_side()) {
+            (false, _) => order.price().expect("Order has no limit `price`"),
+            (true, OrderSide::Buy) => Price::max(price_precision),
+            (true, _) => Price::min(price_precision),
+        };
+        let book_order = BookOrder::new(order.order_side(), price, order.leaves_qty(), 0);
+
+        self.book.simulate_fills(&book_order)
+    }
+
+    fn set_targets(&mut self) {
+        self.has_targets = true;
+        self.target_bid = self.core.bid;
+        self.target_ask = self.core.ask;
+        self.target_last = self.core.last;
+    }
+
+    fn reset_targets(&mut self) {
+        self.has_targets = false;
+        self.target_bid = None;
+        self.target_ask = None;
+        self.target_last = None;
+    }
+
+    fn move_market(&mut self, side: OrderSide, price: Price) {
+        match side {
+            OrderSide::Buy => self.core.ask = Some(price),
+            OrderSide::Sell => self.core.bid = Some(price),
+            _ => panic!("Invalid `OrderSide`, was {side}"),
+        }
+        self.core.last = Some(price);
+    }
+}
+
+fn determine_order_liquidity(
+    initial: bool,
+    side: OrderSide,
+    price: Price,
+    trigger_price: Price,
+) -> LiquiditySide {
+    if initial {
+        return LiquiditySide::Taker;
+    }
+
+    match side {
+        OrderSide::Buy if trigger_price > price => LiquiditySide::Maker,
+        OrderSide::Sell if trigger_price < price => LiquiditySide::Maker,
+        _ => LiquiditySide::Taker,
+    }
+}
+
+fn level_size_raw<'a>(mut levels: impl Iterator<Item = &'a Level>, price: Price) -> u64 {
+    levels
+        .find(|level| level.price.value == price)
+        .map_or(0, Level::size_raw)
+}
+
+fn netting_position_id(instrument_id: InstrumentId, strategy_id: StrategyId) -> PositionId {
+    PositionId::new(&format!("{instrument_id}-{strategy_id}")).expect("Invalid position ID")
+}
+
+fn has_contingencies(order: &OrderAny) -> bool {
+    !matches!(
+        order.contingency_type(),
+        None | Some(ContingencyType::NoContingency)
+    )
+}
+
+fn fmt_price(price: Option<Price>) -> String {
+    price.map_or("None".to_string(), |price| format!("{price}"))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use nautilus_accounting::fee::{FixedFeeModel, MakerTakerFeeModel};
+    use nautilus_common::handlers::{MessageHandler, SafeAnyMessageCallback};
+    use nautilus_model::{
+        enums::{TrailingOffsetType, TriggerType},
+        events::order::submitted::OrderSubmitted,
+        identifiers::stubs::{account_id, strategy_id_ema_cross, trader_id},
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+        orders::{
+            limit::LimitOrder, limit_if_touched::LimitIfTouchedOrder,
+            market_if_touched::MarketIfTouchedOrder, market_to_limit::MarketToLimitOrder,
+            stop_limit::StopLimitOrder, stubs::TestOrderStubs,
+            trailing_stop_market::TrailingStopMarketOrder,
+        },
+    };
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::{engine::TimeEventAccumulator, models::latency::FixedLatencyModel};
+
+    type EventStore = Arc<Mutex<Vec<OrderEvent>>>;
+
+    fn get_engine(
+        instrument: CurrencyPair,
+        config: Option<OrderMatchingEngineConfig>,
+    ) -> (OrderMatchingEngine, EventStore) {
+        get_engine_with_models(instrument, config, Box::new(MakerTakerFeeModel), None)
+    }
+
+    fn get_engine_with_latency(
+        instrument: CurrencyPair,
+        config: Option<OrderMatchingEngineConfig>,
+        latency_model: Option<Box<dyn LatencyModel>>,
+    ) -> (OrderMatchingEngine, EventStore) {
+        get_engine_with_models(
+            instrument,
+            config,
+            Box::new(MakerTakerFeeModel),
+            latency_model,
+        )
+    }
+
+    fn get_engine_with_models(
+        instrument: CurrencyPair,
+        config: Option<OrderMatchingEngineConfig>,
+        fee_model: Box<dyn FeeModel>,
+        latency_model: Option<Box<dyn LatencyModel>>,
+    ) -> (OrderMatchingEngine, EventStore) {
+        let events: EventStore = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let callback = SafeAnyMessageCallback {
+            callback: Arc::new(move |message: &dyn Any| {
+                if let Some(event) = message.downcast_ref::<OrderEvent>() {
+                    events_clone.lock().unwrap().push(event.clone());
+                }
+            }),
+        };
+        let mut msgbus = MessageBus::new(trader_id(), UUID4::new(), None, None).unwrap();
+        msgbus.register(
+            EXEC_ENGINE_PROCESS,
+            MessageHandler::with_any_callback(Ustr::from("ExecEngine"), callback),
+        );
+        let msgbus: &'static MessageBus = Box::leak(Box::new(msgbus));
+        let clock: &'static AtomicTime = Box::leak(Box::new(AtomicTime::new(false, 0)));
+
+        let engine = OrderMatchingEngine::new(
+            Box::new(instrument),
+            1,
+            BookType::L1_MBP,
+            OmsType::Netting,
+            AccountType::Margin,
+            clock,
+            msgbus,
+            config.unwrap_or_default(),
+            fee_model,
+            latency_model,
+        );
+        (engine, events)
+    }
+
+    fn submitted(order: impl Into<OrderAny>) -> OrderAny {
+        let mut order = order.into();
+        let event = OrderSubmitted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            account_id(),
+            UUID4::new(),
+            0,
+            0,
+        )
+        .unwrap();
+        order.apply(OrderEvent::OrderSubmitted(event)).unwrap();
+        order
+    }
+
+    fn quote(instrument_id: InstrumentId, bid: &str, ask: &str, ts: UnixNanos) -> QuoteTick {
+        QuoteTick::new(
+            instrument_id,
+            Price::from(bid),
+            Price::from(ask),
+            Quantity::from(1_000_000),
+            Quantity::from(1_000_000),
+            ts,
+            ts,
+        )
+        .unwrap()
+    }
+
+    fn event_names(events: &EventStore) -> Vec<String> {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn stop_limit_order(
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        price: &str,
+        trigger_price: &str,
+    ) -> StopLimitOrder {
+        StopLimitOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from("O-1"),
+            order_side,
+            Quantity::from(100_000),
+            Price::from(price),
+            Price::from(trigger_price),
+            TriggerType::BidAsk,
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn limit_if_touched_order(
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        price: &str,
+        trigger_price: &str,
+    ) -> LimitIfTouchedOrder {
+        LimitIfTouchedOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from("O-1"),
+            order_side,
+            Quantity::from(100_000),
+            Price::from(price),
+            Price::from(trigger_price),
+            TriggerType::BidAsk,
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn market_if_touched_order(
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        trigger_price: &str,
+    ) -> MarketIfTouchedOrder {
+        MarketIfTouchedOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from("O-1"),
+            order_side,
+            Quantity::from(100_000),
+            Price::from(trigger_price),
+            TriggerType::BidAsk,
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn trailing_stop_market_order(
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        trigger_price: &str,
+        trailing_offset: &str,
+    ) -> TrailingStopMarketOrder {
+        TrailingStopMarketOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from("O-1"),
+            order_side,
+            Quantity::from(100_000),
+            Price::from(trigger_price),
+            TriggerType::BidAsk,
+            Price::from(trailing_offset),
+            TrailingOffsetType::Price,
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn market_to_limit_order(
+        instrument_id: InstrumentId,
+        order_side: OrderSide,
+        quantity: Quantity,
+    ) -> MarketToLimitOrder {
+        MarketToLimitOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from("O-1"),
+            order_side,
+            quantity,
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn contingent_limit_order(
+        instrument_id: InstrumentId,
+        client_order_id: &str,
+        order_side: OrderSide,
+        price: &str,
+        contingency_type: ContingencyType,
+        linked_order_ids: Option<Vec<&str>>,
+        parent_order_id: Option<&str>,
+    ) -> LimitOrder {
+        LimitOrder::new(
+            trader_id(),
+            strategy_id_ema_cross(),
+            instrument_id,
+            ClientOrderId::from(client_order_id),
+            order_side,
+            Quantity::from(100_000),
+            Price::from(price),
+            TimeInForce::Gtc,
+            None,
+            false,
+            false,
+            false,
+            None,
+            None,
+            None,
+            Some(contingency_type),
+            None,
+            linked_order_ids.map(|ids| ids.into_iter().map(ClientOrderId::from).collect()),
+            parent_order_id.map(ClientOrderId::from),
+            None,
+            None,
+            None,
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_market_order_filled_with_fee_model_commission(audusd_sim: CurrencyPair) {
+        let fee_model = FixedFeeModel::new(
+            dec!(0.0001),
+            Currency::USD(),
+            Some(Money::from("5.00 USD")),
+            None,
+        )
+        .unwrap();
+        let (mut engine, events) =
+            get_engine_with_models(audusd_sim, None, Box::new(fee_model), None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        let events = events.lock().unwrap();
+        match &events[0] {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.commission, Some(Money::from("10.00 USD")));
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+    }
+
+    /// A fee model which fails for fills above the `max_px` (or for all fills, if `None`).
+    #[derive(Debug)]
+    struct InvalidFeeModel {
+        max_px: Option<Price>,
+    }
+
+    impl FeeModel for InvalidFeeModel {
+        fn get_commission(
+            &self,
+            instrument: &dyn Instrument,
+            last_qty: Quantity,
+            last_px: Price,
+            liquidity_side: LiquiditySide,
+            use_quote_for_inverse: Option<bool>,
+        ) -> anyhow::Result<Money> {
+            match self.max_px {
+                Some(max_px) if last_px <= max_px => MakerTakerFeeModel.get_commission(
+                    instrument,
+                    last_qty,
+                    last_px,
+                    liquidity_side,
+                    use_quote_for_inverse,
+                ),
+                _ => anyhow::bail!("Invalid fee"),
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_market_order_rejected_with_invalid_commission(audusd_sim: CurrencyPair) {
+        let fee_model = InvalidFeeModel { max_px: None };
+        let (mut engine, events) =
+            get_engine_with_models(audusd_sim, None, Box::new(fee_model), None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        let events = events.lock().unwrap();
+        match &events[..] {
+            [OrderEvent::OrderRejected(rejected)] => {
+                assert_eq!(
+                    rejected.reason,
+                    Ustr::from("Invalid commission: Invalid fee")
+                );
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_resting_limit_order_rejected_with_invalid_commission(audusd_sim: CurrencyPair) {
+        let fee_model = InvalidFeeModel { max_px: None };
+        let (mut engine, events) =
+            get_engine_with_models(audusd_sim, None, Box::new(fee_model), None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        engine.process_order(&submitted(order), account_id());
+
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79980", "0.79990", 2));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79970", "0.79980", 3));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderRejected"]);
+    }
+
+    #[rstest]
+    fn test_partially_filled_order_canceled_with_invalid_commission(audusd_sim: CurrencyPair) {
+        let fee_model = InvalidFeeModel {
+            max_px: Some(Price::from("0.80010")),
+        };
+        let (mut engine, events) =
+            get_engine_with_models(audusd_sim, None, Box::new(fee_model), None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        // Fills the 1,000,000 at the ask, then slips a tick for the remainder
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(1_500_000),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderPartiallyFilled", "OrderCanceled"]
+        );
+    }
+
+    #[rstest]
+    fn test_market_order_rejected_when_no_market(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderRejected"]);
+    }
+
+    #[rstest]
+    fn test_market_order_filled_at_ask(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.80010"));
+                assert_eq!(fill.last_qty, Quantity::from(100_000));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Taker);
+                assert_eq!(fill.trade_id, TradeId::new("SIM-1-001").unwrap());
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+    }
+
+    #[rstest]
+    fn test_limit_order_accepted_then_filled_as_maker(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+        assert_eq!(engine.get_open_bid_orders().len(), 1);
+
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79980", "0.79990", 2));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderFilled"]);
+        assert!(engine.get_open_orders().is_empty());
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Filled);
+        assert_eq!(order.liquidity_side(), Some(LiquiditySide::Maker));
+    }
+
+    #[rstest]
+    fn test_post_only_limit_order_rejected_when_marketable(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let mut order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80020"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        order.is_post_only = true;
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderRejected"]);
+    }
+
+    #[rstest]
+    fn test_ioc_limit_order_canceled_when_not_marketable(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            Some(TimeInForce::Ioc),
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderCanceled"]);
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_stop_market_order_rejected_when_in_market(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::stop_market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80000"),
+            Quantity::from(100_000),
+            None,
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderRejected"]);
+    }
+
+    #[rstest]
+    fn test_stop_market_order_triggers_and_fills(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::stop_market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80100"),
+            Quantity::from(100_000),
+            Some(TriggerType::Default),
+            None,
+            None,
+        );
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        match &events[1] {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.80100"));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Taker);
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+    }
+
+    #[rstest]
+    fn test_modify_limit_order(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+        let strategy_id = order.strategy_id;
+        engine.process_order(&submitted(order), account_id());
+
+        let command = ModifyOrder::new(
+            trader_id(),
+            None,
+            strategy_id,
+            audusd_sim.id,
+            client_order_id,
+            None,
+            Some(Quantity::from(200_000)),
+            Some(Price::from("0.79980")),
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        engine.process_modify(&command, account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderUpdated"]);
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.quantity(), Quantity::from(200_000));
+        assert_eq!(order.price(), Some(Price::from("0.79980")));
+    }
+
+    #[rstest]
+    fn test_cancel_unknown_order_rejected(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        let command = CancelOrder::new(
+            trader_id(),
+            None,
+            StrategyId::from("S-001"),
+            audusd_sim.id,
+            ClientOrderId::from("O-123456"),
+            None,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+
+        engine.process_cancel(&command, account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderCancelRejected"]);
+    }
+
+    #[rstest]
+    fn test_cancel_all_orders(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        for (i, side) in [OrderSide::Buy, OrderSide::Sell].into_iter().enumerate() {
+            let price = if side == OrderSide::Buy {
+                "0.79000"
+            } else {
+                "0.81000"
+            };
+            let order = TestOrderStubs::limit_order(
+                audusd_sim.id,
+                side,
+                Price::from(price),
+                Quantity::from(100_000),
+                Some(ClientOrderId::from(format!("O-{i}").as_str())),
+                None,
+            );
+            engine.process_order(&submitted(order), account_id());
+        }
+
+        let command = CancelAllOrders::new(
+            trader_id(),
+            None,
+            StrategyId::from("S-001"),
+            audusd_sim.id,
+            OrderSide::NoOrderSide,
+            UUID4::new(),
+            0,
+        )
+        .unwrap();
+        engine.process_cancel_all(&command, account_id());
+
+        assert_eq!(
+            event_names(&events),
+            vec![
+                "OrderAccepted",
+                "OrderAccepted",
+                "OrderCanceled",
+                "OrderCanceled"
+            ]
+        );
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_gtd_limit_order_expires(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let mut order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79000"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        order.time_in_force = TimeInForce::Gtd;
+        order.expire_time = Some(10);
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 10));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderExpired"]);
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_limit_order_fills_only_after_queue_ahead_traded(audusd_sim: CurrencyPair) {
+        let config = OrderMatchingEngineConfig {
+            queue_position: true,
+            ..Default::default()
+        };
+        let (mut engine, events) = get_engine(audusd_sim, Some(config));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80000"),
+            Quantity::from(200_000),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+        engine.process_order(&submitted(order), account_id());
+
+        let trade = |size: i64, ts: UnixNanos| {
+            TradeTick::new(
+                audusd_sim.id,
+                Price::from("0.80000"),
+                Quantity::from(size),
+                AggressorSide::Seller,
+                TradeId::from(format!("T-{ts}").as_str()),
+                ts,
+                ts,
+            )
+        };
+
+        // Queue ahead is the 1,000,000 resting at the bid when the order joined
+        engine.process_trade_tick(&trade(600_000, 2));
+        assert_eq!(event_names(&events), vec!["OrderAccepted"]);
+
+        engine.process_trade_tick(&trade(500_000, 3));
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderPartiallyFilled"]
+        );
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.filled_qty(), Quantity::from(100_000));
+        assert_eq!(order.liquidity_side(), Some(LiquiditySide::Maker));
+
+        engine.process_trade_tick(&trade(500_000, 4));
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderPartiallyFilled", "OrderFilled"]
+        );
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_level_update_after_trade_does_not_reduce_queue_ahead_again(audusd_sim: CurrencyPair) {
+        let config = OrderMatchingEngineConfig {
+            queue_position: true,
+            ..Default::default()
+        };
+        let (mut engine, events) = get_engine(audusd_sim, Some(config));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80000"),
+            Quantity::from(200_000),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+        engine.process_order(&submitted(order), account_id());
+
+        let trade = |size: i64, ts: UnixNanos| {
+            TradeTick::new(
+                audusd_sim.id,
+                Price::from("0.80000"),
+                Quantity::from(size),
+                AggressorSide::Seller,
+                TradeId::from(format!("T-{ts}").as_str()),
+                ts,
+                ts,
+            )
+        };
+        engine.process_trade_tick(&trade(600_000, 2));
+        // The venue then reduces the bid level by the traded size
+        let quote = QuoteTick::new(
+            audusd_sim.id,
+            Price::from("0.80000"),
+            Price::from("0.80010"),
+            Quantity::from(400_000),
+            Quantity::from(1_000_000),
+            3,
+            3,
+        )
+        .unwrap();
+        engine.process_quote_tick(&quote);
+
+        // 400,000 is still ahead of the order
+        engine.process_trade_tick(&trade(500_000, 4));
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderPartiallyFilled"]
+        );
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.filled_qty(), Quantity::from(100_000));
+    }
+
+    #[rstest]
+    #[case(100_000, vec!["OrderAccepted", "OrderUpdated", "OrderFilled"])]
+    #[case(300_000, vec!["OrderAccepted", "OrderUpdated"])]
+    fn test_modify_quantity_queue_priority(
+        audusd_sim: CurrencyPair,
+        #[case] quantity: i64,
+        #[case] expected: Vec<&str>,
+    ) {
+        let config = OrderMatchingEngineConfig {
+            queue_position: true,
+            ..Default::default()
+        };
+        let (mut engine, events) = get_engine(audusd_sim, Some(config));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.80000"),
+            Quantity::from(200_000),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+        let strategy_id = order.strategy_id;
+        engine.process_order(&submitted(order), account_id());
+
+        let trade = |size: i64, ts: UnixNanos| {
+            TradeTick::new(
+                audusd_sim.id,
+                Price::from("0.80000"),
+                Quantity::from(size),
+                AggressorSide::Seller,
+                TradeId::from(format!("T-{ts}").as_str()),
+                ts,
+                ts,
+            )
+        };
+        engine.process_trade_tick(&trade(600_000, 2));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 3));
+
+        let command = ModifyOrder::new(
+            trader_id(),
+            None,
+            strategy_id,
+            audusd_sim.id,
+            client_order_id,
+            None,
+            Some(Quantity::from(quantity)),
+            None,
+            None,
+            UUID4::new(),
+            4,
+        )
+        .unwrap();
+        engine.process_modify(&command, account_id());
+
+        // Reducing the quantity retains the queue position (400,000 ahead), whereas
+        // increasing it joins the back of the queue (1,000,000 ahead)
+        engine.process_trade_tick(&trade(500_000, 5));
+        assert_eq!(event_names(&events), expected);
+    }
+
+    #[rstest]
+    fn test_commands_held_in_flight_until_latency_elapsed(audusd_sim: CurrencyPair) {
+        let latency_model = FixedLatencyModel::new(0, 100, 50, 10);
+        let (mut engine, events) =
+            get_engine_with_latency(audusd_sim, None, Some(Box::new(latency_model)));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let command = SubmitOrder::new(
+            trader_id(),
+            None,
+            order.strategy_id,
+            submitted(order),
+            None,
+            UUID4::new(),
+            1,
+        )
+        .unwrap();
+
+        engine.process_command(TradingCommand::SubmitOrder(command), account_id());
+        assert_eq!(engine.next_inflight_ts(), Some(101));
+
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 100));
+        assert!(event_names(&events).is_empty());
+
+        engine.process_inflight_commands(101);
+        assert_eq!(event_names(&events), vec!["OrderAccepted"]);
+        assert_eq!(engine.next_inflight_ts(), None);
+    }
+
+    #[rstest]
+    fn test_inflight_commands_released_by_clock_at_arrival_time(audusd_sim: CurrencyPair) {
+        let latency_model = FixedLatencyModel::new(0, 100, 50, 10);
+        let (engine, events) =
+            get_engine_with_latency(audusd_sim, None, Some(Box::new(latency_model)));
+        let engine = Rc::new(RefCell::new(engine));
+        let clock = Rc::new(RefCell::new(TestClock::new()));
+        OrderMatchingEngine::register_inflight_timer(&engine, clock.clone());
+        engine
+            .borrow_mut()
+            .process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let command = SubmitOrder::new(
+            trader_id(),
+            None,
+            order.strategy_id,
+            submitted(order),
+            None,
+            UUID4::new(),
+            1,
+        )
+        .unwrap();
+        engine
+            .borrow_mut()
+            .process_command(TradingCommand::SubmitOrder(command), account_id());
+
+        // No market data, only the clock is advanced past the arrival time
+        let mut accumulator = TimeEventAccumulator::new();
+        accumulator.advance_clock(&mut clock.borrow_mut(), 150, true);
+        for handler in accumulator.drain_local() {
+            handler.handle();
+        }
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], OrderEvent::OrderAccepted(_)));
+        assert_eq!(events[0].ts_event(), 101);
+        assert_eq!(engine.borrow().next_inflight_ts(), None);
+    }
+
+    #[rstest]
+    fn test_inflight_commands_stamped_with_arrival_time_on_iterate(audusd_sim: CurrencyPair) {
+        let latency_model = FixedLatencyModel::new(0, 100, 50, 10);
+        let (mut engine, events) =
+            get_engine_with_latency(audusd_sim, None, Some(Box::new(latency_model)));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let command = SubmitOrder::new(
+            trader_id(),
+            None,
+            order.strategy_id,
+            submitted(order),
+            None,
+            UUID4::new(),
+            1,
+        )
+        .unwrap();
+
+        engine.process_command(TradingCommand::SubmitOrder(command), account_id());
+        engine.clock.set_time(500);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 500));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].ts_event(), 101);
+        assert_eq!(engine.clock.get_time_ns(), 500); // Shared clock not rewound
+    }
+
+    #[rstest]
+    fn test_inflight_commands_processed_in_arrival_order(audusd_sim: CurrencyPair) {
+        // Cancel latency is lower than insert latency, so the cancel arrives first
+        let latency_model = FixedLatencyModel::new(0, 100, 50, 10);
+        let (mut engine, events) =
+            get_engine_with_latency(audusd_sim, None, Some(Box::new(latency_model)));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79990"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let cancel = CancelOrder::new(
+            trader_id(),
+            None,
+            order.strategy_id,
+            audusd_sim.id,
+            order.client_order_id,
+            None,
+            UUID4::new(),
+            1,
+        )
+        .unwrap();
+        let submit = SubmitOrder::new(
+            trader_id(),
+            None,
+            order.strategy_id,
+            submitted(order),
+            None,
+            UUID4::new(),
+            1,
+        )
+        .unwrap();
+
+        engine.process_command(TradingCommand::SubmitOrder(submit), account_id());
+        engine.process_command(TradingCommand::CancelOrder(cancel), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 200));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderCancelRejected", "OrderAccepted"]
+        );
+    }
+
+    #[rstest]
+    fn test_process_bar_fills_limit_order(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("0.79500"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        engine.process_order(&submitted(order), account_id());
+
+        let bar = Bar::new(
+            "AUD/USD.SIM-1-MINUTE-LAST-EXTERNAL".parse().unwrap(),
+            Price::from("0.80000"),
+            Price::from("0.80100"),
+            Price::from("0.79400"),
+            Price::from("0.79900"),
+            Quantity::from(1_000_000),
+            2,
+            2,
+        );
+        engine.process_bar(&bar);
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderFilled"]);
+    }
+
+    #[rstest]
+    fn test_stop_limit_order_triggers_without_fill_when_limit_not_matched(
+        audusd_sim: CurrencyPair,
+    ) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = stop_limit_order(audusd_sim.id, OrderSide::Buy, "0.80090", "0.80100");
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderTriggered"]
+        );
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Triggered);
+        assert_eq!(engine.get_open_bid_orders().len(), 1);
+    }
+
+    #[rstest]
+    fn test_stop_limit_order_fills_at_limit_once_triggered(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = stop_limit_order(audusd_sim.id, OrderSide::Buy, "0.80090", "0.80100");
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80080", "0.80090", 3));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderTriggered", "OrderFilled"]
+        );
+        match events.lock().unwrap().last().unwrap() {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.80090"));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Maker);
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_market_if_touched_order_not_triggered_when_market_moves_away(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = market_if_touched_order(audusd_sim.id, OrderSide::Buy, "0.79900");
+
+        engine.process_order(&submitted(order), account_id());
+        // A BUY stop at the same trigger price would have triggered
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted"]);
+        assert_eq!(engine.get_open_bid_orders().len(), 1);
+    }
+
+    #[rstest]
+    fn test_market_if_touched_order_fills_at_trigger_when_touched(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = market_if_touched_order(audusd_sim.id, OrderSide::Buy, "0.79900");
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79880", "0.79890", 2));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderFilled"]);
+        match events.lock().unwrap().last().unwrap() {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.79900"));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Taker);
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_limit_if_touched_order_triggers_without_fill_when_limit_not_matched(
+        audusd_sim: CurrencyPair,
+    ) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = limit_if_touched_order(audusd_sim.id, OrderSide::Buy, "0.79940", "0.79950");
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79940", "0.79950", 2));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderTriggered"]
+        );
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Triggered);
+    }
+
+    #[rstest]
+    fn test_limit_if_touched_order_fills_at_limit_once_triggered(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = limit_if_touched_order(audusd_sim.id, OrderSide::Buy, "0.79940", "0.79950");
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79940", "0.79950", 2));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79930", "0.79940", 3));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderTriggered", "OrderFilled"]
+        );
+        match events.lock().unwrap().last().unwrap() {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.79940"));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Maker);
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_trailing_stop_market_order_trails_market(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order =
+            trailing_stop_market_order(audusd_sim.id, OrderSide::Sell, "0.79900", "0.00050");
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+
+        assert_eq!(event_names(&events), vec!["OrderAccepted", "OrderUpdated"]);
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.trigger_price(), Some(Price::from("0.80050")));
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
+
+    #[rstest]
+    fn test_trailing_stop_market_order_fills_at_trailed_trigger(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order =
+            trailing_stop_market_order(audusd_sim.id, OrderSide::Sell, "0.79900", "0.00050");
+
+        engine.process_order(&submitted(order), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80040", "0.80050", 3));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderUpdated", "OrderFilled"]
+        );
+        match events.lock().unwrap().last().unwrap() {
+            OrderEvent::OrderFilled(fill) => {
+                assert_eq!(fill.last_px, Price::from("0.80050"));
+                assert_eq!(fill.liquidity_side, LiquiditySide::Taker);
+            }
+            event => panic!("Unexpected event {event}"),
+        }
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_market_to_limit_order_filled_at_market(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = market_to_limit_order(audusd_sim.id, OrderSide::Buy, Quantity::from(100_000));
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderUpdated", "OrderFilled"]);
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Filled);
+        assert_eq!(order.price(), Some(Price::from("0.80010")));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_market_to_limit_order_rests_remainder_at_first_fill_price(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let order = market_to_limit_order(audusd_sim.id, OrderSide::Buy, Quantity::from(1_500_000));
+        let client_order_id = order.client_order_id;
+
+        engine.process_order(&submitted(order), account_id());
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderUpdated", "OrderPartiallyFilled", "OrderAccepted"]
+        );
+        assert_eq!(engine.get_open_bid_orders().len(), 1);
+
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 2));
+
+        let order = engine.get_order(&client_order_id).unwrap();
+        assert_eq!(order.status(), OrderStatus::Filled);
+        assert_eq!(order.filled_qty(), Quantity::from(1_500_000));
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_oto_child_order_released_when_parent_filled(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let parent = contingent_limit_order(
+            audusd_sim.id,
+            "O-1",
+            OrderSide::Buy,
+            "0.79990",
+            ContingencyType::Oto,
+            Some(vec!["O-2"]),
+            None,
+        );
+        let child = contingent_limit_order(
+            audusd_sim.id,
+            "O-2",
+            OrderSide::Sell,
+            "0.80100",
+            ContingencyType::NoContingency,
+            None,
+            Some("O-1"),
+        );
+
+        engine.process_order(&submitted(parent), account_id());
+        engine.process_order(&submitted(child), account_id());
+        // The child is held until the parent is filled
+        assert_eq!(event_names(&events), vec!["OrderAccepted"]);
+        assert!(engine.get_open_ask_orders().is_empty());
+
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.79980", "0.79990", 2));
+
+        assert_eq!(
+            event_names(&events),
+            vec!["OrderAccepted", "OrderFilled", "OrderAccepted"]
+        );
+        let child = engine.get_order(&ClientOrderId::from("O-2")).unwrap();
+        assert_eq!(child.status(), OrderStatus::Accepted);
+        assert_eq!(engine.get_open_ask_orders().len(), 1);
+    }
+
+    #[rstest]
+    fn test_oto_child_order_rejected_when_parent_rejected(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let mut parent = contingent_limit_order(
+            audusd_sim.id,
+            "O-1",
+            OrderSide::Buy,
+            "0.80020",
+            ContingencyType::Oto,
+            Some(vec!["O-2"]),
+            None,
+        );
+        parent.is_post_only = true; // Rejected as marketable
+        let child = contingent_limit_order(
+            audusd_sim.id,
+            "O-2",
+            OrderSide::Sell,
+            "0.80100",
+            ContingencyType::NoContingency,
+            None,
+            Some("O-1"),
+        );
+
+        engine.process_order(&submitted(parent), account_id());
+        engine.process_order(&submitted(child), account_id());
+
+        assert_eq!(event_names(&events), vec!["OrderRejected", "OrderRejected"]);
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_oco_order_filled_cancels_linked_order(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let take_profit = contingent_limit_order(
+            audusd_sim.id,
+            "O-1",
+            OrderSide::Sell,
+            "0.80100",
+            ContingencyType::Oco,
+            Some(vec!["O-2"]),
+            None,
+        );
+        let other = contingent_limit_order(
+            audusd_sim.id,
+            "O-2",
+            OrderSide::Sell,
+            "0.80200",
+            ContingencyType::Oco,
+            Some(vec!["O-1"]),
+            None,
+        );
+
+        engine.process_order(&submitted(take_profit), account_id());
+        engine.process_order(&submitted(other), account_id());
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80100", "0.80110", 2));
+
+        assert_eq!(
+            event_names(&events),
+            vec![
+                "OrderAccepted",
+                "OrderAccepted",
+                "OrderFilled",
+                "OrderCanceled"
+            ]
+        );
+        let other = engine.get_order(&ClientOrderId::from("O-2")).unwrap();
+        assert_eq!(other.status(), OrderStatus::Canceled);
+        assert!(engine.get_open_orders().is_empty());
+    }
+
+    #[rstest]
+    fn test_ouo_order_partially_filled_reduces_linked_order(audusd_sim: CurrencyPair) {
+        let (mut engine, events) = get_engine(audusd_sim, None);
+        engine.process_quote_tick(&quote(audusd_sim.id, "0.80000", "0.80010", 1));
+        let first = contingent_limit_order(
+            audusd_sim.id,
+            "O-1",
+            OrderSide::Sell,
+            "0.80100",
+            ContingencyType::Ouo,
+            Some(vec!["O-2"]),
+            None,
+        );
+        let second = contingent_limit_order(
+            audusd_sim.id,
+            "O-2",
+            OrderSide::Sell,
+            "0.80200",
+            ContingencyType::Ouo,
+            Some(vec!["O-1"]),
+            None,
+        );
+
+        engine.process_order(&submitted(first), account_id());
+        engine.process_order(&submitted(second), account_id());
+        let mut partial = quote(audusd_sim.id, "0.80100", "0.80110", 2);
+        partial.bid_size = Quantity::from(40_000);
+        engine.process_quote_tick(&partial);
+
+        assert_eq!(
+            event_names(&events),
+            vec![
+                "OrderAccepted",
+                "OrderAccepted",
+                "OrderPartiallyFilled",
+                "OrderUpdated"
+            ]
+        );
+        let second = engine.get_order(&ClientOrderId::from("O-2")).unwrap();
+        assert_eq!(second.quantity(), Quantity::from(60_000));
+        assert_eq!(second.status(), OrderStatus::Accepted);
+    }
 }
@@ -0,0 +1,612 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use nautilus_model::{
+    data::trade::TradeTick,
+    enums::{AggressorSide, OrderSide},
+    identifiers::client_order_id::ClientOrderId,
+    types::{price::Price, quantity::Quantity},
+};
+
+/// The queue position of a passive order resting at a price level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueuePosition {
+    /// The side of the resting order.
+    pub side: OrderSide,
+    /// The price level the order is resting at.
+    pub price: Price,
+    /// The size (raw) still ahead of the order in the queue.
+    pub ahead_raw: u64,
+    /// The size (raw) traded against the order which has not yet been filled.
+    pub fillable_raw: u64,
+    /// The size (raw) of the order which has not yet been filled.
+    pub leaves_raw: u64,
+    /// The size precision for the order.
+    pub size_precision: u8,
+    /// The sequence the order joined the queue in, where lower sequences are further ahead.
+    pub sequence: u64,
+}
+
+impl QueuePosition {
+    /// Returns the size still ahead of the order in the queue.
+    #[must_use]
+    pub fn ahead(&self) -> Quantity {
+        Quantity::from_raw(self.ahead_raw, self.size_precision).unwrap()
+    }
+
+    /// Returns the size which is available to fill the order.
+    #[must_use]
+    pub fn fillable(&self) -> Quantity {
+        Quantity::from_raw(self.fillable_raw, self.size_precision).unwrap()
+    }
+
+    /// Returns the size (raw) of the order which traded size may still be credited to.
+    fn unfillable_raw(&self) -> u64 {
+        self.leaves_raw.saturating_sub(self.fillable_raw)
+    }
+
+    /// Returns the key for ordering positions by their priority, across price levels.
+    fn priority(&self) -> (i64, u64) {
+        let price_priority = match self.side {
+            OrderSide::Buy => -self.price.raw,
+            _ => self.price.raw,
+        };
+        (price_priority, self.sequence)
+    }
+
+    fn is_at_or_through(&self, price: Price) -> (bool, bool) {
+        let at = price == self.price;
+        let through = match self.side {
+            OrderSide::Buy => price < self.price,
+            _ => price > self.price,
+        };
+        (at, through)
+    }
+}
+
+/// Provides a book-depth-aware fill model which tracks the queue position of passive orders.
+///
+/// When an order joins a price level, the size of the `Level` already resting at that price
+/// is recorded as being ahead of it. The size ahead is then reduced as trades print and
+/// the level size is reduced at that price, and only once the queue ahead is exhausted does
+/// any further traded size become available to fill the order.
+///
+/// Traded size is allocated across the resting orders in queue order, so the size credited
+/// to an order is no longer available to the orders behind it.
+///
+/// Level size reductions (deletes, or updates to a smaller size) are assumed to come from
+/// ahead of the order in the queue (the size ahead can never go below zero, nor above the
+/// level size), which is the optimistic assumption where order IDs for the queue are not
+/// tracked. The size traded at a level since its last update is already accounted for, so
+/// only the remainder of the reduction is treated as cancels.
+#[derive(Clone, Debug, Default)]
+pub struct QueuePositionFillModel {
+    positions: HashMap<ClientOrderId, QueuePosition>,
+    traded: HashMap<(OrderSide, Price), u64>,
+    next_sequence: u64,
+}
+
+impl QueuePositionFillModel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.positions.clear();
+        self.traded.clear();
+    }
+
+    #[must_use]
+    pub fn contains(&self, client_order_id: &ClientOrderId) -> bool {
+        self.positions.contains_key(client_order_id)
+    }
+
+    #[must_use]
+    pub fn get_position(&self, client_order_id: &ClientOrderId) -> Option<&QueuePosition> {
+        self.positions.get(client_order_id)
+    }
+
+    /// Adds the order with `leaves_qty` to the back of the queue at the given `price`, with
+    /// `size_ahead` being the size of the level resting at that price when the order joined.
+    ///
+    /// Any existing queue position for the order is replaced (so it loses its priority).
+    pub fn add_order(
+        &mut self,
+        client_order_id: ClientOrderId,
+        side: OrderSide,
+        price: Price,
+        size_ahead: Quantity,
+        leaves_qty: Quantity,
+    ) {
+        let position = QueuePosition {
+            side,
+            price,
+            ahead_raw: size_ahead.raw,
+            fillable_raw: 0,
+            leaves_raw: leaves_qty.raw,
+            size_precision: size_ahead.precision,
+            sequence: self.next_sequence,
+        };
+        self.next_sequence += 1;
+        self.positions.insert(client_order_id, position);
+    }
+
+    /// Updates the unfilled quantity of the order, retaining its place in the queue.
+    pub fn update_leaves_qty(&mut self, client_order_id: &ClientOrderId, leaves_qty: Quantity) {
+        if let Some(position) = self.positions.get_mut(client_order_id) {
+            position.leaves_raw = leaves_qty.raw;
+            position.fillable_raw = position.fillable_raw.min(leaves_qty.raw);
+        }
+    }
+
+    pub fn remove_order(&mut self, client_order_id: &ClientOrderId) {
+        let Some(removed) = self.positions.remove(client_order_id) else {
+            return;
+        };
+        let is_queued = self
+            .positions
+            .values()
+            .any(|position| position.side == removed.side && position.price == removed.price);
+        if !is_queued {
+            self.traded.remove(&(removed.side, removed.price));
+        }
+    }
+
+    /// Processes the given `trade`, reducing the size ahead of any orders resting at the
+    /// trade price and crediting the remaining traded size as fillable, in queue order.
+    ///
+    /// A trade printing through the price of a resting order consumes the entire queue
+    /// ahead of it at that price.
+    ///
+    /// The traded size is recorded against the queued levels at the trade price, until
+    /// the next update of the level (see [`QueuePositionFillModel::process_level_update`]).
+    pub fn process_trade(&mut self, trade: &TradeTick) {
+        let mut positions: Vec<&mut QueuePosition> = self
+            .positions
+            .values_mut()
+            .filter(|position| {
+                // Only trades which were aggressing against the orders side can fill it
+                match trade.aggressor_side {
+                    AggressorSide::Buyer => position.side == OrderSide::Sell,
+                    AggressorSide::Seller => position.side == OrderSide::Buy,
+                    AggressorSide::NoAggressor => true,
+                }
+            })
+            .collect();
+        positions.sort_by_key(|position| position.priority());
+
+        // The traded size credited to orders ahead is not available to those behind
+        let mut credited_raw = 0;
+        let mut traded_sides = Vec::new();
+        for position in positions {
+            let (at, through) = position.is_at_or_through(trade.price);
+            if !(at || through) {
+                continue;
+            }
+            if at && !traded_sides.contains(&position.side) {
+                traded_sides.push(position.side);
+            }
+            if through {
+                position.ahead_raw = 0;
+            }
+
+            let available_raw = trade.size.raw.saturating_sub(credited_raw);
+            let consumed = position.ahead_raw.min(available_raw);
+            position.ahead_raw -= consumed;
+            let fillable = (available_raw - consumed).min(position.unfillable_raw());
+            position.fillable_raw += fillable;
+            credited_raw += fillable;
+        }
+
+        for side in traded_sides {
+            *self.traded.entry((side, trade.price)).or_default() += trade.size.raw;
+        }
+    }
+
+    /// Processes an order of `size` being deleted from the book at the given `price`.
+    pub fn process_delete(&mut self, side: OrderSide, price: Price, size: Quantity) {
+        for position in self.positions.values_mut() {
+            if position.side == side && position.price == price {
+                position.ahead_raw = position.ahead_raw.saturating_sub(size.raw);
+            }
+        }
+    }
+
+    /// Processes the size of the level at the given `price` changing from `previous_size`
+    /// to `size` (such as from an update or delete), where any reduction beyond the size
+    /// traded at the level since its last update is processed as a delete.
+    pub fn process_level_update(
+        &mut self,
+        side: OrderSide,
+        price: Price,
+        previous_size: Quantity,
+        size: Quantity,
+    ) {
+        let traded_raw = self.traded.remove(&(side, price)).unwrap_or_default();
+        if size >= previous_size {
+            return; // Orders joining the level are behind those already queued
+        }
+
+        // Traded size has already reduced the queue ahead
+        let canceled_raw = (previous_size.raw - size.raw).saturating_sub(traded_raw);
+        self.process_delete(
+            side,
+            price,
+            Quantity::from_raw(canceled_raw, size.precision).unwrap(),
+        );
+        for position in self.positions.values_mut() {
+            if position.side == side && position.price == price {
+                position.ahead_raw = position.ahead_raw.min(size.raw);
+            }
+        }
+    }
+
+    /// Returns the distinct price levels which orders are queued at.
+    #[must_use]
+    pub fn queued_levels(&self) -> Vec<(OrderSide, Price)> {
+        let mut levels: Vec<(OrderSide, Price)> = self
+            .positions
+            .values()
+            .map(|position| (position.side, position.price))
+            .collect();
+        levels.sort();
+        levels.dedup();
+        levels
+    }
+
+    /// Returns the size available to fill the order (if tracked).
+    #[must_use]
+    pub fn fillable_qty(&self, client_order_id: &ClientOrderId) -> Option<Quantity> {
+        self.positions
+            .get(client_order_id)
+            .map(QueuePosition::fillable)
+    }
+
+    /// Consumes the given filled `qty` from the size available to fill the order.
+    pub fn consume(&mut self, client_order_id: &ClientOrderId, qty: Quantity) {
+        if let Some(position) = self.positions.get_mut(client_order_id) {
+            position.fillable_raw = position.fillable_raw.saturating_sub(qty.raw);
+            position.leaves_raw = position.leaves_raw.saturating_sub(qty.raw);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::identifiers::{instrument_id::InstrumentId, trade_id::TradeId};
+    use rstest::rstest;
+
+    use super::*;
+
+    fn trade(price: &str, size: i64, aggressor_side: AggressorSide) -> TradeTick {
+        TradeTick::new(
+            InstrumentId::from("AUD/USD.SIM"),
+            Price::from(price),
+            Quantity::from(size),
+            aggressor_side,
+            TradeId::from("1"),
+            0,
+            0,
+        )
+    }
+
+    #[rstest]
+    fn test_trades_at_price_consume_queue_ahead_first() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(300),
+            Quantity::from(1_000),
+        );
+
+        model.process_trade(&trade("1.00000", 200, AggressorSide::Seller));
+        assert_eq!(
+            model.fillable_qty(&client_order_id),
+            Some(Quantity::from(0))
+        );
+
+        model.process_trade(&trade("1.00000", 150, AggressorSide::Seller));
+        let position = model.get_position(&client_order_id).unwrap();
+        assert_eq!(position.ahead(), Quantity::from(0));
+        assert_eq!(position.fillable(), Quantity::from(50));
+    }
+
+    #[rstest]
+    #[case(AggressorSide::Buyer, 0)]
+    #[case(AggressorSide::Seller, 100)]
+    #[case(AggressorSide::NoAggressor, 100)]
+    fn test_trade_aggressor_side(#[case] aggressor_side: AggressorSide, #[case] expected: i64) {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(0),
+            Quantity::from(1_000),
+        );
+
+        model.process_trade(&trade("1.00000", 100, aggressor_side));
+
+        assert_eq!(
+            model.fillable_qty(&client_order_id),
+            Some(Quantity::from(expected))
+        );
+    }
+
+    #[rstest]
+    fn test_trade_through_price_consumes_queue() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            client_order_id,
+            OrderSide::Sell,
+            Price::from("1.00000"),
+            Quantity::from(1_000),
+            Quantity::from(1_000),
+        );
+
+        model.process_trade(&trade("1.00010", 100, AggressorSide::Buyer));
+
+        let position = model.get_position(&client_order_id).unwrap();
+        assert_eq!(position.ahead(), Quantity::from(0));
+        assert_eq!(position.fillable(), Quantity::from(100));
+    }
+
+    #[rstest]
+    fn test_deletes_reduce_queue_ahead() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(300),
+            Quantity::from(1_000),
+        );
+
+        model.process_delete(OrderSide::Buy, Price::from("1.00000"), Quantity::from(100));
+        model.process_delete(OrderSide::Buy, Price::from("0.99990"), Quantity::from(100));
+        model.process_delete(OrderSide::Sell, Price::from("1.00000"), Quantity::from(100));
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead(),
+            Quantity::from(200)
+        );
+
+        model.process_delete(OrderSide::Buy, Price::from("1.00000"), Quantity::from(500));
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead(),
+            Quantity::from(0)
+        );
+    }
+
+    #[rstest]
+    fn test_consume_fillable() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(0),
+            Quantity::from(1_000),
+        );
+        model.process_trade(&trade("1.00000", 100, AggressorSide::Seller));
+
+        model.consume(&client_order_id, Quantity::from(60));
+
+        assert_eq!(
+            model.fillable_qty(&client_order_id),
+            Some(Quantity::from(40))
+        );
+    }
+
+    #[rstest]
+    fn test_trade_is_allocated_across_orders_in_queue_order() {
+        let first_id = ClientOrderId::from("O-1");
+        let second_id = ClientOrderId::from("O-2");
+        let mut model = QueuePositionFillModel::new();
+        let price = Price::from("1.00000");
+        model.add_order(
+            first_id,
+            OrderSide::Buy,
+            price,
+            Quantity::from(100),
+            Quantity::from(30),
+        );
+        model.add_order(
+            second_id,
+            OrderSide::Buy,
+            price,
+            Quantity::from(150),
+            Quantity::from(100),
+        );
+
+        // 100 ahead of both, 30 to the first order, 50 ahead of the second, then 20 to it
+        model.process_trade(&trade("1.00000", 200, AggressorSide::Seller));
+
+        assert_eq!(model.fillable_qty(&first_id), Some(Quantity::from(30)));
+        assert_eq!(model.fillable_qty(&second_id), Some(Quantity::from(20)));
+    }
+
+    #[rstest]
+    fn test_trade_through_prices_allocated_by_price_priority() {
+        let first_id = ClientOrderId::from("O-1");
+        let second_id = ClientOrderId::from("O-2");
+        let mut model = QueuePositionFillModel::new();
+        model.add_order(
+            first_id,
+            OrderSide::Sell,
+            Price::from("1.00010"),
+            Quantity::from(100),
+            Quantity::from(100),
+        );
+        model.add_order(
+            second_id,
+            OrderSide::Sell,
+            Price::from("1.00000"),
+            Quantity::from(100),
+            Quantity::from(100),
+        );
+
+        model.process_trade(&trade("1.00020", 150, AggressorSide::Buyer));
+
+        assert_eq!(model.fillable_qty(&second_id), Some(Quantity::from(100)));
+        assert_eq!(model.fillable_qty(&first_id), Some(Quantity::from(50)));
+    }
+
+    #[rstest]
+    fn test_level_update_reducing_size_reduces_queue_ahead() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        let price = Price::from("1.00000");
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            price,
+            Quantity::from(300),
+            Quantity::from(100),
+        );
+
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(300),
+            Quantity::from(500),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead(),
+            Quantity::from(300)
+        );
+
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(500),
+            Quantity::from(400),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead(),
+            Quantity::from(200)
+        );
+
+        // The size ahead can not be more than the size resting at the level
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(400),
+            Quantity::from(150),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead(),
+            Quantity::from(0)
+        );
+    }
+
+    #[rstest]
+    fn test_trade_then_level_update_only_reduces_queue_ahead_once() {
+        let client_order_id = ClientOrderId::from("O-1");
+        let mut model = QueuePositionFillModel::new();
+        let price = Price::from("1.00000");
+        model.add_order(
+            client_order_id,
+            OrderSide::Buy,
+            price,
+            Quantity::from(300),
+            Quantity::from(100),
+        );
+
+        model.process_trade(&trade("1.00000", 100, AggressorSide::Seller));
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead_raw,
+            Quantity::from(200).raw
+        );
+
+        // The level is reduced by the traded size, which is not a cancel
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(300),
+            Quantity::from(200),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead_raw,
+            Quantity::from(200).raw
+        );
+
+        // Any further reduction beyond the traded size is processed as a cancel
+        model.process_trade(&trade("1.00000", 50, AggressorSide::Seller));
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(200),
+            Quantity::from(120),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead_raw,
+            Quantity::from(120).raw
+        );
+
+        // The traded size only applies until the next update of the level
+        model.process_level_update(
+            OrderSide::Buy,
+            price,
+            Quantity::from(120),
+            Quantity::from(100),
+        );
+        assert_eq!(
+            model.get_position(&client_order_id).unwrap().ahead_raw,
+            Quantity::from(100).raw
+        );
+    }
+
+    #[rstest]
+    fn test_add_order_again_moves_to_back_of_queue() {
+        let first_id = ClientOrderId::from("O-1");
+        let second_id = ClientOrderId::from("O-2");
+        let mut model = QueuePositionFillModel::new();
+        let price = Price::from("1.00000");
+        for client_order_id in [first_id, second_id] {
+            model.add_order(
+                client_order_id,
+                OrderSide::Buy,
+                price,
+                Quantity::from(0),
+                Quantity::from(100),
+            );
+        }
+
+        model.add_order(
+            first_id,
+            OrderSide::Buy,
+            price,
+            Quantity::from(0),
+            Quantity::from(200),
+        );
+        model.process_trade(&trade("1.00000", 150, AggressorSide::Seller));
+
+        assert_eq!(model.fillable_qty(&second_id), Some(Quantity::from(100)));
+        assert_eq!(model.fillable_qty(&first_id), Some(Quantity::from(50)));
+    }
+}
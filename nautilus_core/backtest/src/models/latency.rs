@@ -0,0 +1,255 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// Provides the simulated latency between a trading command being sent and it
+/// reaching the venue.
+///
+/// Each latency includes the base latency of the model.
+pub trait LatencyModel {
+    /// Returns the latency (nanoseconds) for an order insert (submit).
+    fn insert_latency_nanos(&mut self) -> u64;
+    /// Returns the latency (nanoseconds) for an order update (modify).
+    fn update_latency_nanos(&mut self) -> u64;
+    /// Returns the latency (nanoseconds) for an order cancel.
+    fn cancel_latency_nanos(&mut self) -> u64;
+}
+
+/// Provides a latency model with fixed latencies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedLatencyModel {
+    pub base_latency_nanos: u64,
+    pub insert_latency_nanos: u64,
+    pub update_latency_nanos: u64,
+    pub cancel_latency_nanos: u64,
+}
+
+impl FixedLatencyModel {
+    #[must_use]
+    pub fn new(
+        base_latency_nanos: u64,
+        insert_latency_nanos: u64,
+        update_latency_nanos: u64,
+        cancel_latency_nanos: u64,
+    ) -> Self {
+        Self {
+            base_latency_nanos,
+            insert_latency_nanos,
+            update_latency_nanos,
+            cancel_latency_nanos,
+        }
+    }
+}
+
+impl LatencyModel for FixedLatencyModel {
+    fn insert_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + self.insert_latency_nanos
+    }
+
+    fn update_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + self.update_latency_nanos
+    }
+
+    fn cancel_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + self.cancel_latency_nanos
+    }
+}
+
+/// Provides a latency model with latencies drawn uniformly from inclusive `(min, max)` ranges.
+///
+/// The random number generator is seeded so that backtests are reproducible.
+#[derive(Clone, Debug)]
+pub struct UniformLatencyModel {
+    pub base_latency_nanos: u64,
+    pub insert_range_nanos: (u64, u64),
+    pub update_range_nanos: (u64, u64),
+    pub cancel_range_nanos: (u64, u64),
+    rng: StdRng,
+}
+
+impl UniformLatencyModel {
+    pub fn new(
+        base_latency_nanos: u64,
+        insert_range_nanos: (u64, u64),
+        update_range_nanos: (u64, u64),
+        cancel_range_nanos: (u64, u64),
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        for (range, param) in [
+            (insert_range_nanos, "insert_range_nanos"),
+            (update_range_nanos, "update_range_nanos"),
+            (cancel_range_nanos, "cancel_range_nanos"),
+        ] {
+            if range.0 > range.1 {
+                anyhow::bail!("`{param}` min was greater than max, was {range:?}");
+            }
+        }
+
+        Ok(Self {
+            base_latency_nanos,
+            insert_range_nanos,
+            update_range_nanos,
+            cancel_range_nanos,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    fn sample(&mut self, range: (u64, u64)) -> u64 {
+        self.base_latency_nanos + self.rng.gen_range(range.0..=range.1)
+    }
+}
+
+impl LatencyModel for UniformLatencyModel {
+    fn insert_latency_nanos(&mut self) -> u64 {
+        self.sample(self.insert_range_nanos)
+    }
+
+    fn update_latency_nanos(&mut self) -> u64 {
+        self.sample(self.update_range_nanos)
+    }
+
+    fn cancel_latency_nanos(&mut self) -> u64 {
+        self.sample(self.cancel_range_nanos)
+    }
+}
+
+/// Provides a latency model which draws latencies from empirical distributions of
+/// observed latency samples.
+///
+/// The random number generator is seeded so that backtests are reproducible.
+#[derive(Clone, Debug)]
+pub struct EmpiricalLatencyModel {
+    pub base_latency_nanos: u64,
+    insert_samples: Vec<u64>,
+    update_samples: Vec<u64>,
+    cancel_samples: Vec<u64>,
+    rng: StdRng,
+}
+
+impl EmpiricalLatencyModel {
+    pub fn new(
+        base_latency_nanos: u64,
+        insert_samples: Vec<u64>,
+        update_samples: Vec<u64>,
+        cancel_samples: Vec<u64>,
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        for (samples, param) in [
+            (&insert_samples, "insert_samples"),
+            (&update_samples, "update_samples"),
+            (&cancel_samples, "cancel_samples"),
+        ] {
+            if samples.is_empty() {
+                anyhow::bail!("`{param}` was empty");
+            }
+        }
+
+        Ok(Self {
+            base_latency_nanos,
+            insert_samples,
+            update_samples,
+            cancel_samples,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+}
+
+fn choose_sample(samples: &[u64], rng: &mut StdRng) -> u64 {
+    // Samples are validated as non-empty on construction
+    *samples.choose(rng).unwrap()
+}
+
+impl LatencyModel for EmpiricalLatencyModel {
+    fn insert_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + choose_sample(&self.insert_samples, &mut self.rng)
+    }
+
+    fn update_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + choose_sample(&self.update_samples, &mut self.rng)
+    }
+
+    fn cancel_latency_nanos(&mut self) -> u64 {
+        self.base_latency_nanos + choose_sample(&self.cancel_samples, &mut self.rng)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_fixed_latency_model() {
+        let mut model = FixedLatencyModel::new(1_000, 100, 200, 300);
+
+        assert_eq!(model.insert_latency_nanos(), 1_100);
+        assert_eq!(model.update_latency_nanos(), 1_200);
+        assert_eq!(model.cancel_latency_nanos(), 1_300);
+    }
+
+    #[rstest]
+    fn test_uniform_latency_model_within_ranges() {
+        let mut model =
+            UniformLatencyModel::new(1_000, (100, 200), (300, 400), (500, 500), 42).unwrap();
+
+        for _ in 0..100 {
+            assert!((1_100..=1_200).contains(&model.insert_latency_nanos()));
+            assert!((1_300..=1_400).contains(&model.update_latency_nanos()));
+            assert_eq!(model.cancel_latency_nanos(), 1_500);
+        }
+    }
+
+    #[rstest]
+    fn test_uniform_latency_model_is_reproducible_with_seed() {
+        let mut model1 = UniformLatencyModel::new(0, (0, 1_000_000), (0, 0), (0, 0), 1).unwrap();
+        let mut model2 = UniformLatencyModel::new(0, (0, 1_000_000), (0, 0), (0, 0), 1).unwrap();
+
+        let latencies1: Vec<u64> = (0..10).map(|_| model1.insert_latency_nanos()).collect();
+        let latencies2: Vec<u64> = (0..10).map(|_| model2.insert_latency_nanos()).collect();
+
+        assert_eq!(latencies1, latencies2);
+    }
+
+    #[rstest]
+    fn test_uniform_latency_model_invalid_range() {
+        let result = UniformLatencyModel::new(0, (200, 100), (0, 0), (0, 0), 1);
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_empirical_latency_model_draws_from_samples() {
+        let samples = vec![10, 20, 30];
+        let mut model =
+            EmpiricalLatencyModel::new(5, samples.clone(), vec![7], samples.clone(), 42).unwrap();
+
+        for _ in 0..100 {
+            assert!(samples.contains(&(model.insert_latency_nanos() - 5)));
+            assert_eq!(model.update_latency_nanos(), 12);
+        }
+    }
+
+    #[rstest]
+    fn test_empirical_latency_model_empty_samples() {
+        let result = EmpiricalLatencyModel::new(0, vec![], vec![1], vec![1], 1);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,1589 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Bar aggregation from quote and trade ticks.
+//!
+//! Provides a [`BarBuilder`] and an aggregator for every [`BarAggregation`] variant, so the
+//! same aggregation logic can be used for both backtesting and live trading.
+
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Formatter},
+    rc::Rc,
+};
+
+use chrono::{DateTime, Datelike, NaiveDate};
+use nautilus_core::{
+    datetime::{NANOSECONDS_IN_MILLISECOND, NANOSECONDS_IN_SECOND},
+    time::UnixNanos,
+};
+use nautilus_model::{
+    data::{
+        bar::{Bar, BarType},
+        quote::QuoteTick,
+        trade::TradeTick,
+    },
+    enums::{AggressorSide, BarAggregation},
+    instruments::Instrument,
+    types::{fixed::FIXED_SCALAR, price::Price, quantity::Quantity},
+};
+
+use crate::{
+    clock::{Clock, LiveClock, TestClock},
+    handlers::{EventHandler, LocalTimeEventCallback, SafeTimeEventCallback},
+    timer::TimeEvent,
+};
+
+const NANOSECONDS_IN_DAY: u64 = 86_400 * NANOSECONDS_IN_SECOND;
+
+/// The offset from the UNIX epoch (a Thursday) to the first Monday (1970-01-05).
+const WEEK_START_OFFSET_NS: u64 = 4 * NANOSECONDS_IN_DAY;
+
+/// Provides a generic bar builder for aggregation.
+pub struct BarBuilder {
+    bar_type: BarType,
+    size_precision: u8,
+    initialized: bool,
+    ts_last: UnixNanos,
+    count: usize,
+    partial_set: bool,
+    last_close: Option<Price>,
+    open: Option<Price>,
+    high: Option<Price>,
+    low: Option<Price>,
+    close: Option<Price>,
+    volume: Quantity,
+}
+
+impl BarBuilder {
+    /// Creates a new [`BarBuilder`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `instrument.id()` is not equal to `bar_type.instrument_id`.
+    pub fn new(instrument: &dyn Instrument, bar_type: BarType) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            instrument.id() == bar_type.instrument_id,
+            "`instrument.id` {} was not equal to `bar_type.instrument_id` {}",
+            instrument.id(),
+            bar_type.instrument_id
+        );
+
+        let size_precision = instrument.size_precision();
+        Ok(Self {
+            bar_type,
+            size_precision,
+            initialized: false,
+            ts_last: 0,
+            count: 0,
+            partial_set: false,
+            last_close: None,
+            open: None,
+            high: None,
+            low: None,
+            close: None,
+            volume: Quantity::zero(size_precision),
+        })
+    }
+
+    /// Returns whether the builder has received its first update (or partial bar).
+    #[must_use]
+    pub fn initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// Returns the UNIX timestamp (nanoseconds) of the last update.
+    #[must_use]
+    pub fn ts_last(&self) -> UnixNanos {
+        self.ts_last
+    }
+
+    /// Returns the count of updates since the last build.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the volume accumulated since the last build.
+    #[must_use]
+    pub fn volume(&self) -> Quantity {
+        self.volume
+    }
+
+    /// Sets the initial values for a partially completed bar.
+    ///
+    /// This only has an effect the first time it is called.
+    pub fn set_partial(&mut self, partial_bar: Bar) {
+        if self.partial_set {
+            return; // Already updated
+        }
+
+        self.open = Some(partial_bar.open);
+
+        if !matches!(self.high, Some(high) if high >= partial_bar.high) {
+            self.high = Some(partial_bar.high);
+        }
+
+        if !matches!(self.low, Some(low) if low <= partial_bar.low) {
+            self.low = Some(partial_bar.low);
+        }
+
+        if self.close.is_none() {
+            self.close = Some(partial_bar.close);
+        }
+
+        self.volume = partial_bar.volume;
+
+        if self.ts_last == 0 {
+            self.ts_last = partial_bar.ts_init;
+        }
+
+        self.partial_set = true;
+        self.initialized = true;
+    }
+
+    /// Updates the builder with the given `price` and `size`.
+    ///
+    /// Updates older than the last update are ignored.
+    pub fn update(&mut self, price: Price, size: Quantity, ts_event: UnixNanos) {
+        if ts_event < self.ts_last {
+            return; // Not applicable
+        }
+
+        match (self.open, self.high, self.low) {
+            (Some(_), Some(high), Some(low)) => {
+                if price > high {
+                    self.high = Some(price);
+                } else if price < low {
+                    self.low = Some(price);
+                }
+            }
+            _ => {
+                // Initialize builder
+                self.open = Some(price);
+                self.high = Some(price);
+                self.low = Some(price);
+                self.initialized = true;
+            }
+        }
+
+        self.close = Some(price);
+        self.volume.raw += size.raw;
+        self.count += 1;
+        self.ts_last = ts_event;
+    }
+
+    /// Resets the builder, all stateful fields are reset to their initial value.
+    pub fn reset(&mut self) {
+        self.open = None;
+        self.high = None;
+        self.low = None;
+        self.volume = Quantity::zero(self.size_precision);
+        self.count = 0;
+    }
+
+    /// Returns the aggregated bar timestamped at the last update, and resets.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the builder has not been initialized.
+    pub fn build_now(&mut self) -> Bar {
+        self.build(self.ts_last, self.ts_last)
+    }
+
+    /// Returns the aggregated bar with the given timestamps, and resets.
+    ///
+    /// If no update was received since the last build then all prices are set
+    /// to the last close.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the builder has not been initialized.
+    pub fn build(&mut self, ts_event: UnixNanos, ts_init: UnixNanos) -> Bar {
+        if self.open.is_none() {
+            // No update was received
+            self.open = self.last_close;
+            self.high = self.last_close;
+            self.low = self.last_close;
+            self.close = self.last_close;
+        }
+
+        let close = self.close.expect("`BarBuilder` was not initialized");
+        let bar = Bar::new(
+            self.bar_type,
+            self.open.unwrap_or(close),
+            self.high.unwrap_or(close),
+            self.low.unwrap_or(close),
+            close,
+            Quantity::from_raw(self.volume.raw, self.size_precision).unwrap(),
+            ts_event,
+            ts_init,
+        );
+
+        self.last_close = self.close;
+        self.reset();
+        bar
+    }
+}
+
+impl Debug for BarBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(BarBuilder))
+            .field("bar_type", &self.bar_type)
+            .field("open", &self.open)
+            .field("high", &self.high)
+            .field("low", &self.low)
+            .field("close", &self.close)
+            .field("volume", &self.volume)
+            .finish()
+    }
+}
+
+/// Represents a handler which receives the bars built by an aggregator.
+pub type BarHandler = Box<dyn FnMut(Bar)>;
+
+/// Provides a means of aggregating bars of a specified [`BarType`] and sending them
+/// to a registered handler.
+pub trait BarAggregator {
+    /// Returns the bar type for the aggregator.
+    fn bar_type(&self) -> BarType;
+
+    /// Returns whether the aggregator is awaiting an initial partial bar prior to aggregating.
+    fn await_partial(&self) -> bool;
+
+    /// Sets whether the aggregator should await an initial partial bar prior to aggregating.
+    fn set_await_partial(&mut self, value: bool);
+
+    /// Sets the initial values for a partially completed bar.
+    fn set_partial(&mut self, partial_bar: Bar);
+
+    /// Updates the aggregator with the given price and size.
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    );
+
+    /// Updates the aggregator with the given quote.
+    ///
+    /// Quotes have no aggressor side, so do not contribute to imbalance or runs.
+    fn handle_quote_tick(&mut self, quote: QuoteTick) {
+        if !self.await_partial() {
+            let price_type = self.bar_type().spec.price_type;
+            self.update(
+                quote.extract_price(price_type),
+                quote.extract_volume(price_type),
+                AggressorSide::NoAggressor,
+                quote.ts_event,
+            );
+        }
+    }
+
+    /// Updates the aggregator with the given trade.
+    fn handle_trade_tick(&mut self, trade: TradeTick) {
+        if !self.await_partial() {
+            self.update(
+                trade.price,
+                trade.size,
+                trade.aggressor_side,
+                trade.ts_event,
+            );
+        }
+    }
+}
+
+/// The state common to all bar aggregators.
+struct BarAggregatorCore {
+    bar_type: BarType,
+    builder: BarBuilder,
+    handler: BarHandler,
+    await_partial: bool,
+}
+
+impl BarAggregatorCore {
+    fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(bar_type.spec.step > 0, "`step` was zero for {bar_type}");
+
+        Ok(Self {
+            bar_type,
+            builder: BarBuilder::new(instrument, bar_type)?,
+            handler,
+            await_partial: false,
+        })
+    }
+
+    fn check_aggregation(&self, aggregations: &[BarAggregation]) -> anyhow::Result<()> {
+        let aggregation = self.bar_type.spec.aggregation;
+        anyhow::ensure!(
+            aggregations.contains(&aggregation),
+            "Invalid aggregation {aggregation} for aggregator, expected one of {aggregations:?}"
+        );
+        Ok(())
+    }
+
+    fn build_now_and_send(&mut self) {
+        let bar = self.builder.build_now();
+        (self.handler)(bar);
+    }
+
+    fn build_and_send(&mut self, ts_event: UnixNanos, ts_init: UnixNanos) {
+        let bar = self.builder.build(ts_event, ts_init);
+        (self.handler)(bar);
+    }
+}
+
+macro_rules! impl_bar_aggregator_core {
+    () => {
+        fn bar_type(&self) -> BarType {
+            self.core.bar_type
+        }
+
+        fn await_partial(&self) -> bool {
+            self.core.await_partial
+        }
+
+        fn set_await_partial(&mut self, value: bool) {
+            self.core.await_partial = value;
+        }
+
+        fn set_partial(&mut self, partial_bar: Bar) {
+            self.core.builder.set_partial(partial_bar);
+        }
+    };
+}
+
+/// Returns the tick, volume or value measure of an update for the given `aggregation`.
+fn update_measure(aggregation: BarAggregation, price: Price, size: Quantity) -> f64 {
+    match aggregation {
+        BarAggregation::TickImbalance | BarAggregation::TickRuns => 1.0,
+        BarAggregation::VolumeImbalance | BarAggregation::VolumeRuns => size.as_f64(),
+        _ => price.as_f64() * size.as_f64(),
+    }
+}
+
+/// Provides a means of building tick bars from ticks.
+///
+/// When the received tick count reaches the step threshold of the bar
+/// specification, then a bar is created and sent to the handler.
+pub struct TickBarAggregator {
+    core: BarAggregatorCore,
+}
+
+impl TickBarAggregator {
+    /// Creates a new [`TickBarAggregator`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[BarAggregation::Tick])?;
+        Ok(Self { core })
+    }
+}
+
+impl BarAggregator for TickBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        _aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        self.core.builder.update(price, size, ts_event);
+
+        if self.core.builder.count == self.core.bar_type.spec.step {
+            self.core.build_now_and_send();
+        }
+    }
+}
+
+/// Provides a means of building volume bars from ticks.
+///
+/// When the received volume reaches the step threshold of the bar
+/// specification, then a bar is created and sent to the handler.
+/// Updates which cross the threshold are split across bars.
+pub struct VolumeBarAggregator {
+    core: BarAggregatorCore,
+}
+
+impl VolumeBarAggregator {
+    /// Creates a new [`VolumeBarAggregator`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[BarAggregation::Volume])?;
+        Ok(Self { core })
+    }
+}
+
+impl BarAggregator for VolumeBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        _aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        let mut raw_size_update = size.raw;
+        let raw_step = (self.core.bar_type.spec.step as f64 * FIXED_SCALAR) as u64;
+
+        while raw_size_update > 0 {
+            // While there is size to apply
+            if self.core.builder.volume.raw + raw_size_update < raw_step {
+                // Update and break
+                self.core.builder.update(
+                    price,
+                    Quantity::from_raw(raw_size_update, size.precision).unwrap(),
+                    ts_event,
+                );
+                break;
+            }
+
+            // Update builder to the step threshold
+            let raw_size_diff = raw_step - self.core.builder.volume.raw;
+            self.core.builder.update(
+                price,
+                Quantity::from_raw(raw_size_diff, size.precision).unwrap(),
+                ts_event,
+            );
+
+            // Build a bar and reset builder
+            self.core.build_now_and_send();
+
+            // Decrement the update size
+            raw_size_update -= raw_size_diff;
+        }
+    }
+}
+
+/// Provides a means of building value bars from ticks.
+///
+/// When the received value (price * size) reaches the step threshold of the bar
+/// specification, then a bar is created and sent to the handler.
+/// Updates which cross the threshold are split across bars.
+pub struct ValueBarAggregator {
+    core: BarAggregatorCore,
+    cum_value: f64,
+}
+
+impl ValueBarAggregator {
+    /// Creates a new [`ValueBarAggregator`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[BarAggregation::Value])?;
+        Ok(Self {
+            core,
+            cum_value: 0.0,
+        })
+    }
+
+    /// Returns the current cumulative value of the aggregator.
+    #[must_use]
+    pub fn get_cumulative_value(&self) -> f64 {
+        self.cum_value
+    }
+}
+
+impl BarAggregator for ValueBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        _aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        let step = self.core.bar_type.spec.step as f64;
+        let mut raw_size_update = size.raw;
+
+        while raw_size_update > 0 {
+            // While there is value to apply
+            let value_update = price.as_f64() * (raw_size_update as f64 / FIXED_SCALAR);
+            if self.cum_value + value_update < step {
+                // Update and break
+                self.cum_value += value_update;
+                self.core.builder.update(
+                    price,
+                    Quantity::from_raw(raw_size_update, size.precision).unwrap(),
+                    ts_event,
+                );
+                break;
+            }
+
+            // Update builder to the step threshold
+            let value_diff = step - self.cum_value;
+            let raw_size_diff = ((raw_size_update as f64 * (value_diff / value_update)).round()
+                as u64)
+                .clamp(1, raw_size_update);
+            self.core.builder.update(
+                price,
+                Quantity::from_raw(raw_size_diff, size.precision).unwrap(),
+                ts_event,
+            );
+
+            // Build a bar and reset builder and cumulative value
+            self.core.build_now_and_send();
+            self.cum_value = 0.0;
+
+            // Decrement the update size
+            raw_size_update -= raw_size_diff;
+        }
+    }
+}
+
+/// Provides a means of building tick, volume or value imbalance bars from trades.
+///
+/// Buyer initiated trades add to the imbalance and seller initiated trades subtract
+/// from it. When the absolute imbalance reaches the step threshold of the bar
+/// specification, then a bar is created and sent to the handler.
+pub struct ImbalanceBarAggregator {
+    core: BarAggregatorCore,
+    imbalance: f64,
+}
+
+impl ImbalanceBarAggregator {
+    /// Creates a new [`ImbalanceBarAggregator`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[
+            BarAggregation::TickImbalance,
+            BarAggregation::VolumeImbalance,
+            BarAggregation::ValueImbalance,
+        ])?;
+        Ok(Self {
+            core,
+            imbalance: 0.0,
+        })
+    }
+
+    /// Returns the current signed imbalance of the aggregator.
+    #[must_use]
+    pub fn get_imbalance(&self) -> f64 {
+        self.imbalance
+    }
+}
+
+impl BarAggregator for ImbalanceBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        self.core.builder.update(price, size, ts_event);
+
+        let sign = match aggressor_side {
+            AggressorSide::Buyer => 1.0,
+            AggressorSide::Seller => -1.0,
+            AggressorSide::NoAggressor => return,
+        };
+
+        let spec = &self.core.bar_type.spec;
+        self.imbalance += sign * update_measure(spec.aggregation, price, size);
+
+        if self.imbalance.abs() >= spec.step as f64 {
+            self.core.build_now_and_send();
+            self.imbalance = 0.0;
+        }
+    }
+}
+
+/// Provides a means of building tick, volume or value runs bars from trades.
+///
+/// A run is a sequence of trades with the same aggressor side. When the ticks,
+/// volume or value of the current run reaches the step threshold of the bar
+/// specification, then a bar is created and sent to the handler.
+pub struct RunsBarAggregator {
+    core: BarAggregatorCore,
+    run_side: Option<AggressorSide>,
+    run_value: f64,
+}
+
+impl RunsBarAggregator {
+    /// Creates a new [`RunsBarAggregator`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[
+            BarAggregation::TickRuns,
+            BarAggregation::VolumeRuns,
+            BarAggregation::ValueRuns,
+        ])?;
+        Ok(Self {
+            core,
+            run_side: None,
+            run_value: 0.0,
+        })
+    }
+
+    /// Returns the ticks, volume or value of the current run.
+    #[must_use]
+    pub fn get_run_value(&self) -> f64 {
+        self.run_value
+    }
+}
+
+impl BarAggregator for RunsBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        self.core.builder.update(price, size, ts_event);
+
+        if aggressor_side == AggressorSide::NoAggressor {
+            return; // Does not contribute to a run
+        }
+
+        if self.run_side != Some(aggressor_side) {
+            // Start a new run
+            self.run_side = Some(aggressor_side);
+            self.run_value = 0.0;
+        }
+
+        let spec = &self.core.bar_type.spec;
+        self.run_value += update_measure(spec.aggregation, price, size);
+
+        if self.run_value >= spec.step as f64 {
+            self.core.build_now_and_send();
+            self.run_side = None;
+            self.run_value = 0.0;
+        }
+    }
+}
+
+/// Provides a means of building time bars from ticks with a clock timer.
+///
+/// When the time reaches the next time interval of the bar specification, then a
+/// bar is created and sent to the handler. The aggregator is driven by its clock timer
+/// when started with [`TimeBarAggregator::start_with_test_clock`] or run with
+/// [`TimeBarAggregator::run_live`], otherwise the time events for its timer must be
+/// passed to [`TimeBarAggregator::on_time_event`].
+///
+/// Intervals are aligned to the UNIX epoch, weeks start on Monday and months on the
+/// first day of the month (UTC). No bars are built until the first update is received.
+pub struct TimeBarAggregator {
+    core: BarAggregatorCore,
+    timer_name: String,
+    build_with_no_updates: bool,
+    timestamp_on_close: bool,
+    callback: Option<EventHandler>,
+    local_callback: Option<Rc<LocalTimeEventCallback>>,
+    live_callback: Option<SafeTimeEventCallback>,
+    stored_open_ns: UnixNanos,
+    next_close_ns: UnixNanos,
+}
+
+impl TimeBarAggregator {
+    /// Creates a new [`TimeBarAggregator`] instance.
+    ///
+    /// If `build_with_no_updates` is false then no bar is built for an interval with no
+    /// updates. If `timestamp_on_close` is true then bars are timestamped at the interval
+    /// close, otherwise at the interval open.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the bar type is not valid for the aggregator or `instrument`.
+    pub fn new(
+        instrument: &dyn Instrument,
+        bar_type: BarType,
+        handler: BarHandler,
+        build_with_no_updates: bool,
+        timestamp_on_close: bool,
+    ) -> anyhow::Result<Self> {
+        let core = BarAggregatorCore::new(instrument, bar_type, handler)?;
+        core.check_aggregation(&[
+            BarAggregation::Millisecond,
+            BarAggregation::Second,
+            BarAggregation::Minute,
+            BarAggregation::Hour,
+            BarAggregation::Day,
+            BarAggregation::Week,
+            BarAggregation::Month,
+        ])?;
+        Ok(Self {
+            core,
+            timer_name: bar_type.to_string(),
+            build_with_no_updates,
+            timestamp_on_close,
+            callback: None,
+            local_callback: None,
+            live_callback: None,
+            stored_open_ns: 0,
+            next_close_ns: 0,
+        })
+    }
+
+    /// Returns the name of the aggregators clock timer.
+    #[must_use]
+    pub fn timer_name(&self) -> &str {
+        &self.timer_name
+    }
+
+    /// Returns the UNIX timestamp (nanoseconds) of the next bar close.
+    #[must_use]
+    pub fn next_close_ns(&self) -> UnixNanos {
+        self.next_close_ns
+    }
+
+    /// Returns the fixed interval (nanoseconds) of the bars, or `None` for month bars.
+    #[must_use]
+    pub fn interval_ns(&self) -> Option<u64> {
+        let step = self.core.bar_type.spec.step as u64;
+        match self.core.bar_type.spec.aggregation {
+            BarAggregation::Millisecond => Some(step * NANOSECONDS_IN_MILLISECOND),
+            BarAggregation::Second => Some(step * NANOSECONDS_IN_SECOND),
+            BarAggregation::Minute => Some(step * 60 * NANOSECONDS_IN_SECOND),
+            BarAggregation::Hour => Some(step * 60 * 60 * NANOSECONDS_IN_SECOND),
+            BarAggregation::Day => Some(step * NANOSECONDS_IN_DAY),
+            BarAggregation::Week => Some(step * 7 * NANOSECONDS_IN_DAY),
+            _ => None,
+        }
+    }
+
+    /// Returns the UNIX timestamp (nanoseconds) for the start of the bar containing `now_ns`.
+    #[must_use]
+    pub fn get_start_time_ns(&self, now_ns: UnixNanos) -> UnixNanos {
+        match self.interval_ns() {
+            Some(interval_ns) if self.core.bar_type.spec.aggregation == BarAggregation::Week => {
+                now_ns - now_ns.saturating_sub(WEEK_START_OFFSET_NS) % interval_ns
+            }
+            Some(interval_ns) => now_ns - now_ns % interval_ns,
+            None => {
+                let month = month_index(now_ns);
+                month_start_ns(month - month % self.core.bar_type.spec.step as i64)
+            }
+        }
+    }
+
+    /// Starts the aggregator by setting its timer on the given `clock`.
+    ///
+    /// The optional `callback` is used for the timer events, otherwise the clocks
+    /// default handler is used.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `callback` is `None` and the clock has no default handler.
+    pub fn start(&mut self, clock: &mut dyn Clock, callback: Option<EventHandler>) {
+        self.callback = callback;
+        self.local_callback = None;
+        self.live_callback = None;
+
+        match self.init_interval(clock.timestamp_ns()) {
+            Some(interval_ns) => clock.set_timer_ns(
+                &self.timer_name,
+                interval_ns,
+                self.stored_open_ns,
+                None,
+                self.callback.clone(),
+            ),
+            None => {
+                clock.set_time_alert_ns(&self.timer_name, self.next_close_ns, self.callback.clone())
+            }
+        }
+
+        log::debug!("Started timer {}", self.timer_name);
+    }
+
+    /// Starts the `aggregator` by setting its timer on the given test `clock`, with the
+    /// timer events handled by the aggregator itself.
+    ///
+    /// The events are handled by the clocks local handlers (see `TestClock::match_local_handlers`),
+    /// which must be called while neither the aggregator nor the clock is borrowed.
+    pub fn start_with_test_clock(aggregator: &Rc<RefCell<Self>>, clock: &Rc<RefCell<TestClock>>) {
+        let aggregator_weak = Rc::downgrade(aggregator);
+        let clock_weak = Rc::downgrade(clock);
+        let callback: Rc<LocalTimeEventCallback> = Rc::new(move |event: &TimeEvent| {
+            let (Some(aggregator), Some(clock)) = (aggregator_weak.upgrade(), clock_weak.upgrade())
+            else {
+                return; // Aggregator or clock dropped
+            };
+            let mut aggregator = aggregator.borrow_mut();
+            let now_ns = clock.borrow().timestamp_ns();
+            if let Some(alert_time_ns) = aggregator.close_interval(event, now_ns) {
+                if let Some(callback) = aggregator.local_callback.clone() {
+                    clock.borrow_mut().set_local_time_alert_ns(
+                        &aggregator.timer_name,
+                        alert_time_ns,
+                        callback,
+                    );
+                }
+            }
+        });
+
+        let mut aggregator = aggregator.borrow_mut();
+        let mut clock = clock.borrow_mut();
+        aggregator.callback = None;
+        aggregator.local_callback = Some(callback.clone());
+        aggregator.live_callback = None;
+
+        match aggregator.init_interval(clock.timestamp_ns()) {
+            Some(interval_ns) => clock.set_local_timer_ns(
+                &aggregator.timer_name,
+                interval_ns,
+                aggregator.stored_open_ns,
+                None,
+                callback,
+            ),
+            None => clock.set_local_time_alert_ns(
+                &aggregator.timer_name,
+                aggregator.next_close_ns,
+                callback,
+            ),
+        }
+
+        log::debug!("Started timer {}", aggregator.timer_name);
+    }
+
+    /// Runs the `aggregator` with its timer set on the given live `clock`, handling the
+    /// timer events on the current thread until the aggregator is stopped.
+    ///
+    /// The timer events are generated on the runtime of the clock and sent to this task
+    /// by a Rust-native callback, so the aggregator is never shared with another thread.
+    pub async fn run_live(aggregator: Rc<RefCell<Self>>, clock: Rc<RefCell<LiveClock>>) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<TimeEvent>();
+        let callback = SafeTimeEventCallback {
+            callback: std::sync::Arc::new(move |event| {
+                let _ = tx.send(event); // Receiver dropped when no longer running
+            }),
+        };
+
+        {
+            let mut aggregator = aggregator.borrow_mut();
+            let mut clock = clock.borrow_mut();
+            aggregator.callback = None;
+            aggregator.local_callback = None;
+            aggregator.live_callback = Some(callback.clone());
+
+            match aggregator.init_interval(clock.timestamp_ns()) {
+                Some(interval_ns) => clock.set_rust_timer_ns(
+                    &aggregator.timer_name,
+                    interval_ns,
+                    aggregator.stored_open_ns,
+                    None,
+                    callback,
+                ),
+                None => clock.set_rust_time_alert_ns(
+                    &aggregator.timer_name,
+                    aggregator.next_close_ns,
+                    callback,
+                ),
+            }
+
+            log::debug!("Started timer {}", aggregator.timer_name);
+        }
+
+        // The channel hangs up once the timer is canceled and the callbacks dropped
+        while let Some(event) = rx.recv().await {
+            let mut aggregator = aggregator.borrow_mut();
+            let now_ns = clock.borrow().timestamp_ns();
+            if let Some(alert_time_ns) = aggregator.close_interval(&event, now_ns) {
+                if let Some(callback) = aggregator.live_callback.clone() {
+                    clock.borrow_mut().set_rust_time_alert_ns(
+                        &aggregator.timer_name,
+                        alert_time_ns,
+                        callback,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Stops the aggregator by canceling its timer on the given `clock`.
+    pub fn stop(&mut self, clock: &mut dyn Clock) {
+        clock.cancel_timer(&self.timer_name);
+        self.callback = None;
+        self.local_callback = None;
+        self.live_callback = None;
+    }
+
+    /// Handles the given time `event` from the aggregators timer, building and
+    /// sending the bar for the closed interval.
+    ///
+    /// Events for other timers are ignored.
+    pub fn on_time_event(&mut self, event: &TimeEvent, clock: &mut dyn Clock) {
+        if let Some(alert_time_ns) = self.close_interval(event, clock.timestamp_ns()) {
+            clock.set_time_alert_ns(&self.timer_name, alert_time_ns, self.callback.clone());
+        }
+    }
+
+    /// Initializes the first interval from `now_ns`, returning the fixed interval
+    /// (nanoseconds) for the timer, or `None` if an alert must be set for the next close.
+    fn init_interval(&mut self, now_ns: UnixNanos) -> Option<u64> {
+        self.stored_open_ns = self.get_start_time_ns(now_ns);
+
+        let interval_ns = self.interval_ns();
+        self.next_close_ns = match interval_ns {
+            Some(interval_ns) => self.stored_open_ns + interval_ns,
+            None => self.next_month_close_ns(self.stored_open_ns, now_ns),
+        };
+        interval_ns
+    }
+
+    /// Closes the interval for the given time `event`, returning the time of the next
+    /// alert to set for the timer (if any).
+    fn close_interval(&mut self, event: &TimeEvent, now_ns: UnixNanos) -> Option<UnixNanos> {
+        if event.name.as_str() != self.timer_name {
+            return None;
+        }
+
+        let builder = &self.core.builder;
+        if builder.initialized && (self.build_with_no_updates || builder.count > 0) {
+            let ts_event = if self.timestamp_on_close {
+                event.ts_event
+            } else {
+                self.stored_open_ns
+            };
+            self.core.build_and_send(ts_event, event.ts_event);
+        }
+
+        // Close time becomes the next open time
+        self.stored_open_ns = event.ts_event;
+
+        match self.interval_ns() {
+            Some(interval_ns) => {
+                self.next_close_ns = event.ts_event + interval_ns;
+                None
+            }
+            None => {
+                self.next_close_ns = self.next_month_close_ns(event.ts_event, now_ns);
+                Some(self.next_close_ns)
+            }
+        }
+    }
+
+    fn next_month_close_ns(&self, open_ns: UnixNanos, now_ns: UnixNanos) -> UnixNanos {
+        let step = self.core.bar_type.spec.step as i64;
+        let mut month = month_index(open_ns) + step;
+        let mut close_ns = month_start_ns(month);
+
+        // Skip any closes already passed (alerts cannot be set in the past)
+        while close_ns <= now_ns {
+            month += step;
+            close_ns = month_start_ns(month);
+        }
+        close_ns
+    }
+}
+
+impl BarAggregator for TimeBarAggregator {
+    impl_bar_aggregator_core!();
+
+    fn update(
+        &mut self,
+        price: Price,
+        size: Quantity,
+        _aggressor_side: AggressorSide,
+        ts_event: UnixNanos,
+    ) {
+        self.core.builder.update(price, size, ts_event);
+    }
+}
+
+/// Returns the number of months since year zero for the given UNIX timestamp.
+fn month_index(timestamp_ns: UnixNanos) -> i64 {
+    let datetime = DateTime::from_timestamp_nanos(timestamp_ns as i64);
+    i64::from(datetime.year()) * 12 + i64::from(datetime.month0())
+}
+
+/// Returns the UNIX timestamp (nanoseconds) for the start of the given month index.
+fn month_start_ns(month_index: i64) -> UnixNanos {
+    NaiveDate::from_ymd_opt((month_index / 12) as i32, (month_index % 12) as u32 + 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|datetime| datetime.and_utc().timestamp_nanos_opt())
+        .expect("Month start out of range for UNIX nanoseconds") as UnixNanos
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+    use nautilus_model::{
+        data::bar::BarSpecification,
+        enums::{AggregationSource, PriceType},
+        identifiers::trade_id::TradeId,
+        instruments::{currency_pair::CurrencyPair, stubs::audusd_sim},
+    };
+    use rstest::rstest;
+
+    use super::*;
+    use crate::clock::TestClock;
+
+    #[cfg(feature = "python")]
+    fn stub_event_handler() -> EventHandler {
+        pyo3::prepare_freethreaded_python();
+        pyo3::Python::with_gil(|py| EventHandler::new(py.None()))
+    }
+
+    #[cfg(not(feature = "python"))]
+    fn stub_event_handler() -> EventHandler {
+        EventHandler::new(crate::handlers::SafeTimeEventCallback {
+            callback: std::sync::Arc::new(|_| {}),
+        })
+    }
+
+    fn bar_type(aggregation: BarAggregation, step: usize) -> BarType {
+        BarType::new(
+            audusd_sim().id,
+            BarSpecification::new(step, aggregation, PriceType::Last),
+            AggregationSource::Internal,
+        )
+    }
+
+    fn bar_handler() -> (BarHandler, Rc<RefCell<Vec<Bar>>>) {
+        let bars = Rc::new(RefCell::new(Vec::new()));
+        let bars_clone = bars.clone();
+        let handler = Box::new(move |bar| bars_clone.borrow_mut().push(bar));
+        (handler, bars)
+    }
+
+    fn trade(price: &str, size: &str, aggressor_side: AggressorSide, ts: UnixNanos) -> TradeTick {
+        TradeTick::new(
+            audusd_sim().id,
+            Price::from(price),
+            Quantity::from(size),
+            aggressor_side,
+            TradeId::new("1").unwrap(),
+            ts,
+            ts,
+        )
+    }
+
+    #[rstest]
+    fn test_builder_update_and_build(audusd_sim: CurrencyPair) {
+        let mut builder = BarBuilder::new(&audusd_sim, bar_type(BarAggregation::Tick, 3)).unwrap();
+
+        builder.update(Price::from("1.00001"), Quantity::from(1), 1);
+        builder.update(Price::from("1.00003"), Quantity::from(1), 2);
+        builder.update(Price::from("0.99999"), Quantity::from(1), 3);
+        builder.update(Price::from("1.00000"), Quantity::from(1), 0); // Ignored
+
+        assert!(builder.initialized());
+        assert_eq!(builder.count(), 3);
+
+        let bar = builder.build_now();
+
+        assert_eq!(bar.open, Price::from("1.00001"));
+        assert_eq!(bar.high, Price::from("1.00003"));
+        assert_eq!(bar.low, Price::from("0.99999"));
+        assert_eq!(bar.close, Price::from("0.99999"));
+        assert_eq!(bar.volume, Quantity::from(3));
+        assert_eq!(bar.ts_event, 3);
+        assert_eq!(builder.count(), 0);
+    }
+
+    #[rstest]
+    fn test_builder_build_with_no_updates_uses_last_close(audusd_sim: CurrencyPair) {
+        let mut builder = BarBuilder::new(&audusd_sim, bar_type(BarAggregation::Tick, 3)).unwrap();
+        builder.update(Price::from("1.00001"), Quantity::from(1), 1);
+        builder.update(Price::from("1.00002"), Quantity::from(1), 1);
+        builder.build_now();
+
+        let bar = builder.build(10, 10);
+
+        assert_eq!(bar.open, Price::from("1.00002"));
+        assert_eq!(bar.high, Price::from("1.00002"));
+        assert_eq!(bar.low, Price::from("1.00002"));
+        assert_eq!(bar.close, Price::from("1.00002"));
+        assert_eq!(bar.volume, Quantity::from(0));
+    }
+
+    #[rstest]
+    fn test_builder_set_partial(audusd_sim: CurrencyPair) {
+        let bar_type = bar_type(BarAggregation::Tick, 3);
+        let mut builder = BarBuilder::new(&audusd_sim, bar_type).unwrap();
+        let partial_bar = Bar::new(
+            bar_type,
+            Price::from("1.00001"),
+            Price::from("1.00010"),
+            Price::from("1.00000"),
+            Price::from("1.00002"),
+            Quantity::from(5),
+            1,
+            1,
+        );
+
+        builder.set_partial(partial_bar);
+        builder.update(Price::from("1.00020"), Quantity::from(1), 2);
+        let bar = builder.build_now();
+
+        assert_eq!(bar.open, Price::from("1.00001"));
+        assert_eq!(bar.high, Price::from("1.00020"));
+        assert_eq!(bar.low, Price::from("1.00000"));
+        assert_eq!(bar.close, Price::from("1.00020"));
+        assert_eq!(bar.volume, Quantity::from(6));
+    }
+
+    #[rstest]
+    fn test_builder_with_mismatched_instrument() {
+        let bar_type = BarType::from_str("ETHUSDT.BINANCE-1-TICK-LAST-INTERNAL").unwrap();
+
+        assert!(BarBuilder::new(&audusd_sim(), bar_type).is_err());
+    }
+
+    #[rstest]
+    fn test_aggregator_with_invalid_aggregation(audusd_sim: CurrencyPair) {
+        let (handler, _) = bar_handler();
+
+        assert!(
+            TickBarAggregator::new(&audusd_sim, bar_type(BarAggregation::Volume, 1), handler)
+                .is_err()
+        );
+    }
+
+    #[rstest]
+    fn test_tick_bar_aggregator(audusd_sim: CurrencyPair) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            TickBarAggregator::new(&audusd_sim, bar_type(BarAggregation::Tick, 3), handler)
+                .unwrap();
+
+        for i in 0..7 {
+            aggregator.handle_trade_tick(trade("1.00001", "1", AggressorSide::Buyer, i));
+        }
+
+        assert_eq!(bars.borrow().len(), 2);
+        assert_eq!(bars.borrow()[1].volume, Quantity::from(3));
+        assert_eq!(bars.borrow()[1].ts_event, 5);
+    }
+
+    #[rstest]
+    fn test_tick_bar_aggregator_awaiting_partial(audusd_sim: CurrencyPair) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            TickBarAggregator::new(&audusd_sim, bar_type(BarAggregation::Tick, 1), handler)
+                .unwrap();
+        aggregator.set_await_partial(true);
+
+        aggregator.handle_trade_tick(trade("1.00001", "1", AggressorSide::Buyer, 1));
+
+        assert!(bars.borrow().is_empty());
+    }
+
+    #[rstest]
+    fn test_tick_bar_aggregator_with_quotes(audusd_sim: CurrencyPair) {
+        let (handler, bars) = bar_handler();
+        let bar_type = BarType::new(
+            audusd_sim.id,
+            BarSpecification::new(1, BarAggregation::Tick, PriceType::Mid),
+            AggregationSource::Internal,
+        );
+        let mut aggregator = TickBarAggregator::new(&audusd_sim, bar_type, handler).unwrap();
+        let quote = QuoteTick::new(
+            audusd_sim.id,
+            Price::from("1.00001"),
+            Price::from("1.00003"),
+            Quantity::from(1),
+            Quantity::from(3),
+            1,
+            1,
+        )
+        .unwrap();
+
+        aggregator.handle_quote_tick(quote);
+
+        assert_eq!(bars.borrow().len(), 1);
+        assert_eq!(bars.borrow()[0].close, Price::from("1.000020"));
+        assert_eq!(bars.borrow()[0].volume, Quantity::from(2));
+    }
+
+    #[rstest]
+    fn test_volume_bar_aggregator_splits_updates(audusd_sim: CurrencyPair) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            VolumeBarAggregator::new(&audusd_sim, bar_type(BarAggregation::Volume, 10), handler)
+                .unwrap();
+
+        aggregator.handle_trade_tick(trade("1.00001", "3", AggressorSide::Buyer, 1));
+        aggregator.handle_trade_tick(trade("1.00002", "25", AggressorSide::Buyer, 2));
+
+        let bars = bars.borrow();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, Price::from("1.00001"));
+        assert_eq!(bars[0].close, Price::from("1.00002"));
+        assert_eq!(bars[0].volume, Quantity::from(10));
+        assert_eq!(bars[1].volume, Quantity::from(10));
+    }
+
+    #[rstest]
+    fn test_value_bar_aggregator_splits_updates(audusd_sim: CurrencyPair) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            ValueBarAggregator::new(&audusd_sim, bar_type(BarAggregation::Value, 100), handler)
+                .unwrap();
+
+        aggregator.handle_trade_tick(trade("2.00000", "20", AggressorSide::Buyer, 1));
+        aggregator.handle_trade_tick(trade("4.00000", "30", AggressorSide::Buyer, 2));
+
+        let bars = bars.borrow();
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].volume, Quantity::from(35));
+        assert_eq!(aggregator.get_cumulative_value(), 60.0);
+    }
+
+    #[rstest]
+    #[case(BarAggregation::TickImbalance, 2)]
+    #[case(BarAggregation::VolumeImbalance, 20)]
+    #[case(BarAggregation::ValueImbalance, 20)]
+    fn test_imbalance_bar_aggregator(
+        audusd_sim: CurrencyPair,
+        #[case] aggregation: BarAggregation,
+        #[case] step: usize,
+    ) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            ImbalanceBarAggregator::new(&audusd_sim, bar_type(aggregation, step), handler).unwrap();
+
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Buyer, 1));
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Seller, 2));
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::NoAggressor, 3));
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Seller, 4));
+
+        assert!(bars.borrow().is_empty());
+
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Seller, 5));
+
+        assert_eq!(bars.borrow().len(), 1);
+        assert_eq!(bars.borrow()[0].volume, Quantity::from(50));
+        assert_eq!(aggregator.get_imbalance(), 0.0);
+    }
+
+    #[rstest]
+    #[case(BarAggregation::TickRuns, 2)]
+    #[case(BarAggregation::VolumeRuns, 20)]
+    #[case(BarAggregation::ValueRuns, 20)]
+    fn test_runs_bar_aggregator(
+        audusd_sim: CurrencyPair,
+        #[case] aggregation: BarAggregation,
+        #[case] step: usize,
+    ) {
+        let (handler, bars) = bar_handler();
+        let mut aggregator =
+            RunsBarAggregator::new(&audusd_sim, bar_type(aggregation, step), handler).unwrap();
+
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Buyer, 1));
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Seller, 2));
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::NoAggressor, 3));
+
+        assert!(bars.borrow().is_empty());
+
+        aggregator.handle_trade_tick(trade("1.00000", "10", AggressorSide::Seller, 4));
+
+        assert_eq!(bars.borrow().len(), 1);
+        assert_eq!(bars.borrow()[0].volume, Quantity::from(40));
+        assert_eq!(aggregator.get_run_value(), 0.0);
+    }
+
+    #[rstest]
+    #[case(
+        BarAggregation::Millisecond,
+        500,
+        1_234_567_890_123_456_789,
+        1_234_567_890_000_000_000
+    )]
+    #[case(
+        BarAggregation::Second,
+        20,
+        1_234_567_890_123_456_789,
+        1_234_567_880_000_000_000
+    )]
+    #[case(
+        BarAggregation::Minute,
+        5,
+        1_234_567_890_123_456_789,
+        1_234_567_800_000_000_000
+    )]
+    #[case(
+        BarAggregation::Hour,
+        1,
+        1_234_567_890_123_456_789,
+        1_234_566_000_000_000_000
+    )]
+    #[case(
+        BarAggregation::Day,
+        1,
+        1_234_567_890_123_456_789,
+        1_234_483_200_000_000_000
+    )]
+    #[case(
+        BarAggregation::Week,
+        1,
+        1_234_567_890_123_456_789,
+        1_234_137_600_000_000_000
+    )]
+    #[case(
+        BarAggregation::Month,
+        1,
+        1_234_567_890_123_456_789,
+        1_233_446_400_000_000_000
+    )]
+    #[case(
+        BarAggregation::Month,
+        3,
+        1_234_567_890_123_456_789,
+        1_230_768_000_000_000_000
+    )]
+    fn test_time_bar_aggregator_start_time(
+        audusd_sim: CurrencyPair,
+        #[case] aggregation: BarAggregation,
+        #[case] step: usize,
+        #[case] now_ns: UnixNanos,
+        #[case] expected: UnixNanos,
+    ) {
+        let (handler, _) = bar_handler();
+        let aggregator = TimeBarAggregator::new(
+            &audusd_sim,
+            bar_type(aggregation, step),
+            handler,
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(aggregator.get_start_time_ns(now_ns), expected);
+    }
+
+    #[rstest]
+    #[case(true, true, vec![(60, 60), (120, 120)])]
+    #[case(true, false, vec![(0, 60), (60, 120)])]
+    #[case(false, true, vec![(60, 60)])]
+    fn test_time_bar_aggregator_with_test_clock(
+        audusd_sim: CurrencyPair,
+        #[case] build_with_no_updates: bool,
+        #[case] timestamp_on_close: bool,
+        #[case] expected: Vec<(u64, u64)>,
+    ) {
+        let secs = NANOSECONDS_IN_SECOND;
+        let mut clock = TestClock::new();
+        clock.set_time(10 * secs);
+        let (handler, bars) = bar_handler();
+        let mut aggregator = TimeBarAggregator::new(
+            &audusd_sim,
+            bar_type(BarAggregation::Minute, 1),
+            handler,
+            build_with_no_updates,
+            timestamp_on_close,
+        )
+        .unwrap();
+        aggregator.start(&mut clock, Some(stub_event_handler()));
+
+        assert_eq!(aggregator.next_close_ns(), 60 * secs);
+
+        aggregator.handle_trade_tick(trade("1.00001", "1", AggressorSide::Buyer, 20 * secs));
+        aggregator.handle_trade_tick(trade("1.00002", "1", AggressorSide::Buyer, 30 * secs));
+        for event in clock.advance_time(130 * secs, true) {
+            aggregator.on_time_event(&event, &mut clock);
+        }
+
+        let timestamps: Vec<(u64, u64)> = bars
+            .borrow()
+            .iter()
+            .map(|bar| (bar.ts_event / secs, bar.ts_init / secs))
+            .collect();
+        assert_eq!(timestamps, expected);
+        assert_eq!(bars.borrow()[0].close, Price::from("1.00002"));
+        assert_eq!(bars.borrow()[0].volume, Quantity::from(2));
+        assert_eq!(aggregator.next_close_ns(), 180 * secs);
+
+        aggregator.stop(&mut clock);
+
+        assert_eq!(clock.timer_count(), 0);
+    }
+
+    #[rstest]
+    fn test_time_bar_aggregator_month_bars(audusd_sim: CurrencyPair) {
+        let jan_15 = 1_705_276_800_000_000_000; // 2024-01-15
+        let feb_01 = 1_706_745_600_000_000_000; // 2024-02-01
+        let mar_01 = 1_709_251_200_000_000_000; // 2024-03-01
+        let apr_01 = 1_711_929_600_000_000_000; // 2024-04-01
+        let mut clock = TestClock::new();
+        clock.set_time(jan_15);
+        let (handler, bars) = bar_handler();
+        let mut aggregator = TimeBarAggregator::new(
+            &audusd_sim,
+            bar_type(BarAggregation::Month, 1),
+            handler,
+            true,
+            true,
+        )
+        .unwrap();
+        aggregator.start(&mut clock, Some(stub_event_handler()));
+        aggregator.handle_trade_tick(trade("1.00001", "1", AggressorSide::Buyer, jan_15));
+
+        for ts in [feb_01, mar_01] {
+            for event in clock.advance_time(ts, true) {
+                aggregator.on_time_event(&event, &mut clock);
+            }
+        }
+
+        let timestamps: Vec<UnixNanos> = bars.borrow().iter().map(|bar| bar.ts_event).collect();
+        assert_eq!(timestamps, vec![feb_01, mar_01]);
+        assert_eq!(aggregator.next_close_ns(), apr_01);
+    }
+
+    #[rstest]
+    #[case(BarAggregation::Minute, 10_000_000_000, vec![130_000_000_000], vec![60_000_000_000, 120_000_000_000])]
+    #[case(
+        BarAggregation::Month,
+        1_705_276_800_000_000_000, // 2024-01-15
+        vec![1_706_745_600_000_000_000, 1_709_251_200_000_000_000],
+        vec![1_706_745_600_000_000_000, 1_709_251_200_000_000_000],
+    )]
+    fn test_time_bar_aggregator_driven_by_test_clock_timer(
+        audusd_sim: CurrencyPair,
+        #[case] aggregation: BarAggregation,
+        #[case] start_ns: UnixNanos,
+        #[case] advance_to: Vec<UnixNanos>,
+        #[case] expected: Vec<UnixNanos>,
+    ) {
+        let clock = Rc::new(RefCell::new(TestClock::new()));
+        clock.borrow_mut().set_time(start_ns);
+        let (handler, bars) = bar_handler();
+        let aggregator = Rc::new(RefCell::new(
+            TimeBarAggregator::new(&audusd_sim, bar_type(aggregation, 1), handler, true, true)
+                .unwrap(),
+        ));
+        TimeBarAggregator::start_with_test_clock(&aggregator, &clock);
+        aggregator.borrow_mut().handle_trade_tick(trade(
+            "1.00001",
+            "1",
+            AggressorSide::Buyer,
+            start_ns,
+        ));
+
+        for ts in advance_to {
+            let events = clock.borrow_mut().advance_time(ts, true);
+            let handlers = clock.borrow().match_local_handlers(&events);
+            for handler in handlers {
+                handler.handle();
+            }
+        }
+
+        let timestamps: Vec<UnixNanos> = bars.borrow().iter().map(|bar| bar.ts_event).collect();
+        assert_eq!(timestamps, expected);
+
+        aggregator.borrow_mut().stop(&mut *clock.borrow_mut());
+
+        assert_eq!(clock.borrow().timer_count(), 0);
+    }
+
+    #[rstest]
+    fn test_time_bar_aggregator_run_live_until_stopped(audusd_sim: CurrencyPair) {
+        use std::time::Duration;
+
+        let clock = Rc::new(RefCell::new(LiveClock::new()));
+        let (handler, bars) = bar_handler();
+        let aggregator = Rc::new(RefCell::new(
+            TimeBarAggregator::new(
+                &audusd_sim,
+                bar_type(BarAggregation::Millisecond, 10),
+                handler,
+                true,
+                true,
+            )
+            .unwrap(),
+        ));
+        let ts_now = clock.borrow().timestamp_ns();
+        aggregator.borrow_mut().handle_trade_tick(trade(
+            "1.00001",
+            "1",
+            AggressorSide::Buyer,
+            ts_now,
+        ));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async {
+            let task = tokio::task::spawn_local(TimeBarAggregator::run_live(
+                aggregator.clone(),
+                clock.clone(),
+            ));
+            while bars.borrow().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            aggregator.borrow_mut().stop(&mut *clock.borrow_mut());
+            tokio::time::timeout(Duration::from_secs(5), task)
+                .await
+                .unwrap()
+                .unwrap();
+        });
+
+        let bars = bars.borrow();
+        assert_eq!(
+            bars[1].ts_event - bars[0].ts_event,
+            10 * NANOSECONDS_IN_MILLISECOND
+        );
+        assert_eq!(clock.borrow().timer_count(), 0);
+    }
+}
@@ -16,30 +16,57 @@
 #![allow(dead_code)] // Under development
 
 use std::{
+    cell::RefCell,
+    cmp,
     collections::{HashMap, HashSet, VecDeque},
     sync::mpsc::Receiver,
 };
 
+use log::{debug, error, info};
 use nautilus_core::uuid::UUID4;
 use nautilus_model::{
     data::{
         bar::{Bar, BarType},
+        delta::OrderBookDelta,
+        deltas::OrderBookDeltas,
+        depth::OrderBookDepth10,
         quote::QuoteTick,
         trade::TradeTick,
     },
+    enums::{CurrencyType, PriceType, TriggerType},
+    events::{account::state::AccountState, order::event::OrderEvent},
     identifiers::{
         account_id::AccountId, client_id::ClientId, client_order_id::ClientOrderId,
         component_id::ComponentId, exec_algorithm_id::ExecAlgorithmId, instrument_id::InstrumentId,
         position_id::PositionId, strategy_id::StrategyId, symbol::Symbol, trader_id::TraderId,
         venue::Venue, venue_order_id::VenueOrderId,
     },
-    instruments::{synthetic::SyntheticInstrument, Instrument},
-    orders::base::Order,
+    instruments::{
+        crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair,
+        synthetic::SyntheticInstrument, Instrument, InstrumentType,
+    },
+    orderbook::book::OrderBook,
+    orders::any::OrderAny,
     position::Position,
-    types::currency::Currency,
+    types::{currency::Currency, fixed::FIXED_PRECISION, price::Price},
 };
+use serde::{Deserialize, Serialize};
 use ustr::Ustr;
 
+use crate::xrate::{ExchangeRateCalculator, RateGraph};
+
+const DELIMITER: char = ':';
+const GENERAL: &str = "general";
+const CURRENCIES: &str = "currencies";
+const INSTRUMENTS: &str = "instruments";
+const SYNTHETICS: &str = "synthetics";
+const ACCOUNTS: &str = "accounts";
+const ORDERS: &str = "orders";
+const POSITIONS: &str = "positions";
+
+const INDEX_ORDER_POSITION: &str = "index:order_position";
+const INDEX_ORDER_CLIENT: &str = "index:order_client";
+
 /// A type of database operation.
 #[derive(Clone, Debug)]
 pub enum DatabaseOperation {
@@ -76,13 +103,13 @@ impl DatabaseCommand {
 ///
 /// Delete operations may need a `payload` to target specific values.
 pub trait CacheDatabase {
-    type DatabaseType;
-
     fn new(
         trader_id: TraderId,
         instance_id: UUID4,
         config: HashMap<String, serde_json::Value>,
-    ) -> anyhow::Result<Self::DatabaseType>;
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized;
     fn flushdb(&mut self) -> anyhow::Result<()>;
     fn keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>>;
     fn read(&mut self, key: &str) -> anyhow::Result<Vec<Vec<u8>>>;
@@ -93,7 +120,8 @@ pub trait CacheDatabase {
         rx: Receiver<DatabaseCommand>,
         trader_key: String,
         config: HashMap<String, serde_json::Value>,
-    );
+    ) where
+        Self: Sized;
 }
 
 pub struct CacheConfig {
@@ -103,6 +131,18 @@ pub struct CacheConfig {
     pub snapshot_positions: bool,
 }
 
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            tick_capacity: 10_000,
+            bar_capacity: 10_000,
+            snapshot_orders: false,
+            snapshot_positions: false,
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct CacheIndex {
     venue_account: HashMap<Venue, AccountId>,
     venue_orders: HashMap<Venue, HashSet<ClientOrderId>>,
@@ -118,7 +158,7 @@ pub struct CacheIndex {
     strategy_orders: HashMap<StrategyId, HashSet<ClientOrderId>>,
     strategy_positions: HashMap<StrategyId, HashSet<PositionId>>,
     exec_algorithm_orders: HashMap<ExecAlgorithmId, HashSet<ClientOrderId>>,
-    exec_spawn_orders: HashMap<ExecAlgorithmId, HashSet<ClientOrderId>>,
+    exec_spawn_orders: HashMap<ClientOrderId, HashSet<ClientOrderId>>,
     orders: HashSet<ClientOrderId>,
     orders_open: HashSet<ClientOrderId>,
     orders_closed: HashSet<ClientOrderId>,
@@ -133,25 +173,1672 @@ pub struct CacheIndex {
     exec_algorithms: HashSet<ExecAlgorithmId>,
 }
 
+impl CacheIndex {
+    /// Clears the index, with the persisted order to position and order to client
+    /// mappings retained (these cannot be rebuilt from the cached objects alone).
+    fn clear_derived(&mut self) {
+        let order_position = std::mem::take(&mut self.order_position);
+        let order_client = std::mem::take(&mut self.order_client);
+        *self = Self {
+            order_position,
+            order_client,
+            ..Default::default()
+        };
+    }
+}
+
+/// The persisted representation of a currency, so that custom currencies can be
+/// registered again on load.
+#[derive(Serialize, Deserialize)]
+struct CurrencyDef {
+    code: Ustr,
+    precision: u8,
+    iso4217: u16,
+    name: Ustr,
+    currency_type: CurrencyType,
+}
+
 pub struct Cache {
     config: CacheConfig,
     index: CacheIndex,
-    // database: Option<Box<dyn CacheDatabase>>,  TODO
-    // xrate_calculator: ExchangeRateCalculator TODO
+    database: Option<Box<dyn CacheDatabase>>,
+    xrate_calculator: ExchangeRateCalculator,
     general: HashMap<Ustr, Vec<u8>>,
     xrate_symbols: HashMap<InstrumentId, Symbol>,
+    xrate_graphs: RefCell<HashMap<(Venue, PriceType), RateGraph>>,
     quote_ticks: HashMap<InstrumentId, VecDeque<QuoteTick>>,
     trade_ticks: HashMap<InstrumentId, VecDeque<TradeTick>>,
-    // order_books: HashMap<InstrumentId, OrderBook>>,  TODO: Needs single book
+    books: HashMap<InstrumentId, OrderBook>,
     bars: HashMap<BarType, VecDeque<Bar>>,
     bars_bid: HashMap<BarType, Bar>,
     bars_ask: HashMap<BarType, Bar>,
     currencies: HashMap<Ustr, Currency>,
     instruments: HashMap<InstrumentId, Box<dyn Instrument>>,
     synthetics: HashMap<InstrumentId, SyntheticInstrument>,
-    // accounts: HashMap<AccountId, Box<dyn Account>>,  TODO: Decide where trait should go
-    orders: HashMap<ClientOrderId, VecDeque<Box<dyn Order>>>, // TODO: Efficency (use enum)
+    // The `Account` trait lives downstream in `nautilus-accounting`, so accounts are
+    // cached as their state events (from which any account can be rebuilt)
+    accounts: HashMap<AccountId, Vec<AccountState>>,
+    orders: HashMap<ClientOrderId, OrderAny>,
     // order_lists: HashMap<OrderListId, VecDeque<OrderList>>,  TODO: Need `OrderList`
     positions: HashMap<PositionId, Position>,
     position_snapshots: HashMap<PositionId, Vec<u8>>,
 }
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(CacheConfig::default(), None)
+    }
+}
+
+impl Cache {
+    /// Creates a new cache, backed by the given `database` (if any).
+    ///
+    /// No state is loaded from the database until [`Cache::cache_all`] (or the
+    /// individual `cache_*` methods) are called.
+    #[must_use]
+    pub fn new(config: CacheConfig, database: Option<Box<dyn CacheDatabase>>) -> Self {
+        Self {
+            config,
+            index: CacheIndex::default(),
+            database,
+            xrate_calculator: ExchangeRateCalculator::new(),
+            general: HashMap::new(),
+            xrate_symbols: HashMap::new(),
+            xrate_graphs: RefCell::new(HashMap::new()),
+            quote_ticks: HashMap::new(),
+            trade_ticks: HashMap::new(),
+            books: HashMap::new(),
+            bars: HashMap::new(),
+            bars_bid: HashMap::new(),
+            bars_ask: HashMap::new(),
+            currencies: HashMap::new(),
+            instruments: HashMap::new(),
+            synthetics: HashMap::new(),
+            accounts: HashMap::new(),
+            orders: HashMap::new(),
+            positions: HashMap::new(),
+            position_snapshots: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn has_backing(&self) -> bool {
+        self.database.is_some()
+    }
+
+    // -- COMMANDS --------------------------------------------------------------------------------
+
+    /// Loads the full cache state from the database, then builds the index.
+    pub fn cache_all(&mut self) -> anyhow::Result<()> {
+        self.cache_general()?;
+        self.cache_currencies()?;
+        self.cache_instruments()?;
+        self.cache_synthetics()?;
+        self.cache_accounts()?;
+        self.cache_orders()?;
+        self.cache_positions()?;
+        self.build_index();
+        Ok(())
+    }
+
+    /// Loads the general objects from the database.
+    pub fn cache_general(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), GENERAL)? {
+            if let Some(value) = database.read(&key)?.pop() {
+                let name = &key[GENERAL.len() + 1..];
+                self.general.insert(Ustr::from(name), value);
+            }
+        }
+
+        info!(
+            "Cached {} general object(s) from database",
+            self.general.len()
+        );
+        Ok(())
+    }
+
+    /// Loads the currencies from the database, registering each.
+    pub fn cache_currencies(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), CURRENCIES)? {
+            let Some(payload) = database.read(&key)?.pop() else {
+                continue;
+            };
+            match decode_currency(&payload) {
+                Ok(currency) => {
+                    Currency::register(currency, false)?;
+                    self.currencies.insert(currency.code, currency);
+                }
+                Err(e) => error!("Failed to decode currency for '{key}': {e}"),
+            }
+        }
+
+        info!("Cached {} currencies from database", self.currencies.len());
+        Ok(())
+    }
+
+    /// Loads the instruments from the database.
+    pub fn cache_instruments(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), INSTRUMENTS)? {
+            let Some(payload) = database.read(&key)?.pop() else {
+                continue;
+            };
+            match serde_json::from_slice::<InstrumentType>(&payload) {
+                Ok(instrument) => {
+                    let instrument = instrument.into_boxed();
+                    if let Some(symbol) = Self::xrate_symbol(instrument.as_ref()) {
+                        self.xrate_symbols.insert(instrument.id(), symbol);
+                        self.xrate_graphs.get_mut().clear();
+                    }
+                    self.instruments.insert(instrument.id(), instrument);
+                }
+                Err(e) => error!("Failed to decode instrument for '{key}': {e}"),
+            }
+        }
+
+        info!(
+            "Cached {} instrument(s) from database",
+            self.instruments.len()
+        );
+        Ok(())
+    }
+
+    /// Loads the synthetic instruments from the database.
+    pub fn cache_synthetics(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), SYNTHETICS)? {
+            let Some(payload) = database.read(&key)?.pop() else {
+                continue;
+            };
+            match serde_json::from_slice::<SyntheticInstrument>(&payload) {
+                Ok(synthetic) => {
+                    self.synthetics.insert(synthetic.id, synthetic);
+                }
+                Err(e) => error!("Failed to decode synthetic instrument for '{key}': {e}"),
+            }
+        }
+
+        info!(
+            "Cached {} synthetic instrument(s) from database",
+            self.synthetics.len()
+        );
+        Ok(())
+    }
+
+    /// Loads the account state events for each account from the database.
+    pub fn cache_accounts(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), ACCOUNTS)? {
+            let events = database
+                .read(&key)?
+                .iter()
+                .map(|payload| serde_json::from_slice::<AccountState>(payload))
+                .collect::<Result<Vec<_>, _>>();
+            match events {
+                Ok(events) if !events.is_empty() => {
+                    self.accounts.insert(events[0].account_id, events);
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to decode account state for '{key}': {e}"),
+            }
+        }
+
+        info!("Cached {} account(s) from database", self.accounts.len());
+        Ok(())
+    }
+
+    /// Loads the orders from the database, rebuilding each order from its events.
+    ///
+    /// The persisted order to position and order to client index mappings are also loaded.
+    pub fn cache_orders(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), ORDERS)? {
+            let events = database
+                .read(&key)?
+                .iter()
+                .map(|payload| serde_json::from_slice::<OrderEvent>(payload))
+                .collect::<Result<Vec<_>, _>>();
+            match events
+                .map_err(anyhow::Error::from)
+                .and_then(OrderAny::from_events)
+            {
+                Ok(order) => {
+                    self.orders.insert(order.client_order_id(), order);
+                }
+                Err(e) => error!("Failed to rebuild order for '{key}': {e}"),
+            }
+        }
+
+        for (client_order_id, position_id) in
+            read_index_map(database.as_mut(), INDEX_ORDER_POSITION)?
+        {
+            self.index.order_position.insert(
+                ClientOrderId::new(&client_order_id)?,
+                PositionId::new(&position_id)?,
+            );
+        }
+
+        for (client_order_id, client_id) in read_index_map(database.as_mut(), INDEX_ORDER_CLIENT)? {
+            self.index.order_client.insert(
+                ClientOrderId::new(&client_order_id)?,
+                ClientId::new(&client_id)?,
+            );
+        }
+
+        info!("Cached {} order(s) from database", self.orders.len());
+        Ok(())
+    }
+
+    /// Loads the positions from the database (the latest snapshot of each position).
+    pub fn cache_positions(&mut self) -> anyhow::Result<()> {
+        let Some(database) = self.database.as_mut() else {
+            return Ok(());
+        };
+
+        for key in collection_keys(database.as_mut(), POSITIONS)? {
+            let Some(payload) = database.read(&key)?.pop() else {
+                continue;
+            };
+            match serde_json::from_slice::<Position>(&payload) {
+                Ok(position) => {
+                    self.positions.insert(position.id, position);
+                }
+                Err(e) => error!("Failed to decode position for '{key}': {e}"),
+            }
+        }
+
+        info!("Cached {} position(s) from database", self.positions.len());
+        Ok(())
+    }
+
+    /// Builds the cache index from the currently cached accounts, orders and positions.
+    pub fn build_index(&mut self) {
+        debug!("Building index");
+        self.index.clear_derived();
+
+        for account_id in self.accounts.keys() {
+            let venue = Venue::from_str_unchecked(account_id.get_issuer().as_str());
+            self.index.venue_account.insert(venue, *account_id);
+        }
+
+        for order in self.orders.values() {
+            Self::index_order(&mut self.index, order);
+        }
+
+        let order_position: Vec<(ClientOrderId, PositionId)> = self
+            .index
+            .order_position
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .collect();
+        for (client_order_id, position_id) in order_position {
+            self.index
+                .position_orders
+                .entry(position_id)
+                .or_default()
+                .insert(client_order_id);
+        }
+
+        for position in self.positions.values() {
+            Self::index_position(&mut self.index, position);
+        }
+    }
+
+    /// Flushes the backing database (if any), permanently removing all persisted data.
+    pub fn flush_db(&mut self) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            database.flushdb()?;
+        }
+        info!("Flushed database");
+        Ok(())
+    }
+
+    /// Adds the general `value` at the given `key`.
+    pub fn add(&mut self, key: &str, value: Vec<u8>) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            database.insert(
+                format!("{GENERAL}{DELIMITER}{key}"),
+                Some(vec![value.clone()]),
+            )?;
+        }
+        self.general.insert(Ustr::from(key), value);
+        Ok(())
+    }
+
+    /// Adds the given `currency`.
+    pub fn add_currency(&mut self, currency: Currency) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            let key = format!("{CURRENCIES}{DELIMITER}{}", currency.code);
+            database.insert(key, Some(vec![encode_currency(&currency)?]))?;
+        }
+        self.currencies.insert(currency.code, currency);
+        Ok(())
+    }
+
+    /// Adds the given `instrument`.
+    pub fn add_instrument(&mut self, instrument: Box<dyn Instrument>) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            let Some(instrument_type) = InstrumentType::from_instrument(instrument.as_ref()) else {
+                anyhow::bail!(
+                    "Cannot persist instrument {}: unsupported type",
+                    instrument.id()
+                )
+            };
+            let key = format!("{INSTRUMENTS}{DELIMITER}{}", instrument.id());
+            database.insert(key, Some(vec![serde_json::to_vec(&instrument_type)?]))?;
+        }
+        if let Some(symbol) = Self::xrate_symbol(instrument.as_ref()) {
+            self.xrate_symbols.insert(instrument.id(), symbol);
+            self.invalidate_xrates(instrument.id().venue);
+        }
+        self.instruments.insert(instrument.id(), instrument);
+        Ok(())
+    }
+
+    /// Returns the currency pair symbol for the given `instrument` if it quotes an
+    /// exchange rate, so that its quotes are used to calculate exchange rates.
+    fn xrate_symbol(instrument: &dyn Instrument) -> Option<Symbol> {
+        let any = instrument.as_any();
+        if !any.is::<CurrencyPair>() && !any.is::<CryptoPerpetual>() {
+            return None;
+        }
+        instrument.base_currency().map(|base_currency| {
+            Symbol::from_str_unchecked(&format!(
+                "{}/{}",
+                base_currency.code,
+                instrument.quote_currency().code
+            ))
+        })
+    }
+
+    /// Adds the given `quote` tick, retaining up to the configured tick capacity
+    /// (most recent first).
+    pub fn add_quote_tick(&mut self, quote: QuoteTick) {
+        let capacity = self.config.tick_capacity;
+        let quotes = self
+            .quote_ticks
+            .entry(quote.instrument_id)
+            .or_insert_with(|| VecDeque::with_capacity(capacity));
+        if quotes.len() >= capacity {
+            quotes.pop_back();
+        }
+        quotes.push_front(quote);
+
+        if self.xrate_symbols.contains_key(&quote.instrument_id) {
+            self.invalidate_xrates(quote.instrument_id.venue);
+        }
+    }
+
+    /// Adds the given `quotes` (which are assumed to be in chronological order).
+    pub fn add_quote_ticks(&mut self, quotes: &[QuoteTick]) {
+        for quote in quotes {
+            self.add_quote_tick(*quote);
+        }
+    }
+
+    /// Adds the given `trade` tick, retaining up to the configured tick capacity
+    /// (most recent first).
+    pub fn add_trade_tick(&mut self, trade: TradeTick) {
+        let capacity = self.config.tick_capacity;
+        let trades = self
+            .trade_ticks
+            .entry(trade.instrument_id)
+            .or_insert_with(|| VecDeque::with_capacity(capacity));
+        if trades.len() >= capacity {
+            trades.pop_back();
+        }
+        trades.push_front(trade);
+    }
+
+    /// Adds the given `trades` (which are assumed to be in chronological order).
+    pub fn add_trade_ticks(&mut self, trades: &[TradeTick]) {
+        for trade in trades {
+            self.add_trade_tick(*trade);
+        }
+    }
+
+    /// Adds the given order `book`, replacing any existing book for the instrument.
+    pub fn add_book(&mut self, book: OrderBook) {
+        self.books.insert(book.instrument_id, book);
+    }
+
+    /// Applies the given `delta` to the cached order book for its instrument.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - No order book is cached for the instrument.
+    /// - The book detects a sequence gap.
+    pub fn update_book_delta(&mut self, delta: OrderBookDelta) -> anyhow::Result<()> {
+        self.book_for_update(&delta.instrument_id)?
+            .apply_delta(delta)?;
+        Ok(())
+    }
+
+    /// Applies the given `deltas` to the cached order book for their instrument.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - No order book is cached for the instrument.
+    /// - The book detects a sequence gap.
+    pub fn update_book_deltas(&mut self, deltas: OrderBookDeltas) -> anyhow::Result<()> {
+        self.book_for_update(&deltas.instrument_id)?
+            .apply_deltas(deltas)?;
+        Ok(())
+    }
+
+    /// Applies the given `depth` snapshot to the cached order book for its instrument.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - No order book is cached for the instrument.
+    /// - The book detects a sequence gap.
+    pub fn update_book_depth(&mut self, depth: OrderBookDepth10) -> anyhow::Result<()> {
+        self.book_for_update(&depth.instrument_id)?
+            .apply_depth(depth)?;
+        Ok(())
+    }
+
+    fn book_for_update(&mut self, instrument_id: &InstrumentId) -> anyhow::Result<&mut OrderBook> {
+        match self.books.get_mut(instrument_id) {
+            Some(book) => Ok(book),
+            None => anyhow::bail!("No order book for {instrument_id}"),
+        }
+    }
+
+    /// Adds the given `synthetic` instrument.
+    pub fn add_synthetic(&mut self, synthetic: SyntheticInstrument) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            let key = format!("{SYNTHETICS}{DELIMITER}{}", synthetic.id);
+            database.insert(key, Some(vec![serde_json::to_vec(&synthetic)?]))?;
+        }
+        self.synthetics.insert(synthetic.id, synthetic);
+        Ok(())
+    }
+
+    /// Adds the given account `state` event, either starting a new account or
+    /// updating an existing one.
+    pub fn add_account_state(&mut self, state: AccountState) -> anyhow::Result<()> {
+        let account_id = state.account_id;
+        if let Some(database) = self.database.as_mut() {
+            let key = format!("{ACCOUNTS}{DELIMITER}{account_id}");
+            let payload = Some(vec![serde_json::to_vec(&state)?]);
+            if self.accounts.contains_key(&account_id) {
+                database.update(key, payload)?;
+            } else {
+                database.insert(key, payload)?;
+            }
+        }
+
+        let venue = Venue::from_str_unchecked(account_id.get_issuer().as_str());
+        self.index.venue_account.insert(venue, account_id);
+        self.accounts.entry(account_id).or_default().push(state);
+        Ok(())
+    }
+
+    /// Adds the given `order`, indexing it against the optional `position_id` and `client_id`.
+    pub fn add_order(
+        &mut self,
+        order: OrderAny,
+        position_id: Option<PositionId>,
+        client_id: Option<ClientId>,
+    ) -> anyhow::Result<()> {
+        let client_order_id = order.client_order_id();
+        if self.orders.contains_key(&client_order_id) {
+            anyhow::bail!("Order {client_order_id} already exists in the cache");
+        }
+
+        if let Some(database) = self.database.as_mut() {
+            // Orders are persisted as their events, starting with the initialization
+            let key = format!("{ORDERS}{DELIMITER}{client_order_id}");
+            let init = OrderEvent::OrderInitialized(order.initialized_event());
+            database.insert(key.clone(), Some(vec![serde_json::to_vec(&init)?]))?;
+            for event in order.events() {
+                database.update(key.clone(), Some(vec![serde_json::to_vec(event)?]))?;
+            }
+        }
+
+        Self::index_order(&mut self.index, &order);
+
+        if let Some(position_id) = position_id {
+            self.add_position_id(
+                position_id,
+                order.instrument_id().venue,
+                client_order_id,
+                order.strategy_id(),
+            )?;
+        }
+
+        if let Some(client_id) = client_id {
+            if let Some(database) = self.database.as_mut() {
+                let payload = vec![
+                    client_order_id.to_string().into_bytes(),
+                    client_id.to_string().into_bytes(),
+                ];
+                database.insert(INDEX_ORDER_CLIENT.to_string(), Some(payload))?;
+            }
+            self.index.order_client.insert(client_order_id, client_id);
+        }
+
+        self.orders.insert(client_order_id, order);
+        Ok(())
+    }
+
+    /// Indexes the given `position_id` against the order and strategy.
+    pub fn add_position_id(
+        &mut self,
+        position_id: PositionId,
+        venue: Venue,
+        client_order_id: ClientOrderId,
+        strategy_id: StrategyId,
+    ) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            let payload = vec![
+                client_order_id.to_string().into_bytes(),
+                position_id.to_string().into_bytes(),
+            ];
+            database.insert(INDEX_ORDER_POSITION.to_string(), Some(payload))?;
+        }
+
+        self.index
+            .order_position
+            .insert(client_order_id, position_id);
+        self.index
+            .position_strategy
+            .insert(position_id, strategy_id);
+        self.index
+            .position_orders
+            .entry(position_id)
+            .or_default()
+            .insert(client_order_id);
+        self.index
+            .strategy_positions
+            .entry(strategy_id)
+            .or_default()
+            .insert(position_id);
+        self.index
+            .venue_positions
+            .entry(venue)
+            .or_default()
+            .insert(position_id);
+        Ok(())
+    }
+
+    /// Adds the given `position`.
+    pub fn add_position(&mut self, position: Position) -> anyhow::Result<()> {
+        if let Some(database) = self.database.as_mut() {
+            let key = format!("{POSITIONS}{DELIMITER}{}", position.id);
+            database.insert(key, Some(vec![serde_json::to_vec(&position)?]))?;
+        }
+
+        self.add_position_id(
+            position.id,
+            position.instrument_id.venue,
+            position.opening_order_id,
+            position.strategy_id,
+        )?;
+        Self::index_position(&mut self.index, &position);
+        self.positions.insert(position.id, position);
+        Ok(())
+    }
+
+    /// Updates the given `order` in the cache, persisting any new events.
+    pub fn update_order(&mut self, order: &OrderAny) -> anyhow::Result<()> {
+        let client_order_id = order.client_order_id();
+        if !self.orders.contains_key(&client_order_id) {
+            anyhow::bail!("Order {client_order_id} not found in the cache");
+        }
+
+        if let Some(database) = self.database.as_mut() {
+            // Persist every event applied since the order was last added or updated
+            let persisted_count = self.orders[&client_order_id].events().len();
+            let key = format!("{ORDERS}{DELIMITER}{client_order_id}");
+            for event in order.events().into_iter().skip(persisted_count) {
+                database.update(key.clone(), Some(vec![serde_json::to_vec(event)?]))?;
+            }
+        }
+
+        Self::index_order(&mut self.index, order);
+        self.orders.insert(client_order_id, order.clone());
+        Ok(())
+    }
+
+    /// Updates the given `position` in the cache, persisting a snapshot of its state.
+    pub fn update_position(&mut self, position: &Position) -> anyhow::Result<()> {
+        if !self.positions.contains_key(&position.id) {
+            anyhow::bail!("Position {} not found in the cache", position.id);
+        }
+
+        if let Some(database) = self.database.as_mut() {
+            let key = format!("{POSITIONS}{DELIMITER}{}", position.id);
+            database.update(key, Some(vec![serde_json::to_vec(position)?]))?;
+        }
+
+        Self::index_position(&mut self.index, position);
+        self.positions.insert(position.id, position.clone());
+        Ok(())
+    }
+
+    fn index_order(index: &mut CacheIndex, order: &OrderAny) {
+        let client_order_id = order.client_order_id();
+        let strategy_id = order.strategy_id();
+
+        index
+            .venue_orders
+            .entry(order.instrument_id().venue)
+            .or_default()
+            .insert(client_order_id);
+        if let Some(venue_order_id) = order.venue_order_id() {
+            index.order_ids.insert(venue_order_id, client_order_id);
+        }
+        if let Some(position_id) = order.position_id() {
+            index.order_position.insert(client_order_id, position_id);
+        }
+        index.order_strategy.insert(client_order_id, strategy_id);
+        index
+            .instrument_orders
+            .entry(order.instrument_id())
+            .or_default()
+            .insert(client_order_id);
+        index
+            .strategy_orders
+            .entry(strategy_id)
+            .or_default()
+            .insert(client_order_id);
+        index.strategies.insert(strategy_id);
+
+        if let Some(exec_algorithm_id) = order.exec_algorithm_id() {
+            index.exec_algorithms.insert(exec_algorithm_id);
+            index
+                .exec_algorithm_orders
+                .entry(exec_algorithm_id)
+                .or_default()
+                .insert(client_order_id);
+        }
+        if let Some(exec_spawn_id) = order.exec_spawn_id() {
+            index
+                .exec_spawn_orders
+                .entry(exec_spawn_id)
+                .or_default()
+                .insert(client_order_id);
+        }
+
+        index.orders.insert(client_order_id);
+        if order.is_open() {
+            index.orders_open.insert(client_order_id);
+        } else {
+            index.orders_open.remove(&client_order_id);
+        }
+        if order.is_closed() {
+            index.orders_closed.insert(client_order_id);
+        } else {
+            index.orders_closed.remove(&client_order_id);
+        }
+        if order.is_inflight() {
+            index.orders_inflight.insert(client_order_id);
+        } else {
+            index.orders_inflight.remove(&client_order_id);
+        }
+        let is_emulated = !matches!(
+            order.emulation_trigger(),
+            None | Some(TriggerType::NoTrigger)
+        );
+        if is_emulated && !order.is_closed() {
+            index.orders_emulated.insert(client_order_id);
+        } else {
+            index.orders_emulated.remove(&client_order_id);
+        }
+    }
+
+    fn index_position(index: &mut CacheIndex, position: &Position) {
+        let position_id = position.id;
+
+        index
+            .venue_positions
+            .entry(position.instrument_id.venue)
+            .or_default()
+            .insert(position_id);
+        index
+            .position_strategy
+            .insert(position_id, position.strategy_id);
+        for client_order_id in position.client_order_ids() {
+            index
+                .position_orders
+                .entry(position_id)
+                .or_default()
+                .insert(client_order_id);
+        }
+        index
+            .instrument_positions
+            .entry(position.instrument_id)
+            .or_default()
+            .insert(position_id);
+        index
+            .strategy_positions
+            .entry(position.strategy_id)
+            .or_default()
+            .insert(position_id);
+
+        index.positions.insert(position_id);
+        if position.is_open() {
+            index.positions_open.insert(position_id);
+            index.positions_closed.remove(&position_id);
+        } else {
+            index.positions_open.remove(&position_id);
+            index.positions_closed.insert(position_id);
+        }
+    }
+
+    // -- QUERIES ---------------------------------------------------------------------------------
+
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.general.get(&Ustr::from(key)).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn currency(&self, code: &Ustr) -> Option<&Currency> {
+        self.currencies.get(code)
+    }
+
+    #[must_use]
+    pub fn instrument(&self, instrument_id: &InstrumentId) -> Option<&dyn Instrument> {
+        self.instruments.get(instrument_id).map(AsRef::as_ref)
+    }
+
+    #[must_use]
+    pub fn synthetic(&self, instrument_id: &InstrumentId) -> Option<&SyntheticInstrument> {
+        self.synthetics.get(instrument_id)
+    }
+
+    /// Returns the latest quote tick for the given `instrument_id` (if any).
+    #[must_use]
+    pub fn quote_tick(&self, instrument_id: &InstrumentId) -> Option<&QuoteTick> {
+        self.quote_ticks
+            .get(instrument_id)
+            .and_then(VecDeque::front)
+    }
+
+    /// Returns the cached quote ticks for the given `instrument_id` (most recent first).
+    #[must_use]
+    pub fn quote_ticks(&self, instrument_id: &InstrumentId) -> Option<Vec<QuoteTick>> {
+        self.quote_ticks
+            .get(instrument_id)
+            .map(|quotes| quotes.iter().copied().collect())
+    }
+
+    /// Returns the latest trade tick for the given `instrument_id` (if any).
+    #[must_use]
+    pub fn trade_tick(&self, instrument_id: &InstrumentId) -> Option<&TradeTick> {
+        self.trade_ticks
+            .get(instrument_id)
+            .and_then(VecDeque::front)
+    }
+
+    /// Returns the cached trade ticks for the given `instrument_id` (most recent first).
+    #[must_use]
+    pub fn trade_ticks(&self, instrument_id: &InstrumentId) -> Option<Vec<TradeTick>> {
+        self.trade_ticks
+            .get(instrument_id)
+            .map(|trades| trades.iter().copied().collect())
+    }
+
+    #[must_use]
+    pub fn book(&self, instrument_id: &InstrumentId) -> Option<&OrderBook> {
+        self.books.get(instrument_id)
+    }
+
+    #[must_use]
+    pub fn book_mut(&mut self, instrument_id: &InstrumentId) -> Option<&mut OrderBook> {
+        self.books.get_mut(instrument_id)
+    }
+
+    #[must_use]
+    pub fn has_book(&self, instrument_id: &InstrumentId) -> bool {
+        self.books.contains_key(instrument_id)
+    }
+
+    /// Returns the current price of the given `price_type` for the given `instrument_id`.
+    ///
+    /// Bid, ask and mid prices are taken from the top of the cached order book, falling
+    /// back to the latest quote tick when there is no book (or the book side is empty).
+    /// Last prices are taken from the latest trade tick.
+    #[must_use]
+    pub fn price(&self, instrument_id: &InstrumentId, price_type: PriceType) -> Option<Price> {
+        let book = self.books.get(instrument_id);
+        let quote = self.quote_tick(instrument_id);
+        match price_type {
+            PriceType::Bid => book
+                .and_then(OrderBook::best_bid_price)
+                .or_else(|| quote.map(|quote| quote.bid_price)),
+            PriceType::Ask => book
+                .and_then(OrderBook::best_ask_price)
+                .or_else(|| quote.map(|quote| quote.ask_price)),
+            PriceType::Mid => {
+                match book.and_then(|book| book.best_bid_price().zip(book.best_ask_price())) {
+                    Some((bid, ask)) => Some(mid_price(bid, ask)),
+                    None => quote.map(|quote| quote.extract_price(PriceType::Mid)),
+                }
+            }
+            PriceType::Last => self.trade_tick(instrument_id).map(|trade| trade.price),
+        }
+    }
+
+    /// Returns the current spread for the given `instrument_id`, from the top of the
+    /// cached order book or else the latest quote tick.
+    #[must_use]
+    pub fn spread(&self, instrument_id: &InstrumentId) -> Option<f64> {
+        self.books
+            .get(instrument_id)
+            .and_then(OrderBook::spread)
+            .or_else(|| {
+                self.quote_tick(instrument_id)
+                    .map(|quote| quote.ask_price.as_f64() - quote.bid_price.as_f64())
+            })
+    }
+
+    /// Returns the exchange rate between the given currencies, calculated from the
+    /// latest quotes for currency pairs at the given `venue`.
+    ///
+    /// Returns `None` if there are insufficient quotes to calculate the rate. The rate
+    /// graph for the venue is cached until its next currency pair quote is added.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `price_type` is `PriceType::Last`.
+    pub fn get_xrate(
+        &self,
+        venue: Venue,
+        from_currency: Currency,
+        to_currency: Currency,
+        price_type: PriceType,
+    ) -> anyhow::Result<Option<f64>> {
+        if from_currency == to_currency {
+            return Ok(Some(1.0)); // No conversion necessary
+        }
+
+        let key = (venue, price_type);
+        if let Some(graph) = self.xrate_graphs.borrow().get(&key) {
+            return Ok(graph.get_rate(from_currency, to_currency));
+        }
+
+        let (bid_quotes, ask_quotes) = self.build_quote_table(&venue);
+        let graph = self
+            .xrate_calculator
+            .build_graph(price_type, &bid_quotes, &ask_quotes)?;
+        let rate = graph.get_rate(from_currency, to_currency);
+        self.xrate_graphs.borrow_mut().insert(key, graph);
+        Ok(rate)
+    }
+
+    fn invalidate_xrates(&mut self, venue: Venue) {
+        self.xrate_graphs
+            .get_mut()
+            .retain(|(graph_venue, _), _| *graph_venue != venue);
+    }
+
+    fn build_quote_table(&self, venue: &Venue) -> (HashMap<Symbol, f64>, HashMap<Symbol, f64>) {
+        let mut bid_quotes = HashMap::new();
+        let mut ask_quotes = HashMap::new();
+
+        for (instrument_id, symbol) in &self.xrate_symbols {
+            if instrument_id.venue != *venue {
+                continue;
+            }
+            if let Some(quote) = self.quote_tick(instrument_id) {
+                bid_quotes.insert(*symbol, quote.bid_price.as_f64());
+                ask_quotes.insert(*symbol, quote.ask_price.as_f64());
+            }
+        }
+
+        (bid_quotes, ask_quotes)
+    }
+
+    /// Returns the latest account state for the given `account_id`.
+    #[must_use]
+    pub fn account_state(&self, account_id: &AccountId) -> Option<&AccountState> {
+        self.accounts
+            .get(account_id)
+            .and_then(|events| events.last())
+    }
+
+    /// Returns all account state events for the given `account_id` (oldest first).
+    #[must_use]
+    pub fn account_states(&self, account_id: &AccountId) -> Option<&[AccountState]> {
+        self.accounts.get(account_id).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn account_id(&self, venue: &Venue) -> Option<&AccountId> {
+        self.index.venue_account.get(venue)
+    }
+
+    #[must_use]
+    pub fn order(&self, client_order_id: &ClientOrderId) -> Option<&OrderAny> {
+        self.orders.get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn client_order_id(&self, venue_order_id: &VenueOrderId) -> Option<&ClientOrderId> {
+        self.index.order_ids.get(venue_order_id)
+    }
+
+    #[must_use]
+    pub fn client_id(&self, client_order_id: &ClientOrderId) -> Option<&ClientId> {
+        self.index.order_client.get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn position(&self, position_id: &PositionId) -> Option<&Position> {
+        self.positions.get(position_id)
+    }
+
+    #[must_use]
+    pub fn position_id(&self, client_order_id: &ClientOrderId) -> Option<&PositionId> {
+        self.index.order_position.get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn strategy_id_for_order(&self, client_order_id: &ClientOrderId) -> Option<&StrategyId> {
+        self.index.order_strategy.get(client_order_id)
+    }
+
+    #[must_use]
+    pub fn strategy_id_for_position(&self, position_id: &PositionId) -> Option<&StrategyId> {
+        self.index.position_strategy.get(position_id)
+    }
+
+    #[must_use]
+    pub fn is_order_open(&self, client_order_id: &ClientOrderId) -> bool {
+        self.index.orders_open.contains(client_order_id)
+    }
+
+    #[must_use]
+    pub fn is_order_closed(&self, client_order_id: &ClientOrderId) -> bool {
+        self.index.orders_closed.contains(client_order_id)
+    }
+
+    #[must_use]
+    pub fn is_order_emulated(&self, client_order_id: &ClientOrderId) -> bool {
+        self.index.orders_emulated.contains(client_order_id)
+    }
+
+    #[must_use]
+    pub fn is_order_inflight(&self, client_order_id: &ClientOrderId) -> bool {
+        self.index.orders_inflight.contains(client_order_id)
+    }
+
+    #[must_use]
+    pub fn is_position_open(&self, position_id: &PositionId) -> bool {
+        self.index.positions_open.contains(position_id)
+    }
+
+    #[must_use]
+    pub fn is_position_closed(&self, position_id: &PositionId) -> bool {
+        self.index.positions_closed.contains(position_id)
+    }
+
+    #[must_use]
+    pub fn orders_total_count(&self) -> usize {
+        self.index.orders.len()
+    }
+
+    #[must_use]
+    pub fn positions_total_count(&self) -> usize {
+        self.index.positions.len()
+    }
+
+    /// Returns the open orders, filtered by the given `venue` and `instrument_id` (if any).
+    #[must_use]
+    pub fn orders_open(
+        &self,
+        venue: Option<&Venue>,
+        instrument_id: Option<&InstrumentId>,
+    ) -> Vec<&OrderAny> {
+        self.index
+            .orders_open
+            .iter()
+            .filter_map(|client_order_id| self.orders.get(client_order_id))
+            .filter(|order| is_query_match(&order.instrument_id(), venue, instrument_id))
+            .collect()
+    }
+
+    /// Returns all positions, filtered by the given `venue` and `instrument_id` (if any).
+    #[must_use]
+    pub fn positions(
+        &self,
+        venue: Option<&Venue>,
+        instrument_id: Option<&InstrumentId>,
+    ) -> Vec<&Position> {
+        self.positions
+            .values()
+            .filter(|position| is_query_match(&position.instrument_id, venue, instrument_id))
+            .collect()
+    }
+
+    /// Returns the open positions, filtered by the given `venue` and `instrument_id` (if any).
+    #[must_use]
+    pub fn positions_open(
+        &self,
+        venue: Option<&Venue>,
+        instrument_id: Option<&InstrumentId>,
+    ) -> Vec<&Position> {
+        self.index
+            .positions_open
+            .iter()
+            .filter_map(|position_id| self.positions.get(position_id))
+            .filter(|position| is_query_match(&position.instrument_id, venue, instrument_id))
+            .collect()
+    }
+}
+
+fn is_query_match(
+    id: &InstrumentId,
+    venue: Option<&Venue>,
+    instrument_id: Option<&InstrumentId>,
+) -> bool {
+    (venue.is_none() || venue == Some(&id.venue))
+        && (instrument_id.is_none() || instrument_id == Some(id))
+}
+
+/// Returns the price halfway between the given `bid` and `ask`, with one more
+/// digit of precision (as for a quote tick mid price).
+fn mid_price(bid: Price, ask: Price) -> Price {
+    Price::from_raw(
+        (bid.raw + ask.raw) / 2,
+        cmp::min(bid.precision + 1, FIXED_PRECISION),
+    )
+    .unwrap() // Already a valid `Price`
+}
+
+/// Returns the keys for the given `collection`, relative to the trader key.
+fn collection_keys(
+    database: &mut dyn CacheDatabase,
+    collection: &str,
+) -> anyhow::Result<Vec<String>> {
+    let prefix = format!("{collection}{DELIMITER}");
+    let keys = database
+        .keys(&format!("{prefix}*"))?
+        .into_iter()
+        .filter_map(|key| key.find(&prefix).map(|i| key[i..].to_string()))
+        .collect();
+    Ok(keys)
+}
+
+fn read_index_map(
+    database: &mut dyn CacheDatabase,
+    key: &str,
+) -> anyhow::Result<HashMap<String, String>> {
+    match database.read(key)?.pop() {
+        Some(payload) => Ok(serde_json::from_slice(&payload)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn encode_currency(currency: &Currency) -> anyhow::Result<Vec<u8>> {
+    let def = CurrencyDef {
+        code: currency.code,
+        precision: currency.precision,
+        iso4217: currency.iso4217,
+        name: currency.name,
+        currency_type: currency.currency_type,
+    };
+    Ok(serde_json::to_vec(&def)?)
+}
+
+fn decode_currency(payload: &[u8]) -> anyhow::Result<Currency> {
+    let def: CurrencyDef = serde_json::from_slice(payload)?;
+    Currency::new(
+        def.code.as_str(),
+        def.precision,
+        def.iso4217,
+        def.name.as_str(),
+        def.currency_type,
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+    use nautilus_model::{
+        data::{depth::stubs::stub_depth10, order::BookOrder},
+        enums::{AggressorSide, BookAction, BookType, OrderSide},
+        events::{
+            account::stubs::cash_account_state,
+            order::{accepted::OrderAccepted, submitted::OrderSubmitted},
+        },
+        identifiers::{symbol::Symbol, trade_id::TradeId},
+        instruments::{
+            currency_pair::CurrencyPair,
+            stubs::{audusd_sim, default_fx_ccy, usdjpy_idealpro},
+        },
+        orders::stubs::{TestOrderEventStubs, TestOrderStubs},
+        types::{price::Price, quantity::Quantity},
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockDatabaseState {
+        values: HashMap<String, Vec<Vec<u8>>>,
+        hsets: HashMap<String, HashMap<String, String>>,
+    }
+
+    /// An in-memory database mirroring the collection semantics of the Redis backing.
+    struct MockCacheDatabase {
+        state: Rc<RefCell<MockDatabaseState>>,
+    }
+
+    impl CacheDatabase for MockCacheDatabase {
+        fn new(
+            _trader_id: TraderId,
+            _instance_id: UUID4,
+            _config: HashMap<String, serde_json::Value>,
+        ) -> anyhow::Result<Self> {
+            Ok(Self {
+                state: Rc::new(RefCell::new(MockDatabaseState::default())),
+            })
+        }
+
+        fn flushdb(&mut self) -> anyhow::Result<()> {
+            *self.state.borrow_mut() = MockDatabaseState::default();
+            Ok(())
+        }
+
+        fn keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>> {
+            let prefix = pattern.trim_end_matches('*');
+            Ok(self
+                .state
+                .borrow()
+                .values
+                .keys()
+                .filter(|key| key.starts_with(prefix))
+                .map(|key| format!("TRADER-001:{key}"))
+                .collect())
+        }
+
+        fn read(&mut self, key: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+            let state = self.state.borrow();
+            if let Some(hset) = state.hsets.get(key) {
+                return Ok(vec![serde_json::to_vec(hset)?]);
+            }
+            Ok(state.values.get(key).cloned().unwrap_or_default())
+        }
+
+        fn insert(&mut self, key: String, payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+            let payload = payload.unwrap();
+            let mut state = self.state.borrow_mut();
+            if key.starts_with("index:") {
+                let name = String::from_utf8(payload[0].clone())?;
+                let value = String::from_utf8(payload[1].clone())?;
+                state.hsets.entry(key).or_default().insert(name, value);
+            } else if [ACCOUNTS, ORDERS, POSITIONS]
+                .iter()
+                .any(|c| key.starts_with(&format!("{c}{DELIMITER}")))
+            {
+                state
+                    .values
+                    .entry(key)
+                    .or_default()
+                    .push(payload[0].clone());
+            } else {
+                state.values.insert(key, vec![payload[0].clone()]);
+            }
+            Ok(())
+        }
+
+        fn update(&mut self, key: String, payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+            let mut state = self.state.borrow_mut();
+            let Some(list) = state.values.get_mut(&key) else {
+                anyhow::bail!("Key '{key}' does not exist");
+            };
+            list.push(payload.unwrap()[0].clone());
+            Ok(())
+        }
+
+        fn delete(&mut self, key: String, _payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+            self.state.borrow_mut().values.remove(&key);
+            Ok(())
+        }
+
+        fn handle_messages(
+            _rx: Receiver<DatabaseCommand>,
+            _trader_key: String,
+            _config: HashMap<String, serde_json::Value>,
+        ) {
+        }
+    }
+
+    fn cache_with_state(state: &Rc<RefCell<MockDatabaseState>>) -> Cache {
+        let database = MockCacheDatabase {
+            state: state.clone(),
+        };
+        Cache::new(CacheConfig::default(), Some(Box::new(database)))
+    }
+
+    fn limit_order(instrument_id: InstrumentId) -> OrderAny {
+        OrderAny::from(TestOrderStubs::limit_order(
+            instrument_id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(100_000),
+            None,
+            None,
+        ))
+    }
+
+    fn accept_order(order: &mut OrderAny) {
+        let account_id = AccountId::from("SIM-001");
+        let submitted = OrderSubmitted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            account_id,
+            UUID4::new(),
+            1,
+            1,
+        )
+        .unwrap();
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+
+        let accepted = OrderAccepted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            VenueOrderId::from("V-1"),
+            account_id,
+            UUID4::new(),
+            2,
+            2,
+            false,
+        )
+        .unwrap();
+        order.apply(OrderEvent::OrderAccepted(accepted)).unwrap();
+    }
+
+    #[rstest]
+    fn test_cache_without_database_holds_state() {
+        let mut cache = Cache::default();
+        cache.add("key", b"value".to_vec()).unwrap();
+        cache.add_currency(Currency::USD()).unwrap();
+
+        assert!(!cache.has_backing());
+        assert_eq!(cache.get("key"), Some(b"value".as_slice()));
+        assert_eq!(cache.currency(&Ustr::from("USD")), Some(&Currency::USD()));
+        assert!(cache.cache_all().is_ok());
+    }
+
+    #[rstest]
+    fn test_add_order_twice_fails() {
+        let mut cache = Cache::default();
+        let order = limit_order(InstrumentId::from("AUD/USD.SIM"));
+        cache.add_order(order.clone(), None, None).unwrap();
+
+        assert!(cache.add_order(order, None, None).is_err());
+    }
+
+    #[rstest]
+    fn test_update_order_updates_index() {
+        let mut cache = Cache::default();
+        let mut order = limit_order(InstrumentId::from("AUD/USD.SIM"));
+        let client_order_id = order.client_order_id();
+        cache.add_order(order.clone(), None, None).unwrap();
+        assert!(!cache.is_order_open(&client_order_id));
+
+        accept_order(&mut order);
+        cache.update_order(&order).unwrap();
+
+        assert!(cache.is_order_open(&client_order_id));
+        assert!(!cache.is_order_inflight(&client_order_id));
+        assert_eq!(
+            cache.client_order_id(&VenueOrderId::from("V-1")),
+            Some(&client_order_id)
+        );
+    }
+
+    #[rstest]
+    fn test_cache_rebuilds_state_from_database(
+        audusd_sim: CurrencyPair,
+        cash_account_state: AccountState,
+    ) {
+        let state = Rc::new(RefCell::new(MockDatabaseState::default()));
+        let mut cache = cache_with_state(&state);
+
+        let custom =
+            Currency::new("CUSTOM", 4, 0, "Custom currency", CurrencyType::Crypto).unwrap();
+        let synthetic = SyntheticInstrument::new(
+            Symbol::from("AUD-USD"),
+            5,
+            vec![audusd_sim.id],
+            "AUD/USD.SIM * 1.0".to_string(),
+            0,
+            0,
+        )
+        .unwrap();
+        let mut order = limit_order(audusd_sim.id);
+        let client_order_id = order.client_order_id();
+        let position_id = PositionId::from("P-1");
+        let client_id = ClientId::from("SIM");
+
+        cache.add("key", b"value".to_vec()).unwrap();
+        cache.add_currency(custom).unwrap();
+        cache.add_instrument(Box::new(audusd_sim)).unwrap();
+        cache.add_synthetic(synthetic.clone()).unwrap();
+        cache.add_account_state(cash_account_state.clone()).unwrap();
+        cache
+            .add_order(order.clone(), Some(position_id), Some(client_id))
+            .unwrap();
+        accept_order(&mut order);
+        cache.update_order(&order).unwrap();
+
+        let fill = TestOrderEventStubs::order_filled(
+            &TestOrderStubs::limit_order(
+                audusd_sim.id,
+                OrderSide::Buy,
+                Price::from("1.00000"),
+                Quantity::from(100_000),
+                None,
+                None,
+            ),
+            &audusd_sim,
+            None,
+            None,
+            Some(position_id),
+            Some(Price::from("1.00000")),
+            None,
+            None,
+            None,
+        );
+        let position = Position::new(audusd_sim, fill).unwrap();
+        cache.add_position(position.clone()).unwrap();
+
+        // Simulate a restart with a fresh cache on the same database
+        let mut cache = cache_with_state(&state);
+        cache.cache_all().unwrap();
+
+        let account_id = cash_account_state.account_id;
+        let venue = Venue::from("SIM");
+        assert_eq!(cache.get("key"), Some(b"value".as_slice()));
+        assert_eq!(cache.currency(&custom.code), Some(&custom));
+        assert_eq!(Currency::from_str("CUSTOM").unwrap(), custom);
+        assert_eq!(
+            cache.instrument(&audusd_sim.id).unwrap().id(),
+            audusd_sim.id
+        );
+        assert_eq!(cache.synthetic(&synthetic.id), Some(&synthetic));
+        assert_eq!(cache.account_states(&account_id).unwrap().len(), 1);
+        assert_eq!(cache.account_id(&venue), Some(&account_id));
+
+        let cached_order = cache.order(&client_order_id).unwrap();
+        assert_eq!(cached_order.status(), order.status());
+        assert_eq!(cached_order.events().len(), 2);
+        assert!(cache.is_order_open(&client_order_id));
+        assert_eq!(cache.orders_total_count(), 1);
+        assert_eq!(cache.client_id(&client_order_id), Some(&client_id));
+        assert_eq!(cache.position_id(&client_order_id), Some(&position_id));
+
+        assert_eq!(
+            cache.position(&position_id).unwrap().quantity,
+            position.quantity
+        );
+        assert!(cache.is_position_open(&position_id));
+        assert_eq!(cache.positions_total_count(), 1);
+        assert_eq!(
+            cache.strategy_id_for_position(&position_id),
+            Some(&position.strategy_id)
+        );
+    }
+
+    #[rstest]
+    fn test_update_position_persists_latest_snapshot(audusd_sim: CurrencyPair) {
+        let state = Rc::new(RefCell::new(MockDatabaseState::default()));
+        let mut cache = cache_with_state(&state);
+
+        let order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Price::from("1.00000"),
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let position_id = PositionId::from("P-1");
+        let fill = TestOrderEventStubs::order_filled(
+            &order,
+            &audusd_sim,
+            None,
+            None,
+            Some(position_id),
+            Some(Price::from("1.00000")),
+            None,
+            None,
+            None,
+        );
+        let mut position = Position::new(audusd_sim, fill).unwrap();
+        cache.add_position(position.clone()).unwrap();
+
+        let close_order = TestOrderStubs::limit_order(
+            audusd_sim.id,
+            OrderSide::Sell,
+            Price::from("1.00010"),
+            Quantity::from(100_000),
+            Some(ClientOrderId::from("O-2")),
+            None,
+        );
+        let close_fill = TestOrderEventStubs::order_filled(
+            &close_order,
+            &audusd_sim,
+            None,
+            Some(TradeId::from("E-2")),
+            Some(position_id),
+            Some(Price::from("1.00010")),
+            None,
+            None,
+            None,
+        );
+        position.apply(&close_fill);
+        cache.update_position(&position).unwrap();
+
+        let mut cache = cache_with_state(&state);
+        cache.cache_all().unwrap();
+
+        assert!(cache.position(&position_id).unwrap().is_closed());
+        assert!(cache.is_position_closed(&position_id));
+        assert!(!cache.is_position_open(&position_id));
+    }
+
+    fn quote(instrument_id: InstrumentId, bid: &str, ask: &str) -> QuoteTick {
+        QuoteTick::new(
+            instrument_id,
+            Price::from(bid),
+            Price::from(ask),
+            Quantity::from(1_000_000),
+            Quantity::from(1_000_000),
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_add_quote_ticks_retains_capacity() {
+        let config = CacheConfig {
+            tick_capacity: 2,
+            ..Default::default()
+        };
+        let mut cache = Cache::new(config, None);
+        let instrument_id = InstrumentId::from("AUD/USD.SIM");
+
+        cache.add_quote_ticks(&[
+            quote(instrument_id, "0.80000", "0.80010"),
+            quote(instrument_id, "0.80001", "0.80011"),
+            quote(instrument_id, "0.80002", "0.80012"),
+        ]);
+
+        let quotes = cache.quote_ticks(&instrument_id).unwrap();
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].bid_price, Price::from("0.80002"));
+        assert_eq!(
+            cache.quote_tick(&instrument_id).unwrap().bid_price,
+            Price::from("0.80002")
+        );
+    }
+
+    #[rstest]
+    fn test_get_xrate(audusd_sim: CurrencyPair, usdjpy_idealpro: CurrencyPair) {
+        let mut cache = Cache::default();
+        let usdjpy_sim = default_fx_ccy(Symbol::from("USD/JPY"), Some(Venue::from("SIM")));
+        cache.add_instrument(Box::new(audusd_sim)).unwrap();
+        cache.add_instrument(Box::new(usdjpy_sim)).unwrap();
+        cache.add_instrument(Box::new(usdjpy_idealpro)).unwrap();
+        cache.add_quote_tick(quote(audusd_sim.id, "0.80000", "0.80010"));
+        cache.add_quote_tick(quote(usdjpy_sim.id, "110.000", "110.010"));
+
+        let venue = Venue::from("SIM");
+        let aud = Currency::AUD();
+        let usd = Currency::USD();
+        let jpy = Currency::JPY();
+
+        let bid = cache.get_xrate(venue, aud, usd, PriceType::Bid).unwrap();
+        let inverse = cache.get_xrate(venue, usd, aud, PriceType::Bid).unwrap();
+        let triangulated = cache.get_xrate(venue, aud, jpy, PriceType::Bid).unwrap();
+        let other_venue = cache
+            .get_xrate(usdjpy_idealpro.id.venue, usd, jpy, PriceType::Mid)
+            .unwrap();
+
+        assert_eq!(bid, Some(0.8));
+        assert!((inverse.unwrap() - 1.0 / 0.8001).abs() < 1e-12);
+        assert!((triangulated.unwrap() - 88.0).abs() < 1e-9);
+        assert_eq!(other_venue, None);
+        assert!(cache.get_xrate(venue, aud, usd, PriceType::Last).is_err());
+    }
+
+    #[rstest]
+    fn test_get_xrate_after_quote_update(audusd_sim: CurrencyPair) {
+        let mut cache = Cache::default();
+        cache.add_instrument(Box::new(audusd_sim)).unwrap();
+        cache.add_quote_tick(quote(audusd_sim.id, "0.80000", "0.80010"));
+
+        let venue = audusd_sim.id.venue;
+        let aud = Currency::AUD();
+        let usd = Currency::USD();
+        assert_eq!(
+            cache.get_xrate(venue, aud, usd, PriceType::Bid).unwrap(),
+            Some(0.8)
+        );
+
+        cache.add_quote_tick(quote(audusd_sim.id, "0.90000", "0.90010"));
+
+        assert_eq!(
+            cache.get_xrate(venue, aud, usd, PriceType::Bid).unwrap(),
+            Some(0.9)
+        );
+    }
+
+    #[rstest]
+    fn test_update_book_without_book_fails(stub_depth10: OrderBookDepth10) {
+        let mut cache = Cache::default();
+
+        assert!(cache.update_book_depth(stub_depth10).is_err());
+    }
+
+    #[rstest]
+    fn test_book_updates_from_depth_and_deltas(stub_depth10: OrderBookDepth10) {
+        let mut cache = Cache::default();
+        let instrument_id = stub_depth10.instrument_id;
+        cache.add_book(OrderBook::new(instrument_id, BookType::L2_MBP));
+
+        cache.update_book_depth(stub_depth10).unwrap();
+        let delta = OrderBookDelta::new(
+            instrument_id,
+            BookAction::Add,
+            BookOrder::new(
+                OrderSide::Buy,
+                Price::from("99.50"),
+                Quantity::from("100"),
+                21,
+            ),
+            0,
+            1,
+            3,
+            4,
+        );
+        cache.update_book_delta(delta).unwrap();
+
+        let book = cache.book(&instrument_id).unwrap();
+        assert_eq!(book.best_bid_price(), Some(Price::from("99.50")));
+        assert_eq!(book.best_ask_price(), Some(Price::from("100.00")));
+        assert_eq!(cache.spread(&instrument_id), Some(0.5));
+        assert_eq!(
+            cache.price(&instrument_id, PriceType::Mid),
+            Some(Price::from("99.750"))
+        );
+    }
+
+    #[rstest]
+    fn test_price_falls_back_to_quotes_and_trades() {
+        let mut cache = Cache::default();
+        let instrument_id = InstrumentId::from("AUD/USD.SIM");
+
+        assert_eq!(cache.price(&instrument_id, PriceType::Bid), None);
+        assert_eq!(cache.spread(&instrument_id), None);
+
+        cache.add_quote_tick(quote(instrument_id, "0.80000", "0.80010"));
+        cache.add_trade_tick(TradeTick::new(
+            instrument_id,
+            Price::from("0.80005"),
+            Quantity::from("100000"),
+            AggressorSide::Buyer,
+            TradeId::from("1"),
+            0,
+            0,
+        ));
+
+        assert_eq!(
+            cache.price(&instrument_id, PriceType::Bid),
+            Some(Price::from("0.80000"))
+        );
+        assert_eq!(
+            cache.price(&instrument_id, PriceType::Ask),
+            Some(Price::from("0.80010"))
+        );
+        assert_eq!(
+            cache.price(&instrument_id, PriceType::Mid),
+            Some(Price::from("0.800050"))
+        );
+        assert_eq!(
+            cache.price(&instrument_id, PriceType::Last),
+            Some(Price::from("0.80005"))
+        );
+        assert!((cache.spread(&instrument_id).unwrap() - 0.0001).abs() < 1e-12);
+    }
+}
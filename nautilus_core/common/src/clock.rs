@@ -13,7 +13,7 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-use std::{collections::HashMap, ops::Deref};
+use std::{collections::HashMap, ops::Deref, rc::Rc};
 
 use nautilus_core::{
     correctness::check_valid_string,
@@ -22,8 +22,8 @@ use nautilus_core::{
 use ustr::Ustr;
 
 use crate::{
-    handlers::EventHandler,
-    timer::{LiveTimer, TestTimer, TimeEvent, TimeEventHandler},
+    handlers::{EventHandler, LocalTimeEventCallback, SafeTimeEventCallback},
+    timer::{LiveTimer, LocalTimeEventHandler, TestTimer, TimeEvent, TimeEventHandler},
 };
 
 /// Represents a type of clock.
@@ -31,6 +31,9 @@ use crate::{
 /// # Notes
 /// An active timer is one which has not expired (`timer.is_expired == False`).
 pub trait Clock {
+    /// Return the current UNIX timestamp (nanoseconds) of the clock.
+    fn timestamp_ns(&self) -> UnixNanos;
+
     /// Return the names of active timers in the clock.
     fn timer_names(&self) -> Vec<&str>;
 
@@ -72,6 +75,7 @@ pub struct TestClock {
     timers: HashMap<Ustr, TestTimer>,
     default_callback: Option<EventHandler>,
     callbacks: HashMap<Ustr, EventHandler>,
+    local_callbacks: HashMap<Ustr, Rc<LocalTimeEventCallback>>,
 }
 
 impl TestClock {
@@ -82,6 +86,7 @@ impl TestClock {
             timers: HashMap::new(),
             default_callback: None,
             callbacks: HashMap::new(),
+            local_callbacks: HashMap::new(),
         }
     }
 
@@ -112,11 +117,55 @@ impl TestClock {
         timers
     }
 
+    /// Set a `Timer` to alert at a particular time, with the Rust-native `callback`
+    /// handling the generated event (see [`TestClock::match_local_handlers`]).
+    pub fn set_local_time_alert_ns(
+        &mut self,
+        name: &str,
+        alert_time_ns: UnixNanos,
+        callback: Rc<LocalTimeEventCallback>,
+    ) {
+        check_valid_string(name, stringify!(name)).unwrap();
+
+        let name_ustr = Ustr::from(name);
+        self.callbacks.remove(&name_ustr);
+        self.local_callbacks.insert(name_ustr, callback);
+
+        let time_ns = self.time.get_time_ns();
+        let timer = TestTimer::new(name, alert_time_ns - time_ns, time_ns, Some(alert_time_ns));
+        self.timers.insert(name_ustr, timer);
+    }
+
+    /// Set a `Timer` to start alerting at every interval between start and stop time,
+    /// with the Rust-native `callback` handling the generated events (see
+    /// [`TestClock::match_local_handlers`]).
+    pub fn set_local_timer_ns(
+        &mut self,
+        name: &str,
+        interval_ns: u64,
+        start_time_ns: UnixNanos,
+        stop_time_ns: Option<UnixNanos>,
+        callback: Rc<LocalTimeEventCallback>,
+    ) {
+        check_valid_string(name, stringify!(name)).unwrap();
+
+        let name_ustr = Ustr::from(name);
+        self.callbacks.remove(&name_ustr);
+        self.local_callbacks.insert(name_ustr, callback);
+
+        let timer = TestTimer::new(name, interval_ns, start_time_ns, stop_time_ns);
+        self.timers.insert(name_ustr, timer);
+    }
+
     /// Assumes time events are sorted by their `ts_event`.
+    ///
+    /// Events for timers with a Rust-native callback are not matched, see
+    /// [`TestClock::match_local_handlers`].
     #[must_use]
     pub fn match_handlers(&self, events: Vec<TimeEvent>) -> Vec<TimeEventHandler> {
         events
             .into_iter()
+            .filter(|event| !self.local_callbacks.contains_key(&event.name))
             .map(|event| {
                 let handler = self.callbacks.get(&event.name).cloned().unwrap_or_else(|| {
                     // If callback_py is None, use the default_callback_py
@@ -127,6 +176,22 @@ impl TestClock {
             })
             .collect()
     }
+
+    /// Returns the handlers for the `events` of timers with a Rust-native callback.
+    #[must_use]
+    pub fn match_local_handlers(&self, events: &[TimeEvent]) -> Vec<LocalTimeEventHandler> {
+        events
+            .iter()
+            .filter_map(|event| {
+                self.local_callbacks
+                    .get(&event.name)
+                    .map(|callback| LocalTimeEventHandler {
+                        event: event.clone(),
+                        callback: callback.clone(),
+                    })
+            })
+            .collect()
+    }
 }
 
 #[cfg(not(feature = "python"))]
@@ -159,6 +224,10 @@ impl Deref for TestClock {
 }
 
 impl Clock for TestClock {
+    fn timestamp_ns(&self) -> UnixNanos {
+        self.time.get_time_ns()
+    }
+
     fn timer_names(&self) -> Vec<&str> {
         self.timers
             .iter()
@@ -191,6 +260,7 @@ impl Clock for TestClock {
         );
 
         let name_ustr = Ustr::from(name);
+        self.local_callbacks.remove(&name_ustr);
         match callback {
             Some(callback_py) => self.callbacks.insert(name_ustr, callback_py),
             None => None,
@@ -218,6 +288,7 @@ impl Clock for TestClock {
         );
 
         let name_ustr = Ustr::from(name);
+        self.local_callbacks.remove(&name_ustr);
         match callback {
             Some(callback_py) => self.callbacks.insert(name_ustr, callback_py),
             None => None,
@@ -236,7 +307,9 @@ impl Clock for TestClock {
     }
 
     fn cancel_timer(&mut self, name: &str) {
-        let timer = self.timers.remove(&Ustr::from(name));
+        let name_ustr = Ustr::from(name);
+        self.local_callbacks.remove(&name_ustr);
+        let timer = self.timers.remove(&name_ustr);
         match timer {
             None => {}
             Some(mut timer) => timer.cancel(),
@@ -248,6 +321,7 @@ impl Clock for TestClock {
             timer.cancel();
         }
         self.timers = HashMap::new();
+        self.local_callbacks = HashMap::new();
     }
 }
 
@@ -271,6 +345,51 @@ impl LiveClock {
     pub fn get_timers(&self) -> &HashMap<Ustr, LiveTimer> {
         &self.timers
     }
+
+    /// Set a `Timer` to alert at a particular time, with the Rust-native `callback`
+    /// handling the generated event on the runtime thread.
+    ///
+    /// Unlike the clocks event handlers, the callback is used with either feature set.
+    pub fn set_rust_time_alert_ns(
+        &mut self,
+        name: &str,
+        mut alert_time_ns: UnixNanos,
+        callback: SafeTimeEventCallback,
+    ) {
+        check_valid_string(name, stringify!(name)).unwrap();
+
+        let ts_now = self.get_time_ns();
+        alert_time_ns = std::cmp::max(alert_time_ns, ts_now);
+        let mut timer = LiveTimer::with_rust_callback(
+            name,
+            alert_time_ns - ts_now,
+            ts_now,
+            Some(alert_time_ns),
+            callback,
+        );
+        timer.start();
+        self.timers.insert(Ustr::from(name), timer);
+    }
+
+    /// Set a `Timer` to start alerting at every interval between start and stop time,
+    /// with the Rust-native `callback` handling the generated events on the runtime thread.
+    ///
+    /// Unlike the clocks event handlers, the callback is used with either feature set.
+    pub fn set_rust_timer_ns(
+        &mut self,
+        name: &str,
+        interval_ns: u64,
+        start_time_ns: UnixNanos,
+        stop_time_ns: Option<UnixNanos>,
+        callback: SafeTimeEventCallback,
+    ) {
+        check_valid_string(name, stringify!(name)).unwrap();
+
+        let mut timer =
+            LiveTimer::with_rust_callback(name, interval_ns, start_time_ns, stop_time_ns, callback);
+        timer.start();
+        self.timers.insert(Ustr::from(name), timer);
+    }
 }
 
 impl Default for LiveClock {
@@ -288,6 +407,10 @@ impl Deref for LiveClock {
 }
 
 impl Clock for LiveClock {
+    fn timestamp_ns(&self) -> UnixNanos {
+        self.time.get_time_ns()
+    }
+
     fn timer_names(&self) -> Vec<&str> {
         self.timers
             .iter()
@@ -78,7 +78,10 @@ impl DerefMut for LogGuard_API {
 /// - Assume `directory_ptr` is either NULL or a valid C string pointer.
 /// - Assume `file_name_ptr` is either NULL or a valid C string pointer.
 /// - Assume `file_format_ptr` is either NULL or a valid C string pointer.
+/// - Assume `rotation_interval_ptr` is either NULL or a valid C string pointer.
 /// - Assume `component_level_ptr` is either NULL or a valid C string pointer.
+///
+/// A `max_file_size` or `max_backup_count` of zero is unlimited.
 #[no_mangle]
 pub unsafe extern "C" fn logging_init(
     trader_id: TraderId,
@@ -88,6 +91,10 @@ pub unsafe extern "C" fn logging_init(
     directory_ptr: *const c_char,
     file_name_ptr: *const c_char,
     file_format_ptr: *const c_char,
+    max_file_size: u64,
+    rotation_interval_ptr: *const c_char,
+    max_backup_count: u64,
+    compress_backups: u8,
     component_levels_ptr: *const c_char,
     is_colored: u8,
     is_bypassed: u8,
@@ -110,7 +117,18 @@ pub unsafe extern "C" fn logging_init(
     let directory = optional_cstr_to_str(directory_ptr).map(|s| s.to_string());
     let file_name = optional_cstr_to_str(file_name_ptr).map(|s| s.to_string());
     let file_format = optional_cstr_to_str(file_format_ptr).map(|s| s.to_string());
-    let file_config = FileWriterConfig::new(directory, file_name, file_format);
+    let rotation_interval = optional_cstr_to_str(rotation_interval_ptr).map(|s| s.to_string());
+    let file_config = FileWriterConfig::new(
+        directory,
+        file_name,
+        file_format,
+        (max_file_size > 0).then_some(max_file_size),
+        rotation_interval,
+        usize::try_from(max_backup_count)
+            .ok()
+            .filter(|count| *count > 0),
+        u8_as_bool(compress_backups),
+    );
 
     if u8_as_bool(is_bypassed) {
         logging_set_bypass();
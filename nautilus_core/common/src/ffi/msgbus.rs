@@ -36,7 +36,7 @@ use pyo3::{
 
 use crate::{
     handlers::MessageHandler,
-    msgbus::{is_matching, MessageBus, Subscription},
+    msgbus::{is_matching, MessageBus},
 };
 
 /// Provides a C compatible Foreign Function Interface (FFI) for an underlying [`MessageBus`].
@@ -321,7 +321,7 @@ pub unsafe extern "C" fn msgbus_matching_callbacks(
     pattern_ptr: *const c_char,
 ) -> CVec {
     let pattern = cstr_to_ustr(pattern_ptr);
-    let subs: Vec<&Subscription> = bus.matching_subscriptions(&pattern);
+    let subs = bus.matching_subscriptions(&pattern);
     subs.iter()
         .map(|s| s.handler.handler_id.as_ptr().cast::<c_char>())
         .collect::<Vec<*const c_char>>()
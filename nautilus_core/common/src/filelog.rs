@@ -0,0 +1,827 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! An append-only file log backing for the message bus.
+//!
+//! Messages are written to one stream per topic prefix, under the directory
+//! `{path}/{stream_name}/{topic_prefix}/`, with any path separators or `..` in the
+//! topic prefix escaped so it stays a single directory. Each stream is a sequence of segment files
+//! named by the UNIX timestamp (nanoseconds) of their first record, with a new segment
+//! started once the current one reaches the configured maximum size.
+//!
+//! Each record in a segment is length-prefixed (all integers are little-endian):
+//! `[u32 record length][u64 ts_init][u32 topic length][topic][payload]`.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use nautilus_core::{
+    time::{duration_since_unix_epoch, UnixNanos},
+    uuid::UUID4,
+};
+use nautilus_model::identifiers::trader_id::TraderId;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::msgbus::{get_buffer_interval, get_stream_name, BusMessage, STREAM_DELIMITER};
+
+const SEGMENT_EXTENSION: &str = "seg";
+const DEFAULT_MAX_SEGMENT_BYTES: u64 = 64 * 1024 * 1024;
+const TOPIC_PREFIX_DEPTH: usize = 2;
+const TRIM_INTERVAL: Duration = Duration::from_secs(60);
+const HEADER_LEN: usize = 4;
+const FIXED_BODY_LEN: usize = 12;
+
+/// Represents a message record read from a file log segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SegmentRecord {
+    /// UNIX timestamp (nanoseconds) when the message was written.
+    pub ts_init: UnixNanos,
+    /// The message written.
+    pub message: BusMessage,
+}
+
+/// Configuration for a [`FileLogWriter`].
+#[derive(Clone, Debug)]
+pub struct FileLogConfig {
+    /// The root directory for the stream directories.
+    pub path: PathBuf,
+    /// The stream name prefixing each stream directory.
+    pub stream_name: String,
+    /// The maximum size (bytes) of a segment before a new segment is started.
+    pub max_segment_bytes: u64,
+    /// The age beyond which segments are trimmed (if any).
+    pub autotrim_duration: Option<Duration>,
+}
+
+impl FileLogConfig {
+    /// Creates a new [`FileLogConfig`] from the message bus `config`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the database config has no `path`.
+    pub fn from_bus_config(
+        trader_id: TraderId,
+        instance_id: UUID4,
+        config: &HashMap<String, Value>,
+    ) -> anyhow::Result<Self> {
+        let database_config = config
+            .get("database")
+            .ok_or(anyhow::anyhow!("No database config"))?;
+        let path = database_config
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or(anyhow::anyhow!("No `path` in file database config"))?;
+        let max_segment_bytes = database_config
+            .get("max_segment_bytes")
+            .and_then(Value::as_u64)
+            .unwrap_or(DEFAULT_MAX_SEGMENT_BYTES);
+        let autotrim_mins = config
+            .get("autotrim_mins")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+
+        Ok(Self {
+            path: PathBuf::from(path),
+            stream_name: get_stream_name(trader_id, instance_id, config),
+            max_segment_bytes,
+            autotrim_duration: (autotrim_mins > 0).then(|| Duration::from_secs(autotrim_mins * 60)),
+        })
+    }
+
+    /// Returns the directory for the stream of the given `topic`.
+    #[must_use]
+    pub fn stream_dir(&self, topic: &str) -> PathBuf {
+        let mut dir = self.path.clone();
+        for component in self.stream_name.split(STREAM_DELIMITER) {
+            if !component.is_empty() {
+                dir.push(component);
+            }
+        }
+        dir.push(stream_dir_name(topic_prefix(topic)));
+        dir
+    }
+}
+
+/// Handles messages by writing them to append-only file log segments.
+///
+/// Messages are buffered and written at the configured `buffer_interval_ms`, with each
+/// stream trimmed of segments older than `autotrim_mins` at most once a minute.
+pub fn handle_messages_with_file(
+    rx: Receiver<BusMessage>,
+    trader_id: TraderId,
+    instance_id: UUID4,
+    config: HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    let mut writer = FileLogWriter::new(FileLogConfig::from_bus_config(
+        trader_id,
+        instance_id,
+        &config,
+    )?);
+    debug!("Writing msgbus file log to {:?}", writer.config.path);
+
+    // Buffering
+    let mut buffer: VecDeque<BusMessage> = VecDeque::new();
+    let mut last_drain = Instant::now();
+    let recv_interval = Duration::from_millis(1);
+    let buffer_interval = get_buffer_interval(&config);
+
+    loop {
+        if last_drain.elapsed() >= buffer_interval && !buffer.is_empty() {
+            writer.write_all(buffer.drain(..), unix_nanos_now())?;
+            last_drain = Instant::now();
+        } else {
+            // Continue to receive and handle messages until channel is hung up
+            match rx.try_recv() {
+                Ok(msg) => buffer.push_back(msg),
+                Err(TryRecvError::Empty) => thread::sleep(recv_interval),
+                Err(TryRecvError::Disconnected) => break, // Channel hung up
+            }
+        }
+    }
+
+    // Drain any remaining messages
+    if !buffer.is_empty() {
+        writer.write_all(buffer.drain(..), unix_nanos_now())?;
+    }
+
+    Ok(())
+}
+
+/// The segment currently being appended to for a stream.
+#[derive(Debug)]
+struct ActiveSegment {
+    writer: BufWriter<File>,
+    ts_start: UnixNanos,
+    size: u64,
+    last_trim: Option<UnixNanos>,
+}
+
+/// Provides a writer of messages to append-only file log segments.
+#[derive(Debug)]
+pub struct FileLogWriter {
+    pub config: FileLogConfig,
+    segments: HashMap<PathBuf, ActiveSegment>,
+}
+
+impl FileLogWriter {
+    /// Creates a new [`FileLogWriter`] instance.
+    #[must_use]
+    pub fn new(config: FileLogConfig) -> Self {
+        Self {
+            config,
+            segments: HashMap::new(),
+        }
+    }
+
+    /// Writes the `msgs` to their streams at `ts_init` then flushes, trimming any
+    /// streams written to which are due.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if writing to a segment file fails.
+    pub fn write_all(
+        &mut self,
+        msgs: impl IntoIterator<Item = BusMessage>,
+        ts_init: UnixNanos,
+    ) -> anyhow::Result<()> {
+        for msg in msgs {
+            self.write(&msg, ts_init)?;
+        }
+        self.flush()?;
+
+        if let Some(autotrim_duration) = self.config.autotrim_duration {
+            let trim_interval = TRIM_INTERVAL.as_nanos() as UnixNanos;
+            let cutoff = ts_init.saturating_sub(autotrim_duration.as_nanos() as UnixNanos);
+            for (dir, segment) in &mut self.segments {
+                if segment
+                    .last_trim
+                    .is_some_and(|last_trim| ts_init < last_trim + trim_interval)
+                {
+                    continue;
+                }
+                trim_segments(dir, cutoff)?;
+                segment.last_trim = Some(ts_init);
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the `msg` to the active segment of its stream, starting a new segment
+    /// if the active segment would exceed the maximum size.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if writing to a segment file fails.
+    pub fn write(&mut self, msg: &BusMessage, ts_init: UnixNanos) -> anyhow::Result<()> {
+        let record = encode_record(&msg.topic, &msg.payload, ts_init);
+        let dir = self.config.stream_dir(&msg.topic);
+
+        let rotate = match self.segments.get(&dir) {
+            Some(segment) => {
+                segment.size > 0
+                    && segment.size + record.len() as u64 > self.config.max_segment_bytes
+            }
+            None => {
+                let segment = open_active_segment(&dir, ts_init, self.config.max_segment_bytes)?;
+                self.segments.insert(dir.clone(), segment);
+                false
+            }
+        };
+        if rotate {
+            let last_trim = self.segments[&dir].last_trim;
+            let ts_start = ts_init.max(self.segments[&dir].ts_start + 1);
+            let mut segment = create_segment(&dir, ts_start)?;
+            segment.last_trim = last_trim;
+            if let Some(mut previous) = self.segments.insert(dir.clone(), segment) {
+                previous.writer.flush()?;
+            }
+        }
+
+        let segment = self
+            .segments
+            .get_mut(&dir)
+            .expect("Active segment should exist");
+        segment.writer.write_all(&record)?;
+        segment.size += record.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes all active segments to disk.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if flushing a segment file fails.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        for segment in self.segments.values_mut() {
+            segment.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Provides a reader of the records in the segments of a file log stream, in the order
+/// they were written.
+///
+/// Once all records have been read, further calls to [`SegmentReader::read_next`] return
+/// any records written since, so the reader can replay a stream and then tail it.
+#[derive(Debug)]
+pub struct SegmentReader {
+    dir: PathBuf,
+    segment: Option<PathBuf>,
+    offset: u64,
+}
+
+impl SegmentReader {
+    /// Creates a new [`SegmentReader`] for the stream directory `dir`, starting from
+    /// its oldest segment.
+    #[must_use]
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            segment: None,
+            offset: 0,
+        }
+    }
+
+    /// Returns the next record in the stream, or `None` if all records written so far
+    /// have been read.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a segment cannot be read or a record is invalid.
+    pub fn read_next(&mut self) -> anyhow::Result<Option<SegmentRecord>> {
+        loop {
+            if self.segment.is_none() {
+                match list_segments(&self.dir)?.into_iter().next() {
+                    Some(first) => self.segment = Some(first),
+                    None => return Ok(None), // No segments yet
+                }
+            }
+            let segment = self.segment.clone().expect("Segment should be set");
+
+            if let Some(record) = self.read_record(&segment)? {
+                return Ok(Some(record));
+            }
+
+            // Move to the next segment only once the current one is exhausted
+            let next = list_segments(&self.dir)?
+                .into_iter()
+                .find(|path| path > &segment);
+            match next {
+                Some(next) => {
+                    if self.has_partial_record(&segment)? {
+                        warn!("Skipping truncated record at end of {segment:?}");
+                    }
+                    self.segment = Some(next);
+                    self.offset = 0;
+                }
+                None => return Ok(None), // Caught up
+            }
+        }
+    }
+
+    /// Reads all records written so far.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a segment cannot be read or a record is invalid.
+    pub fn replay(&mut self) -> anyhow::Result<Vec<SegmentRecord>> {
+        let mut records = Vec::new();
+        while let Some(record) = self.read_next()? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    /// Tails the stream, passing each record to the `handler` as it is written, polling
+    /// for new records at the `poll_interval`. Tailing stops when the `handler` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a segment cannot be read or a record is invalid.
+    pub fn tail(
+        &mut self,
+        poll_interval: Duration,
+        mut handler: impl FnMut(SegmentRecord) -> bool,
+    ) -> anyhow::Result<()> {
+        loop {
+            match self.read_next()? {
+                Some(record) => {
+                    if !handler(record) {
+                        return Ok(());
+                    }
+                }
+                None => thread::sleep(poll_interval),
+            }
+        }
+    }
+
+    fn read_record(&mut self, segment: &Path) -> anyhow::Result<Option<SegmentRecord>> {
+        let mut file = match File::open(segment) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None), // Trimmed
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset + HEADER_LEN as u64 {
+            return Ok(None); // No complete header yet
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut header = [0u8; HEADER_LEN];
+        file.read_exact(&mut header)?;
+        let record_len = u64::from(u32::from_le_bytes(header));
+        if len < self.offset + HEADER_LEN as u64 + record_len {
+            return Ok(None); // Record still being written
+        }
+
+        let mut body = vec![0u8; record_len as usize];
+        file.read_exact(&mut body)?;
+        let record = decode_record(&body)?;
+        self.offset += HEADER_LEN as u64 + record_len;
+        Ok(Some(record))
+    }
+
+    fn has_partial_record(&self, segment: &Path) -> anyhow::Result<bool> {
+        match fs::metadata(segment) {
+            Ok(metadata) => Ok(metadata.len() > self.offset),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// Returns the stream prefix for the `topic`, being its first components.
+#[must_use]
+pub fn topic_prefix(topic: &str) -> &str {
+    match topic.match_indices('.').nth(TOPIC_PREFIX_DEPTH - 1) {
+        Some((index, _)) => &topic[..index],
+        None => topic,
+    }
+}
+
+/// Returns the `prefix` escaped as a single directory name, so a topic cannot
+/// address a path outside of its stream directory.
+fn stream_dir_name(prefix: &str) -> String {
+    let name = prefix.replace(['/', '\\', ':'], "_");
+    if name.chars().all(|c| c == '.') {
+        format!("_{name}") // Not a current or parent directory
+    } else {
+        name
+    }
+}
+
+fn unix_nanos_now() -> UnixNanos {
+    duration_since_unix_epoch().as_nanos() as UnixNanos
+}
+
+fn segment_path(dir: &Path, ts_start: UnixNanos) -> PathBuf {
+    dir.join(format!("{ts_start:020}.{SEGMENT_EXTENSION}"))
+}
+
+fn segment_ts_start(path: &Path) -> Option<UnixNanos> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Returns the segment files in the stream directory `dir`, oldest first.
+fn list_segments(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut segments = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == SEGMENT_EXTENSION)
+            && segment_ts_start(&path).is_some()
+        {
+            segments.push(path);
+        }
+    }
+    // Zero padded names sort in time order
+    segments.sort();
+    Ok(segments)
+}
+
+fn create_segment(dir: &Path, ts_start: UnixNanos) -> anyhow::Result<ActiveSegment> {
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new()
+        .create_new(true)
+        .append(true)
+        .open(segment_path(dir, ts_start))?;
+    Ok(ActiveSegment {
+        writer: BufWriter::new(file),
+        ts_start,
+        size: 0,
+        last_trim: None,
+    })
+}
+
+/// Opens the latest segment in `dir` for appending if it has space, otherwise creates
+/// a new segment.
+fn open_active_segment(
+    dir: &Path,
+    ts_init: UnixNanos,
+    max_segment_bytes: u64,
+) -> anyhow::Result<ActiveSegment> {
+    let Some(latest) = list_segments(dir)?.pop() else {
+        return create_segment(dir, ts_init);
+    };
+    let ts_start = segment_ts_start(&latest).expect("Segment name should be a timestamp");
+    let size = fs::metadata(&latest)?.len();
+    if size >= max_segment_bytes {
+        return create_segment(dir, ts_init.max(ts_start + 1));
+    }
+
+    let file = OpenOptions::new().append(true).open(&latest)?;
+    Ok(ActiveSegment {
+        writer: BufWriter::new(file),
+        ts_start,
+        size,
+        last_trim: None,
+    })
+}
+
+/// Removes the segments in `dir` containing only records written before the `cutoff`.
+///
+/// A segment only contains records written before the start of the next segment,
+/// so the latest segment is never removed.
+fn trim_segments(dir: &Path, cutoff: UnixNanos) -> anyhow::Result<usize> {
+    let segments = list_segments(dir)?;
+    let mut removed = 0;
+    for pair in segments.windows(2) {
+        let next_start = segment_ts_start(&pair[1]).unwrap_or_default();
+        if next_start > cutoff {
+            break;
+        }
+        fs::remove_file(&pair[0])?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+fn encode_record(topic: &str, payload: &[u8], ts_init: UnixNanos) -> Vec<u8> {
+    let record_len = FIXED_BODY_LEN + topic.len() + payload.len();
+    let mut record = Vec::with_capacity(HEADER_LEN + record_len);
+    record.extend_from_slice(&(record_len as u32).to_le_bytes());
+    record.extend_from_slice(&ts_init.to_le_bytes());
+    record.extend_from_slice(&(topic.len() as u32).to_le_bytes());
+    record.extend_from_slice(topic.as_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+fn decode_record(body: &[u8]) -> anyhow::Result<SegmentRecord> {
+    anyhow::ensure!(
+        body.len() >= FIXED_BODY_LEN,
+        "Invalid record, length {} less than {FIXED_BODY_LEN}",
+        body.len()
+    );
+    let ts_init = u64::from_le_bytes(body[..8].try_into()?);
+    let topic_len = u32::from_le_bytes(body[8..12].try_into()?) as usize;
+    anyhow::ensure!(
+        body.len() >= FIXED_BODY_LEN + topic_len,
+        "Invalid record, topic length {topic_len} exceeds record"
+    );
+    let topic = std::str::from_utf8(&body[FIXED_BODY_LEN..FIXED_BODY_LEN + topic_len])?;
+    let payload = body[FIXED_BODY_LEN + topic_len..].to_vec();
+
+    Ok(SegmentRecord {
+        ts_init,
+        message: BusMessage {
+            topic: topic.to_string(),
+            payload,
+        },
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::path::Component;
+
+    use rstest::rstest;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn config(path: &Path, max_segment_bytes: u64) -> FileLogConfig {
+        FileLogConfig {
+            path: path.to_path_buf(),
+            stream_name: "trader-TESTER-001:streams:".to_string(),
+            max_segment_bytes,
+            autotrim_duration: None,
+        }
+    }
+
+    fn msg(topic: &str, payload: &str) -> BusMessage {
+        BusMessage {
+            topic: topic.to_string(),
+            payload: payload.as_bytes().to_vec(),
+        }
+    }
+
+    #[rstest]
+    #[case("events.order.S-001", "events.order")]
+    #[case("data.quotes.AUD/USD.SIM", "data.quotes")]
+    #[case("events", "events")]
+    fn test_topic_prefix(#[case] topic: &str, #[case] expected: &str) {
+        assert_eq!(topic_prefix(topic), expected);
+    }
+
+    #[rstest]
+    #[case("events.order", "events.order")]
+    #[case("/etc/passwd", "_etc_passwd")]
+    #[case("../../tmp", ".._.._tmp")]
+    #[case("..", "_..")]
+    #[case("", "_")]
+    #[case("C:\\logs", "C__logs")]
+    fn test_stream_dir_name(#[case] prefix: &str, #[case] expected: &str) {
+        assert_eq!(stream_dir_name(prefix), expected);
+    }
+
+    #[rstest]
+    fn test_stream_dir_stays_within_log_dir() {
+        let config = FileLogConfig {
+            path: PathBuf::from("/tmp/logs"),
+            stream_name: "trader-001".to_string(),
+            max_segment_bytes: DEFAULT_MAX_SEGMENT_BYTES,
+            autotrim_duration: None,
+        };
+
+        for topic in ["/etc.passwd", "../..", "..", "a/../../b.c"] {
+            let dir = config.stream_dir(topic);
+            assert_eq!(dir.parent(), Some(Path::new("/tmp/logs/trader-001")));
+            assert!(!dir.components().any(|c| c == Component::ParentDir));
+        }
+    }
+
+    #[rstest]
+    fn test_encode_decode_record_round_trip() {
+        let record = encode_record("events.order.S-001", b"payload", 5);
+
+        let decoded = decode_record(&record[HEADER_LEN..]).unwrap();
+
+        assert_eq!(
+            decoded,
+            SegmentRecord {
+                ts_init: 5,
+                message: msg("events.order.S-001", "payload"),
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_decode_record_with_invalid_topic_length() {
+        let mut record = encode_record("events", b"", 5);
+        record[HEADER_LEN + 8] = 100;
+
+        assert!(decode_record(&record[HEADER_LEN..]).is_err());
+    }
+
+    #[rstest]
+    fn test_from_bus_config() {
+        let mut config = HashMap::new();
+        config.insert(
+            "database".to_string(),
+            json!({"type": "file", "path": "/tmp/msgbus", "max_segment_bytes": 1024}),
+        );
+        config.insert("autotrim_mins".to_string(), json!(10));
+        config.insert("use_trader_prefix".to_string(), json!(true));
+        config.insert("use_trader_id".to_string(), json!(true));
+        config.insert("streams_prefix".to_string(), json!("streams"));
+
+        let config =
+            FileLogConfig::from_bus_config(TraderId::from("TESTER-001"), UUID4::new(), &config)
+                .unwrap();
+
+        assert_eq!(config.max_segment_bytes, 1024);
+        assert_eq!(config.autotrim_duration, Some(Duration::from_secs(600)));
+        assert_eq!(
+            config.stream_dir("events.order.S-001"),
+            PathBuf::from("/tmp/msgbus/trader-TESTER-001/streams/events.order")
+        );
+    }
+
+    #[rstest]
+    fn test_handle_messages_with_file_drains_on_hang_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = HashMap::new();
+        config.insert(
+            "database".to_string(),
+            json!({"type": "file", "path": temp_dir.path()}),
+        );
+        config.insert("streams_prefix".to_string(), json!("streams"));
+        let file_config =
+            FileLogConfig::from_bus_config(TraderId::from("TESTER-001"), UUID4::new(), &config)
+                .unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel::<BusMessage>();
+        tx.send(msg("events.order.S-001", "1")).unwrap();
+        tx.send(msg("events.order.S-001", "2")).unwrap();
+        drop(tx);
+        handle_messages_with_file(rx, TraderId::from("TESTER-001"), UUID4::new(), config).unwrap();
+
+        let records = SegmentReader::new(file_config.stream_dir("events.order.S-001"))
+            .replay()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+    }
+
+    #[rstest]
+    fn test_write_then_replay_by_stream() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = config(temp_dir.path(), DEFAULT_MAX_SEGMENT_BYTES);
+        let mut writer = FileLogWriter::new(config.clone());
+
+        writer
+            .write_all(
+                vec![
+                    msg("events.order.S-001", "1"),
+                    msg("data.quotes.AUD/USD.SIM", "2"),
+                    msg("events.order.S-002", "3"),
+                ],
+                1,
+            )
+            .unwrap();
+
+        let mut reader = SegmentReader::new(config.stream_dir("events.order"));
+        let records = reader.replay().unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.message.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                msg("events.order.S-001", "1"),
+                msg("events.order.S-002", "3")
+            ]
+        );
+        let mut reader = SegmentReader::new(config.stream_dir("data.quotes"));
+        assert_eq!(reader.replay().unwrap().len(), 1);
+    }
+
+    #[rstest]
+    fn test_write_rotates_segments_at_max_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let record_len = encode_record("events.order.S-001", b"1", 0).len() as u64;
+        let config = config(temp_dir.path(), record_len * 2);
+        let mut writer = FileLogWriter::new(config.clone());
+
+        for ts in 1..=5 {
+            writer
+                .write_all(vec![msg("events.order.S-001", &ts.to_string())], ts)
+                .unwrap();
+        }
+
+        let dir = config.stream_dir("events.order");
+        assert_eq!(list_segments(&dir).unwrap().len(), 3);
+        let payloads: Vec<Vec<u8>> = SegmentReader::new(&dir)
+            .replay()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.message.payload)
+            .collect();
+        assert_eq!(payloads, vec![b"1", b"2", b"3", b"4", b"5"]);
+    }
+
+    #[rstest]
+    fn test_reader_tails_new_records_across_segments() {
+        let temp_dir = TempDir::new().unwrap();
+        let record_len = encode_record("events.order.S-001", b"1", 0).len() as u64;
+        let config = config(temp_dir.path(), record_len);
+        let mut writer = FileLogWriter::new(config.clone());
+        let mut reader = SegmentReader::new(config.stream_dir("events.order"));
+
+        assert_eq!(reader.read_next().unwrap(), None);
+
+        writer
+            .write_all(vec![msg("events.order.S-001", "1")], 1)
+            .unwrap();
+        assert_eq!(reader.read_next().unwrap().unwrap().ts_init, 1);
+        assert_eq!(reader.read_next().unwrap(), None);
+
+        writer
+            .write_all(vec![msg("events.order.S-001", "2")], 2)
+            .unwrap();
+        let mut tailed = Vec::new();
+        reader
+            .tail(Duration::from_millis(1), |record| {
+                tailed.push(record.ts_init);
+                false
+            })
+            .unwrap();
+        assert_eq!(tailed, vec![2]);
+    }
+
+    #[rstest]
+    fn test_writer_appends_to_existing_segment_on_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = config(temp_dir.path(), DEFAULT_MAX_SEGMENT_BYTES);
+
+        FileLogWriter::new(config.clone())
+            .write_all(vec![msg("events.order.S-001", "1")], 1)
+            .unwrap();
+        FileLogWriter::new(config.clone())
+            .write_all(vec![msg("events.order.S-001", "2")], 2)
+            .unwrap();
+
+        let dir = config.stream_dir("events.order");
+        assert_eq!(list_segments(&dir).unwrap().len(), 1);
+        assert_eq!(SegmentReader::new(&dir).replay().unwrap().len(), 2);
+    }
+
+    #[rstest]
+    fn test_autotrim_removes_segments_older_than_cutoff() {
+        let temp_dir = TempDir::new().unwrap();
+        let record_len = encode_record("events.order.S-001", b"1", 0).len() as u64;
+        let mut config = config(temp_dir.path(), record_len);
+        config.autotrim_duration = Some(Duration::from_nanos(10));
+        let mut writer = FileLogWriter::new(config.clone());
+        let dir = config.stream_dir("events.order");
+
+        writer
+            .write_all(vec![msg("events.order.S-001", "1")], 1)
+            .unwrap();
+        writer
+            .write_all(vec![msg("events.order.S-001", "2")], 5)
+            .unwrap();
+        assert_eq!(list_segments(&dir).unwrap().len(), 2);
+
+        // Segments are trimmed at most once per trim interval
+        let ts_trim = TRIM_INTERVAL.as_nanos() as UnixNanos + 20;
+        writer
+            .write_all(vec![msg("events.order.S-001", "3")], ts_trim)
+            .unwrap();
+
+        // The second segment may hold records up to the start of the latest, so is kept
+        let records = SegmentReader::new(&dir).replay().unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.ts_init).collect::<Vec<_>>(),
+            vec![5, ts_trim]
+        );
+    }
+}
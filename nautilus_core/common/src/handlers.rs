@@ -15,7 +15,7 @@
 
 #[cfg(not(feature = "python"))]
 use std::ffi::c_char;
-use std::{fmt, sync::Arc};
+use std::{any::Any, fmt, rc::Rc, sync::Arc};
 
 #[cfg(not(feature = "python"))]
 use nautilus_core::message::Message;
@@ -37,6 +37,25 @@ pub struct SafeMessageCallback {
 unsafe impl Send for SafeMessageCallback {}
 unsafe impl Sync for SafeMessageCallback {}
 
+/// A Rust-native callback which receives typed messages as `&dyn Any`.
+pub type AnyMessageCallback = dyn Fn(&dyn Any) + Send + Sync;
+
+/// A Rust-native callback which receives typed messages as `&dyn Any`, for components
+/// owned by the thread which handles the messages.
+///
+/// These callbacks may capture single-threaded shared state (such as `Rc<RefCell<T>>`),
+/// and so are only held by a [`LocalMessageHandler`].
+pub type LocalAnyMessageCallback = dyn Fn(&dyn Any);
+
+#[derive(Clone)]
+pub struct SafeAnyMessageCallback {
+    pub callback: Arc<AnyMessageCallback>,
+}
+
+/// A Rust-native callback which receives time events, for components owned by the
+/// thread which advances the clock (such as a `TestClock` in a backtest).
+pub type LocalTimeEventCallback = dyn Fn(&TimeEvent);
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct SafeTimeEventCallback {
@@ -55,6 +74,7 @@ unsafe impl Sync for SafeTimeEventCallback {}
 pub struct MessageHandler {
     pub handler_id: Ustr,
     _callback: Option<SafeMessageCallback>,
+    any_callback: Option<SafeAnyMessageCallback>,
 }
 
 impl MessageHandler {
@@ -63,6 +83,24 @@ impl MessageHandler {
         Self {
             handler_id,
             _callback: callback,
+            any_callback: None,
+        }
+    }
+
+    /// Creates a new handler which will receive typed Rust messages.
+    #[must_use]
+    pub fn with_any_callback(handler_id: Ustr, callback: SafeAnyMessageCallback) -> Self {
+        Self {
+            handler_id,
+            _callback: None,
+            any_callback: Some(callback),
+        }
+    }
+
+    /// Handles the given typed `message` with the Rust-native callback (if set).
+    pub fn handle(&self, message: &dyn Any) {
+        if let Some(callback) = &self.any_callback {
+            (callback.callback)(message);
         }
     }
 }
@@ -81,6 +119,45 @@ impl fmt::Debug for MessageHandler {
     }
 }
 
+/// Handles typed Rust messages on the thread which owns the message bus.
+///
+/// Unlike a [`MessageHandler`], the callback may capture single-threaded shared state,
+/// and so the handler can not be sent to another thread.
+#[derive(Clone)]
+pub struct LocalMessageHandler {
+    pub handler_id: Ustr,
+    callback: Rc<LocalAnyMessageCallback>,
+}
+
+impl LocalMessageHandler {
+    #[must_use]
+    pub fn new(handler_id: Ustr, callback: Rc<LocalAnyMessageCallback>) -> Self {
+        Self {
+            handler_id,
+            callback,
+        }
+    }
+
+    /// Handles the given typed `message` with the callback.
+    pub fn handle(&self, message: &dyn Any) {
+        (self.callback)(message);
+    }
+}
+
+impl PartialEq for LocalMessageHandler {
+    fn eq(&self, other: &Self) -> bool {
+        self.handler_id == other.handler_id
+    }
+}
+
+impl fmt::Debug for LocalMessageHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(stringify!(LocalMessageHandler))
+            .field("handler_id", &self.handler_id)
+            .finish()
+    }
+}
+
 #[derive(Clone)]
 #[cfg_attr(
     feature = "python",
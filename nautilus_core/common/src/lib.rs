@@ -13,10 +13,12 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod aggregation;
 pub mod cache;
 pub mod clock;
 pub mod enums;
 pub mod factories;
+pub mod filelog;
 pub mod generators;
 pub mod handlers;
 pub mod logging;
@@ -24,6 +26,7 @@ pub mod msgbus;
 pub mod runtime;
 pub mod testing;
 pub mod timer;
+pub mod xrate;
 
 #[cfg(feature = "stubs")]
 pub mod stubs;
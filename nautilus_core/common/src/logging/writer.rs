@@ -14,16 +14,22 @@
 // -------------------------------------------------------------------------------------------------
 
 use std::{
-    fs::{create_dir_all, File},
+    fs::{self, create_dir_all, File},
     io::{self, BufWriter, Stderr, Stdout, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+    time::SystemTime,
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Utc};
+use flate2::{write::GzEncoder, Compression};
 use log::LevelFilter;
 
 use crate::logging::logger::LogLine;
 
+const GZIP_EXTENSION: &str = "gz";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H-%M-%S-%9f";
+
 pub trait LogWriter {
     /// Writes a log line.
     fn write(&mut self, line: &str);
@@ -112,9 +118,20 @@ impl LogWriter for StderrWriter {
 )]
 #[derive(Debug, Clone, Default)]
 pub struct FileWriterConfig {
+    /// The directory to write log files to (the current directory if `None`).
     pub directory: Option<String>,
+    /// The base name for log files (derived from the trader ID, date and instance ID if `None`).
     pub file_name: Option<String>,
+    /// The log file format, either plain text (the default) or "json".
     pub file_format: Option<String>,
+    /// The maximum size (bytes) of a log file before it is rotated.
+    pub max_file_size: Option<u64>,
+    /// The interval at which log files are rotated, either "daily" (the default) or "hourly".
+    pub rotation_interval: Option<String>,
+    /// The maximum number of rotated log files to keep, with older files deleted.
+    pub max_backup_count: Option<usize>,
+    /// If rotated log files are compressed with gzip.
+    pub compress_backups: bool,
 }
 
 impl FileWriterConfig {
@@ -122,15 +139,48 @@ impl FileWriterConfig {
         directory: Option<String>,
         file_name: Option<String>,
         file_format: Option<String>,
+        max_file_size: Option<u64>,
+        rotation_interval: Option<String>,
+        max_backup_count: Option<usize>,
+        compress_backups: bool,
     ) -> Self {
         Self {
             directory,
             file_name,
             file_format,
+            max_file_size,
+            rotation_interval,
+            max_backup_count,
+            compress_backups,
         }
     }
 }
 
+/// The interval at which log files are rotated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RotationInterval {
+    Daily,
+    Hourly,
+}
+
+impl RotationInterval {
+    /// Returns the start of the next interval following `now`.
+    fn next_boundary(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let start = match self {
+            Self::Daily => now.date_naive().and_time(NaiveTime::MIN),
+            Self::Hourly => now
+                .date_naive()
+                .and_hms_opt(now.hour(), 0, 0)
+                .expect("Hour of the current time should be valid"),
+        };
+        let interval = match self {
+            Self::Daily => TimeDelta::try_days(1).expect("Invalid interval"),
+            Self::Hourly => TimeDelta::try_hours(1).expect("Invalid interval"),
+        };
+        start.and_utc() + interval
+    }
+}
+
 #[derive(Debug)]
 pub struct FileWriter {
     pub json_format: bool,
@@ -140,6 +190,10 @@ pub struct FileWriter {
     trader_id: String,
     instance_id: String,
     level: LevelFilter,
+    rotation_interval: RotationInterval,
+    next_rotation: DateTime<Utc>,
+    file_size: u64,
+    backup_task: Option<JoinHandle<()>>,
 }
 
 impl FileWriter {
@@ -161,22 +215,38 @@ impl FileWriter {
             }
         };
 
+        let rotation_interval = match file_config
+            .rotation_interval
+            .as_ref()
+            .map(|s| s.to_lowercase())
+        {
+            Some(ref interval) if interval == "hourly" => RotationInterval::Hourly,
+            Some(ref interval) if interval == "daily" => RotationInterval::Daily,
+            None => RotationInterval::Daily,
+            Some(ref unrecognized) => {
+                eprintln!(
+                    "Unrecognized log file rotation interval: {unrecognized}. Using daily rotation as default."
+                );
+                RotationInterval::Daily
+            }
+        };
+
         let file_path =
             Self::create_log_file_path(&file_config, &trader_id, &instance_id, json_format);
 
-        match File::options()
-            .create(true)
-            .append(true)
-            .open(file_path.clone())
-        {
-            Ok(file) => Some(Self {
+        match Self::open_log_file(&file_path) {
+            Ok((buf, file_size)) => Some(Self {
                 json_format,
-                buf: BufWriter::new(file),
+                buf,
                 path: file_path,
                 file_config,
                 trader_id,
                 instance_id,
                 level: fileout_level,
+                rotation_interval,
+                next_rotation: rotation_interval.next_boundary(Utc::now()),
+                file_size,
+                backup_task: None,
             }),
             Err(e) => {
                 eprintln!("Error creating log file: {}", e);
@@ -212,50 +282,253 @@ impl FileWriter {
         file_path
     }
 
-    pub fn should_rotate_file(&self) -> bool {
-        let current_date_utc = Utc::now().date_naive();
-        let metadata = self
+    /// Opens the log file at `path` for appending, returning the writer and the current
+    /// size of the file.
+    fn open_log_file(path: &Path) -> io::Result<(BufWriter<File>, u64)> {
+        let file = File::options().create(true).append(true).open(path)?;
+        let file_size = file.metadata()?.len();
+        Ok((BufWriter::new(file), file_size))
+    }
+
+    /// Returns whether the log file should be rotated before writing a line of `line_len`
+    /// bytes at `now`.
+    ///
+    /// The check is made against in-memory counters so is cheap enough for every write.
+    pub fn should_rotate_file(&self, line_len: usize, now: DateTime<Utc>) -> bool {
+        if now >= self.next_rotation {
+            return true;
+        }
+
+        // A single line is never split, so an empty file is always written to
+        self.file_config.max_file_size.is_some_and(|max_file_size| {
+            self.file_size > 0 && self.file_size + line_len as u64 > max_file_size
+        })
+    }
+
+    fn rotate_file(&mut self, now: DateTime<Utc>) {
+        self.flush();
+
+        let file_path = Self::create_log_file_path(
+            &self.file_config,
+            &self.trader_id,
+            &self.instance_id,
+            self.json_format,
+        );
+
+        // The rotated file is moved aside if the new file would reuse its name
+        let mut rotated_path = self.path.clone();
+        if file_path == self.path {
+            let backup_path = self.create_backup_path(now);
+            match fs::rename(&self.path, &backup_path) {
+                Ok(()) => rotated_path = backup_path,
+                Err(e) => eprintln!("Error renaming rotated log file: {e}"),
+            }
+        }
+
+        match Self::open_log_file(&file_path) {
+            Ok((buf, file_size)) => {
+                self.buf = buf;
+                self.path = file_path;
+                self.file_size = file_size;
+            }
+            Err(e) => eprintln!("Error creating log file: {}", e),
+        }
+        self.next_rotation = self.rotation_interval.next_boundary(now);
+
+        if rotated_path == self.path {
+            return; // Still writing to the same file
+        }
+
+        let compress_backups = self.file_config.compress_backups;
+        let max_backup_count = self.file_config.max_backup_count;
+        if !compress_backups && max_backup_count.is_none() {
+            return;
+        }
+
+        // Compress and remove backups off the logging thread, one rotation at a time
+        self.join_backup_task();
+        let backups = self.backup_files();
+        let current_path = self.path.clone();
+        let task = thread::Builder::new()
+            .name("log-backups".to_string())
+            .spawn(move || {
+                if compress_backups {
+                    if let Err(e) = compress_file(&rotated_path) {
+                        eprintln!("Error compressing rotated log file: {e}");
+                    }
+                }
+                if let Some(max_backup_count) = max_backup_count {
+                    backups.remove_old(&current_path, max_backup_count);
+                }
+            });
+        match task {
+            Ok(handle) => self.backup_task = Some(handle),
+            Err(e) => eprintln!("Error spawning log backup thread: {e}"),
+        }
+    }
+
+    /// Waits for the compression and removal of backups from the last rotation.
+    fn join_backup_task(&mut self) {
+        if let Some(handle) = self.backup_task.take() {
+            if handle.join().is_err() {
+                eprintln!("Error joining log backup thread");
+            }
+        }
+    }
+
+    fn backup_files(&self) -> BackupFiles {
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        BackupFiles {
+            directory,
+            file_name: self.file_config.file_name.clone(),
+            trader_id: self.trader_id.clone(),
+            instance_id: self.instance_id.clone(),
+            suffix: if self.json_format { "json" } else { "log" },
+        }
+    }
+
+    fn create_backup_path(&self, now: DateTime<Utc>) -> PathBuf {
+        let stem = self
             .path
-            .metadata()
-            .expect("Failed to read log file metadata");
-        let creation_time = metadata
-            .created()
-            .expect("Failed to get log file creation time");
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let suffix = if self.json_format { "json" } else { "log" };
+        let timestamp = now.format(BACKUP_TIMESTAMP_FORMAT);
+
+        let mut backup_path = self
+            .path
+            .with_file_name(format!("{stem}_{timestamp}.{suffix}"));
+        let mut count = 1;
+        while backup_path.exists() || gzip_path(&backup_path).exists() {
+            backup_path = self
+                .path
+                .with_file_name(format!("{stem}_{timestamp}_{count}.{suffix}"));
+            count += 1;
+        }
+        backup_path
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        self.join_backup_task();
+    }
+}
+
+/// Identifies the rotated log files of a [`FileWriter`] by name.
+///
+/// Rotated files are named after the log file they were rotated from, being either:
+/// - `{file_name}_{timestamp}` for a configured file name.
+/// - `{trader_id}_{date}_{instance_id}` or `{trader_id}_{date}_{instance_id}_{timestamp}`
+///   for default file names.
+///
+/// Where the timestamp may be followed by a `_{count}` to make the name unique, and the
+/// file may be compressed with a `.gz` extension.
+#[derive(Clone, Debug)]
+struct BackupFiles {
+    directory: PathBuf,
+    file_name: Option<String>,
+    trader_id: String,
+    instance_id: String,
+    suffix: &'static str,
+}
+
+impl BackupFiles {
+    /// Returns whether the file `name` is a rotated log file.
+    fn is_backup(&self, name: &str) -> bool {
+        let name = name
+            .strip_suffix(&format!(".{GZIP_EXTENSION}"))
+            .unwrap_or(name);
+        let Some(base) = name.strip_suffix(&format!(".{}", self.suffix)) else {
+            return false;
+        };
+
+        if let Some(file_name) = self.file_name.as_ref() {
+            return base
+                .strip_prefix(file_name.as_str())
+                .and_then(|rest| rest.strip_prefix('_'))
+                .is_some_and(is_backup_timestamp);
+        }
+
+        let Some(rest) = base.strip_prefix(&format!("{}_", self.trader_id)) else {
+            return false;
+        };
+        let Some((date, rest)) = rest.split_once('_') else {
+            return false;
+        };
+        if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return false;
+        }
+        match rest.strip_prefix(self.instance_id.as_str()) {
+            Some("") => true,
+            Some(rest) => rest.strip_prefix('_').is_some_and(is_backup_timestamp),
+            None => false,
+        }
+    }
 
-        let creation_time_utc: DateTime<Utc> = creation_time.into();
-        let creation_date_utc = creation_time_utc.date_naive();
+    /// Removes the oldest rotated log files so that at most `max_backup_count` are kept,
+    /// never removing the `current_path` being written to.
+    fn remove_old(&self, current_path: &Path, max_backup_count: usize) {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Error reading log directory: {e}");
+                return;
+            }
+        };
 
-        current_date_utc != creation_date_utc
+        let mut backups: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry.path().file_name() != current_path.file_name()
+                    && self.is_backup(&entry.file_name().to_string_lossy())
+            })
+            .filter_map(|entry| {
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+
+        if backups.len() <= max_backup_count {
+            return;
+        }
+
+        // Oldest first
+        backups.sort();
+        let excess = backups.len() - max_backup_count;
+        for (_, path) in backups.into_iter().take(excess) {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Error removing rotated log file {path:?}: {e}");
+            }
+        }
     }
 }
 
+/// Returns whether `value` is a backup timestamp, optionally followed by a `_{count}`.
+fn is_backup_timestamp(value: &str) -> bool {
+    let (timestamp, count) = match value.split_once('_') {
+        Some((timestamp, count)) => (timestamp, Some(count)),
+        None => (value, None),
+    };
+    NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).is_ok()
+        && count.map_or(true, |count| {
+            !count.is_empty() && count.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
 impl LogWriter for FileWriter {
     fn write(&mut self, line: &str) {
-        if self.should_rotate_file() {
-            self.flush();
-
-            let file_path = Self::create_log_file_path(
-                &self.file_config,
-                &self.trader_id,
-                &self.instance_id,
-                self.json_format,
-            );
-
-            match File::options()
-                .create(true)
-                .append(true)
-                .open(file_path.clone())
-            {
-                Ok(file) => {
-                    self.buf = BufWriter::new(file);
-                    self.path = file_path;
-                }
-                Err(e) => eprintln!("Error creating log file: {}", e),
-            }
+        let now = Utc::now();
+        if self.should_rotate_file(line.len(), now) {
+            self.rotate_file(now);
         }
 
         match self.buf.write_all(line.as_bytes()) {
-            Ok(()) => {}
+            Ok(()) => self.file_size += line.len() as u64,
             Err(e) => eprintln!("Error writing to file: {e:?}"),
         }
     }
@@ -271,3 +544,216 @@ impl LogWriter for FileWriter {
         line.level <= self.level
     }
 }
+
+fn gzip_path(path: &Path) -> PathBuf {
+    let mut gzip_path = path.as_os_str().to_owned();
+    gzip_path.push(format!(".{GZIP_EXTENSION}"));
+    PathBuf::from(gzip_path)
+}
+
+/// Compresses the file at `path` with gzip, replacing it with the compressed file.
+fn compress_file(path: &Path) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(gzip_path(path))?, Compression::default());
+    io::copy(&mut file, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use chrono::TimeZone;
+    use flate2::read::GzDecoder;
+    use rstest::rstest;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn file_writer(directory: &Path, file_config: FileWriterConfig) -> FileWriter {
+        let file_config = FileWriterConfig {
+            directory: Some(directory.to_str().unwrap().to_string()),
+            file_name: Some("test".to_string()),
+            ..file_config
+        };
+        FileWriter::new(
+            "TRADER-001".to_string(),
+            "instance".to_string(),
+            file_config,
+            LevelFilter::Debug,
+        )
+        .unwrap()
+    }
+
+    fn file_names(directory: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(directory)
+            .unwrap()
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[rstest]
+    #[case(RotationInterval::Daily, Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())]
+    #[case(RotationInterval::Hourly, Utc.with_ymd_and_hms(2024, 1, 1, 14, 0, 0).unwrap())]
+    fn test_rotation_interval_next_boundary(
+        #[case] interval: RotationInterval,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 13, 30, 15).unwrap();
+        assert_eq!(interval.next_boundary(now), expected);
+    }
+
+    #[rstest]
+    fn test_should_rotate_file_at_next_rotation() {
+        let temp_dir = tempdir().unwrap();
+        let writer = file_writer(
+            temp_dir.path(),
+            FileWriterConfig {
+                rotation_interval: Some("hourly".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert!(!writer
+            .should_rotate_file(1, writer.next_rotation - TimeDelta::try_seconds(1).unwrap()));
+        assert!(writer.should_rotate_file(1, writer.next_rotation));
+    }
+
+    #[rstest]
+    fn test_should_rotate_file_at_max_file_size() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = file_writer(
+            temp_dir.path(),
+            FileWriterConfig {
+                max_file_size: Some(10),
+                ..Default::default()
+            },
+        );
+        let now = Utc::now();
+
+        // An empty file is written to even if the line exceeds the maximum size
+        assert!(!writer.should_rotate_file(20, now));
+        writer.write("12345\n");
+        assert!(!writer.should_rotate_file(4, now));
+        assert!(writer.should_rotate_file(5, now));
+    }
+
+    #[rstest]
+    fn test_rotation_at_max_file_size_keeps_max_backups() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = file_writer(
+            temp_dir.path(),
+            FileWriterConfig {
+                max_file_size: Some(10),
+                max_backup_count: Some(2),
+                ..Default::default()
+            },
+        );
+
+        for i in 0..5 {
+            writer.write(&format!("line-{i}\n"));
+            writer.flush();
+        }
+        writer.join_backup_task();
+
+        let names = file_names(temp_dir.path());
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"test.log".to_string()));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("test.log")).unwrap(),
+            "line-4\n"
+        );
+    }
+
+    #[rstest]
+    fn test_rotation_compresses_backups() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = file_writer(
+            temp_dir.path(),
+            FileWriterConfig {
+                max_file_size: Some(10),
+                compress_backups: true,
+                ..Default::default()
+            },
+        );
+
+        writer.write("line-0\n");
+        writer.write("line-1\n");
+        writer.flush();
+        writer.join_backup_task();
+
+        let backup_name = file_names(temp_dir.path())
+            .into_iter()
+            .find(|name| name.ends_with(".log.gz"))
+            .expect("Compressed backup should exist");
+        let mut decoder = GzDecoder::new(File::open(temp_dir.path().join(backup_name)).unwrap());
+        let mut contents = String::new();
+        decoder.read_to_string(&mut contents).unwrap();
+
+        assert_eq!(contents, "line-0\n");
+        assert_eq!(file_names(temp_dir.path()).len(), 2);
+    }
+
+    #[rstest]
+    #[case(Some("test"), "test_2024-01-01T13-30-15-000000001.log", true)]
+    #[case(Some("test"), "test_2024-01-01T13-30-15-000000001_2.log.gz", true)]
+    #[case(Some("test"), "test.log", false)]
+    #[case(Some("test"), "test_other.log", false)]
+    #[case(Some("test"), "test_2024-01-01T13-30-15-000000001.json", false)]
+    #[case(Some("test"), "testing_2024-01-01T13-30-15-000000001.log", false)]
+    #[case(None, "TRADER-001_2024-01-01_instance.log", true)]
+    #[case(None, "TRADER-001_2024-01-01_instance.log.gz", true)]
+    #[case(
+        None,
+        "TRADER-001_2024-01-01_instance_2024-01-01T13-30-15-000000001.log",
+        true
+    )]
+    #[case(None, "TRADER-001_2024-01-01_other.log", false)]
+    #[case(None, "TRADER-001_notes.log", false)]
+    #[case(None, "TRADER-0012_2024-01-01_instance.log", false)]
+    fn test_backup_files_is_backup(
+        #[case] file_name: Option<&str>,
+        #[case] name: &str,
+        #[case] expected: bool,
+    ) {
+        let backups = BackupFiles {
+            directory: PathBuf::from("."),
+            file_name: file_name.map(ToString::to_string),
+            trader_id: "TRADER-001".to_string(),
+            instance_id: "instance".to_string(),
+            suffix: "log",
+        };
+
+        assert_eq!(backups.is_backup(name), expected);
+    }
+
+    #[rstest]
+    fn test_rotation_keeps_unrelated_files() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("test_notes.log"), "notes\n").unwrap();
+        let mut writer = file_writer(
+            temp_dir.path(),
+            FileWriterConfig {
+                max_file_size: Some(10),
+                max_backup_count: Some(1),
+                ..Default::default()
+            },
+        );
+
+        for i in 0..3 {
+            writer.write(&format!("line-{i}\n"));
+        }
+        writer.join_backup_task();
+
+        let names = file_names(temp_dir.path());
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"test_notes.log".to_string()));
+    }
+}
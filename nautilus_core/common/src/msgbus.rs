@@ -14,23 +14,43 @@
 // -------------------------------------------------------------------------------------------------
 
 use std::{
-    collections::HashMap,
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
     fmt,
     hash::{Hash, Hasher},
-    sync::mpsc::{channel, Receiver, SendError, Sender},
+    rc::Rc,
+    sync::{
+        mpsc::{channel, Receiver, SendError, Sender},
+        Arc,
+    },
     thread,
+    time::Duration,
 };
 
-use indexmap::IndexMap;
-use nautilus_core::uuid::UUID4;
+use indexmap::{IndexMap, IndexSet};
+use nautilus_core::{serialization::Serializable, uuid::UUID4};
 use nautilus_model::identifiers::trader_id::TraderId;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    Mutex,
+};
 use ustr::Ustr;
 
-use crate::handlers::MessageHandler;
 #[cfg(feature = "redis")]
-use crate::redis::handle_messages_with_redis;
+use crate::redis::{consume_streams_with_redis, handle_messages_with_redis};
+use crate::{
+    filelog::handle_messages_with_file,
+    handlers::{LocalMessageHandler, MessageHandler},
+};
+
+/// The delimiter between the components of stream names.
+pub(crate) const STREAM_DELIMITER: char = ':';
+
+/// The maximum number of topics with cached matching subscriptions.
+const TOPIC_CACHE_CAPACITY: usize = 10_000;
 
 // Represents a subscription to a particular topic.
 //
@@ -91,8 +111,159 @@ impl Hash for Subscription {
     }
 }
 
+// Represents a subscription of a local handler to a particular topic.
+//
+// Local handlers are held apart from the other subscriptions, so that only they are
+// tied to the thread which owns the message bus.
+#[derive(Clone, Debug)]
+pub struct LocalSubscription {
+    pub handler: LocalMessageHandler,
+    pub topic: Ustr,
+    pub sequence: usize,
+    pub priority: u8,
+}
+
+/// A node in a [`PatternTrie`], with an edge for each next character of the patterns
+/// passing through it (including the `*` and `?` wildcard characters).
+#[derive(Clone, Debug, Default)]
+struct PatternNode {
+    children: HashMap<char, usize>,
+    subs: Vec<Subscription>,
+}
+
+/// Provides an index of subscription patterns as a trie of pattern characters.
+///
+/// Patterns sharing a prefix share nodes, so a topic is matched against all patterns
+/// in a single walk of the trie, following wildcard edges where they exist. Matching
+/// needs no fixed size table, so topics and patterns may be of any length.
+#[derive(Clone, Debug)]
+struct PatternTrie {
+    nodes: Vec<PatternNode>,
+}
+
+impl Default for PatternTrie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![PatternNode::default()], // Root
+        }
+    }
+}
+
+impl PatternTrie {
+    /// Inserts the `sub` at the node for its topic pattern.
+    fn insert(&mut self, sub: Subscription) {
+        let mut node = 0;
+        for c in sub.topic.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(child) => *child,
+                None => {
+                    self.nodes.push(PatternNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].subs.push(sub);
+    }
+
+    /// Removes the `sub` from the node for its topic pattern (if found).
+    fn remove(&mut self, sub: &Subscription) {
+        let mut node = 0;
+        for c in sub.topic.chars() {
+            match self.nodes[node].children.get(&c) {
+                Some(child) => node = *child,
+                None => return,
+            }
+        }
+        self.nodes[node].subs.retain(|s| s != sub);
+    }
+
+    /// Returns all subscriptions with a pattern matching the `topic`, in priority order.
+    fn matches(&self, topic: &str) -> Vec<Subscription> {
+        let topic: Vec<char> = topic.chars().collect();
+        let mut matches = Vec::new();
+
+        // Each (node, topic position) state only needs to be visited once
+        let mut visited: HashSet<(usize, usize)> = HashSet::new();
+        let mut stack = vec![(0, 0)];
+        while let Some((node, pos)) = stack.pop() {
+            if !visited.insert((node, pos)) {
+                continue;
+            }
+            let children = &self.nodes[node].children;
+            if pos == topic.len() {
+                matches.extend(self.nodes[node].subs.iter().cloned());
+            } else {
+                let c = topic[pos];
+                if c != '*' && c != '?' {
+                    if let Some(child) = children.get(&c) {
+                        stack.push((*child, pos + 1));
+                    }
+                }
+                if let Some(child) = children.get(&'?') {
+                    stack.push((*child, pos + 1));
+                }
+            }
+            if let Some(child) = children.get(&'*') {
+                // Asterisk matches zero or more characters
+                stack.extend((pos..=topic.len()).map(|p| (*child, p)));
+            }
+        }
+
+        matches.sort();
+        matches
+    }
+}
+
+/// Caches the subscriptions matching each published topic, up to a capacity of topics.
+///
+/// Once full, the topic cached first is evicted to make room for each new topic. Topics
+/// without any matching subscriptions are never cached, as these are cheap to match and
+/// would otherwise fill the cache.
+#[derive(Clone, Debug)]
+struct TopicCache {
+    capacity: usize,
+    subs: HashMap<Ustr, Arc<[Subscription]>>,
+    order: VecDeque<Ustr>,
+}
+
+impl TopicCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            subs: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, topic: &Ustr) -> Option<Arc<[Subscription]>> {
+        self.subs.get(topic).cloned()
+    }
+
+    fn insert(&mut self, topic: Ustr, subs: Arc<[Subscription]>) {
+        if subs.is_empty() || self.capacity == 0 {
+            return;
+        }
+        if self.subs.insert(topic, subs).is_some() {
+            return; // Already in eviction order
+        }
+        self.order.push_back(topic);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.subs.remove(&evicted);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.subs.clear();
+        self.order.clear();
+    }
+}
+
 /// Represents a bus message including a topic and payload.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BusMessage {
     /// The topic to publish on.
     pub topic: String,
@@ -100,6 +271,28 @@ pub struct BusMessage {
     pub payload: Vec<u8>,
 }
 
+/// The encoding for message payloads published to and consumed from external streams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PayloadEncoding {
+    #[default]
+    MsgPack,
+    Json,
+}
+
+/// Decodes an external message payload into a typed message.
+type ExternalDecoder = fn(Vec<u8>, PayloadEncoding) -> anyhow::Result<Box<dyn Any>>;
+
+fn decode_payload<T: Serializable + Any>(
+    payload: Vec<u8>,
+    encoding: PayloadEncoding,
+) -> anyhow::Result<Box<dyn Any>> {
+    let msg = match encoding {
+        PayloadEncoding::MsgPack => Box::new(T::from_msgpack_bytes(payload)?) as Box<dyn Any>,
+        PayloadEncoding::Json => Box::new(T::from_json_bytes(payload)?) as Box<dyn Any>,
+    };
+    Ok(msg)
+}
+
 impl fmt::Display for BusMessage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -149,25 +342,47 @@ pub struct MessageBus {
     pub pub_count: u64,
     /// If the message bus is backed by a database.
     pub has_backing: bool,
+    /// The encoding for message payloads consumed from external streams.
+    pub encoding: PayloadEncoding,
     tx: Option<Sender<BusMessage>>,
-    /// mapping from topic to the corresponding handler
-    /// a topic can be a string with wildcards
+    /// Receives the messages consumed from external streams.
+    external_rx: Option<Arc<Mutex<UnboundedReceiver<BusMessage>>>>,
+    /// Maps a topic pattern to the decoder for messages consumed from external streams.
+    external_decoders: IndexMap<Ustr, ExternalDecoder>,
+    /// The active subscriptions, where a topic can be a string with wildcards
     /// * '?' - any character
     /// * '*' - any number of any characters
-    subscriptions: IndexMap<Subscription, Vec<Ustr>>,
-    /// maps a pattern to all the handlers registered for it
-    /// this is updated whenever a new subscription is created.
-    patterns: IndexMap<Ustr, Vec<Subscription>>,
+    subscriptions: IndexSet<Subscription>,
+    /// Indexes the subscription topic patterns for matching published topics.
+    patterns: PatternTrie,
+    /// Caches the subscriptions matching each published topic, in priority order.
+    /// This is invalidated whenever a subscription is added or removed.
+    topic_cache: RefCell<TopicCache>,
     /// handles a message or a request destined for a specific endpoint.
     endpoints: IndexMap<Ustr, MessageHandler>,
     /// Relates a request with a response
     /// a request maps it's id to a handler so that a response
     /// with the same id can later be handled.
     correlation_index: IndexMap<UUID4, MessageHandler>,
+    /// The active subscriptions of handlers local to the thread which owns the bus.
+    local_subscriptions: Vec<LocalSubscription>,
 }
 
 impl MessageBus {
     /// Initializes a new instance of the [`MessageBus`].
+    ///
+    /// If the bus is backed by a database and the `external_streams` config contains stream
+    /// key patterns (e.g. `trader-*:events.order.*`), then messages are also consumed from the
+    /// matching streams of other message buses. These are published to local handlers as they
+    /// arrive by [`MessageBus::run_external`] on the event loop (or by polling
+    /// [`MessageBus::process_external`]), for the types registered with
+    /// [`MessageBus::register_external_type`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - The `encoding` config is not supported.
+    /// - External streams are configured for a backing which cannot consume them.
     pub fn new(
         trader_id: TraderId,
         instance_id: UUID4,
@@ -178,11 +393,36 @@ impl MessageBus {
         let has_backing = config
             .get("database")
             .map_or(false, |v| v != &serde_json::Value::Null);
+        let encoding = match config.get("encoding").and_then(Value::as_str) {
+            None | Some("msgpack") => PayloadEncoding::MsgPack,
+            Some("json") => PayloadEncoding::Json,
+            Some(other) => anyhow::bail!("Unsupported message bus encoding '{other}'"),
+        };
+        let external_rx = if has_backing && !get_external_streams(&config).is_empty() {
+            check_external_backing(&config)?;
+            let (tx, rx) = unbounded_channel::<BusMessage>();
+            let config = config.clone();
+            let _join_handler = thread::Builder::new()
+                .name("msgbus-external".to_string())
+                .spawn(move || {
+                    if let Err(e) = Self::consume_external(tx, trader_id, instance_id, config) {
+                        eprintln!("Error consuming external streams: {e}");
+                    }
+                })
+                .expect("Error spawning `msgbus-external` thread");
+            Some(Arc::new(Mutex::new(rx)))
+        } else {
+            None
+        };
         let tx = if has_backing {
             let (tx, rx) = channel::<BusMessage>();
             let _join_handler = thread::Builder::new()
                 .name("msgbus".to_string())
-                .spawn(move || Self::handle_messages(rx, trader_id, instance_id, config))
+                .spawn(move || {
+                    if let Err(e) = Self::handle_messages(rx, trader_id, instance_id, config) {
+                        eprintln!("Error handling external messages: {e}");
+                    }
+                })
                 .expect("Error spawning `msgbus` thread");
             Some(tx)
         } else {
@@ -191,6 +431,9 @@ impl MessageBus {
 
         Ok(Self {
             tx,
+            external_rx,
+            external_decoders: IndexMap::new(),
+            encoding,
             trader_id,
             instance_id,
             name: name.unwrap_or_else(|| stringify!(MessageBus).to_owned()),
@@ -198,10 +441,12 @@ impl MessageBus {
             req_count: 0,
             res_count: 0,
             pub_count: 0,
-            subscriptions: IndexMap::new(),
-            patterns: IndexMap::new(),
+            subscriptions: IndexSet::new(),
+            patterns: PatternTrie::default(),
+            topic_cache: RefCell::new(TopicCache::new(TOPIC_CACHE_CAPACITY)),
             endpoints: IndexMap::new(),
             correlation_index: IndexMap::new(),
+            local_subscriptions: Vec::new(),
             has_backing,
         })
     }
@@ -216,7 +461,7 @@ impl MessageBus {
     #[must_use]
     pub fn topics(&self) -> Vec<&str> {
         self.subscriptions
-            .keys()
+            .iter()
             .map(|s| s.topic.as_str())
             .collect()
     }
@@ -230,22 +475,25 @@ impl MessageBus {
     /// Returns whether there are subscribers for the given `pattern`.
     #[must_use]
     pub fn has_subscribers(&self, pattern: &str) -> bool {
-        self.matching_handlers(&Ustr::from(pattern))
-            .next()
-            .is_some()
+        let pattern = Ustr::from(pattern);
+        self.matching_handlers(&pattern).next().is_some()
+            || self
+                .local_subscriptions
+                .iter()
+                .any(|sub| is_matching(&sub.topic, &pattern))
     }
 
     /// Returns whether there are subscribers for the given `pattern`.
     #[must_use]
     pub fn subscriptions(&self) -> Vec<&Subscription> {
-        self.subscriptions.keys().collect()
+        self.subscriptions.iter().collect()
     }
 
     /// Returns whether there are subscribers for the given `pattern`.
     #[must_use]
     pub fn subscription_handler_ids(&self) -> Vec<&str> {
         self.subscriptions
-            .keys()
+            .iter()
             .map(|s| s.handler.handler_id.as_str())
             .collect()
     }
@@ -260,7 +508,7 @@ impl MessageBus {
     #[must_use]
     pub fn is_subscribed(&self, topic: &str, handler: MessageHandler) -> bool {
         let sub = Subscription::new(Ustr::from(topic), handler, self.subscriptions.len(), None);
-        self.subscriptions.contains_key(&sub)
+        self.subscriptions.contains(&sub)
     }
 
     /// Returns whether there is a pending request for the given `request_id`.
@@ -284,34 +532,63 @@ impl MessageBus {
     /// Subscribes the given `handler` to the `topic`.
     pub fn subscribe(&mut self, topic: &str, handler: MessageHandler, priority: Option<u8>) {
         let topic = Ustr::from(topic);
-        let sub = Subscription::new(topic, handler, self.subscriptions.len(), priority);
+        let sequence = self.subscriptions.len() + self.local_subscriptions.len();
+        let sub = Subscription::new(topic, handler, sequence, priority);
 
-        if self.subscriptions.contains_key(&sub) {
+        if self.subscriptions.contains(&sub) {
             // TODO: Implement proper logging
             println!("{sub:?} already exists.");
             return;
         }
 
-        // Find existing patterns which match this topic
-        let mut matches = Vec::new();
-        for (pattern, subs) in &mut self.patterns {
-            if is_matching(&topic, pattern) {
-                subs.push(sub.clone());
-                subs.sort();
-                // subs.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.cmp(b)));
-                matches.push(*pattern);
-            }
-        }
-
-        matches.sort();
-
-        self.subscriptions.insert(sub, matches);
+        self.patterns.insert(sub.clone());
+        self.subscriptions.insert(sub);
+        self.topic_cache.borrow_mut().clear();
     }
 
     /// Unsubscribes the given `handler` from the `topic`.
     pub fn unsubscribe(&mut self, topic: &str, handler: MessageHandler) {
         let sub = Subscription::new(Ustr::from(topic), handler, self.subscriptions.len(), None);
-        self.subscriptions.shift_remove(&sub);
+        if self.subscriptions.shift_remove(&sub) {
+            self.patterns.remove(&sub);
+            self.topic_cache.borrow_mut().clear();
+        }
+    }
+
+    /// Subscribes the given local `handler` to the `topic`.
+    ///
+    /// Local handlers receive published messages in priority order along with the other
+    /// subscribed handlers.
+    pub fn subscribe_local(
+        &mut self,
+        topic: &str,
+        handler: LocalMessageHandler,
+        priority: Option<u8>,
+    ) {
+        let topic = Ustr::from(topic);
+        if self
+            .local_subscriptions
+            .iter()
+            .any(|sub| sub.topic == topic && sub.handler == handler)
+        {
+            // TODO: Implement proper logging
+            println!("{handler:?} already subscribed to {topic}.");
+            return;
+        }
+
+        self.local_subscriptions.push(LocalSubscription {
+            handler,
+            topic,
+            sequence: self.subscriptions.len() + self.local_subscriptions.len(),
+            priority: priority.unwrap_or(0),
+        });
+    }
+
+    /// Unsubscribes the given local `handler` from the `topic`.
+    pub fn unsubscribe_local(&mut self, topic: &str, handler: &LocalMessageHandler) {
+        let topic = Ustr::from(topic);
+        self.local_subscriptions
+            .retain(|sub| sub.topic != topic || sub.handler != *handler);
     }
 
     /// Returns the handler for the given `endpoint`.
@@ -350,41 +627,95 @@ impl MessageBus {
         self.correlation_index.shift_remove(correlation_id)
     }
 
-    #[must_use]
-    pub fn matching_subscriptions<'a>(&'a self, pattern: &'a Ustr) -> Vec<&'a Subscription> {
-        let mut matching_subs: Vec<&'a Subscription> = Vec::new();
+    /// Sends the typed `message` to the handler registered for the `endpoint` (if found).
+    pub fn send(&self, endpoint: &str, message: &dyn Any) {
+        if let Some(handler) = self.get_endpoint(&Ustr::from(endpoint)) {
+            handler.handle(message);
+        }
+    }
 
-        // Collect matching subscriptions from direct subscriptions
-        matching_subs.extend(self.subscriptions.iter().filter_map(|(sub, _)| {
-            if is_matching(&sub.topic, pattern) {
-                Some(sub)
-            } else {
-                None
+    /// Publishes the typed `message` to all handlers subscribed to a pattern matching the `topic`,
+    /// in priority order.
+    pub fn publish(&self, topic: &str, message: &dyn Any) {
+        let topic = Ustr::from(topic);
+        // Handlers may publish further messages, so the cache is not borrowed while handling
+        let subs = self.topic_subscriptions(&topic);
+        let local_subs = self.local_topic_subscriptions(&topic);
+
+        // Merges both subscriptions in priority order, then in order of subscribing
+        let mut subs = subs.iter().peekable();
+        let mut local_subs = local_subs.iter().peekable();
+        loop {
+            match (subs.peek(), local_subs.peek()) {
+                (Some(sub), Some(local_sub))
+                    if (local_sub.priority, sub.sequence) > (sub.priority, local_sub.sequence) =>
+                {
+                    local_sub.handler.handle(message);
+                    local_subs.next();
+                }
+                (Some(sub), _) => {
+                    sub.handler.handle(message);
+                    subs.next();
+                }
+                (None, Some(local_sub)) => {
+                    local_sub.handler.handle(message);
+                    local_subs.next();
+                }
+                (None, None) => break,
             }
-        }));
-
-        // Collect matching subscriptions from pattern-based subscriptions
-        // TODO: Improve efficiency of this
-        for subs in self.patterns.values() {
-            let filtered_subs: Vec<&Subscription> = subs
-                .iter()
-                // .filter(|sub| is_matching(&sub.topic, pattern))
-                // .filter(|sub| !matching_subs.contains(sub) && is_matching(&sub.topic, pattern))
-                .collect();
-
-            matching_subs.extend(filtered_subs);
         }
+    }
 
-        // Sort into priority order
+    /// Returns the local subscriptions with a pattern matching the published `topic`, in
+    /// priority order.
+    #[must_use]
+    pub fn local_topic_subscriptions(&self, topic: &Ustr) -> Vec<LocalSubscription> {
+        let mut subs: Vec<LocalSubscription> = self
+            .local_subscriptions
+            .iter()
+            .filter(|sub| is_matching(topic, &sub.topic))
+            .cloned()
+            .collect();
+        subs.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.sequence.cmp(&b.sequence))
+        });
+        subs
+    }
+
+    /// Returns the subscriptions with a topic matching the `pattern`, in priority order.
+    #[must_use]
+    pub fn matching_subscriptions<'a>(&'a self, pattern: &'a Ustr) -> Vec<&'a Subscription> {
+        let mut matching_subs: Vec<&'a Subscription> = self
+            .subscriptions
+            .iter()
+            .filter(|sub| is_matching(&sub.topic, pattern))
+            .collect();
         matching_subs.sort();
         matching_subs
     }
 
+    /// Returns the subscriptions with a pattern matching the published `topic`, in
+    /// priority order.
+    ///
+    /// The result is cached per topic until a subscription is added or removed.
+    #[must_use]
+    pub fn topic_subscriptions(&self, topic: &Ustr) -> Arc<[Subscription]> {
+        if let Some(subs) = self.topic_cache.borrow().get(topic) {
+            return subs;
+        }
+
+        let subs: Arc<[Subscription]> = self.patterns.matches(topic).into();
+        self.topic_cache.borrow_mut().insert(*topic, subs.clone());
+        subs
+    }
+
     fn matching_handlers<'a>(
         &'a self,
         pattern: &'a Ustr,
     ) -> impl Iterator<Item = &'a MessageHandler> {
-        self.subscriptions.iter().filter_map(move |(sub, _)| {
+        self.subscriptions.iter().filter_map(move |sub| {
             if is_matching(&sub.topic, pattern) {
                 Some(&sub.handler)
             } else {
@@ -393,6 +724,88 @@ impl MessageBus {
         })
     }
 
+    /// Registers the serializable type `T` for decoding messages consumed from external
+    /// streams on topics matching the `pattern`.
+    pub fn register_external_type<T: Serializable + Any>(&mut self, pattern: &str) {
+        self.external_decoders
+            .insert(Ustr::from(pattern), decode_payload::<T>);
+    }
+
+    /// Publishes the messages received from external streams to the local handlers
+    /// subscribed to their topics as they arrive, until external consumption stops.
+    ///
+    /// This drives the bus from the event loop, and should be spawned as a local task
+    /// (e.g. with `tokio::task::spawn_local`), as the bus is only borrowed to publish
+    /// each message.
+    pub async fn run_external(msgbus: Rc<RefCell<Self>>) {
+        let Some(rx) = msgbus.borrow().external_rx.clone() else {
+            return; // Not consuming external streams
+        };
+
+        loop {
+            let Some(msg) = rx.lock().await.recv().await else {
+                break; // Channel hung up
+            };
+            if let Err(e) = msgbus.borrow().publish_from_external(msg) {
+                eprintln!("Error publishing external message: {e}");
+            }
+        }
+    }
+
+    /// Publishes all messages already received from external streams to the local handlers
+    /// subscribed to their topics, returning the count of messages published.
+    ///
+    /// Returns zero if the messages are currently being published by
+    /// [`MessageBus::run_external`].
+    pub fn process_external(&self) -> usize {
+        let Some(rx) = &self.external_rx else {
+            return 0;
+        };
+        let Ok(mut rx) = rx.try_lock() else {
+            return 0; // Driven from the event loop
+        };
+
+        // Drain before publishing, as handlers may themselves process external messages
+        let mut msgs = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            msgs.push(msg);
+        }
+        drop(rx);
+
+        let mut count = 0;
+        for msg in msgs {
+            match self.publish_from_external(msg) {
+                Ok(()) => count += 1,
+                Err(e) => eprintln!("Error publishing external message: {e}"),
+            }
+        }
+        count
+    }
+
+    /// Decodes the external `msg` with the type registered for its topic, and publishes
+    /// it to the local handlers subscribed to the topic.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - No type is registered for a pattern matching the topic.
+    /// - The payload cannot be decoded as the registered type.
+    pub fn publish_from_external(&self, msg: BusMessage) -> anyhow::Result<()> {
+        let topic = Ustr::from(&msg.topic);
+        let Some(decode) = self
+            .external_decoders
+            .iter()
+            .find(|(pattern, _)| is_matching(&topic, pattern))
+            .map(|(_, decode)| decode)
+        else {
+            anyhow::bail!("No external type registered for topic '{topic}'");
+        };
+
+        let message = decode(msg.payload, self.encoding)?;
+        self.publish(&topic, message.as_ref());
+        Ok(())
+    }
+
     pub fn publish_external(&self, topic: String, payload: Vec<u8>) {
         if let Some(tx) = &self.tx {
             let msg = BusMessage { topic, payload };
@@ -410,20 +823,127 @@ impl MessageBus {
         instance_id: UUID4,
         config: HashMap<String, serde_json::Value>,
     ) -> anyhow::Result<()> {
-        let database_config = config
+        let backing_type = config
             .get("database")
-            .expect("No `MessageBusConfig` `database` config specified");
-        let backing_type = database_config
-            .get("type")
-            .expect("No `MessageBusConfig` database config `type` specified")
-            .as_str()
-            .expect("`MessageBusConfig` database `type` must be a valid string");
+            .and_then(|database_config| database_config.get("type"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                anyhow::anyhow!("No `MessageBusConfig` database config `type` specified")
+            })?;
 
         match backing_type {
             "redis" => handle_messages_with_redis_if_enabled(rx, trader_id, instance_id, config),
-            other => panic!("Unsupported message bus backing database type '{other}'"),
+            "file" => handle_messages_with_file(rx, trader_id, instance_id, config),
+            other => anyhow::bail!("Unsupported message bus backing database type '{other}'"),
         }
     }
+
+    fn consume_external(
+        tx: UnboundedSender<BusMessage>,
+        trader_id: TraderId,
+        instance_id: UUID4,
+        config: HashMap<String, serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        check_external_backing(&config)?;
+        consume_streams_with_redis_if_enabled(tx, trader_id, instance_id, config)
+    }
+}
+
+/// Checks the backing database of the `config` can consume external streams.
+fn check_external_backing(config: &HashMap<String, Value>) -> anyhow::Result<()> {
+    let backing_type = config
+        .get("database")
+        .and_then(|database_config| database_config.get("type"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("No `MessageBusConfig` database config `type` specified"))?;
+
+    match backing_type {
+        "redis" if cfg!(feature = "redis") => Ok(()),
+        "redis" => anyhow::bail!("Cannot consume external streams: `redis` feature is not enabled"),
+        other => anyhow::bail!(
+            "Consuming external streams is not supported for message bus backing database type '{other}'"
+        ),
+    }
+}
+
+/// Returns the stream key patterns configured for consuming external messages.
+#[must_use]
+pub fn get_external_streams(config: &HashMap<String, Value>) -> Vec<String> {
+    config
+        .get("external_streams")
+        .and_then(Value::as_array)
+        .map(|patterns| {
+            patterns
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the interval between draining buffered messages to the backing.
+#[must_use]
+pub fn get_buffer_interval(config: &HashMap<String, Value>) -> Duration {
+    let buffer_interval_ms = config
+        .get("buffer_interval_ms")
+        .map(|v| v.as_u64().unwrap_or(0));
+    Duration::from_millis(buffer_interval_ms.unwrap_or(0))
+}
+
+/// Returns the stream name prefixing the stream keys for messages published by the bus.
+#[must_use]
+pub fn get_stream_name(
+    trader_id: TraderId,
+    instance_id: UUID4,
+    config: &HashMap<String, Value>,
+) -> String {
+    let mut stream_name = String::new();
+
+    if let Some(json!(true)) = config.get("use_trader_prefix") {
+        stream_name.push_str("trader-");
+    }
+
+    if let Some(json!(true)) = config.get("use_trader_id") {
+        stream_name.push_str(trader_id.value.as_str());
+        stream_name.push(STREAM_DELIMITER);
+    }
+
+    if let Some(json!(true)) = config.get("use_instance_id") {
+        stream_name.push_str(&format!("{instance_id}"));
+        stream_name.push(STREAM_DELIMITER);
+    }
+
+    let stream_prefix = config
+        .get("streams_prefix")
+        .expect("Invalid configuration: no `streams_prefix` key found")
+        .as_str()
+        .expect("Invalid configuration: `streams_prefix` is not a string");
+    stream_name.push_str(stream_prefix);
+    stream_name.push(STREAM_DELIMITER);
+    stream_name
+}
+
+/// Consumes external streams using Redis if the `redis` feature is enabled.
+#[cfg(feature = "redis")]
+fn consume_streams_with_redis_if_enabled(
+    tx: UnboundedSender<BusMessage>,
+    trader_id: TraderId,
+    instance_id: UUID4,
+    config: HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    consume_streams_with_redis(tx, trader_id, instance_id, config)
+}
+
+/// Consumes external streams using a default method if the "redis" feature is not enabled.
+#[cfg(not(feature = "redis"))]
+fn consume_streams_with_redis_if_enabled(
+    _tx: UnboundedSender<BusMessage>,
+    _trader_id: TraderId,
+    _instance_id: UUID4,
+    _config: HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    anyhow::bail!("`redis` feature is not enabled");
 }
 
 /// Handles messages using Redis if the `redis` feature is enabled.
@@ -444,8 +964,8 @@ fn handle_messages_with_redis_if_enabled(
     _trader_id: TraderId,
     _instance_id: UUID4,
     _config: HashMap<String, Value>,
-) {
-    panic!("`redis` feature is not enabled");
+) -> anyhow::Result<()> {
+    anyhow::bail!("`redis` feature is not enabled");
 }
 
 /// Match a topic and a string pattern
@@ -455,29 +975,35 @@ fn handle_messages_with_redis_if_enabled(
 /// 'a-z' - match the specific character
 #[must_use]
 pub fn is_matching(topic: &Ustr, pattern: &Ustr) -> bool {
-    let mut table = [[false; 256]; 256];
-    table[0][0] = true;
-
-    let m = pattern.len();
-    let n = topic.len();
-
-    pattern.chars().enumerate().for_each(|(j, c)| {
-        if c == '*' {
-            table[0][j + 1] = table[0][j];
-        }
-    });
-
-    topic.chars().enumerate().for_each(|(i, tc)| {
-        pattern.chars().enumerate().for_each(|(j, pc)| {
-            if pc == '*' {
-                table[i + 1][j + 1] = table[i][j + 1] || table[i + 1][j];
-            } else if pc == '?' || tc == pc {
-                table[i + 1][j + 1] = table[i][j];
+    let topic: Vec<char> = topic.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // Greedy matching, backtracking to the last asterisk on a mismatch
+    let (mut i, mut j) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while i < topic.len() {
+        match pattern.get(j) {
+            Some('*') => {
+                last_star = Some((j, i));
+                j += 1;
             }
-        });
-    });
+            Some(&pc) if pc == '?' || pc == topic[i] => {
+                i += 1;
+                j += 1;
+            }
+            _ => match last_star {
+                Some((star_j, star_i)) => {
+                    // Let the asterisk match one more character
+                    last_star = Some((star_j, star_i + 1));
+                    i = star_i + 1;
+                    j = star_j + 1;
+                }
+                None => return false,
+            },
+        }
+    }
 
-    table[n][m]
+    pattern[j..].iter().all(|c| *c == '*')
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -486,16 +1012,23 @@ pub fn is_matching(topic: &Ustr, pattern: &Ustr) -> bool {
 #[cfg(not(feature = "python"))]
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
     use nautilus_core::{message::Message, uuid::UUID4};
+    use nautilus_model::{
+        data::quote::QuoteTick,
+        identifiers::instrument_id::InstrumentId,
+        types::{price::Price, quantity::Quantity},
+    };
     use rstest::*;
 
     use super::*;
-    use crate::handlers::{MessageHandler, SafeMessageCallback};
+    use crate::handlers::{
+        LocalMessageHandler, MessageHandler, SafeAnyMessageCallback, SafeMessageCallback,
+    };
 
     fn stub_msgbus() -> MessageBus {
-        MessageBus::new(TraderId::from("trader-001"), UUID4::new(), None, None)
+        MessageBus::new(TraderId::from("trader-001"), UUID4::new(), None, None).unwrap()
     }
 
     fn stub_rust_callback() -> SafeMessageCallback {
@@ -509,7 +1042,7 @@ mod tests {
     #[rstest]
     fn test_new() {
         let trader_id = TraderId::from("trader-001");
-        let msgbus = MessageBus::new(trader_id, UUID4::new(), None, None);
+        let msgbus = MessageBus::new(trader_id, UUID4::new(), None, None).unwrap();
 
         assert_eq!(msgbus.trader_id, trader_id);
         assert_eq!(msgbus.name, stringify!(MessageBus));
@@ -696,10 +1229,355 @@ mod tests {
     #[case("data.quotes.BINANCE", "data.*.BINANCE", true)]
     #[case("data.trades.BINANCE.ETHUSDT", "data.*.BINANCE.*", true)]
     #[case("data.trades.BINANCE.ETHUSDT", "data.*.BINANCE.ETH*", true)]
+    #[case("data.trades.BINANCE.ETHUSDT", "data.*.BINANCE.BTC*", false)]
+    #[case("data.quotes.BINANCE", "data.?uotes.*", true)]
+    #[case("data.quotes.BINANCE", "data.?.*", false)]
+    #[case("data.quotes", "data.quotes.*", false)]
+    #[case("", "*", true)]
+    #[case("", "?", false)]
+    #[case("abcbc", "a*bc", true)]
     fn test_is_matching(#[case] topic: &str, #[case] pattern: &str, #[case] expected: bool) {
         assert_eq!(
             is_matching(&Ustr::from(topic), &Ustr::from(pattern)),
             expected
         );
     }
+
+    #[rstest]
+    #[case("data.trades.BINANCE.ETHUSDT", "data.*.BINANCE.ETH*", true)]
+    #[case("data.trades.BINANCE.ETHUSDT", "data.*.BINANCE.BTC*", false)]
+    #[case("data.quotes.BINANCE", "data.?uotes.*", true)]
+    #[case("data.quotes.BINANCE", "data.?.*", false)]
+    #[case("data.quotes", "data.quotes.*", false)]
+    #[case("abcbc", "a*bc", true)]
+    #[case("abcbc", "a**c", true)]
+    fn test_topic_subscriptions_agrees_with_is_matching(
+        #[case] topic: &str,
+        #[case] pattern: &str,
+        #[case] expected: bool,
+    ) {
+        let mut msgbus = stub_msgbus();
+        let handler = MessageHandler::new(Ustr::from("1"), Some(stub_rust_callback()));
+        msgbus.subscribe(pattern, handler, None);
+
+        let subs = msgbus.topic_subscriptions(&Ustr::from(topic));
+
+        assert_eq!(!subs.is_empty(), expected);
+        assert_eq!(
+            is_matching(&Ustr::from(topic), &Ustr::from(pattern)),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_is_matching_with_topic_longer_than_255_characters() {
+        let topic = format!("data.bars.{}", "X".repeat(300));
+        let pattern = format!("data.*{}", "X".repeat(299));
+
+        assert!(is_matching(&Ustr::from(&topic), &Ustr::from("data.bars.*")));
+        assert!(is_matching(&Ustr::from(&topic), &Ustr::from(&pattern)));
+        assert!(!is_matching(
+            &Ustr::from(&topic),
+            &Ustr::from(&format!("{pattern}X?"))
+        ));
+    }
+
+    #[rstest]
+    fn test_topic_subscriptions_with_patterns_in_priority_order() {
+        let mut msgbus = stub_msgbus();
+        let callback = stub_rust_callback();
+        let handler1 = MessageHandler::new(Ustr::from("1"), Some(callback.clone()));
+        let handler2 = MessageHandler::new(Ustr::from("2"), Some(callback.clone()));
+        let handler3 = MessageHandler::new(Ustr::from("3"), Some(callback.clone()));
+        let handler4 = MessageHandler::new(Ustr::from("4"), Some(callback));
+
+        msgbus.subscribe("data.*", handler1, None);
+        msgbus.subscribe("data.quotes.*", handler2, Some(5));
+        msgbus.subscribe("data.quotes.AUD/USD.SIM", handler3, None);
+        msgbus.subscribe("data.trades.*", handler4, Some(10));
+        let subs = msgbus.topic_subscriptions(&Ustr::from("data.quotes.AUD/USD.SIM"));
+
+        let handler_ids: Vec<&str> = subs.iter().map(|s| s.handler.handler_id.as_str()).collect();
+        assert_eq!(handler_ids, vec!["2", "1", "3"]);
+    }
+
+    fn stub_recording_handlers(
+        received: &Arc<Mutex<Vec<&'static str>>>,
+    ) -> (
+        impl Fn(&'static str) -> MessageHandler,
+        impl Fn(&'static str) -> LocalMessageHandler,
+    ) {
+        let received_any = received.clone();
+        let any_handler = move |handler_id: &'static str| {
+            let received = received_any.clone();
+            let callback = SafeAnyMessageCallback {
+                callback: Arc::new(move |_: &dyn Any| received.lock().unwrap().push(handler_id)),
+            };
+            MessageHandler::with_any_callback(Ustr::from(handler_id), callback)
+        };
+        let received_local = received.clone();
+        let local_handler = move |handler_id: &'static str| {
+            let received = received_local.clone();
+            let callback = Rc::new(move |_: &dyn Any| received.lock().unwrap().push(handler_id));
+            LocalMessageHandler::new(Ustr::from(handler_id), callback)
+        };
+        (any_handler, local_handler)
+    }
+
+    #[rstest]
+    fn test_publish_to_local_and_other_handlers_in_priority_order() {
+        let mut msgbus = stub_msgbus();
+        let received: Arc<Mutex<Vec<&str>>> = Arc::default();
+        let (any_handler, local_handler) = stub_recording_handlers(&received);
+
+        msgbus.subscribe("data.*", any_handler("1"), None);
+        msgbus.subscribe_local("data.quotes.*", local_handler("2"), Some(5));
+        msgbus.subscribe_local("data.quotes.*", local_handler("3"), None);
+        msgbus.subscribe("data.quotes.*", any_handler("4"), Some(10));
+        msgbus.subscribe_local("data.trades.*", local_handler("5"), Some(10));
+        msgbus.publish("data.quotes.AUD/USD.SIM", &stub_quote());
+
+        assert_eq!(*received.lock().unwrap(), vec!["4", "2", "1", "3"]);
+        assert!(msgbus.has_subscribers("data.trades.*"));
+    }
+
+    #[rstest]
+    fn test_unsubscribe_local() {
+        let mut msgbus = stub_msgbus();
+        let received: Arc<Mutex<Vec<&str>>> = Arc::default();
+        let (_, local_handler) = stub_recording_handlers(&received);
+        let handler = local_handler("1");
+
+        msgbus.subscribe_local("data.*", handler.clone(), None);
+        msgbus.subscribe_local("data.*", handler.clone(), None);
+        msgbus.publish("data.quotes.AUD/USD.SIM", &stub_quote());
+        msgbus.unsubscribe_local("data.*", &handler);
+        msgbus.publish("data.quotes.AUD/USD.SIM", &stub_quote());
+
+        assert_eq!(*received.lock().unwrap(), vec!["1"]);
+        assert!(!msgbus.has_subscribers("data.*"));
+    }
+
+    #[rstest]
+    fn test_topic_subscriptions_cache_invalidated_on_subscribe_and_unsubscribe() {
+        let mut msgbus = stub_msgbus();
+        let topic = Ustr::from("data.quotes.AUD/USD.SIM");
+        let callback = stub_rust_callback();
+        let handler1 = MessageHandler::new(Ustr::from("1"), Some(callback.clone()));
+        let handler2 = MessageHandler::new(Ustr::from("2"), Some(callback));
+
+        msgbus.subscribe("data.quotes.*", handler1.clone(), None);
+        assert_eq!(msgbus.topic_subscriptions(&topic).len(), 1);
+
+        msgbus.subscribe("data.*", handler2, Some(1));
+        let subs = msgbus.topic_subscriptions(&topic);
+        assert_eq!(subs.len(), 2);
+        assert_eq!(subs[0].handler.handler_id, Ustr::from("2"));
+
+        msgbus.unsubscribe("data.quotes.*", handler1);
+        let subs = msgbus.topic_subscriptions(&topic);
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].handler.handler_id, Ustr::from("2"));
+    }
+
+    #[rstest]
+    fn test_matching_subscriptions_with_pattern() {
+        let mut msgbus = stub_msgbus();
+        let callback = stub_rust_callback();
+        let handler1 = MessageHandler::new(Ustr::from("1"), Some(callback.clone()));
+        let handler2 = MessageHandler::new(Ustr::from("2"), Some(callback.clone()));
+        let handler3 = MessageHandler::new(Ustr::from("3"), Some(callback));
+
+        msgbus.subscribe("data.quotes.AUD/USD.SIM", handler1, None);
+        msgbus.subscribe("data.quotes.EUR/USD.SIM", handler2, Some(1));
+        msgbus.subscribe("data.trades.AUD/USD.SIM", handler3, None);
+        let pattern = Ustr::from("data.quotes.*");
+        let subs = msgbus.matching_subscriptions(&pattern);
+
+        let handler_ids: Vec<&str> = subs.iter().map(|s| s.handler.handler_id.as_str()).collect();
+        assert_eq!(handler_ids, vec!["2", "1"]);
+    }
+
+    #[rstest]
+    fn test_topic_subscriptions_does_not_cache_unmatched_topics() {
+        let mut msgbus = stub_msgbus();
+        let handler = MessageHandler::new(Ustr::from("1"), Some(stub_rust_callback()));
+        msgbus.subscribe("data.quotes.*", handler, None);
+
+        assert!(msgbus
+            .topic_subscriptions(&Ustr::from("data.trades.AUD/USD.SIM"))
+            .is_empty());
+        assert!(msgbus.topic_cache.borrow().subs.is_empty());
+    }
+
+    #[rstest]
+    fn test_topic_cache_evicts_oldest_topic_at_capacity() {
+        let sub = Subscription::new(
+            Ustr::from("data.*"),
+            MessageHandler::new(Ustr::from("1"), Some(stub_rust_callback())),
+            0,
+            None,
+        );
+        let subs: Arc<[Subscription]> = vec![sub].into();
+        let mut cache = TopicCache::new(2);
+
+        cache.insert(Ustr::from("data.1"), subs.clone());
+        cache.insert(Ustr::from("data.2"), subs.clone());
+        cache.insert(Ustr::from("data.1"), subs.clone());
+        cache.insert(Ustr::from("data.3"), subs);
+
+        assert_eq!(cache.subs.len(), 2);
+        assert!(cache.get(&Ustr::from("data.1")).is_none());
+        assert!(cache.get(&Ustr::from("data.2")).is_some());
+        assert!(cache.get(&Ustr::from("data.3")).is_some());
+    }
+
+    fn stub_quote() -> QuoteTick {
+        QuoteTick::new(
+            InstrumentId::from("AUD/USD.SIM"),
+            Price::from("0.80000"),
+            Price::from("0.80010"),
+            Quantity::from(100_000),
+            Quantity::from(100_000),
+            1,
+            2,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(PayloadEncoding::MsgPack)]
+    #[case(PayloadEncoding::Json)]
+    fn test_publish_from_external(#[case] encoding: PayloadEncoding) {
+        let mut msgbus = stub_msgbus();
+        msgbus.encoding = encoding;
+        msgbus.register_external_type::<QuoteTick>("data.quotes.*");
+
+        let received: Arc<Mutex<Vec<QuoteTick>>> = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let callback = SafeAnyMessageCallback {
+            callback: Arc::new(move |msg: &dyn Any| {
+                if let Some(quote) = msg.downcast_ref::<QuoteTick>() {
+                    received_clone.lock().unwrap().push(*quote);
+                }
+            }),
+        };
+        let handler = MessageHandler::with_any_callback(Ustr::from("1"), callback);
+        msgbus.subscribe("data.quotes.AUD/USD.SIM", handler, None);
+
+        let quote = stub_quote();
+        let payload = match encoding {
+            PayloadEncoding::MsgPack => quote.as_msgpack_bytes().unwrap(),
+            PayloadEncoding::Json => quote.as_json_bytes().unwrap(),
+        };
+        let msg = BusMessage {
+            topic: "data.quotes.AUD/USD.SIM".to_string(),
+            payload,
+        };
+        msgbus.publish_from_external(msg).unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![quote]);
+    }
+
+    #[rstest]
+    fn test_publish_from_external_when_no_type_registered() {
+        let msgbus = stub_msgbus();
+        let msg = BusMessage {
+            topic: "data.quotes.AUD/USD.SIM".to_string(),
+            payload: stub_quote().as_msgpack_bytes().unwrap(),
+        };
+
+        assert!(msgbus.publish_from_external(msg).is_err());
+    }
+
+    #[rstest]
+    fn test_publish_from_external_with_invalid_payload() {
+        let mut msgbus = stub_msgbus();
+        msgbus.register_external_type::<QuoteTick>("data.quotes.*");
+        let msg = BusMessage {
+            topic: "data.quotes.AUD/USD.SIM".to_string(),
+            payload: b"invalid".to_vec(),
+        };
+
+        assert!(msgbus.publish_from_external(msg).is_err());
+    }
+
+    #[rstest]
+    fn test_process_external_when_not_consuming() {
+        let msgbus = stub_msgbus();
+
+        assert_eq!(msgbus.process_external(), 0);
+    }
+
+    #[rstest]
+    fn test_run_external_publishes_until_hung_up() {
+        let mut msgbus = stub_msgbus();
+        msgbus.register_external_type::<QuoteTick>("data.quotes.*");
+        let (tx, rx) = unbounded_channel::<BusMessage>();
+        msgbus.external_rx = Some(Arc::new(tokio::sync::Mutex::new(rx)));
+
+        let received: Arc<Mutex<Vec<QuoteTick>>> = Arc::default();
+        let received_clone = received.clone();
+        let callback = SafeAnyMessageCallback {
+            callback: Arc::new(move |msg: &dyn Any| {
+                if let Some(quote) = msg.downcast_ref::<QuoteTick>() {
+                    received_clone.lock().unwrap().push(*quote);
+                }
+            }),
+        };
+        let handler = MessageHandler::with_any_callback(Ustr::from("1"), callback);
+        msgbus.subscribe("data.quotes.AUD/USD.SIM", handler, None);
+
+        let quote = stub_quote();
+        for _ in 0..2 {
+            let msg = BusMessage {
+                topic: "data.quotes.AUD/USD.SIM".to_string(),
+                payload: quote.as_msgpack_bytes().unwrap(),
+            };
+            tx.send(msg).unwrap();
+        }
+        drop(tx);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(MessageBus::run_external(Rc::new(RefCell::new(msgbus))));
+
+        assert_eq!(*received.lock().unwrap(), vec![quote, quote]);
+    }
+
+    #[rstest]
+    fn test_new_with_external_streams_for_file_backing_errors() {
+        let config = HashMap::from([
+            ("database".to_string(), serde_json::json!({"type": "file"})),
+            (
+                "external_streams".to_string(),
+                serde_json::json!(["trader-*:events.order.*"]),
+            ),
+        ]);
+
+        let result = MessageBus::new(
+            TraderId::from("trader-001"),
+            UUID4::new(),
+            None,
+            Some(config),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    fn test_get_external_streams() {
+        let mut config = HashMap::new();
+        assert!(get_external_streams(&config).is_empty());
+
+        config.insert(
+            "external_streams".to_string(),
+            serde_json::json!(["trader-*:events.order.*", "signals:*"]),
+        );
+        assert_eq!(
+            get_external_streams(&config),
+            vec!["trader-*:events.order.*", "signals:*"]
+        );
+    }
 }
@@ -47,8 +47,20 @@ impl FileWriterConfig {
         directory: Option<String>,
         file_name: Option<String>,
         file_format: Option<String>,
+        max_file_size: Option<u64>,
+        rotation_interval: Option<String>,
+        max_backup_count: Option<usize>,
+        compress_backups: Option<bool>,
     ) -> Self {
-        Self::new(directory, file_name, file_format)
+        Self::new(
+            directory,
+            file_name,
+            file_format,
+            max_file_size,
+            rotation_interval,
+            max_backup_count,
+            compress_backups.unwrap_or(false),
+        )
     }
 }
 
@@ -94,6 +106,10 @@ pub fn py_init_logging(
     is_colored: Option<bool>,
     is_bypassed: Option<bool>,
     print_config: Option<bool>,
+    max_file_size: Option<u64>,
+    rotation_interval: Option<String>,
+    max_backup_count: Option<usize>,
+    compress_backups: Option<bool>,
 ) -> LogGuard {
     let level_file = level_file
         .map(map_log_level_to_filter)
@@ -107,7 +123,15 @@ pub fn py_init_logging(
         print_config.unwrap_or(false),
     );
 
-    let file_config = FileWriterConfig::new(directory, file_name, file_format);
+    let file_config = FileWriterConfig::new(
+        directory,
+        file_name,
+        file_format,
+        max_file_size,
+        rotation_interval,
+        max_backup_count,
+        compress_backups.unwrap_or(false),
+    );
 
     if is_bypassed.unwrap_or(false) {
         logging_set_bypass();
@@ -22,15 +22,27 @@ use std::{
 
 use nautilus_core::{time::duration_since_unix_epoch, uuid::UUID4};
 use nautilus_model::identifiers::trader_id::TraderId;
-use redis::*;
-use serde_json::{json, Value};
-use tracing::debug;
+use redis::{
+    streams::{StreamRangeReply, StreamReadOptions, StreamReadReply},
+    *,
+};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, error, warn};
 
-use crate::msgbus::BusMessage;
+use crate::msgbus::{
+    get_buffer_interval, get_external_streams, get_stream_name, BusMessage, STREAM_DELIMITER,
+};
 
-const DELIMITER: char = ':';
 const XTRIM: &str = "XTRIM";
 const MINID: &str = "MINID";
+const PAYLOAD: &str = "payload";
+const STREAM_START_ID: &str = "0-0";
+const EXTERNAL_OFFSETS: &str = "external_stream_offsets";
+const EXTERNAL_READ_BLOCK_MS: usize = 100;
+const EXTERNAL_READ_COUNT: usize = 1_000;
+const EXTERNAL_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
 
 pub fn handle_messages_with_redis(
     rx: Receiver<BusMessage>,
@@ -142,6 +154,265 @@ fn drain_buffer(
     pipe.query::<()>(conn).map_err(anyhow::Error::from)
 }
 
+/// Consumes the external streams with keys matching the configured `external_streams`
+/// patterns, sending each message read to the `tx` channel.
+///
+/// The last entry ID read from each stream is tracked and persisted to a hash at the
+/// [`get_offsets_key`] for the trader, so that on a reconnect or restart consumption
+/// resumes from where it left off without any duplicate messages.
+/// Streams written by this message bus are never consumed.
+pub fn consume_streams_with_redis(
+    tx: UnboundedSender<BusMessage>,
+    trader_id: TraderId,
+    instance_id: UUID4,
+    config: HashMap<String, Value>,
+) -> anyhow::Result<()> {
+    let database_config = config
+        .get("database")
+        .ok_or(anyhow::anyhow!("No database config"))?;
+    let patterns = get_external_streams(&config);
+    let stream_name = get_stream_name(trader_id, instance_id, &config);
+    let offsets_key = get_offsets_key(trader_id);
+    let mut offsets: Option<StreamOffsets> = None;
+
+    loop {
+        debug!("Creating msgbus external streams redis connection");
+        let mut conn = match create_redis_connection(database_config) {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!("Error connecting to consume external streams: {e}");
+                thread::sleep(RECONNECT_INTERVAL);
+                continue;
+            }
+        };
+
+        let offsets = match offsets.as_mut() {
+            Some(offsets) => offsets,
+            None => match load_offsets(&mut conn, &offsets_key) {
+                Ok(loaded) => offsets.insert(loaded),
+                Err(e) => {
+                    error!("Error loading external stream offsets, reconnecting: {e}");
+                    thread::sleep(RECONNECT_INTERVAL);
+                    continue;
+                }
+            },
+        };
+
+        match read_streams(
+            &mut conn,
+            &tx,
+            &patterns,
+            &stream_name,
+            &offsets_key,
+            offsets,
+        ) {
+            Ok(()) => return Ok(()), // Channel hung up
+            Err(e) => {
+                error!("Error consuming external streams, reconnecting: {e}");
+                thread::sleep(RECONNECT_INTERVAL);
+            }
+        }
+    }
+}
+
+fn read_streams(
+    conn: &mut Connection,
+    tx: &UnboundedSender<BusMessage>,
+    patterns: &[String],
+    stream_name: &str,
+    offsets_key: &str,
+    offsets: &mut StreamOffsets,
+) -> anyhow::Result<()> {
+    let options = StreamReadOptions::default()
+        .block(EXTERNAL_READ_BLOCK_MS)
+        .count(EXTERNAL_READ_COUNT);
+    let mut last_scan: Option<Instant> = None;
+
+    loop {
+        if !matches!(last_scan, Some(last) if last.elapsed() < EXTERNAL_SCAN_INTERVAL) {
+            // Streams created before the first scan are consumed from their latest entry,
+            // any created later are consumed from the start.
+            let from_latest = last_scan.is_none() && offsets.is_empty();
+            scan_streams(conn, patterns, stream_name, offsets, from_latest)?;
+            last_scan = Some(Instant::now());
+        }
+
+        if offsets.is_empty() {
+            thread::sleep(Duration::from_millis(EXTERNAL_READ_BLOCK_MS as u64));
+            continue;
+        }
+
+        let (keys, ids) = offsets.read_args();
+        let reply: Option<StreamReadReply> = conn.xread_options(&keys, &ids, &options)?;
+        let Some(reply) = reply else {
+            continue; // No new entries
+        };
+
+        let mut consumed: Vec<(String, String)> = Vec::new();
+        for stream in reply.keys {
+            for entry in stream.ids {
+                if !offsets.advance(&stream.key, &entry.id) {
+                    continue; // Already consumed
+                }
+                match consumed.last_mut() {
+                    Some((key, id)) if *key == stream.key => id.clone_from(&entry.id),
+                    _ => consumed.push((stream.key.clone(), entry.id.clone())),
+                }
+                let Some(payload) = entry.get::<Vec<u8>>(PAYLOAD) else {
+                    warn!(
+                        "No payload for entry {} in stream '{}'",
+                        entry.id, stream.key
+                    );
+                    continue;
+                };
+                let msg = BusMessage {
+                    topic: topic_from_stream_key(&stream.key).to_string(),
+                    payload,
+                };
+                if tx.send(msg).is_err() {
+                    return Ok(()); // Channel hung up
+                }
+            }
+        }
+
+        if !consumed.is_empty() {
+            conn.hset_multiple::<_, _, _, ()>(offsets_key, &consumed)?;
+        }
+    }
+}
+
+/// Returns the key of the hash persisting the offsets of the external streams
+/// consumed for the trader.
+#[must_use]
+pub fn get_offsets_key(trader_id: TraderId) -> String {
+    format!("trader-{trader_id}{STREAM_DELIMITER}{EXTERNAL_OFFSETS}")
+}
+
+fn load_offsets(conn: &mut Connection, offsets_key: &str) -> anyhow::Result<StreamOffsets> {
+    let persisted: HashMap<String, String> = conn.hgetall(offsets_key)?;
+    let mut offsets = StreamOffsets::default();
+    for (key, id) in persisted {
+        if parse_stream_id(&id).is_some() {
+            debug!("Resuming external stream '{key}' after {id}");
+            offsets.track(&key, &id);
+        }
+    }
+    Ok(offsets)
+}
+
+fn scan_streams(
+    conn: &mut Connection,
+    patterns: &[String],
+    stream_name: &str,
+    offsets: &mut StreamOffsets,
+    from_latest: bool,
+) -> anyhow::Result<()> {
+    for pattern in patterns {
+        let keys: Vec<String> = conn.scan_match::<_, String>(pattern)?.collect();
+        for key in keys {
+            if is_own_stream(&key, stream_name) || offsets.contains(&key) {
+                continue; // Own stream or already consuming
+            }
+            let key_type: String = redis::cmd("TYPE").arg(&key).query(conn)?;
+            if key_type != "stream" {
+                continue;
+            }
+
+            let start_id = if from_latest {
+                let latest: StreamRangeReply = conn.xrevrange_count(&key, "+", "-", 1)?;
+                latest
+                    .ids
+                    .first()
+                    .map_or_else(|| STREAM_START_ID.to_string(), |entry| entry.id.clone())
+            } else {
+                STREAM_START_ID.to_string()
+            };
+            debug!("Consuming external stream '{key}' after {start_id}");
+            offsets.track(&key, &start_id);
+        }
+    }
+    Ok(())
+}
+
+/// Tracks the last entry ID consumed from each external stream.
+#[derive(Clone, Debug, Default)]
+pub struct StreamOffsets {
+    offsets: HashMap<String, String>,
+}
+
+impl StreamOffsets {
+    /// Returns whether no streams are being tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns whether the stream `key` is being tracked.
+    #[must_use]
+    pub fn contains(&self, key: &str) -> bool {
+        self.offsets.contains_key(key)
+    }
+
+    /// Returns the last entry ID consumed from the stream `key` (if tracked).
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.offsets.get(key).map(String::as_str)
+    }
+
+    /// Starts tracking the stream `key` from the entry `id` (if not already tracked).
+    pub fn track(&mut self, key: &str, id: &str) {
+        self.offsets
+            .entry(key.to_string())
+            .or_insert_with(|| id.to_string());
+    }
+
+    /// Advances the offset for the stream `key` to the entry `id`.
+    ///
+    /// Returns `false` if the entry is not after the current offset (already consumed).
+    pub fn advance(&mut self, key: &str, id: &str) -> bool {
+        let Some(next) = parse_stream_id(id) else {
+            return false;
+        };
+        match self.offsets.get_mut(key) {
+            Some(offset) if parse_stream_id(offset).is_some_and(|last| next <= last) => false,
+            Some(offset) => {
+                *offset = id.to_string();
+                true
+            }
+            None => {
+                self.offsets.insert(key.to_string(), id.to_string());
+                true
+            }
+        }
+    }
+
+    /// Returns the stream keys and their offsets as aligned `XREAD` arguments.
+    #[must_use]
+    pub fn read_args(&self) -> (Vec<&str>, Vec<&str>) {
+        self.offsets
+            .iter()
+            .map(|(key, id)| (key.as_str(), id.as_str()))
+            .unzip()
+    }
+}
+
+/// Parses a stream entry ID of the form `<milliseconds>-<sequence>`.
+fn parse_stream_id(id: &str) -> Option<(u64, u64)> {
+    let (ms, seq) = id.split_once('-').unwrap_or((id, "0"));
+    Some((ms.parse().ok()?, seq.parse().ok()?))
+}
+
+/// Returns the bus topic for the stream `key`, being the final component after the stream name.
+fn topic_from_stream_key(key: &str) -> &str {
+    key.rsplit(STREAM_DELIMITER).next().unwrap_or(key)
+}
+
+/// Returns whether the stream `key` is written by the bus with the `stream_name`, being
+/// exactly the stream name followed by a topic.
+fn is_own_stream(key: &str, stream_name: &str) -> bool {
+    key.strip_prefix(stream_name) == Some(topic_from_stream_key(key))
+}
+
 pub fn get_redis_url(database_config: &serde_json::Value) -> (String, String) {
     let host = database_config
         .get("host")
@@ -223,44 +494,6 @@ pub fn get_timeout_duration(database_config: &serde_json::Value, default: u64) -
     Duration::from_secs(timeout_seconds)
 }
 
-pub fn get_buffer_interval(config: &HashMap<String, Value>) -> Duration {
-    let buffer_interval_ms = config
-        .get("buffer_interval_ms")
-        .map(|v| v.as_u64().unwrap_or(0));
-    Duration::from_millis(buffer_interval_ms.unwrap_or(0))
-}
-
-fn get_stream_name(
-    trader_id: TraderId,
-    instance_id: UUID4,
-    config: &HashMap<String, Value>,
-) -> String {
-    let mut stream_name = String::new();
-
-    if let Some(json!(true)) = config.get("use_trader_prefix") {
-        stream_name.push_str("trader-");
-    }
-
-    if let Some(json!(true)) = config.get("use_trader_id") {
-        stream_name.push_str(trader_id.value.as_str());
-        stream_name.push(DELIMITER);
-    }
-
-    if let Some(json!(true)) = config.get("use_instance_id") {
-        stream_name.push_str(&format!("{instance_id}"));
-        stream_name.push(DELIMITER);
-    }
-
-    let stream_prefix = config
-        .get("streams_prefix")
-        .expect("Invalid configuration: no `streams_prefix` key found")
-        .as_str()
-        .expect("Invalid configuration: `streams_prefix` is not a string");
-    stream_name.push_str(stream_prefix);
-    stream_name.push(DELIMITER);
-    stream_name
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -393,4 +626,72 @@ mod tests {
         let key = get_stream_name(trader_id, instance_id, &config);
         assert_eq!(key, format!("streams:"));
     }
+
+    #[rstest]
+    #[case("1700000000000-0", Some((1_700_000_000_000, 0)))]
+    #[case("1700000000000-12", Some((1_700_000_000_000, 12)))]
+    #[case("1700000000000", Some((1_700_000_000_000, 0)))]
+    #[case("invalid", None)]
+    fn test_parse_stream_id(#[case] id: &str, #[case] expected: Option<(u64, u64)>) {
+        assert_eq!(parse_stream_id(id), expected);
+    }
+
+    #[rstest]
+    #[case("trader-TESTER-001:streams:events.order.S-001", "events.order.S-001")]
+    #[case("streams:data.quotes.AUD/USD.SIM", "data.quotes.AUD/USD.SIM")]
+    fn test_topic_from_stream_key(#[case] key: &str, #[case] expected: &str) {
+        assert_eq!(topic_from_stream_key(key), expected);
+    }
+
+    #[rstest]
+    #[case("trader-TESTER-001:streams:events.order.S-001", true)]
+    #[case("trader-TESTER-001:streams:other:events.order.S-001", false)]
+    #[case("trader-TESTER-001:streams-backup:events.order.S-001", false)]
+    #[case("trader-TESTER-0011:streams:events.order.S-001", false)]
+    #[case("trader-TESTER-002:streams:events.order.S-001", false)]
+    fn test_is_own_stream(#[case] key: &str, #[case] expected: bool) {
+        assert_eq!(is_own_stream(key, "trader-TESTER-001:streams:"), expected);
+    }
+
+    #[rstest]
+    fn test_get_offsets_key() {
+        let key = get_offsets_key(TraderId::from("TESTER-001"));
+        assert_eq!(key, "trader-TESTER-001:external_stream_offsets");
+    }
+
+    #[rstest]
+    fn test_stream_offsets_track_does_not_overwrite() {
+        let mut offsets = StreamOffsets::default();
+        offsets.track("stream-1", "0-0");
+        offsets.track("stream-1", "5-0");
+
+        assert_eq!(offsets.get("stream-1"), Some("0-0"));
+    }
+
+    #[rstest]
+    fn test_stream_offsets_advance_skips_consumed_entries() {
+        let mut offsets = StreamOffsets::default();
+        offsets.track("stream-1", "0-0");
+
+        assert!(offsets.advance("stream-1", "10-0"));
+        assert!(offsets.advance("stream-1", "10-1"));
+        assert!(!offsets.advance("stream-1", "10-1"));
+        assert!(!offsets.advance("stream-1", "9-5"));
+        assert!(offsets.advance("stream-1", "11-0"));
+        assert_eq!(offsets.get("stream-1"), Some("11-0"));
+    }
+
+    #[rstest]
+    fn test_stream_offsets_read_args_are_aligned() {
+        let mut offsets = StreamOffsets::default();
+        offsets.track("stream-1", "1-0");
+        offsets.track("stream-2", "2-0");
+        offsets.advance("stream-2", "3-0");
+
+        let (keys, ids) = offsets.read_args();
+        let mut args: Vec<(&str, &str)> = keys.into_iter().zip(ids).collect();
+        args.sort_unstable();
+
+        assert_eq!(args, vec![("stream-1", "1-0"), ("stream-2", "3-0")]);
+    }
 }
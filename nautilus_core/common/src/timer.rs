@@ -17,6 +17,7 @@ use std::{
     cmp::Ordering,
     ffi::c_char,
     fmt::{Display, Formatter},
+    rc::Rc,
     time::Duration,
 };
 
@@ -30,7 +31,10 @@ use pyo3::{types::PyCapsule, IntoPy, PyObject, Python};
 use tokio::sync::oneshot;
 use ustr::Ustr;
 
-use crate::{handlers::EventHandler, runtime::get_runtime};
+use crate::{
+    handlers::{EventHandler, LocalTimeEventCallback, SafeTimeEventCallback},
+    runtime::get_runtime,
+};
 
 #[repr(C)]
 #[derive(Clone, Debug)]
@@ -89,6 +93,22 @@ pub struct TimeEventHandler {
     pub callback_ptr: *mut c_char,
 }
 
+/// Represents a time event and its associated Rust-native local callback.
+#[derive(Clone)]
+pub struct LocalTimeEventHandler {
+    /// The event.
+    pub event: TimeEvent,
+    /// The callback for the event.
+    pub callback: Rc<LocalTimeEventCallback>,
+}
+
+impl LocalTimeEventHandler {
+    /// Handles the event with the callback.
+    pub fn handle(&self) {
+        (self.callback)(&self.event);
+    }
+}
+
 impl PartialOrd for TimeEventHandler {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -208,6 +228,15 @@ impl Iterator for TestTimer {
     }
 }
 
+/// The callback for the events generated by a [`LiveTimer`].
+#[derive(Clone)]
+enum LiveTimerCallback {
+    /// An event handler (a Python callable with the `python` feature).
+    Handler(EventHandler),
+    /// A Rust-native callback, called on the runtime thread with either feature.
+    Rust(SafeTimeEventCallback),
+}
+
 /// Provides a live timer for use with a `LiveClock`.
 ///
 /// Note: `next_time_ns` is only accurate when initially starting the timer
@@ -219,7 +248,7 @@ pub struct LiveTimer {
     pub stop_time_ns: Option<UnixNanos>,
     pub next_time_ns: UnixNanos,
     pub is_expired: bool,
-    callback: EventHandler,
+    callback: LiveTimerCallback,
     canceler: Option<oneshot::Sender<()>>,
 }
 
@@ -231,6 +260,40 @@ impl LiveTimer {
         start_time_ns: UnixNanos,
         stop_time_ns: Option<UnixNanos>,
         callback: EventHandler,
+    ) -> Self {
+        Self::from_callback(
+            name,
+            interval_ns,
+            start_time_ns,
+            stop_time_ns,
+            LiveTimerCallback::Handler(callback),
+        )
+    }
+
+    /// Creates a new [`LiveTimer`] with the Rust-native `callback` handling its events.
+    #[must_use]
+    pub fn with_rust_callback(
+        name: &str,
+        interval_ns: u64,
+        start_time_ns: UnixNanos,
+        stop_time_ns: Option<UnixNanos>,
+        callback: SafeTimeEventCallback,
+    ) -> Self {
+        Self::from_callback(
+            name,
+            interval_ns,
+            start_time_ns,
+            stop_time_ns,
+            LiveTimerCallback::Rust(callback),
+        )
+    }
+
+    fn from_callback(
+        name: &str,
+        interval_ns: u64,
+        start_time_ns: UnixNanos,
+        stop_time_ns: Option<UnixNanos>,
+        callback: LiveTimerCallback,
     ) -> Self {
         check_valid_string(name, stringify!(name)).unwrap();
 
@@ -269,9 +332,17 @@ impl LiveTimer {
             loop {
                 tokio::select! {
                     _ = tokio::time::sleep(Duration::from_nanos(next_time_ns.saturating_sub(clock.get_time_ns()))) => {
-                        // TODO: Remove this clone
-                        let callback = callback.clone();
-                        call_python_with_time_event(event_name, next_time_ns, clock.get_time_ns(), callback);
+                        match &callback {
+                            LiveTimerCallback::Handler(handler) => {
+                                // TODO: Remove this clone
+                                let handler = handler.clone();
+                                call_time_event_handler(event_name, next_time_ns, clock.get_time_ns(), handler);
+                            }
+                            LiveTimerCallback::Rust(callback) => {
+                                let event = TimeEvent::new(event_name, UUID4::new(), next_time_ns, clock.get_time_ns());
+                                (callback.callback)(event);
+                            }
+                        }
 
                         // Prepare next time interval
                         next_time_ns += interval_ns;
@@ -304,7 +375,7 @@ impl LiveTimer {
 }
 
 #[cfg(feature = "python")]
-fn call_python_with_time_event(
+fn call_time_event_handler(
     name: Ustr,
     ts_event: UnixNanos,
     ts_init: UnixNanos,
@@ -325,13 +396,14 @@ fn call_python_with_time_event(
 }
 
 #[cfg(not(feature = "python"))]
-fn call_python_with_time_event(
-    _name: Ustr,
-    _ts_event: UnixNanos,
-    _ts_init: UnixNanos,
-    _handler: EventHandler,
+fn call_time_event_handler(
+    name: Ustr,
+    ts_event: UnixNanos,
+    ts_init: UnixNanos,
+    handler: EventHandler,
 ) {
-    panic!("`python` feature is not enabled");
+    let event = TimeEvent::new(name, UUID4::new(), ts_event, ts_init);
+    (handler.callback.callback)(event);
 }
 
 ////////////////////////////////////////////////////////////////////////////////
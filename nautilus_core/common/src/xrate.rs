@@ -0,0 +1,327 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use nautilus_model::{enums::PriceType, identifiers::symbol::Symbol, types::currency::Currency};
+use ustr::Ustr;
+
+/// Provides exchange rate calculations between currencies.
+///
+/// An exchange rate is the value of one asset versus that of another. Quotes are
+/// keyed by a currency pair symbol of the form `BASE/QUOTE`, from which a graph of
+/// direct and inverse rates is built, with any rate not directly available then
+/// triangulated through a single common currency.
+///
+/// Inverse rates are taken from the opposite side of the quote, so that converting
+/// `QUOTE` to `BASE` at the bid uses the inverse of the `BASE/QUOTE` ask (and vice versa).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExchangeRateCalculator;
+
+impl ExchangeRateCalculator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the exchange rate for the given `price_type` calculated from the
+    /// given bid and ask quotes, or `None` if there is insufficient data.
+    ///
+    /// For `PriceType::Mid` only symbols with both a bid and an ask quote are used.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `price_type` is `PriceType::Last`.
+    pub fn get_rate(
+        &self,
+        from_currency: Currency,
+        to_currency: Currency,
+        price_type: PriceType,
+        bid_quotes: &HashMap<Symbol, f64>,
+        ask_quotes: &HashMap<Symbol, f64>,
+    ) -> anyhow::Result<Option<f64>> {
+        if from_currency == to_currency {
+            return Ok(Some(1.0)); // No conversion necessary
+        }
+
+        let graph = self.build_graph(price_type, bid_quotes, ask_quotes)?;
+        Ok(graph.get_rate(from_currency, to_currency))
+    }
+
+    /// Builds the [`RateGraph`] for the given `price_type` from the given bid and ask
+    /// quotes, which may then be reused until the quotes change.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if `price_type` is `PriceType::Last`.
+    pub fn build_graph(
+        &self,
+        price_type: PriceType,
+        bid_quotes: &HashMap<Symbol, f64>,
+        ask_quotes: &HashMap<Symbol, f64>,
+    ) -> anyhow::Result<RateGraph> {
+        let graph = match price_type {
+            PriceType::Bid => RateGraph::new(bid_quotes, ask_quotes),
+            PriceType::Ask => RateGraph::new(ask_quotes, bid_quotes),
+            PriceType::Mid => {
+                let mid_quotes: HashMap<Symbol, f64> = bid_quotes
+                    .iter()
+                    .filter_map(|(symbol, bid)| {
+                        ask_quotes
+                            .get(symbol)
+                            .map(|ask| (*symbol, (bid + ask) / 2.0))
+                    })
+                    .collect();
+                RateGraph::new(&mid_quotes, &mid_quotes)
+            }
+            PriceType::Last => {
+                anyhow::bail!("Cannot calculate exchange rate for `PriceType::Last`")
+            }
+        };
+        Ok(graph)
+    }
+}
+
+/// Represents the graph of rates between currency codes for a single price type,
+/// where `rates[lhs][rhs]` is the price of one unit of `lhs` in `rhs`.
+#[derive(Clone, Debug, Default)]
+pub struct RateGraph {
+    rates: HashMap<Ustr, HashMap<Ustr, f64>>,
+}
+
+impl RateGraph {
+    /// Creates a new [`RateGraph`] from the `direct_quotes` for each `BASE/QUOTE` symbol,
+    /// with the inverse rates implied by the `inverse_quotes`.
+    ///
+    /// Quoted rates always take precedence over rates implied by inverting another quote.
+    #[must_use]
+    pub fn new(
+        direct_quotes: &HashMap<Symbol, f64>,
+        inverse_quotes: &HashMap<Symbol, f64>,
+    ) -> Self {
+        let mut rates: HashMap<Ustr, HashMap<Ustr, f64>> = HashMap::new();
+        for (lhs, rhs, quote) in parse_pairs(direct_quotes) {
+            rates.entry(lhs).or_default().insert(rhs, quote);
+        }
+        for (lhs, rhs, quote) in parse_pairs(inverse_quotes) {
+            rates
+                .entry(rhs)
+                .or_default()
+                .entry(lhs)
+                .or_insert(1.0 / quote);
+        }
+        Self { rates }
+    }
+
+    /// Returns the rate to convert `from_currency` into `to_currency`, or `None` if
+    /// there is insufficient data.
+    #[must_use]
+    pub fn get_rate(&self, from_currency: Currency, to_currency: Currency) -> Option<f64> {
+        if from_currency == to_currency {
+            return Some(1.0); // No conversion necessary
+        }
+
+        let from_rates = self.rates.get(&from_currency.code)?;
+        let to = to_currency.code;
+
+        // Direct (or inverse) rate
+        if let Some(rate) = from_rates.get(&to) {
+            return Some(*rate);
+        }
+
+        // Triangulate through a common currency, sorted so the result is deterministic
+        let mut common: Vec<(&Ustr, &f64)> = from_rates.iter().collect();
+        common.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        common.into_iter().find_map(|(code, rate1)| {
+            self.rates
+                .get(code)
+                .and_then(|code_rates| code_rates.get(&to))
+                .map(|rate2| rate1 * rate2)
+        })
+    }
+}
+
+fn parse_pairs(quotes: &HashMap<Symbol, f64>) -> impl Iterator<Item = (Ustr, Ustr, f64)> + '_ {
+    quotes
+        .iter()
+        .filter(|(_, quote)| quote.is_finite() && **quote > 0.0)
+        .filter_map(|(symbol, quote)| {
+            symbol
+                .value
+                .as_str()
+                .split_once('/')
+                .map(|(lhs, rhs)| (Ustr::from(lhs), Ustr::from(rhs), *quote))
+        })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn quotes(values: &[(&str, f64)]) -> HashMap<Symbol, f64> {
+        values
+            .iter()
+            .map(|(symbol, quote)| (Symbol::from(*symbol), *quote))
+            .collect()
+    }
+
+    #[rstest]
+    fn test_get_rate_same_currency() {
+        let calculator = ExchangeRateCalculator::new();
+        let rate = calculator
+            .get_rate(
+                Currency::USD(),
+                Currency::USD(),
+                PriceType::Mid,
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(rate, Some(1.0));
+    }
+
+    #[rstest]
+    fn test_get_rate_for_last_price_type_errors() {
+        let calculator = ExchangeRateCalculator::new();
+        let result = calculator.get_rate(
+            Currency::AUD(),
+            Currency::USD(),
+            PriceType::Last,
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(PriceType::Bid, 0.80)]
+    #[case(PriceType::Ask, 0.82)]
+    #[case(PriceType::Mid, 0.81)]
+    fn test_get_rate_direct(#[case] price_type: PriceType, #[case] expected: f64) {
+        let calculator = ExchangeRateCalculator::new();
+        let bid_quotes = quotes(&[("AUD/USD", 0.80)]);
+        let ask_quotes = quotes(&[("AUD/USD", 0.82)]);
+
+        let rate = calculator
+            .get_rate(
+                Currency::AUD(),
+                Currency::USD(),
+                price_type,
+                &bid_quotes,
+                &ask_quotes,
+            )
+            .unwrap()
+            .unwrap();
+
+        assert!((rate - expected).abs() < 1e-12);
+    }
+
+    #[rstest]
+    #[case(PriceType::Bid, 1.0 / 0.82)]
+    #[case(PriceType::Ask, 1.0 / 0.80)]
+    #[case(PriceType::Mid, 1.0 / 0.81)]
+    fn test_get_rate_inverse_uses_opposite_side(
+        #[case] price_type: PriceType,
+        #[case] expected: f64,
+    ) {
+        let calculator = ExchangeRateCalculator::new();
+        let bid_quotes = quotes(&[("AUD/USD", 0.80)]);
+        let ask_quotes = quotes(&[("AUD/USD", 0.82)]);
+
+        let rate = calculator
+            .get_rate(
+                Currency::USD(),
+                Currency::AUD(),
+                price_type,
+                &bid_quotes,
+                &ask_quotes,
+            )
+            .unwrap()
+            .unwrap();
+
+        assert!((rate - expected).abs() < 1e-12);
+    }
+
+    #[rstest]
+    fn test_get_rate_triangulated() {
+        let calculator = ExchangeRateCalculator::new();
+        let bid_quotes = quotes(&[("AUD/USD", 0.80), ("USD/JPY", 110.0)]);
+
+        let rate = calculator
+            .get_rate(
+                Currency::AUD(),
+                Currency::JPY(),
+                PriceType::Bid,
+                &bid_quotes,
+                &bid_quotes,
+            )
+            .unwrap()
+            .unwrap();
+        let inverse = calculator
+            .get_rate(
+                Currency::JPY(),
+                Currency::AUD(),
+                PriceType::Bid,
+                &bid_quotes,
+                &bid_quotes,
+            )
+            .unwrap()
+            .unwrap();
+
+        assert!((rate - 88.0).abs() < 1e-9);
+        assert!((inverse - 1.0 / 88.0).abs() < 1e-12);
+    }
+
+    #[rstest]
+    fn test_get_rate_with_insufficient_data() {
+        let calculator = ExchangeRateCalculator::new();
+        let bid_quotes = quotes(&[("AUD/USD", 0.80), ("EUR/GBP", 0.85)]);
+
+        let rate = calculator
+            .get_rate(
+                Currency::AUD(),
+                Currency::GBP(),
+                PriceType::Bid,
+                &bid_quotes,
+                &bid_quotes,
+            )
+            .unwrap();
+
+        assert_eq!(rate, None);
+    }
+
+    #[rstest]
+    fn test_rate_graph_reused_for_multiple_rates() {
+        let calculator = ExchangeRateCalculator::new();
+        let bid_quotes = quotes(&[("AUD/USD", 0.80), ("USD/JPY", 110.0)]);
+        let ask_quotes = quotes(&[("AUD/USD", 0.82), ("USD/JPY", 110.0)]);
+
+        let graph = calculator
+            .build_graph(PriceType::Bid, &bid_quotes, &ask_quotes)
+            .unwrap();
+
+        assert_eq!(graph.get_rate(Currency::AUD(), Currency::USD()), Some(0.80));
+        assert_eq!(graph.get_rate(Currency::USD(), Currency::USD()), Some(1.0));
+        assert!((graph.get_rate(Currency::AUD(), Currency::JPY()).unwrap() - 88.0).abs() < 1e-9);
+        assert_eq!(graph.get_rate(Currency::AUD(), Currency::GBP()), None);
+    }
+}
@@ -20,7 +20,10 @@ use std::{
     str::FromStr,
 };
 
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use uuid::Uuid;
 
 /// The maximum length of ASCII characters for a `UUID4` string value (includes null terminator).
@@ -95,18 +98,70 @@ impl Serialize for UUID4 {
     where
         S: Serializer,
     {
-        self.value.serialize(serializer)
+        self.to_string().serialize(serializer)
     }
 }
 
 impl<'de> Deserialize<'de> for UUID4 {
-    fn deserialize<D>(_deserializer: D) -> Result<Self, D::Error>
+    /// Deserializes from the UUID string, or from the legacy null-terminated byte array
+    /// (previously written by serializing the `value` field directly).
+    ///
+    /// Human-readable formats fall back to the legacy form explicitly, while other formats
+    /// are asked for a string, so formats which are not self-describing are supported.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let uuid4_str: &str = Deserialize::deserialize(_deserializer)?;
-        let uuid4: Self = uuid4_str.into();
-        Ok(uuid4)
+        if deserializer.is_human_readable() {
+            match UUID4Repr::deserialize(deserializer)? {
+                UUID4Repr::Str(value) => value.parse().map_err(de::Error::custom),
+                UUID4Repr::Legacy(bytes) => UUID4Visitor::parse_bytes(&bytes),
+            }
+        } else {
+            deserializer.deserialize_str(UUID4Visitor)
+        }
+    }
+}
+
+/// The serialized forms of a [`UUID4`] in human-readable formats.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UUID4Repr {
+    Str(String),
+    Legacy(Vec<u8>),
+}
+
+struct UUID4Visitor;
+
+impl UUID4Visitor {
+    fn parse_bytes<E: de::Error>(bytes: &[u8]) -> Result<UUID4, E> {
+        let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        let uuid4_str = std::str::from_utf8(&bytes[..len]).map_err(E::custom)?;
+        uuid4_str.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Visitor<'de> for UUID4Visitor {
+    type Value = UUID4;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a UUID string or null-terminated byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Self::parse_bytes(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(UUID4_LEN);
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Self::parse_bytes(&bytes)
     }
 }
 
@@ -162,4 +217,63 @@ mod tests {
         let result_string = format!("{uuid}");
         assert_eq!(result_string, uuid_string);
     }
+
+    #[rstest]
+    fn test_uuid4_serde_json_round_trip() {
+        let uuid = UUID4::from("6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+        let json = serde_json::to_string(&uuid).unwrap();
+        let deserialized: UUID4 = serde_json::from_str(&json).unwrap();
+        assert_eq!(json, "\"6ba7b810-9dad-11d1-80b4-00c04fd430c8\"");
+        assert_eq!(deserialized, uuid);
+    }
+
+    #[rstest]
+    fn test_uuid4_msgpack_round_trip() {
+        let uuid = UUID4::from("6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+        let bytes = rmp_serde::to_vec(&uuid).unwrap();
+        let legacy_bytes = rmp_serde::to_vec(&uuid.value.to_vec()).unwrap();
+        assert_eq!(rmp_serde::from_slice::<UUID4>(&bytes).unwrap(), uuid);
+        assert_eq!(rmp_serde::from_slice::<UUID4>(&legacy_bytes).unwrap(), uuid);
+    }
+
+    /// A deserializer for a format which is not self-describing, so only reads for the
+    /// expected type succeed.
+    struct StrOnlyDeserializer<'a>(&'a str);
+
+    impl<'de, 'a> Deserializer<'de> for StrOnlyDeserializer<'a> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("`deserialize_any` is not supported"))
+        }
+
+        fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_str(self.0)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    #[rstest]
+    fn test_uuid4_deserialize_from_non_self_describing_format() {
+        let uuid_string = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        let deserialized = UUID4::deserialize(StrOnlyDeserializer(uuid_string)).unwrap();
+        assert_eq!(deserialized, UUID4::from(uuid_string));
+    }
+
+    #[rstest]
+    fn test_uuid4_deserialize_legacy_byte_array() {
+        let uuid = UUID4::from("6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+        let legacy_json = serde_json::to_string(&uuid.value.to_vec()).unwrap();
+        let deserialized: UUID4 = serde_json::from_str(&legacy_json).unwrap();
+        assert_eq!(deserialized, uuid);
+    }
 }
@@ -14,3 +14,5 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod matching_core;
+pub mod messages;
+pub mod trailing;
@@ -178,17 +178,35 @@ impl OrderMatchingCore {
 
     #[must_use]
     pub fn is_limit_matched(&self, order: &LimitOrderType) -> bool {
-        match order.get_order_side() {
-            OrderSideFixed::Buy => self.ask.map_or(false, |a| a <= order.get_limit_px()),
-            OrderSideFixed::Sell => self.bid.map_or(false, |b| b >= order.get_limit_px()),
-        }
+        self.is_limit_price_matched(order.get_order_side(), order.get_limit_px())
     }
 
     #[must_use]
     pub fn is_stop_matched(&self, order: &StopOrderType) -> bool {
-        match order.get_order_side() {
-            OrderSideFixed::Buy => self.ask.map_or(false, |a| a >= order.get_stop_px()),
-            OrderSideFixed::Sell => self.bid.map_or(false, |b| b <= order.get_stop_px()),
+        self.is_stop_triggered(order.get_order_side(), order.get_stop_px())
+    }
+
+    #[must_use]
+    pub fn is_limit_price_matched(&self, side: OrderSideFixed, price: Price) -> bool {
+        match side {
+            OrderSideFixed::Buy => self.ask.map_or(false, |a| a <= price),
+            OrderSideFixed::Sell => self.bid.map_or(false, |b| b >= price),
+        }
+    }
+
+    #[must_use]
+    pub fn is_stop_triggered(&self, side: OrderSideFixed, trigger_price: Price) -> bool {
+        match side {
+            OrderSideFixed::Buy => self.ask.map_or(false, |a| a >= trigger_price),
+            OrderSideFixed::Sell => self.bid.map_or(false, |b| b <= trigger_price),
+        }
+    }
+
+    #[must_use]
+    pub fn is_touch_triggered(&self, side: OrderSideFixed, trigger_price: Price) -> bool {
+        match side {
+            OrderSideFixed::Buy => self.ask.map_or(false, |a| a <= trigger_price),
+            OrderSideFixed::Sell => self.bid.map_or(false, |b| b >= trigger_price),
         }
     }
 }
@@ -201,7 +219,9 @@ mod tests {
     use std::sync::Mutex;
 
     use nautilus_model::{
-        enums::OrderSide, orders::stubs::TestOrderStubs, types::quantity::Quantity,
+        enums::OrderSide,
+        orders::{base::order_side_to_fixed, stubs::TestOrderStubs},
+        types::quantity::Quantity,
     };
     use rstest::rstest;
 
@@ -479,6 +499,55 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[rstest]
+    #[case(None, None, Price::from("100.00"), OrderSide::Buy, false)]
+    #[case(None, None, Price::from("100.00"), OrderSide::Sell, false)]
+    #[case(
+        Some(Price::from("100.00")),
+        Some(Price::from("101.00")),
+        Price::from("100.00"),  // <-- Trigger below ask
+        OrderSide::Buy,
+        false
+    )]
+    #[case(
+        Some(Price::from("100.00")),
+        Some(Price::from("101.00")),
+        Price::from("101.00"),  // <-- Trigger at ask
+        OrderSide::Buy,
+        true
+    )]
+    #[case(
+        Some(Price::from("100.00")),
+        Some(Price::from("101.00")),
+        Price::from("101.00"),  // <-- Trigger above bid
+        OrderSide::Sell,
+        false
+    )]
+    #[case(
+        Some(Price::from("100.00")),
+        Some(Price::from("101.00")),
+        Price::from("99.00"),  // <-- Trigger below bid
+        OrderSide::Sell,
+        true
+    )]
+    fn test_is_touch_triggered(
+        #[case] bid: Option<Price>,
+        #[case] ask: Option<Price>,
+        #[case] trigger_price: Price,
+        #[case] order_side: OrderSide,
+        #[case] expected: bool,
+    ) {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut matching_core = create_matching_core(instrument_id, Price::from("0.01"));
+        matching_core.bid = bid;
+        matching_core.ask = ask;
+
+        let result =
+            matching_core.is_touch_triggered(order_side_to_fixed(order_side), trigger_price);
+
+        assert_eq!(result, expected);
+    }
+
     #[rstest]
     #[case(OrderSide::Buy)]
     #[case(OrderSide::Sell)]
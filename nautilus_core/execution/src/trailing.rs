@@ -0,0 +1,286 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use anyhow::{anyhow, bail};
+use nautilus_model::{
+    enums::{OrderSide, OrderType, TrailingOffsetType, TriggerType},
+    orders::any::OrderAny,
+    types::price::Price,
+};
+
+/// Calculates the new trigger price (and limit price for trailing stop limit orders)
+/// for the given trailing stop `order`, based on the latest market prices.
+///
+/// Returns a tuple of `(new_trigger_price, new_price)` where a value will only be
+/// `Some` if the respective price should be moved.
+pub fn trailing_stop_calculate(
+    price_increment: Price,
+    order: &OrderAny,
+    bid: Option<Price>,
+    ask: Option<Price>,
+    last: Option<Price>,
+) -> anyhow::Result<(Option<Price>, Option<Price>)> {
+    let order_type = order.order_type();
+    if !matches!(
+        order_type,
+        OrderType::TrailingStopMarket | OrderType::TrailingStopLimit
+    ) {
+        bail!("Invalid `OrderType` for calculation, was {order_type}");
+    }
+
+    let side = order.order_side();
+    let offset_type = order
+        .trailing_offset_type()
+        .ok_or_else(|| anyhow!("No `trailing_offset_type` for {}", order.client_order_id()))?;
+    let trailing_offset = order
+        .trailing_offset()
+        .ok_or_else(|| anyhow!("No `trailing_offset` for {}", order.client_order_id()))?
+        .as_f64();
+    let limit_offset = if order_type == OrderType::TrailingStopLimit {
+        order.limit_offset().map(|offset| offset.as_f64())
+    } else {
+        None
+    };
+
+    let mut trigger_price = order.trigger_price();
+    let mut price = order.price();
+    let mut new_trigger_price = None;
+    let mut new_price = None;
+
+    let trigger_type = order.trigger_type().unwrap_or(TriggerType::Default);
+    let use_last = matches!(
+        trigger_type,
+        TriggerType::Default
+            | TriggerType::LastTrade
+            | TriggerType::MarkPrice
+            | TriggerType::LastOrBidAsk
+    );
+    let use_bid_ask = matches!(
+        trigger_type,
+        TriggerType::BidAsk | TriggerType::LastOrBidAsk
+    );
+    if !use_last && !use_bid_ask {
+        bail!("Cannot process trailing stop, `TriggerType.{trigger_type}` not currently supported");
+    }
+
+    if use_last {
+        let last = last.ok_or_else(|| {
+            anyhow!(
+                "Cannot process trailing stop, no LAST price for {} (add trade ticks or use bars)",
+                order.instrument_id()
+            )
+        })?;
+        let temp_trigger_price = trailing_stop_calculate_with_last(
+            price_increment,
+            offset_type,
+            side,
+            trailing_offset,
+            last,
+        )?;
+        if is_trailed(side, trigger_price, temp_trigger_price) {
+            new_trigger_price = Some(temp_trigger_price);
+            trigger_price = new_trigger_price;
+        }
+        if let Some(limit_offset) = limit_offset {
+            let temp_price = trailing_stop_calculate_with_last(
+                price_increment,
+                offset_type,
+                side,
+                limit_offset,
+                last,
+            )?;
+            if is_trailed(side, price, temp_price) {
+                new_price = Some(temp_price);
+                price = new_price;
+            }
+        }
+    }
+
+    if use_bid_ask {
+        let bid = bid.ok_or_else(|| {
+            anyhow!(
+                "Cannot process trailing stop, no BID price for {} (add quote ticks or use bars)",
+                order.instrument_id()
+            )
+        })?;
+        let ask = ask.ok_or_else(|| {
+            anyhow!(
+                "Cannot process trailing stop, no ASK price for {} (add quote ticks or use bars)",
+                order.instrument_id()
+            )
+        })?;
+        let temp_trigger_price = trailing_stop_calculate_with_bid_ask(
+            price_increment,
+            offset_type,
+            side,
+            trailing_offset,
+            bid,
+            ask,
+        )?;
+        if is_trailed(side, trigger_price, temp_trigger_price) {
+            new_trigger_price = Some(temp_trigger_price);
+        }
+        if let Some(limit_offset) = limit_offset {
+            let temp_price = trailing_stop_calculate_with_bid_ask(
+                price_increment,
+                offset_type,
+                side,
+                limit_offset,
+                bid,
+                ask,
+            )?;
+            if is_trailed(side, price, temp_price) {
+                new_price = Some(temp_price);
+            }
+        }
+    }
+
+    Ok((new_trigger_price, new_price))
+}
+
+/// Calculates a trailing price for the `side` which is `offset` away from the `last` price.
+pub fn trailing_stop_calculate_with_last(
+    price_increment: Price,
+    trailing_offset_type: TrailingOffsetType,
+    side: OrderSide,
+    offset: f64,
+    last: Price,
+) -> anyhow::Result<Price> {
+    let last_f64 = last.as_f64();
+    let offset = match trailing_offset_type {
+        TrailingOffsetType::Price => offset,
+        TrailingOffsetType::BasisPoints => last_f64 * (offset / 100.0) / 100.0,
+        TrailingOffsetType::Ticks => offset * price_increment.as_f64(),
+        _ => bail!(
+            "Cannot process trailing stop, `TrailingOffsetType` {trailing_offset_type} not currently supported"
+        ),
+    };
+
+    match side {
+        OrderSide::Buy => Price::new(last_f64 + offset, price_increment.precision),
+        OrderSide::Sell => Price::new(last_f64 - offset, price_increment.precision),
+        _ => bail!("Invalid `OrderSide`, was {side}"),
+    }
+}
+
+/// Calculates a trailing price for the `side` which is `offset` away from the `bid` or `ask` price.
+pub fn trailing_stop_calculate_with_bid_ask(
+    price_increment: Price,
+    trailing_offset_type: TrailingOffsetType,
+    side: OrderSide,
+    offset: f64,
+    bid: Price,
+    ask: Price,
+) -> anyhow::Result<Price> {
+    let ask_f64 = ask.as_f64();
+    let bid_f64 = bid.as_f64();
+    let offset = match trailing_offset_type {
+        TrailingOffsetType::Price => offset,
+        TrailingOffsetType::BasisPoints => match side {
+            OrderSide::Buy => ask_f64 * (offset / 100.0) / 100.0,
+            OrderSide::Sell => bid_f64 * (offset / 100.0) / 100.0,
+            _ => bail!("Invalid `OrderSide`, was {side}"),
+        },
+        TrailingOffsetType::Ticks => offset * price_increment.as_f64(),
+        _ => bail!(
+            "Cannot process trailing stop, `TrailingOffsetType` {trailing_offset_type} not currently supported"
+        ),
+    };
+
+    match side {
+        OrderSide::Buy => Price::new(ask_f64 + offset, price_increment.precision),
+        OrderSide::Sell => Price::new(bid_f64 - offset, price_increment.precision),
+        _ => bail!("Invalid `OrderSide`, was {side}"),
+    }
+}
+
+/// Returns whether the `candidate` price trails the `current` price closer to the market.
+fn is_trailed(side: OrderSide, current: Option<Price>, candidate: Price) -> bool {
+    match current {
+        None => true,
+        Some(current) => match side {
+            OrderSide::Buy => current > candidate,
+            OrderSide::Sell => current < candidate,
+            _ => false,
+        },
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(TrailingOffsetType::Price, OrderSide::Buy, 1.0, "101.00")]
+    #[case(TrailingOffsetType::Price, OrderSide::Sell, 1.0, "99.00")]
+    #[case(TrailingOffsetType::BasisPoints, OrderSide::Buy, 50.0, "100.50")]
+    #[case(TrailingOffsetType::BasisPoints, OrderSide::Sell, 50.0, "99.50")]
+    #[case(TrailingOffsetType::Ticks, OrderSide::Buy, 5.0, "100.05")]
+    #[case(TrailingOffsetType::Ticks, OrderSide::Sell, 5.0, "99.95")]
+    fn test_calculate_with_last(
+        #[case] offset_type: TrailingOffsetType,
+        #[case] side: OrderSide,
+        #[case] offset: f64,
+        #[case] expected: &str,
+    ) {
+        let result = trailing_stop_calculate_with_last(
+            Price::from("0.01"),
+            offset_type,
+            side,
+            offset,
+            Price::from("100.00"),
+        )
+        .unwrap();
+
+        assert_eq!(result, Price::from(expected));
+    }
+
+    #[rstest]
+    #[case(OrderSide::Buy, "101.01")]
+    #[case(OrderSide::Sell, "98.99")]
+    fn test_calculate_with_bid_ask(#[case] side: OrderSide, #[case] expected: &str) {
+        let result = trailing_stop_calculate_with_bid_ask(
+            Price::from("0.01"),
+            TrailingOffsetType::Price,
+            side,
+            1.0,
+            Price::from("99.99"),
+            Price::from("100.01"),
+        )
+        .unwrap();
+
+        assert_eq!(result, Price::from(expected));
+    }
+
+    #[rstest]
+    fn test_is_trailed() {
+        assert!(is_trailed(OrderSide::Buy, None, Price::from("1.00")));
+        assert!(is_trailed(
+            OrderSide::Buy,
+            Some(Price::from("1.10")),
+            Price::from("1.00")
+        ));
+        assert!(!is_trailed(
+            OrderSide::Sell,
+            Some(Price::from("1.10")),
+            Price::from("1.00")
+        ));
+    }
+}
@@ -22,7 +22,8 @@ use std::{
 
 use nautilus_common::{
     cache::{CacheDatabase, DatabaseCommand, DatabaseOperation},
-    redis::{create_redis_connection, get_buffer_interval},
+    msgbus::get_buffer_interval,
+    redis::create_redis_connection,
 };
 use nautilus_core::uuid::UUID4;
 use nautilus_model::identifiers::trader_id::TraderId;
@@ -76,8 +77,6 @@ pub struct RedisCacheDatabase {
 }
 
 impl CacheDatabase for RedisCacheDatabase {
-    type DatabaseType = RedisCacheDatabase;
-
     fn new(
         trader_id: TraderId,
         instance_id: UUID4,
@@ -326,6 +326,40 @@ impl FromU8 for BookAction {
     }
 }
 
+/// The policy for handling sequence gaps in the deltas applied to an order book.
+#[repr(C)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Display,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    AsRefStr,
+    FromRepr,
+    EnumIter,
+    EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.model.enums")
+)]
+pub enum BookGapPolicy {
+    /// Delta sequence numbers are not checked.
+    #[default]
+    Ignore = 1,
+    /// Skipped or regressing delta sequence numbers put the book into a stale state,
+    /// buffering deltas until a snapshot is received (a `Clear` delta followed by adds,
+    /// or an `OrderBookDepth10`). The buffered deltas are then replayed on top of the snapshot.
+    Resync = 2,
+}
+
 /// The order book type, representing the type of levels granularity and delta updating heuristics.
 #[repr(C)]
 #[derive(
@@ -501,6 +535,38 @@ pub enum LiquiditySide {
     Taker = 2,
 }
 
+/// The policy for matching a reducing fill against the open lots of a position.
+#[repr(C)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Display,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    AsRefStr,
+    FromRepr,
+    EnumIter,
+    EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum LotMatchingPolicy {
+    /// The earliest opened lots are closed first (first-in, first-out).
+    #[default]
+    Fifo = 1,
+    /// The latest opened lots are closed first (last-in, first-out).
+    Lifo = 2,
+    /// The lots with the least favorable open price are closed first (highest price for long lots, lowest price for short lots).
+    HighestCost = 3,
+    /// The lots designated by opening trade ID for the reducing fill are closed, in order (a reducing fill without designated lots covering its quantity is rejected).
+    SpecificId = 4,
+}
+
 /// The status of an individual market on a trading venue.
 #[repr(C)]
 #[derive(
@@ -868,6 +934,47 @@ pub enum PriceType {
     Last = 4,
 }
 
+/// A record flag bit field, indicating packet end and data information.
+///
+/// Flags are combined as bits in the `flags` field of order book data.
+#[repr(C)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Display,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    AsRefStr,
+    FromRepr,
+    EnumIter,
+    EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[allow(non_camel_case_types)]
+pub enum RecordFlag {
+    /// Last message in the packet from the venue for a given `instrument_id`.
+    F_LAST = 1 << 7, // 128
+    /// Top-of-book message, not an individual order.
+    F_TOB = 1 << 6, // 64
+    /// Message sourced from a replay, such as a snapshot server.
+    F_SNAPSHOT = 1 << 5, // 32
+    /// Aggregated price level message, not an individual order.
+    F_MBP = 1 << 4, // 16
+}
+
+impl RecordFlag {
+    /// Returns whether this flag is set in the given `value` bit field.
+    #[must_use]
+    pub fn matches(self, value: u8) -> bool {
+        (self as u8) & value != 0
+    }
+}
+
 /// The 'Time in Force' instruction for an order in the financial market.
 #[repr(C)]
 #[derive(
@@ -1029,11 +1136,13 @@ enum_strum_serde!(AssetClass);
 enum_strum_serde!(InstrumentClass);
 enum_strum_serde!(BarAggregation);
 enum_strum_serde!(BookAction);
+enum_strum_serde!(BookGapPolicy);
 enum_strum_serde!(BookType);
 enum_strum_serde!(ContingencyType);
 enum_strum_serde!(CurrencyType);
 enum_strum_serde!(InstrumentCloseType);
 enum_strum_serde!(LiquiditySide);
+enum_strum_serde!(LotMatchingPolicy);
 enum_strum_serde!(MarketStatus);
 enum_strum_serde!(OmsType);
 enum_strum_serde!(OptionKind);
@@ -1042,6 +1151,7 @@ enum_strum_serde!(OrderStatus);
 enum_strum_serde!(OrderType);
 enum_strum_serde!(PositionSide);
 enum_strum_serde!(PriceType);
+enum_strum_serde!(RecordFlag);
 enum_strum_serde!(TimeInForce);
 enum_strum_serde!(TradingState);
 enum_strum_serde!(TrailingOffsetType);
@@ -26,7 +26,10 @@ use crate::{
         rejected::OrderRejected, released::OrderReleased, submitted::OrderSubmitted,
         triggered::OrderTriggered, updated::OrderUpdated,
     },
-    identifiers::{client_order_id::ClientOrderId, strategy_id::StrategyId},
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
+        strategy_id::StrategyId,
+    },
 };
 
 #[derive(Clone, PartialEq, Eq, Display, Debug, Serialize, Deserialize)]
@@ -97,6 +100,53 @@ impl OrderEvent {
         }
     }
 
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        match self {
+            Self::OrderInitialized(e) => e.instrument_id,
+            Self::OrderDenied(e) => e.instrument_id,
+            Self::OrderEmulated(e) => e.instrument_id,
+            Self::OrderReleased(e) => e.instrument_id,
+            Self::OrderSubmitted(e) => e.instrument_id,
+            Self::OrderAccepted(e) => e.instrument_id,
+            Self::OrderRejected(e) => e.instrument_id,
+            Self::OrderCanceled(e) => e.instrument_id,
+            Self::OrderExpired(e) => e.instrument_id,
+            Self::OrderTriggered(e) => e.instrument_id,
+            Self::OrderPendingUpdate(e) => e.instrument_id,
+            Self::OrderPendingCancel(e) => e.instrument_id,
+            Self::OrderModifyRejected(e) => e.instrument_id,
+            Self::OrderCancelRejected(e) => e.instrument_id,
+            Self::OrderUpdated(e) => e.instrument_id,
+            Self::OrderPartiallyFilled(e) => e.instrument_id,
+            Self::OrderFilled(e) => e.instrument_id,
+        }
+    }
+
+    /// Returns the account ID for the event (if assigned).
+    #[must_use]
+    pub fn account_id(&self) -> Option<AccountId> {
+        match self {
+            Self::OrderInitialized(_) => None,
+            Self::OrderDenied(_) => None,
+            Self::OrderEmulated(_) => None,
+            Self::OrderReleased(_) => None,
+            Self::OrderSubmitted(e) => Some(e.account_id),
+            Self::OrderAccepted(e) => Some(e.account_id),
+            Self::OrderRejected(e) => Some(e.account_id),
+            Self::OrderCanceled(e) => e.account_id,
+            Self::OrderExpired(e) => e.account_id,
+            Self::OrderTriggered(e) => e.account_id,
+            Self::OrderPendingUpdate(e) => Some(e.account_id),
+            Self::OrderPendingCancel(e) => Some(e.account_id),
+            Self::OrderModifyRejected(e) => e.account_id,
+            Self::OrderCancelRejected(e) => e.account_id,
+            Self::OrderUpdated(e) => e.account_id,
+            Self::OrderPartiallyFilled(e) => Some(e.account_id),
+            Self::OrderFilled(e) => Some(e.account_id),
+        }
+    }
+
     #[must_use]
     pub fn ts_event(&self) -> UnixNanos {
         match self {
@@ -13,8 +13,11 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-use crate::events::position::{
-    changed::PositionChanged, closed::PositionClosed, opened::PositionOpened,
+use nautilus_core::time::UnixNanos;
+
+use crate::{
+    events::position::{changed::PositionChanged, closed::PositionClosed, opened::PositionOpened},
+    identifiers::{account_id::AccountId, instrument_id::InstrumentId, position_id::PositionId},
 };
 
 pub mod changed;
@@ -23,8 +26,47 @@ pub mod opened;
 
 pub mod state;
 
+#[derive(Clone, PartialEq, Debug)]
 pub enum PositionEvent {
     PositionOpened(PositionOpened),
     PositionChanged(PositionChanged),
     PositionClosed(PositionClosed),
 }
+
+impl PositionEvent {
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        match self {
+            Self::PositionOpened(e) => e.instrument_id,
+            Self::PositionChanged(e) => e.instrument_id,
+            Self::PositionClosed(e) => e.instrument_id,
+        }
+    }
+
+    #[must_use]
+    pub fn position_id(&self) -> PositionId {
+        match self {
+            Self::PositionOpened(e) => e.position_id,
+            Self::PositionChanged(e) => e.position_id,
+            Self::PositionClosed(e) => e.position_id,
+        }
+    }
+
+    #[must_use]
+    pub fn account_id(&self) -> AccountId {
+        match self {
+            Self::PositionOpened(e) => e.account_id,
+            Self::PositionChanged(e) => e.account_id,
+            Self::PositionClosed(e) => e.account_id,
+        }
+    }
+
+    #[must_use]
+    pub fn ts_event(&self) -> UnixNanos {
+        match self {
+            Self::PositionOpened(e) => e.ts_event,
+            Self::PositionChanged(e) => e.ts_event,
+            Self::PositionClosed(e) => e.ts_event,
+        }
+    }
+}
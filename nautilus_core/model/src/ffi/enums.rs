@@ -19,9 +19,9 @@ use nautilus_core::ffi::string::{cstr_to_str, str_to_cstr};
 
 use crate::enums::{
     AccountType, AggregationSource, AggressorSide, AssetClass, BarAggregation, BookAction,
-    BookType, ContingencyType, CurrencyType, HaltReason, InstrumentClass, InstrumentCloseType,
-    LiquiditySide, MarketStatus, OmsType, OptionKind, OrderSide, OrderStatus, OrderType,
-    PositionSide, PriceType, TimeInForce, TradingState, TrailingOffsetType, TriggerType,
+    BookGapPolicy, BookType, ContingencyType, CurrencyType, HaltReason, InstrumentClass,
+    InstrumentCloseType, LiquiditySide, MarketStatus, OmsType, OptionKind, OrderSide, OrderStatus,
+    OrderType, PositionSide, PriceType, TimeInForce, TradingState, TrailingOffsetType, TriggerType,
 };
 
 #[no_mangle]
@@ -143,6 +143,23 @@ pub unsafe extern "C" fn book_action_from_cstr(ptr: *const c_char) -> BookAction
         .unwrap_or_else(|_| panic!("invalid `BookAction` enum string value, was '{value}'"))
 }
 
+#[no_mangle]
+pub extern "C" fn book_gap_policy_to_cstr(value: BookGapPolicy) -> *const c_char {
+    str_to_cstr(value.as_ref())
+}
+
+/// Returns an enum from a Python string.
+///
+/// # Safety
+///
+/// - Assumes `ptr` is a valid C string pointer.
+#[no_mangle]
+pub unsafe extern "C" fn book_gap_policy_from_cstr(ptr: *const c_char) -> BookGapPolicy {
+    let value = cstr_to_str(ptr);
+    BookGapPolicy::from_str(value)
+        .unwrap_or_else(|_| panic!("invalid `BookGapPolicy` enum string value, was '{value}'"))
+}
+
 #[no_mangle]
 pub extern "C" fn book_type_to_cstr(value: BookType) -> *const c_char {
     str_to_cstr(value.as_ref())
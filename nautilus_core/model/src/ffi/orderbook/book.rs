@@ -20,14 +20,15 @@ use std::{
 
 use nautilus_core::ffi::{cvec::CVec, string::str_to_cstr};
 
-use super::{container::OrderBookContainer, level::Level_API};
+use super::level::Level_API;
 use crate::{
     data::{
         delta::OrderBookDelta, deltas::OrderBookDeltas_API, depth::OrderBookDepth10,
         order::BookOrder, quote::QuoteTick, trade::TradeTick,
     },
-    enums::{BookType, OrderSide},
+    enums::{BookGapPolicy, BookType, OrderSide},
     identifiers::instrument_id::InstrumentId,
+    orderbook::book::OrderBook,
     types::{price::Price, quantity::Quantity},
 };
 
@@ -41,10 +42,10 @@ use crate::{
 /// having to manually access the underlying `OrderBook` instance.
 #[repr(C)]
 #[allow(non_camel_case_types)]
-pub struct OrderBook_API(Box<OrderBookContainer>);
+pub struct OrderBook_API(Box<OrderBook>);
 
 impl Deref for OrderBook_API {
-    type Target = OrderBookContainer;
+    type Target = OrderBook;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -59,7 +60,7 @@ impl DerefMut for OrderBook_API {
 
 #[no_mangle]
 pub extern "C" fn orderbook_new(instrument_id: InstrumentId, book_type: BookType) -> OrderBook_API {
-    OrderBook_API(Box::new(OrderBookContainer::new(instrument_id, book_type)))
+    OrderBook_API(Box::new(OrderBook::new(instrument_id, book_type)))
 }
 
 #[no_mangle]
@@ -84,17 +85,32 @@ pub extern "C" fn orderbook_book_type(book: &OrderBook_API) -> BookType {
 
 #[no_mangle]
 pub extern "C" fn orderbook_sequence(book: &OrderBook_API) -> u64 {
-    book.sequence()
+    book.sequence
 }
 
 #[no_mangle]
 pub extern "C" fn orderbook_ts_last(book: &OrderBook_API) -> u64 {
-    book.ts_last()
+    book.ts_last
 }
 
 #[no_mangle]
 pub extern "C" fn orderbook_count(book: &OrderBook_API) -> u64 {
-    book.count()
+    book.count
+}
+
+#[no_mangle]
+pub extern "C" fn orderbook_gap_policy(book: &OrderBook_API) -> BookGapPolicy {
+    book.gap_policy
+}
+
+#[no_mangle]
+pub extern "C" fn orderbook_set_gap_policy(book: &mut OrderBook_API, gap_policy: BookGapPolicy) {
+    book.gap_policy = gap_policy;
+}
+
+#[no_mangle]
+pub extern "C" fn orderbook_is_stale(book: &OrderBook_API) -> u8 {
+    u8::from(book.is_stale())
 }
 
 #[no_mangle]
@@ -144,25 +160,25 @@ pub extern "C" fn orderbook_clear_asks(book: &mut OrderBook_API, ts_event: u64,
 
 #[no_mangle]
 pub extern "C" fn orderbook_apply_delta(book: &mut OrderBook_API, delta: OrderBookDelta) {
-    book.apply_delta(delta);
+    // Sequence gaps put a book into a stale state (see `orderbook_is_stale`)
+    let _ = book.apply_delta(delta);
 }
 
 #[no_mangle]
 pub extern "C" fn orderbook_apply_deltas(book: &mut OrderBook_API, deltas: &OrderBookDeltas_API) {
     // Clone will actually copy the contents of the `deltas` vec
-    book.apply_deltas(deltas.deref().clone());
+    let _ = book.apply_deltas(deltas.deref().clone());
 }
 
 #[no_mangle]
 pub extern "C" fn orderbook_apply_depth(book: &mut OrderBook_API, depth: OrderBookDepth10) {
-    book.apply_depth(depth);
+    let _ = book.apply_depth(depth);
 }
 
 #[no_mangle]
 pub extern "C" fn orderbook_bids(book: &mut OrderBook_API) -> CVec {
     book.bids()
-        .iter()
-        .map(|l| Level_API::new(l.to_owned().clone()))
+        .map(|l| Level_API::new(l.clone()))
         .collect::<Vec<Level_API>>()
         .into()
 }
@@ -170,8 +186,7 @@ pub extern "C" fn orderbook_bids(book: &mut OrderBook_API) -> CVec {
 #[no_mangle]
 pub extern "C" fn orderbook_asks(book: &mut OrderBook_API) -> CVec {
     book.asks()
-        .iter()
-        .map(|l| Level_API::new(l.to_owned().clone()))
+        .map(|l| Level_API::new(l.clone()))
         .collect::<Vec<Level_API>>()
         .into()
 }
@@ -14,5 +14,4 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod book;
-pub mod container;
 pub mod level;
@@ -48,6 +48,13 @@ impl AccountId {
             value: Ustr::from(value),
         })
     }
+
+    /// Returns the account issuer (the part of the value before the first hyphen).
+    #[must_use]
+    pub fn get_issuer(&self) -> Ustr {
+        // SAFETY: Account ID is guaranteed to contain a hyphen on construction
+        Ustr::from(self.value.split_once('-').unwrap().0)
+    }
 }
 
 impl Default for AccountId {
@@ -112,4 +119,9 @@ mod tests {
     fn test_string_reprs(account_ib: AccountId) {
         assert_eq!(account_ib.to_string(), "IB-1234567890");
     }
+
+    #[rstest]
+    fn test_get_issuer(account_ib: AccountId) {
+        assert_eq!(account_ib.get_issuer(), Ustr::from("IB"));
+    }
 }
@@ -227,6 +227,14 @@ impl Instrument for FuturesContract {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn margin_init(&self) -> Decimal {
+        self.margin_init
+    }
+
+    fn margin_maint(&self) -> Decimal {
+        self.margin_maint
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
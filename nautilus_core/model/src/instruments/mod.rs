@@ -30,6 +30,7 @@ pub mod stubs;
 use nautilus_core::time::UnixNanos;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
 
 use self::{
     crypto_future::CryptoFuture, crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair,
@@ -42,7 +43,7 @@ use crate::{
     types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum InstrumentType {
     CryptoFuture(CryptoFuture),
     CryptoPerpetual(CryptoPerpetual),
@@ -54,6 +55,166 @@ pub enum InstrumentType {
     OptionsSpread(OptionsSpread),
 }
 
+impl InstrumentType {
+    /// Returns the concrete instrument type for the given `instrument` (if supported).
+    #[must_use]
+    #[allow(clippy::clone_on_copy)] // Instruments are only `Copy` with `trivial_copy`
+    pub fn from_instrument(instrument: &dyn Instrument) -> Option<Self> {
+        let any = instrument.as_any();
+        if let Some(inst) = any.downcast_ref::<CryptoFuture>() {
+            Some(Self::CryptoFuture(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<CryptoPerpetual>() {
+            Some(Self::CryptoPerpetual(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<CurrencyPair>() {
+            Some(Self::CurrencyPair(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<Equity>() {
+            Some(Self::Equity(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<FuturesContract>() {
+            Some(Self::FuturesContract(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<FuturesSpread>() {
+            Some(Self::FuturesSpread(inst.clone()))
+        } else if let Some(inst) = any.downcast_ref::<OptionsContract>() {
+            Some(Self::OptionsContract(inst.clone()))
+        } else {
+            any.downcast_ref::<OptionsSpread>()
+                .map(|inst| Self::OptionsSpread(inst.clone()))
+        }
+    }
+
+    /// Returns the wrapped instrument as a boxed [`Instrument`].
+    #[must_use]
+    pub fn into_boxed(self) -> Box<dyn Instrument> {
+        match self {
+            Self::CryptoFuture(inst) => Box::new(inst),
+            Self::CryptoPerpetual(inst) => Box::new(inst),
+            Self::CurrencyPair(inst) => Box::new(inst),
+            Self::Equity(inst) => Box::new(inst),
+            Self::FuturesContract(inst) => Box::new(inst),
+            Self::FuturesSpread(inst) => Box::new(inst),
+            Self::OptionsContract(inst) => Box::new(inst),
+            Self::OptionsSpread(inst) => Box::new(inst),
+        }
+    }
+}
+
+/// Delegates the expression to the wrapped instrument for each [`InstrumentType`] variant.
+macro_rules! delegate {
+    ($self:ident, $inst:ident => $e:expr) => {
+        match $self {
+            InstrumentType::CryptoFuture($inst) => $e,
+            InstrumentType::CryptoPerpetual($inst) => $e,
+            InstrumentType::CurrencyPair($inst) => $e,
+            InstrumentType::Equity($inst) => $e,
+            InstrumentType::FuturesContract($inst) => $e,
+            InstrumentType::FuturesSpread($inst) => $e,
+            InstrumentType::OptionsContract($inst) => $e,
+            InstrumentType::OptionsSpread($inst) => $e,
+        }
+    };
+}
+
+impl Instrument for InstrumentType {
+    fn id(&self) -> InstrumentId {
+        delegate!(self, inst => inst.id())
+    }
+
+    fn raw_symbol(&self) -> Symbol {
+        delegate!(self, inst => inst.raw_symbol())
+    }
+
+    fn asset_class(&self) -> AssetClass {
+        delegate!(self, inst => inst.asset_class())
+    }
+
+    fn instrument_class(&self) -> InstrumentClass {
+        delegate!(self, inst => inst.instrument_class())
+    }
+
+    fn base_currency(&self) -> Option<Currency> {
+        delegate!(self, inst => inst.base_currency())
+    }
+
+    fn quote_currency(&self) -> Currency {
+        delegate!(self, inst => inst.quote_currency())
+    }
+
+    fn settlement_currency(&self) -> Currency {
+        delegate!(self, inst => inst.settlement_currency())
+    }
+
+    fn is_inverse(&self) -> bool {
+        delegate!(self, inst => inst.is_inverse())
+    }
+
+    fn price_precision(&self) -> u8 {
+        delegate!(self, inst => inst.price_precision())
+    }
+
+    fn size_precision(&self) -> u8 {
+        delegate!(self, inst => inst.size_precision())
+    }
+
+    fn price_increment(&self) -> Price {
+        delegate!(self, inst => inst.price_increment())
+    }
+
+    fn size_increment(&self) -> Quantity {
+        delegate!(self, inst => inst.size_increment())
+    }
+
+    fn multiplier(&self) -> Quantity {
+        delegate!(self, inst => inst.multiplier())
+    }
+
+    fn lot_size(&self) -> Option<Quantity> {
+        delegate!(self, inst => inst.lot_size())
+    }
+
+    fn max_quantity(&self) -> Option<Quantity> {
+        delegate!(self, inst => inst.max_quantity())
+    }
+
+    fn min_quantity(&self) -> Option<Quantity> {
+        delegate!(self, inst => inst.min_quantity())
+    }
+
+    fn max_price(&self) -> Option<Price> {
+        delegate!(self, inst => inst.max_price())
+    }
+
+    fn min_price(&self) -> Option<Price> {
+        delegate!(self, inst => inst.min_price())
+    }
+
+    fn margin_init(&self) -> Decimal {
+        delegate!(self, inst => inst.margin_init())
+    }
+
+    fn margin_maint(&self) -> Decimal {
+        delegate!(self, inst => inst.margin_maint())
+    }
+
+    fn maker_fee(&self) -> Decimal {
+        delegate!(self, inst => inst.maker_fee())
+    }
+
+    fn taker_fee(&self) -> Decimal {
+        delegate!(self, inst => inst.taker_fee())
+    }
+
+    fn ts_event(&self) -> UnixNanos {
+        delegate!(self, inst => inst.ts_event())
+    }
+
+    fn ts_init(&self) -> UnixNanos {
+        delegate!(self, inst => inst.ts_init())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        delegate!(self, inst => inst.as_any())
+    }
+}
+
 pub trait Instrument: Any + 'static + Send {
     fn id(&self) -> InstrumentId;
     fn symbol(&self) -> Symbol {
@@ -20,6 +20,7 @@ use std::{
 
 use evalexpr::{ContextWithMutableVariables, HashMapContext, Node, Value};
 use nautilus_core::time::UnixNanos;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
     identifiers::{instrument_id::InstrumentId, symbol::Symbol, venue::Venue},
@@ -147,6 +148,53 @@ impl Hash for SyntheticInstrument {
     }
 }
 
+/// The serializable definition of a synthetic instrument, from which the evaluation
+/// context and operator tree are rebuilt.
+#[derive(Serialize, Deserialize)]
+struct SyntheticInstrumentDef {
+    symbol: Symbol,
+    price_precision: u8,
+    components: Vec<InstrumentId>,
+    formula: String,
+    ts_event: UnixNanos,
+    ts_init: UnixNanos,
+}
+
+impl Serialize for SyntheticInstrument {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SyntheticInstrumentDef {
+            symbol: self.id.symbol,
+            price_precision: self.price_precision,
+            components: self.components.clone(),
+            formula: self.formula.clone(),
+            ts_event: self.ts_event,
+            ts_init: self.ts_init,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SyntheticInstrument {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let def = SyntheticInstrumentDef::deserialize(deserializer)?;
+        Self::new(
+            def.symbol,
+            def.price_precision,
+            def.components,
+            def.formula,
+            def.ts_event,
+            def.ts_init,
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -228,4 +276,32 @@ mod tests {
         assert_eq!(price.as_f64(), 75.0);
         assert_eq!(synth.formula, new_formula);
     }
+
+    #[rstest]
+    fn test_serde_json_round_trip() {
+        let synth = SyntheticInstrument::new(
+            Symbol::new("BTC-LTC").unwrap(),
+            2,
+            vec![
+                InstrumentId::from("BTC.BINANCE"),
+                InstrumentId::from("LTC.BINANCE"),
+            ],
+            "(BTC.BINANCE + LTC.BINANCE) / 2.0".to_string(),
+            1,
+            2,
+        )
+        .unwrap();
+
+        let json = serde_json::to_vec(&synth).unwrap();
+        let mut deserialized: SyntheticInstrument = serde_json::from_slice(&json).unwrap();
+
+        assert_eq!(deserialized, synth);
+        assert_eq!(deserialized.formula, synth.formula);
+        assert_eq!(deserialized.components, synth.components);
+        assert_eq!(deserialized.ts_init, 2);
+        assert_eq!(
+            deserialized.calculate(&[100.0, 200.0]).unwrap().as_f64(),
+            150.0
+        );
+    }
 }
@@ -13,13 +13,23 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
+use nautilus_core::time::UnixNanos;
 use thiserror::Error;
 
-use super::{ladder::BookPrice, level::Level};
+use super::{
+    display::pprint_book,
+    ladder::{BookPrice, Ladder},
+    level::Level,
+};
 use crate::{
-    enums::{BookType, OrderSide},
+    data::{
+        delta::OrderBookDelta, deltas::OrderBookDeltas, depth::OrderBookDepth10, order::BookOrder,
+        quote::QuoteTick, trade::TradeTick,
+    },
+    enums::{BookAction, BookGapPolicy, BookType, OrderSide, RecordFlag},
+    identifiers::instrument_id::InstrumentId,
     types::{price::Price, quantity::Quantity},
 };
 
@@ -29,6 +39,8 @@ pub enum InvalidBookOperation {
     PreProcessOrder(BookType),
     #[error("Invalid book operation: cannot add order for {0} book")]
     Add(BookType),
+    #[error("Invalid book operation: cannot update with tick for {0} book")]
+    Update(BookType),
 }
 
 #[derive(Error, Debug)]
@@ -43,6 +55,591 @@ pub enum BookIntegrityError {
     TooManyOrders(OrderSide, usize),
     #[error("Integrity error: number of {0} levels > 1 for L1_MBP book, was {1}")]
     TooManyLevels(OrderSide, usize),
+    #[error("Integrity error: sequence gap, last={0}, received={1}")]
+    SequenceGap(u64, u64),
+    #[error("Integrity error: sequence regressed, last={0}, received={1}")]
+    SequenceRegression(u64, u64),
+}
+
+/// The maximum number of deltas buffered while a book is stale, beyond which the oldest
+/// deltas are dropped (a gap is then detected on replay if they were not in the snapshot).
+const BUFFERED_DELTAS_MAX: usize = 10_000;
+
+/// Provides an order book which can handle L1/L2/L3 granularity data.
+///
+/// The book type determines how orders are processed:
+/// - `L3_MBO` (market by order): every order is held individually.
+/// - `L2_MBP` (market by price): one aggregated order is held per price level.
+/// - `L1_MBP` (top of book): only the top most level of the bid and ask side is held.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.model")
+)]
+pub struct OrderBook {
+    /// The instrument ID for the order book.
+    pub instrument_id: InstrumentId,
+    /// The order book type (MBP types will aggregate orders).
+    pub book_type: BookType,
+    /// The last event sequence number for the order book.
+    pub sequence: u64,
+    /// The timestamp of the last event applied to the order book.
+    pub ts_last: UnixNanos,
+    /// The current count of events applied to the order book.
+    pub count: u64,
+    /// The policy for handling sequence gaps in applied deltas.
+    pub gap_policy: BookGapPolicy,
+    bids: Ladder,
+    asks: Ladder,
+    is_stale: bool,
+    is_resyncing: bool,
+    buffered_deltas: VecDeque<OrderBookDelta>,
+}
+
+impl OrderBook {
+    #[must_use]
+    pub fn new(instrument_id: InstrumentId, book_type: BookType) -> Self {
+        Self {
+            instrument_id,
+            book_type,
+            sequence: 0,
+            ts_last: 0,
+            count: 0,
+            gap_policy: BookGapPolicy::default(),
+            bids: Ladder::new(OrderSide::Buy),
+            asks: Ladder::new(OrderSide::Sell),
+            is_stale: false,
+            is_resyncing: false,
+            buffered_deltas: VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.sequence = 0;
+        self.ts_last = 0;
+        self.count = 0;
+        self.is_stale = false;
+        self.is_resyncing = false;
+        self.buffered_deltas.clear();
+    }
+
+    /// Returns whether the book is stale (awaiting a snapshot after a sequence gap).
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.is_stale
+    }
+
+    /// Adds the given `order` to the book.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the book type is `L1_MBP` (use `update` instead).
+    pub fn add(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        if self.book_type == BookType::L1_MBP {
+            panic!("{}", InvalidBookOperation::Add(self.book_type));
+        }
+        let order = self.pre_process_order(order);
+
+        match order.side {
+            OrderSide::Buy => self.bids.add(order),
+            OrderSide::Sell => self.asks.add(order),
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+
+        self.increment(ts_event, sequence);
+    }
+
+    pub fn update(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        if self.book_type == BookType::L1_MBP {
+            self.update_top(order, ts_event, sequence);
+        }
+        let order = self.pre_process_order(order);
+
+        match order.side {
+            OrderSide::Buy => self.bids.update(order),
+            OrderSide::Sell => self.asks.update(order),
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+
+        self.increment(ts_event, sequence);
+    }
+
+    /// Updates the top of the book from the given `quote`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the book type is `L3_MBO`.
+    pub fn update_quote_tick(&mut self, quote: &QuoteTick) {
+        if self.book_type == BookType::L3_MBO {
+            panic!("{}", InvalidBookOperation::Update(self.book_type));
+        }
+        self.update_bid(
+            BookOrder::from_quote_tick(quote, OrderSide::Buy),
+            quote.ts_event,
+            0,
+        );
+        self.update_ask(
+            BookOrder::from_quote_tick(quote, OrderSide::Sell),
+            quote.ts_event,
+            0,
+        );
+    }
+
+    /// Updates the top of the book from the given `trade`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the book type is `L3_MBO`.
+    pub fn update_trade_tick(&mut self, trade: &TradeTick) {
+        if self.book_type == BookType::L3_MBO {
+            panic!("{}", InvalidBookOperation::Update(self.book_type));
+        }
+        self.update_bid(
+            BookOrder::from_trade_tick(trade, OrderSide::Buy),
+            trade.ts_event,
+            0,
+        );
+        self.update_ask(
+            BookOrder::from_trade_tick(trade, OrderSide::Sell),
+            trade.ts_event,
+            0,
+        );
+    }
+
+    pub fn delete(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        let order = self.pre_process_order(order);
+
+        match order.side {
+            OrderSide::Buy => self.bids.delete(order, ts_event, sequence),
+            OrderSide::Sell => self.asks.delete(order, ts_event, sequence),
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+
+        self.increment(ts_event, sequence);
+    }
+
+    pub fn clear(&mut self, ts_event: u64, sequence: u64) {
+        self.bids.clear();
+        self.asks.clear();
+        self.increment(ts_event, sequence);
+    }
+
+    pub fn clear_bids(&mut self, ts_event: u64, sequence: u64) {
+        self.bids.clear();
+        self.increment(ts_event, sequence);
+    }
+
+    pub fn clear_asks(&mut self, ts_event: u64, sequence: u64) {
+        self.asks.clear();
+        self.increment(ts_event, sequence);
+    }
+
+    /// Applies the given `delta` to the book.
+    ///
+    /// With a `Resync` gap policy, a delta with a skipped or regressing sequence number
+    /// puts the book into a stale state, buffering deltas until a snapshot is received.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a sequence gap is detected.
+    pub fn apply_delta(&mut self, delta: OrderBookDelta) -> Result<(), BookIntegrityError> {
+        if self.gap_policy == BookGapPolicy::Resync {
+            if self.is_stale {
+                return self.apply_stale_delta(delta);
+            }
+
+            // A clear starts a new snapshot, so does not need to follow the last sequence
+            if delta.action != BookAction::Clear {
+                if let Err(e) = self.check_sequence(delta.sequence) {
+                    self.is_stale = true;
+                    self.buffer_delta(delta);
+                    return Err(e);
+                }
+            }
+        }
+
+        self.apply_delta_unchecked(delta);
+        Ok(())
+    }
+
+    /// Applies the given `deltas` to the book (see [`OrderBook::apply_delta`]).
+    ///
+    /// # Errors
+    ///
+    /// This function returns the first error if any sequence gap is detected.
+    pub fn apply_deltas(&mut self, deltas: OrderBookDeltas) -> Result<(), BookIntegrityError> {
+        let mut result = Ok(());
+        for delta in deltas.deltas {
+            if let Err(e) = self.apply_delta(delta) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Applies the given `depth` snapshot to the book.
+    ///
+    /// If the book is stale then the buffered deltas are replayed on top of the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a sequence gap is detected when replaying buffered deltas.
+    pub fn apply_depth(&mut self, depth: OrderBookDepth10) -> Result<(), BookIntegrityError> {
+        self.bids.clear();
+        self.asks.clear();
+
+        if self.book_type == BookType::L1_MBP {
+            self.update(depth.bids[0], depth.ts_event, depth.sequence);
+            self.update(depth.asks[0], depth.ts_event, depth.sequence);
+        } else {
+            for order in depth.bids {
+                self.add(order, depth.ts_event, depth.sequence);
+            }
+
+            for order in depth.asks {
+                self.add(order, depth.ts_event, depth.sequence);
+            }
+        }
+
+        if self.is_stale {
+            return self.complete_resync();
+        }
+        Ok(())
+    }
+
+    fn apply_delta_unchecked(&mut self, delta: OrderBookDelta) {
+        match delta.action {
+            // An L1_MBP book only holds the top level, so an add replaces it
+            BookAction::Add if self.book_type == BookType::L1_MBP => {
+                self.update(delta.order, delta.ts_event, delta.sequence);
+            }
+            BookAction::Add => self.add(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Update => self.update(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Delete => self.delete(delta.order, delta.ts_event, delta.sequence),
+            BookAction::Clear => self.clear(delta.ts_event, delta.sequence),
+        }
+    }
+
+    fn check_sequence(&self, sequence: u64) -> Result<(), BookIntegrityError> {
+        if self.count == 0 {
+            return Ok(()); // Nothing applied yet to sequence from
+        }
+
+        if sequence < self.sequence {
+            return Err(BookIntegrityError::SequenceRegression(
+                self.sequence,
+                sequence,
+            ));
+        }
+
+        if sequence > self.sequence + 1 {
+            return Err(BookIntegrityError::SequenceGap(self.sequence, sequence));
+        }
+
+        Ok(())
+    }
+
+    fn apply_stale_delta(&mut self, delta: OrderBookDelta) -> Result<(), BookIntegrityError> {
+        match delta.action {
+            BookAction::Clear => {
+                // Start (or restart) the snapshot
+                self.is_resyncing = true;
+                self.apply_delta_unchecked(delta);
+            }
+            BookAction::Add if self.is_resyncing => self.apply_delta_unchecked(delta),
+            _ if self.is_resyncing => {
+                // The snapshot ended without a last flag
+                self.complete_resync()?;
+                return self.apply_delta(delta);
+            }
+            _ => {
+                self.buffer_delta(delta);
+                return Ok(());
+            }
+        }
+
+        if RecordFlag::F_LAST.matches(delta.flags) {
+            return self.complete_resync();
+        }
+
+        Ok(())
+    }
+
+    fn buffer_delta(&mut self, delta: OrderBookDelta) {
+        if self.buffered_deltas.len() == BUFFERED_DELTAS_MAX {
+            self.buffered_deltas.pop_front();
+        }
+        self.buffered_deltas.push_back(delta);
+    }
+
+    fn complete_resync(&mut self) -> Result<(), BookIntegrityError> {
+        self.is_stale = false;
+        self.is_resyncing = false;
+
+        // Replay the buffered deltas not already included in the snapshot
+        let snapshot_sequence = self.sequence;
+        let mut deltas = std::mem::take(&mut self.buffered_deltas);
+        deltas.retain(|delta| delta.sequence > snapshot_sequence);
+
+        while let Some(delta) = deltas.pop_front() {
+            if let Err(e) = self.check_sequence(delta.sequence) {
+                // Still missing deltas, so wait for the next snapshot
+                self.is_stale = true;
+                deltas.push_front(delta);
+                self.buffered_deltas = deltas;
+                return Err(e);
+            }
+            self.apply_delta_unchecked(delta);
+        }
+        Ok(())
+    }
+
+    pub fn bids(&self) -> impl Iterator<Item = &Level> {
+        self.bids.levels.values()
+    }
+
+    pub fn asks(&self) -> impl Iterator<Item = &Level> {
+        self.asks.levels.values()
+    }
+
+    #[must_use]
+    pub fn has_bid(&self) -> bool {
+        match self.bids.top() {
+            Some(top) => !top.orders.is_empty(),
+            None => false,
+        }
+    }
+
+    #[must_use]
+    pub fn has_ask(&self) -> bool {
+        match self.asks.top() {
+            Some(top) => !top.orders.is_empty(),
+            None => false,
+        }
+    }
+
+    #[must_use]
+    pub fn best_bid_price(&self) -> Option<Price> {
+        self.bids.top().map(|top| top.price.value)
+    }
+
+    #[must_use]
+    pub fn best_ask_price(&self) -> Option<Price> {
+        self.asks.top().map(|top| top.price.value)
+    }
+
+    #[must_use]
+    pub fn best_bid_size(&self) -> Option<Quantity> {
+        match self.bids.top() {
+            Some(top) => top.first().map(|order| order.size),
+            None => None,
+        }
+    }
+
+    #[must_use]
+    pub fn best_ask_size(&self) -> Option<Quantity> {
+        match self.asks.top() {
+            Some(top) => top.first().map(|order| order.size),
+            None => None,
+        }
+    }
+
+    #[must_use]
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_ask_price(), self.best_bid_price()) {
+            (Some(ask), Some(bid)) => Some(ask.as_f64() - bid.as_f64()),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn midpoint(&self) -> Option<f64> {
+        match (self.best_ask_price(), self.best_bid_price()) {
+            (Some(ask), Some(bid)) => Some((ask.as_f64() + bid.as_f64()) / 2.0),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_avg_px_for_quantity(&self, qty: Quantity, order_side: OrderSide) -> f64 {
+        let levels = match order_side {
+            OrderSide::Buy => &self.asks.levels,
+            OrderSide::Sell => &self.bids.levels,
+            _ => panic!("Invalid `OrderSide` {order_side}"),
+        };
+
+        get_avg_px_for_quantity(qty, levels)
+    }
+
+    #[must_use]
+    pub fn get_quantity_for_price(&self, price: Price, order_side: OrderSide) -> f64 {
+        let levels = match order_side {
+            OrderSide::Buy => &self.asks.levels,
+            OrderSide::Sell => &self.bids.levels,
+            _ => panic!("Invalid `OrderSide` {order_side}"),
+        };
+
+        get_quantity_for_price(price, order_side, levels)
+    }
+
+    #[must_use]
+    pub fn simulate_fills(&self, order: &BookOrder) -> Vec<(Price, Quantity)> {
+        match order.side {
+            OrderSide::Buy => self.asks.simulate_fills(order),
+            OrderSide::Sell => self.bids.simulate_fills(order),
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+
+    /// Return a [`String`] representation of the order book in a human-readable table format.
+    #[must_use]
+    pub fn pprint(&self, num_levels: usize) -> String {
+        pprint_book(&self.bids, &self.asks, num_levels)
+    }
+
+    /// Checks the integrity of the book for its book type, returning an error if the
+    /// book holds more levels (`L1_MBP`) or orders per level (`L2_MBP`) than allowed,
+    /// or if the top bid and ask are crossed.
+    pub fn check_integrity(&self) -> Result<(), BookIntegrityError> {
+        match self.book_type {
+            BookType::L1_MBP => {
+                if self.bids.len() > 1 {
+                    return Err(BookIntegrityError::TooManyLevels(
+                        OrderSide::Buy,
+                        self.bids.len(),
+                    ));
+                }
+                if self.asks.len() > 1 {
+                    return Err(BookIntegrityError::TooManyLevels(
+                        OrderSide::Sell,
+                        self.asks.len(),
+                    ));
+                }
+            }
+            BookType::L2_MBP => {
+                for bid_level in self.bids.levels.values() {
+                    let num_orders = bid_level.orders.len();
+                    if num_orders > 1 {
+                        return Err(BookIntegrityError::TooManyOrders(
+                            OrderSide::Buy,
+                            num_orders,
+                        ));
+                    }
+                }
+
+                for ask_level in self.asks.levels.values() {
+                    let num_orders = ask_level.orders.len();
+                    if num_orders > 1 {
+                        return Err(BookIntegrityError::TooManyOrders(
+                            OrderSide::Sell,
+                            num_orders,
+                        ));
+                    }
+                }
+            }
+            BookType::L3_MBO => {}
+        }
+
+        let top_bid_level = self.bids.top();
+        let top_ask_level = self.asks.top();
+
+        if top_bid_level.is_none() || top_ask_level.is_none() {
+            return Ok(());
+        }
+
+        // SAFETY: Levels were already checked for None
+        let best_bid = top_bid_level.unwrap().price;
+        let best_ask = top_ask_level.unwrap().price;
+
+        if best_bid.value >= best_ask.value {
+            return Err(BookIntegrityError::OrdersCrossed(best_bid, best_ask));
+        }
+
+        Ok(())
+    }
+
+    fn increment(&mut self, ts_event: u64, sequence: u64) {
+        self.ts_last = ts_event;
+        self.sequence = sequence;
+        self.count += 1;
+    }
+
+    fn update_bid(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        match self.bids.top() {
+            Some(top_bids) => match top_bids.first() {
+                Some(top_bid) => {
+                    let order_id = top_bid.order_id;
+                    self.bids.remove(order_id, ts_event, sequence);
+                    self.bids.add(order);
+                }
+                None => {
+                    self.bids.add(order);
+                }
+            },
+            None => {
+                self.bids.add(order);
+            }
+        }
+    }
+
+    fn update_ask(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        match self.asks.top() {
+            Some(top_asks) => match top_asks.first() {
+                Some(top_ask) => {
+                    let order_id = top_ask.order_id;
+                    self.asks.remove(order_id, ts_event, sequence);
+                    self.asks.add(order);
+                }
+                None => {
+                    self.asks.add(order);
+                }
+            },
+            None => {
+                self.asks.add(order);
+            }
+        }
+    }
+
+    fn update_top(&mut self, order: BookOrder, ts_event: u64, sequence: u64) {
+        // Because of the way we typically get updates from a L1_MBP order book (bid
+        // and ask updates at the same time), its quite probable that the last
+        // bid is now the ask price we are trying to insert (or vice versa). We
+        // just need to add some extra protection against this if we aren't calling
+        // `check_integrity()` on each individual update.
+        match order.side {
+            OrderSide::Buy => {
+                if let Some(best_ask_price) = self.best_ask_price() {
+                    if order.price > best_ask_price {
+                        self.clear_bids(ts_event, sequence);
+                    }
+                }
+            }
+            OrderSide::Sell => {
+                if let Some(best_bid_price) = self.best_bid_price() {
+                    if order.price < best_bid_price {
+                        self.clear_asks(ts_event, sequence);
+                    }
+                }
+            }
+            _ => panic!("{}", BookIntegrityError::NoOrderSide),
+        }
+    }
+
+    fn pre_process_order(&self, mut order: BookOrder) -> BookOrder {
+        match self.book_type {
+            // Only one order per side for the top level
+            BookType::L1_MBP => order.order_id = order.side as u64,
+            // Only one order per level, identified by its price
+            BookType::L2_MBP => order.order_id = order.price.raw as u64,
+            // Orders are held individually
+            BookType::L3_MBO => {}
+        };
+        order
+    }
 }
 
 /// Calculates the estimated average price for a specified quantity from a set of
@@ -106,21 +703,424 @@ pub fn get_quantity_for_price(
 mod tests {
     use rstest::rstest;
 
+    use super::*;
     use crate::{
-        data::{
-            depth::{stubs::stub_depth10, OrderBookDepth10},
-            order::BookOrder,
-        },
-        enums::OrderSide,
-        identifiers::instrument_id::InstrumentId,
-        orderbook::{book_mbo::OrderBookMbo, book_mbp::OrderBookMbp},
-        types::{price::Price, quantity::Quantity},
+        data::depth::stubs::stub_depth10, enums::AggressorSide, identifiers::trade_id::TradeId,
     };
 
+    #[rstest]
+    #[case(BookType::L1_MBP)]
+    #[case(BookType::L2_MBP)]
+    #[case(BookType::L3_MBO)]
+    fn test_orderbook_creation(#[case] book_type: BookType) {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let book = OrderBook::new(instrument_id, book_type);
+
+        assert_eq!(book.instrument_id, instrument_id);
+        assert_eq!(book.book_type, book_type);
+        assert_eq!(book.sequence, 0);
+        assert_eq!(book.ts_last, 0);
+        assert_eq!(book.count, 0);
+    }
+
+    #[rstest]
+    fn test_orderbook_reset() {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+        book.sequence = 10;
+        book.ts_last = 100;
+        book.count = 3;
+
+        book.reset();
+
+        assert_eq!(book.book_type, BookType::L1_MBP);
+        assert_eq!(book.sequence, 0);
+        assert_eq!(book.ts_last, 0);
+        assert_eq!(book.count, 0);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "cannot add order for L1_MBP book")]
+    fn test_add_when_l1_panics() {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+        let order = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("1.0"),
+            1,
+        );
+
+        book.add(order, 100, 1);
+    }
+
+    #[rstest]
+    fn test_apply_delta_add_when_l1_replaces_top() {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+        for (price, sequence) in [("1.000", 1), ("1.001", 2)] {
+            let order =
+                BookOrder::new(OrderSide::Buy, Price::from(price), Quantity::from("1.0"), 0);
+            book.apply_delta(OrderBookDelta::new(
+                instrument_id,
+                BookAction::Add,
+                order,
+                0,
+                sequence,
+                100,
+                100,
+            ))
+            .unwrap();
+        }
+
+        assert_eq!(book.best_bid_price(), Some(Price::from("1.001")));
+        assert_eq!(book.bids().count(), 1);
+        assert!(book.check_integrity().is_ok());
+    }
+
+    fn delta(action: BookAction, price: &str, flags: u8, sequence: u64) -> OrderBookDelta {
+        let order = BookOrder::new(OrderSide::Buy, Price::from(price), Quantity::from("1.0"), 0);
+        OrderBookDelta::new(
+            InstrumentId::from("AAPL.XNAS"),
+            action,
+            order,
+            flags,
+            sequence,
+            100,
+            100,
+        )
+    }
+
+    #[rstest]
+    fn test_apply_delta_with_ignore_gap_policy_applies_gaps() {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+
+        book.apply_delta(delta(BookAction::Add, "1.000", 0, 1))
+            .unwrap();
+        book.apply_delta(delta(BookAction::Add, "1.001", 0, 5))
+            .unwrap();
+
+        assert!(!book.is_stale());
+        assert_eq!(book.bids().count(), 2);
+        assert_eq!(book.sequence, 5);
+    }
+
+    #[rstest]
+    #[case(5, BookIntegrityError::SequenceGap(2, 5))]
+    #[case(1, BookIntegrityError::SequenceRegression(2, 1))]
+    fn test_apply_delta_with_resync_gap_policy_detects_gaps(
+        #[case] sequence: u64,
+        #[case] expected: BookIntegrityError,
+    ) {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        book.apply_delta(delta(BookAction::Add, "1.000", 0, 1))
+            .unwrap();
+        book.apply_delta(delta(BookAction::Add, "1.001", 0, 2))
+            .unwrap();
+        book.apply_delta(delta(BookAction::Update, "1.001", 0, 2))
+            .unwrap();
+
+        let result = book.apply_delta(delta(BookAction::Add, "1.002", 0, sequence));
+
+        assert_eq!(result.unwrap_err().to_string(), expected.to_string());
+        assert!(book.is_stale());
+        assert_eq!(book.bids().count(), 2);
+
+        // Deltas are buffered while stale
+        book.apply_delta(delta(BookAction::Add, "1.003", 0, sequence + 1))
+            .unwrap();
+
+        assert_eq!(book.bids().count(), 2);
+    }
+
+    #[rstest]
+    fn test_apply_delta_with_resync_gap_policy_replays_on_clear_snapshot() {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        book.apply_delta(delta(BookAction::Add, "1.000", 0, 1))
+            .unwrap();
+        assert!(book
+            .apply_delta(delta(BookAction::Add, "1.001", 0, 3))
+            .is_err());
+        book.apply_delta(delta(BookAction::Add, "1.002", 0, 4))
+            .unwrap();
+        book.apply_delta(delta(BookAction::Add, "1.003", 0, 5))
+            .unwrap();
+
+        // Snapshot includes sequences up to 4
+        book.apply_delta(delta(BookAction::Clear, "0", 0, 4))
+            .unwrap();
+        book.apply_delta(delta(BookAction::Add, "0.900", 0, 4))
+            .unwrap();
+
+        assert!(book.is_stale());
+
+        book.apply_delta(delta(BookAction::Add, "0.901", RecordFlag::F_LAST as u8, 4))
+            .unwrap();
+
+        let bid_prices: Vec<Price> = book.bids().map(|level| level.price.value).collect();
+        assert!(!book.is_stale());
+        assert_eq!(
+            bid_prices,
+            vec![
+                Price::from("1.003"),
+                Price::from("0.901"),
+                Price::from("0.900")
+            ]
+        );
+        assert_eq!(book.sequence, 5);
+
+        // Sequence checks resume from the replayed deltas
+        book.apply_delta(delta(BookAction::Add, "1.004", 0, 6))
+            .unwrap();
+        assert!(book
+            .apply_delta(delta(BookAction::Add, "1.005", 0, 8))
+            .is_err());
+    }
+
+    #[rstest]
+    fn test_apply_depth_with_resync_gap_policy_replays_buffered_deltas(
+        mut stub_depth10: OrderBookDepth10,
+    ) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        stub_depth10.sequence = 10;
+        book.apply_depth(stub_depth10).unwrap();
+        assert!(book
+            .apply_delta(delta(BookAction::Add, "99.50", 0, 12))
+            .is_err());
+
+        stub_depth10.sequence = 11;
+        book.apply_depth(stub_depth10).unwrap();
+
+        assert!(!book.is_stale());
+        assert_eq!(book.best_bid_price(), Some(Price::from("99.50")));
+        assert_eq!(book.sequence, 12);
+    }
+
+    #[rstest]
+    fn test_apply_depth_with_resync_gap_policy_checks_sequence_of_replayed_deltas(
+        mut stub_depth10: OrderBookDepth10,
+    ) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        stub_depth10.sequence = 10;
+        book.apply_depth(stub_depth10).unwrap();
+        assert!(book
+            .apply_delta(delta(BookAction::Add, "99.50", 0, 12))
+            .is_err());
+        book.apply_delta(delta(BookAction::Add, "99.60", 0, 14))
+            .unwrap();
+
+        // Sequence 13 is missing from both the snapshot and the buffered deltas
+        stub_depth10.sequence = 11;
+        let result = book.apply_depth(stub_depth10);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            BookIntegrityError::SequenceGap(12, 14).to_string()
+        );
+        assert!(book.is_stale());
+        assert_eq!(book.sequence, 12);
+
+        stub_depth10.sequence = 13;
+        book.apply_depth(stub_depth10).unwrap();
+
+        assert!(!book.is_stale());
+        assert_eq!(book.best_bid_price(), Some(Price::from("99.60")));
+        assert_eq!(book.sequence, 14);
+    }
+
+    #[rstest]
+    fn test_apply_delta_with_resync_gap_policy_caps_buffered_deltas() {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        book.apply_delta(delta(BookAction::Add, "1.000", 0, 1))
+            .unwrap();
+
+        for sequence in 3..BUFFERED_DELTAS_MAX as u64 + 4 {
+            let _ = book.apply_delta(delta(BookAction::Add, "1.001", 0, sequence));
+        }
+
+        assert!(book.is_stale());
+        assert_eq!(book.buffered_deltas.len(), BUFFERED_DELTAS_MAX);
+        assert_eq!(book.buffered_deltas.front().unwrap().sequence, 4);
+    }
+
+    #[rstest]
+    fn test_reset_clears_stale_state() {
+        let mut book = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+        book.gap_policy = BookGapPolicy::Resync;
+        book.apply_delta(delta(BookAction::Add, "1.000", 0, 1))
+            .unwrap();
+        assert!(book
+            .apply_delta(delta(BookAction::Add, "1.001", 0, 3))
+            .is_err());
+
+        book.reset();
+
+        assert!(!book.is_stale());
+        book.apply_delta(delta(BookAction::Add, "1.002", 0, 10))
+            .unwrap();
+        assert_eq!(book.bids().count(), 1);
+    }
+
+    #[rstest]
+    fn test_apply_depth_when_l1_takes_top_levels(stub_depth10: OrderBookDepth10) {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+
+        book.apply_depth(stub_depth10).unwrap();
+
+        assert_eq!(book.best_bid_price().unwrap().as_f64(), 99.00);
+        assert_eq!(book.best_ask_price().unwrap().as_f64(), 100.00);
+        assert_eq!(book.bids().count(), 1);
+        assert_eq!(book.asks().count(), 1);
+    }
+
+    #[rstest]
+    fn test_add_when_l2_aggregates_by_price() {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L2_MBP);
+        let order1 = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("1.0"),
+            1,
+        );
+        let order2 = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("2.0"),
+            2,
+        );
+        book.add(order1, 100, 1);
+        book.update(order2, 200, 2);
+
+        assert_eq!(book.best_bid_size(), Some(Quantity::from("2.0")));
+        assert!(book.check_integrity().is_ok());
+    }
+
+    #[rstest]
+    fn test_add_when_l3_holds_orders() {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
+        let order1 = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("1.0"),
+            1,
+        );
+        let order2 = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("1.000"),
+            Quantity::from("2.0"),
+            2,
+        );
+        book.add(order1, 100, 1);
+        book.add(order2, 200, 2);
+
+        assert_eq!(book.bids().next().unwrap().orders.len(), 2);
+        assert_eq!(book.best_bid_size(), Some(Quantity::from("1.0")));
+        assert!(book.check_integrity().is_ok());
+    }
+
+    #[rstest]
+    #[case(BookType::L2_MBP)]
+    #[case(BookType::L3_MBO)]
+    fn test_check_integrity_when_crossed(#[case] book_type: BookType) {
+        let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
+        let mut book = OrderBook::new(instrument_id, book_type);
+
+        let ask1 = BookOrder::new(
+            OrderSide::Sell,
+            Price::from("1.000"),
+            Quantity::from("1.0"),
+            1,
+        );
+        let bid1 = BookOrder::new(
+            OrderSide::Buy,
+            Price::from("2.000"),
+            Quantity::from("1.0"),
+            2,
+        );
+        book.add(bid1, 0, 1);
+        book.add(ask1, 0, 1);
+
+        assert!(book.check_integrity().is_err());
+    }
+
+    #[rstest]
+    fn test_update_quote_tick_l1() {
+        let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+        let quote = QuoteTick::new(
+            InstrumentId::from("ETHUSDT-PERP.BINANCE"),
+            Price::from("5000.000"),
+            Price::from("5100.000"),
+            Quantity::from("100.00000000"),
+            Quantity::from("99.00000000"),
+            0,
+            0,
+        )
+        .unwrap();
+
+        book.update_quote_tick(&quote);
+
+        assert_eq!(book.best_bid_price().unwrap(), quote.bid_price);
+        assert_eq!(book.best_ask_price().unwrap(), quote.ask_price);
+        assert_eq!(book.best_bid_size().unwrap(), quote.bid_size);
+        assert_eq!(book.best_ask_size().unwrap(), quote.ask_size);
+    }
+
+    #[rstest]
+    fn test_update_trade_tick_l1() {
+        let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
+        let mut book = OrderBook::new(instrument_id, BookType::L1_MBP);
+
+        let price = Price::from("15000.000");
+        let size = Quantity::from("10.00000000");
+        let trade = TradeTick::new(
+            instrument_id,
+            price,
+            size,
+            AggressorSide::Buyer,
+            TradeId::new("123456789").unwrap(),
+            0,
+            0,
+        );
+
+        book.update_trade_tick(&trade);
+
+        assert_eq!(book.best_bid_price().unwrap(), price);
+        assert_eq!(book.best_ask_price().unwrap(), price);
+        assert_eq!(book.best_bid_size().unwrap(), size);
+        assert_eq!(book.best_ask_size().unwrap(), size);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "cannot update with tick for L3_MBO book")]
+    fn test_update_trade_tick_when_l3_panics() {
+        let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
+        let trade = TradeTick::new(
+            instrument_id,
+            Price::from("15000.000"),
+            Quantity::from("10.00000000"),
+            AggressorSide::Buyer,
+            TradeId::new("123456789").unwrap(),
+            0,
+            0,
+        );
+
+        book.update_trade_tick(&trade);
+    }
+
     #[rstest]
     fn test_best_bid_and_ask_when_nothing_in_book() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let book = OrderBookMbp::new(instrument_id, false);
+        let book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         assert_eq!(book.best_bid_price(), None);
         assert_eq!(book.best_ask_price(), None);
@@ -133,7 +1133,7 @@ mod tests {
     #[rstest]
     fn test_bid_side_with_one_order() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbo::new(instrument_id);
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
         let order1 = BookOrder::new(
             OrderSide::Buy,
             Price::from("1.000"),
@@ -150,7 +1150,7 @@ mod tests {
     #[rstest]
     fn test_ask_side_with_one_order() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbo::new(instrument_id);
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
         let order = BookOrder::new(
             OrderSide::Sell,
             Price::from("2.000"),
@@ -167,14 +1167,14 @@ mod tests {
     #[rstest]
     fn test_spread_with_no_bids_or_asks() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let book = OrderBookMbo::new(instrument_id);
+        let book = OrderBook::new(instrument_id, BookType::L3_MBO);
         assert_eq!(book.spread(), None);
     }
 
     #[rstest]
     fn test_spread_with_bids_and_asks() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbo::new(instrument_id);
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
         let bid1 = BookOrder::new(
             OrderSide::Buy,
             Price::from("1.000"),
@@ -196,14 +1196,14 @@ mod tests {
     #[rstest]
     fn test_midpoint_with_no_bids_or_asks() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let book = OrderBookMbp::new(instrument_id, false);
+        let book = OrderBook::new(instrument_id, BookType::L2_MBP);
         assert_eq!(book.midpoint(), None);
     }
 
     #[rstest]
     fn test_midpoint_with_bids_asks() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbp::new(instrument_id, false);
+        let mut book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         let bid1 = BookOrder::new(
             OrderSide::Buy,
@@ -226,7 +1226,7 @@ mod tests {
     #[rstest]
     fn test_get_price_for_quantity_no_market() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let book = OrderBookMbp::new(instrument_id, false);
+        let book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         let qty = Quantity::from(1);
 
@@ -237,7 +1237,7 @@ mod tests {
     #[rstest]
     fn test_get_quantity_for_price_no_market() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let book = OrderBookMbp::new(instrument_id, false);
+        let book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         let price = Price::from("1.0");
 
@@ -248,7 +1248,7 @@ mod tests {
     #[rstest]
     fn test_get_price_for_quantity() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbp::new(instrument_id, false);
+        let mut book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         let ask2 = BookOrder::new(
             OrderSide::Sell,
@@ -294,7 +1294,7 @@ mod tests {
     #[rstest]
     fn test_get_quantity_for_price() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbp::new(instrument_id, false);
+        let mut book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
         let ask3 = BookOrder::new(
             OrderSide::Sell,
@@ -353,9 +1353,9 @@ mod tests {
     fn test_apply_depth(stub_depth10: OrderBookDepth10) {
         let depth = stub_depth10;
         let instrument_id = InstrumentId::from("AAPL.XNAS");
-        let mut book = OrderBookMbp::new(instrument_id, false);
+        let mut book = OrderBook::new(instrument_id, BookType::L2_MBP);
 
-        book.apply_depth(depth);
+        book.apply_depth(depth).unwrap();
 
         assert_eq!(book.best_bid_price().unwrap().as_f64(), 99.00);
         assert_eq!(book.best_ask_price().unwrap().as_f64(), 100.00);
@@ -366,7 +1366,7 @@ mod tests {
     #[rstest]
     fn test_pprint() {
         let instrument_id = InstrumentId::from("ETHUSDT-PERP.BINANCE");
-        let mut book = OrderBookMbo::new(instrument_id);
+        let mut book = OrderBook::new(instrument_id, BookType::L3_MBO);
 
         let order1 = BookOrder::new(
             OrderSide::Buy,
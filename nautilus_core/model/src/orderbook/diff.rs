@@ -0,0 +1,293 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Functions for diffing order book states into the deltas which regenerate them.
+
+use std::collections::HashMap;
+
+use nautilus_core::time::UnixNanos;
+
+use super::{book::OrderBook, level::Level};
+use crate::{
+    data::{
+        delta::OrderBookDelta,
+        deltas::OrderBookDeltas,
+        depth::OrderBookDepth10,
+        order::{BookOrder, OrderId},
+    },
+    enums::{BookAction, RecordFlag},
+};
+
+/// Returns the smallest set of deltas which turns the `old` book into the `new` book,
+/// when applied in order, or `None` if the books hold the same orders.
+///
+/// Every delta is assigned the sequence number and event timestamp of the `new` book,
+/// with the `F_LAST` flag set on the final delta.
+///
+/// # Errors
+///
+/// This function returns an error if the books are for different instruments or book types.
+pub fn diff_books(
+    old: &OrderBook,
+    new: &OrderBook,
+    ts_init: UnixNanos,
+) -> anyhow::Result<Option<OrderBookDeltas>> {
+    anyhow::ensure!(
+        old.instrument_id == new.instrument_id,
+        "Cannot diff books for different instruments, {} and {}",
+        old.instrument_id,
+        new.instrument_id
+    );
+    anyhow::ensure!(
+        old.book_type == new.book_type,
+        "Cannot diff books of different types, {} and {}",
+        old.book_type,
+        new.book_type
+    );
+
+    Ok(diff(old, new, new.sequence, new.ts_last, ts_init))
+}
+
+/// Returns the smallest set of deltas which turns the given `book` into the state
+/// it would have after applying the `depth` snapshot, or `None` if nothing changes.
+///
+/// Every delta is assigned the sequence number and timestamps of the `depth`,
+/// with the `F_LAST` flag set on the final delta.
+///
+/// # Errors
+///
+/// This function returns an error if the `depth` is for a different instrument.
+pub fn diff_depth(
+    book: &OrderBook,
+    depth: OrderBookDepth10,
+) -> anyhow::Result<Option<OrderBookDeltas>> {
+    anyhow::ensure!(
+        book.instrument_id == depth.instrument_id,
+        "Cannot diff book for {} with depth for {}",
+        book.instrument_id,
+        depth.instrument_id
+    );
+
+    let (sequence, ts_event, ts_init) = (depth.sequence, depth.ts_event, depth.ts_init);
+    let mut new = OrderBook::new(book.instrument_id, book.book_type);
+    new.apply_depth(depth)?;
+
+    Ok(diff(book, &new, sequence, ts_event, ts_init))
+}
+
+fn diff(
+    old: &OrderBook,
+    new: &OrderBook,
+    sequence: u64,
+    ts_event: UnixNanos,
+    ts_init: UnixNanos,
+) -> Option<OrderBookDeltas> {
+    let mut changes: Vec<(BookAction, BookOrder)> = Vec::new();
+
+    let old_bids = orders_by_id(old.bids());
+    let old_asks = orders_by_id(old.asks());
+    let new_bids = orders_by_id(new.bids());
+    let new_asks = orders_by_id(new.asks());
+
+    // Deletes are applied first, so that no intermediate state is crossed
+    diff_deletes(old.bids(), &new_bids, &mut changes);
+    diff_deletes(old.asks(), &new_asks, &mut changes);
+    diff_upserts(new.bids(), &old_bids, &mut changes);
+    diff_upserts(new.asks(), &old_asks, &mut changes);
+
+    let last_index = changes.len().checked_sub(1)?;
+    let deltas = changes
+        .into_iter()
+        .enumerate()
+        .map(|(i, (action, order))| {
+            let flags = if i == last_index {
+                RecordFlag::F_LAST as u8
+            } else {
+                0
+            };
+            OrderBookDelta::new(
+                old.instrument_id,
+                action,
+                order,
+                flags,
+                sequence,
+                ts_event,
+                ts_init,
+            )
+        })
+        .collect();
+
+    Some(OrderBookDeltas::new(old.instrument_id, deltas))
+}
+
+fn orders_by_id<'a>(levels: impl Iterator<Item = &'a Level>) -> HashMap<OrderId, BookOrder> {
+    levels
+        .flat_map(|level| level.orders.iter().map(|(id, order)| (*id, *order)))
+        .collect()
+}
+
+fn diff_deletes<'a>(
+    old_levels: impl Iterator<Item = &'a Level>,
+    new_orders: &HashMap<OrderId, BookOrder>,
+    changes: &mut Vec<(BookAction, BookOrder)>,
+) {
+    for level in old_levels {
+        for order in level.get_orders() {
+            if !new_orders.contains_key(&order.order_id) {
+                changes.push((BookAction::Delete, order));
+            }
+        }
+    }
+}
+
+fn diff_upserts<'a>(
+    new_levels: impl Iterator<Item = &'a Level>,
+    old_orders: &HashMap<OrderId, BookOrder>,
+    changes: &mut Vec<(BookAction, BookOrder)>,
+) {
+    for level in new_levels {
+        for order in level.get_orders() {
+            match old_orders.get(&order.order_id) {
+                None => changes.push((BookAction::Add, order)),
+                Some(old) if old.price != order.price || old.size != order.size => {
+                    changes.push((BookAction::Update, order));
+                }
+                Some(_) => {} // Unchanged
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        data::depth::stubs::stub_depth10,
+        enums::{BookType, OrderSide},
+        identifiers::instrument_id::InstrumentId,
+        types::{price::Price, quantity::Quantity},
+    };
+
+    // `BookOrder` equality is by order ID only, so compare the full order state
+    fn book_orders(book: &OrderBook) -> Vec<(OrderSide, Price, Quantity, OrderId)> {
+        book.bids()
+            .chain(book.asks())
+            .flat_map(Level::get_orders)
+            .map(|order| (order.side, order.price, order.size, order.order_id))
+            .collect()
+    }
+
+    fn order(side: OrderSide, price: &str, size: &str, order_id: u64) -> BookOrder {
+        BookOrder::new(side, Price::from(price), Quantity::from(size), order_id)
+    }
+
+    #[rstest]
+    fn test_diff_books_when_equal_returns_none(stub_depth10: OrderBookDepth10) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.apply_depth(stub_depth10).unwrap();
+
+        assert!(diff_books(&book, &book.clone(), 0).unwrap().is_none());
+    }
+
+    #[rstest]
+    fn test_diff_books_when_mismatched_fails() {
+        let old = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L2_MBP);
+        let other_instrument = OrderBook::new(InstrumentId::from("MSFT.XNAS"), BookType::L2_MBP);
+        let other_type = OrderBook::new(InstrumentId::from("AAPL.XNAS"), BookType::L3_MBO);
+
+        assert!(diff_books(&old, &other_instrument, 0).is_err());
+        assert!(diff_books(&old, &other_type, 0).is_err());
+    }
+
+    #[rstest]
+    #[case(BookType::L2_MBP)]
+    #[case(BookType::L3_MBO)]
+    fn test_diff_books_regenerates_new_book(#[case] book_type: BookType) {
+        let instrument_id = InstrumentId::from("AAPL.XNAS");
+        let mut old = OrderBook::new(instrument_id, book_type);
+        old.add(order(OrderSide::Buy, "99.00", "100", 1), 1, 1);
+        old.add(order(OrderSide::Buy, "98.00", "200", 2), 1, 2);
+        old.add(order(OrderSide::Sell, "100.00", "100", 3), 1, 3);
+        old.add(order(OrderSide::Sell, "101.00", "300", 4), 1, 4);
+
+        let mut new = old.clone();
+        new.update(order(OrderSide::Buy, "99.00", "150", 1), 2, 5);
+        new.delete(order(OrderSide::Buy, "98.00", "200", 2), 2, 6);
+        new.add(order(OrderSide::Sell, "100.50", "50", 5), 2, 7);
+
+        let deltas = diff_books(&old, &new, 3).unwrap().unwrap();
+        let actions: Vec<BookAction> = deltas.deltas.iter().map(|d| d.action).collect();
+        assert_eq!(
+            actions,
+            vec![BookAction::Delete, BookAction::Update, BookAction::Add]
+        );
+        assert!(deltas.deltas.iter().all(|d| d.sequence == 7));
+        assert_eq!(deltas.flags, RecordFlag::F_LAST as u8);
+        assert_eq!(deltas.deltas[0].flags, 0);
+
+        old.apply_deltas(deltas).unwrap();
+        assert_eq!(book_orders(&old), book_orders(&new));
+    }
+
+    #[rstest]
+    fn test_diff_depth_from_empty_book_adds_all_levels(stub_depth10: OrderBookDepth10) {
+        let book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+
+        let deltas = diff_depth(&book, stub_depth10).unwrap().unwrap();
+
+        assert_eq!(deltas.deltas.len(), 20);
+        assert!(deltas
+            .deltas
+            .iter()
+            .all(|delta| delta.action == BookAction::Add));
+        assert_eq!(deltas.sequence, stub_depth10.sequence);
+        assert_eq!(deltas.ts_event, stub_depth10.ts_event);
+        assert_eq!(deltas.ts_init, stub_depth10.ts_init);
+    }
+
+    #[rstest]
+    fn test_diff_depth_returns_only_changes(stub_depth10: OrderBookDepth10) {
+        let mut book = OrderBook::new(stub_depth10.instrument_id, BookType::L2_MBP);
+        book.apply_depth(stub_depth10).unwrap();
+        let mut depth = stub_depth10;
+        depth.bids[0].size = Quantity::from("50");
+        depth.asks[9].price = Price::from("120.00");
+
+        let deltas = diff_depth(&book, depth).unwrap().unwrap();
+        let actions: Vec<BookAction> = deltas.deltas.iter().map(|d| d.action).collect();
+        assert_eq!(
+            actions,
+            vec![BookAction::Delete, BookAction::Update, BookAction::Add]
+        );
+
+        let mut expected = book.clone();
+        expected.apply_depth(depth).unwrap();
+        book.apply_deltas(deltas).unwrap();
+        assert_eq!(book_orders(&book), book_orders(&expected));
+        assert!(diff_depth(&book, depth).unwrap().is_none());
+    }
+
+    #[rstest]
+    fn test_diff_depth_when_mismatched_instrument_fails(stub_depth10: OrderBookDepth10) {
+        let book = OrderBook::new(InstrumentId::from("MSFT.XNAS"), BookType::L2_MBP);
+
+        assert!(diff_depth(&book, stub_depth10).is_err());
+    }
+}
@@ -14,8 +14,7 @@
 // -------------------------------------------------------------------------------------------------
 
 pub mod book;
-pub mod book_mbo;
-pub mod book_mbp;
+pub mod diff;
 pub mod display;
 pub mod ladder;
 pub mod level;
@@ -0,0 +1,549 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use nautilus_core::time::UnixNanos;
+
+use super::{
+    base::{LimitOrderType, Order, OrderError, PassiveOrderType, StopOrderType},
+    limit::LimitOrder,
+    limit_if_touched::LimitIfTouchedOrder,
+    market::MarketOrder,
+    market_if_touched::MarketIfTouchedOrder,
+    market_to_limit::MarketToLimitOrder,
+    stop_limit::StopLimitOrder,
+    stop_market::StopMarketOrder,
+    trailing_stop_limit::TrailingStopLimitOrder,
+    trailing_stop_market::TrailingStopMarketOrder,
+};
+use crate::{
+    enums::{
+        ContingencyType, LiquiditySide, OrderSide, OrderStatus, OrderType, TimeInForce,
+        TrailingOffsetType, TriggerType,
+    },
+    events::order::{event::OrderEvent, initialized::OrderInitialized},
+    identifiers::{
+        account_id::AccountId, client_order_id::ClientOrderId, exec_algorithm_id::ExecAlgorithmId,
+        instrument_id::InstrumentId, position_id::PositionId, strategy_id::StrategyId,
+        trader_id::TraderId, venue_order_id::VenueOrderId,
+    },
+    types::{price::Price, quantity::Quantity},
+};
+
+/// Dispatches the expression to the concrete order held by an [`OrderAny`].
+macro_rules! dispatch {
+    ($self:expr, $order:ident => $expr:expr) => {
+        match $self {
+            OrderAny::Limit($order) => $expr,
+            OrderAny::LimitIfTouched($order) => $expr,
+            OrderAny::Market($order) => $expr,
+            OrderAny::MarketIfTouched($order) => $expr,
+            OrderAny::MarketToLimit($order) => $expr,
+            OrderAny::StopLimit($order) => $expr,
+            OrderAny::StopMarket($order) => $expr,
+            OrderAny::TrailingStopLimit($order) => $expr,
+            OrderAny::TrailingStopMarket($order) => $expr,
+        }
+    };
+}
+
+/// Wraps any concrete order type so that orders can be held and processed uniformly.
+#[derive(Clone, Debug)]
+pub enum OrderAny {
+    Limit(LimitOrder),
+    LimitIfTouched(LimitIfTouchedOrder),
+    Market(MarketOrder),
+    MarketIfTouched(MarketIfTouchedOrder),
+    MarketToLimit(MarketToLimitOrder),
+    StopLimit(StopLimitOrder),
+    StopMarket(StopMarketOrder),
+    TrailingStopLimit(TrailingStopLimitOrder),
+    TrailingStopMarket(TrailingStopMarketOrder),
+}
+
+impl OrderAny {
+    /// Creates a new order by replaying the given `events`, the first of which must
+    /// be the `OrderInitialized` event for the order.
+    pub fn from_events(events: Vec<OrderEvent>) -> anyhow::Result<Self> {
+        let mut events = events.into_iter();
+        let mut order = match events.next() {
+            Some(OrderEvent::OrderInitialized(init)) => Self::from(init),
+            Some(event) => anyhow::bail!("First event must be `OrderInitialized`, was {event}"),
+            None => anyhow::bail!("No order events"),
+        };
+
+        for event in events {
+            order.apply(event)?;
+        }
+
+        Ok(order)
+    }
+
+    /// Applies the `event` to the wrapped order.
+    pub fn apply(&mut self, event: OrderEvent) -> Result<(), OrderError> {
+        dispatch!(self, o => o.apply(event))
+    }
+
+    /// Returns an `OrderInitialized` event from which the order can be recreated in its
+    /// initialized state (the event is not held by the order itself).
+    #[must_use]
+    pub fn initialized_event(&self) -> OrderInitialized {
+        dispatch!(self, o => initialized_event(o))
+    }
+
+    #[must_use]
+    pub fn events(&self) -> Vec<&OrderEvent> {
+        dispatch!(self, o => o.events())
+    }
+
+    #[must_use]
+    pub fn last_event(&self) -> &OrderEvent {
+        dispatch!(self, o => o.last_event())
+    }
+
+    #[must_use]
+    pub fn trader_id(&self) -> TraderId {
+        dispatch!(self, o => o.trader_id)
+    }
+
+    #[must_use]
+    pub fn strategy_id(&self) -> StrategyId {
+        dispatch!(self, o => o.strategy_id)
+    }
+
+    #[must_use]
+    pub fn instrument_id(&self) -> InstrumentId {
+        dispatch!(self, o => o.instrument_id)
+    }
+
+    #[must_use]
+    pub fn client_order_id(&self) -> ClientOrderId {
+        dispatch!(self, o => o.client_order_id)
+    }
+
+    #[must_use]
+    pub fn venue_order_id(&self) -> Option<VenueOrderId> {
+        dispatch!(self, o => o.venue_order_id)
+    }
+
+    #[must_use]
+    pub fn account_id(&self) -> Option<AccountId> {
+        dispatch!(self, o => o.account_id)
+    }
+
+    #[must_use]
+    pub fn position_id(&self) -> Option<PositionId> {
+        dispatch!(self, o => o.position_id)
+    }
+
+    #[must_use]
+    pub fn status(&self) -> OrderStatus {
+        dispatch!(self, o => o.status)
+    }
+
+    #[must_use]
+    pub fn previous_status(&self) -> Option<OrderStatus> {
+        dispatch!(self, o => o.previous_status)
+    }
+
+    #[must_use]
+    pub fn order_side(&self) -> OrderSide {
+        dispatch!(self, o => o.side)
+    }
+
+    #[must_use]
+    pub fn order_type(&self) -> OrderType {
+        dispatch!(self, o => o.order_type)
+    }
+
+    #[must_use]
+    pub fn quantity(&self) -> Quantity {
+        dispatch!(self, o => o.quantity)
+    }
+
+    #[must_use]
+    pub fn filled_qty(&self) -> Quantity {
+        dispatch!(self, o => o.filled_qty)
+    }
+
+    #[must_use]
+    pub fn leaves_qty(&self) -> Quantity {
+        dispatch!(self, o => o.leaves_qty)
+    }
+
+    #[must_use]
+    pub fn time_in_force(&self) -> TimeInForce {
+        dispatch!(self, o => o.time_in_force)
+    }
+
+    #[must_use]
+    pub fn expire_time(&self) -> Option<UnixNanos> {
+        dispatch!(self, o => Order::expire_time(o))
+    }
+
+    #[must_use]
+    pub fn price(&self) -> Option<Price> {
+        dispatch!(self, o => Order::price(o))
+    }
+
+    #[must_use]
+    pub fn trigger_price(&self) -> Option<Price> {
+        dispatch!(self, o => Order::trigger_price(o))
+    }
+
+    #[must_use]
+    pub fn trigger_type(&self) -> Option<TriggerType> {
+        dispatch!(self, o => Order::trigger_type(o))
+    }
+
+    #[must_use]
+    pub fn limit_offset(&self) -> Option<Price> {
+        dispatch!(self, o => Order::limit_offset(o))
+    }
+
+    #[must_use]
+    pub fn trailing_offset(&self) -> Option<Price> {
+        dispatch!(self, o => Order::trailing_offset(o))
+    }
+
+    #[must_use]
+    pub fn trailing_offset_type(&self) -> Option<TrailingOffsetType> {
+        dispatch!(self, o => Order::trailing_offset_type(o))
+    }
+
+    #[must_use]
+    pub fn liquidity_side(&self) -> Option<LiquiditySide> {
+        dispatch!(self, o => o.liquidity_side)
+    }
+
+    pub fn set_liquidity_side(&mut self, liquidity_side: LiquiditySide) {
+        dispatch!(self, o => o.liquidity_side = Some(liquidity_side));
+    }
+
+    #[must_use]
+    pub fn contingency_type(&self) -> Option<ContingencyType> {
+        dispatch!(self, o => o.contingency_type)
+    }
+
+    #[must_use]
+    pub fn linked_order_ids(&self) -> Option<Vec<ClientOrderId>> {
+        dispatch!(self, o => o.linked_order_ids.clone())
+    }
+
+    #[must_use]
+    pub fn parent_order_id(&self) -> Option<ClientOrderId> {
+        dispatch!(self, o => o.parent_order_id)
+    }
+
+    #[must_use]
+    pub fn emulation_trigger(&self) -> Option<TriggerType> {
+        dispatch!(self, o => o.emulation_trigger())
+    }
+
+    #[must_use]
+    pub fn exec_algorithm_id(&self) -> Option<ExecAlgorithmId> {
+        dispatch!(self, o => o.exec_algorithm_id())
+    }
+
+    #[must_use]
+    pub fn exec_spawn_id(&self) -> Option<ClientOrderId> {
+        dispatch!(self, o => o.exec_spawn_id())
+    }
+
+    #[must_use]
+    pub fn is_post_only(&self) -> bool {
+        dispatch!(self, o => Order::is_post_only(o))
+    }
+
+    #[must_use]
+    pub fn is_reduce_only(&self) -> bool {
+        dispatch!(self, o => o.is_reduce_only)
+    }
+
+    #[must_use]
+    pub fn is_buy(&self) -> bool {
+        self.order_side() == OrderSide::Buy
+    }
+
+    #[must_use]
+    pub fn is_sell(&self) -> bool {
+        self.order_side() == OrderSide::Sell
+    }
+
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        dispatch!(self, o => Order::is_open(o))
+    }
+
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        dispatch!(self, o => Order::is_closed(o))
+    }
+
+    #[must_use]
+    pub fn is_inflight(&self) -> bool {
+        dispatch!(self, o => Order::is_inflight(o))
+    }
+
+    #[must_use]
+    pub fn is_active_local(&self) -> bool {
+        dispatch!(self, o => Order::is_active_local(o))
+    }
+
+    #[must_use]
+    pub fn is_passive(&self) -> bool {
+        self.order_type() != OrderType::Market
+    }
+
+    /// Returns whether the order has been triggered (always `false` for non-triggered types).
+    #[must_use]
+    pub fn is_triggered(&self) -> bool {
+        match self {
+            Self::StopMarket(o) => o.is_triggered,
+            Self::StopLimit(o) => o.is_triggered,
+            Self::MarketIfTouched(o) => o.is_triggered,
+            Self::LimitIfTouched(o) => o.is_triggered,
+            Self::TrailingStopMarket(o) => o.is_triggered,
+            Self::TrailingStopLimit(o) => o.is_triggered,
+            _ => false,
+        }
+    }
+
+    /// Returns the order as a passive order type (will be `None` for market orders).
+    #[must_use]
+    pub fn to_passive(&self) -> Option<PassiveOrderType> {
+        match self {
+            Self::Limit(o) => Some(PassiveOrderType::Limit(LimitOrderType::Limit(o.clone()))),
+            Self::MarketToLimit(o) => Some(PassiveOrderType::Limit(LimitOrderType::MarketToLimit(
+                o.clone(),
+            ))),
+            Self::StopMarket(o) => {
+                Some(PassiveOrderType::Stop(StopOrderType::StopMarket(o.clone())))
+            }
+            Self::StopLimit(o) => Some(PassiveOrderType::Stop(StopOrderType::StopLimit(o.clone()))),
+            Self::MarketIfTouched(o) => Some(PassiveOrderType::Stop(
+                StopOrderType::MarketIfTouched(o.clone()),
+            )),
+            Self::LimitIfTouched(o) => Some(PassiveOrderType::Stop(StopOrderType::LimitIfTouched(
+                o.clone(),
+            ))),
+            Self::TrailingStopMarket(o) => Some(PassiveOrderType::Stop(
+                StopOrderType::TrailingStopMarket(o.clone()),
+            )),
+            Self::TrailingStopLimit(o) => Some(PassiveOrderType::Stop(
+                StopOrderType::TrailingStopLimit(o.clone()),
+            )),
+            Self::Market(_) => None,
+        }
+    }
+}
+
+fn initialized_event<T: Order>(order: &T) -> OrderInitialized {
+    OrderInitialized::new(
+        order.trader_id(),
+        order.strategy_id(),
+        order.instrument_id(),
+        order.client_order_id(),
+        order.side(),
+        order.order_type(),
+        order.quantity(),
+        order.time_in_force(),
+        order.is_post_only(),
+        order.is_reduce_only(),
+        order.is_quote_quantity(),
+        false,
+        order.init_id(),
+        order.ts_init(),
+        order.ts_init(),
+        order.price(),
+        order.trigger_price(),
+        order.trigger_type(),
+        order.limit_offset(),
+        order.trailing_offset(),
+        order.trailing_offset_type(),
+        order.expire_time(),
+        order.display_qty(),
+        order.emulation_trigger(),
+        order.trigger_instrument_id(),
+        order.contingency_type(),
+        order.order_list_id(),
+        order.linked_order_ids(),
+        order.parent_order_id(),
+        order.exec_algorithm_id(),
+        order.exec_algorithm_params(),
+        order.exec_spawn_id(),
+        order.tags(),
+    )
+    .unwrap() // SAFETY: All fields were already validated on order construction
+}
+
+impl PartialEq for OrderAny {
+    fn eq(&self, other: &Self) -> bool {
+        self.client_order_id() == other.client_order_id()
+    }
+}
+
+impl From<OrderInitialized> for OrderAny {
+    fn from(event: OrderInitialized) -> Self {
+        match event.order_type {
+            OrderType::Market => Self::Market(MarketOrder::from(event)),
+            OrderType::Limit => Self::Limit(LimitOrder::from(event)),
+            OrderType::StopMarket => Self::StopMarket(StopMarketOrder::from(event)),
+            OrderType::StopLimit => Self::StopLimit(StopLimitOrder::from(event)),
+            OrderType::MarketToLimit => Self::MarketToLimit(MarketToLimitOrder::from(event)),
+            OrderType::MarketIfTouched => Self::MarketIfTouched(MarketIfTouchedOrder::from(event)),
+            OrderType::LimitIfTouched => Self::LimitIfTouched(LimitIfTouchedOrder::from(event)),
+            OrderType::TrailingStopMarket => {
+                Self::TrailingStopMarket(TrailingStopMarketOrder::from(event))
+            }
+            OrderType::TrailingStopLimit => {
+                Self::TrailingStopLimit(TrailingStopLimitOrder::from(event))
+            }
+        }
+    }
+}
+
+impl From<LimitOrder> for OrderAny {
+    fn from(order: LimitOrder) -> Self {
+        Self::Limit(order)
+    }
+}
+
+impl From<LimitIfTouchedOrder> for OrderAny {
+    fn from(order: LimitIfTouchedOrder) -> Self {
+        Self::LimitIfTouched(order)
+    }
+}
+
+impl From<MarketOrder> for OrderAny {
+    fn from(order: MarketOrder) -> Self {
+        Self::Market(order)
+    }
+}
+
+impl From<MarketIfTouchedOrder> for OrderAny {
+    fn from(order: MarketIfTouchedOrder) -> Self {
+        Self::MarketIfTouched(order)
+    }
+}
+
+impl From<MarketToLimitOrder> for OrderAny {
+    fn from(order: MarketToLimitOrder) -> Self {
+        Self::MarketToLimit(order)
+    }
+}
+
+impl From<StopLimitOrder> for OrderAny {
+    fn from(order: StopLimitOrder) -> Self {
+        Self::StopLimit(order)
+    }
+}
+
+impl From<StopMarketOrder> for OrderAny {
+    fn from(order: StopMarketOrder) -> Self {
+        Self::StopMarket(order)
+    }
+}
+
+impl From<TrailingStopLimitOrder> for OrderAny {
+    fn from(order: TrailingStopLimitOrder) -> Self {
+        Self::TrailingStopLimit(order)
+    }
+}
+
+impl From<TrailingStopMarketOrder> for OrderAny {
+    fn from(order: TrailingStopMarketOrder) -> Self {
+        Self::TrailingStopMarket(order)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        identifiers::instrument_id::InstrumentId, orders::stubs::TestOrderStubs,
+        types::quantity::Quantity,
+    };
+
+    #[rstest]
+    fn test_market_order_is_not_passive() {
+        let order = TestOrderStubs::market_order(
+            InstrumentId::from("AAPL.XNAS"),
+            OrderSide::Buy,
+            Quantity::from(100),
+            None,
+            None,
+        );
+        let order = OrderAny::from(order);
+
+        assert!(!order.is_passive());
+        assert!(order.to_passive().is_none());
+        assert_eq!(order.order_type(), OrderType::Market);
+        assert_eq!(order.price(), None);
+    }
+
+    #[rstest]
+    fn test_limit_order_to_passive() {
+        let order = TestOrderStubs::limit_order(
+            InstrumentId::from("AAPL.XNAS"),
+            OrderSide::Sell,
+            Price::from("100.00"),
+            Quantity::from(100),
+            None,
+            None,
+        );
+        let client_order_id = order.client_order_id;
+        let order = OrderAny::from(order);
+
+        assert!(order.is_passive());
+        assert!(order.is_sell());
+        assert_eq!(order.price(), Some(Price::from("100.00")));
+        assert_eq!(order.client_order_id(), client_order_id);
+        assert!(matches!(
+            order.to_passive(),
+            Some(PassiveOrderType::Limit(LimitOrderType::Limit(_)))
+        ));
+    }
+
+    #[rstest]
+    fn test_from_events_replays_order() {
+        let order = TestOrderStubs::limit_order(
+            InstrumentId::from("AAPL.XNAS"),
+            OrderSide::Buy,
+            Price::from("100.00"),
+            Quantity::from(100),
+            None,
+            None,
+        );
+        let order = OrderAny::from(order);
+        let events = vec![OrderEvent::OrderInitialized(order.initialized_event())];
+
+        let rebuilt = OrderAny::from_events(events).unwrap();
+
+        assert_eq!(rebuilt, order);
+        assert_eq!(rebuilt.order_type(), OrderType::Limit);
+        assert_eq!(rebuilt.status(), order.status());
+        assert_eq!(rebuilt.price(), Some(Price::from("100.00")));
+    }
+
+    #[rstest]
+    fn test_from_events_with_no_events() {
+        assert!(OrderAny::from_events(vec![]).is_err());
+    }
+}
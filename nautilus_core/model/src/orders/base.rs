@@ -78,6 +78,7 @@ pub enum OrderError {
     NoPreviousState,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OrderSideFixed {
     /// The order is a BUY.
     Buy = 1,
@@ -85,7 +86,8 @@ pub enum OrderSideFixed {
     Sell = 2,
 }
 
-fn order_side_to_fixed(side: OrderSide) -> OrderSideFixed {
+#[must_use]
+pub fn order_side_to_fixed(side: OrderSide) -> OrderSideFixed {
     match side {
         OrderSide::Buy => OrderSideFixed::Buy,
         OrderSide::Sell => OrderSideFixed::Sell,
@@ -271,6 +273,10 @@ pub fn str_hashmap_to_ustr(h: HashMap<String, String>) -> HashMap<Ustr, Ustr> {
 }
 
 impl OrderStatus {
+    /// Returns the status following the `event`.
+    ///
+    /// Events resolving a pending update or cancel request keep the pending status here,
+    /// which is then reverted to the status prior to the request by the [`OrderCore`] handlers.
     #[rustfmt::skip]
     pub fn transition(&mut self, event: &OrderEvent) -> Result<Self, OrderError> {
         let new_state = match (self, event) {
@@ -296,6 +302,9 @@ impl OrderStatus {
             (Self::Submitted, OrderEvent::OrderAccepted(_)) => Self::Accepted,
             (Self::Submitted, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::Submitted, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::Submitted, OrderEvent::OrderUpdated(_)) => Self::Submitted,
+            (Self::Submitted, OrderEvent::OrderModifyRejected(_)) => Self::Submitted,
+            (Self::Submitted, OrderEvent::OrderCancelRejected(_)) => Self::Submitted,
             (Self::Accepted, OrderEvent::OrderRejected(_)) => Self::Rejected,  // StopLimit order
             (Self::Accepted, OrderEvent::OrderPendingUpdate(_)) => Self::PendingUpdate,
             (Self::Accepted, OrderEvent::OrderPendingCancel(_)) => Self::PendingCancel,
@@ -304,6 +313,9 @@ impl OrderStatus {
             (Self::Accepted, OrderEvent::OrderExpired(_)) => Self::Expired,
             (Self::Accepted, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::Accepted, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::Accepted, OrderEvent::OrderUpdated(_)) => Self::Accepted,
+            (Self::Accepted, OrderEvent::OrderModifyRejected(_)) => Self::Accepted,  // Request no longer pending
+            (Self::Accepted, OrderEvent::OrderCancelRejected(_)) => Self::Accepted,  // Request no longer pending
             (Self::Canceled, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,  // Real world possibility
             (Self::Canceled, OrderEvent::OrderFilled(_)) => Self::Filled,  // Real world possibility
             (Self::PendingUpdate, OrderEvent::OrderRejected(_)) => Self::Rejected,
@@ -315,6 +327,9 @@ impl OrderStatus {
             (Self::PendingUpdate, OrderEvent::OrderPendingCancel(_)) => Self::PendingCancel,
             (Self::PendingUpdate, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::PendingUpdate, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::PendingUpdate, OrderEvent::OrderUpdated(_)) => Self::PendingUpdate,  // Then reverted by `OrderCore::updated`
+            (Self::PendingUpdate, OrderEvent::OrderModifyRejected(_)) => Self::PendingUpdate,  // Then reverted by `OrderCore::modify_rejected`
+            (Self::PendingUpdate, OrderEvent::OrderCancelRejected(_)) => Self::PendingUpdate,
             (Self::PendingCancel, OrderEvent::OrderRejected(_)) => Self::Rejected,
             (Self::PendingCancel, OrderEvent::OrderPendingCancel(_)) => Self::PendingCancel,  // Allow multiple requests
             (Self::PendingCancel, OrderEvent::OrderCanceled(_)) => Self::Canceled,
@@ -322,6 +337,9 @@ impl OrderStatus {
             (Self::PendingCancel, OrderEvent::OrderAccepted(_)) => Self::Accepted,  // Allow failed cancel requests
             (Self::PendingCancel, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::PendingCancel, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::PendingCancel, OrderEvent::OrderUpdated(_)) => Self::PendingCancel,
+            (Self::PendingCancel, OrderEvent::OrderModifyRejected(_)) => Self::PendingCancel,
+            (Self::PendingCancel, OrderEvent::OrderCancelRejected(_)) => Self::PendingCancel,  // Then reverted by `OrderCore::cancel_rejected`
             (Self::Triggered, OrderEvent::OrderRejected(_)) => Self::Rejected,
             (Self::Triggered, OrderEvent::OrderPendingUpdate(_)) => Self::PendingUpdate,
             (Self::Triggered, OrderEvent::OrderPendingCancel(_)) => Self::PendingCancel,
@@ -329,12 +347,18 @@ impl OrderStatus {
             (Self::Triggered, OrderEvent::OrderExpired(_)) => Self::Expired,
             (Self::Triggered, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::Triggered, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::Triggered, OrderEvent::OrderUpdated(_)) => Self::Triggered,
+            (Self::Triggered, OrderEvent::OrderModifyRejected(_)) => Self::Triggered,  // Request no longer pending
+            (Self::Triggered, OrderEvent::OrderCancelRejected(_)) => Self::Triggered,  // Request no longer pending
             (Self::PartiallyFilled, OrderEvent::OrderPendingUpdate(_)) => Self::PendingUpdate,
             (Self::PartiallyFilled, OrderEvent::OrderPendingCancel(_)) => Self::PendingCancel,
             (Self::PartiallyFilled, OrderEvent::OrderCanceled(_)) => Self::Canceled,
             (Self::PartiallyFilled, OrderEvent::OrderExpired(_)) => Self::Expired,
             (Self::PartiallyFilled, OrderEvent::OrderPartiallyFilled(_)) => Self::PartiallyFilled,
             (Self::PartiallyFilled, OrderEvent::OrderFilled(_)) => Self::Filled,
+            (Self::PartiallyFilled, OrderEvent::OrderUpdated(_)) => Self::PartiallyFilled,
+            (Self::PartiallyFilled, OrderEvent::OrderModifyRejected(_)) => Self::PartiallyFilled,  // Request no longer pending
+            (Self::PartiallyFilled, OrderEvent::OrderCancelRejected(_)) => Self::PartiallyFilled,  // Request no longer pending
             _ => return Err(OrderError::InvalidStateTransition),
         };
         Ok(new_state)
@@ -459,15 +483,17 @@ pub trait Order {
     }
 
     fn is_open(&self) -> bool {
-        self.emulation_trigger().is_none()
-            && matches!(
-                self.status(),
-                OrderStatus::Accepted
-                    | OrderStatus::Triggered
-                    | OrderStatus::PendingCancel
-                    | OrderStatus::PendingUpdate
-                    | OrderStatus::PartiallyFilled
-            )
+        matches!(
+            self.emulation_trigger(),
+            None | Some(TriggerType::NoTrigger)
+        ) && matches!(
+            self.status(),
+            OrderStatus::Accepted
+                | OrderStatus::Triggered
+                | OrderStatus::PendingCancel
+                | OrderStatus::PendingUpdate
+                | OrderStatus::PartiallyFilled
+        )
     }
 
     fn is_canceled(&self) -> bool {
@@ -486,11 +512,13 @@ pub trait Order {
     }
 
     fn is_inflight(&self) -> bool {
-        self.emulation_trigger().is_none()
-            && matches!(
-                self.status(),
-                OrderStatus::Submitted | OrderStatus::PendingCancel | OrderStatus::PendingUpdate
-            )
+        matches!(
+            self.emulation_trigger(),
+            None | Some(TriggerType::NoTrigger)
+        ) && matches!(
+            self.status(),
+            OrderStatus::Submitted | OrderStatus::PendingCancel | OrderStatus::PendingUpdate
+        )
     }
 
     fn is_pending_update(&self) -> bool {
@@ -663,7 +691,13 @@ impl OrderCore {
         assert_eq!(self.strategy_id, event.strategy_id());
 
         let new_status = self.status.transition(&event)?;
-        self.previous_status = Some(self.status);
+        if !matches!(
+            self.status,
+            OrderStatus::PendingUpdate | OrderStatus::PendingCancel
+        ) {
+            // Retains the status prior to a pending request, to revert to once it's resolved
+            self.previous_status = Some(self.status);
+        }
         self.status = new_status;
 
         match &event {
@@ -724,12 +758,18 @@ impl OrderCore {
     }
 
     fn modify_rejected(&mut self, _event: &OrderModifyRejected) {
-        self.status = self
-            .previous_status
-            .unwrap_or_else(|| panic!("{}", OrderError::NoPreviousState));
+        if self.status == OrderStatus::PendingUpdate {
+            self.revert_status();
+        }
     }
 
     fn cancel_rejected(&mut self, _event: &OrderCancelRejected) {
+        if self.status == OrderStatus::PendingCancel {
+            self.revert_status();
+        }
+    }
+
+    fn revert_status(&mut self) {
         self.status = self
             .previous_status
             .unwrap_or_else(|| panic!("{}", OrderError::NoPreviousState));
@@ -742,6 +782,9 @@ impl OrderCore {
     fn expired(&mut self, _event: &OrderExpired) {}
 
     fn updated(&mut self, event: &OrderUpdated) {
+        if self.status == OrderStatus::PendingUpdate {
+            self.revert_status();
+        }
         if let Some(venue_order_id) = &event.venue_order_id {
             if self.venue_order_id.is_none()
                 || venue_order_id != self.venue_order_id.as_ref().unwrap()
@@ -862,8 +905,11 @@ mod tests {
     use crate::{
         enums::{OrderSide, OrderStatus, PositionSide},
         events::order::{
-            accepted::OrderAcceptedBuilder, denied::OrderDeniedBuilder, filled::OrderFilledBuilder,
-            initialized::OrderInitializedBuilder, submitted::OrderSubmittedBuilder,
+            accepted::OrderAcceptedBuilder, cancel_rejected::OrderCancelRejectedBuilder,
+            denied::OrderDeniedBuilder, filled::OrderFilledBuilder,
+            initialized::OrderInitializedBuilder, modify_rejected::OrderModifyRejectedBuilder,
+            pending_cancel::OrderPendingCancelBuilder, pending_update::OrderPendingUpdateBuilder,
+            submitted::OrderSubmittedBuilder, updated::OrderUpdatedBuilder,
         },
         orders::market::MarketOrder,
     };
@@ -977,4 +1023,101 @@ mod tests {
         assert_eq!(order.commission(&Currency::USD()), None);
         assert_eq!(order.commissions(), HashMap::new());
     }
+
+    fn accepted_market_order() -> MarketOrder {
+        let mut order: MarketOrder = OrderInitializedBuilder::default().build().unwrap().into();
+        let submitted = OrderSubmittedBuilder::default().build().unwrap();
+        let accepted = OrderAcceptedBuilder::default().build().unwrap();
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+        order.apply(OrderEvent::OrderAccepted(accepted)).unwrap();
+        order
+    }
+
+    #[rstest]
+    fn test_order_cancel_rejected_reverts_pending_cancel() {
+        let mut order = accepted_market_order();
+        let pending_cancel = OrderPendingCancelBuilder::default().build().unwrap();
+        let cancel_rejected = OrderCancelRejectedBuilder::default().build().unwrap();
+
+        order
+            .apply(OrderEvent::OrderPendingCancel(pending_cancel))
+            .unwrap();
+        assert_eq!(order.status(), OrderStatus::PendingCancel);
+        order
+            .apply(OrderEvent::OrderCancelRejected(cancel_rejected))
+            .unwrap();
+
+        assert_eq!(order.status(), OrderStatus::Accepted);
+        assert!(order.is_open());
+    }
+
+    #[rstest]
+    fn test_order_modify_rejected_reverts_pending_update() {
+        let mut order = accepted_market_order();
+        let pending_update = OrderPendingUpdateBuilder::default().build().unwrap();
+        let modify_rejected = OrderModifyRejectedBuilder::default().build().unwrap();
+
+        order
+            .apply(OrderEvent::OrderPendingUpdate(pending_update))
+            .unwrap();
+        order
+            .apply(OrderEvent::OrderModifyRejected(modify_rejected))
+            .unwrap();
+
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
+
+    #[rstest]
+    fn test_order_updated_reverts_pending_update() {
+        let mut order = accepted_market_order();
+        let pending_update = OrderPendingUpdateBuilder::default().build().unwrap();
+        let updated = OrderUpdatedBuilder::default()
+            .quantity(Quantity::from(100_000))
+            .build()
+            .unwrap();
+
+        order
+            .apply(OrderEvent::OrderPendingUpdate(pending_update))
+            .unwrap();
+        order.apply(OrderEvent::OrderUpdated(updated)).unwrap();
+
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
+
+    #[rstest]
+    fn test_order_modify_rejected_when_pending_cancel() {
+        let mut order = accepted_market_order();
+        let pending_update = OrderPendingUpdateBuilder::default().build().unwrap();
+        let pending_cancel = OrderPendingCancelBuilder::default().build().unwrap();
+        let modify_rejected = OrderModifyRejectedBuilder::default().build().unwrap();
+        let cancel_rejected = OrderCancelRejectedBuilder::default().build().unwrap();
+
+        order
+            .apply(OrderEvent::OrderPendingUpdate(pending_update))
+            .unwrap();
+        order
+            .apply(OrderEvent::OrderPendingCancel(pending_cancel))
+            .unwrap();
+        order
+            .apply(OrderEvent::OrderModifyRejected(modify_rejected))
+            .unwrap();
+        assert_eq!(order.status(), OrderStatus::PendingCancel);
+
+        order
+            .apply(OrderEvent::OrderCancelRejected(cancel_rejected))
+            .unwrap();
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
+
+    #[rstest]
+    fn test_order_cancel_rejected_when_no_longer_pending() {
+        let mut order = accepted_market_order();
+        let cancel_rejected = OrderCancelRejectedBuilder::default().build().unwrap();
+
+        order
+            .apply(OrderEvent::OrderCancelRejected(cancel_rejected))
+            .unwrap();
+
+        assert_eq!(order.status(), OrderStatus::Accepted);
+    }
 }
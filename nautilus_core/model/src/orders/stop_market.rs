@@ -326,9 +326,18 @@ impl Order for StopMarketOrder {
             self.update(event);
         };
         let is_order_filled = matches!(event, OrderEvent::OrderFilled(_));
+        let ts_triggered = match event {
+            OrderEvent::OrderTriggered(ref event) => Some(event.ts_event),
+            _ => None,
+        };
 
         self.core.apply(event)?;
 
+        if ts_triggered.is_some() {
+            self.is_triggered = true;
+            self.ts_triggered = ts_triggered;
+        }
+
         if is_order_filled {
             self.core.set_slippage(self.trigger_price);
         };
@@ -23,7 +23,7 @@ use nautilus_core::time::UnixNanos;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    enums::{OrderSide, PositionSide},
+    enums::{LotMatchingPolicy, OrderSide, PositionSide},
     events::order::filled::OrderFilled,
     identifiers::{
         account_id::AccountId, client_order_id::ClientOrderId, instrument_id::InstrumentId,
@@ -34,6 +34,52 @@ use crate::{
     types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
 };
 
+/// Represents an open lot of a position, created by an opening fill.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PositionLot {
+    /// The trade ID of the fill which opened the lot.
+    pub trade_id: TradeId,
+    /// The entry side of the lot.
+    pub side: OrderSide,
+    /// The quantity of the lot which remains open.
+    pub quantity: Quantity,
+    /// The open price of the lot.
+    pub px_open: f64,
+    /// UNIX timestamp (nanoseconds) when the lot was opened.
+    pub ts_opened: UnixNanos,
+}
+
+/// Represents the quantity of a lot closed by a reducing fill.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClosedLot {
+    /// The trade ID of the fill which opened the lot.
+    pub open_trade_id: TradeId,
+    /// The trade ID of the fill which closed the quantity.
+    pub close_trade_id: TradeId,
+    /// The entry side of the lot.
+    pub side: OrderSide,
+    /// The quantity closed.
+    pub quantity: Quantity,
+    /// The open price of the lot.
+    pub px_open: f64,
+    /// The close price of the quantity.
+    pub px_close: f64,
+    /// UNIX timestamp (nanoseconds) when the lot was opened.
+    pub ts_opened: UnixNanos,
+    /// UNIX timestamp (nanoseconds) when the quantity was closed.
+    pub ts_closed: UnixNanos,
+    /// The realized PnL for the quantity closed (excluding commissions).
+    pub realized_pnl: Money,
+}
+
+impl ClosedLot {
+    /// Returns the holding period (nanoseconds) for the quantity closed.
+    #[must_use]
+    pub fn holding_period_ns(&self) -> u64 {
+        self.ts_closed.saturating_sub(self.ts_opened)
+    }
+}
+
 /// Represents a position in a financial market.
 ///
 /// The position ID may be assigned at the trading venue, or can be system
@@ -78,6 +124,14 @@ pub struct Position {
     pub buy_qty: Quantity,
     pub sell_qty: Quantity,
     pub commissions: HashMap<Currency, Money>,
+    #[serde(default)]
+    pub lot_matching_policy: LotMatchingPolicy,
+    #[serde(default)]
+    pub open_lots: Vec<PositionLot>,
+    #[serde(default)]
+    pub closed_lots: Vec<ClosedLot>,
+    #[serde(default)]
+    pub lot_designations: HashMap<TradeId, Vec<TradeId>>,
 }
 
 impl Position {
@@ -120,11 +174,20 @@ impl Position {
             avg_px_close: None,
             realized_return: 0.0,
             realized_pnl: None,
+            lot_matching_policy: LotMatchingPolicy::Fifo,
+            open_lots: Vec::new(),
+            closed_lots: Vec::new(),
+            lot_designations: HashMap::new(),
         };
         item.apply(&fill);
         Ok(item)
     }
 
+    /// Applies the `fill` to the position.
+    ///
+    /// With the `SpecificId` lot matching policy, any quantity not covered by the lots
+    /// designated for the fill closes lots FIFO. Use [`Position::apply_with_lots`] to
+    /// reject such fills instead.
     pub fn apply(&mut self, fill: &OrderFilled) {
         assert!(
             !self.trade_ids.contains(&fill.trade_id),
@@ -149,6 +212,8 @@ impl Position {
             self.avg_px_close = None;
             self.realized_return = 0.0;
             self.realized_pnl = None;
+            self.open_lots.clear();
+            self.closed_lots.clear();
         }
 
         self.events.push(*fill);
@@ -165,6 +230,9 @@ impl Position {
             }
         }
 
+        // Open or close lots (before the signed quantity is updated)
+        self.update_lots(fill);
+
         // Calculate avg prices, points, return, PnL
         if fill.order_side == OrderSide::Buy {
             self.handle_buy_order_fill(fill);
@@ -201,6 +269,19 @@ impl Position {
         self.ts_last = fill.ts_event;
     }
 
+    /// Applies the `fill` to the position, validating any `SpecificId` lot designations.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the fill reduces the position with the `SpecificId`
+    /// lot matching policy, and the lots designated for it do not cover the quantity closed.
+    /// The position is unchanged on error.
+    pub fn apply_with_lots(&mut self, fill: &OrderFilled) -> anyhow::Result<()> {
+        self.check_lot_designations(fill)?;
+        self.apply(fill);
+        Ok(())
+    }
+
     pub fn handle_buy_order_fill(&mut self, fill: &OrderFilled) {
         let mut realized_pnl = if fill.commission.unwrap().currency == self.settlement_currency {
             -fill.commission.unwrap().as_f64()
@@ -271,6 +352,175 @@ impl Position {
         self.sell_qty += last_qty_object;
     }
 
+    /// Sets the policy for matching reducing fills against the open lots.
+    ///
+    /// Only fills applied after the policy is set are matched with it.
+    pub fn set_lot_matching_policy(&mut self, policy: LotMatchingPolicy) {
+        self.lot_matching_policy = policy;
+    }
+
+    /// Designates the open lots (by opening trade ID) to close, in order, for the
+    /// reducing fill with the given `trade_id`.
+    ///
+    /// Designations are only used with the `SpecificId` lot matching policy, where each
+    /// reducing fill must have designated lots covering the quantity it closes.
+    pub fn designate_lots(&mut self, trade_id: TradeId, lot_trade_ids: Vec<TradeId>) {
+        self.lot_designations.insert(trade_id, lot_trade_ids);
+    }
+
+    /// Returns the total realized PnL of the closed lots (excluding commissions).
+    #[must_use]
+    pub fn lots_realized_pnl(&self) -> Money {
+        let raw = self
+            .closed_lots
+            .iter()
+            .map(|lot| lot.realized_pnl.raw)
+            .sum();
+        Money::from_raw(raw, self.settlement_currency)
+    }
+
+    fn is_reducing(&self, fill: &OrderFilled) -> bool {
+        (self.signed_qty > 0.0 && fill.order_side == OrderSide::Sell)
+            || (self.signed_qty < 0.0 && fill.order_side == OrderSide::Buy)
+    }
+
+    fn check_lot_designations(&self, fill: &OrderFilled) -> anyhow::Result<()> {
+        if self.lot_matching_policy != LotMatchingPolicy::SpecificId || !self.is_reducing(fill) {
+            return Ok(());
+        }
+
+        let designated = self
+            .lot_designations
+            .get(&fill.trade_id)
+            .map_or(&[][..], Vec::as_slice);
+        if let Some(trade_id) = designated
+            .iter()
+            .find(|trade_id| !self.open_lots.iter().any(|lot| lot.trade_id == **trade_id))
+        {
+            anyhow::bail!(
+                "Lot {trade_id} designated for fill {} is not open in position {}",
+                fill.trade_id,
+                self.id
+            );
+        }
+
+        let open_raw: u64 = self.open_lots.iter().map(|lot| lot.quantity.raw).sum();
+        let closing_raw = fill.last_qty.raw.min(open_raw);
+        let designated_raw: u64 = self
+            .open_lots
+            .iter()
+            .filter(|lot| designated.contains(&lot.trade_id))
+            .map(|lot| lot.quantity.raw)
+            .sum();
+        anyhow::ensure!(
+            designated_raw >= closing_raw,
+            "Lots designated for fill {} do not cover the {} closed in position {}",
+            fill.trade_id,
+            Quantity::from_raw(closing_raw, self.size_precision)?,
+            self.id
+        );
+        Ok(())
+    }
+
+    fn update_lots(&mut self, fill: &OrderFilled) {
+        let is_reducing = self.is_reducing(fill);
+        let mut remaining_raw = fill.last_qty.raw;
+        let designated = self
+            .lot_designations
+            .remove(&fill.trade_id)
+            .unwrap_or_default();
+
+        if is_reducing {
+            let mut designated = designated.into_iter();
+            while remaining_raw > 0 && !self.open_lots.is_empty() {
+                let index = self.next_lot_index(&mut designated);
+                let lot = &mut self.open_lots[index];
+                let closed_raw = lot.quantity.raw.min(remaining_raw);
+                lot.quantity.raw -= closed_raw;
+                remaining_raw -= closed_raw;
+
+                let lot = *lot;
+                if lot.quantity.raw == 0 {
+                    self.open_lots.remove(index);
+                }
+                let quantity = Quantity::from_raw(closed_raw, self.size_precision).unwrap();
+                let px_close = fill.last_px.as_f64();
+                let pnl = self.calculate_lot_pnl_raw(lot.side, lot.px_open, px_close, quantity);
+                self.closed_lots.push(ClosedLot {
+                    open_trade_id: lot.trade_id,
+                    close_trade_id: fill.trade_id,
+                    side: lot.side,
+                    quantity,
+                    px_open: lot.px_open,
+                    px_close,
+                    ts_opened: lot.ts_opened,
+                    ts_closed: fill.ts_event,
+                    realized_pnl: Money::new(pnl, self.settlement_currency).unwrap(),
+                });
+            }
+        }
+
+        // Any quantity not closing a lot opens a new lot (including when flipping side)
+        if remaining_raw > 0 {
+            self.open_lots.push(PositionLot {
+                trade_id: fill.trade_id,
+                side: fill.order_side,
+                quantity: Quantity::from_raw(remaining_raw, self.size_precision).unwrap(),
+                px_open: fill.last_px.as_f64(),
+                ts_opened: fill.ts_event,
+            });
+        }
+    }
+
+    fn next_lot_index(&self, designated: &mut impl Iterator<Item = TradeId>) -> usize {
+        match self.lot_matching_policy {
+            LotMatchingPolicy::Fifo => 0,
+            LotMatchingPolicy::Lifo => self.open_lots.len() - 1,
+            LotMatchingPolicy::HighestCost => {
+                let mut best = 0;
+                for (i, lot) in self.open_lots.iter().enumerate().skip(1) {
+                    let best_px = self.open_lots[best].px_open;
+                    let is_higher_cost = match lot.side {
+                        OrderSide::Buy => lot.px_open > best_px,
+                        _ => lot.px_open < best_px,
+                    };
+                    if is_higher_cost {
+                        best = i; // Ties keep the earliest lot
+                    }
+                }
+                best
+            }
+            LotMatchingPolicy::SpecificId => designated
+                .find_map(|trade_id| {
+                    self.open_lots
+                        .iter()
+                        .position(|lot| lot.trade_id == trade_id)
+                })
+                .unwrap_or(0), // Falls back to FIFO once the designated lots are exhausted
+        }
+    }
+
+    fn calculate_lot_pnl_raw(
+        &self,
+        side: OrderSide,
+        px_open: f64,
+        px_close: f64,
+        quantity: Quantity,
+    ) -> f64 {
+        let points = if self.is_inverse {
+            match side {
+                OrderSide::Buy => 1.0 / px_open - 1.0 / px_close,
+                _ => 1.0 / px_close - 1.0 / px_open,
+            }
+        } else {
+            match side {
+                OrderSide::Buy => px_close - px_open,
+                _ => px_open - px_close,
+            }
+        };
+        quantity.as_f64() * self.multiplier.as_f64() * points
+    }
+
     #[must_use]
     pub fn calculate_avg_px(&self, qty: f64, avg_pg: f64, last_px: f64, last_qty: f64) -> f64 {
         let start_cost = avg_pg * qty;
@@ -524,7 +774,7 @@ mod tests {
     use rstest::rstest;
 
     use crate::{
-        enums::{LiquiditySide, OrderSide, OrderType, PositionSide},
+        enums::{LiquiditySide, LotMatchingPolicy, OrderSide, OrderType, PositionSide},
         events::order::filled::OrderFilled,
         identifiers::{
             account_id::AccountId, position_id::PositionId, strategy_id::StrategyId, stubs::uuid4,
@@ -535,7 +785,7 @@ mod tests {
             market::MarketOrder,
             stubs::{TestOrderEventStubs, TestOrderStubs},
         },
-        position::Position,
+        position::{Position, PositionLot},
         stubs::*,
         types::{money::Money, price::Price, quantity::Quantity},
     };
@@ -2075,4 +2325,284 @@ mod tests {
         let position = Position::new(audusd_sim, fill).unwrap();
         assert_eq!(position.signed_qty, expected);
     }
+
+    fn lot_fill(
+        instrument: &CurrencyPair,
+        side: OrderSide,
+        quantity: i64,
+        price: &str,
+        trade_id: &str,
+        ts_event: u64,
+    ) -> OrderFilled {
+        let order =
+            TestOrderStubs::market_order(instrument.id, side, Quantity::from(quantity), None, None);
+        OrderFilled::new(
+            order.trader_id,
+            order.strategy_id,
+            order.instrument_id,
+            order.client_order_id,
+            VenueOrderId::from("1"),
+            AccountId::new("SIM-001").unwrap(),
+            TradeId::from(trade_id),
+            side,
+            OrderType::Market,
+            Quantity::from(quantity),
+            Price::from(price),
+            instrument.quote_currency,
+            LiquiditySide::Taker,
+            uuid4(),
+            ts_event,
+            0,
+            false,
+            Some(PositionId::from("P-1")),
+            Some(Money::from("0 USD")),
+        )
+        .unwrap()
+    }
+
+    fn lot_quantities(lots: &[PositionLot]) -> Vec<(TradeId, Quantity)> {
+        lots.iter()
+            .map(|lot| (lot.trade_id, lot.quantity))
+            .collect()
+    }
+
+    fn position_with_three_long_lots(audusd_sim: &CurrencyPair) -> Position {
+        let mut position = Position::new(
+            *audusd_sim,
+            lot_fill(audusd_sim, OrderSide::Buy, 100_000, "1.00000", "T1", 1),
+        )
+        .unwrap();
+        position.apply(&lot_fill(
+            audusd_sim,
+            OrderSide::Buy,
+            100_000,
+            "1.00020",
+            "T2",
+            2,
+        ));
+        position.apply(&lot_fill(
+            audusd_sim,
+            OrderSide::Buy,
+            100_000,
+            "1.00010",
+            "T3",
+            3,
+        ));
+        position
+    }
+
+    #[rstest]
+    #[case(LotMatchingPolicy::Fifo, vec![("T1", 100_000), ("T2", 50_000)], vec![("T2", 50_000), ("T3", 100_000)], "35 USD")]
+    #[case(LotMatchingPolicy::Lifo, vec![("T3", 100_000), ("T2", 50_000)], vec![("T1", 100_000), ("T2", 50_000)], "25 USD")]
+    #[case(LotMatchingPolicy::HighestCost, vec![("T2", 100_000), ("T3", 50_000)], vec![("T1", 100_000), ("T3", 50_000)], "20 USD")]
+    fn test_reducing_fill_closes_lots_by_policy(
+        #[case] policy: LotMatchingPolicy,
+        #[case] expected_closed: Vec<(&str, i64)>,
+        #[case] expected_open: Vec<(&str, i64)>,
+        #[case] expected_pnl: &str,
+        audusd_sim: CurrencyPair,
+    ) {
+        let mut position = position_with_three_long_lots(&audusd_sim);
+        position.set_lot_matching_policy(policy);
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Sell,
+            150_000,
+            "1.00030",
+            "T4",
+            4,
+        ));
+
+        let to_lots = |lots: Vec<(&str, i64)>| -> Vec<(TradeId, Quantity)> {
+            lots.into_iter()
+                .map(|(id, qty)| (TradeId::from(id), Quantity::from(qty)))
+                .collect()
+        };
+        let closed: Vec<(TradeId, Quantity)> = position
+            .closed_lots
+            .iter()
+            .map(|lot| (lot.open_trade_id, lot.quantity))
+            .collect();
+        assert_eq!(closed, to_lots(expected_closed));
+        assert_eq!(lot_quantities(&position.open_lots), to_lots(expected_open));
+        assert_eq!(position.lots_realized_pnl(), Money::from(expected_pnl));
+        // Average cost realized PnL is unchanged by the lot matching policy
+        assert_eq!(position.realized_pnl, Some(Money::from("30 USD")));
+    }
+
+    #[rstest]
+    fn test_reducing_fill_closes_designated_lots(audusd_sim: CurrencyPair) {
+        let mut position = position_with_three_long_lots(&audusd_sim);
+        position.set_lot_matching_policy(LotMatchingPolicy::SpecificId);
+        position.designate_lots(
+            TradeId::from("T4"),
+            vec![TradeId::from("T3"), TradeId::from("T1")],
+        );
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Sell,
+            150_000,
+            "1.00030",
+            "T4",
+            4,
+        ));
+
+        let first = position.closed_lots[0];
+        assert_eq!(first.open_trade_id, TradeId::from("T3"));
+        assert_eq!(first.close_trade_id, TradeId::from("T4"));
+        assert_eq!(first.realized_pnl, Money::from("20 USD"));
+        assert_eq!(first.holding_period_ns(), 1);
+        // The designated lots are closed in order
+        let second = position.closed_lots[1];
+        assert_eq!(second.open_trade_id, TradeId::from("T1"));
+        assert_eq!(second.quantity, Quantity::from(50_000));
+        assert_eq!(second.realized_pnl, Money::from("15 USD"));
+        assert_eq!(second.holding_period_ns(), 3);
+        assert!(position.lot_designations.is_empty());
+        assert_eq!(
+            lot_quantities(&position.open_lots),
+            vec![
+                (TradeId::from("T1"), Quantity::from(50_000)),
+                (TradeId::from("T2"), Quantity::from(100_000)),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_flipping_fill_opens_opposite_lot_and_reopen_resets_lots(audusd_sim: CurrencyPair) {
+        let mut position = Position::new(
+            audusd_sim,
+            lot_fill(&audusd_sim, OrderSide::Buy, 100_000, "1.00000", "T1", 1),
+        )
+        .unwrap();
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Sell,
+            150_000,
+            "1.00010",
+            "T2",
+            2,
+        ));
+        assert_eq!(position.closed_lots.len(), 1);
+        assert_eq!(position.open_lots.len(), 1);
+        assert_eq!(position.open_lots[0].side, OrderSide::Sell);
+        assert_eq!(position.open_lots[0].quantity, Quantity::from(50_000));
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Buy,
+            50_000,
+            "1.00000",
+            "T3",
+            3,
+        ));
+        assert!(position.is_closed());
+        assert!(position.open_lots.is_empty());
+        assert_eq!(position.closed_lots[1].realized_pnl, Money::from("5 USD"));
+        assert_eq!(position.lots_realized_pnl(), Money::from("15 USD"));
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Buy,
+            10_000,
+            "1.00000",
+            "T4",
+            4,
+        ));
+        assert!(position.closed_lots.is_empty());
+        assert_eq!(
+            lot_quantities(&position.open_lots),
+            vec![(TradeId::from("T4"), Quantity::from(10_000))]
+        );
+    }
+
+    #[rstest]
+    #[case(vec!["T9"])]
+    #[case(vec!["T3"])]
+    #[case(vec![])]
+    fn test_reducing_fill_without_designated_lots_covering_quantity_errors(
+        audusd_sim: CurrencyPair,
+        #[case] lot_trade_ids: Vec<&str>,
+    ) {
+        let mut position = position_with_three_long_lots(&audusd_sim);
+        position.set_lot_matching_policy(LotMatchingPolicy::SpecificId);
+        position.designate_lots(
+            TradeId::from("T4"),
+            lot_trade_ids.into_iter().map(TradeId::from).collect(),
+        );
+
+        let result = position.apply_with_lots(&lot_fill(
+            &audusd_sim,
+            OrderSide::Sell,
+            150_000,
+            "1.00030",
+            "T4",
+            4,
+        ));
+
+        assert!(result.is_err());
+        assert_eq!(position.quantity, Quantity::from(300_000));
+        assert_eq!(position.trade_ids.len(), 3);
+        assert!(position.closed_lots.is_empty());
+        assert_eq!(position.open_lots.len(), 3);
+    }
+
+    #[rstest]
+    fn test_reducing_fill_without_designated_lots_covering_quantity_falls_back_to_fifo(
+        audusd_sim: CurrencyPair,
+    ) {
+        let mut position = position_with_three_long_lots(&audusd_sim);
+        position.set_lot_matching_policy(LotMatchingPolicy::SpecificId);
+        position.designate_lots(TradeId::from("T4"), vec![TradeId::from("T3")]);
+
+        position.apply(&lot_fill(
+            &audusd_sim,
+            OrderSide::Sell,
+            150_000,
+            "1.00030",
+            "T4",
+            4,
+        ));
+
+        let closed: Vec<(TradeId, Quantity)> = position
+            .closed_lots
+            .iter()
+            .map(|lot| (lot.open_trade_id, lot.quantity))
+            .collect();
+        assert_eq!(
+            closed,
+            vec![
+                (TradeId::from("T3"), Quantity::from(100_000)),
+                (TradeId::from("T1"), Quantity::from(50_000)),
+            ]
+        );
+        assert_eq!(position.quantity, Quantity::from(150_000));
+    }
+
+    #[rstest]
+    fn test_deserialize_without_lot_fields(audusd_sim: CurrencyPair) {
+        let position = position_with_three_long_lots(&audusd_sim);
+        let mut value = serde_json::to_value(&position).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for key in [
+            "lot_matching_policy",
+            "open_lots",
+            "closed_lots",
+            "lot_designations",
+        ] {
+            fields.remove(key).unwrap();
+        }
+
+        let json = serde_json::to_vec(&value).unwrap();
+        let position: Position = serde_json::from_slice(&json).unwrap();
+
+        assert_eq!(position.lot_matching_policy, LotMatchingPolicy::Fifo);
+        assert!(position.open_lots.is_empty());
+        assert!(position.closed_lots.is_empty());
+        assert!(position.lot_designations.is_empty());
+        assert_eq!(position.quantity, Quantity::from(300_000));
+    }
 }
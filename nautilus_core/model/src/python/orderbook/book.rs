@@ -21,18 +21,17 @@ use crate::{
         delta::OrderBookDelta, deltas::OrderBookDeltas, depth::OrderBookDepth10, order::BookOrder,
         quote::QuoteTick, trade::TradeTick,
     },
-    enums::{BookType, OrderSide},
+    enums::{BookGapPolicy, BookType, OrderSide},
     identifiers::instrument_id::InstrumentId,
-    orderbook::{book_mbp::OrderBookMbp, level::Level},
+    orderbook::{book::OrderBook, level::Level},
     types::{price::Price, quantity::Quantity},
 };
 
 #[pymethods]
-impl OrderBookMbp {
+impl OrderBook {
     #[new]
-    #[pyo3(signature = (instrument_id, top_only=false))]
-    fn py_new(instrument_id: InstrumentId, top_only: bool) -> Self {
-        Self::new(instrument_id, top_only)
+    fn py_new(instrument_id: InstrumentId, book_type: BookType) -> Self {
+        Self::new(instrument_id, book_type)
     }
 
     fn __str__(&self) -> String {
@@ -53,10 +52,7 @@ impl OrderBookMbp {
     #[getter]
     #[pyo3(name = "book_type")]
     fn py_book_type(&self) -> BookType {
-        match self.top_only {
-            true => BookType::L1_MBP,
-            false => BookType::L2_MBP,
-        }
+        self.book_type
     }
 
     #[getter]
@@ -89,11 +85,35 @@ impl OrderBookMbp {
         self.count
     }
 
+    #[getter]
+    #[pyo3(name = "gap_policy")]
+    fn py_gap_policy(&self) -> BookGapPolicy {
+        self.gap_policy
+    }
+
+    #[setter]
+    #[pyo3(name = "gap_policy")]
+    fn py_set_gap_policy(&mut self, gap_policy: BookGapPolicy) {
+        self.gap_policy = gap_policy;
+    }
+
+    #[getter]
+    #[pyo3(name = "is_stale")]
+    fn py_is_stale(&self) -> bool {
+        self.is_stale()
+    }
+
     #[pyo3(name = "reset")]
     fn py_reset(&mut self) {
         self.reset();
     }
 
+    #[pyo3(signature = (order, ts_event, sequence=0))]
+    #[pyo3(name = "add")]
+    fn py_add(&mut self, order: BookOrder, ts_event: UnixNanos, sequence: u64) {
+        self.add(order, ts_event, sequence);
+    }
+
     #[pyo3(signature = (order, ts_event, sequence=0))]
     #[pyo3(name = "update")]
     fn py_update(&mut self, order: BookOrder, ts_event: UnixNanos, sequence: u64) {
@@ -135,18 +155,18 @@ impl OrderBookMbp {
     }
 
     #[pyo3(name = "apply_delta")]
-    fn py_apply_delta(&mut self, delta: OrderBookDelta) {
-        self.apply_delta(delta);
+    fn py_apply_delta(&mut self, delta: OrderBookDelta) -> PyResult<()> {
+        self.apply_delta(delta).map_err(to_pyruntime_err)
     }
 
     #[pyo3(name = "apply_deltas")]
-    fn py_apply_deltas(&mut self, deltas: OrderBookDeltas) {
-        self.apply_deltas(deltas);
+    fn py_apply_deltas(&mut self, deltas: OrderBookDeltas) -> PyResult<()> {
+        self.apply_deltas(deltas).map_err(to_pyruntime_err)
     }
 
     #[pyo3(name = "apply_depth")]
-    fn py_apply_depth(&mut self, depth: OrderBookDepth10) {
-        self.apply_depth(depth);
+    fn py_apply_depth(&mut self, depth: OrderBookDepth10) -> PyResult<()> {
+        self.apply_depth(depth).map_err(to_pyruntime_err)
     }
 
     #[pyo3(name = "check_integrity")]
@@ -13,6 +13,5 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
-pub mod book_mbo;
-pub mod book_mbp;
+pub mod book;
 pub mod level;
@@ -0,0 +1,262 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{fmt::Display, time::Duration};
+
+use rand::Rng;
+use tokio::{sync::Mutex, time::sleep};
+use tracing::{error, warn};
+
+pub const DEFAULT_RECONNECT_DELAY_INITIAL_MS: u64 = 1_000;
+pub const DEFAULT_RECONNECT_DELAY_MAX_MS: u64 = 30_000;
+pub const DEFAULT_RECONNECT_BACKOFF_FACTOR: f64 = 2.0;
+pub const DEFAULT_RECONNECT_JITTER_MS: u64 = 500;
+
+/// Provides the delays between retries of a failing operation, growing exponentially
+/// from an initial delay up to a maximum, with random jitter added to each delay.
+///
+/// Once the maximum number of retries (if any) is reached no further delays are returned.
+#[derive(Clone, Debug)]
+pub struct ExponentialBackoff {
+    delay_initial: Duration,
+    delay_max: Duration,
+    factor: f64,
+    jitter_ms: u64,
+    max_retries: Option<u32>,
+    delay_current: Duration,
+    retries: u32,
+}
+
+impl ExponentialBackoff {
+    /// Creates a new [`ExponentialBackoff`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    /// - `delay_initial` is zero or greater than `delay_max`.
+    /// - `factor` is not finite or less than 1.
+    pub fn new(
+        delay_initial: Duration,
+        delay_max: Duration,
+        factor: f64,
+        jitter_ms: u64,
+        max_retries: Option<u32>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!delay_initial.is_zero(), "`delay_initial` was zero");
+        anyhow::ensure!(
+            delay_initial <= delay_max,
+            "`delay_initial` {delay_initial:?} was greater than `delay_max` {delay_max:?}"
+        );
+        anyhow::ensure!(
+            factor.is_finite() && factor >= 1.0,
+            "`factor` {factor} was not a finite value >= 1"
+        );
+
+        Ok(Self {
+            delay_initial,
+            delay_max,
+            factor,
+            jitter_ms,
+            max_retries,
+            delay_current: delay_initial,
+            retries: 0,
+        })
+    }
+
+    /// Creates a new [`ExponentialBackoff`] instance using the defaults for any
+    /// parameters not given.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the resulting parameters are invalid (see [`Self::new`]).
+    pub fn from_optional(
+        delay_initial_ms: Option<u64>,
+        delay_max_ms: Option<u64>,
+        factor: Option<f64>,
+        jitter_ms: Option<u64>,
+        max_retries: Option<u32>,
+    ) -> anyhow::Result<Self> {
+        Self::new(
+            Duration::from_millis(delay_initial_ms.unwrap_or(DEFAULT_RECONNECT_DELAY_INITIAL_MS)),
+            Duration::from_millis(delay_max_ms.unwrap_or(DEFAULT_RECONNECT_DELAY_MAX_MS)),
+            factor.unwrap_or(DEFAULT_RECONNECT_BACKOFF_FACTOR),
+            jitter_ms.unwrap_or(DEFAULT_RECONNECT_JITTER_MS),
+            max_retries,
+        )
+    }
+
+    /// Returns the number of retries since creation or the last reset.
+    #[must_use]
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Returns the delay before the next retry, or `None` if the maximum number of
+    /// retries has been reached.
+    pub fn next_duration(&mut self) -> Option<Duration> {
+        if self
+            .max_retries
+            .is_some_and(|max_retries| self.retries >= max_retries)
+        {
+            return None;
+        }
+        self.retries += 1;
+
+        let jitter = match self.jitter_ms {
+            0 => Duration::ZERO,
+            jitter_ms => Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_ms)),
+        };
+        let delay = self.delay_current + jitter;
+
+        self.delay_current = self.delay_current.mul_f64(self.factor).min(self.delay_max);
+        Some(delay)
+    }
+
+    /// Resets the backoff to the initial delay with no retries.
+    pub fn reset(&mut self) {
+        self.delay_current = self.delay_initial;
+        self.retries = 0;
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self::from_optional(None, None, None, None, None)
+            .expect("Default backoff config should be valid")
+    }
+}
+
+/// Provides a connection which can be re-established once lost.
+pub(crate) trait Reconnect {
+    type Error: Display;
+
+    /// Returns the backoff between reconnection attempts.
+    fn reconnect_backoff(&self) -> &ExponentialBackoff;
+
+    /// Makes a single attempt to re-establish the connection.
+    async fn reconnect(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Reconnects the `client`, retrying with its exponential backoff.
+///
+/// Returns `true` once reconnected, or `false` if the maximum number of
+/// retries was reached or the client was set to disconnect.
+pub(crate) async fn reconnect_with_backoff<T: Reconnect>(
+    client: &mut T,
+    disconnect_mode: &Mutex<bool>,
+) -> bool {
+    let mut backoff = client.reconnect_backoff().clone();
+    backoff.reset();
+
+    loop {
+        if *disconnect_mode.lock().await {
+            return false;
+        }
+
+        match client.reconnect().await {
+            Ok(()) => return true,
+            Err(e) => match backoff.next_duration() {
+                Some(delay) => {
+                    warn!("Reconnect failed: {e}, retrying in {delay:?}");
+                    sleep(delay).await;
+                }
+                None => {
+                    error!("Reconnect failed after {} retries: {e}", backoff.retries());
+                    return false;
+                }
+            },
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_next_duration_grows_exponentially_up_to_max() {
+        let mut backoff = ExponentialBackoff::new(
+            Duration::from_millis(100),
+            Duration::from_millis(500),
+            2.0,
+            0,
+            None,
+        )
+        .unwrap();
+
+        let delays: Vec<u64> = (0..5)
+            .map(|_| backoff.next_duration().unwrap().as_millis() as u64)
+            .collect();
+
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+        assert_eq!(backoff.retries(), 5);
+    }
+
+    #[rstest]
+    fn test_next_duration_adds_jitter() {
+        let mut backoff = ExponentialBackoff::new(
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+            1.0,
+            50,
+            None,
+        )
+        .unwrap();
+
+        for _ in 0..10 {
+            let delay = backoff.next_duration().unwrap();
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(150));
+        }
+    }
+
+    #[rstest]
+    fn test_next_duration_stops_at_max_retries_until_reset() {
+        let mut backoff = ExponentialBackoff::new(
+            Duration::from_millis(100),
+            Duration::from_millis(500),
+            2.0,
+            0,
+            Some(2),
+        )
+        .unwrap();
+
+        assert!(backoff.next_duration().is_some());
+        assert!(backoff.next_duration().is_some());
+        assert_eq!(backoff.next_duration(), None);
+
+        backoff.reset();
+
+        assert_eq!(backoff.next_duration(), Some(Duration::from_millis(100)));
+    }
+
+    #[rstest]
+    #[case(Duration::ZERO, Duration::from_millis(100), 2.0)]
+    #[case(Duration::from_millis(200), Duration::from_millis(100), 2.0)]
+    #[case(Duration::from_millis(100), Duration::from_millis(200), 0.5)]
+    #[case(Duration::from_millis(100), Duration::from_millis(200), f64::NAN)]
+    fn test_new_with_invalid_config(
+        #[case] delay_initial: Duration,
+        #[case] delay_max: Duration,
+        #[case] factor: f64,
+    ) {
+        assert!(ExponentialBackoff::new(delay_initial, delay_max, factor, 0, None).is_err());
+    }
+}
@@ -17,16 +17,27 @@ use std::{
     collections::{hash_map::DefaultHasher, HashMap},
     hash::{Hash, Hasher},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
 
-use futures_util::{stream, StreamExt};
 use pyo3::{exceptions::PyException, prelude::*, types::PyBytes};
 use reqwest::{
-    header::{HeaderMap, HeaderName},
-    Method, Response, Url,
+    header::{HeaderMap, HeaderName, RETRY_AFTER},
+    Method, Response, StatusCode, Url,
 };
+use tokio::time::sleep;
+use tracing::warn;
 
-use crate::ratelimiter::{clock::MonotonicClock, quota::Quota, RateLimiter};
+use crate::{
+    backoff::ExponentialBackoff,
+    ratelimiter::{clock::MonotonicClock, quota::Quota, RateLimiter},
+};
+
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+const RETRY_DELAY_INITIAL: Duration = Duration::from_millis(500);
+const RETRY_DELAY_MAX: Duration = Duration::from_secs(10);
+const RETRY_BACKOFF_FACTOR: f64 = 2.0;
+const RETRY_JITTER_MS: u64 = 100;
 
 /// Provides a high-performance `HttpClient` for HTTP requests.
 ///
@@ -80,6 +91,11 @@ impl InnerHttpClient {
             .filter_map(|(key, val)| val.to_str().map(|v| (key, v)).ok())
             .map(|(k, v)| (k.clone(), v.to_owned()))
             .collect();
+        let retry_after = res
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|val| val.to_str().ok())
+            .and_then(parse_retry_after);
         let status = res.status().as_u16();
         let bytes = res.bytes().await?;
 
@@ -87,10 +103,24 @@ impl InnerHttpClient {
             status,
             headers,
             body: bytes.to_vec(),
+            retry_after,
         })
     }
 }
 
+/// Parses a `Retry-After` header value given as either delay seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = httpdate::parse_http_date(value.trim()).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "python",
@@ -138,6 +168,18 @@ pub struct HttpResponse {
     #[pyo3(get)]
     headers: HashMap<String, String>,
     body: Vec<u8>,
+    retry_after: Option<Duration>,
+}
+
+impl HttpResponse {
+    /// Returns whether the request should be retried, being rate limited (429) or
+    /// unavailable with a `Retry-After` (503).
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS.as_u16()
+            || (self.status == StatusCode::SERVICE_UNAVAILABLE.as_u16()
+                && self.retry_after.is_some())
+    }
 }
 
 impl Default for InnerHttpClient {
@@ -158,6 +200,7 @@ impl HttpResponse {
             status,
             body,
             headers: Default::default(),
+            retry_after: None,
         }
     }
 
@@ -167,6 +210,9 @@ impl HttpResponse {
     }
 }
 
+/// Provides an HTTP client which rate limits requests by keyed quotas, and retries
+/// requests which are rate limited by the server.
+#[derive(Clone)]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(module = "nautilus_trader.core.nautilus_pyo3.network")
@@ -174,6 +220,55 @@ impl HttpResponse {
 pub struct HttpClient {
     rate_limiter: Arc<RateLimiter<String, MonotonicClock>>,
     client: InnerHttpClient,
+    retry_backoff: ExponentialBackoff,
+}
+
+impl HttpClient {
+    /// Sends an HTTP request once the quota for every one of the `keys` allows it.
+    ///
+    /// Requests which are rate limited by the server are retried up to the maximum
+    /// number of retries, after the `Retry-After` delay if given, otherwise with
+    /// exponential backoff. Each retry waits for the quotas again.
+    ///
+    /// A rate limited response is returned without retrying if its `Retry-After` delay
+    /// is longer than the maximum retry delay.
+    pub async fn request(
+        &self,
+        method: Method,
+        url: String,
+        headers: HashMap<String, String>,
+        body: Option<Vec<u8>>,
+        keys: Vec<String>,
+    ) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut backoff = self.retry_backoff.clone();
+
+        loop {
+            self.rate_limiter.until_keys_ready(&keys).await;
+
+            let res = self
+                .client
+                .send_request(method.clone(), url.clone(), headers.clone(), body.clone())
+                .await?;
+            if !res.is_retryable() {
+                return Ok(res);
+            }
+
+            // The backoff is advanced even when the server gives a delay to count retries
+            let Some(backoff_delay) = backoff.next_duration() else {
+                return Ok(res);
+            };
+            let delay = match res.retry_after {
+                Some(retry_after) if retry_after > RETRY_DELAY_MAX => return Ok(res),
+                Some(retry_after) => retry_after,
+                None => backoff_delay,
+            };
+            warn!(
+                "Request to {url} rate limited with status {}, retrying in {delay:?}",
+                res.status
+            );
+            sleep(delay).await;
+        }
+    }
 }
 
 #[pymethods]
@@ -184,13 +279,15 @@ impl HttpClient {
     /// * `keyed_quota` - A list of string quota pairs that gives quota for specific key values.
     /// * `default_quota` - The default rate limiting quota for any request.
     /// Default quota is optional and no quota is passthrough.
+    /// * `max_retries` - The maximum number of retries for requests rate limited by the server.
     #[new]
-    #[pyo3(signature = (header_keys = Vec::new(), keyed_quotas = Vec::new(), default_quota = None))]
+    #[pyo3(signature = (header_keys = Vec::new(), keyed_quotas = Vec::new(), default_quota = None, max_retries = DEFAULT_MAX_RETRIES))]
     #[must_use]
     pub fn py_new(
         header_keys: Vec<String>,
         keyed_quotas: Vec<(String, Quota)>,
         default_quota: Option<Quota>,
+        max_retries: u32,
     ) -> Self {
         let client = reqwest::Client::new();
         let rate_limiter = Arc::new(RateLimiter::new_with_quota(default_quota, keyed_quotas));
@@ -200,9 +297,19 @@ impl HttpClient {
             header_keys,
         };
 
+        let retry_backoff = ExponentialBackoff::new(
+            RETRY_DELAY_INITIAL,
+            RETRY_DELAY_MAX,
+            RETRY_BACKOFF_FACTOR,
+            RETRY_JITTER_MS,
+            Some(max_retries),
+        )
+        .expect("Retry backoff config should be valid");
+
         Self {
             rate_limiter,
             client,
+            retry_backoff,
         }
     }
 
@@ -226,18 +333,10 @@ impl HttpClient {
         let headers = headers.unwrap_or_default();
         let body_vec = body.map(|py_bytes| py_bytes.as_bytes().to_vec());
         let keys = keys.unwrap_or_default();
-        let client = self.client.clone();
-        let rate_limiter = self.rate_limiter.clone();
+        let client = self.clone();
         let method = method.into();
         pyo3_asyncio::tokio::future_into_py(py, async move {
-            // Check keys for rate limiting quota
-            let tasks = keys.iter().map(|key| rate_limiter.until_key_ready(key));
-            stream::iter(tasks)
-                .for_each(|key| async move {
-                    key.await;
-                })
-                .await;
-            match client.send_request(method, url, headers, body_vec).await {
+            match client.request(method, url, headers, body_vec, keys).await {
                 Ok(res) => Ok(res),
                 Err(e) => Err(PyErr::new::<PyException, _>(format!(
                     "Error handling response: {e}"
@@ -252,13 +351,20 @@ impl HttpClient {
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use std::net::{SocketAddr, TcpListener};
+    use std::{
+        net::{SocketAddr, TcpListener},
+        num::NonZeroU32,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Instant,
+    };
 
     use axum::{
+        response::IntoResponse,
         routing::{delete, get, patch, post},
         serve, Router,
     };
     use http::status::StatusCode;
+    use rstest::rstest;
 
     use super::*;
 
@@ -275,11 +381,49 @@ mod tests {
     }
 
     fn create_router() -> Router {
+        // Rate limited for the first two requests, then succeeds
+        let limited_count = Arc::new(AtomicUsize::new(0));
+
         Router::new()
             .route("/get", get(|| async { "hello-world!" }))
             .route("/post", post(|| async { StatusCode::OK }))
             .route("/patch", patch(|| async { StatusCode::OK }))
             .route("/delete", delete(|| async { StatusCode::OK }))
+            .route(
+                "/limited",
+                get(move || {
+                    let limited_count = limited_count.clone();
+                    async move {
+                        if limited_count.fetch_add(1, Ordering::SeqCst) < 2 {
+                            (
+                                StatusCode::TOO_MANY_REQUESTS,
+                                [(http::header::RETRY_AFTER, "0")],
+                            )
+                                .into_response()
+                        } else {
+                            "hello-world!".into_response()
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/limited-for-hour",
+                get(|| async {
+                    (
+                        StatusCode::TOO_MANY_REQUESTS,
+                        [(http::header::RETRY_AFTER, "3600")],
+                    )
+                }),
+            )
+            .route(
+                "/always-limited",
+                get(|| async {
+                    (
+                        StatusCode::TOO_MANY_REQUESTS,
+                        [(http::header::RETRY_AFTER, "0")],
+                    )
+                }),
+            )
     }
 
     async fn start_test_server() -> Result<SocketAddr, Box<dyn std::error::Error + Send + Sync>> {
@@ -405,4 +549,111 @@ mod tests {
 
         assert_eq!(response.status, StatusCode::OK);
     }
+
+    #[rstest]
+    #[case("120", Some(Duration::from_secs(120)))]
+    #[case(" 0 ", Some(Duration::ZERO))]
+    #[case("Wed, 21 Oct 2015 07:28:00 GMT", Some(Duration::ZERO))]
+    #[case("soon", None)]
+    fn test_parse_retry_after(#[case] value: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(parse_retry_after(value), expected);
+    }
+
+    #[tokio::test]
+    async fn test_request_retries_when_rate_limited() {
+        let addr = start_test_server().await.unwrap();
+        let url = format!("http://{addr}");
+
+        let client = HttpClient::py_new(vec![], vec![], None, DEFAULT_MAX_RETRIES);
+        let response = client
+            .request(
+                reqwest::Method::GET,
+                format!("{url}/limited"),
+                HashMap::new(),
+                None,
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(String::from_utf8_lossy(&response.body), "hello-world!");
+    }
+
+    #[tokio::test]
+    async fn test_request_returns_rate_limited_response_after_max_retries() {
+        let addr = start_test_server().await.unwrap();
+        let url = format!("http://{addr}");
+
+        let client = HttpClient::py_new(vec![], vec![], None, 1);
+        let response = client
+            .request(
+                reqwest::Method::GET,
+                format!("{url}/always-limited"),
+                HashMap::new(),
+                None,
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.retry_after, Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_request_returns_rate_limited_response_when_retry_after_exceeds_max_delay() {
+        let addr = start_test_server().await.unwrap();
+        let url = format!("http://{addr}");
+
+        let client = HttpClient::py_new(vec![], vec![], None, DEFAULT_MAX_RETRIES);
+        let start = Instant::now();
+        let response = client
+            .request(
+                reqwest::Method::GET,
+                format!("{url}/limited-for-hour"),
+                HashMap::new(),
+                None,
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        assert!(start.elapsed() < RETRY_DELAY_MAX);
+        assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.retry_after, Some(Duration::from_secs(3600)));
+    }
+
+    #[tokio::test]
+    async fn test_request_waits_for_keyed_quota() {
+        let addr = start_test_server().await.unwrap();
+        let url = format!("http://{addr}");
+
+        let quota = Quota::with_period(Duration::from_millis(200))
+            .unwrap()
+            .allow_burst(NonZeroU32::new(1).unwrap());
+        let client = HttpClient::py_new(
+            vec![],
+            vec![("endpoint".to_string(), quota)],
+            None,
+            DEFAULT_MAX_RETRIES,
+        );
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            let response = client
+                .request(
+                    reqwest::Method::GET,
+                    format!("{url}/get"),
+                    HashMap::new(),
+                    None,
+                    vec!["endpoint".to_string()],
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status, StatusCode::OK);
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
 }
@@ -13,6 +13,7 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod backoff;
 pub mod http;
 #[allow(dead_code)]
 mod ratelimiter;
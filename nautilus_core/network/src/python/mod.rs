@@ -14,9 +14,20 @@
 // -------------------------------------------------------------------------------------------------
 
 use pyo3::prelude::*;
+use tracing::{debug, error};
 
 use crate::{http, ratelimiter, socket, websocket};
 
+/// Calls the optional Python `handler` (such as a connection event handler), logging any error.
+pub(crate) fn call_handler(handler: Option<&PyObject>, name: &str) {
+    if let Some(handler) = handler {
+        Python::with_gil(|py| match handler.call0(py) {
+            Ok(_) => debug!("Called `{name}` handler"),
+            Err(e) => error!("Error calling `{name}` handler: {e}"),
+        });
+    }
+}
+
 /// Loaded as nautilus_pyo3.network
 #[pymodule]
 pub fn network(_: Python<'_>, m: &PyModule) -> PyResult<()> {
@@ -135,6 +135,33 @@ impl Gcra {
         t0 + self.t
     }
 
+    /// Tests a single cell against the rate limiter state at the given key, without
+    /// updating it.
+    pub(crate) fn test<K, S: StateStore<Key = K>, P: clock::Reference>(
+        &self,
+        start: P,
+        key: &K,
+        state: &S,
+        t0: P,
+    ) -> Result<(), NotUntil<P>> {
+        let t0 = t0.duration_since(start);
+        state.measure_and_replace(key, |tat| {
+            // Replaced with the same state (an absent state is kept absent)
+            let Some(tat) = tat else {
+                return Ok(((), Nanos::from(0)));
+            };
+            let earliest_time = tat.saturating_sub(self.tau);
+            if t0 < earliest_time {
+                Err(NotUntil::new(
+                    StateSnapshot::new(self.t, self.tau, earliest_time, earliest_time),
+                    start,
+                ))
+            } else {
+                Ok(((), tat))
+            }
+        })
+    }
+
     /// Tests a single cell against the rate limiter state and updates it at the given key.
     pub(crate) fn test_and_update<K, S: StateStore<Key = K>, P: clock::Reference>(
         &self,
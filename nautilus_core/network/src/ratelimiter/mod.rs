@@ -24,7 +24,10 @@ pub mod quota;
 use std::{
     hash::Hash,
     num::NonZeroU64,
-    sync::atomic::{AtomicU64, Ordering},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
     time::Duration,
 };
 
@@ -134,6 +137,8 @@ where
     gcra: DashMap<K, Gcra>,
     clock: C,
     start: C::Instant,
+    /// Serializes the checks which consume cells, so multiple keys are consumed atomically.
+    check_lock: Mutex<()>,
 }
 
 impl<K> RateLimiter<K, MonotonicClock>
@@ -150,6 +155,7 @@ where
             gcra,
             clock,
             start,
+            check_lock: Mutex::new(()),
         }
     }
 }
@@ -173,24 +179,73 @@ where
     }
 
     pub fn check_key(&self, key: &K) -> Result<(), NotUntil<C::Instant>> {
+        let _guard = self.check_lock.lock().expect("Poisoned lock");
+        self.test_and_update_key(key, self.clock.now())
+    }
+
+    /// Consumes one cell for each of the `keys` only if the quotas for all of them allow
+    /// a request, otherwise consumes none and returns the longest wait until they would.
+    pub fn check_keys(&self, keys: &[K]) -> Result<(), Duration> {
+        let _guard = self.check_lock.lock().expect("Poisoned lock");
+        let now = self.clock.now();
+
+        let wait = keys
+            .iter()
+            .filter_map(|key| self.test_key(key, now).err())
+            .map(|neg| neg.wait_time_from(now))
+            .max();
+        if let Some(wait) = wait {
+            return Err(wait);
+        }
+
+        for key in keys {
+            // Only fails where a key is repeated beyond its burst capacity
+            let _ = self.test_and_update_key(key, now);
+        }
+        Ok(())
+    }
+
+    fn test_key(&self, key: &K, now: C::Instant) -> Result<(), NotUntil<C::Instant>> {
         match self.gcra.get(key) {
-            Some(quota) => quota.test_and_update(self.start, key, &self.state, self.clock.now()),
+            Some(quota) => quota.test(self.start, key, &self.state, now),
+            None => self
+                .default_gcra
+                .as_ref()
+                .map_or(Ok(()), |gcra| gcra.test(self.start, key, &self.state, now)),
+        }
+    }
+
+    fn test_and_update_key(&self, key: &K, now: C::Instant) -> Result<(), NotUntil<C::Instant>> {
+        match self.gcra.get(key) {
+            Some(quota) => quota.test_and_update(self.start, key, &self.state, now),
             None => self.default_gcra.as_ref().map_or(Ok(()), |gcra| {
-                gcra.test_and_update(self.start, key, &self.state, self.clock.now())
+                gcra.test_and_update(self.start, key, &self.state, now)
             }),
         }
     }
 
+    /// Waits until the quota for the `key` allows a request, consuming one cell.
     pub async fn until_key_ready(&self, key: &K) {
         loop {
             match self.check_key(key) {
-                Ok(x) => x,
+                Ok(()) => return,
                 Err(neg) => {
                     sleep(neg.wait_time_from(self.clock.now())).await;
                 }
             }
         }
     }
+
+    /// Waits until the quota for every one of the `keys` allows a request, then consumes
+    /// one cell for each of them together.
+    ///
+    /// No cells are consumed while waiting, so a key which is ready does not have its
+    /// quota spent waiting on the others.
+    pub async fn until_keys_ready(&self, keys: &[K]) {
+        while let Err(wait) = self.check_keys(keys) {
+            sleep(wait).await;
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -220,9 +275,57 @@ mod tests {
             gcra,
             clock,
             start,
+            check_lock: std::sync::Mutex::new(()),
         }
     }
 
+    #[test]
+    fn test_check_keys_consumes_none_until_all_ready() {
+        let mock_limiter = initialize_mock_rate_limiter();
+        let (a, b) = ("a".to_string(), "b".to_string());
+        mock_limiter.add_quota_for_key(a.clone(), Quota::per_second(NonZeroU32::new(1).unwrap()));
+        assert!(mock_limiter.check_key(&a).is_ok());
+
+        let keys = vec![a.clone(), b.clone()];
+        let wait = mock_limiter.check_keys(&keys).unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+
+        // The default quota for `b` (two per second) was not consumed
+        assert!(mock_limiter.check_key(&b).is_ok());
+        assert!(mock_limiter.check_key(&b).is_ok());
+        assert!(mock_limiter.check_key(&b).is_err());
+
+        mock_limiter.advance_clock(Duration::from_secs(1));
+        assert!(mock_limiter.check_keys(&keys).is_ok());
+        assert!(mock_limiter.check_key(&a).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_until_keys_ready_waits_for_each_quota() {
+        let limiter = RateLimiter::new_with_quota(
+            None,
+            vec![
+                (
+                    "a".to_string(),
+                    Quota::per_second(NonZeroU32::new(1).unwrap()),
+                ),
+                (
+                    "b".to_string(),
+                    Quota::with_period(Duration::from_millis(200)).unwrap(),
+                ),
+            ],
+        );
+        let keys = vec!["a".to_string(), "b".to_string()];
+        let start = std::time::Instant::now();
+
+        limiter.until_keys_ready(&keys).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // The slowest quota (one per second) governs the wait
+        limiter.until_keys_ready(&keys).await;
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
     #[test]
     fn test_default_quota() {
         let mock_limiter = initialize_mock_rate_limiter();
@@ -15,7 +15,7 @@
 
 use std::{sync::Arc, time::Duration};
 
-use nautilus_core::python::to_pyruntime_err;
+use nautilus_core::python::{to_pyruntime_err, to_pyvalue_err};
 use pyo3::prelude::*;
 use tokio::{
     io::{split, AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf},
@@ -29,7 +29,12 @@ use tokio_tungstenite::{
     tungstenite::{client::IntoClientRequest, stream::Mode, Error},
     MaybeTlsStream,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
+
+use crate::{
+    backoff::{reconnect_with_backoff, ExponentialBackoff, Reconnect},
+    python::call_handler,
+};
 
 type TcpWriter = WriteHalf<MaybeTlsStream<TcpStream>>;
 type SharedTcpWriter = Arc<Mutex<WriteHalf<MaybeTlsStream<TcpStream>>>>;
@@ -52,26 +57,48 @@ pub struct SocketConfig {
     handler: PyObject,
     /// The optional heartbeat with period and beat message.
     heartbeat: Option<(u64, Vec<u8>)>,
+    /// The messages to send after each connection (such as auth and subscriptions).
+    post_connect_msgs: Vec<Vec<u8>>,
+    /// The backoff between reconnection attempts.
+    reconnect_backoff: ExponentialBackoff,
 }
 
 #[pymethods]
 impl SocketConfig {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     fn py_new(
         url: String,
         ssl: bool,
         suffix: Vec<u8>,
         handler: PyObject,
         heartbeat: Option<(u64, Vec<u8>)>,
-    ) -> Self {
+        post_connect_msgs: Option<Vec<Vec<u8>>>,
+        reconnect_delay_initial_ms: Option<u64>,
+        reconnect_delay_max_ms: Option<u64>,
+        reconnect_backoff_factor: Option<f64>,
+        reconnect_jitter_ms: Option<u64>,
+        reconnect_max_retries: Option<u32>,
+    ) -> PyResult<Self> {
         let mode = if ssl { Mode::Tls } else { Mode::Plain };
-        Self {
+        let reconnect_backoff = ExponentialBackoff::from_optional(
+            reconnect_delay_initial_ms,
+            reconnect_delay_max_ms,
+            reconnect_backoff_factor,
+            reconnect_jitter_ms,
+            reconnect_max_retries,
+        )
+        .map_err(to_pyvalue_err)?;
+
+        Ok(Self {
             url,
             mode,
             suffix,
             handler,
             heartbeat,
-        }
+            post_connect_msgs: post_connect_msgs.unwrap_or_default(),
+            reconnect_backoff,
+        })
     }
 }
 
@@ -109,8 +136,11 @@ impl SocketClientInner {
             heartbeat,
             suffix,
             handler,
+            post_connect_msgs,
+            ..
         } = &config;
-        let (reader, writer) = Self::tls_connect_with_server(url, *mode).await?;
+        let (reader, mut writer) = Self::tls_connect_with_server(url, *mode).await?;
+        Self::send_post_connect_msgs(&mut writer, post_connect_msgs, suffix).await?;
         let shared_writer = Arc::new(Mutex::new(writer));
 
         // Keep receiving messages from socket pass them as arguments to handler
@@ -187,6 +217,22 @@ impl SocketClientInner {
         })
     }
 
+    /// Sends the configured post-connect messages (such as auth and subscriptions) to the server.
+    pub async fn send_post_connect_msgs(
+        writer: &mut TcpWriter,
+        post_connect_msgs: &[Vec<u8>],
+        suffix: &[u8],
+    ) -> Result<(), std::io::Error> {
+        for msg in post_connect_msgs {
+            writer.write_all(msg).await?;
+            writer.write_all(suffix).await?;
+        }
+        if !post_connect_msgs.is_empty() {
+            debug!("Sent {} post-connect message(s)", post_connect_msgs.len());
+        }
+        Ok(())
+    }
+
     /// Optionally spawn a heartbeat task to periodically ping the server.
     pub fn spawn_heartbeat_task(
         heartbeat: Option<(u64, Vec<u8>)>,
@@ -238,7 +284,11 @@ impl SocketClientInner {
     /// Reconnect with server.
     ///
     /// Make a new connection with server. Use the new read and write halves
-    /// to update the shared writer and the read and heartbeat tasks.
+    /// to update the shared writer and the read and heartbeat tasks, then re-send
+    /// the post-connect messages.
+    ///
+    /// The writer is locked until the post-connect messages are sent, so that
+    /// they are sent on the new connection before any other message.
     ///
     /// TODO: fix error type
     pub async fn reconnect(&mut self) -> Result<(), Error> {
@@ -248,13 +298,21 @@ impl SocketClientInner {
             heartbeat,
             suffix,
             handler,
+            post_connect_msgs,
+            ..
         } = &self.config;
         debug!("Reconnecting client");
         let (reader, new_writer) = Self::tls_connect_with_server(url, *mode).await?;
 
+        // Abort the previous heartbeat task which holds the shared writer
+        if let Some(ref handle) = self.heartbeat_task.take() {
+            handle.abort();
+        }
+
         debug!("Use new writer end");
         let mut guard = self.writer.lock().await;
         *guard = new_writer;
+        Self::send_post_connect_msgs(&mut guard, post_connect_msgs, suffix).await?;
         drop(guard);
 
         debug!("Recreate reader and heartbeat task");
@@ -277,6 +335,18 @@ impl SocketClientInner {
     }
 }
 
+impl Reconnect for SocketClientInner {
+    type Error = Error;
+
+    fn reconnect_backoff(&self) -> &ExponentialBackoff {
+        &self.config.reconnect_backoff
+    }
+
+    async fn reconnect(&mut self) -> Result<(), Error> {
+        Self::reconnect(self).await
+    }
+}
+
 impl Drop for SocketClientInner {
     fn drop(&mut self) {
         if !self.read_task.is_finished() {
@@ -321,12 +391,7 @@ impl SocketClient {
             post_disconnection,
         );
 
-        if let Some(handler) = post_connection {
-            Python::with_gil(|py| match handler.call0(py) {
-                Ok(_) => debug!("Called `post_connection` handler"),
-                Err(e) => error!("Error calling `post_connection` handler: {e}"),
-            });
-        }
+        call_handler(post_connection.as_ref(), "post_connection");
 
         Ok(Self {
             writer,
@@ -372,38 +437,24 @@ impl SocketClient {
                 drop(guard);
 
                 match (disconnect_flag, inner.is_alive()) {
-                    (false, false) => match inner.reconnect().await {
-                        Ok(()) => {
+                    (false, false) => {
+                        warn!("Connection lost - reconnecting");
+                        call_handler(post_disconnection.as_ref(), "post_disconnection");
+
+                        if reconnect_with_backoff(&mut inner, &disconnect_mode).await {
                             debug!("Reconnected successfully");
-                            if let Some(ref handler) = post_reconnection {
-                                Python::with_gil(|py| match handler.call0(py) {
-                                    Ok(_) => debug!("Called `post_reconnection` handler"),
-                                    Err(e) => {
-                                        error!("Error calling `post_reconnection` handler: {e}");
-                                    }
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            error!("Reconnect failed {e}");
+                            call_handler(post_reconnection.as_ref(), "post_reconnection");
+                        } else {
                             break;
                         }
-                    },
+                    }
                     (true, true) => {
                         debug!("Shutting down inner client");
                         match inner.shutdown().await {
                             Ok(()) => debug!("Closed connection"),
                             Err(e) => error!("Error on `shutdown`: {e}"),
                         }
-
-                        if let Some(ref handler) = post_disconnection {
-                            Python::with_gil(|py| match handler.call0(py) {
-                                Ok(_) => debug!("Called `post_disconnection` handler"),
-                                Err(e) => {
-                                    error!("Error calling `post_disconnection` handler: {e}");
-                                }
-                            });
-                        }
+                        call_handler(post_disconnection.as_ref(), "post_disconnection");
                         break;
                     }
                     (true, false) => break,
@@ -515,7 +566,10 @@ mod tests {
     use tracing::debug;
     use tracing_test::traced_test;
 
-    use crate::socket::{SocketClient, SocketConfig};
+    use crate::{
+        backoff::ExponentialBackoff,
+        socket::{SocketClient, SocketConfig},
+    };
 
     struct TestServer {
         task: JoinHandle<()>,
@@ -626,6 +680,8 @@ counter = Counter()",
             mode: Mode::Plain,
             suffix: b"\r\n".to_vec(),
             heartbeat: None,
+            post_connect_msgs: vec![],
+            reconnect_backoff: ExponentialBackoff::default(),
         };
         let client: SocketClient = SocketClient::connect(config, None, None, None)
             .await
@@ -683,4 +739,88 @@ counter = Counter()",
         sleep(Duration::from_secs(1)).await;
         assert!(client.is_disconnected());
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconnect_resends_post_connect_msgs_test() {
+        prepare_freethreaded_python();
+
+        let server = TestServer::basic_client_test().await;
+
+        let (counter, handler, on_reconnect) = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(
+                py,
+                r"
+class Counter:
+    def __init__(self):
+        self.count = 0
+        self.reconnects = 0
+
+    def handler(self, bytes):
+        if bytes.decode().rstrip() == 'subscribe':
+            self.count = self.count + 1
+
+    def on_reconnect(self):
+        self.reconnects = self.reconnects + 1
+
+    def get_counts(self):
+        return (self.count, self.reconnects)
+
+counter = Counter()",
+                "",
+                "",
+            )
+            .unwrap();
+
+            let counter = pymod.getattr("counter").unwrap().into_py(py);
+            let handler = counter.getattr(py, "handler").unwrap().into_py(py);
+            let on_reconnect = counter.getattr(py, "on_reconnect").unwrap().into_py(py);
+
+            (counter, handler, on_reconnect)
+        });
+        let get_counts = || -> (usize, usize) {
+            Python::with_gil(|py| {
+                counter
+                    .getattr(py, "get_counts")
+                    .unwrap()
+                    .call0(py)
+                    .unwrap()
+                    .extract(py)
+                    .unwrap()
+            })
+        };
+
+        let config = SocketConfig {
+            url: format!("127.0.0.1:{}", server.port),
+            handler,
+            mode: Mode::Plain,
+            suffix: b"\r\n".to_vec(),
+            heartbeat: None,
+            post_connect_msgs: vec![b"subscribe".to_vec()],
+            reconnect_backoff: ExponentialBackoff::from_optional(
+                Some(100),
+                None,
+                None,
+                Some(0),
+                None,
+            )
+            .unwrap(),
+        };
+        let client = SocketClient::connect(config, None, Some(on_reconnect), None)
+            .await
+            .unwrap();
+
+        // Post-connect message is echoed back on connection
+        sleep(Duration::from_millis(500)).await;
+        assert_eq!(get_counts(), (1, 0));
+
+        // Server drops the connection, client reconnects and re-sends
+        let _ = client.send_bytes(b"close".as_slice()).await;
+        sleep(Duration::from_secs(2)).await;
+        assert_eq!(get_counts(), (2, 1));
+
+        client.disconnect().await;
+        sleep(Duration::from_secs(1)).await;
+        assert!(client.is_disconnected());
+    }
 }
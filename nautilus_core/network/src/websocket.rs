@@ -28,7 +28,12 @@ use tokio_tungstenite::{
     tungstenite::{client::IntoClientRequest, http::HeaderValue, Error, Message},
     MaybeTlsStream, WebSocketStream,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
+
+use crate::{
+    backoff::{reconnect_with_backoff, ExponentialBackoff, Reconnect},
+    python::call_handler,
+};
 
 type MessageWriter = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
 type SharedMessageWriter =
@@ -47,11 +52,14 @@ pub struct WebSocketConfig {
     heartbeat: Option<u64>,
     heartbeat_msg: Option<String>,
     ping_handler: Option<PyObject>,
+    post_connect_msgs: Vec<String>,
+    reconnect_backoff: ExponentialBackoff,
 }
 
 #[pymethods]
 impl WebSocketConfig {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     fn py_new(
         url: String,
         handler: PyObject,
@@ -59,15 +67,32 @@ impl WebSocketConfig {
         heartbeat: Option<u64>,
         heartbeat_msg: Option<String>,
         ping_handler: Option<PyObject>,
-    ) -> Self {
-        Self {
+        post_connect_msgs: Option<Vec<String>>,
+        reconnect_delay_initial_ms: Option<u64>,
+        reconnect_delay_max_ms: Option<u64>,
+        reconnect_backoff_factor: Option<f64>,
+        reconnect_jitter_ms: Option<u64>,
+        reconnect_max_retries: Option<u32>,
+    ) -> PyResult<Self> {
+        let reconnect_backoff = ExponentialBackoff::from_optional(
+            reconnect_delay_initial_ms,
+            reconnect_delay_max_ms,
+            reconnect_backoff_factor,
+            reconnect_jitter_ms,
+            reconnect_max_retries,
+        )
+        .map_err(to_pyvalue_err)?;
+
+        Ok(Self {
             url,
             handler,
             headers,
             heartbeat,
             heartbeat_msg,
             ping_handler,
-        }
+            post_connect_msgs: post_connect_msgs.unwrap_or_default(),
+            reconnect_backoff,
+        })
     }
 }
 
@@ -103,8 +128,11 @@ impl WebSocketClientInner {
             headers,
             heartbeat_msg,
             ping_handler,
+            post_connect_msgs,
+            ..
         } = &config;
-        let (writer, reader) = Self::connect_with_server(url, headers.clone()).await?;
+        let (mut writer, reader) = Self::connect_with_server(url, headers.clone()).await?;
+        Self::send_post_connect_msgs(&mut writer, post_connect_msgs).await?;
         let writer = Arc::new(Mutex::new(writer));
 
         // Keep receiving messages from socket and pass them as arguments to handler
@@ -141,6 +169,20 @@ impl WebSocketClientInner {
         connect_async(request).await.map(|resp| resp.0.split())
     }
 
+    /// Sends the configured post-connect messages (such as auth and subscriptions) to the server.
+    pub async fn send_post_connect_msgs(
+        writer: &mut MessageWriter,
+        post_connect_msgs: &[String],
+    ) -> Result<(), Error> {
+        for msg in post_connect_msgs {
+            writer.send(Message::Text(msg.clone())).await?;
+        }
+        if !post_connect_msgs.is_empty() {
+            debug!("Sent {} post-connect message(s)", post_connect_msgs.len());
+        }
+        Ok(())
+    }
+
     /// Optionally spawn a hearbeat task to periodically ping the server.
     pub fn spawn_heartbeat_task(
         heartbeat: Option<u64>,
@@ -264,12 +306,23 @@ impl WebSocketClientInner {
     /// Reconnect with server.
     ///
     /// Make a new connection with server. Use the new read and write halves
-    /// to update self writer and read and heartbeat tasks.
+    /// to update self writer and read and heartbeat tasks, then re-send the
+    /// post-connect messages.
+    ///
+    /// The writer is locked until the post-connect messages are sent, so that
+    /// they are sent on the new connection before any other message.
     pub async fn reconnect(&mut self) -> Result<(), Error> {
         let (new_writer, reader) =
             Self::connect_with_server(&self.config.url, self.config.headers.clone()).await?;
+
+        // Abort the previous heartbeat task which holds the shared writer
+        if let Some(ref handle) = self.heartbeat_task.take() {
+            handle.abort();
+        }
+
         let mut guard = self.writer.lock().await;
         *guard = new_writer;
+        Self::send_post_connect_msgs(&mut guard, &self.config.post_connect_msgs).await?;
         drop(guard);
 
         self.read_task = Self::spawn_read_task(
@@ -298,6 +351,18 @@ impl WebSocketClientInner {
     }
 }
 
+impl Reconnect for WebSocketClientInner {
+    type Error = Error;
+
+    fn reconnect_backoff(&self) -> &ExponentialBackoff {
+        &self.config.reconnect_backoff
+    }
+
+    async fn reconnect(&mut self) -> Result<(), Error> {
+        Self::reconnect(self).await
+    }
+}
+
 impl Drop for WebSocketClientInner {
     fn drop(&mut self) {
         if !self.read_task.is_finished() {
@@ -345,12 +410,7 @@ impl WebSocketClient {
             post_disconnection,
         );
 
-        if let Some(handler) = post_connection {
-            Python::with_gil(|py| match handler.call0(py) {
-                Ok(_) => debug!("Called `post_connection` handler"),
-                Err(e) => error!("Error calling `post_connection` handler: {e}"),
-            });
-        };
+        call_handler(post_connection.as_ref(), "post_connection");
 
         Ok(Self {
             writer,
@@ -404,34 +464,21 @@ impl WebSocketClient {
                 drop(guard);
 
                 match (disconnect_flag, inner.is_alive()) {
-                    (false, false) => match inner.reconnect().await {
-                        Ok(()) => {
+                    (false, false) => {
+                        warn!("Connection lost - reconnecting");
+                        call_handler(post_disconnection.as_ref(), "post_disconnection");
+
+                        if reconnect_with_backoff(&mut inner, &disconnect_mode).await {
                             debug!("Reconnected successfully");
-                            if let Some(ref handler) = post_reconnection {
-                                Python::with_gil(|py| match handler.call0(py) {
-                                    Ok(_) => debug!("Called `post_reconnection` handler"),
-                                    Err(e) => {
-                                        error!("Error calling `post_reconnection` handler: {e}");
-                                    }
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            error!("Reconnect failed {e}");
+                            call_handler(post_reconnection.as_ref(), "post_reconnection");
+                        } else {
                             break;
                         }
-                    },
+                    }
                     (true, true) => {
                         debug!("Shutting down inner client");
                         inner.shutdown().await;
-                        if let Some(ref handler) = post_disconnection {
-                            Python::with_gil(|py| match handler.call0(py) {
-                                Ok(_) => debug!("Called `post_reconnection` handler"),
-                                Err(e) => {
-                                    error!("Error calling `post_reconnection` handler: {e}");
-                                }
-                            });
-                        }
+                        call_handler(post_disconnection.as_ref(), "post_disconnection");
                         break;
                     }
                     (true, false) => break,
@@ -711,7 +758,14 @@ counter = Counter()",
             None,
             None,
             None,
-        );
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
         let client = WebSocketClient::connect(config, None, None, None)
             .await
             .unwrap();
@@ -816,7 +870,14 @@ checker = Checker()",
             Some(1),
             Some("heartbeat message".to_string()),
             None,
-        );
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
         let client = WebSocketClient::connect(config, None, None, None)
             .await
             .unwrap();
@@ -839,4 +900,98 @@ checker = Checker()",
         sleep(Duration::from_secs(1)).await;
         assert!(client.is_disconnected());
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconnect_resends_post_connect_msgs_test() {
+        prepare_freethreaded_python();
+
+        let header_key = "hello-custom-key".to_string();
+        let header_value = "hello-custom-value".to_string();
+
+        let (counter, handler, on_reconnect, on_disconnect) = Python::with_gil(|py| {
+            let pymod = PyModule::from_code(
+                py,
+                r"
+class Counter:
+    def __init__(self):
+        self.count = 0
+        self.reconnects = 0
+        self.disconnects = 0
+
+    def handler(self, bytes):
+        if bytes.decode() == 'subscribe':
+            self.count = self.count + 1
+
+    def on_reconnect(self):
+        self.reconnects = self.reconnects + 1
+
+    def on_disconnect(self):
+        self.disconnects = self.disconnects + 1
+
+    def get_counts(self):
+        return (self.count, self.reconnects, self.disconnects)
+
+counter = Counter()",
+                "",
+                "",
+            )
+            .unwrap();
+
+            let counter = pymod.getattr("counter").unwrap().into_py(py);
+            let handler = counter.getattr(py, "handler").unwrap().into_py(py);
+            let on_reconnect = counter.getattr(py, "on_reconnect").unwrap().into_py(py);
+            let on_disconnect = counter.getattr(py, "on_disconnect").unwrap().into_py(py);
+
+            (counter, handler, on_reconnect, on_disconnect)
+        });
+        let get_counts = || -> (usize, usize, usize) {
+            Python::with_gil(|py| {
+                counter
+                    .getattr(py, "get_counts")
+                    .unwrap()
+                    .call0(py)
+                    .unwrap()
+                    .extract(py)
+                    .unwrap()
+            })
+        };
+
+        let server = TestServer::setup(header_key.clone(), header_value.clone()).await;
+        let config = WebSocketConfig::py_new(
+            format!("ws://127.0.0.1:{}", server.port),
+            handler,
+            vec![(header_key, header_value)],
+            None,
+            None,
+            None,
+            Some(vec!["subscribe".to_string()]),
+            Some(100),
+            Some(1_000),
+            None,
+            Some(0),
+            Some(3),
+        )
+        .unwrap();
+        let client =
+            WebSocketClient::connect(config, None, Some(on_reconnect), Some(on_disconnect))
+                .await
+                .unwrap();
+
+        // Post-connect message is echoed back on connection
+        sleep(Duration::from_millis(500)).await;
+        assert_eq!(get_counts(), (1, 0, 0));
+
+        // Server closes the connection, client reconnects and re-sends
+        client.send_close_message().await;
+        sleep(Duration::from_secs(2)).await;
+        assert_eq!(get_counts(), (2, 1, 1));
+
+        // Server goes away, client stops after max retries
+        drop(server);
+        client.send_close_message().await;
+        sleep(Duration::from_secs(3)).await;
+        assert!(client.is_disconnected());
+        assert_eq!(get_counts(), (2, 1, 2));
+    }
 }
@@ -0,0 +1,79 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use datafusion::arrow::datatypes::DataType;
+use nautilus_model::events::account::state::AccountState;
+
+use super::serialized::impl_arrow_serialized;
+
+impl_arrow_serialized!(
+    AccountState,
+    [
+        ("account_id", DataType::Utf8, false),
+        ("account_type", DataType::Utf8, false),
+        ("base_currency", DataType::Utf8, true),
+        ("balances", DataType::Binary, false),
+        ("margins", DataType::Binary, false),
+        ("is_reported", DataType::Boolean, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use datafusion::arrow::array::BinaryArray;
+    use nautilus_model::events::account::stubs::*;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::arrow::{DecodeFromRecordBatch, EncodeToRecordBatch};
+
+    #[rstest]
+    fn test_encode_account_state_balances_as_json(cash_account_state: AccountState) {
+        let data = vec![cash_account_state];
+        let record_batch = AccountState::encode_batch(&HashMap::new(), &data).unwrap();
+        let balances = record_batch
+            .column_by_name("balances")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(balances.value(0)).unwrap();
+
+        assert_eq!(value, serde_json::to_value(&data[0].balances).unwrap());
+    }
+
+    #[rstest]
+    fn test_account_state_round_trip(
+        cash_account_state: AccountState,
+        margin_account_state: AccountState,
+    ) {
+        let data = vec![cash_account_state, margin_account_state];
+        let record_batch = AccountState::encode_batch(&HashMap::new(), &data).unwrap();
+        let decoded = AccountState::decode_batch(&HashMap::new(), record_batch).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&data).unwrap()
+        );
+    }
+}
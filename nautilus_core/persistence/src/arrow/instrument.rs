@@ -0,0 +1,323 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use datafusion::arrow::datatypes::DataType;
+use nautilus_model::instruments::{
+    crypto_future::CryptoFuture, crypto_perpetual::CryptoPerpetual, currency_pair::CurrencyPair,
+    equity::Equity, futures_contract::FuturesContract, futures_spread::FuturesSpread,
+    options_contract::OptionsContract, options_spread::OptionsSpread,
+};
+
+use super::serialized::impl_arrow_serialized;
+
+impl_arrow_serialized!(
+    CryptoFuture,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("underlying", DataType::Utf8, false),
+        ("quote_currency", DataType::Utf8, false),
+        ("settlement_currency", DataType::Utf8, false),
+        ("activation_ns", DataType::UInt64, false),
+        ("expiration_ns", DataType::UInt64, false),
+        ("price_precision", DataType::UInt8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("maker_fee", DataType::Utf8, false),
+        ("taker_fee", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_notional", DataType::Utf8, true),
+        ("min_notional", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    CryptoPerpetual,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("base_currency", DataType::Utf8, false),
+        ("quote_currency", DataType::Utf8, false),
+        ("settlement_currency", DataType::Utf8, false),
+        ("is_inverse", DataType::Boolean, false),
+        ("price_precision", DataType::UInt8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("maker_fee", DataType::Utf8, false),
+        ("taker_fee", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_notional", DataType::Utf8, true),
+        ("min_notional", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    CurrencyPair,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("base_currency", DataType::Utf8, false),
+        ("quote_currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("maker_fee", DataType::Utf8, false),
+        ("taker_fee", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, true),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_notional", DataType::Utf8, true),
+        ("min_notional", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    Equity,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("isin", DataType::Utf8, true),
+        ("currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("maker_fee", DataType::Utf8, false),
+        ("taker_fee", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, true),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    FuturesContract,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("asset_class", DataType::Utf8, false),
+        ("exchange", DataType::Utf8, true),
+        ("underlying", DataType::Utf8, false),
+        ("activation_ns", DataType::UInt64, false),
+        ("expiration_ns", DataType::UInt64, false),
+        ("currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("multiplier", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    FuturesSpread,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("asset_class", DataType::Utf8, false),
+        ("exchange", DataType::Utf8, true),
+        ("underlying", DataType::Utf8, false),
+        ("strategy_type", DataType::Utf8, false),
+        ("activation_ns", DataType::UInt64, false),
+        ("expiration_ns", DataType::UInt64, false),
+        ("currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("multiplier", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OptionsContract,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("asset_class", DataType::Utf8, false),
+        ("exchange", DataType::Utf8, true),
+        ("underlying", DataType::Utf8, false),
+        ("option_kind", DataType::Utf8, false),
+        ("activation_ns", DataType::UInt64, false),
+        ("expiration_ns", DataType::UInt64, false),
+        ("strike_price", DataType::Utf8, false),
+        ("currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("multiplier", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OptionsSpread,
+    [
+        ("id", DataType::Utf8, false),
+        ("raw_symbol", DataType::Utf8, false),
+        ("asset_class", DataType::Utf8, false),
+        ("exchange", DataType::Utf8, true),
+        ("underlying", DataType::Utf8, false),
+        ("strategy_type", DataType::Utf8, false),
+        ("activation_ns", DataType::UInt64, false),
+        ("expiration_ns", DataType::UInt64, false),
+        ("currency", DataType::Utf8, false),
+        ("price_precision", DataType::UInt8, false),
+        ("price_increment", DataType::Utf8, false),
+        ("size_increment", DataType::Utf8, false),
+        ("size_precision", DataType::UInt8, false),
+        ("multiplier", DataType::Utf8, false),
+        ("lot_size", DataType::Utf8, false),
+        ("margin_init", DataType::Utf8, false),
+        ("margin_maint", DataType::Utf8, false),
+        ("max_quantity", DataType::Utf8, true),
+        ("min_quantity", DataType::Utf8, true),
+        ("max_price", DataType::Utf8, true),
+        ("min_price", DataType::Utf8, true),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use nautilus_model::instruments::stubs::*;
+    use rstest::rstest;
+    use serde::Serialize;
+
+    use super::*;
+    use crate::arrow::{DecodeFromRecordBatch, EncodeToRecordBatch};
+
+    fn assert_round_trip<T>(instrument: T)
+    where
+        T: Serialize + EncodeToRecordBatch + DecodeFromRecordBatch,
+    {
+        let metadata = HashMap::new();
+        let data = vec![instrument];
+        let record_batch = T::encode_batch(&metadata, &data).unwrap();
+        let decoded = T::decode_batch(&metadata, record_batch.clone()).unwrap();
+
+        assert_eq!(record_batch.num_rows(), 1);
+        assert_eq!(
+            record_batch.schema().fields().len(),
+            T::get_schema_map().len()
+        );
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&data).unwrap()
+        );
+    }
+
+    #[rstest]
+    fn test_crypto_future_round_trip(crypto_future_btcusdt: CryptoFuture) {
+        assert_round_trip(crypto_future_btcusdt);
+    }
+
+    #[rstest]
+    fn test_crypto_perpetual_round_trip(crypto_perpetual_ethusdt: CryptoPerpetual) {
+        assert_round_trip(crypto_perpetual_ethusdt);
+    }
+
+    #[rstest]
+    fn test_currency_pair_round_trip(currency_pair_btcusdt: CurrencyPair) {
+        assert_round_trip(currency_pair_btcusdt);
+    }
+
+    #[rstest]
+    fn test_equity_round_trip(equity_aapl: Equity) {
+        assert_round_trip(equity_aapl);
+    }
+
+    #[rstest]
+    fn test_futures_contract_round_trip(futures_contract_es: FuturesContract) {
+        assert_round_trip(futures_contract_es);
+    }
+
+    #[rstest]
+    fn test_futures_spread_round_trip(futures_spread_es: FuturesSpread) {
+        assert_round_trip(futures_spread_es);
+    }
+
+    #[rstest]
+    fn test_options_contract_round_trip(options_contract_appl: OptionsContract) {
+        assert_round_trip(options_contract_appl);
+    }
+
+    #[rstest]
+    fn test_options_spread_round_trip(options_spread: OptionsSpread) {
+        assert_round_trip(options_spread);
+    }
+}
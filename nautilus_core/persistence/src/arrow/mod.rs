@@ -13,10 +13,15 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod account_state;
 pub mod bar;
 pub mod delta;
 pub mod depth;
+pub mod instrument;
+pub mod order_event;
+pub mod position_event;
 pub mod quote;
+mod serialized;
 pub mod trade;
 
 use std::{
@@ -92,7 +97,7 @@ where
 
 pub trait DecodeFromRecordBatch
 where
-    Self: Sized + Into<Data> + ArrowSchemaProvider,
+    Self: Sized + ArrowSchemaProvider,
 {
     fn decode_batch(
         metadata: &HashMap<String, String>,
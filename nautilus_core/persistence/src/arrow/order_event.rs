@@ -0,0 +1,436 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use datafusion::arrow::datatypes::DataType;
+use nautilus_model::events::order::{
+    accepted::OrderAccepted, cancel_rejected::OrderCancelRejected, canceled::OrderCanceled,
+    denied::OrderDenied, emulated::OrderEmulated, expired::OrderExpired, filled::OrderFilled,
+    initialized::OrderInitialized, modify_rejected::OrderModifyRejected,
+    pending_cancel::OrderPendingCancel, pending_update::OrderPendingUpdate,
+    rejected::OrderRejected, released::OrderReleased, submitted::OrderSubmitted,
+    triggered::OrderTriggered, updated::OrderUpdated,
+};
+
+use super::serialized::impl_arrow_serialized;
+
+impl_arrow_serialized!(
+    OrderInitialized,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("order_side", DataType::Utf8, false),
+        ("order_type", DataType::Utf8, false),
+        ("quantity", DataType::Utf8, false),
+        ("time_in_force", DataType::Utf8, false),
+        ("post_only", DataType::Boolean, false),
+        ("reduce_only", DataType::Boolean, false),
+        ("quote_quantity", DataType::Boolean, false),
+        ("reconciliation", DataType::Boolean, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("price", DataType::Utf8, true),
+        ("trigger_price", DataType::Utf8, true),
+        ("trigger_type", DataType::Utf8, true),
+        ("limit_offset", DataType::Utf8, true),
+        ("trailing_offset", DataType::Utf8, true),
+        ("trailing_offset_type", DataType::Utf8, true),
+        ("expire_time", DataType::UInt64, true),
+        ("display_qty", DataType::Utf8, true),
+        ("emulation_trigger", DataType::Utf8, true),
+        ("trigger_instrument_id", DataType::Utf8, true),
+        ("contingency_type", DataType::Utf8, true),
+        ("order_list_id", DataType::Utf8, true),
+        ("linked_order_ids", DataType::Binary, true),
+        ("parent_order_id", DataType::Utf8, true),
+        ("exec_algorithm_id", DataType::Utf8, true),
+        ("exec_algorithm_params", DataType::Binary, true),
+        ("exec_spawn_id", DataType::Utf8, true),
+        ("tags", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderDenied,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("reason", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderEmulated,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderReleased,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("released_price", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderSubmitted,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderAccepted,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("venue_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderRejected,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("reason", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderCanceled,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderExpired,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderTriggered,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderPendingUpdate,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderPendingCancel,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderModifyRejected,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("reason", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderCancelRejected,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("reason", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderUpdated,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("venue_order_id", DataType::Utf8, true),
+        ("account_id", DataType::Utf8, true),
+        ("quantity", DataType::Utf8, false),
+        ("price", DataType::Utf8, true),
+        ("trigger_price", DataType::Utf8, true),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::UInt8, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    OrderFilled,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("client_order_id", DataType::Utf8, false),
+        ("venue_order_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("trade_id", DataType::Utf8, false),
+        ("order_side", DataType::Utf8, false),
+        ("order_type", DataType::Utf8, false),
+        ("last_qty", DataType::Utf8, false),
+        ("last_px", DataType::Utf8, false),
+        ("currency", DataType::Utf8, false),
+        ("liquidity_side", DataType::Utf8, false),
+        ("event_id", DataType::Utf8, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+        ("reconciliation", DataType::Boolean, false),
+        ("position_id", DataType::Utf8, true),
+        ("commission", DataType::Utf8, true),
+    ]
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fmt::Debug};
+
+    use nautilus_model::{
+        events::order::stubs::*,
+        identifiers::{account_id::AccountId, venue_order_id::VenueOrderId},
+    };
+    use rstest::rstest;
+
+    use super::*;
+    use crate::arrow::{ArrowSchemaProvider, DecodeFromRecordBatch, EncodeToRecordBatch};
+
+    fn assert_round_trip<T>(event: T)
+    where
+        T: EncodeToRecordBatch + DecodeFromRecordBatch + PartialEq + Debug,
+    {
+        let metadata = HashMap::from([("type".to_string(), "OrderEvent".to_string())]);
+        let data = vec![event];
+        let record_batch = T::encode_batch(&metadata, &data).unwrap();
+        let decoded = T::decode_batch(&metadata, record_batch.clone()).unwrap();
+
+        assert_eq!(record_batch.schema().metadata(), &metadata);
+        assert_eq!(record_batch.num_rows(), 1);
+        assert_eq!(decoded, data);
+    }
+
+    #[rstest]
+    fn test_get_schema_with_nullable_fields() {
+        let schema = OrderFilled::get_schema(None);
+        let field = schema.field_with_name("commission").unwrap();
+
+        assert_eq!(field.data_type(), &DataType::Utf8);
+        assert!(field.is_nullable());
+        assert!(!schema.field_with_name("trade_id").unwrap().is_nullable());
+    }
+
+    #[rstest]
+    fn test_order_initialized_round_trip(order_initialized_buy_limit: OrderInitialized) {
+        assert_round_trip(order_initialized_buy_limit);
+    }
+
+    #[rstest]
+    fn test_order_denied_round_trip(order_denied_max_submitted_rate: OrderDenied) {
+        assert_round_trip(order_denied_max_submitted_rate);
+    }
+
+    #[rstest]
+    fn test_order_emulated_round_trip(order_emulated: OrderEmulated) {
+        assert_round_trip(order_emulated);
+    }
+
+    #[rstest]
+    fn test_order_released_round_trip(order_released: OrderReleased) {
+        assert_round_trip(order_released);
+    }
+
+    #[rstest]
+    fn test_order_submitted_round_trip(order_submitted: OrderSubmitted) {
+        assert_round_trip(order_submitted);
+    }
+
+    #[rstest]
+    fn test_order_accepted_round_trip(order_accepted: OrderAccepted) {
+        assert_round_trip(order_accepted);
+    }
+
+    #[rstest]
+    fn test_order_rejected_round_trip(order_rejected_insufficient_margin: OrderRejected) {
+        assert_round_trip(order_rejected_insufficient_margin);
+    }
+
+    #[rstest]
+    fn test_order_canceled_round_trip() {
+        let event = OrderCanceled {
+            venue_order_id: Some(VenueOrderId::new("123456").unwrap()),
+            account_id: Some(AccountId::new("SIM-001").unwrap()),
+            ..Default::default()
+        };
+        assert_round_trip(event);
+    }
+
+    #[rstest]
+    fn test_order_expired_round_trip(order_expired: OrderExpired) {
+        assert_round_trip(order_expired);
+    }
+
+    #[rstest]
+    fn test_order_triggered_round_trip(order_triggered: OrderTriggered) {
+        assert_round_trip(order_triggered);
+    }
+
+    #[rstest]
+    fn test_order_pending_update_round_trip(order_pending_update: OrderPendingUpdate) {
+        assert_round_trip(order_pending_update);
+    }
+
+    #[rstest]
+    fn test_order_pending_cancel_round_trip(order_pending_cancel: OrderPendingCancel) {
+        assert_round_trip(order_pending_cancel);
+    }
+
+    #[rstest]
+    fn test_order_modify_rejected_round_trip(order_modify_rejected: OrderModifyRejected) {
+        assert_round_trip(order_modify_rejected);
+    }
+
+    #[rstest]
+    fn test_order_cancel_rejected_round_trip(order_cancel_rejected: OrderCancelRejected) {
+        assert_round_trip(order_cancel_rejected);
+    }
+
+    #[rstest]
+    fn test_order_updated_round_trip(order_updated: OrderUpdated) {
+        assert_round_trip(order_updated);
+    }
+
+    #[rstest]
+    fn test_order_filled_round_trip(order_filled: OrderFilled) {
+        assert_round_trip(order_filled);
+    }
+}
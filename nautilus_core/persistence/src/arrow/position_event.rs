@@ -0,0 +1,226 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use datafusion::arrow::datatypes::DataType;
+use nautilus_model::events::position::{
+    changed::PositionChanged, closed::PositionClosed, opened::PositionOpened,
+};
+
+use super::serialized::impl_arrow_serialized;
+
+impl_arrow_serialized!(
+    PositionOpened,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("position_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("opening_order_id", DataType::Utf8, false),
+        ("entry", DataType::Utf8, false),
+        ("side", DataType::Utf8, false),
+        ("signed_qty", DataType::Float64, false),
+        ("quantity", DataType::Utf8, false),
+        ("last_qty", DataType::Utf8, false),
+        ("last_px", DataType::Utf8, false),
+        ("currency", DataType::Utf8, false),
+        ("avg_px_open", DataType::Float64, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    PositionChanged,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("position_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("opening_order_id", DataType::Utf8, false),
+        ("entry", DataType::Utf8, false),
+        ("side", DataType::Utf8, false),
+        ("signed_qty", DataType::Float64, false),
+        ("quantity", DataType::Utf8, false),
+        ("peak_quantity", DataType::Utf8, false),
+        ("last_qty", DataType::Utf8, false),
+        ("last_px", DataType::Utf8, false),
+        ("currency", DataType::Utf8, false),
+        ("avg_px_open", DataType::Float64, false),
+        ("avg_px_closed", DataType::Float64, false),
+        ("realized_return", DataType::Float64, false),
+        ("realized_pnl", DataType::Utf8, false),
+        ("unrealized_pnl", DataType::Utf8, false),
+        ("ts_opened", DataType::UInt64, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+impl_arrow_serialized!(
+    PositionClosed,
+    [
+        ("trader_id", DataType::Utf8, false),
+        ("strategy_id", DataType::Utf8, false),
+        ("instrument_id", DataType::Utf8, false),
+        ("position_id", DataType::Utf8, false),
+        ("account_id", DataType::Utf8, false),
+        ("opening_order_id", DataType::Utf8, false),
+        ("closing_order_id", DataType::Utf8, false),
+        ("entry", DataType::Utf8, false),
+        ("side", DataType::Utf8, false),
+        ("signed_qty", DataType::Float64, false),
+        ("quantity", DataType::Utf8, false),
+        ("peak_quantity", DataType::Utf8, false),
+        ("last_qty", DataType::Utf8, false),
+        ("last_px", DataType::Utf8, false),
+        ("currency", DataType::Utf8, false),
+        ("avg_px_open", DataType::Float64, false),
+        ("avg_px_closed", DataType::Float64, false),
+        ("realized_return", DataType::Float64, false),
+        ("realized_pnl", DataType::Utf8, false),
+        ("unrealized_pnl", DataType::Utf8, false),
+        ("duration", DataType::UInt64, false),
+        ("ts_opened", DataType::UInt64, false),
+        ("ts_closed", DataType::UInt64, false),
+        ("ts_event", DataType::UInt64, false),
+        ("ts_init", DataType::UInt64, false),
+    ]
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use nautilus_model::{
+        enums::{OrderSide, PositionSide},
+        identifiers::{
+            account_id::AccountId,
+            client_order_id::ClientOrderId,
+            position_id::PositionId,
+            stubs::{instrument_id_btc_usdt, strategy_id_ema_cross, trader_id},
+        },
+        types::{currency::Currency, money::Money, price::Price, quantity::Quantity},
+    };
+    use rstest::rstest;
+
+    use super::*;
+    use crate::arrow::{DecodeFromRecordBatch, EncodeToRecordBatch};
+
+    fn position_closed() -> PositionClosed {
+        PositionClosed {
+            trader_id: trader_id(),
+            strategy_id: strategy_id_ema_cross(),
+            instrument_id: instrument_id_btc_usdt(),
+            position_id: PositionId::new("P-001").unwrap(),
+            account_id: AccountId::new("SIM-001").unwrap(),
+            opening_order_id: ClientOrderId::new("O-001").unwrap(),
+            closing_order_id: ClientOrderId::new("O-002").unwrap(),
+            entry: OrderSide::Buy,
+            side: PositionSide::Flat,
+            signed_qty: 0.0,
+            quantity: Quantity::from("0"),
+            peak_quantity: Quantity::from("1.5"),
+            last_qty: Quantity::from("1.5"),
+            last_px: Price::from("22050.50"),
+            currency: Currency::USDT(),
+            avg_px_open: 22000.0,
+            avg_px_closed: 22050.5,
+            realized_return: 0.002_295,
+            realized_pnl: Money::from("75.75 USDT"),
+            unrealized_pnl: Money::from("0 USDT"),
+            duration: 1_000_000_000,
+            ts_opened: 1_000_000_000,
+            ts_closed: 2_000_000_000,
+            ts_event: 2_000_000_000,
+            ts_init: 2_000_000_001,
+        }
+    }
+
+    #[rstest]
+    fn test_position_opened_round_trip() {
+        let closed = position_closed();
+        let event = PositionOpened {
+            trader_id: closed.trader_id,
+            strategy_id: closed.strategy_id,
+            instrument_id: closed.instrument_id,
+            position_id: closed.position_id,
+            account_id: closed.account_id,
+            opening_order_id: closed.opening_order_id,
+            entry: OrderSide::Buy,
+            side: PositionSide::Long,
+            signed_qty: 1.5,
+            quantity: Quantity::from("1.5"),
+            last_qty: Quantity::from("1.5"),
+            last_px: Price::from("22000.00"),
+            currency: closed.currency,
+            avg_px_open: 22000.0,
+            ts_event: closed.ts_opened,
+            ts_init: closed.ts_opened,
+        };
+        let data = vec![event];
+        let record_batch = PositionOpened::encode_batch(&HashMap::new(), &data).unwrap();
+        let decoded = PositionOpened::decode_batch(&HashMap::new(), record_batch).unwrap();
+
+        assert_eq!(decoded, data);
+    }
+
+    #[rstest]
+    fn test_position_changed_round_trip() {
+        let closed = position_closed();
+        let event = PositionChanged {
+            trader_id: closed.trader_id,
+            strategy_id: closed.strategy_id,
+            instrument_id: closed.instrument_id,
+            position_id: closed.position_id,
+            account_id: closed.account_id,
+            opening_order_id: closed.opening_order_id,
+            entry: OrderSide::Buy,
+            side: PositionSide::Long,
+            signed_qty: 0.5,
+            quantity: Quantity::from("0.5"),
+            peak_quantity: Quantity::from("1.5"),
+            last_qty: Quantity::from("1.0"),
+            last_px: Price::from("22050.50"),
+            currency: closed.currency,
+            avg_px_open: 22000.0,
+            avg_px_closed: 22050.5,
+            realized_return: 0.002_295,
+            realized_pnl: Money::from("50.50 USDT"),
+            unrealized_pnl: Money::from("25.25 USDT"),
+            ts_opened: closed.ts_opened,
+            ts_event: closed.ts_event,
+            ts_init: closed.ts_init,
+        };
+        let data = vec![event];
+        let record_batch = PositionChanged::encode_batch(&HashMap::new(), &data).unwrap();
+        let decoded = PositionChanged::decode_batch(&HashMap::new(), record_batch).unwrap();
+
+        assert_eq!(decoded, data);
+    }
+
+    #[rstest]
+    fn test_position_closed_round_trip() {
+        let data = vec![position_closed(), position_closed()];
+        let record_batch = PositionClosed::encode_batch(&HashMap::new(), &data).unwrap();
+        let decoded = PositionClosed::decode_batch(&HashMap::new(), record_batch).unwrap();
+
+        assert_eq!(decoded, data);
+    }
+}
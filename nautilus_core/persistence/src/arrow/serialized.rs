@@ -0,0 +1,298 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+//! Arrow encoding for types which implement `serde` serialization.
+//!
+//! Each column of the schema is filled from the serialized field of the same name, following
+//! the layout of the Python catalog: prices, quantities, identifiers and enums are stored as
+//! strings, timestamps as `UInt64`, and nested values (such as lists and maps) as JSON binary.
+
+use std::sync::Arc;
+
+use datafusion::arrow::{
+    array::{
+        Array, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder, Float64Array,
+        Float64Builder, StringArray, StringBuilder, UInt64Array, UInt64Builder, UInt8Array,
+        UInt8Builder,
+    },
+    datatypes::{DataType, Field, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use super::EncodingError;
+
+/// Implements the Arrow schema and record batch encoding traits for a `serde` type.
+///
+/// Columns are given as `(name, data_type, nullable)`, where each name must match a
+/// serialized field of the type.
+macro_rules! impl_arrow_serialized {
+    ($type:ident, [$(($name:literal, $data_type:expr, $nullable:literal)),+ $(,)?]) => {
+        impl $crate::arrow::ArrowSchemaProvider for $type {
+            fn get_schema(
+                metadata: Option<std::collections::HashMap<String, String>>,
+            ) -> datafusion::arrow::datatypes::Schema {
+                let fields = vec![$(
+                    datafusion::arrow::datatypes::Field::new($name, $data_type, $nullable)
+                ),+];
+
+                match metadata {
+                    Some(metadata) => {
+                        datafusion::arrow::datatypes::Schema::new_with_metadata(fields, metadata)
+                    }
+                    None => datafusion::arrow::datatypes::Schema::new(fields),
+                }
+            }
+        }
+
+        impl $crate::arrow::EncodeToRecordBatch for $type {
+            fn encode_batch(
+                metadata: &std::collections::HashMap<String, String>,
+                data: &[Self],
+            ) -> Result<
+                datafusion::arrow::record_batch::RecordBatch,
+                datafusion::arrow::error::ArrowError,
+            > {
+                let schema = <Self as $crate::arrow::ArrowSchemaProvider>::get_schema(Some(
+                    metadata.clone(),
+                ));
+                $crate::arrow::serialized::encode_serialized(schema, data)
+            }
+        }
+
+        impl $crate::arrow::DecodeFromRecordBatch for $type {
+            fn decode_batch(
+                _metadata: &std::collections::HashMap<String, String>,
+                record_batch: datafusion::arrow::record_batch::RecordBatch,
+            ) -> Result<Vec<Self>, $crate::arrow::EncodingError> {
+                $crate::arrow::serialized::decode_serialized(stringify!($type), &record_batch)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_arrow_serialized;
+
+/// The key of the tag field written by internally tagged `serde` types.
+const KEY_TYPE: &str = "type";
+
+pub(crate) fn encode_serialized<T: Serialize>(
+    schema: Schema,
+    data: &[T],
+) -> Result<RecordBatch, ArrowError> {
+    let rows = data
+        .iter()
+        .map(|item| match serde_json::to_value(item) {
+            Ok(Value::Object(row)) => Ok(row),
+            Ok(value) => Err(ArrowError::InvalidArgumentError(format!(
+                "Expected serialized object, was {value}"
+            ))),
+            Err(e) => Err(ArrowError::ExternalError(Box::new(e))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| encode_column(field, &rows))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+fn encode_column(field: &Field, rows: &[Map<String, Value>]) -> Result<ArrayRef, ArrowError> {
+    let name = field.name().as_str();
+    let values = rows
+        .iter()
+        .map(|row| row.get(name).filter(|value| !value.is_null()));
+    let invalid = |value: &Value| {
+        ArrowError::InvalidArgumentError(format!(
+            "Invalid value {value} for column `{name}` of type {}",
+            field.data_type()
+        ))
+    };
+
+    let array: ArrayRef = match field.data_type() {
+        DataType::Utf8 => {
+            let mut builder = StringBuilder::with_capacity(rows.len(), rows.len() * 16);
+            for value in values {
+                match value {
+                    Some(Value::String(s)) => builder.append_value(s),
+                    Some(value) => return Err(invalid(value)),
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Binary => {
+            let mut builder = BinaryBuilder::with_capacity(rows.len(), rows.len() * 64);
+            for value in values {
+                match value {
+                    Some(value) => builder.append_value(
+                        serde_json::to_vec(value)
+                            .map_err(|e| ArrowError::ExternalError(e.into()))?,
+                    ),
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::UInt64 => {
+            let mut builder = UInt64Builder::with_capacity(rows.len());
+            for value in values {
+                match value {
+                    Some(value) => {
+                        builder.append_value(value.as_u64().ok_or_else(|| invalid(value))?)
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::UInt8 => {
+            let mut builder = UInt8Builder::with_capacity(rows.len());
+            for value in values {
+                match value {
+                    Some(value) => builder.append_value(
+                        value
+                            .as_u64()
+                            .and_then(|v| u8::try_from(v).ok())
+                            .ok_or_else(|| invalid(value))?,
+                    ),
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Float64 => {
+            let mut builder = Float64Builder::with_capacity(rows.len());
+            for value in values {
+                match value {
+                    Some(value) => {
+                        builder.append_value(value.as_f64().ok_or_else(|| invalid(value))?)
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Boolean => {
+            let mut builder = BooleanBuilder::with_capacity(rows.len());
+            for value in values {
+                match value {
+                    Some(value) => {
+                        builder.append_value(value.as_bool().ok_or_else(|| invalid(value))?)
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        data_type => {
+            return Err(ArrowError::NotYetImplemented(format!(
+                "Encoding column `{name}` of type {data_type}"
+            )))
+        }
+    };
+
+    Ok(array)
+}
+
+pub(crate) fn decode_serialized<T: DeserializeOwned>(
+    type_name: &'static str,
+    record_batch: &RecordBatch,
+) -> Result<Vec<T>, EncodingError> {
+    let mut rows = vec![Map::new(); record_batch.num_rows()];
+    for row in &mut rows {
+        // Ignored by types which are not internally tagged
+        row.insert(KEY_TYPE.to_string(), Value::String(type_name.to_string()));
+    }
+
+    let schema = record_batch.schema();
+    for (field, column) in schema.fields().iter().zip(record_batch.columns()) {
+        let name = field.name();
+        let invalid = || {
+            EncodingError::ParseError(
+                type_name,
+                format!("invalid column `{name}` of type {}", column.data_type()),
+            )
+        };
+
+        for (i, row) in rows.iter_mut().enumerate() {
+            let value = if column.is_null(i) {
+                Value::Null
+            } else {
+                match column.data_type() {
+                    DataType::Utf8 => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<StringArray>()
+                            .ok_or_else(invalid)?;
+                        Value::String(values.value(i).to_string())
+                    }
+                    DataType::Binary => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<BinaryArray>()
+                            .ok_or_else(invalid)?;
+                        serde_json::from_slice(values.value(i))
+                            .map_err(|e| EncodingError::ParseError(type_name, e.to_string()))?
+                    }
+                    DataType::UInt64 => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<UInt64Array>()
+                            .ok_or_else(invalid)?;
+                        Value::from(values.value(i))
+                    }
+                    DataType::UInt8 => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<UInt8Array>()
+                            .ok_or_else(invalid)?;
+                        Value::from(values.value(i))
+                    }
+                    DataType::Float64 => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<Float64Array>()
+                            .ok_or_else(invalid)?;
+                        Value::from(values.value(i))
+                    }
+                    DataType::Boolean => {
+                        let values = column
+                            .as_any()
+                            .downcast_ref::<BooleanArray>()
+                            .ok_or_else(invalid)?;
+                        Value::from(values.value(i))
+                    }
+                    _ => return Err(invalid()),
+                }
+            };
+            row.insert(name.clone(), value);
+        }
+    }
+
+    // Deserialize from JSON bytes (rather than `Value`) as model types may borrow string data
+    rows.into_iter()
+        .map(|row| {
+            serde_json::to_vec(&row)
+                .and_then(|json| serde_json::from_slice(&json))
+                .map_err(|e| EncodingError::ParseError(type_name, e.to_string()))
+        })
+        .collect()
+}
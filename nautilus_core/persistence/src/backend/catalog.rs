@@ -0,0 +1,623 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use datafusion::parquet::{
+    arrow::ArrowWriter,
+    file::{
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+    },
+    format::KeyValue,
+};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::data::{
+    bar::Bar, delta::OrderBookDelta, depth::OrderBookDepth10, quote::QuoteTick, trade::TradeTick,
+    Data, HasTsInit,
+};
+
+use super::{
+    query::DataQuery,
+    session::{DataBackendSession, QueryResult},
+};
+use crate::arrow::{DecodeDataFromRecordBatch, EncodeToRecordBatch};
+
+const KEY_TS_INIT_MIN: &str = "ts_init_min";
+const KEY_TS_INIT_MAX: &str = "ts_init_max";
+
+/// The default time partition interval for catalog files (one day).
+const DEFAULT_PARTITION_INTERVAL_NS: u64 = 86_400_000_000_000;
+
+/// Provides the catalog layout for a data type.
+pub trait CatalogData:
+    EncodeToRecordBatch + DecodeDataFromRecordBatch + HasTsInit + Into<Data>
+{
+    /// Returns the directory name for the data type, matching the Python catalog.
+    fn path_prefix() -> &'static str;
+
+    /// Returns the partition key (the instrument ID, or bar type for bars) for the data.
+    fn partition_key(&self) -> String;
+
+    /// Returns the Arrow metadata to encode a chunk of data with the same partition key.
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String>;
+
+    /// Returns whether the given partition directory contains data for the instrument ID.
+    fn partition_matches(partition: &str, instrument_id: &str) -> bool {
+        partition == instrument_id
+    }
+}
+
+impl CatalogData for OrderBookDelta {
+    fn path_prefix() -> &'static str {
+        "order_book_delta"
+    }
+
+    fn partition_key(&self) -> String {
+        self.instrument_id.to_string()
+    }
+
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String> {
+        let first = &chunk[0];
+        Self::get_metadata(
+            &first.instrument_id,
+            first.order.price.precision,
+            first.order.size.precision,
+        )
+    }
+}
+
+impl CatalogData for OrderBookDepth10 {
+    fn path_prefix() -> &'static str {
+        "order_book_depth10"
+    }
+
+    fn partition_key(&self) -> String {
+        self.instrument_id.to_string()
+    }
+
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String> {
+        let first = &chunk[0];
+        Self::get_metadata(
+            &first.instrument_id,
+            first.bids[0].price.precision,
+            first.bids[0].size.precision,
+        )
+    }
+}
+
+impl CatalogData for QuoteTick {
+    fn path_prefix() -> &'static str {
+        "quote_tick"
+    }
+
+    fn partition_key(&self) -> String {
+        self.instrument_id.to_string()
+    }
+
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String> {
+        let first = &chunk[0];
+        Self::get_metadata(
+            &first.instrument_id,
+            first.bid_price.precision,
+            first.bid_size.precision,
+        )
+    }
+}
+
+impl CatalogData for TradeTick {
+    fn path_prefix() -> &'static str {
+        "trade_tick"
+    }
+
+    fn partition_key(&self) -> String {
+        self.instrument_id.to_string()
+    }
+
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String> {
+        let first = &chunk[0];
+        Self::get_metadata(
+            &first.instrument_id,
+            first.price.precision,
+            first.size.precision,
+        )
+    }
+}
+
+impl CatalogData for Bar {
+    fn path_prefix() -> &'static str {
+        "bar"
+    }
+
+    fn partition_key(&self) -> String {
+        self.bar_type.to_string()
+    }
+
+    fn chunk_metadata(chunk: &[Self]) -> HashMap<String, String> {
+        let first = &chunk[0];
+        Self::get_metadata(
+            &first.bar_type,
+            first.open.precision,
+            first.volume.precision,
+        )
+    }
+
+    fn partition_matches(partition: &str, instrument_id: &str) -> bool {
+        // Bar types are formatted as `{instrument_id}-{step}-{aggregation}-{price_type}-{source}`
+        partition
+            .strip_prefix(instrument_id)
+            .is_some_and(|rest| rest.starts_with('-'))
+    }
+}
+
+/// Converts the partition key into a valid directory name (as per the Python catalog).
+fn urisafe_key(key: &str) -> String {
+    key.replace('/', "")
+}
+
+/// Provides a Parquet data catalog with a directory layout compatible with the Python catalog.
+///
+/// Data is written to `{base_path}/data/{type}/{instrument_id}/` (or the bar type for bars),
+/// split into one file per time partition of `ts_init`. Each file records its minimum and
+/// maximum `ts_init` in the Parquet key-value metadata, so that queries only register the
+/// files overlapping the requested time range. Query results are merged in ascending order
+/// of `ts_init` by the underlying [`DataBackendSession`].
+pub struct ParquetDataCatalog {
+    pub base_path: PathBuf,
+    pub partition_interval_ns: u64,
+    session: DataBackendSession,
+    table_count: usize,
+}
+
+impl ParquetDataCatalog {
+    /// Creates a new [`ParquetDataCatalog`] instance.
+    ///
+    /// The `partition_interval_ns` defaults to one day.
+    #[must_use]
+    pub fn new(base_path: PathBuf, partition_interval_ns: Option<u64>, chunk_size: usize) -> Self {
+        Self {
+            base_path,
+            partition_interval_ns: partition_interval_ns.unwrap_or(DEFAULT_PARTITION_INTERVAL_NS),
+            session: DataBackendSession::new(chunk_size),
+            table_count: 0,
+        }
+    }
+
+    /// Returns the directory containing all data of type `T`.
+    #[must_use]
+    pub fn type_path<T: CatalogData>(&self) -> PathBuf {
+        self.base_path.join("data").join(T::path_prefix())
+    }
+
+    /// Writes the data to time partitioned Parquet files, returning the paths of the files written.
+    ///
+    /// Data is grouped by partition key and sorted by `ts_init` before writing. Existing files
+    /// are never merged or replaced, so a write overlapping the `ts_init` range of any existing
+    /// file for a partition is rejected before any file is written.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error:
+    /// - If the partition interval is zero.
+    /// - If the data overlaps the time range of an existing file.
+    /// - If encoding the data or writing a file fails.
+    pub fn write_data<T: CatalogData>(&self, data: Vec<T>) -> anyhow::Result<Vec<PathBuf>> {
+        anyhow::ensure!(
+            self.partition_interval_ns > 0,
+            "`partition_interval_ns` was zero"
+        );
+
+        let mut partitions: BTreeMap<String, Vec<T>> = BTreeMap::new();
+        for item in data {
+            partitions
+                .entry(item.partition_key())
+                .or_default()
+                .push(item);
+        }
+
+        let interval_ns = self.partition_interval_ns;
+        let mut chunks = Vec::new();
+        for (key, items) in &mut partitions {
+            items.sort_by_key(HasTsInit::get_ts_init);
+
+            let dir = self.type_path::<T>().join(urisafe_key(key));
+            for chunk in items
+                .chunk_by(|a, b| a.get_ts_init() / interval_ns == b.get_ts_init() / interval_ns)
+            {
+                check_no_overlapping_file(&dir, chunk)?;
+                chunks.push((dir.clone(), chunk));
+            }
+        }
+
+        let mut paths = Vec::new();
+        for (dir, chunk) in chunks {
+            fs::create_dir_all(&dir)?;
+            paths.push(write_parquet_file(&dir, chunk)?);
+        }
+
+        Ok(paths)
+    }
+
+    /// Returns the paths of the files of type `T` which may contain data for the `query`
+    /// instrument IDs (all instruments if `None`) within its inclusive `start` and `end` range.
+    ///
+    /// Only the Parquet footer of each file is read.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the catalog directory or a file footer cannot be read.
+    pub fn list_files<T: CatalogData>(&self, query: &DataQuery) -> anyhow::Result<Vec<PathBuf>> {
+        let type_path = self.type_path::<T>();
+        if !type_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let instrument_ids: Option<Vec<String>> = query
+            .instrument_ids
+            .as_ref()
+            .map(|ids| ids.iter().map(|id| urisafe_key(&id.to_string())).collect());
+
+        let mut files = Vec::new();
+        for partition in sorted_entries(&type_path)? {
+            let Some(name) = partition.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !partition.is_dir() {
+                continue;
+            }
+            if let Some(ids) = &instrument_ids {
+                if !ids.iter().any(|id| T::partition_matches(name, id)) {
+                    continue;
+                }
+            }
+
+            for file in sorted_entries(&partition)? {
+                if file.extension().is_some_and(|ext| ext == "parquet")
+                    && file_overlaps(&file, query.start, query.end)?
+                {
+                    files.push(file);
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Registers the `query` for data of type `T` on each file which may contain matching data.
+    ///
+    /// Queries for several types may be registered before calling [`Self::get_query_result`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if listing or registering the files fails.
+    pub fn query<T: CatalogData>(&mut self, query: &DataQuery) -> anyhow::Result<()> {
+        for file in self.list_files::<T>(query)? {
+            let table_name = format!("{}_{}", T::path_prefix(), self.table_count);
+            self.table_count += 1;
+
+            let file_path = file
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid file path {}", file.display()))?;
+            self.session
+                .add_file_with_query::<T>(&table_name, file_path, query)?;
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the registered queries, returning the data merged in ascending order of `ts_init`.
+    pub fn get_query_result(&mut self) -> QueryResult {
+        self.session.get_query_result()
+    }
+}
+
+fn write_parquet_file<T: CatalogData>(dir: &Path, chunk: &[T]) -> anyhow::Result<PathBuf> {
+    let ts_init_min = chunk[0].get_ts_init();
+    let ts_init_max = chunk[chunk.len() - 1].get_ts_init();
+    let path = dir.join(format!("{ts_init_min}-{ts_init_max}.parquet"));
+
+    let record_batch = T::encode_batch(&T::chunk_metadata(chunk), chunk)?;
+    let props = WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![
+            KeyValue::new(KEY_TS_INIT_MIN.to_string(), ts_init_min.to_string()),
+            KeyValue::new(KEY_TS_INIT_MAX.to_string(), ts_init_max.to_string()),
+        ]))
+        .build();
+
+    let mut writer =
+        ArrowWriter::try_new(File::create(&path)?, record_batch.schema(), Some(props))?;
+    writer.write(&record_batch)?;
+    writer.close()?;
+
+    Ok(path)
+}
+
+fn check_no_overlapping_file<T: CatalogData>(dir: &Path, chunk: &[T]) -> anyhow::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    let ts_init_min = chunk[0].get_ts_init();
+    let ts_init_max = chunk[chunk.len() - 1].get_ts_init();
+    for file in sorted_entries(dir)? {
+        if file.extension().is_some_and(|ext| ext == "parquet")
+            && file_overlaps(&file, Some(ts_init_min), Some(ts_init_max))?
+        {
+            anyhow::bail!(
+                "Data from {ts_init_min} to {ts_init_max} overlaps existing file {}",
+                file.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn file_overlaps(
+    path: &Path,
+    start: Option<UnixNanos>,
+    end: Option<UnixNanos>,
+) -> anyhow::Result<bool> {
+    if start.is_none() && end.is_none() {
+        return Ok(true);
+    }
+
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    let Some(key_value_metadata) = reader.metadata().file_metadata().key_value_metadata() else {
+        return Ok(true); // Written outside the catalog, so may overlap
+    };
+    let get_ts = |key: &str| -> anyhow::Result<Option<UnixNanos>> {
+        key_value_metadata
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.as_ref())
+            .map(|value| value.parse::<UnixNanos>())
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Invalid `{key}` in {}: {e}", path.display()))
+    };
+
+    let ts_init_min = get_ts(KEY_TS_INIT_MIN)?;
+    let ts_init_max = get_ts(KEY_TS_INIT_MAX)?;
+
+    let after_start = match (start, ts_init_max) {
+        (Some(start), Some(max)) => max >= start,
+        _ => true,
+    };
+    let before_end = match (end, ts_init_min) {
+        (Some(end), Some(min)) => min <= end,
+        _ => true,
+    };
+
+    Ok(after_start && before_end)
+}
+
+fn sorted_entries(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_model::{
+        data::{bar::BarType, is_monotonically_increasing_by_init},
+        identifiers::instrument_id::InstrumentId,
+        types::{price::Price, quantity::Quantity},
+    };
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const DAY_NS: u64 = DEFAULT_PARTITION_INTERVAL_NS;
+
+    fn quote(instrument_id: &str, ts_init: UnixNanos) -> QuoteTick {
+        QuoteTick::new(
+            InstrumentId::from(instrument_id),
+            Price::from("1.00010"),
+            Price::from("1.00020"),
+            Quantity::from("100000"),
+            Quantity::from("100000"),
+            ts_init,
+            ts_init,
+        )
+        .unwrap()
+    }
+
+    fn quotes() -> Vec<QuoteTick> {
+        // Two instruments over two days, written out of order
+        vec![
+            quote("AUD/USD.SIM", DAY_NS + 2),
+            quote("AUD/USD.SIM", 1),
+            quote("EUR/USD.SIM", 2),
+            quote("AUD/USD.SIM", DAY_NS + 1),
+            quote("EUR/USD.SIM", DAY_NS + 3),
+            quote("AUD/USD.SIM", 3),
+        ]
+    }
+
+    fn file_names(paths: &[PathBuf], base_path: &Path) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(base_path)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_write_data_partitions_by_instrument_and_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+
+        let paths = catalog.write_data(quotes()).unwrap();
+
+        assert_eq!(
+            file_names(&paths, temp_dir.path()),
+            vec![
+                "data/quote_tick/AUDUSD.SIM/1-3.parquet",
+                "data/quote_tick/AUDUSD.SIM/86400000000001-86400000000002.parquet",
+                "data/quote_tick/EURUSD.SIM/2-2.parquet",
+                "data/quote_tick/EURUSD.SIM/86400000000003-86400000000003.parquet",
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_list_files_filters_by_instrument_and_time_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+        catalog.write_data(quotes()).unwrap();
+
+        let all = catalog.list_files::<QuoteTick>(&DataQuery::new()).unwrap();
+        let first_day = catalog
+            .list_files::<QuoteTick>(
+                &DataQuery::new()
+                    .with_instrument_ids(vec![InstrumentId::from("AUD/USD.SIM")])
+                    .with_start(0)
+                    .with_end(DAY_NS - 1),
+            )
+            .unwrap();
+        let second_day = catalog
+            .list_files::<QuoteTick>(&DataQuery::new().with_start(DAY_NS))
+            .unwrap();
+
+        assert_eq!(all.len(), 4);
+        assert_eq!(
+            file_names(&first_day, temp_dir.path()),
+            vec!["data/quote_tick/AUDUSD.SIM/1-3.parquet"]
+        );
+        assert_eq!(second_day.len(), 2);
+        assert!(catalog
+            .list_files::<TradeTick>(&DataQuery::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[rstest]
+    fn test_query_merges_files_within_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+        catalog.write_data(quotes()).unwrap();
+
+        catalog
+            .query::<QuoteTick>(&DataQuery::new().with_start(2).with_end(DAY_NS + 2))
+            .unwrap();
+        let data: Vec<Data> = catalog.get_query_result().collect();
+        let ts_inits: Vec<UnixNanos> = data.iter().map(HasTsInit::get_ts_init).collect();
+
+        assert!(is_monotonically_increasing_by_init(&data));
+        assert_eq!(ts_inits, vec![2, 3, DAY_NS + 1, DAY_NS + 2]);
+    }
+
+    #[rstest]
+    fn test_query_by_instrument_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+        catalog.write_data(quotes()).unwrap();
+
+        let query = DataQuery::new().with_instrument_ids(vec![InstrumentId::from("EUR/USD.SIM")]);
+        catalog.query::<QuoteTick>(&query).unwrap();
+        let data: Vec<Data> = catalog.get_query_result().collect();
+
+        assert_eq!(data.len(), 2);
+        assert!(data.iter().all(|item| match item {
+            Data::Quote(quote) => quote.instrument_id == InstrumentId::from("EUR/USD.SIM"),
+            _ => false,
+        }));
+    }
+
+    #[rstest]
+    fn test_query_with_price_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+        catalog.write_data(quotes()).unwrap();
+
+        let query =
+            DataQuery::new().with_price_range("bid_price", Some(Price::from("1.00010")), None);
+        catalog.query::<QuoteTick>(&query).unwrap();
+        let included: Vec<Data> = catalog.get_query_result().collect();
+
+        let query =
+            DataQuery::new().with_price_range("bid_price", Some(Price::from("1.00011")), None);
+        catalog.query::<QuoteTick>(&query).unwrap();
+        let excluded: Vec<Data> = catalog.get_query_result().collect();
+
+        assert_eq!(included.len(), 6);
+        assert!(excluded.is_empty());
+    }
+
+    #[rstest]
+    fn test_write_data_when_overlapping_existing_file_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), None, 1_000);
+        catalog.write_data(quotes()).unwrap();
+
+        // The EUR/USD chunk is not written either, as the AUD/USD chunk overlaps `1-3.parquet`
+        let result = catalog.write_data(vec![
+            quote("EUR/USD.SIM", 5),
+            quote("AUD/USD.SIM", 2),
+            quote("AUD/USD.SIM", 4),
+        ]);
+        let paths = catalog.write_data(vec![quote("AUD/USD.SIM", 4)]).unwrap();
+        catalog.query::<QuoteTick>(&DataQuery::new()).unwrap();
+        let data: Vec<Data> = catalog.get_query_result().collect();
+
+        assert!(result.is_err());
+        assert_eq!(
+            file_names(&paths, temp_dir.path()),
+            vec!["data/quote_tick/AUDUSD.SIM/4-4.parquet"]
+        );
+        assert_eq!(data.len(), 7);
+    }
+
+    #[rstest]
+    fn test_write_data_when_zero_partition_interval() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = ParquetDataCatalog::new(temp_dir.path().to_path_buf(), Some(0), 1_000);
+
+        assert!(catalog.write_data(quotes()).is_err());
+    }
+
+    #[rstest]
+    fn test_bar_partition_matches_instrument_id() {
+        let bar_type = BarType::from("AUD/USD.SIM-1-MINUTE-BID-EXTERNAL");
+        let partition = urisafe_key(&bar_type.to_string());
+
+        assert!(Bar::partition_matches(
+            &partition,
+            &urisafe_key("AUD/USD.SIM")
+        ));
+        assert!(!Bar::partition_matches(
+            &partition,
+            &urisafe_key("AUD/USD.SI")
+        ));
+        assert!(!Bar::partition_matches(
+            &partition,
+            &urisafe_key("EUR/USD.SIM")
+        ));
+    }
+}
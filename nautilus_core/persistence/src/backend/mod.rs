@@ -13,5 +13,7 @@
 //  limitations under the License.
 // -------------------------------------------------------------------------------------------------
 
+pub mod catalog;
 pub mod kmerge_batch;
+pub mod query;
 pub mod session;
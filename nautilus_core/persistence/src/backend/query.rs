@@ -0,0 +1,198 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{collections::HashMap, str::FromStr};
+
+use datafusion::prelude::{col, lit, Expr};
+use nautilus_core::time::UnixNanos;
+use nautilus_model::{
+    data::bar::BarType,
+    identifiers::instrument_id::InstrumentId,
+    types::{price::Price, quantity::Quantity},
+};
+
+const KEY_BAR_TYPE: &str = "bar_type";
+const KEY_INSTRUMENT_ID: &str = "instrument_id";
+
+/// Represents a typed query for a data file, translated into DataFusion expressions.
+///
+/// The `ts_init`, price and size filters are pushed down to the Parquet scan, so that row
+/// groups and pages are pruned by their statistics. As the instrument ID is held in the file
+/// metadata (rather than a column), the instrument ID filter applies to the whole file.
+///
+/// Prices and sizes are compared by their raw fixed-point values, as stored in the file.
+#[derive(Clone, Debug, Default)]
+pub struct DataQuery {
+    pub instrument_ids: Option<Vec<InstrumentId>>,
+    pub start: Option<UnixNanos>,
+    pub end: Option<UnixNanos>,
+    filters: Vec<Expr>,
+}
+
+impl DataQuery {
+    /// Creates a new [`DataQuery`] instance which matches all data.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the query filtered to data for the given instrument IDs.
+    #[must_use]
+    pub fn with_instrument_ids(mut self, instrument_ids: Vec<InstrumentId>) -> Self {
+        self.instrument_ids = Some(instrument_ids);
+        self
+    }
+
+    /// Returns the query filtered to data with a `ts_init` on or after `start`.
+    #[must_use]
+    pub fn with_start(mut self, start: UnixNanos) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Returns the query filtered to data with a `ts_init` on or before `end`.
+    #[must_use]
+    pub fn with_end(mut self, end: UnixNanos) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Returns the query filtered to data with a price `column` within the inclusive range.
+    #[must_use]
+    pub fn with_price_range(
+        mut self,
+        column: &str,
+        min: Option<Price>,
+        max: Option<Price>,
+    ) -> Self {
+        if let Some(min) = min {
+            self.filters.push(col(column).gt_eq(lit(min.raw)));
+        }
+        if let Some(max) = max {
+            self.filters.push(col(column).lt_eq(lit(max.raw)));
+        }
+        self
+    }
+
+    /// Returns the query filtered to data with a size `column` within the inclusive range.
+    #[must_use]
+    pub fn with_size_range(
+        mut self,
+        column: &str,
+        min: Option<Quantity>,
+        max: Option<Quantity>,
+    ) -> Self {
+        if let Some(min) = min {
+            self.filters.push(col(column).gt_eq(lit(min.raw)));
+        }
+        if let Some(max) = max {
+            self.filters.push(col(column).lt_eq(lit(max.raw)));
+        }
+        self
+    }
+
+    /// Returns the filter expression for the query, or `None` if there are no row filters.
+    #[must_use]
+    pub fn filter_expr(&self) -> Option<Expr> {
+        let mut exprs = Vec::new();
+        if let Some(start) = self.start {
+            exprs.push(col("ts_init").gt_eq(lit(start)));
+        }
+        if let Some(end) = self.end {
+            exprs.push(col("ts_init").lt_eq(lit(end)));
+        }
+        exprs.extend(self.filters.iter().cloned());
+        exprs.into_iter().reduce(Expr::and)
+    }
+
+    /// Returns whether a file with the given schema metadata may contain data for the query.
+    ///
+    /// Files without an instrument ID or bar type in their metadata always match.
+    #[must_use]
+    pub fn matches_metadata(&self, metadata: &HashMap<String, String>) -> bool {
+        let Some(instrument_ids) = &self.instrument_ids else {
+            return true;
+        };
+
+        let instrument_id = if let Some(value) = metadata.get(KEY_INSTRUMENT_ID) {
+            InstrumentId::from_str(value).ok()
+        } else if let Some(value) = metadata.get(KEY_BAR_TYPE) {
+            BarType::from_str(value)
+                .ok()
+                .map(|bar_type| bar_type.instrument_id)
+        } else {
+            return true;
+        };
+
+        instrument_id.is_some_and(|instrument_id| instrument_ids.contains(&instrument_id))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_filter_expr_when_empty() {
+        assert!(DataQuery::new().filter_expr().is_none());
+    }
+
+    #[rstest]
+    fn test_filter_expr() {
+        let query = DataQuery::new()
+            .with_start(1)
+            .with_end(2)
+            .with_price_range("price", Some(Price::from("1.0")), None)
+            .with_size_range("size", None, Some(Quantity::from("5")));
+
+        let expected = col("ts_init")
+            .gt_eq(lit(1_u64))
+            .and(col("ts_init").lt_eq(lit(2_u64)))
+            .and(col("price").gt_eq(lit(Price::from("1.0").raw)))
+            .and(col("size").lt_eq(lit(Quantity::from("5").raw)));
+
+        assert_eq!(query.filter_expr(), Some(expected));
+    }
+
+    #[rstest]
+    #[case(None, HashMap::new(), true)]
+    #[case(Some(vec!["EUR/USD.SIM"]), HashMap::new(), true)]
+    #[case(Some(vec!["EUR/USD.SIM"]), HashMap::from([(KEY_INSTRUMENT_ID, "EUR/USD.SIM")]), true)]
+    #[case(Some(vec!["EUR/USD.SIM"]), HashMap::from([(KEY_INSTRUMENT_ID, "AUD/USD.SIM")]), false)]
+    #[case(Some(vec!["AUD/USD.SIM"]), HashMap::from([(KEY_BAR_TYPE, "AUD/USD.SIM-1-MINUTE-BID-EXTERNAL")]), true)]
+    #[case(Some(vec!["EUR/USD.SIM"]), HashMap::from([(KEY_BAR_TYPE, "AUD/USD.SIM-1-MINUTE-BID-EXTERNAL")]), false)]
+    fn test_matches_metadata(
+        #[case] instrument_ids: Option<Vec<&str>>,
+        #[case] metadata: HashMap<&str, &str>,
+        #[case] expected: bool,
+    ) {
+        let mut query = DataQuery::new();
+        if let Some(instrument_ids) = instrument_ids {
+            query = query
+                .with_instrument_ids(instrument_ids.into_iter().map(InstrumentId::from).collect());
+        }
+        let metadata = metadata
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        assert_eq!(query.matches_metadata(&metadata), expected);
+    }
+}
@@ -23,7 +23,10 @@ use futures::StreamExt;
 use nautilus_core::ffi::cvec::CVec;
 use nautilus_model::data::{Data, HasTsInit};
 
-use super::kmerge_batch::{EagerStream, ElementBatchIter, KMerge};
+use super::{
+    kmerge_batch::{EagerStream, ElementBatchIter, KMerge},
+    query::DataQuery,
+};
 use crate::arrow::{
     DataStreamingError, DecodeDataFromRecordBatch, EncodeToRecordBatch, WriteStream,
 };
@@ -70,8 +73,14 @@ impl DataBackendSession {
             .enable_all()
             .build()
             .unwrap();
+        // Push filters down into the Parquet scan, so that rows are filtered while decoding
+        // (row groups and pages are already pruned by their statistics by default)
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.parquet.pushdown_filters = true;
+        config.options_mut().execution.parquet.reorder_filters = true;
+
         Self {
-            session_ctx: SessionContext::default(),
+            session_ctx: SessionContext::new_with_config(config),
             batch_streams: Vec::default(),
             chunk_size,
             runtime: Arc::new(runtime),
@@ -110,6 +119,73 @@ impl DataBackendSession {
     where
         T: DecodeDataFromRecordBatch + Into<Data>,
     {
+        self.register_parquet(table_name, file_path)?;
+
+        let default_query = format!("SELECT * FROM {}", &table_name);
+        let sql_query = sql_query.unwrap_or(&default_query);
+        let query = self.runtime.block_on(self.session_ctx.sql(sql_query))?;
+
+        let batch_stream = self.runtime.block_on(query.execute_stream())?;
+
+        self.add_batch_stream::<T>(batch_stream);
+        Ok(())
+    }
+
+    /// Query a file for the records matching the typed `query`. the caller must specify `T` to
+    /// indicate the kind of data expected from this query.
+    ///
+    /// Only the columns decoded by `T` are read (when present), and the query filters are pushed
+    /// down to the Parquet scan. If the file metadata does not match the query's instrument IDs, then the
+    /// file is not queried.
+    ///
+    /// `table_name`: Logical `table_name` assigned to this file.
+    /// `file_path`: Path to file
+    /// `query`: The typed query to filter the records with.
+    ///
+    /// # Safety
+    ///
+    /// The file data must be ordered by the `ts_init` in ascending order for this
+    /// to work correctly.
+    pub fn add_file_with_query<T>(
+        &mut self,
+        table_name: &str,
+        file_path: &str,
+        query: &DataQuery,
+    ) -> Result<()>
+    where
+        T: DecodeDataFromRecordBatch + Into<Data>,
+    {
+        self.register_parquet(table_name, file_path)?;
+
+        let mut df = self.runtime.block_on(self.session_ctx.table(table_name))?;
+        if !query.matches_metadata(df.schema().metadata()) {
+            return Ok(());
+        }
+
+        // Files written with other column names are decoded by column index, so are not projected
+        let schema = T::get_schema(None);
+        let columns: Vec<&str> = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect();
+        if columns
+            .iter()
+            .all(|name| df.schema().has_column_with_unqualified_name(name))
+        {
+            df = df.select_columns(&columns)?;
+        }
+        if let Some(expr) = query.filter_expr() {
+            df = df.filter(expr)?;
+        }
+
+        let batch_stream = self.runtime.block_on(df.execute_stream())?;
+
+        self.add_batch_stream::<T>(batch_stream);
+        Ok(())
+    }
+
+    fn register_parquet(&mut self, table_name: &str, file_path: &str) -> Result<()> {
         let parquet_options = ParquetReadOptions::<'_> {
             skip_metadata: Some(false),
             file_sort_order: vec![vec![Expr::Sort(Sort {
@@ -123,16 +199,7 @@ impl DataBackendSession {
             table_name,
             file_path,
             parquet_options,
-        ))?;
-
-        let default_query = format!("SELECT * FROM {}", &table_name);
-        let sql_query = sql_query.unwrap_or(&default_query);
-        let query = self.runtime.block_on(self.session_ctx.sql(sql_query))?;
-
-        let batch_stream = self.runtime.block_on(query.execute_stream())?;
-
-        self.add_batch_stream::<T>(batch_stream);
-        Ok(())
+        ))
     }
 
     fn add_batch_stream<T>(&mut self, stream: SendableRecordBatchStream)
@@ -31,7 +31,7 @@ pub enum DatabaseEngine {
     SQLITE,
 }
 
-fn str_to_database_engine(engine_str: &str) -> DatabaseEngine {
+pub(crate) fn str_to_database_engine(engine_str: &str) -> DatabaseEngine {
     match engine_str {
         "POSTGRES" | "postgres" => DatabaseEngine::POSTGRES,
         "SQLITE" | "sqlite" => DatabaseEngine::SQLITE,
@@ -18,3 +18,19 @@ pub struct GeneralItem {
     pub key: String,
     pub value: String,
 }
+
+#[derive(sqlx::FromRow)]
+pub struct PayloadRow {
+    pub payload: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct KeyRow {
+    pub key: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct IndexRow {
+    pub member: String,
+    pub value: String,
+}
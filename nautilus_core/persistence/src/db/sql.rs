@@ -13,13 +13,48 @@
 //  limitations under the License.
 // ------------------------------------------------------------------------------------------------
 
+use std::{collections::HashMap, future::Future, sync::mpsc::Receiver};
+
+use log::{debug, error};
+use nautilus_common::{
+    cache::{CacheDatabase, DatabaseCommand, DatabaseOperation},
+    runtime::get_runtime,
+};
+use nautilus_core::uuid::UUID4;
 use nautilus_model::identifiers::trader_id::TraderId;
+use serde_json::Value;
 use sqlx::Error;
 
-use crate::db::{database::Database, schema::GeneralItem};
+use crate::db::{
+    database::{init_db_schema, str_to_database_engine, Database, DatabaseEngine},
+    schema::{GeneralItem, IndexRow, KeyRow, PayloadRow},
+};
+
+const DELIMITER: char = ':';
+const INDEX: &str = "index";
+const GENERAL: &str = "general";
+const CURRENCIES: &str = "currencies";
+const INSTRUMENTS: &str = "instruments";
+const SYNTHETICS: &str = "synthetics";
+const ACCOUNTS: &str = "accounts";
+const ORDERS: &str = "orders";
+const POSITIONS: &str = "positions";
+
+/// The maximum attempts to append an event row, where concurrent writers take the same sequence.
+const MAX_APPEND_ATTEMPTS: usize = 10;
 
+/// Index keys which map a member to a value (all other indexes are plain sets).
+const INDEX_HASH_KEYS: [&str; 2] = ["order_position", "order_client"];
+
+/// Provides a `CacheDatabase` backed by relational tables in SQLite or PostgreSQL.
+///
+/// Objects are stored with their serialized payload alongside queryable columns
+/// extracted from it, and all rows are scoped by the trader key so several traders
+/// can share one database. Order and account events, and position snapshots, are
+/// appended in sequence so their full history is retained.
 pub struct SqlCacheDatabase {
     trader_id: TraderId,
+    trader_key: String,
     db: Database,
 }
 
@@ -28,6 +63,7 @@ impl SqlCacheDatabase {
     pub fn new(trader_id: TraderId, database: Database) -> Self {
         Self {
             trader_id,
+            trader_key: format!("trader-{trader_id}"),
             db: database,
         }
     }
@@ -57,18 +93,670 @@ impl SqlCacheDatabase {
     }
 }
 
+impl CacheDatabase for SqlCacheDatabase {
+    /// Creates a new SQL cache database from the `database` config, which must contain
+    /// a connection `url` and may contain an `engine` (default `sqlite`) and a
+    /// `schema_dir` of table definitions to initialize.
+    fn new(
+        trader_id: TraderId,
+        _instance_id: UUID4,
+        config: HashMap<String, serde_json::Value>,
+    ) -> anyhow::Result<Self> {
+        let database = block_on(connect(&config))?;
+
+        let schema_dir = config
+            .get("database")
+            .and_then(|c| c.get("schema_dir"))
+            .and_then(Value::as_str);
+        if let Some(schema_dir) = schema_dir {
+            block_on(init_db_schema(&database, schema_dir))?;
+        }
+
+        Ok(Self::new(trader_id, database))
+    }
+
+    /// Deletes all rows for the trader (rows belonging to other traders are retained).
+    fn flushdb(&mut self) -> anyhow::Result<()> {
+        block_on(flush(&self.db, &self.trader_key))
+    }
+
+    fn keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>> {
+        let keys = block_on(read_keys(&self.db, &self.trader_key))?;
+        Ok(keys
+            .into_iter()
+            .filter(|key| is_glob_match(pattern, key))
+            .map(|key| format!("{}{DELIMITER}{key}", self.trader_key))
+            .collect())
+    }
+
+    fn read(&mut self, key: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+        block_on(read(&self.db, &self.trader_key, key))
+    }
+
+    fn insert(&mut self, key: String, payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+        block_on(insert(&self.db, &self.trader_key, &key, payload))
+    }
+
+    fn update(&mut self, key: String, payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+        block_on(update(&self.db, &self.trader_key, &key, payload))
+    }
+
+    fn delete(&mut self, key: String, payload: Option<Vec<Vec<u8>>>) -> anyhow::Result<()> {
+        block_on(delete(&self.db, &self.trader_key, &key, payload))
+    }
+
+    fn handle_messages(
+        rx: Receiver<DatabaseCommand>,
+        trader_key: String,
+        config: HashMap<String, serde_json::Value>,
+    ) {
+        let db = match block_on(connect(&config)) {
+            Ok(db) => db,
+            Err(e) => {
+                error!("Failed to connect to cache database: {e}");
+                return;
+            }
+        };
+
+        // Continue to receive and handle messages until channel is hung up
+        while let Ok(msg) = rx.recv() {
+            let key = msg.key;
+            let result = match msg.op_type {
+                DatabaseOperation::Insert => block_on(insert(&db, &trader_key, &key, msg.payload)),
+                DatabaseOperation::Update => block_on(update(&db, &trader_key, &key, msg.payload)),
+                DatabaseOperation::Delete => block_on(delete(&db, &trader_key, &key, msg.payload)),
+            };
+            if let Err(e) = result {
+                error!("Failed to handle cache database command for '{key}': {e}");
+            }
+        }
+        debug!("Cache database message channel hung up");
+    }
+}
+
+/// Runs the `future` to completion on the shared runtime.
+///
+/// The trait is synchronous, so when called from within an async context the future
+/// is driven from a separate thread (blocking the runtime thread is not permitted).
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let runtime = get_runtime();
+    if tokio::runtime::Handle::try_current().is_ok() {
+        std::thread::scope(|s| {
+            s.spawn(|| runtime.block_on(future))
+                .join()
+                .expect("Error joining cache database thread")
+        })
+    } else {
+        runtime.block_on(future)
+    }
+}
+
+async fn connect(config: &HashMap<String, Value>) -> anyhow::Result<Database> {
+    let database_config = config
+        .get("database")
+        .ok_or(anyhow::anyhow!("No database config"))?;
+    let engine = database_config
+        .get("engine")
+        .and_then(Value::as_str)
+        .map_or(DatabaseEngine::SQLITE, str_to_database_engine);
+    let url = database_config
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or(anyhow::anyhow!("No database `url` config"))?;
+
+    debug!("Connecting to cache database");
+    Ok(Database::new(Some(engine), Some(url)).await)
+}
+
+/// Returns the (table, id column, is event list) for the given object `collection`.
+fn object_table(collection: &str) -> Option<(&'static str, &'static str, bool)> {
+    match collection {
+        CURRENCIES => Some(("currency", "code", false)),
+        INSTRUMENTS => Some(("instrument", "id", false)),
+        SYNTHETICS => Some(("synthetic", "id", false)),
+        ACCOUNTS => Some(("account_event", "account_id", true)),
+        ORDERS => Some(("order_event", "client_order_id", true)),
+        POSITIONS => Some(("position_snapshot", "position_id", true)),
+        _ => None,
+    }
+}
+
+fn split_key(key: &str) -> anyhow::Result<(&str, &str)> {
+    key.split_once(DELIMITER).ok_or_else(|| {
+        anyhow::anyhow!("Invalid `key`, missing a '{DELIMITER}' delimiter, was {key}")
+    })
+}
+
+fn payload_values(payload: Option<Vec<Vec<u8>>>, op: &str) -> anyhow::Result<Vec<String>> {
+    let values = payload.unwrap_or_default();
+    if values.is_empty() {
+        anyhow::bail!("Empty `payload` for `{op}`");
+    }
+    values
+        .into_iter()
+        .map(|value| {
+            String::from_utf8(value)
+                .map_err(|_| anyhow::anyhow!("SQL cache `payload` must be UTF-8 encoded"))
+        })
+        .collect()
+}
+
+async fn read_keys(db: &Database, trader_key: &str) -> anyhow::Result<Vec<String>> {
+    let mut keys = Vec::new();
+
+    let rows: Vec<KeyRow> = sqlx::query_as("SELECT key FROM cache_general WHERE trader_key = $1")
+        .bind(trader_key)
+        .fetch_all(&db.pool)
+        .await?;
+    keys.extend(
+        rows.into_iter()
+            .map(|row| format!("{GENERAL}{DELIMITER}{}", row.key)),
+    );
+
+    for collection in [
+        CURRENCIES,
+        INSTRUMENTS,
+        SYNTHETICS,
+        ACCOUNTS,
+        ORDERS,
+        POSITIONS,
+    ] {
+        let (table, id_column, _) = object_table(collection).unwrap();
+        let query =
+            format!("SELECT DISTINCT {id_column} AS key FROM {table} WHERE trader_key = $1");
+        let rows: Vec<KeyRow> = sqlx::query_as(&query)
+            .bind(trader_key)
+            .fetch_all(&db.pool)
+            .await?;
+        keys.extend(
+            rows.into_iter()
+                .map(|row| format!("{collection}{DELIMITER}{}", row.key)),
+        );
+    }
+
+    let rows: Vec<KeyRow> =
+        sqlx::query_as("SELECT DISTINCT index_key AS key FROM cache_index WHERE trader_key = $1")
+            .bind(trader_key)
+            .fetch_all(&db.pool)
+            .await?;
+    keys.extend(
+        rows.into_iter()
+            .map(|row| format!("{INDEX}{DELIMITER}{}", row.key)),
+    );
+
+    Ok(keys)
+}
+
+async fn read(db: &Database, trader_key: &str, key: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+    let (collection, id) = split_key(key)?;
+
+    let payloads: Vec<String> = match collection {
+        INDEX => {
+            let rows: Vec<IndexRow> = sqlx::query_as(
+                "SELECT member, value FROM cache_index WHERE trader_key = $1 AND index_key = $2",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .fetch_all(&db.pool)
+            .await?;
+
+            if INDEX_HASH_KEYS.contains(&id) {
+                let map: HashMap<String, String> = rows
+                    .into_iter()
+                    .map(|row| (row.member, row.value))
+                    .collect();
+                vec![serde_json::to_string(&map)?]
+            } else {
+                rows.into_iter().map(|row| row.member).collect()
+            }
+        }
+        GENERAL => {
+            let rows: Vec<PayloadRow> = sqlx::query_as(
+                "SELECT value AS payload FROM cache_general WHERE trader_key = $1 AND key = $2",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .fetch_all(&db.pool)
+            .await?;
+            rows.into_iter().map(|row| row.payload).collect()
+        }
+        _ => {
+            let Some((table, id_column, is_list)) = object_table(collection) else {
+                anyhow::bail!("Unsupported operation: `read` for collection '{collection}'")
+            };
+            let order_by = if is_list { " ORDER BY seq" } else { "" };
+            let query = format!(
+                "SELECT payload FROM {table} WHERE trader_key = $1 AND {id_column} = $2{order_by}"
+            );
+            let rows: Vec<PayloadRow> = sqlx::query_as(&query)
+                .bind(trader_key)
+                .bind(id)
+                .fetch_all(&db.pool)
+                .await?;
+            rows.into_iter().map(|row| row.payload).collect()
+        }
+    };
+
+    Ok(payloads.into_iter().map(String::into_bytes).collect())
+}
+
+async fn insert(
+    db: &Database,
+    trader_key: &str,
+    key: &str,
+    payload: Option<Vec<Vec<u8>>>,
+) -> anyhow::Result<()> {
+    let (collection, id) = split_key(key)?;
+    let values = payload_values(payload, "insert")?;
+
+    match collection {
+        INDEX => {
+            let value = values.get(1).cloned().unwrap_or_default();
+            sqlx::query(
+                "INSERT INTO cache_index (trader_key, index_key, member, value) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (trader_key, index_key, member) DO UPDATE SET value = excluded.value",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .bind(&values[0])
+            .bind(value)
+            .execute(&db.pool)
+            .await?;
+        }
+        GENERAL => {
+            sqlx::query(
+                "INSERT INTO cache_general (trader_key, key, value) VALUES ($1, $2, $3) \
+                 ON CONFLICT (trader_key, key) DO UPDATE SET value = excluded.value",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .bind(&values[0])
+            .execute(&db.pool)
+            .await?;
+        }
+        CURRENCIES => {
+            let json: Value = serde_json::from_str(&values[0])?;
+            sqlx::query(
+                "INSERT INTO currency \
+                 (trader_key, code, precision, iso4217, name, currency_type, payload) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                 ON CONFLICT (trader_key, code) DO UPDATE SET \
+                 precision = excluded.precision, iso4217 = excluded.iso4217, \
+                 name = excluded.name, currency_type = excluded.currency_type, \
+                 payload = excluded.payload",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .bind(json_i64(&json, "precision"))
+            .bind(json_i64(&json, "iso4217"))
+            .bind(json_string(&json, "name"))
+            .bind(json_string(&json, "currency_type"))
+            .bind(&values[0])
+            .execute(&db.pool)
+            .await?;
+        }
+        INSTRUMENTS => {
+            // Instruments are serialized externally tagged by their type
+            let json: Value = serde_json::from_str(&values[0])?;
+            let kind = json.as_object().and_then(|map| map.keys().next().cloned());
+            sqlx::query(
+                "INSERT INTO instrument (trader_key, id, kind, payload) VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (trader_key, id) DO UPDATE SET \
+                 kind = excluded.kind, payload = excluded.payload",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .bind(kind)
+            .bind(&values[0])
+            .execute(&db.pool)
+            .await?;
+        }
+        SYNTHETICS => {
+            let json: Value = serde_json::from_str(&values[0])?;
+            sqlx::query(
+                "INSERT INTO synthetic (trader_key, id, formula, payload) VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (trader_key, id) DO UPDATE SET \
+                 formula = excluded.formula, payload = excluded.payload",
+            )
+            .bind(trader_key)
+            .bind(id)
+            .bind(json_string(&json, "formula"))
+            .bind(&values[0])
+            .execute(&db.pool)
+            .await?;
+        }
+        ACCOUNTS | ORDERS | POSITIONS => {
+            append_event(db, trader_key, collection, id, &values[0], false).await?;
+        }
+        _ => anyhow::bail!("Unsupported operation: `insert` for collection '{collection}'"),
+    }
+
+    Ok(())
+}
+
+async fn update(
+    db: &Database,
+    trader_key: &str,
+    key: &str,
+    payload: Option<Vec<Vec<u8>>>,
+) -> anyhow::Result<()> {
+    let (collection, id) = split_key(key)?;
+    let values = payload_values(payload, "update")?;
+
+    match collection {
+        ACCOUNTS | ORDERS | POSITIONS => {
+            append_event(db, trader_key, collection, id, &values[0], true).await
+        }
+        _ => anyhow::bail!("Unsupported operation: `update` for collection '{collection}'"),
+    }
+}
+
+/// Appends the `payload` as the next row in sequence for the object, extracting the
+/// queryable columns for the `collection`.
+///
+/// The sequence number is assigned from the last row, so where a concurrent writer takes the
+/// same number first (violating the primary key) the append is retried.
+///
+/// # Errors
+///
+/// Returns an error if `must_exist` and the object has no existing rows.
+async fn append_event(
+    db: &Database,
+    trader_key: &str,
+    collection: &str,
+    id: &str,
+    payload: &str,
+    must_exist: bool,
+) -> anyhow::Result<()> {
+    let json: Value = serde_json::from_str(payload)?;
+    let (table, id_column, _) = object_table(collection).unwrap();
+    // Order events are externally tagged, as `{"OrderFilled": {..}}`
+    let (event_type, event) = match (collection, &json) {
+        (ORDERS, Value::Object(map)) if map.len() == 1 => {
+            let (event_type, event) = map.iter().next().unwrap();
+            (Some(event_type.clone()), event)
+        }
+        _ => (None, &json),
+    };
+    let (columns, values): (&str, Vec<Option<String>>) = match collection {
+        ACCOUNTS => ("account_type", vec![json_string(&json, "account_type")]),
+        ORDERS => ("event_type", vec![event_type]),
+        _ => (
+            "instrument_id, strategy_id, side, quantity",
+            vec![
+                json_string(&json, "instrument_id"),
+                json_string(&json, "strategy_id"),
+                json_string(&json, "side"),
+                json_string(&json, "quantity"),
+            ],
+        ),
+    };
+    let ts_column = if collection == POSITIONS {
+        "ts_last"
+    } else {
+        "ts_event"
+    };
+
+    // Parameters: $1 trader_key, $2 id, $3 ts, $4 payload, $5.. extracted columns
+    let placeholders: Vec<String> = (0..values.len()).map(|i| format!("${}", i + 5)).collect();
+    let having = if must_exist {
+        " HAVING COUNT(*) > 0"
+    } else {
+        ""
+    };
+    let query = format!(
+        "INSERT INTO {table} (trader_key, {id_column}, seq, {ts_column}, payload, {columns}) \
+         SELECT $1, $2, COALESCE(MAX(seq) + 1, 0), $3, $4, {} \
+         FROM {table} WHERE trader_key = $1 AND {id_column} = $2{having}",
+        placeholders.join(", "),
+    );
+
+    let mut attempt = 1;
+    let result = loop {
+        let mut sql_query = sqlx::query(&query)
+            .bind(trader_key)
+            .bind(id)
+            .bind(json_i64(event, ts_column))
+            .bind(payload);
+        for value in &values {
+            sql_query = sql_query.bind(value.clone());
+        }
+        match sql_query.execute(&db.pool).await {
+            Err(Error::Database(e)) if e.is_unique_violation() && attempt < MAX_APPEND_ATTEMPTS => {
+                debug!("Retrying append for '{collection}{DELIMITER}{id}': {e}");
+                attempt += 1;
+            }
+            result => break result?,
+        }
+    };
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("Cannot update '{collection}{DELIMITER}{id}': no existing entry");
+    }
+    Ok(())
+}
+
+async fn delete(
+    db: &Database,
+    trader_key: &str,
+    key: &str,
+    payload: Option<Vec<Vec<u8>>>,
+) -> anyhow::Result<()> {
+    let (collection, id) = split_key(key)?;
+
+    match collection {
+        INDEX => match payload {
+            Some(payload) => {
+                for member in payload_values(Some(payload), "delete")? {
+                    sqlx::query(
+                        "DELETE FROM cache_index \
+                         WHERE trader_key = $1 AND index_key = $2 AND member = $3",
+                    )
+                    .bind(trader_key)
+                    .bind(id)
+                    .bind(member)
+                    .execute(&db.pool)
+                    .await?;
+                }
+            }
+            None => {
+                sqlx::query("DELETE FROM cache_index WHERE trader_key = $1 AND index_key = $2")
+                    .bind(trader_key)
+                    .bind(id)
+                    .execute(&db.pool)
+                    .await?;
+            }
+        },
+        GENERAL => {
+            sqlx::query("DELETE FROM cache_general WHERE trader_key = $1 AND key = $2")
+                .bind(trader_key)
+                .bind(id)
+                .execute(&db.pool)
+                .await?;
+        }
+        _ => {
+            let Some((table, id_column, _)) = object_table(collection) else {
+                anyhow::bail!("Unsupported operation: `delete` for collection '{collection}'")
+            };
+            let query = format!("DELETE FROM {table} WHERE trader_key = $1 AND {id_column} = $2");
+            sqlx::query(&query)
+                .bind(trader_key)
+                .bind(id)
+                .execute(&db.pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn flush(db: &Database, trader_key: &str) -> anyhow::Result<()> {
+    for table in [
+        "cache_general",
+        "currency",
+        "instrument",
+        "synthetic",
+        "account_event",
+        "order_event",
+        "position_snapshot",
+        "cache_index",
+    ] {
+        let query = format!("DELETE FROM {table} WHERE trader_key = $1");
+        sqlx::query(&query)
+            .bind(trader_key)
+            .execute(&db.pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn json_string(json: &Value, field: &str) -> Option<String> {
+    match json.get(field)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+fn json_i64(json: &Value, field: &str) -> Option<i64> {
+    json.get(field).and_then(Value::as_i64)
+}
+
+/// Returns whether the `key` matches the glob `pattern` (supporting `*` wildcards only).
+fn is_glob_match(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // SAFETY: `split` always yields at least one part
+    let first = parts.next().unwrap();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty(); // No wildcard, so must be an exact match
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Tests
 ////////////////////////////////////////////////////////////////////////////////
 #[cfg(test)]
 mod tests {
-    use nautilus_model::identifiers::stubs::trader_id;
+    use std::collections::HashMap;
 
+    use nautilus_common::cache::CacheDatabase;
+    use nautilus_core::uuid::UUID4;
+    use nautilus_model::{
+        events::order::{
+            accepted::OrderAccepted, event::OrderEvent, initialized::OrderInitialized, stubs::*,
+            submitted::OrderSubmitted,
+        },
+        identifiers::stubs::trader_id,
+    };
+    use rstest::rstest;
+    use serde_json::json;
+    use sqlx::Row;
+    use tempfile::TempDir;
+
+    use super::{block_on, is_glob_match};
     use crate::db::{
         database::{init_db_schema, setup_test_database},
         sql::SqlCacheDatabase,
     };
 
+    fn sqlite_cache_database(dir: &TempDir) -> SqlCacheDatabase {
+        let path = dir.path().join("cache.sqlite");
+        let config = HashMap::from([(
+            "database".to_string(),
+            json!({
+                "engine": "sqlite",
+                "url": format!("sqlite:{}?mode=rwc", path.display()),
+                "schema_dir": "../../schema",
+            }),
+        )]);
+        <SqlCacheDatabase as CacheDatabase>::new(trader_id(), UUID4::new(), config).unwrap()
+    }
+
+    fn connect_postgres_cache_database(schema_dir: Option<&str>) -> SqlCacheDatabase {
+        let url = std::env::var("TEST_POSTGRES_URL").expect("No `TEST_POSTGRES_URL` env var");
+        let config = HashMap::from([(
+            "database".to_string(),
+            json!({
+                "engine": "postgres",
+                "url": url,
+                "schema_dir": schema_dir,
+            }),
+        )]);
+        <SqlCacheDatabase as CacheDatabase>::new(trader_id(), UUID4::new(), config).unwrap()
+    }
+
+    /// Returns a flushed cache database for a PostgreSQL server, which is shared between
+    /// tests so these must be run one at a time (e.g. with `--test-threads=1`).
+    fn postgres_cache_database() -> SqlCacheDatabase {
+        let mut db = connect_postgres_cache_database(Some("../../schema"));
+        db.flushdb().unwrap();
+        db
+    }
+
+    fn payload(value: &str) -> Option<Vec<Vec<u8>>> {
+        Some(vec![value.as_bytes().to_vec()])
+    }
+
+    fn event_payload(event: OrderEvent) -> String {
+        serde_json::to_value(event).unwrap().to_string()
+    }
+
+    /// Returns the serialized order initialized, submitted and accepted events, with
+    /// event timestamps of 1, 2 and 3.
+    fn order_event_payloads(
+        mut initialized: OrderInitialized,
+        mut submitted: OrderSubmitted,
+        mut accepted: OrderAccepted,
+    ) -> [String; 3] {
+        initialized.ts_event = 1;
+        submitted.ts_event = 2;
+        accepted.ts_event = 3;
+        [
+            event_payload(OrderEvent::OrderInitialized(initialized)),
+            event_payload(OrderEvent::OrderSubmitted(submitted)),
+            event_payload(OrderEvent::OrderAccepted(accepted)),
+        ]
+    }
+
+    /// Returns the (event type, event timestamp) columns of the order event rows in sequence.
+    fn order_event_columns(
+        db: &SqlCacheDatabase,
+        client_order_id: &str,
+    ) -> Vec<(Option<String>, Option<i64>)> {
+        let rows = block_on(
+            sqlx::query(
+                "SELECT event_type, ts_event FROM order_event \
+                 WHERE trader_key = $1 AND client_order_id = $2 ORDER BY seq",
+            )
+            .bind(&db.trader_key)
+            .bind(client_order_id)
+            .fetch_all(&db.db.pool),
+        )
+        .unwrap();
+        rows.iter()
+            .map(|row| (row.get("event_type"), row.get("ts_event")))
+            .collect()
+    }
+
     async fn setup_sql_cache_database() -> SqlCacheDatabase {
         let db = setup_test_database().await;
         let schema_dir = "../../schema";
@@ -99,4 +787,220 @@ mod tests {
         assert_eq!(item.key, "key1");
         assert_eq!(item.value, "value1");
     }
+
+    #[rstest]
+    #[case("*", "orders:O-1", true)]
+    #[case("orders:*", "orders:O-1", true)]
+    #[case("orders:*", "positions:P-1", false)]
+    #[case("*:O-1", "orders:O-1", true)]
+    #[case("orders:O-1", "orders:O-1", true)]
+    #[case("orders:O-1", "orders:O-12", false)]
+    #[case("o*s:*1", "orders:O-1", true)]
+    fn test_is_glob_match(#[case] pattern: &str, #[case] key: &str, #[case] expected: bool) {
+        assert_eq!(is_glob_match(pattern, key), expected);
+    }
+
+    fn check_insert_and_read_objects(db: &mut SqlCacheDatabase) {
+        let currency = r#"{"code":"AUD","precision":2,"iso4217":36,"name":"Australian dollar","currency_type":"FIAT"}"#;
+        let instrument = r#"{"CurrencyPair":{"id":"AUD/USD.SIM"}}"#;
+        db.insert("general:A".to_string(), payload("1")).unwrap();
+        db.insert("currencies:AUD".to_string(), payload(currency))
+            .unwrap();
+        db.insert("instruments:AUD/USD.SIM".to_string(), payload(instrument))
+            .unwrap();
+
+        assert_eq!(db.read("general:A").unwrap(), vec![b"1".to_vec()]);
+        assert_eq!(
+            db.read("currencies:AUD").unwrap(),
+            vec![currency.as_bytes().to_vec()]
+        );
+        assert_eq!(
+            db.read("instruments:AUD/USD.SIM").unwrap(),
+            vec![instrument.as_bytes().to_vec()]
+        );
+        assert!(db.read("currencies:USD").unwrap().is_empty());
+    }
+
+    fn check_insert_and_update_event_lists_in_sequence(
+        db: &mut SqlCacheDatabase,
+        [event1, event2, event3]: [String; 3],
+    ) {
+        db.insert("orders:O-1".to_string(), payload(&event1))
+            .unwrap();
+        db.update("orders:O-1".to_string(), payload(&event2))
+            .unwrap();
+        db.update("orders:O-1".to_string(), payload(&event3))
+            .unwrap();
+
+        // Update is only applied for existing orders
+        assert!(db
+            .update("orders:O-2".to_string(), payload(&event2))
+            .is_err());
+
+        assert_eq!(
+            db.read("orders:O-1").unwrap(),
+            vec![
+                event1.as_bytes().to_vec(),
+                event2.as_bytes().to_vec(),
+                event3.as_bytes().to_vec(),
+            ]
+        );
+        assert!(db.read("orders:O-2").unwrap().is_empty());
+        assert_eq!(
+            order_event_columns(db, "O-1"),
+            vec![
+                (Some("OrderInitialized".to_string()), Some(1)),
+                (Some("OrderSubmitted".to_string()), Some(2)),
+                (Some("OrderAccepted".to_string()), Some(3)),
+            ]
+        );
+    }
+
+    fn check_index_sets_and_maps(db: &mut SqlCacheDatabase) {
+        db.insert(
+            "index:order_position".to_string(),
+            Some(vec![b"O-1".to_vec(), b"P-1".to_vec()]),
+        )
+        .unwrap();
+        db.insert("index:orders_open".to_string(), payload("O-1"))
+            .unwrap();
+        db.insert("index:orders_open".to_string(), payload("O-2"))
+            .unwrap();
+        db.delete("index:orders_open".to_string(), payload("O-1"))
+            .unwrap();
+
+        let map = db.read("index:order_position").unwrap();
+        let map: HashMap<String, String> = serde_json::from_slice(&map[0]).unwrap();
+        assert_eq!(map, HashMap::from([("O-1".to_string(), "P-1".to_string())]));
+        assert_eq!(db.read("index:orders_open").unwrap(), vec![b"O-2".to_vec()]);
+    }
+
+    fn check_keys_delete_and_flushdb(db: &mut SqlCacheDatabase) {
+        db.insert("general:A".to_string(), payload("1")).unwrap();
+        db.insert(
+            "accounts:SIM-001".to_string(),
+            payload(r#"{"account_type":"CASH","ts_event":1}"#),
+        )
+        .unwrap();
+        db.insert(
+            "positions:P-1".to_string(),
+            payload(r#"{"instrument_id":"AUD/USD.SIM","side":"LONG","ts_last":1}"#),
+        )
+        .unwrap();
+
+        let mut keys = db.keys("*").unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "trader-TRADER-001:accounts:SIM-001",
+                "trader-TRADER-001:general:A",
+                "trader-TRADER-001:positions:P-1",
+            ]
+        );
+        assert_eq!(
+            db.keys("positions:*").unwrap(),
+            vec!["trader-TRADER-001:positions:P-1"]
+        );
+
+        db.delete("positions:P-1".to_string(), None).unwrap();
+        assert!(db.read("positions:P-1").unwrap().is_empty());
+
+        db.flushdb().unwrap();
+        assert!(db.keys("*").unwrap().is_empty());
+    }
+
+    #[rstest]
+    fn test_sqlite_insert_and_read_objects() {
+        let dir = TempDir::new().unwrap();
+        check_insert_and_read_objects(&mut sqlite_cache_database(&dir));
+    }
+
+    #[rstest]
+    fn test_sqlite_insert_and_update_event_lists_in_sequence(
+        order_initialized_buy_limit: OrderInitialized,
+        order_submitted: OrderSubmitted,
+        order_accepted: OrderAccepted,
+    ) {
+        let dir = TempDir::new().unwrap();
+        check_insert_and_update_event_lists_in_sequence(
+            &mut sqlite_cache_database(&dir),
+            order_event_payloads(order_initialized_buy_limit, order_submitted, order_accepted),
+        );
+    }
+
+    #[rstest]
+    fn test_sqlite_index_sets_and_maps() {
+        let dir = TempDir::new().unwrap();
+        check_index_sets_and_maps(&mut sqlite_cache_database(&dir));
+    }
+
+    #[rstest]
+    fn test_sqlite_keys_delete_and_flushdb() {
+        let dir = TempDir::new().unwrap();
+        check_keys_delete_and_flushdb(&mut sqlite_cache_database(&dir));
+    }
+
+    #[rstest]
+    #[ignore = "requires a PostgreSQL database at `TEST_POSTGRES_URL`"]
+    fn test_postgres_insert_and_read_objects() {
+        check_insert_and_read_objects(&mut postgres_cache_database());
+    }
+
+    #[rstest]
+    #[ignore = "requires a PostgreSQL database at `TEST_POSTGRES_URL`"]
+    fn test_postgres_insert_and_update_event_lists_in_sequence(
+        order_initialized_buy_limit: OrderInitialized,
+        order_submitted: OrderSubmitted,
+        order_accepted: OrderAccepted,
+    ) {
+        check_insert_and_update_event_lists_in_sequence(
+            &mut postgres_cache_database(),
+            order_event_payloads(order_initialized_buy_limit, order_submitted, order_accepted),
+        );
+    }
+
+    #[rstest]
+    #[ignore = "requires a PostgreSQL database at `TEST_POSTGRES_URL`"]
+    fn test_postgres_index_sets_and_maps() {
+        check_index_sets_and_maps(&mut postgres_cache_database());
+    }
+
+    #[rstest]
+    #[ignore = "requires a PostgreSQL database at `TEST_POSTGRES_URL`"]
+    fn test_postgres_keys_delete_and_flushdb() {
+        check_keys_delete_and_flushdb(&mut postgres_cache_database());
+    }
+
+    #[rstest]
+    #[ignore = "requires a PostgreSQL database at `TEST_POSTGRES_URL`"]
+    fn test_postgres_concurrent_updates_are_sequenced(
+        order_submitted: OrderSubmitted,
+        order_accepted: OrderAccepted,
+    ) {
+        let mut db = postgres_cache_database();
+        let event = event_payload(OrderEvent::OrderSubmitted(order_submitted));
+        db.insert("orders:O-1".to_string(), payload(&event))
+            .unwrap();
+
+        std::thread::scope(|s| {
+            for i in 1..=4 {
+                s.spawn(move || {
+                    let mut db = connect_postgres_cache_database(None);
+                    for j in 0..10 {
+                        let mut accepted = order_accepted;
+                        accepted.ts_event = i * 100 + j;
+                        let event = event_payload(OrderEvent::OrderAccepted(accepted));
+                        db.update("orders:O-1".to_string(), payload(&event))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(db.read("orders:O-1").unwrap().len(), 41);
+        assert!(order_event_columns(&db, "O-1")
+            .iter()
+            .all(|(event_type, ts_event)| event_type.is_some() && ts_event.is_some()));
+    }
 }
@@ -14,12 +14,18 @@
 // -------------------------------------------------------------------------------------------------
 
 use nautilus_core::ffi::cvec::CVec;
-use nautilus_model::data::{
-    bar::Bar, delta::OrderBookDelta, is_monotonically_increasing_by_init, quote::QuoteTick,
-    trade::TradeTick, Data,
+use nautilus_model::{
+    data::{
+        bar::Bar, delta::OrderBookDelta, is_monotonically_increasing_by_init, quote::QuoteTick,
+        trade::TradeTick, Data, HasTsInit,
+    },
+    identifiers::instrument_id::InstrumentId,
 };
 use nautilus_persistence::{
-    backend::session::{DataBackendSession, DataQueryResult, QueryResult},
+    backend::{
+        query::DataQuery,
+        session::{DataBackendSession, DataQueryResult, QueryResult},
+    },
     python::backend::session::NautilusDataType,
 };
 #[cfg(target_os = "linux")]
@@ -235,6 +241,54 @@ fn test_quote_tick_query() {
     assert!(is_monotonically_increasing_by_init(&ticks));
 }
 
+#[rstest]
+fn test_quote_tick_query_with_data_query() {
+    let file_path = "../../tests/test_data/nautilus/quotes.parquet";
+    let mut catalog = DataBackendSession::new(10_000);
+    catalog
+        .add_file::<QuoteTick>("quote_005", file_path, None)
+        .unwrap();
+    let all: Vec<Data> = catalog.get_query_result().collect();
+    let start = all[1_000].get_ts_init();
+    let end = all[1_999].get_ts_init();
+    let expected: Vec<u64> = all
+        .iter()
+        .map(HasTsInit::get_ts_init)
+        .filter(|ts_init| (start..=end).contains(ts_init))
+        .collect();
+
+    let query = DataQuery::new()
+        .with_instrument_ids(vec![InstrumentId::from("EUR/USD.SIM")])
+        .with_start(start)
+        .with_end(end);
+    catalog
+        .add_file_with_query::<QuoteTick>("quote_006", file_path, &query)
+        .unwrap();
+    let ticks: Vec<Data> = catalog.get_query_result().collect();
+
+    assert_eq!(
+        ticks
+            .iter()
+            .map(HasTsInit::get_ts_init)
+            .collect::<Vec<u64>>(),
+        expected
+    );
+    assert!(is_monotonically_increasing_by_init(&ticks));
+}
+
+#[rstest]
+fn test_quote_tick_query_with_data_query_for_other_instrument() {
+    let file_path = "../../tests/test_data/nautilus/quotes.parquet";
+    let mut catalog = DataBackendSession::new(10_000);
+    let query = DataQuery::new().with_instrument_ids(vec![InstrumentId::from("AUD/USD.SIM")]);
+    catalog
+        .add_file_with_query::<QuoteTick>("quote_005", file_path, &query)
+        .unwrap();
+    let ticks: Vec<Data> = catalog.get_query_result().collect();
+
+    assert!(ticks.is_empty());
+}
+
 #[rstest]
 fn test_quote_tick_multiple_query() {
     let expected_length = 9_600;
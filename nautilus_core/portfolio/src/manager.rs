@@ -0,0 +1,628 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use nautilus_accounting::{
+    account::{any::AccountAny, margin::MarginAccount, Account},
+    margin::{MarginLeg, MarginPosition},
+};
+use nautilus_common::cache::Cache;
+use nautilus_core::time::{AtomicTime, UnixNanos};
+use nautilus_model::{
+    enums::{OrderSide, PriceType},
+    events::{account::state::AccountState, order::filled::OrderFilled},
+    identifiers::instrument_id::InstrumentId,
+    instruments::{Instrument, InstrumentType},
+    orders::any::OrderAny,
+    position::Position,
+    types::{balance::AccountBalance, currency::Currency, money::Money},
+};
+
+/// Manages the calculated balances and margins of accounts, generating a new
+/// account state event for each update.
+pub struct AccountsManager {
+    clock: &'static AtomicTime,
+    cache: Rc<RefCell<Cache>>,
+}
+
+impl AccountsManager {
+    #[must_use]
+    pub fn new(clock: &'static AtomicTime, cache: Rc<RefCell<Cache>>) -> Self {
+        Self { clock, cache }
+    }
+
+    /// Updates the `account` balances for the given `fill`.
+    ///
+    /// Returns `None` if the balances could not be calculated.
+    pub fn update_balances(
+        &self,
+        account: &mut AccountAny,
+        instrument: InstrumentType,
+        fill: OrderFilled,
+    ) -> Option<AccountState> {
+        let position = {
+            let cache = self.cache.borrow();
+            let position_id = fill.position_id.or_else(|| {
+                cache
+                    .positions_open(None, Some(&fill.instrument_id))
+                    .first()
+                    .map(|position| position.id)
+            });
+            position_id.and_then(|position_id| cache.position(&position_id).cloned())
+        };
+
+        account.record_fill(&instrument, &fill);
+
+        let pnls = match account.calculate_pnls(instrument, fill, position) {
+            Ok(pnls) => pnls,
+            Err(e) => {
+                log::error!("Cannot calculate PnLs for {}: {e}", fill.client_order_id);
+                return None;
+            }
+        };
+        log::debug!("Calculated PnLs: {pnls:?}");
+
+        if let Some(base_currency) = account.base_currency {
+            let pnl = pnls
+                .first()
+                .copied()
+                .unwrap_or_else(|| Money::from_raw(0, base_currency));
+            self.update_balance_single_currency(account, &fill, base_currency, pnl);
+        } else {
+            self.update_balance_multi_currency(account, &fill, &pnls);
+        }
+
+        Some(self.generate_account_state(account, fill.ts_event))
+    }
+
+    /// Updates the balance locked (cash accounts) or initial margin (margin accounts)
+    /// of the `account` for the given open orders of the `instrument`.
+    ///
+    /// Returns `None` if the update could not be calculated.
+    pub fn update_orders(
+        &self,
+        account: &mut AccountAny,
+        instrument: InstrumentType,
+        orders_open: &[&OrderAny],
+        ts_event: UnixNanos,
+    ) -> Option<AccountState> {
+        match account {
+            AccountAny::Cash(_) => {
+                self.update_balance_locked(account, instrument, orders_open, ts_event)
+            }
+            AccountAny::Margin(_) => {
+                self.update_margin_init(account, instrument, orders_open, ts_event)
+            }
+        }
+    }
+
+    /// Updates the maintenance margins of a margin `account` for the given open
+    /// positions at its venue, which are calculated together by the account margin
+    /// model (so that positions may offset each other).
+    ///
+    /// The maintenance margin of the `instrument` is cleared if it has no open positions.
+    ///
+    /// Returns `None` if the account is not a margin account, or the update could not
+    /// be calculated.
+    pub fn update_positions(
+        &self,
+        account: &mut AccountAny,
+        instrument: InstrumentType,
+        positions_open: &[&Position],
+        ts_event: UnixNanos,
+    ) -> Option<AccountState> {
+        let base_currency = account.base_currency;
+        let AccountAny::Margin(margin_account) = account else {
+            return None; // Only margin accounts have maintenance margins
+        };
+
+        let mut instruments: HashMap<InstrumentId, (InstrumentType, OrderSide)> = HashMap::new();
+        let mut margin_positions = Vec::with_capacity(positions_open.len());
+        for position in positions_open {
+            if !position.is_open() {
+                continue; // Does not contribute to maintenance margin
+            }
+
+            let position_instrument = if position.instrument_id == instrument.id() {
+                instrument.clone()
+            } else {
+                let cache = self.cache.borrow();
+                let Some(position_instrument) = cache
+                    .instrument(&position.instrument_id)
+                    .and_then(InstrumentType::from_instrument)
+                else {
+                    log::error!(
+                        "Cannot calculate maintenance margin: no instrument for {}",
+                        position.instrument_id
+                    );
+                    return None;
+                };
+                position_instrument
+            };
+            let price = match position_instrument.make_price(position.avg_px_open) {
+                Ok(price) => price,
+                Err(e) => {
+                    log::error!("Cannot calculate maintenance margin: {e}");
+                    return None;
+                }
+            };
+
+            let leverage = margin_account.get_leverage(&position.instrument_id);
+            let mut margin_position = MarginPosition::from_position(
+                position_instrument.clone(),
+                position,
+                price,
+                leverage,
+            );
+            if let Some(legs) = self.spread_margin_legs(margin_account, &position.instrument_id) {
+                margin_position = margin_position.with_legs(legs);
+            }
+            margin_positions.push(margin_position);
+            instruments
+                .entry(position.instrument_id)
+                .or_insert((position_instrument, position.entry));
+        }
+
+        let mut margins = match margin_account.calculate_positions_margin(&margin_positions) {
+            Ok(margins) => margins,
+            Err(e) => {
+                log::error!("Cannot calculate maintenance margin: {e}");
+                return None;
+            }
+        };
+        margins
+            .entry(instrument.id())
+            .or_insert_with(|| Money::from_raw(0, instrument.settlement_currency()));
+
+        for (instrument_id, mut margin_maint) in margins {
+            if let Some(base_currency) = base_currency {
+                if margin_maint.raw == 0 {
+                    margin_maint = Money::from_raw(0, base_currency);
+                } else {
+                    let (margin_instrument, side) = &instruments[&instrument_id];
+                    let Some(xrate) =
+                        self.calculate_xrate_to_base(margin_instrument, base_currency, *side)
+                    else {
+                        log::debug!(
+                            "Cannot calculate maintenance margin: insufficient data for {}/{}",
+                            margin_instrument.settlement_currency().code,
+                            base_currency.code,
+                        );
+                        return None;
+                    };
+                    margin_maint = Money::new(margin_maint.as_f64() * xrate, base_currency).ok()?;
+                }
+            }
+
+            if margin_maint.raw == 0 {
+                margin_account.clear_maintenance_margin(instrument_id);
+            } else {
+                if !margin_account.balances.contains_key(&margin_maint.currency) {
+                    log::error!(
+                        "Cannot update maintenance margin: no {} balance",
+                        margin_maint.currency.code
+                    );
+                    return None;
+                }
+                margin_account.update_maintenance_margin(instrument_id, margin_maint);
+            }
+            log::info!("{instrument_id} margin_maint={margin_maint}");
+        }
+
+        Some(self.generate_account_state(account, ts_event))
+    }
+
+    /// Returns the legs of the given spread instrument for margining, or `None` if the
+    /// legs are not set for the `account`, or any leg has no instrument or price.
+    fn spread_margin_legs(
+        &self,
+        account: &MarginAccount,
+        instrument_id: &InstrumentId,
+    ) -> Option<Vec<MarginLeg>> {
+        let legs = account.spread_legs.get(instrument_id)?;
+        let cache = self.cache.borrow();
+        let margin_legs: Option<Vec<MarginLeg>> = legs
+            .iter()
+            .map(|leg| {
+                let instrument = cache
+                    .instrument(&leg.instrument_id)
+                    .and_then(InstrumentType::from_instrument)?;
+                let price = cache
+                    .price(&leg.instrument_id, PriceType::Mid)
+                    .or_else(|| cache.price(&leg.instrument_id, PriceType::Last))?;
+                Some(MarginLeg::new(instrument, leg.ratio, price))
+            })
+            .collect();
+        if margin_legs.is_none() {
+            log::debug!("Cannot price spread legs for {instrument_id}: margining as outright");
+        }
+        margin_legs
+    }
+
+    fn update_balance_locked(
+        &self,
+        account: &mut AccountAny,
+        instrument: InstrumentType,
+        orders_open: &[&OrderAny],
+        ts_event: UnixNanos,
+    ) -> Option<AccountState> {
+        let base_currency = account.base_currency;
+        let AccountAny::Cash(cash_account) = account else {
+            return None;
+        };
+
+        if orders_open.is_empty() {
+            cash_account.clear_balance_locked(&instrument.id());
+            return Some(self.generate_account_state(account, ts_event));
+        }
+
+        let mut total_locked = 0.0;
+        let mut base_xrate: Option<f64> = None;
+        let mut currency = instrument.settlement_currency();
+
+        for order in orders_open {
+            let Some(price) = order.price().or(order.trigger_price()) else {
+                continue; // No price to lock a balance against
+            };
+
+            let locked = match cash_account.calculate_balance_locked(
+                instrument.clone(),
+                order.order_side(),
+                order.quantity(),
+                price,
+                None,
+            ) {
+                Ok(locked) => locked,
+                Err(e) => {
+                    log::error!("Cannot calculate balance locked: {e}");
+                    return None;
+                }
+            };
+            let mut locked_f64 = locked.as_f64();
+
+            if let Some(base_currency) = base_currency {
+                let xrate = match base_xrate {
+                    Some(xrate) => xrate,
+                    None => {
+                        currency = base_currency;
+                        let Some(xrate) = self.calculate_xrate_to_base(
+                            &instrument,
+                            base_currency,
+                            order.order_side(),
+                        ) else {
+                            log::debug!(
+                                "Cannot calculate balance locked: insufficient data for {}/{}",
+                                instrument.settlement_currency().code,
+                                base_currency.code,
+                            );
+                            return None;
+                        };
+                        *base_xrate.insert(xrate)
+                    }
+                };
+                locked_f64 *= xrate;
+            } else {
+                currency = locked.currency;
+            }
+
+            total_locked += locked_f64;
+        }
+
+        let locked = Money::new(total_locked, currency).ok()?;
+        if let Err(e) = cash_account.update_balance_locked(instrument.id(), locked) {
+            log::error!("Cannot update balance locked: {e}");
+            return None;
+        }
+        log::info!("{} balance_locked={locked}", instrument.id());
+
+        Some(self.generate_account_state(account, ts_event))
+    }
+
+    fn update_margin_init(
+        &self,
+        account: &mut AccountAny,
+        instrument: InstrumentType,
+        orders_open: &[&OrderAny],
+        ts_event: UnixNanos,
+    ) -> Option<AccountState> {
+        let base_currency = account.base_currency;
+        let AccountAny::Margin(margin_account) = account else {
+            return None;
+        };
+
+        let mut total_margin_init = 0.0;
+        let mut base_xrate: Option<f64> = None;
+        let mut currency = instrument.settlement_currency();
+
+        for order in orders_open {
+            let Some(price) = order.price().or(order.trigger_price()) else {
+                continue; // No price to calculate the margin with
+            };
+
+            let mut margin_init = match margin_account.calculate_initial_margin(
+                instrument.clone(),
+                order.quantity(),
+                price,
+                None,
+            ) {
+                Ok(margin_init) => margin_init.as_f64(),
+                Err(e) => {
+                    log::error!("Cannot calculate initial margin: {e}");
+                    return None;
+                }
+            };
+
+            if let Some(base_currency) = base_currency {
+                let xrate = match base_xrate {
+                    Some(xrate) => xrate,
+                    None => {
+                        currency = base_currency;
+                        let Some(xrate) = self.calculate_xrate_to_base(
+                            &instrument,
+                            base_currency,
+                            order.order_side(),
+                        ) else {
+                            log::debug!(
+                                "Cannot calculate initial margin: insufficient data for {}/{}",
+                                instrument.settlement_currency().code,
+                                base_currency.code,
+                            );
+                            return None;
+                        };
+                        *base_xrate.insert(xrate)
+                    }
+                };
+                margin_init *= xrate;
+            }
+
+            total_margin_init += margin_init;
+        }
+
+        let margin_init = Money::new(total_margin_init, currency).ok()?;
+        if margin_init.raw == 0 {
+            margin_account.clear_initial_margin(instrument.id());
+        } else {
+            if !margin_account.balances.contains_key(&currency) {
+                log::error!("Cannot update initial margin: no {} balance", currency.code);
+                return None;
+            }
+            margin_account.update_initial_margin(instrument.id(), margin_init);
+        }
+        log::info!("{} margin_init={margin_init}", instrument.id());
+
+        Some(self.generate_account_state(account, ts_event))
+    }
+
+    fn update_balance_single_currency(
+        &self,
+        account: &mut AccountAny,
+        fill: &OrderFilled,
+        base_currency: Currency,
+        pnl: Money,
+    ) {
+        let commission = fill
+            .commission
+            .unwrap_or_else(|| Money::from_raw(0, base_currency));
+        let Some(commission) = self.convert_fill_amount(fill, commission, base_currency) else {
+            log::error!(
+                "Cannot calculate account state: insufficient data for {}/{}",
+                commission.currency.code,
+                base_currency.code,
+            );
+            return;
+        };
+        let Some(pnl) = self.convert_fill_amount(fill, pnl, base_currency) else {
+            log::error!(
+                "Cannot calculate account state: insufficient data for {}/{}",
+                pnl.currency.code,
+                base_currency.code,
+            );
+            return;
+        };
+
+        let pnl = pnl - commission;
+        if pnl.raw == 0 {
+            return; // Nothing to adjust
+        }
+
+        let Some(balance) = account.balances.get(&base_currency).copied() else {
+            log::error!(
+                "Cannot complete transaction: no balance for {}",
+                base_currency.code
+            );
+            return;
+        };
+        let new_balance = AccountBalance {
+            total: balance.total + pnl,
+            locked: balance.locked,
+            free: balance.free + pnl,
+            currency: base_currency,
+        };
+        if new_balance.total.raw < 0 {
+            log::error!(
+                "Cannot complete transaction: balance would be negative, was {new_balance}"
+            );
+            return;
+        }
+
+        account.update_balances(vec![new_balance]);
+        account.update_commissions(commission);
+    }
+
+    fn update_balance_multi_currency(
+        &self,
+        account: &mut AccountAny,
+        fill: &OrderFilled,
+        pnls: &[Money],
+    ) {
+        let commission = fill.commission;
+        let mut balances: Vec<AccountBalance> = Vec::new();
+
+        for pnl in pnls {
+            let mut pnl = *pnl;
+            match commission {
+                Some(commission) if commission.currency != pnl.currency && commission.raw != 0 => {
+                    let balance = account.balances.get(&commission.currency).copied();
+                    let balance = match balance {
+                        Some(balance) => balance,
+                        None if commission.raw > 0 => {
+                            log::error!(
+                                "Cannot complete transaction: no {} balance to deduct a {commission} commission from",
+                                commission.currency.code
+                            );
+                            return;
+                        }
+                        None => zero_balance(commission.currency),
+                    };
+                    balances.push(AccountBalance {
+                        total: balance.total - commission,
+                        free: balance.free - commission,
+                        ..balance
+                    });
+                }
+                Some(commission) if commission.currency == pnl.currency => pnl -= commission,
+                _ => {}
+            }
+
+            if balances.is_empty() && pnl.raw == 0 {
+                return; // No adjustment
+            }
+
+            let new_balance = match account.balances.get(&pnl.currency).copied() {
+                None if pnl.raw < 0 => {
+                    log::error!(
+                        "Cannot complete transaction: no {} to deduct a {pnl} realized PnL from",
+                        pnl.currency.code
+                    );
+                    return;
+                }
+                None => AccountBalance {
+                    total: pnl,
+                    locked: Money::from_raw(0, pnl.currency),
+                    free: pnl,
+                    currency: pnl.currency,
+                },
+                Some(balance) => AccountBalance {
+                    total: balance.total + pnl,
+                    free: balance.free + pnl,
+                    ..balance
+                },
+            };
+            if new_balance.total.raw < 0 {
+                log::error!(
+                    "Cannot complete transaction: balance would be negative, was {new_balance}"
+                );
+                return;
+            }
+            balances.push(new_balance);
+        }
+
+        if let Some(commission) = commission.filter(|c| pnls.is_empty() && c.raw != 0) {
+            let Some(balance) = account.balances.get(&commission.currency).copied() else {
+                log::error!(
+                    "Cannot calculate account state: no cached balances for {}",
+                    commission.currency.code
+                );
+                return;
+            };
+            balances.push(AccountBalance {
+                total: balance.total - commission,
+                free: balance.free - commission,
+                ..balance
+            });
+        }
+
+        if balances.is_empty() {
+            return; // No adjustment
+        }
+
+        account.update_balances(balances);
+        if let Some(commission) = commission {
+            account.update_commissions(commission);
+        }
+    }
+
+    /// Converts an `amount` from the given `fill` to the `to_currency` (if required).
+    fn convert_fill_amount(
+        &self,
+        fill: &OrderFilled,
+        amount: Money,
+        to_currency: Currency,
+    ) -> Option<Money> {
+        if amount.currency == to_currency {
+            return Some(amount);
+        }
+
+        let price_type = if fill.order_side == OrderSide::Sell {
+            PriceType::Bid
+        } else {
+            PriceType::Ask
+        };
+        let xrate = self
+            .cache
+            .borrow()
+            .get_xrate(
+                fill.instrument_id.venue,
+                amount.currency,
+                to_currency,
+                price_type,
+            )
+            .ok()
+            .flatten()?;
+        Money::new(amount.as_f64() * xrate, to_currency).ok()
+    }
+
+    /// Returns the exchange rate from the settlement currency of the `instrument` to
+    /// the account `base_currency`, or `None` if there is insufficient data.
+    pub(crate) fn calculate_xrate_to_base(
+        &self,
+        instrument: &InstrumentType,
+        base_currency: Currency,
+        side: OrderSide,
+    ) -> Option<f64> {
+        let price_type = if side == OrderSide::Buy {
+            PriceType::Bid
+        } else {
+            PriceType::Ask
+        };
+        self.cache
+            .borrow()
+            .get_xrate(
+                instrument.id().venue,
+                instrument.settlement_currency(),
+                base_currency,
+                price_type,
+            )
+            .ok()
+            .flatten()
+    }
+
+    fn generate_account_state(&self, account: &AccountAny, ts_event: UnixNanos) -> AccountState {
+        account
+            .generate_account_state(ts_event, self.clock.get_time_ns())
+            .expect("Account state should be valid for an existing account")
+    }
+}
+
+fn zero_balance(currency: Currency) -> AccountBalance {
+    let zero = Money::from_raw(0, currency);
+    AccountBalance {
+        total: zero,
+        locked: zero,
+        free: zero,
+        currency,
+    }
+}
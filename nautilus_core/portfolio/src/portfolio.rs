@@ -0,0 +1,1280 @@
+// -------------------------------------------------------------------------------------------------
+//  Copyright (C) 2015-2024 Nautech Systems Pty Ltd. All rights reserved.
+//  https://nautechsystems.io
+//
+//  Licensed under the GNU Lesser General Public License Version 3.0 (the "License");
+//  You may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at https://www.gnu.org/licenses/lgpl-3.0.en.html
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+// -------------------------------------------------------------------------------------------------
+
+use std::{
+    any::Any,
+    cell::{Ref, RefCell},
+    collections::{HashMap, HashSet},
+    rc::{Rc, Weak},
+};
+
+use nautilus_accounting::account::any::AccountAny;
+use nautilus_common::{cache::Cache, handlers::LocalMessageHandler, msgbus::MessageBus};
+use nautilus_core::time::AtomicTime;
+use nautilus_model::{
+    data::quote::QuoteTick,
+    enums::{OrderSide, PositionSide},
+    events::{account::state::AccountState, order::event::OrderEvent, position::PositionEvent},
+    identifiers::{account_id::AccountId, instrument_id::InstrumentId, venue::Venue},
+    instruments::{Instrument, InstrumentType},
+    position::Position,
+    types::{currency::Currency, money::Money, price::Price},
+};
+use ustr::Ustr;
+
+use crate::manager::AccountsManager;
+
+/// The priority of the portfolio handlers, so its state is up to date before any
+/// other subscriber (such as a strategy) receives the same message.
+const HANDLER_PRIORITY: u8 = 10;
+
+type PortfolioHandler = fn(&Portfolio, &dyn Any);
+
+#[derive(Debug, Default)]
+struct PortfolioState {
+    accounts: HashMap<AccountId, AccountAny>,
+    calculated_issuers: HashSet<Ustr>,
+    unrealized_pnls: HashMap<InstrumentId, Money>,
+    realized_pnls: HashMap<InstrumentId, Money>,
+    net_positions: HashMap<InstrumentId, f64>,
+    pending_calcs: HashSet<InstrumentId>,
+    initialized: bool,
+}
+
+/// Provides a trading portfolio, aggregating the accounts, positions and market
+/// data of the system into balances, margins, PnLs and net exposures.
+///
+/// The portfolio subscribes to quotes along with order, position and account
+/// events on the message bus. Any account states calculated in response are then
+/// published on the `events.account.{account_id}` topic.
+pub struct Portfolio {
+    clock: &'static AtomicTime,
+    cache: Rc<RefCell<Cache>>,
+    msgbus: Rc<RefCell<MessageBus>>,
+    manager: Rc<AccountsManager>,
+    state: Rc<RefCell<PortfolioState>>,
+}
+
+impl Portfolio {
+    /// Creates a new [`Portfolio`] instance, subscribing its handlers on the `msgbus`.
+    pub fn new(
+        clock: &'static AtomicTime,
+        cache: Rc<RefCell<Cache>>,
+        msgbus: Rc<RefCell<MessageBus>>,
+    ) -> Self {
+        let portfolio = Self {
+            clock,
+            manager: Rc::new(AccountsManager::new(clock, cache.clone())),
+            cache,
+            msgbus,
+            state: Rc::new(RefCell::new(PortfolioState::default())),
+        };
+        portfolio.register_message_handlers();
+        portfolio
+    }
+
+    fn downgrade(&self) -> WeakPortfolio {
+        WeakPortfolio {
+            clock: self.clock,
+            cache: Rc::downgrade(&self.cache),
+            msgbus: Rc::downgrade(&self.msgbus),
+            manager: Rc::downgrade(&self.manager),
+            state: Rc::downgrade(&self.state),
+        }
+    }
+
+    fn register_message_handlers(&self) {
+        let subscriptions: [(&str, &str, PortfolioHandler); 4] = [
+            ("data.quotes.*", "Portfolio.update_quote_tick", |p, msg| {
+                if let Some(quote) = msg.downcast_ref::<QuoteTick>() {
+                    p.update_quote_tick(quote);
+                }
+            }),
+            ("events.order.*", "Portfolio.update_order", |p, msg| {
+                if let Some(event) = msg.downcast_ref::<OrderEvent>() {
+                    p.update_order(event);
+                }
+            }),
+            (
+                "events.position.*",
+                "Portfolio.update_position",
+                |p, msg| {
+                    if let Some(event) = msg.downcast_ref::<PositionEvent>() {
+                        p.update_position(event);
+                    }
+                },
+            ),
+            ("events.account.*", "Portfolio.update_account", |p, msg| {
+                if let Some(event) = msg.downcast_ref::<AccountState>() {
+                    p.update_account(event);
+                }
+            }),
+        ];
+
+        let mut msgbus = self.msgbus.borrow_mut();
+        for (topic, handler_id, update) in subscriptions {
+            // The message bus only holds weak references, so it does not keep the portfolio alive
+            let portfolio = self.downgrade();
+            let callback = Rc::new(move |msg: &dyn Any| {
+                if let Some(portfolio) = portfolio.upgrade() {
+                    update(&portfolio, msg);
+                }
+            });
+            let handler = LocalMessageHandler::new(Ustr::from(handler_id), callback);
+            msgbus.subscribe_local(topic, handler, Some(HANDLER_PRIORITY));
+        }
+    }
+
+    /// Registers the account `issuer` as one whose account states should be calculated
+    /// by the portfolio (rather than only ever reported by the venue).
+    pub fn register_calculated_account(&self, issuer: &str) {
+        self.state
+            .borrow_mut()
+            .calculated_issuers
+            .insert(Ustr::from(issuer));
+    }
+
+    /// If all initial calculations have completed (none are pending further market data).
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.state.borrow().initialized
+    }
+
+    /// Initializes the account states for all open orders in the cache.
+    pub fn initialize_orders(&self) {
+        let instrument_ids: HashSet<InstrumentId> = self
+            .cache
+            .borrow()
+            .orders_open(None, None)
+            .iter()
+            .map(|order| order.instrument_id())
+            .collect();
+
+        let mut account_states = Vec::new();
+        for instrument_id in instrument_ids {
+            match self.update_instrument_orders(&instrument_id, None) {
+                Some(state) => account_states.push(state),
+                None => {
+                    self.state.borrow_mut().pending_calcs.insert(instrument_id);
+                }
+            }
+        }
+
+        self.finish_initialization();
+        self.publish_account_states(account_states);
+    }
+
+    /// Initializes the net positions and margins for all open positions in the cache.
+    pub fn initialize_positions(&self) {
+        let instrument_ids: HashSet<InstrumentId> = self
+            .cache
+            .borrow()
+            .positions_open(None, None)
+            .iter()
+            .map(|position| position.instrument_id)
+            .collect();
+
+        let mut account_states = Vec::new();
+        for instrument_id in instrument_ids {
+            self.update_net_position(&instrument_id);
+            let maint_state = self.update_instrument_positions(&instrument_id, None);
+            let unrealized_pnl = self.calculate_unrealized_pnl(&instrument_id);
+            let mut state = self.state.borrow_mut();
+            match (maint_state, unrealized_pnl) {
+                (Some(account_state), Some(pnl)) => {
+                    state.unrealized_pnls.insert(instrument_id, pnl);
+                    account_states.extend(account_state);
+                }
+                _ => {
+                    state.pending_calcs.insert(instrument_id);
+                }
+            }
+        }
+
+        self.finish_initialization();
+        self.publish_account_states(account_states);
+    }
+
+    /// Updates the portfolio for the given `quote`, recalculating the margins and
+    /// unrealized PnL of the quoted instrument.
+    pub fn update_quote_tick(&self, quote: &QuoteTick) {
+        let instrument_id = quote.instrument_id;
+        self.state
+            .borrow_mut()
+            .unrealized_pnls
+            .remove(&instrument_id);
+
+        let unrealized_pnl = self.calculate_unrealized_pnl(&instrument_id);
+        if let Some(pnl) = unrealized_pnl {
+            self.state
+                .borrow_mut()
+                .unrealized_pnls
+                .insert(instrument_id, pnl);
+        }
+
+        let is_pending = self.state.borrow().pending_calcs.contains(&instrument_id);
+        if !is_pending && !self.has_calculated_exposure(&instrument_id) {
+            return; // No margins to recalculate
+        }
+
+        let init_state = self.update_instrument_orders(&instrument_id, None);
+        let maint_state = self.update_instrument_positions(&instrument_id, None);
+
+        let mut account_states = Vec::new();
+        if let (Some(init_state), Some(maint_state), Some(_)) =
+            (init_state, maint_state, unrealized_pnl)
+        {
+            self.state.borrow_mut().pending_calcs.remove(&instrument_id);
+            account_states.push(init_state);
+            account_states.extend(maint_state);
+        }
+
+        self.finish_initialization();
+        self.publish_account_states(account_states);
+    }
+
+    /// Updates the portfolio for the given order `event`.
+    pub fn update_order(&self, event: &OrderEvent) {
+        let fill = match event {
+            OrderEvent::OrderAccepted(_)
+            | OrderEvent::OrderCanceled(_)
+            | OrderEvent::OrderRejected(_)
+            | OrderEvent::OrderUpdated(_) => None,
+            OrderEvent::OrderPartiallyFilled(fill) | OrderEvent::OrderFilled(fill) => Some(*fill),
+            _ => return, // No change to account state
+        };
+        let Some(account_id) = event.account_id() else {
+            return; // Order not yet associated with an account
+        };
+        if !self.is_calculated_account(&account_id) {
+            return; // Nothing to calculate
+        }
+
+        let instrument_id = event.instrument_id();
+        let Some(instrument) = self.instrument(&instrument_id) else {
+            log::error!("Cannot update order: no instrument found for {instrument_id}");
+            return;
+        };
+
+        let mut account_states = Vec::new();
+        if let Some(fill) = fill {
+            let mut state = self.state.borrow_mut();
+            if let Some(account) = state.accounts.get_mut(&account_id) {
+                account_states.extend(self.manager.update_balances(
+                    account,
+                    instrument.clone(),
+                    fill,
+                ));
+            }
+            state.unrealized_pnls.remove(&instrument_id);
+        }
+
+        match self.update_instrument_orders(&instrument_id, Some(event.ts_event())) {
+            Some(state) => account_states.push(state),
+            None => log::error!("Cannot calculate account state for {account_id}"),
+        }
+
+        self.publish_account_states(account_states);
+    }
+
+    /// Updates the portfolio for the given position `event`.
+    pub fn update_position(&self, event: &PositionEvent) {
+        let instrument_id = event.instrument_id();
+        self.update_net_position(&instrument_id);
+
+        let unrealized_pnl = self.calculate_unrealized_pnl(&instrument_id);
+        let realized_pnl = self.calculate_realized_pnl(&instrument_id);
+        {
+            let mut state = self.state.borrow_mut();
+            match unrealized_pnl {
+                Some(pnl) => state.unrealized_pnls.insert(instrument_id, pnl),
+                None => state.unrealized_pnls.remove(&instrument_id),
+            };
+            match realized_pnl {
+                Some(pnl) => state.realized_pnls.insert(instrument_id, pnl),
+                None => state.realized_pnls.remove(&instrument_id),
+            };
+        }
+
+        if !self.is_calculated_account(&event.account_id()) {
+            return; // Nothing to calculate
+        }
+
+        let account_states = self
+            .update_instrument_positions(&instrument_id, Some(event.ts_event()))
+            .flatten();
+        self.publish_account_states(account_states.into_iter().collect());
+    }
+
+    /// Updates the portfolio for the given account state `event`, creating the
+    /// account on its first event.
+    pub fn update_account(&self, event: &AccountState) {
+        let account_id = event.account_id;
+        {
+            let mut state = self.state.borrow_mut();
+            if let Some(account) = state.accounts.get_mut(&account_id) {
+                account.apply(event.clone());
+            } else {
+                let calculate_account_state =
+                    state.calculated_issuers.contains(&account_id.get_issuer());
+                match AccountAny::new(event.clone(), calculate_account_state) {
+                    Ok(account) => {
+                        state.accounts.insert(account_id, account);
+                    }
+                    Err(e) => {
+                        log::error!("Cannot create account {account_id}: {e}");
+                        return;
+                    }
+                }
+            }
+        }
+
+        if let Err(e) = self.cache.borrow_mut().add_account_state(event.clone()) {
+            log::error!("Cannot cache account state for {account_id}: {e}");
+        }
+        log::info!("Updated {account_id}");
+    }
+
+    /// Returns the account for the given `venue` (if found).
+    #[must_use]
+    pub fn account(&self, venue: &Venue) -> Option<Ref<'_, AccountAny>> {
+        let account_id = *self.cache.borrow().account_id(venue)?;
+        Ref::filter_map(self.state.borrow(), |state| state.accounts.get(&account_id)).ok()
+    }
+
+    /// Returns the locked balances for the given `venue` (if an account is found).
+    #[must_use]
+    pub fn balances_locked(&self, venue: &Venue) -> Option<HashMap<Currency, Money>> {
+        self.account(venue)
+            .map(|account| account.base_balances_locked())
+    }
+
+    /// Returns the initial (order) margins for the given `venue` (if an account is found).
+    #[must_use]
+    pub fn margins_init(&self, venue: &Venue) -> Option<HashMap<InstrumentId, Money>> {
+        self.account(venue).map(|account| match &*account {
+            AccountAny::Cash(_) => HashMap::new(),
+            AccountAny::Margin(account) => account.initial_margins(),
+        })
+    }
+
+    /// Returns the maintenance (position) margins for the given `venue` (if an account is found).
+    #[must_use]
+    pub fn margins_maint(&self, venue: &Venue) -> Option<HashMap<InstrumentId, Money>> {
+        self.account(venue).map(|account| match &*account {
+            AccountAny::Cash(_) => HashMap::new(),
+            AccountAny::Margin(account) => account.maintenance_margins(),
+        })
+    }
+
+    /// Returns the unrealized PnLs for the given `venue`, keyed by currency.
+    #[must_use]
+    pub fn unrealized_pnls(&self, venue: &Venue) -> HashMap<Currency, Money> {
+        let instrument_ids: HashSet<InstrumentId> = self
+            .cache
+            .borrow()
+            .positions_open(Some(venue), None)
+            .iter()
+            .map(|position| position.instrument_id)
+            .collect();
+
+        sum_by_currency(
+            instrument_ids
+                .iter()
+                .filter_map(|instrument_id| self.unrealized_pnl(instrument_id)),
+        )
+    }
+
+    /// Returns the realized PnLs for the given `venue`, keyed by currency.
+    #[must_use]
+    pub fn realized_pnls(&self, venue: &Venue) -> HashMap<Currency, Money> {
+        let instrument_ids: HashSet<InstrumentId> = self
+            .cache
+            .borrow()
+            .positions(Some(venue), None)
+            .iter()
+            .map(|position| position.instrument_id)
+            .collect();
+
+        sum_by_currency(
+            instrument_ids
+                .iter()
+                .filter_map(|instrument_id| self.realized_pnl(instrument_id)),
+        )
+    }
+
+    /// Returns the net exposures for the given `venue`, keyed by currency.
+    ///
+    /// Returns `None` if there is no account for the venue, or insufficient market data.
+    #[must_use]
+    pub fn net_exposures(&self, venue: &Venue) -> Option<HashMap<Currency, Money>> {
+        self.account(venue)?;
+
+        let positions: Vec<Position> = self
+            .cache
+            .borrow()
+            .positions_open(Some(venue), None)
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let mut exposures = Vec::with_capacity(positions.len());
+        for position in &positions {
+            exposures.push(self.calculate_net_exposure(position)?);
+        }
+        Some(sum_by_currency(exposures.into_iter()))
+    }
+
+    /// Returns the unrealized PnL for the given `instrument_id`.
+    ///
+    /// Returns `None` if there is insufficient data to calculate the PnL.
+    #[must_use]
+    pub fn unrealized_pnl(&self, instrument_id: &InstrumentId) -> Option<Money> {
+        if let Some(pnl) = self.state.borrow().unrealized_pnls.get(instrument_id) {
+            return Some(*pnl);
+        }
+
+        let pnl = self.calculate_unrealized_pnl(instrument_id)?;
+        self.state
+            .borrow_mut()
+            .unrealized_pnls
+            .insert(*instrument_id, pnl);
+        Some(pnl)
+    }
+
+    /// Returns the realized PnL for the given `instrument_id`.
+    ///
+    /// Returns `None` if there is insufficient data to calculate the PnL.
+    #[must_use]
+    pub fn realized_pnl(&self, instrument_id: &InstrumentId) -> Option<Money> {
+        if let Some(pnl) = self.state.borrow().realized_pnls.get(instrument_id) {
+            return Some(*pnl);
+        }
+
+        let pnl = self.calculate_realized_pnl(instrument_id)?;
+        self.state
+            .borrow_mut()
+            .realized_pnls
+            .insert(*instrument_id, pnl);
+        Some(pnl)
+    }
+
+    /// Returns the net exposure for the given `instrument_id`.
+    ///
+    /// Returns `None` if there is no account for the venue, or insufficient market data.
+    #[must_use]
+    pub fn net_exposure(&self, instrument_id: &InstrumentId) -> Option<Money> {
+        let account = self.account(&instrument_id.venue)?;
+        let currency = self.pnl_currency(&account, &self.instrument(instrument_id)?);
+        drop(account);
+
+        let positions: Vec<Position> = self
+            .cache
+            .borrow()
+            .positions_open(None, Some(instrument_id))
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let mut net_exposure = 0.0;
+        for position in &positions {
+            net_exposure += self.calculate_net_exposure(position)?.as_f64();
+        }
+        Money::new(net_exposure, currency).ok()
+    }
+
+    /// Returns the net position (signed quantity) for the given `instrument_id`.
+    #[must_use]
+    pub fn net_position(&self, instrument_id: &InstrumentId) -> f64 {
+        self.state
+            .borrow()
+            .net_positions
+            .get(instrument_id)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    #[must_use]
+    pub fn is_net_long(&self, instrument_id: &InstrumentId) -> bool {
+        self.net_position(instrument_id) > 0.0
+    }
+
+    #[must_use]
+    pub fn is_net_short(&self, instrument_id: &InstrumentId) -> bool {
+        self.net_position(instrument_id) < 0.0
+    }
+
+    #[must_use]
+    pub fn is_flat(&self, instrument_id: &InstrumentId) -> bool {
+        self.net_position(instrument_id) == 0.0
+    }
+
+    /// If the portfolio is flat across all instruments.
+    #[must_use]
+    pub fn is_completely_flat(&self) -> bool {
+        self.state
+            .borrow()
+            .net_positions
+            .values()
+            .all(|net_position| *net_position == 0.0)
+    }
+
+    fn instrument(&self, instrument_id: &InstrumentId) -> Option<InstrumentType> {
+        self.cache
+            .borrow()
+            .instrument(instrument_id)
+            .and_then(InstrumentType::from_instrument)
+    }
+
+    fn account_id_for_venue(&self, venue: &Venue) -> Option<AccountId> {
+        self.cache.borrow().account_id(venue).copied()
+    }
+
+    /// If the venue account for the `instrument_id` is calculated, and has open orders or
+    /// positions for the instrument.
+    fn has_calculated_exposure(&self, instrument_id: &InstrumentId) -> bool {
+        let Some(account_id) = self.account_id_for_venue(&instrument_id.venue) else {
+            return false;
+        };
+        if !self.is_calculated_account(&account_id) {
+            return false;
+        }
+
+        let cache = self.cache.borrow();
+        !cache.orders_open(None, Some(instrument_id)).is_empty()
+            || !cache.positions_open(None, Some(instrument_id)).is_empty()
+    }
+
+    fn is_calculated_account(&self, account_id: &AccountId) -> bool {
+        match self.state.borrow().accounts.get(account_id) {
+            Some(account) => account.calculate_account_state,
+            None => {
+                log::error!(
+                    "Cannot calculate account state: no account registered for {account_id}"
+                );
+                false
+            }
+        }
+    }
+
+    fn pnl_currency(&self, account: &AccountAny, instrument: &InstrumentType) -> Currency {
+        account
+            .base_currency
+            .unwrap_or_else(|| instrument.settlement_currency())
+    }
+
+    /// Updates the balance locked or initial margin of the venue account for the open
+    /// orders of the `instrument_id`.
+    fn update_instrument_orders(
+        &self,
+        instrument_id: &InstrumentId,
+        ts_event: Option<u64>,
+    ) -> Option<AccountState> {
+        let account_id = self.account_id_for_venue(&instrument_id.venue)?;
+        let instrument = self.instrument(instrument_id)?;
+        let ts_event = ts_event.unwrap_or_else(|| self.clock.get_time_ns());
+
+        let cache = self.cache.borrow();
+        let orders_open = cache.orders_open(None, Some(instrument_id));
+        let mut state = self.state.borrow_mut();
+        let account = state.accounts.get_mut(&account_id)?;
+        self.manager
+            .update_orders(account, instrument, &orders_open, ts_event)
+    }
+
+    /// Updates the maintenance margin of the venue account for the open positions of
+    /// the `instrument_id`, with an inner `None` for accounts without position margins.
+    fn update_instrument_positions(
+        &self,
+        instrument_id: &InstrumentId,
+        ts_event: Option<u64>,
+    ) -> Option<Option<AccountState>> {
+        let account_id = self.account_id_for_venue(&instrument_id.venue)?;
+        let instrument = self.instrument(instrument_id)?;
+        let ts_event = ts_event.unwrap_or_else(|| self.clock.get_time_ns());
+
+        let cache = self.cache.borrow();
+        let positions_open = cache.positions_open(Some(&instrument_id.venue), None);
+        let mut state = self.state.borrow_mut();
+        let account = state.accounts.get_mut(&account_id)?;
+        if !account.is_margin_account() {
+            return Some(None);
+        }
+        self.manager
+            .update_positions(account, instrument, &positions_open, ts_event)
+            .map(Some)
+    }
+
+    fn update_net_position(&self, instrument_id: &InstrumentId) {
+        let net_position: f64 = self
+            .cache
+            .borrow()
+            .positions_open(None, Some(instrument_id))
+            .iter()
+            .map(|position| position.signed_qty)
+            .sum();
+
+        let mut state = self.state.borrow_mut();
+        let previous = state.net_positions.insert(*instrument_id, net_position);
+        if previous != Some(net_position) {
+            log::info!("{instrument_id} net_position={net_position}");
+        }
+    }
+
+    fn calculate_unrealized_pnl(&self, instrument_id: &InstrumentId) -> Option<Money> {
+        let account_id = self.account_id_for_venue(&instrument_id.venue)?;
+        let instrument = self.instrument(instrument_id)?;
+        let (currency, base_currency) = {
+            let state = self.state.borrow();
+            let account = state.accounts.get(&account_id)?;
+            (
+                self.pnl_currency(account, &instrument),
+                account.base_currency,
+            )
+        };
+
+        let positions: Vec<Position> = self
+            .cache
+            .borrow()
+            .positions_open(None, Some(instrument_id))
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let mut total_pnl = 0.0;
+        for position in positions.iter().filter(|p| p.side != PositionSide::Flat) {
+            let Some(last) = self.last_price(position) else {
+                log::debug!("Cannot calculate unrealized PnL: no prices for {instrument_id}");
+                self.state.borrow_mut().pending_calcs.insert(*instrument_id);
+                return None;
+            };
+
+            let mut pnl = position.unrealized_pnl(last).as_f64();
+            if let Some(base_currency) = base_currency {
+                let Some(xrate) = self.manager.calculate_xrate_to_base(
+                    &instrument,
+                    base_currency,
+                    position.entry,
+                ) else {
+                    log::debug!(
+                        "Cannot calculate unrealized PnL: insufficient data for {}/{}",
+                        instrument.settlement_currency().code,
+                        base_currency.code,
+                    );
+                    self.state.borrow_mut().pending_calcs.insert(*instrument_id);
+                    return None;
+                };
+                pnl *= xrate;
+            }
+            total_pnl += pnl;
+        }
+
+        Money::new(total_pnl, currency).ok()
+    }
+
+    fn calculate_realized_pnl(&self, instrument_id: &InstrumentId) -> Option<Money> {
+        let account_id = self.account_id_for_venue(&instrument_id.venue)?;
+        let instrument = self.instrument(instrument_id)?;
+        let (currency, base_currency) = {
+            let state = self.state.borrow();
+            let account = state.accounts.get(&account_id)?;
+            (
+                self.pnl_currency(account, &instrument),
+                account.base_currency,
+            )
+        };
+
+        let realized_pnls: Vec<(OrderSide, Money)> = self
+            .cache
+            .borrow()
+            .positions(None, Some(instrument_id))
+            .iter()
+            .filter_map(|position| position.realized_pnl.map(|pnl| (position.entry, pnl)))
+            .collect();
+
+        let mut total_pnl = 0.0;
+        for (entry, pnl) in realized_pnls {
+            let mut pnl = pnl.as_f64();
+            if let Some(base_currency) = base_currency {
+                let Some(xrate) =
+                    self.manager
+                        .calculate_xrate_to_base(&instrument, base_currency, entry)
+                else {
+                    log::debug!(
+                        "Cannot calculate realized PnL: insufficient data for {}/{}",
+                        instrument.settlement_currency().code,
+                        base_currency.code,
+                    );
+                    return None;
+                };
+                pnl *= xrate;
+            }
+            total_pnl += pnl;
+        }
+
+        Money::new(total_pnl, currency).ok()
+    }
+
+    fn calculate_net_exposure(&self, position: &Position) -> Option<Money> {
+        let instrument_id = position.instrument_id;
+        let account_id = self.account_id_for_venue(&instrument_id.venue)?;
+        let Some(instrument) = self.instrument(&instrument_id) else {
+            log::error!("Cannot calculate net exposure: no instrument for {instrument_id}");
+            return None;
+        };
+        let (currency, base_currency) = {
+            let state = self.state.borrow();
+            let account = state.accounts.get(&account_id)?;
+            (
+                self.pnl_currency(account, &instrument),
+                account.base_currency,
+            )
+        };
+
+        let Some(last) = self.last_price(position) else {
+            log::debug!("Cannot calculate net exposure: no prices for {instrument_id}");
+            return None;
+        };
+        let mut net_exposure = instrument
+            .calculate_notional_value(position.quantity, last, None)
+            .as_f64();
+        if let Some(base_currency) = base_currency {
+            let xrate =
+                self.manager
+                    .calculate_xrate_to_base(&instrument, base_currency, position.entry)?;
+            net_exposure *= xrate;
+        }
+
+        Money::new(net_exposure, currency).ok()
+    }
+
+    /// Returns the price an open `position` could currently be closed at.
+    fn last_price(&self, position: &Position) -> Option<Price> {
+        let cache = self.cache.borrow();
+        let quote = cache.quote_tick(&position.instrument_id)?;
+        match position.side {
+            PositionSide::Long => Some(quote.bid_price),
+            PositionSide::Short => Some(quote.ask_price),
+            _ => None,
+        }
+    }
+
+    fn finish_initialization(&self) {
+        let mut state = self.state.borrow_mut();
+        if !state.initialized && state.pending_calcs.is_empty() {
+            state.initialized = true;
+            log::info!("Initialized portfolio");
+        }
+    }
+
+    fn publish_account_states(&self, account_states: Vec<AccountState>) {
+        let msgbus = self.msgbus.borrow();
+        for account_state in account_states {
+            let topic = format!("events.account.{}", account_state.account_id);
+            msgbus.publish(&topic, &account_state);
+        }
+    }
+}
+
+/// A weak reference to a [`Portfolio`], as held by its message handlers.
+struct WeakPortfolio {
+    clock: &'static AtomicTime,
+    cache: Weak<RefCell<Cache>>,
+    msgbus: Weak<RefCell<MessageBus>>,
+    manager: Weak<AccountsManager>,
+    state: Weak<RefCell<PortfolioState>>,
+}
+
+impl WeakPortfolio {
+    fn upgrade(&self) -> Option<Portfolio> {
+        Some(Portfolio {
+            clock: self.clock,
+            cache: self.cache.upgrade()?,
+            msgbus: self.msgbus.upgrade()?,
+            manager: self.manager.upgrade()?,
+            state: self.state.upgrade()?,
+        })
+    }
+}
+
+fn sum_by_currency(amounts: impl Iterator<Item = Money>) -> HashMap<Currency, Money> {
+    let mut totals: HashMap<Currency, Money> = HashMap::new();
+    for amount in amounts {
+        totals
+            .entry(amount.currency)
+            .and_modify(|total| *total += amount)
+            .or_insert(amount);
+    }
+    totals
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tests
+////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use nautilus_accounting::margin::PortfolioMarginModel;
+    use nautilus_core::uuid::UUID4;
+    use nautilus_model::{
+        enums::OrderSide,
+        events::{
+            account::stubs::*,
+            order::{accepted::OrderAccepted, filled::OrderFilled, submitted::OrderSubmitted},
+            position::opened::PositionOpened,
+        },
+        identifiers::{position_id::PositionId, stubs::trader_id, venue_order_id::VenueOrderId},
+        instruments::{currency_pair::CurrencyPair, stubs::*},
+        orders::{
+            any::OrderAny,
+            market::MarketOrder,
+            stubs::{TestOrderEventStubs, TestOrderStubs},
+        },
+        types::quantity::Quantity,
+    };
+    use rstest::rstest;
+
+    use super::*;
+
+    struct TestPortfolio {
+        portfolio: Portfolio,
+        cache: Rc<RefCell<Cache>>,
+        msgbus: Rc<RefCell<MessageBus>>,
+    }
+
+    impl TestPortfolio {
+        fn new(instrument: CurrencyPair) -> Self {
+            let clock: &'static AtomicTime = Box::leak(Box::new(AtomicTime::new(false, 0)));
+            let mut cache = Cache::default();
+            cache.add_instrument(Box::new(instrument)).unwrap();
+            let cache = Rc::new(RefCell::new(cache));
+            let msgbus = MessageBus::new(trader_id(), UUID4::new(), None, None).unwrap();
+            let msgbus = Rc::new(RefCell::new(msgbus));
+            let portfolio = Portfolio::new(clock, cache.clone(), msgbus.clone());
+            portfolio.register_calculated_account("SIM");
+            Self {
+                portfolio,
+                cache,
+                msgbus,
+            }
+        }
+
+        fn publish(&self, topic: &str, message: &dyn Any) {
+            self.msgbus.borrow().publish(topic, message);
+        }
+
+        fn publish_quote(&self, instrument_id: InstrumentId, bid: &str, ask: &str) {
+            let quote = QuoteTick::new(
+                instrument_id,
+                Price::from(bid),
+                Price::from(ask),
+                Quantity::from(1_000_000),
+                Quantity::from(1_000_000),
+                0,
+                0,
+            )
+            .unwrap();
+            self.cache.borrow_mut().add_quote_tick(quote);
+            self.publish("data.quotes.SIM.AUD/USD", &quote);
+        }
+
+        fn open_position(&self, instrument: CurrencyPair, side: OrderSide) -> OrderFilled {
+            self.open_position_with_id(instrument, side, PositionId::from("P-001"))
+        }
+
+        fn open_position_with_id(
+            &self,
+            instrument: CurrencyPair,
+            side: OrderSide,
+            position_id: PositionId,
+        ) -> OrderFilled {
+            let order = TestOrderStubs::market_order(
+                instrument.id,
+                side,
+                Quantity::from(100_000),
+                None,
+                None,
+            );
+            let fill = TestOrderEventStubs::order_filled::<MarketOrder, CurrencyPair>(
+                &order,
+                &instrument,
+                None,
+                None,
+                Some(position_id),
+                Some(Price::from("0.80000")),
+                None,
+                None,
+                None,
+            );
+            let position = Position::new(instrument, fill).unwrap();
+            self.cache
+                .borrow_mut()
+                .add_position(position.clone())
+                .unwrap();
+
+            let event = PositionEvent::PositionOpened(PositionOpened {
+                trader_id: position.trader_id,
+                strategy_id: position.strategy_id,
+                instrument_id: position.instrument_id,
+                position_id: position.id,
+                account_id: position.account_id,
+                opening_order_id: position.opening_order_id,
+                entry: position.entry,
+                side: position.side,
+                signed_qty: position.signed_qty,
+                quantity: position.quantity,
+                last_qty: fill.last_qty,
+                last_px: fill.last_px,
+                currency: position.settlement_currency,
+                avg_px_open: position.avg_px_open,
+                ts_event: 0,
+                ts_init: 0,
+            });
+            self.publish("events.position.S-001", &event);
+            fill
+        }
+    }
+
+    fn accepted_limit_order(instrument_id: InstrumentId) -> (OrderAny, OrderEvent) {
+        let mut order = OrderAny::from(TestOrderStubs::limit_order(
+            instrument_id,
+            OrderSide::Buy,
+            Price::from("0.80000"),
+            Quantity::from(100_000),
+            None,
+            None,
+        ));
+        let account_id = AccountId::from("SIM-001");
+        let submitted = OrderSubmitted::new(
+            order.trader_id(),
+            order.strategy_id(),
+            order.instrument_id(),
+            order.client_order_id(),
+            account_id,
+            UUID4::new(),
+            1,
+            1,
+        )
+        .unwrap();
+        order.apply(OrderEvent::OrderSubmitted(submitted)).unwrap();
+
+        let accepted = OrderEvent::OrderAccepted(
+            OrderAccepted::new(
+                order.trader_id(),
+                order.strategy_id(),
+                order.instrument_id(),
+                order.client_order_id(),
+                VenueOrderId::from("V-1"),
+                account_id,
+                UUID4::new(),
+                2,
+                2,
+                false,
+            )
+            .unwrap(),
+        );
+        order.apply(accepted.clone()).unwrap();
+        (order, accepted)
+    }
+
+    #[rstest]
+    fn test_account_state_event_registers_account(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        let venue = Venue::from("SIM");
+        assert!(test.portfolio.account(&venue).is_none());
+
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+
+        assert!(test.portfolio.account(&venue).unwrap().is_cash_account());
+        assert_eq!(
+            test.portfolio.balances_locked(&venue),
+            Some(HashMap::from([(Currency::USD(), Money::from("0 USD"))]))
+        );
+        assert_eq!(test.portfolio.margins_init(&venue), Some(HashMap::new()));
+        assert!(test
+            .cache
+            .borrow()
+            .account_state(&AccountId::from("SIM-001"))
+            .is_some());
+    }
+
+    #[rstest]
+    fn test_accepted_order_locks_cash_balance(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+        let (order, accepted) = accepted_limit_order(audusd_sim.id);
+        test.cache
+            .borrow_mut()
+            .add_order(order, None, None)
+            .unwrap();
+
+        test.publish("events.order.S-001", &accepted);
+
+        let venue = Venue::from("SIM");
+        let account = test.portfolio.account(&venue).unwrap();
+        // Notional of 80,000 USD plus the expected taker commissions
+        assert_eq!(
+            account.base_balance_locked(None),
+            Some(Money::from("80003.20 USD"))
+        );
+        assert_eq!(
+            account.base_balance_free(None),
+            Some(Money::from("919996.80 USD"))
+        );
+        assert_eq!(account.events.len(), 2);
+        drop(account);
+        assert_eq!(
+            test.cache
+                .borrow()
+                .account_states(&AccountId::from("SIM-001"))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[rstest]
+    fn test_order_fill_updates_cash_balance(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+        let order = TestOrderStubs::market_order(
+            audusd_sim.id,
+            OrderSide::Buy,
+            Quantity::from(100_000),
+            None,
+            None,
+        );
+        let fill = TestOrderEventStubs::order_filled::<MarketOrder, CurrencyPair>(
+            &order,
+            &audusd_sim,
+            None,
+            None,
+            None,
+            Some(Price::from("0.80000")),
+            None,
+            None,
+            None,
+        );
+
+        test.publish("events.order.S-001", &OrderEvent::OrderFilled(fill));
+
+        let account = test.portfolio.account(&Venue::from("SIM")).unwrap();
+        // Cost of 80,000 USD plus a 2 USD commission
+        assert_eq!(
+            account.base_balance_total(None),
+            Some(Money::from("919998 USD"))
+        );
+        assert_eq!(account.commissions.get(&Currency::USD()), Some(&2.0));
+    }
+
+    #[rstest]
+    fn test_open_position_pnl_and_exposure(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+        test.open_position(audusd_sim, OrderSide::Buy);
+
+        // No prices yet
+        assert_eq!(test.portfolio.unrealized_pnl(&audusd_sim.id), None);
+        assert_eq!(test.portfolio.net_exposure(&audusd_sim.id), None);
+
+        test.publish_quote(audusd_sim.id, "0.81000", "0.81010");
+
+        let venue = Venue::from("SIM");
+        assert_eq!(
+            test.portfolio.unrealized_pnl(&audusd_sim.id),
+            Some(Money::from("1000 USD"))
+        );
+        assert_eq!(
+            test.portfolio.unrealized_pnls(&venue),
+            HashMap::from([(Currency::USD(), Money::from("1000 USD"))])
+        );
+        assert_eq!(
+            test.portfolio.realized_pnl(&audusd_sim.id),
+            Some(Money::from("-2 USD")) // Opening commission
+        );
+        assert_eq!(
+            test.portfolio.net_exposure(&audusd_sim.id),
+            Some(Money::from("81000 USD"))
+        );
+        assert_eq!(
+            test.portfolio.net_exposures(&venue),
+            Some(HashMap::from([(Currency::USD(), Money::from("81000 USD"))]))
+        );
+        assert_eq!(test.portfolio.net_position(&audusd_sim.id), 100_000.0);
+        assert!(test.portfolio.is_net_long(&audusd_sim.id));
+        assert!(!test.portfolio.is_flat(&audusd_sim.id));
+        assert!(!test.portfolio.is_completely_flat());
+    }
+
+    #[rstest]
+    fn test_new_quote_invalidates_unrealized_pnl(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+        test.open_position(audusd_sim, OrderSide::Sell);
+        test.publish_quote(audusd_sim.id, "0.79990", "0.80000");
+        assert_eq!(
+            test.portfolio.unrealized_pnl(&audusd_sim.id),
+            Some(Money::from("0 USD"))
+        );
+
+        test.publish_quote(audusd_sim.id, "0.78990", "0.79000");
+
+        assert_eq!(
+            test.portfolio.unrealized_pnl(&audusd_sim.id),
+            Some(Money::from("1000 USD"))
+        );
+        assert!(test.portfolio.is_net_short(&audusd_sim.id));
+    }
+
+    #[rstest]
+    fn test_open_position_updates_maintenance_margin(
+        audusd_sim: CurrencyPair,
+        margin_account_state: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &margin_account_state);
+
+        test.open_position(audusd_sim, OrderSide::Buy);
+
+        let margins = test.portfolio.margins_maint(&Venue::from("SIM")).unwrap();
+        // 80,000 USD notional at a 3% margin, plus the taker commission
+        assert_eq!(
+            margins.get(&audusd_sim.id),
+            Some(&Money::from("2401.60 USD"))
+        );
+    }
+
+    #[rstest]
+    fn test_hedged_positions_offset_with_portfolio_margin_model(
+        audusd_sim: CurrencyPair,
+        margin_account_state: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &margin_account_state);
+        if let Some(AccountAny::Margin(account)) = test
+            .portfolio
+            .state
+            .borrow_mut()
+            .accounts
+            .get_mut(&margin_account_state.account_id)
+        {
+            account.set_margin_model(Box::new(PortfolioMarginModel::new(vec![], 0.0).unwrap()));
+        }
+        let venue = Venue::from("SIM");
+
+        test.open_position_with_id(audusd_sim, OrderSide::Buy, PositionId::from("P-001"));
+        assert_eq!(
+            test.portfolio
+                .margins_maint(&venue)
+                .unwrap()
+                .get(&audusd_sim.id),
+            Some(&Money::from("2401.60 USD"))
+        );
+
+        test.open_position_with_id(audusd_sim, OrderSide::Sell, PositionId::from("P-002"));
+        assert_eq!(
+            test.portfolio
+                .margins_maint(&venue)
+                .unwrap()
+                .get(&audusd_sim.id),
+            None
+        );
+    }
+
+    #[rstest]
+    fn test_initialize_positions_pending_prices(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &cash_account_state_million_usd);
+        test.open_position(audusd_sim, OrderSide::Buy);
+
+        test.portfolio.initialize_positions();
+        assert!(!test.portfolio.is_initialized());
+
+        test.publish_quote(audusd_sim.id, "0.81000", "0.81010");
+        assert!(test.portfolio.is_initialized());
+    }
+
+    #[rstest]
+    fn test_quote_after_initialization_recalculates_unrealized_pnl(
+        audusd_sim: CurrencyPair,
+        margin_account_state: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        test.publish("events.account.SIM-001", &margin_account_state);
+        test.open_position(audusd_sim, OrderSide::Buy);
+        test.publish_quote(audusd_sim.id, "0.80000", "0.80010");
+        test.portfolio.initialize_positions();
+        assert!(test.portfolio.is_initialized());
+        let events_count = test
+            .portfolio
+            .account(&Venue::from("SIM"))
+            .unwrap()
+            .events
+            .len();
+
+        test.publish_quote(audusd_sim.id, "0.81000", "0.81010");
+
+        assert_eq!(
+            test.portfolio
+                .state
+                .borrow()
+                .unrealized_pnls
+                .get(&audusd_sim.id),
+            Some(&Money::from("1000 USD"))
+        );
+        assert!(
+            test.portfolio
+                .account(&Venue::from("SIM"))
+                .unwrap()
+                .events
+                .len()
+                > events_count
+        );
+    }
+
+    #[rstest]
+    fn test_message_bus_does_not_keep_portfolio_alive(
+        audusd_sim: CurrencyPair,
+        cash_account_state_million_usd: AccountState,
+    ) {
+        let test = TestPortfolio::new(audusd_sim);
+        let state = Rc::downgrade(&test.portfolio.state);
+
+        drop(test.portfolio);
+
+        assert!(state.upgrade().is_none());
+        // Handlers of the dropped portfolio are no-ops
+        test.msgbus
+            .borrow()
+            .publish("events.account.SIM-001", &cash_account_state_million_usd);
+    }
+}
@@ -65,6 +65,7 @@ from nautilus_trader.core.rust.backtest cimport TimeEventAccumulatorAPI
 from nautilus_trader.core.rust.backtest cimport time_event_accumulator_advance_clock
 from nautilus_trader.core.rust.backtest cimport time_event_accumulator_drain
 from nautilus_trader.core.rust.backtest cimport time_event_accumulator_drop
+from nautilus_trader.core.rust.backtest cimport time_event_accumulator_handle_local
 from nautilus_trader.core.rust.backtest cimport time_event_accumulator_new
 from nautilus_trader.core.rust.common cimport TimeEventHandler_t
 from nautilus_trader.core.rust.common cimport logging_is_colored
@@ -1164,6 +1165,9 @@ cdef class BacktestEngine:
                 False,
             )
 
+        # Handle Rust-native time events (such as in-flight command releases)
+        time_event_accumulator_handle_local(&self._accumulator)
+
         cdef CVec raw_handlers = time_event_accumulator_drain(&self._accumulator)
 
         # Handle all events prior to the `ts_now`
@@ -155,6 +155,10 @@ cpdef LogGuard init_logging(
     bint colors=*,
     bint bypass=*,
     bint print_config=*,
+    uint64_t max_file_size=*,
+    str rotation_interval=*,
+    uint64_t max_backup_count=*,
+    bint compress_backups=*,
 )
 
 # Global static to flag if pyo3 based logging is initialized
@@ -1049,6 +1049,10 @@ cpdef LogGuard init_logging(
     bint colors = True,
     bint bypass = False,
     bint print_config = False,
+    uint64_t max_file_size = 0,
+    str rotation_interval = None,
+    uint64_t max_backup_count = 0,
+    bint compress_backups = False,
 ):
     """
     Initialize the logging system.
@@ -1089,6 +1093,16 @@ cpdef LogGuard init_logging(
         If the output for the core logging system is bypassed (useful for logging tests).
     print_config : bool, default False
         If the core logging configuration should be printed to stdout on initialization.
+    max_file_size : int, default 0
+        The maximum size (bytes) of a log file before it is rotated.
+        If zero then log files are not rotated on size.
+    rotation_interval : str { 'daily', 'hourly' }, optional
+        The interval at which log files are rotated. If ``None`` then will rotate daily.
+    max_backup_count : int, default 0
+        The maximum number of rotated log files to keep, with older files deleted.
+        If zero then all rotated log files are kept.
+    compress_backups : bool, default False
+        If rotated log files are compressed with gzip.
 
     Returns
     -------
@@ -1118,6 +1132,10 @@ cpdef LogGuard init_logging(
         pystr_to_cstr(directory) if directory else NULL,
         pystr_to_cstr(file_name) if file_name else NULL,
         pystr_to_cstr(file_format) if file_format else NULL,
+        max_file_size,
+        pystr_to_cstr(rotation_interval) if rotation_interval else NULL,
+        max_backup_count,
+        compress_backups,
         pybytes_to_cstr(msgspec.json.encode(component_levels)) if component_levels else NULL,
         colors,
         bypass,
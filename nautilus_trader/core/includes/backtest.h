@@ -24,3 +24,9 @@ void time_event_accumulator_advance_clock(struct TimeEventAccumulatorAPI *accumu
                                           uint8_t set_time);
 
 CVec time_event_accumulator_drain(struct TimeEventAccumulatorAPI *accumulator);
+
+/**
+ * Handles the accumulated Rust-native time event handlers in sorted order (by the events
+ * `ts_event`), as these cannot be passed across the FFI boundary.
+ */
+void time_event_accumulator_handle_local(struct TimeEventAccumulatorAPI *accumulator);
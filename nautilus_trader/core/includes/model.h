@@ -129,6 +129,22 @@ typedef enum BookAction {
     CLEAR = 4,
 } BookAction;
 
+/**
+ * The policy for handling sequence gaps in the deltas applied to an order book.
+ */
+typedef enum BookGapPolicy {
+    /**
+     * Delta sequence numbers are not checked.
+     */
+    IGNORE = 1,
+    /**
+     * Skipped or regressing delta sequence numbers put the book into a stale state,
+     * buffering deltas until a snapshot is received (a `Clear` delta followed by adds,
+     * or an `OrderBookDepth10`). The buffered deltas are then replayed on top of the snapshot.
+     */
+    RESYNC = 2,
+} BookGapPolicy;
+
 /**
  * The order book type, representing the type of levels granularity and delta updating heuristics.
  */
@@ -674,7 +690,15 @@ typedef enum TriggerType {
  */
 typedef struct Level Level;
 
-typedef struct OrderBookContainer OrderBookContainer;
+/**
+ * Provides an order book which can handle L1/L2/L3 granularity data.
+ *
+ * The book type determines how orders are processed:
+ * - `L3_MBO` (market by order): every order is held individually.
+ * - `L2_MBP` (market by price): one aggregated order is held per price level.
+ * - `L1_MBP` (top of book): only the top most level of the bid and ask side is held.
+ */
+typedef struct OrderBook OrderBook;
 
 /**
  * Represents a grouped batch of `OrderBookDelta` updates for an `OrderBook`.
@@ -1267,7 +1291,7 @@ typedef struct SyntheticInstrument_API {
  * having to manually access the underlying `OrderBook` instance.
  */
 typedef struct OrderBook_API {
-    struct OrderBookContainer *_0;
+    struct OrderBook *_0;
 } OrderBook_API;
 
 /**
@@ -1616,6 +1640,17 @@ const char *book_action_to_cstr(enum BookAction value);
  */
 enum BookAction book_action_from_cstr(const char *ptr);
 
+const char *book_gap_policy_to_cstr(enum BookGapPolicy value);
+
+/**
+ * Returns an enum from a Python string.
+ *
+ * # Safety
+ *
+ * - Assumes `ptr` is a valid C string pointer.
+ */
+enum BookGapPolicy book_gap_policy_from_cstr(const char *ptr);
+
 const char *book_type_to_cstr(enum BookType value);
 
 /**
@@ -2140,6 +2175,12 @@ uint64_t orderbook_ts_last(const struct OrderBook_API *book);
 
 uint64_t orderbook_count(const struct OrderBook_API *book);
 
+enum BookGapPolicy orderbook_gap_policy(const struct OrderBook_API *book);
+
+void orderbook_set_gap_policy(struct OrderBook_API *book, enum BookGapPolicy gap_policy);
+
+uint8_t orderbook_is_stale(const struct OrderBook_API *book);
+
 void orderbook_add(struct OrderBook_API *book,
                    struct BookOrder_t order,
                    uint64_t ts_event,
@@ -220,6 +220,10 @@ def init_logging(
     is_colored: bool | None = None,
     is_bypassed: bool | None = None,
     print_config: bool | None = None,
+    max_file_size: int | None = None,
+    rotation_interval: str | None = None,
+    max_backup_count: int | None = None,
+    compress_backups: bool | None = None,
 ) -> LogGuard: ...
 
 def log_header(
@@ -663,6 +667,10 @@ class BookAction(Enum):
     DELETE = "DELETE"
     CLEAR = "CLEAR"
 
+class BookGapPolicy(Enum):
+    IGNORE = "IGNORE"
+    RESYNC = "RESYNC"
+
 class BookType(Enum):
     L1_MBP = "L1_MBP"
     L2_MBP = "L2_MBP"
@@ -1867,50 +1875,11 @@ class Level:
     def first(self) -> BookOrder | None: ...
     def get_orders(self) -> list[BookOrder]: ...
 
-class OrderBookMbo:
-    def __init__(self, instrument_id: InstrumentId) -> None: ...
-    @property
-    def instrument_id(self) -> InstrumentId: ...
-    @property
-    def book_type(self) -> BookType: ...
-    @property
-    def sequence(self) -> int: ...
-    @property
-    def ts_event(self) -> int: ...
-    @property
-    def ts_init(self) -> int: ...
-    @property
-    def ts_last(self) -> int: ...
-    @property
-    def count(self) -> int: ...
-    def reset(self) -> None: ...
-    def update(self, order: BookOrder, ts_event: int, sequence: int = 0) -> None: ...
-    def delete(self, order: BookOrder, ts_event: int, sequence: int = 0) -> None: ...
-    def clear(self, ts_event: int, sequence: int = 0) -> None: ...
-    def clear_bids(self, ts_event: int, sequence: int = 0) -> None: ...
-    def clear_asks(self, ts_event: int, sequence: int = 0) -> None: ...
-    def apply_delta(self, delta: OrderBookDelta) -> None: ...
-    def apply_deltas(self, deltas: OrderBookDeltas) -> None: ...
-    def apply_depth(self, depth: OrderBookDepth10) -> None: ...
-    def check_integrity(self) -> None: ...
-    def bids(self) -> list[Level]: ...
-    def asks(self) -> list[Level]: ...
-    def best_bid_price(self) -> Price | None: ...
-    def best_ask_price(self) -> Price | None: ...
-    def best_bid_size(self) -> Quantity | None: ...
-    def best_ask_size(self) -> Quantity | None: ...
-    def spread(self) -> float | None: ...
-    def midpoint(self) -> float | None: ...
-    def get_avg_px_for_quantity(self, qty: Quantity, order_side: OrderSide) -> float: ...
-    def get_quantity_for_price(self, price: Price, order_side: OrderSide) -> float: ...
-    def simulate_fills(self, order: BookOrder) -> list[tuple[Price, Quantity]]: ...
-    def pprint(self, num_levels: int) -> str: ...
-
-class OrderBookMbp:
+class OrderBook:
     def __init__(
         self,
         instrument_id: InstrumentId,
-        top_only: bool = False,
+        book_type: BookType,
     ) -> None: ...
     @property
     def instrument_id(self) -> InstrumentId: ...
@@ -1926,7 +1895,14 @@ class OrderBookMbp:
     def ts_last(self) -> int: ...
     @property
     def count(self) -> int: ...
+    @property
+    def gap_policy(self) -> BookGapPolicy: ...
+    @gap_policy.setter
+    def gap_policy(self, value: BookGapPolicy) -> None: ...
+    @property
+    def is_stale(self) -> bool: ...
     def reset(self) -> None: ...
+    def add(self, order: BookOrder, ts_event: int, sequence: int = 0) -> None: ...
     def update(self, order: BookOrder, ts_event: int, sequence: int = 0) -> None: ...
     def update_quote_tick(self, quote: QuoteTick) -> None: ...
     def update_trade_tick(self, trade: TradeTick) -> None: ...
@@ -1972,6 +1948,7 @@ class HttpClient:
         header_keys: list[str] = [],
         keyed_quotas: list[tuple[str, Quota]] = [],
         default_quota: Quota | None = None,
+        max_retries: int = 3,
     ) -> None: ...
     async def request(
         self,
@@ -2014,6 +1991,12 @@ class WebSocketConfig:
         heartbeat: int | None = None,
         heartbeat_msg: str | None = None,
         ping_handler: Callable[..., Any] | None = None,
+        post_connect_msgs: list[str] | None = None,
+        reconnect_delay_initial_ms: int | None = None,
+        reconnect_delay_max_ms: int | None = None,
+        reconnect_backoff_factor: float | None = None,
+        reconnect_jitter_ms: int | None = None,
+        reconnect_max_retries: int | None = None,
     ) -> None: ...
 
 class WebSocketClient:
@@ -2054,6 +2037,12 @@ class SocketConfig:
         suffix: bytes,
         handler: Callable[..., Any],
         heartbeat: tuple[int, list[int]] | None = None,
+        post_connect_msgs: list[bytes] | None = None,
+        reconnect_delay_initial_ms: int | None = None,
+        reconnect_delay_max_ms: int | None = None,
+        reconnect_backoff_factor: float | None = None,
+        reconnect_jitter_ms: int | None = None,
+        reconnect_max_retries: int | None = None,
     ) -> None: ...
 
 ###################################################################################################
@@ -2407,8 +2396,7 @@ class BookImbalanceRatio:
     def has_inputs(self) -> bool: ...
     @property
     def value(self) -> float: ...
-    def handle_book_mbo(self, book: OrderBookMbo) -> None:...
-    def handle_book_mbp(self, book: OrderBookMbp) -> None:...
+    def handle_book(self, book: OrderBook) -> None:...
     def update(self, best_bid: Quantity | None, best_ask: Quantity) -> None: ...
     def reset(self) -> None: ...
 
@@ -24,3 +24,7 @@ cdef extern from "../includes/backtest.h":
                                               uint8_t set_time);
 
     CVec time_event_accumulator_drain(TimeEventAccumulatorAPI *accumulator);
+
+    # Handles the accumulated Rust-native time event handlers in sorted order (by the events
+    # `ts_event`), as these cannot be passed across the FFI boundary.
+    void time_event_accumulator_handle_local(TimeEventAccumulatorAPI *accumulator);
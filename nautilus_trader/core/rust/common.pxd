@@ -383,7 +383,10 @@ cdef extern from "../includes/common.h":
     # - Assume `directory_ptr` is either NULL or a valid C string pointer.
     # - Assume `file_name_ptr` is either NULL or a valid C string pointer.
     # - Assume `file_format_ptr` is either NULL or a valid C string pointer.
+    # - Assume `rotation_interval_ptr` is either NULL or a valid C string pointer.
     # - Assume `component_level_ptr` is either NULL or a valid C string pointer.
+    #
+    # A `max_file_size` or `max_backup_count` of zero is unlimited.
     LogGuard_API logging_init(TraderId_t trader_id,
                               UUID4_t instance_id,
                               LogLevel level_stdout,
@@ -391,6 +394,10 @@ cdef extern from "../includes/common.h":
                               const char *directory_ptr,
                               const char *file_name_ptr,
                               const char *file_format_ptr,
+                              uint64_t max_file_size,
+                              const char *rotation_interval_ptr,
+                              uint64_t max_backup_count,
+                              uint8_t compress_backups,
                               const char *component_levels_ptr,
                               uint8_t is_colored,
                               uint8_t is_bypassed,
@@ -76,6 +76,15 @@ cdef extern from "../includes/model.h":
         # The state of the order book is cleared.
         CLEAR # = 4,
 
+    # The policy for handling sequence gaps in the deltas applied to an order book.
+    cpdef enum BookGapPolicy:
+        # Delta sequence numbers are not checked.
+        IGNORE # = 1,
+        # Skipped or regressing delta sequence numbers put the book into a stale state,
+        # buffering deltas until a snapshot is received (a `Clear` delta followed by adds,
+        # or an `OrderBookDepth10`). The buffered deltas are then replayed on top of the snapshot.
+        RESYNC # = 2,
+
     # The order book type, representing the type of levels granularity and delta updating heuristics.
     cpdef enum BookType:
         # Top-of-book best bid/ask, one level per side.
@@ -363,7 +372,13 @@ cdef extern from "../includes/model.h":
     cdef struct Level:
         pass
 
-    cdef struct OrderBookContainer:
+    # Provides an order book which can handle L1/L2/L3 granularity data.
+    #
+    # The book type determines how orders are processed:
+    # - `L3_MBO` (market by order): every order is held individually.
+    # - `L2_MBP` (market by price): one aggregated order is held per price level.
+    # - `L1_MBP` (top of book): only the top most level of the bid and ask side is held.
+    cdef struct OrderBook:
         pass
 
     # Represents a grouped batch of `OrderBookDelta` updates for an `OrderBook`.
@@ -731,7 +746,7 @@ cdef extern from "../includes/model.h":
     # dereferenced to `OrderBook`, providing access to `OrderBook`'s methods without
     # having to manually access the underlying `OrderBook` instance.
     cdef struct OrderBook_API:
-        OrderBookContainer *_0;
+        OrderBook *_0;
 
     # Provides a C compatible Foreign Function Interface (FFI) for an underlying order book[`Level`].
     #
@@ -1030,6 +1045,15 @@ cdef extern from "../includes/model.h":
     # - Assumes `ptr` is a valid C string pointer.
     BookAction book_action_from_cstr(const char *ptr);
 
+    const char *book_gap_policy_to_cstr(BookGapPolicy value);
+
+    # Returns an enum from a Python string.
+    #
+    # # Safety
+    #
+    # - Assumes `ptr` is a valid C string pointer.
+    BookGapPolicy book_gap_policy_from_cstr(const char *ptr);
+
     const char *book_type_to_cstr(BookType value);
 
     # Returns an enum from a Python string.
@@ -1471,6 +1495,12 @@ cdef extern from "../includes/model.h":
 
     uint64_t orderbook_count(const OrderBook_API *book);
 
+    BookGapPolicy orderbook_gap_policy(const OrderBook_API *book);
+
+    void orderbook_set_gap_policy(OrderBook_API *book, BookGapPolicy gap_policy);
+
+    uint8_t orderbook_is_stale(const OrderBook_API *book);
+
     void orderbook_add(OrderBook_API *book,
                        BookOrder_t order,
                        uint64_t ts_event,
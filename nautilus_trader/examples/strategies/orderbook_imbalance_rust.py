@@ -21,7 +21,6 @@ from nautilus_trader.config import PositiveFloat
 from nautilus_trader.config import StrategyConfig
 from nautilus_trader.core import nautilus_pyo3
 from nautilus_trader.core.nautilus_pyo3 import BookImbalanceRatio
-from nautilus_trader.core.nautilus_pyo3 import OrderBookMbp
 from nautilus_trader.core.rust.common import LogColor
 from nautilus_trader.model.book import OrderBook
 from nautilus_trader.model.data import QuoteTick
@@ -114,7 +113,12 @@ class OrderBookImbalance(Strategy):
 
         # We need to initialize the Rust pyo3 objects
         pyo3_instrument_id = nautilus_pyo3.InstrumentId.from_str(self.instrument_id.value)
-        self.book = OrderBookMbp(pyo3_instrument_id, config.use_quote_ticks)
+        pyo3_book_type = (
+            nautilus_pyo3.BookType.L1_MBP
+            if config.use_quote_ticks
+            else nautilus_pyo3.BookType.L2_MBP
+        )
+        self.book = nautilus_pyo3.OrderBook(pyo3_instrument_id, pyo3_book_type)
         self.imbalance = BookImbalanceRatio()
 
     def on_start(self) -> None:
@@ -146,7 +150,7 @@ class OrderBookImbalance(Strategy):
         Actions to be performed when order book deltas are received.
         """
         self.book.apply_deltas(pyo3_deltas)
-        self.imbalance.handle_book_mbp(self.book)
+        self.imbalance.handle_book(self.book)
         self.check_trigger()
 
     def on_quote_tick(self, tick: QuoteTick) -> None:
@@ -154,7 +158,7 @@ class OrderBookImbalance(Strategy):
         Actions to be performed when a delta is received.
         """
         self.book.update_quote_tick(tick)
-        self.imbalance.handle_book_mbp(self.book)
+        self.imbalance.handle_book(self.book)
         self.check_trigger()
 
     def on_order_book(self, order_book: OrderBook) -> None:
@@ -28,6 +28,7 @@ from nautilus_trader.core.correctness cimport Condition
 from nautilus_trader.core.data cimport Data
 from nautilus_trader.core.rust.core cimport CVec
 from nautilus_trader.core.rust.model cimport BookAction
+from nautilus_trader.core.rust.model cimport BookGapPolicy
 from nautilus_trader.core.rust.model cimport BookOrder_t
 from nautilus_trader.core.rust.model cimport BookType
 from nautilus_trader.core.rust.model cimport Level_API
@@ -60,16 +61,19 @@ from nautilus_trader.core.rust.model cimport orderbook_clear_asks
 from nautilus_trader.core.rust.model cimport orderbook_clear_bids
 from nautilus_trader.core.rust.model cimport orderbook_count
 from nautilus_trader.core.rust.model cimport orderbook_delete
+from nautilus_trader.core.rust.model cimport orderbook_gap_policy
 from nautilus_trader.core.rust.model cimport orderbook_get_avg_px_for_quantity
 from nautilus_trader.core.rust.model cimport orderbook_get_quantity_for_price
 from nautilus_trader.core.rust.model cimport orderbook_has_ask
 from nautilus_trader.core.rust.model cimport orderbook_has_bid
 from nautilus_trader.core.rust.model cimport orderbook_instrument_id
+from nautilus_trader.core.rust.model cimport orderbook_is_stale
 from nautilus_trader.core.rust.model cimport orderbook_midpoint
 from nautilus_trader.core.rust.model cimport orderbook_new
 from nautilus_trader.core.rust.model cimport orderbook_pprint_to_cstr
 from nautilus_trader.core.rust.model cimport orderbook_reset
 from nautilus_trader.core.rust.model cimport orderbook_sequence
+from nautilus_trader.core.rust.model cimport orderbook_set_gap_policy
 from nautilus_trader.core.rust.model cimport orderbook_simulate_fills
 from nautilus_trader.core.rust.model cimport orderbook_spread
 from nautilus_trader.core.rust.model cimport orderbook_ts_last
@@ -226,6 +230,34 @@ cdef class OrderBook(Data):
         """
         return orderbook_count(&self._mem)
 
+    @property
+    def gap_policy(self) -> BookGapPolicy:
+        """
+        Return the policy for handling sequence gaps in applied deltas.
+
+        Returns
+        -------
+        BookGapPolicy
+
+        """
+        return <BookGapPolicy>orderbook_gap_policy(&self._mem)
+
+    @gap_policy.setter
+    def gap_policy(self, BookGapPolicy value) -> None:
+        orderbook_set_gap_policy(&self._mem, value)
+
+    @property
+    def is_stale(self) -> bool:
+        """
+        Return whether the book is stale (awaiting a snapshot after a sequence gap).
+
+        Returns
+        -------
+        bool
+
+        """
+        return <bint>orderbook_is_stale(&self._mem)
+
     cpdef void reset(self):
         """
         Reset the order book (clear all stateful values).
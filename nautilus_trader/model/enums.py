@@ -18,6 +18,7 @@ from nautilus_trader.core.rust.model import AggregationSource
 from nautilus_trader.core.rust.model import AggressorSide
 from nautilus_trader.core.rust.model import AssetClass
 from nautilus_trader.core.rust.model import BookAction
+from nautilus_trader.core.rust.model import BookGapPolicy
 from nautilus_trader.core.rust.model import BookType
 from nautilus_trader.core.rust.model import ContingencyType
 from nautilus_trader.core.rust.model import CurrencyType
@@ -50,6 +51,8 @@ from nautilus_trader.model.functions import bar_aggregation_from_str
 from nautilus_trader.model.functions import bar_aggregation_to_str
 from nautilus_trader.model.functions import book_action_from_str
 from nautilus_trader.model.functions import book_action_to_str
+from nautilus_trader.model.functions import book_gap_policy_from_str
+from nautilus_trader.model.functions import book_gap_policy_to_str
 from nautilus_trader.model.functions import book_type_from_str
 from nautilus_trader.model.functions import book_type_to_str
 from nautilus_trader.model.functions import contingency_type_from_str
@@ -98,6 +101,7 @@ __all__ = [
     "InstrumentClass",
     "BarAggregation",
     "BookAction",
+    "BookGapPolicy",
     "BookType",
     "ContingencyType",
     "CurrencyType",
@@ -130,6 +134,8 @@ __all__ = [
     "bar_aggregation_from_str",
     "book_action_to_str",
     "book_action_from_str",
+    "book_gap_policy_to_str",
+    "book_gap_policy_from_str",
     "book_type_to_str",
     "book_type_from_str",
     "contingency_type_to_str",
@@ -18,6 +18,7 @@ from nautilus_trader.core.rust.model cimport AggregationSource
 from nautilus_trader.core.rust.model cimport AggressorSide
 from nautilus_trader.core.rust.model cimport AssetClass
 from nautilus_trader.core.rust.model cimport BookAction
+from nautilus_trader.core.rust.model cimport BookGapPolicy
 from nautilus_trader.core.rust.model cimport BookType
 from nautilus_trader.core.rust.model cimport ContingencyType
 from nautilus_trader.core.rust.model cimport CurrencyType
@@ -62,6 +63,9 @@ cpdef str bar_aggregation_to_str(BarAggregation value)
 cpdef BookAction book_action_from_str(str value)
 cpdef str book_action_to_str(BookAction value)
 
+cpdef BookGapPolicy book_gap_policy_from_str(str value)
+cpdef str book_gap_policy_to_str(BookGapPolicy value)
+
 cpdef BookType book_type_from_str(str value)
 cpdef str book_type_to_str(BookType value)
 
@@ -27,6 +27,8 @@ from nautilus_trader.core.rust.model cimport bar_aggregation_from_cstr
 from nautilus_trader.core.rust.model cimport bar_aggregation_to_cstr
 from nautilus_trader.core.rust.model cimport book_action_from_cstr
 from nautilus_trader.core.rust.model cimport book_action_to_cstr
+from nautilus_trader.core.rust.model cimport book_gap_policy_from_cstr
+from nautilus_trader.core.rust.model cimport book_gap_policy_to_cstr
 from nautilus_trader.core.rust.model cimport book_type_from_cstr
 from nautilus_trader.core.rust.model cimport book_type_to_cstr
 from nautilus_trader.core.rust.model cimport contingency_type_from_cstr
@@ -125,6 +127,14 @@ cpdef str book_action_to_str(BookAction value):
     return cstr_to_pystr(book_action_to_cstr(value))
 
 
+cpdef BookGapPolicy book_gap_policy_from_str(str value):
+    return book_gap_policy_from_cstr(pystr_to_cstr(value))
+
+
+cpdef str book_gap_policy_to_str(BookGapPolicy value):
+    return cstr_to_pystr(book_gap_policy_to_cstr(value))
+
+
 cpdef BookType book_type_from_str(str value):
     return book_type_from_cstr(pystr_to_cstr(value))
 
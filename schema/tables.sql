@@ -1,4 +1,79 @@
 CREATE TABLE IF NOT EXISTS general (
     key SERIAL PRIMARY KEY,
     value TEXT NOT NULL
-);
\ No newline at end of file
+);
+
+CREATE TABLE IF NOT EXISTS cache_general (
+    trader_key TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (trader_key, key)
+);
+
+CREATE TABLE IF NOT EXISTS currency (
+    trader_key TEXT NOT NULL,
+    code TEXT NOT NULL,
+    precision INTEGER,
+    iso4217 INTEGER,
+    name TEXT,
+    currency_type TEXT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, code)
+);
+
+CREATE TABLE IF NOT EXISTS instrument (
+    trader_key TEXT NOT NULL,
+    id TEXT NOT NULL,
+    kind TEXT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, id)
+);
+
+CREATE TABLE IF NOT EXISTS synthetic (
+    trader_key TEXT NOT NULL,
+    id TEXT NOT NULL,
+    formula TEXT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, id)
+);
+
+CREATE TABLE IF NOT EXISTS account_event (
+    trader_key TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    account_type TEXT,
+    ts_event BIGINT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, account_id, seq)
+);
+
+CREATE TABLE IF NOT EXISTS order_event (
+    trader_key TEXT NOT NULL,
+    client_order_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    event_type TEXT,
+    ts_event BIGINT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, client_order_id, seq)
+);
+
+CREATE TABLE IF NOT EXISTS position_snapshot (
+    trader_key TEXT NOT NULL,
+    position_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    instrument_id TEXT,
+    strategy_id TEXT,
+    side TEXT,
+    quantity TEXT,
+    ts_last BIGINT,
+    payload TEXT NOT NULL,
+    PRIMARY KEY (trader_key, position_id, seq)
+);
+
+CREATE TABLE IF NOT EXISTS cache_index (
+    trader_key TEXT NOT NULL,
+    index_key TEXT NOT NULL,
+    member TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (trader_key, index_key, member)
+);